@@ -1,6 +1,32 @@
 """Physobx: High-performance physics sandbox with Metal GPU acceleration."""
 
-from physobx.physobx import version, Scene, Simulator
+from physobx.physobx import (
+    version,
+    Scene,
+    Simulator,
+    NoiseChain,
+    BopExporter,
+    apply_image_noise,
+    detect_moments,
+    compare_trajectories,
+    sweep,
+    finite_difference_gradient,
+    fit_materials,
+    read_shared_state,
+)
 
-__all__ = ["version", "Scene", "Simulator"]
+__all__ = [
+    "version",
+    "Scene",
+    "Simulator",
+    "NoiseChain",
+    "BopExporter",
+    "apply_image_noise",
+    "detect_moments",
+    "compare_trajectories",
+    "sweep",
+    "finite_difference_gradient",
+    "fit_materials",
+    "read_shared_state",
+]
 __version__ = version()
@@ -0,0 +1,125 @@
+//! SIMD kernels vs. their scalar equivalents
+//!
+//! Run with `cargo bench -p physobx-core --bench kernels`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use physobx_core::analysis::divergence_color;
+use physobx_core::physics::RigidBodyStorage;
+use physobx_core::scene::builder::ShapeType;
+use physobx_core::simd;
+
+/// Median time of `runs` calls
+fn time(runs: u32, mut f: impl FnMut()) -> Duration {
+    let mut samples: Vec<Duration> = (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+fn report(name: &str, n: usize, scalar: Duration, simd: Duration) {
+    println!(
+        "{:<18} {:>9} bodies  scalar {:>9.3} ms  simd {:>9.3} ms  speedup {:>5.2}x",
+        name,
+        n,
+        scalar.as_secs_f64() * 1e3,
+        simd.as_secs_f64() * 1e3,
+        scalar.as_secs_f64() / simd.as_secs_f64(),
+    );
+}
+
+/// Storage of `n` randomly posed, moving cubes and spheres
+fn random_storage(n: usize) -> RigidBodyStorage {
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let mut rand = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        (seed >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0
+    };
+
+    let mut storage = RigidBodyStorage::with_capacity(n);
+    for i in 0..n {
+        let q = [rand(), rand(), rand(), rand()];
+        let norm = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt().max(1e-3);
+        let shape = if i % 3 == 0 { ShapeType::Sphere } else { ShapeType::Cube };
+        storage.push_with_shape(
+            [rand() * 50.0, rand() * 50.0, rand() * 50.0],
+            q.map(|c| c / norm),
+            1.0 + rand().abs(),
+            shape,
+            0.3 + 0.2 * rand().abs(),
+            0.5,
+            0.0,
+            [0.5; 3],
+        );
+        storage.linear_velocities[i] = [rand() * 5.0, rand() * 5.0, rand() * 5.0];
+    }
+    storage
+}
+
+fn main() {
+    for n in [100_000, 1_000_000] {
+        let s = random_storage(n);
+        let runs = if n > 100_000 { 21 } else { 101 };
+
+        let scalar = time(runs, || {
+            let e: f32 = s.linear_velocities.iter().zip(&s.masses)
+                .map(|(v, m)| 0.5 * m * (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]))
+                .sum();
+            black_box(e);
+        });
+        let fast = time(runs, || {
+            black_box(simd::kinetic_energy(black_box(&s.linear_velocities), &s.masses));
+        });
+        report("kinetic_energy", n, scalar, fast);
+
+        let scalar = time(runs, || {
+            let mut p = [0.0f32; 3];
+            for (v, m) in s.linear_velocities.iter().zip(&s.masses) {
+                for k in 0..3 {
+                    p[k] += m * v[k];
+                }
+            }
+            black_box(p);
+        });
+        let fast = time(runs, || {
+            black_box(simd::linear_momentum(black_box(&s.linear_velocities), &s.masses));
+        });
+        report("linear_momentum", n, scalar, fast);
+
+        let mut min = vec![[0.0f32; 3]; n];
+        let mut max = vec![[0.0f32; 3]; n];
+        let scalar = time(runs, || {
+            for i in 0..n {
+                (min[i], max[i]) = s.aabb(i);
+            }
+            black_box(&min);
+        });
+        let fast = time(runs, || {
+            simd::aabbs_into(&s.positions, &s.rotations, &s.radii, &s.half_heights, &s.shape_types, &mut min, &mut max);
+            black_box(&min);
+        });
+        report("aabbs", n, scalar, fast);
+
+        let values: Vec<f32> = s.positions.iter().map(|p| p[0].abs()).collect();
+        let mut colors = vec![[0.0f32; 3]; n];
+        let scalar = time(runs, || {
+            for (c, &v) in colors.iter_mut().zip(&values) {
+                *c = divergence_color(v, 50.0);
+            }
+            black_box(&colors);
+        });
+        let fast = time(runs, || {
+            simd::colormap_into(black_box(&values), 50.0, &mut colors);
+            black_box(&colors);
+        });
+        report("colormap", n, scalar, fast);
+    }
+}
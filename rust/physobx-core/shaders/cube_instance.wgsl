@@ -16,12 +16,24 @@ struct Instance {
     _padding: f32,
     rotation: vec4<f32>,  // quaternion (x, y, z, w)
     color: vec3<f32>,
-    _padding2: f32,
+    roughness: f32,
+    scale: vec3<f32>,     // per-axis scale of the cube mesh (box half extents)
+    metallic: f32,
 };
 
 @group(0) @binding(1)
 var<storage, read> instances: array<Instance>;
 
+// Transforms from the previously rendered frame (motion vectors)
+struct PrevTransform {
+    position: vec3<f32>,
+    _padding: f32,
+    rotation: vec4<f32>,
+};
+
+@group(0) @binding(2)
+var<storage, read> prev_transforms: array<PrevTransform>;
+
 // Shadow map bindings (group 1)
 struct ShadowUniforms {
     light_view_proj: mat4x4<f32>,
@@ -48,6 +60,14 @@ struct VertexOutput {
     @location(2) local_position: vec3<f32>,
     @location(3) color: vec3<f32>,
     @location(4) shadow_pos: vec4<f32>,
+    @location(5) curr_clip: vec4<f32>,
+    @location(6) prev_clip: vec4<f32>,
+    @location(7) @interpolate(flat) surface: vec2<f32>,  // roughness, metallic
+};
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) velocity: vec4<f32>,
 };
 
 // Rotate a vector by a quaternion
@@ -65,7 +85,9 @@ fn vs_main(
 ) -> VertexOutput {
     let inst = instances[instance_id];
 
-    let rotated_pos = quat_rotate(inst.rotation, vertex.position);
+    // Axis-aligned face normals are unchanged by the per-axis scale
+    let local_pos = vertex.position * inst.scale;
+    let rotated_pos = quat_rotate(inst.rotation, local_pos);
     let world_pos = rotated_pos + inst.position;
     let world_normal = quat_rotate(inst.rotation, vertex.normal);
 
@@ -75,10 +97,17 @@ fn vs_main(
     out.world_position = world_pos;
     out.local_position = vertex.position;
     out.color = inst.color;
+    out.surface = vec2<f32>(inst.roughness, inst.metallic);
 
     // Transform world position to shadow map space
     out.shadow_pos = shadow_uniforms.light_view_proj * vec4<f32>(world_pos, 1.0);
 
+    // Same vertex at the previous frame's transform
+    let prev = prev_transforms[instance_id];
+    let prev_pos = quat_rotate(prev.rotation, local_pos) + prev.position;
+    out.curr_clip = out.clip_position;
+    out.prev_clip = camera.view_proj * vec4<f32>(prev_pos, 1.0);
+
     return out;
 }
 
@@ -150,8 +179,25 @@ fn sample_shadow_pcf(shadow_pos: vec4<f32>) -> f32 {
     return shadow / 9.0;
 }
 
+// UV-space motion of a fragment since the previous frame
+fn screen_velocity(curr_clip: vec4<f32>, prev_clip: vec4<f32>) -> vec2<f32> {
+    if (prev_clip.w <= 1e-4) {
+        return vec2<f32>(0.0); // Previous position was behind the camera
+    }
+    let ndc_delta = curr_clip.xy / curr_clip.w - prev_clip.xy / prev_clip.w;
+    return ndc_delta * vec2<f32>(0.5, -0.5);
+}
+
+// Highlight for a roughness, equal to pow(NdotH, shininess) * strength at
+// the default roughness of 0.5: sharper and stronger when smoother, fading
+// out towards fully matte
+fn surface_specular(NdotH: f32, shininess: f32, strength: f32, roughness: f32) -> f32 {
+    let exponent = shininess * exp2(10.0 * (0.5 - roughness));
+    return pow(NdotH, exponent) * strength * 2.0 * (1.0 - roughness);
+}
+
 @fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+fn fs_main(in: VertexOutput) -> FragmentOutput {
     let N = normalize(in.world_normal);
     let V = normalize(camera.eye_position.xyz - in.world_position);
 
@@ -179,7 +225,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Specular (GGX-like) - affected by shadow
     let H = normalize(key_dir + V);
     let NdotH = max(dot(N, H), 0.0);
-    let spec = pow(NdotH, 32.0) * 0.4;
+    let spec = surface_specular(NdotH, 32.0, 0.4, in.surface.x);
 
     // === Sky IBL (hemisphere lighting) ===
     // Sky color from above, ground bounce from below
@@ -192,10 +238,14 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let ambient = vec3<f32>(0.06, 0.07, 0.09) + ibl_diffuse;
 
     // Combine lighting with shadows
-    var color = base_color * ambient;
-    color += base_color * key_color * key_diff * 0.85 * shadow;  // Key light shadowed
-    color += base_color * fill_color * fill_diff * 0.25;         // Fill light not shadowed
-    color += key_color * spec * shadow;                          // Specular shadowed
+    // Metals diffuse little light and tint their highlights
+    let metallic = in.surface.y;
+    let diffuse_color = base_color * (1.0 - 0.7 * metallic);
+    let spec_color = mix(key_color, key_color * base_color, metallic) * (1.0 + metallic);
+    var color = diffuse_color * ambient;
+    color += diffuse_color * key_color * key_diff * 0.85 * shadow;  // Key light shadowed
+    color += diffuse_color * fill_color * fill_diff * 0.25;         // Fill light not shadowed
+    color += spec_color * spec * shadow;                            // Specular shadowed
 
     // Fresnel rim highlight
     let fresnel = pow(1.0 - max(dot(N, V), 0.0), 4.0) * 0.12;
@@ -221,5 +271,8 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let fog_factor = smoothstep(400.0, 1000.0, dist);
     color = mix(color, fog_color, fog_factor * 0.05);
 
-    return vec4<f32>(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)), 1.0);
+    var out: FragmentOutput;
+    out.color = vec4<f32>(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)), 1.0);
+    out.velocity = vec4<f32>(screen_velocity(in.curr_clip, in.prev_clip), 0.0, 0.0);
+    return out;
 }
@@ -0,0 +1,134 @@
+// Fluid splat shader for Physobx
+// Camera-facing quads ray-traced as spheres, shaded as water
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    eye_position: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct Instance {
+    position: vec3<f32>,
+    radius: f32,
+    color: vec3<f32>,
+    _padding: f32,
+};
+
+@group(0) @binding(1)
+var<storage, read> instances: array<Instance>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world_position: vec3<f32>,
+    @location(1) @interpolate(flat) center: vec3<f32>,
+    @location(2) @interpolate(flat) radius: f32,
+    @location(3) @interpolate(flat) color: vec3<f32>,
+};
+
+struct FragmentOutput {
+    @location(0) color: vec4<f32>,
+    @location(1) velocity: vec4<f32>,
+    @builtin(frag_depth) depth: f32,
+};
+
+// Orthographic projections keep w = 1
+fn is_orthographic() -> bool {
+    return camera.proj[3][3] == 1.0;
+}
+
+// Direction from a point towards the viewer
+fn to_viewer(p: vec3<f32>) -> vec3<f32> {
+    if (is_orthographic()) {
+        // Third row of the view matrix: camera backward axis
+        return normalize(vec3<f32>(camera.view[0][2], camera.view[1][2], camera.view[2][2]));
+    }
+    return normalize(camera.eye_position.xyz - p);
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_id: u32,
+) -> VertexOutput {
+    let inst = instances[instance_id];
+
+    // Two triangles covering [-1, 1]^2
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, -1.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, -1.0), vec2<f32>(1.0, 1.0), vec2<f32>(-1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+
+    // Quad on the sphere's front tangent plane: its silhouette projects
+    // inside a square of half size `radius` there
+    let back = to_viewer(inst.position);
+    let camera_up = vec3<f32>(camera.view[0][1], camera.view[1][1], camera.view[2][1]);
+    let right = normalize(cross(camera_up, back));
+    let up = cross(back, right);
+    let world_pos = inst.position + back * inst.radius
+        + (right * corner.x + up * corner.y) * inst.radius;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_pos, 1.0);
+    out.world_position = world_pos;
+    out.center = inst.position;
+    out.radius = inst.radius;
+    out.color = inst.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> FragmentOutput {
+    // Ray from the quad into the sphere
+    var dir: vec3<f32>;
+    if (is_orthographic()) {
+        dir = -to_viewer(in.world_position);
+    } else {
+        dir = normalize(in.world_position - camera.eye_position.xyz);
+    }
+    let oc = in.world_position - in.center;
+    let b = dot(oc, dir);
+    let c = dot(oc, oc) - in.radius * in.radius;
+    let disc = b * b - c;
+    if (disc < 0.0) {
+        discard;
+    }
+    let hit = in.world_position + dir * max(-b - sqrt(disc), 0.0);
+    let N = normalize(hit - in.center);
+    let V = -dir;
+
+    let key_dir = normalize(vec3<f32>(-0.5, 0.9, 0.6));
+    let key_color = vec3<f32>(1.0, 0.98, 0.95);
+    let sky_color = vec3<f32>(0.4, 0.5, 0.7);
+    let ground_color = vec3<f32>(0.15, 0.12, 0.1);
+
+    // Tinted body lit softly from the key light and the sky
+    let key_diff = max(dot(N, key_dir), 0.0);
+    let ambient = vec3<f32>(0.1, 0.12, 0.16) + mix(ground_color, sky_color, N.y * 0.5 + 0.5) * 0.25;
+    let body = in.color * (ambient + key_color * key_diff * 0.6);
+
+    // Schlick fresnel towards the reflected sky (water F0 = 0.02)
+    let NdotV = max(dot(N, V), 0.0);
+    let fresnel = 0.02 + 0.98 * pow(1.0 - NdotV, 5.0);
+    let R = reflect(-V, N);
+    let env = mix(ground_color, sky_color * 1.4, R.y * 0.5 + 0.5);
+
+    // Sharp highlight of a smooth liquid
+    let H = normalize(key_dir + V);
+    let spec = pow(max(dot(N, H), 0.0), 128.0) * 1.5;
+
+    let color = mix(body, env, fresnel) + key_color * spec;
+
+    let clip = camera.view_proj * vec4<f32>(hit, 1.0);
+
+    var out: FragmentOutput;
+    out.color = vec4<f32>(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)), 1.0);
+    // Splats are not motion blurred
+    out.velocity = vec4<f32>(0.0);
+    out.depth = clamp(clip.z / clip.w, 0.0, 1.0);
+    return out;
+}
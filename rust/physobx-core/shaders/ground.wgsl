@@ -11,7 +11,14 @@ struct GroundUniforms {
     ground_y: f32,
     ground_size: f32,
     grid_scale: f32,
-    _padding: f32,
+    plane_count: u32,
+};
+
+// Static plane: center of the top face (w = half size) and side directions
+struct Plane {
+    center: vec4<f32>,
+    axis_u: vec4<f32>,
+    axis_v: vec4<f32>,
 };
 
 @group(0) @binding(0)
@@ -20,6 +27,16 @@ var<uniform> camera: Camera;
 @group(0) @binding(1)
 var<uniform> ground: GroundUniforms;
 
+// Persistent impact decals covering the ground quad (r = darkness)
+@group(0) @binding(2)
+var decal_map: texture_2d<f32>;
+
+@group(0) @binding(3)
+var decal_sampler: sampler;
+
+@group(0) @binding(4)
+var<uniform> planes: array<Plane, 16>;
+
 // Shadow map bindings (group 1)
 struct ShadowUniforms {
     light_view_proj: mat4x4<f32>,
@@ -39,10 +56,15 @@ struct VertexOutput {
     @location(0) world_position: vec3<f32>,
     @location(1) uv: vec2<f32>,
     @location(2) shadow_pos: vec4<f32>,
+    // 1 on the ground, 0 on static planes (decals only mark the ground)
+    @location(3) @interpolate(flat) decal_weight: f32,
 };
 
 @vertex
-fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
     // Generate quad from vertex index (0-5 for two triangles)
     var positions = array<vec2<f32>, 6>(
         vec2<f32>(-1.0, -1.0),
@@ -54,16 +76,29 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     );
 
     let pos = positions[vertex_index];
-    let world_pos = vec3<f32>(
+    var world_pos = vec3<f32>(
         pos.x * ground.ground_size,
         ground.ground_y,
         pos.y * ground.ground_size
     );
+    var uv = pos * ground.ground_size;
+    var decal_weight = 1.0;
+
+    // Instances after the first are static planes, gridded in their own axes
+    if (instance_index > 0u) {
+        let plane = planes[instance_index - 1u];
+        let half_size = plane.center.w;
+        world_pos = plane.center.xyz
+            + (pos.x * plane.axis_u.xyz + pos.y * plane.axis_v.xyz) * half_size;
+        uv = pos * half_size;
+        decal_weight = 0.0;
+    }
 
     var out: VertexOutput;
     out.clip_position = camera.view_proj * vec4<f32>(world_pos, 1.0);
     out.world_position = world_pos;
-    out.uv = pos * ground.ground_size;
+    out.uv = uv;
+    out.decal_weight = decal_weight;
 
     // Transform world position to shadow map space
     out.shadow_pos = shadow_uniforms.light_view_proj * vec4<f32>(world_pos, 1.0);
@@ -135,6 +170,11 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Mix base and grid
     var color = mix(ground_base, grid_color, grid * fade * 0.6);
 
+    // Impact decals (scorch marks)
+    let decal_uv = in.uv / ground.ground_size * 0.5 + vec2<f32>(0.5, 0.5);
+    let decal = textureSample(decal_map, decal_sampler, decal_uv).r;
+    color *= 1.0 - decal * 0.85 * in.decal_weight;
+
     // Sample shadow map
     let shadow = sample_shadow_pcf(in.shadow_pos);
 
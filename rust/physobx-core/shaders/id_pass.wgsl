@@ -0,0 +1,99 @@
+// Instance-ID pass: flat per-body ID colors and unoccluded pixel counts
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    eye_position: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct IdInstance {
+    position: vec3<f32>,
+    half_height: f32,     // axial shapes only
+    rotation: vec4<f32>,  // quaternion (x, y, z, w)
+    scale: vec3<f32>,     // box half extents over the baked cube size, radius for spheres and axial shapes
+    body: u32,            // SOA body index
+};
+
+@group(0) @binding(1)
+var<storage, read> instances: array<IdInstance>;
+
+// Rasterized pixels per body when nothing else is drawn (visibility denominator)
+@group(0) @binding(2)
+var<storage, read_write> unoccluded_counts: array<atomic<u32>>;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) @interpolate(flat) body: u32,
+};
+
+// Rotate a vector by a quaternion
+fn quat_rotate(q: vec4<f32>, v: vec3<f32>) -> vec3<f32> {
+    let qvec = q.xyz;
+    let uv = cross(qvec, v);
+    let uuv = cross(qvec, uv);
+    return v + ((uv * q.w) + uuv) * 2.0;
+}
+
+@vertex
+fn vs_main(
+    vertex: VertexInput,
+    @builtin(instance_index) instance_id: u32,
+) -> VertexOutput {
+    let inst = instances[instance_id];
+    let world_pos = quat_rotate(inst.rotation, vertex.position * inst.scale) + inst.position;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_pos, 1.0);
+    out.body = inst.body;
+    return out;
+}
+
+struct AxialVertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) cap: f32,
+};
+
+// Axial meshes are stretched along local Y by the half height
+@vertex
+fn vs_axial(
+    vertex: AxialVertexInput,
+    @builtin(instance_index) instance_id: u32,
+) -> VertexOutput {
+    let inst = instances[instance_id];
+    let local_pos = vertex.position * inst.scale + vec3<f32>(0.0, vertex.cap * inst.half_height, 0.0);
+    let world_pos = quat_rotate(inst.rotation, local_pos) + inst.position;
+
+    var out: VertexOutput;
+    out.clip_position = camera.view_proj * vec4<f32>(world_pos, 1.0);
+    out.body = inst.body;
+    return out;
+}
+
+// Body index + 1 as 24-bit little-endian RGB (black = background)
+@fragment
+fn fs_id(in: VertexOutput) -> @location(0) vec4<f32> {
+    let id = in.body + 1u;
+    return vec4<f32>(
+        f32(id & 0xffu) / 255.0,
+        f32((id >> 8u) & 0xffu) / 255.0,
+        f32((id >> 16u) & 0xffu) / 255.0,
+        1.0,
+    );
+}
+
+// Without depth testing, every front-facing fragment of a convex body is one
+// pixel it would cover if unoccluded
+@fragment
+fn fs_count(in: VertexOutput) {
+    atomicAdd(&unoccluded_counts[in.body], 1u);
+}
@@ -0,0 +1,52 @@
+// Picture-in-picture inset shader for Physobx
+// Draws a captured frame, downscaled and framed by a border, into the viewport rectangle
+
+struct Params {
+    border: vec2<f32>,        // border width as a fraction of the viewport
+    step: vec2<f32>,          // source texels per inset pixel
+    border_color: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> params: Params;
+
+@group(0) @binding(1)
+var inset: texture_2d<f32>;
+
+@group(0) @binding(2)
+var inset_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+// Fullscreen triangle trick - no vertex buffer needed
+@vertex
+fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+    var out: VertexOutput;
+    let x = f32(i32(vertex_index & 1u) * 4 - 1);
+    let y = f32(i32(vertex_index >> 1u) * 4 - 1);
+    out.position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>((x + 1.0) * 0.5, (1.0 - y) * 0.5);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let inner = (in.uv - params.border) / (1.0 - 2.0 * params.border);
+    if (any(inner < vec2<f32>(0.0)) || any(inner > vec2<f32>(1.0))) {
+        return params.border_color;
+    }
+
+    // 2x2 bilinear taps spread over the footprint of the inset pixel, so
+    // the downscaled frame does not shimmer
+    let texel = 1.0 / vec2<f32>(textureDimensions(inset));
+    let spread = max(params.step * 0.25, vec2<f32>(0.0)) * texel;
+    var color = vec4<f32>(0.0);
+    for (var i = 0; i < 4; i++) {
+        let offset = vec2<f32>(f32(i & 1) * 2.0 - 1.0, f32(i >> 1u) * 2.0 - 1.0) * spread;
+        color += textureSampleLevel(inset, inset_sampler, inner + offset, 0.0);
+    }
+    return vec4<f32>((color * 0.25).rgb, 1.0);
+}
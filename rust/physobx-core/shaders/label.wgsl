@@ -0,0 +1,92 @@
+// Label glyph shader for Physobx
+// Camera-facing glyph quads of constant pixel size, drawn over the tonemapped frame
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    eye_position: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct Params {
+    viewport: vec2<f32>,
+    pixel_height: f32,
+    _padding: f32,
+    color: vec4<f32>,
+    background: vec4<f32>,
+};
+
+@group(0) @binding(1)
+var<uniform> params: Params;
+
+struct Glyph {
+    anchor: vec3<f32>,
+    glyph: u32,
+    offset: vec2<f32>,
+    _padding: vec2<f32>,
+};
+
+@group(0) @binding(2)
+var<storage, read> glyphs: array<Glyph>;
+
+@group(0) @binding(3)
+var atlas: texture_2d<f32>;
+
+// Font cell in atlas texels (5x7 glyph plus one texel of spacing)
+const CELL = vec2<f32>(6.0, 8.0);
+const ATLAS_COLUMNS: u32 = 16u;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texel: vec2<f32>,
+};
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_id: u32,
+) -> VertexOutput {
+    let g = glyphs[instance_id];
+
+    // Two triangles covering [0, 1]^2
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0), vec2<f32>(1.0, 0.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 0.0), vec2<f32>(1.0, 1.0), vec2<f32>(0.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+
+    var out: VertexOutput;
+    var clip = camera.view_proj * vec4<f32>(g.anchor, 1.0);
+    if (clip.w <= 0.0) {
+        // Anchor behind the camera: collapse the quad outside the depth range
+        out.clip_position = vec4<f32>(0.0, 0.0, 2.0, 1.0);
+        out.texel = vec2<f32>(0.0);
+        return out;
+    }
+
+    // Offset in pixels from the anchor, scaled back by w so the glyph keeps
+    // its pixel size at any distance
+    let pixels = (g.offset + corner) * CELL * (params.pixel_height / CELL.y);
+    clip = vec4<f32>(clip.xy + pixels * 2.0 / params.viewport * clip.w, clip.zw);
+    out.clip_position = clip;
+
+    // Atlas rows run top to bottom
+    let cell = vec2<f32>(f32(g.glyph % ATLAS_COLUMNS), f32(g.glyph / ATLAS_COLUMNS));
+    out.texel = (cell + vec2<f32>(corner.x, 1.0 - corner.y)) * CELL;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let size = vec2<i32>(textureDimensions(atlas));
+    let texel = clamp(vec2<i32>(floor(in.texel)), vec2<i32>(0), size - 1);
+    let ink = textureLoad(atlas, texel, 0).r;
+    let color = mix(params.background, vec4<f32>(params.color.rgb, 1.0), ink);
+    if (color.a <= 0.0) {
+        discard;
+    }
+    return color;
+}
@@ -0,0 +1,59 @@
+// Ink outline post effect: darkens pixels at depth discontinuities
+// (silhouettes) and sharp color changes
+
+@group(0) @binding(0) var input: texture_2d<f32>;
+@group(0) @binding(1) var output: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2) var depth: texture_depth_2d;
+
+const INK: vec3<f32> = vec3<f32>(0.05, 0.05, 0.06);
+// Relative depth step treated as a full edge
+const DEPTH_EDGE: f32 = 0.05;
+// Sobel luminance gradient treated as a full edge
+const COLOR_EDGE: f32 = 0.6;
+
+fn clamped(p: vec2<i32>, size: vec2<i32>) -> vec2<i32> {
+    return clamp(p, vec2<i32>(0), size - vec2<i32>(1));
+}
+
+fn luma(p: vec2<i32>, size: vec2<i32>) -> f32 {
+    let c = textureLoad(input, clamped(p, size), 0).rgb;
+    return dot(c, vec3<f32>(0.299, 0.587, 0.114));
+}
+
+// 1 - depth shrinks like 1/distance for a perspective camera, so ratios of
+// it compare distances
+fn inverse_distance(p: vec2<i32>, size: vec2<i32>) -> f32 {
+    return 1.0 - textureLoad(depth, clamped(p, size), 0);
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let size = vec2<i32>(textureDimensions(input));
+    let p = vec2<i32>(id.xy);
+    if (p.x >= size.x || p.y >= size.y) {
+        return;
+    }
+
+    // Sobel on luminance
+    var l: array<f32, 9>;
+    for (var k = 0; k < 9; k++) {
+        l[k] = luma(p + vec2<i32>(k % 3 - 1, k / 3 - 1), size);
+    }
+    let gx = (l[2] + 2.0 * l[5] + l[8]) - (l[0] + 2.0 * l[3] + l[6]);
+    let gy = (l[6] + 2.0 * l[7] + l[8]) - (l[0] + 2.0 * l[1] + l[2]);
+    let color_edge = smoothstep(0.5 * COLOR_EDGE, COLOR_EDGE, length(vec2<f32>(gx, gy)));
+
+    // Largest relative depth step to the four direct neighbors
+    let center = inverse_distance(p, size);
+    var jump = 0.0;
+    var offsets = array<vec2<i32>, 4>(vec2<i32>(1, 0), vec2<i32>(-1, 0), vec2<i32>(0, 1), vec2<i32>(0, -1));
+    for (var k = 0; k < 4; k++) {
+        let n = inverse_distance(p + offsets[k], size);
+        jump = max(jump, abs(center - n) / max(max(center, n), 1e-6));
+    }
+    let depth_edge = smoothstep(0.5 * DEPTH_EDGE, DEPTH_EDGE, jump);
+
+    let c = textureLoad(input, p, 0);
+    let edge = max(color_edge, depth_edge);
+    textureStore(output, p, vec4<f32>(mix(c.rgb, INK, edge), c.a));
+}
@@ -0,0 +1,91 @@
+// Overlay line shader for Physobx
+// World-space segments drawn with a constant pixel width over the tonemapped frame
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+    view: mat4x4<f32>,
+    proj: mat4x4<f32>,
+    eye_position: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> camera: Camera;
+
+struct Params {
+    viewport: vec2<f32>,
+    _padding: vec2<f32>,
+};
+
+@group(0) @binding(1)
+var<uniform> params: Params;
+
+struct Segment {
+    start: vec3<f32>,
+    width: f32,
+    end: vec3<f32>,
+    tick: f32,
+    color: vec4<f32>,
+};
+
+@group(0) @binding(2)
+var<storage, read> segments: array<Segment>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) @interpolate(flat) color: vec4<f32>,
+};
+
+// Each segment is three quads: the line itself and a tick across each end
+@vertex
+fn vs_main(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_id: u32,
+) -> VertexOutput {
+    let seg = segments[instance_id];
+
+    // Two triangles covering [0, 1]^2
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0), vec2<f32>(1.0, 0.0), vec2<f32>(1.0, 1.0),
+        vec2<f32>(0.0, 0.0), vec2<f32>(1.0, 1.0), vec2<f32>(0.0, 1.0),
+    );
+    let quad = vertex_index / 6u;
+    let corner = corners[vertex_index % 6u];
+
+    var out: VertexOutput;
+    out.color = seg.color;
+    let clip_a = camera.view_proj * vec4<f32>(seg.start, 1.0);
+    let clip_b = camera.view_proj * vec4<f32>(seg.end, 1.0);
+    if (clip_a.w <= 0.0 || clip_b.w <= 0.0 || (quad > 0u && seg.tick <= 0.0)) {
+        // Behind the camera or no tick: collapse the quad outside the depth range
+        out.clip_position = vec4<f32>(0.0, 0.0, 2.0, 1.0);
+        return out;
+    }
+
+    // Segment direction and normal in pixels
+    let pixel_a = clip_a.xy / clip_a.w * params.viewport * 0.5;
+    let pixel_b = clip_b.xy / clip_b.w * params.viewport * 0.5;
+    let delta = pixel_b - pixel_a;
+    var dir = vec2<f32>(1.0, 0.0);
+    if (dot(delta, delta) > 1e-6) {
+        dir = normalize(delta);
+    }
+    let normal = vec2<f32>(-dir.y, dir.x);
+
+    var t: f32;
+    var offset: vec2<f32>;
+    if (quad == 0u) {
+        t = corner.x;
+        offset = normal * (corner.y - 0.5) * seg.width;
+    } else {
+        t = f32(quad - 1u);
+        offset = normal * (corner.x - 0.5) * seg.tick + dir * (corner.y - 0.5) * seg.width;
+    }
+    let clip = mix(clip_a, clip_b, t);
+    out.clip_position = vec4<f32>(clip.xy + offset * 2.0 / params.viewport * clip.w, clip.zw);
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
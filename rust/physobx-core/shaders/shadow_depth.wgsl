@@ -15,6 +15,8 @@ struct CubeInstance {
     rotation: vec4<f32>,  // quaternion (x, y, z, w)
     color: vec3<f32>,
     _padding2: f32,
+    scale: vec3<f32>,
+    _padding3: f32,
 };
 
 // Sphere instance data
@@ -23,7 +25,22 @@ struct SphereInstance {
     radius: f32,
     rotation: vec4<f32>,
     color: vec3<f32>,
+    roughness: f32,
+    metallic: f32,
     _padding: f32,
+    _padding2: vec2<f32>,
+};
+
+// Axial instance data (capsules, cylinders and cones)
+struct AxialInstance {
+    position: vec3<f32>,
+    radius: f32,
+    rotation: vec4<f32>,
+    color: vec3<f32>,
+    half_height: f32,
+    roughness: f32,
+    metallic: f32,
+    _padding: vec2<f32>,
 };
 
 @group(0) @binding(1)
@@ -32,6 +49,9 @@ var<storage, read> cube_instances: array<CubeInstance>;
 @group(0) @binding(2)
 var<storage, read> sphere_instances: array<SphereInstance>;
 
+@group(0) @binding(3)
+var<storage, read> axial_instances: array<AxialInstance>;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) normal: vec3<f32>,
@@ -57,7 +77,7 @@ fn vs_cube(
 ) -> VertexOutput {
     let inst = cube_instances[instance_id];
 
-    let rotated_pos = quat_rotate(inst.rotation, vertex.position);
+    let rotated_pos = quat_rotate(inst.rotation, vertex.position * inst.scale);
     let world_pos = rotated_pos + inst.position;
 
     var out: VertexOutput;
@@ -81,5 +101,27 @@ fn vs_sphere(
     return out;
 }
 
+struct AxialVertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) normal: vec3<f32>,
+    @location(2) cap: f32,
+};
+
+// Vertex shader for axial shape shadow pass
+@vertex
+fn vs_axial(
+    vertex: AxialVertexInput,
+    @builtin(instance_index) instance_id: u32,
+) -> VertexOutput {
+    let inst = axial_instances[instance_id];
+
+    let local_pos = vertex.position * inst.radius + vec3<f32>(0.0, vertex.cap * inst.half_height, 0.0);
+    let world_pos = quat_rotate(inst.rotation, local_pos) + inst.position;
+
+    var out: VertexOutput;
+    out.clip_position = light_camera.view_proj * vec4<f32>(world_pos, 1.0);
+    return out;
+}
+
 // No fragment shader needed - depth-only pass
 // wgpu writes depth automatically without a fragment shader
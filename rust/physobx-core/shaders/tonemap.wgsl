@@ -9,14 +9,37 @@ var hdr_sampler: sampler;
 
 struct TonemapParams {
     exposure: f32,
-    _padding1: f32,
+    motion_blur_shutter: f32,   // Fraction of the frame interval the shutter is open
+    motion_blur_samples: u32,   // Taps along the motion path (<= 1 disables blur)
+    _padding: f32,
+    radial: vec4<f32>,          // Brown-Conrady k1, k2, k3 (w unused)
+    tangential: vec2<f32>,      // Brown-Conrady p1, p2
+    focal_scale: vec2<f32>,     // tan(fov_y / 2) * (aspect, 1)
+    vignette: f32,              // 0 = off, 1 = cos^4 falloff
+    chromatic_aberration: f32,  // Red/blue radial scale difference at the corners
+    grain: f32,                 // Film grain std in display units
+    grain_seed: u32,            // Per-frame grain seed
+    lut_strength: f32,          // 0 = ungraded, 1 = fully graded
+    lut_size: f32,              // LUT entries per axis
+    output_transfer: u32,       // 0 = sRGB, 1 = linear, 2 = BT.709, 3 = gamma 2.2
     _padding2: f32,
-    _padding3: f32,
+    output_gamut: mat3x3<f32>,  // Linear Rec.709 to output primaries
 };
 
 @group(0) @binding(2)
 var<uniform> params: TonemapParams;
 
+// Per-pixel UV motion since the previous frame (0 for static geometry)
+@group(0) @binding(3)
+var velocity_texture: texture_2d<f32>;
+
+// Color grading LUT over display-referred (sRGB-encoded) RGB
+@group(0) @binding(4)
+var lut_texture: texture_3d<f32>;
+
+// Longest blur streak, in UV units (guards against teleports and resets)
+const MAX_BLUR: f32 = 0.15;
+
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) uv: vec2<f32>,
@@ -64,19 +87,193 @@ fn linear_to_srgb(linear: vec3<f32>) -> vec3<f32> {
     return select(higher, lower, cutoff);
 }
 
+// Brown-Conrady distortion of normalized image coordinates
+fn distort(p: vec2<f32>) -> vec2<f32> {
+    let r2 = dot(p, p);
+    let radial = 1.0 + r2 * (params.radial.x + r2 * (params.radial.y + r2 * params.radial.z));
+    let p1 = params.tangential.x;
+    let p2 = params.tangential.y;
+    let tangential = vec2<f32>(
+        2.0 * p1 * p.x * p.y + p2 * (r2 + 2.0 * p.x * p.x),
+        p1 * (r2 + 2.0 * p.y * p.y) + 2.0 * p2 * p.x * p.y,
+    );
+    return p * radial + tangential;
+}
+
+// Normalized image coordinates (x right, y up) of an output UV
+fn uv_to_image(uv: vec2<f32>) -> vec2<f32> {
+    return (uv * 2.0 - vec2<f32>(1.0)) * vec2<f32>(1.0, -1.0) * params.focal_scale;
+}
+
+// Find where the ideal pinhole render must be sampled so the output shows the
+// distorted image: invert distort() by fixed-point iteration
+fn undistort_uv(uv: vec2<f32>) -> vec2<f32> {
+    let target_p = uv_to_image(uv);
+    var p = target_p;
+    for (var i = 0; i < 8; i++) {
+        p += target_p - distort(p);
+    }
+    return (p / params.focal_scale * vec2<f32>(1.0, -1.0) + vec2<f32>(1.0)) * 0.5;
+}
+
+// HDR scene color; with chromatic aberration, red and blue are sampled
+// scaled outward / inward about the image center (lateral aberration)
+fn sample_scene(uv: vec2<f32>) -> vec3<f32> {
+    let color = textureSampleLevel(hdr_texture, hdr_sampler, uv, 0.0).rgb;
+    if (params.chromatic_aberration == 0.0) {
+        return color;
+    }
+    let offset = (uv - vec2<f32>(0.5)) * params.chromatic_aberration;
+    let r = textureSampleLevel(hdr_texture, hdr_sampler, uv + offset, 0.0).r;
+    let b = textureSampleLevel(hdr_texture, hdr_sampler, uv - offset, 0.0).b;
+    return vec3<f32>(r, color.g, b);
+}
+
+// Motion of the object smearing over this pixel during the shutter interval
+//
+// Moving pixels use their own velocity. Static pixels search a few rings of
+// neighbours for an object whose trail passes over them, so streaks extend
+// past the object's silhouette instead of only blurring its interior.
+fn blur_velocity(uv: vec2<f32>) -> vec2<f32> {
+    let dims = vec2<f32>(textureDimensions(velocity_texture));
+    let max_pixel = vec2<i32>(dims) - vec2<i32>(1);
+    let pixel = vec2<i32>(uv * dims);
+
+    var best = textureLoad(velocity_texture, clamp(pixel, vec2<i32>(0), max_pixel), 0).xy;
+    if (dot(best, best) > 0.0) {
+        return best;
+    }
+
+    var radii = array<f32, 4>(4.0, 12.0, 32.0, 80.0);
+    for (var r = 0; r < 4; r++) {
+        for (var d = 0; d < 8; d++) {
+            let angle = f32(d) * 0.7853982;
+            let offset = vec2<f32>(cos(angle), sin(angle)) * radii[r];
+            let tap = clamp(pixel + vec2<i32>(offset), vec2<i32>(0), max_pixel);
+            let v = textureLoad(velocity_texture, tap, 0).xy;
+
+            // This pixel is on the tap's trail if the tap moved away from it
+            // by at least the tap distance during the shutter interval
+            let trail = v * dims * params.motion_blur_shutter;
+            if (dot(offset, trail) > 0.0 && dot(trail, trail) >= dot(offset, offset)
+                && dot(v, v) > dot(best, best)) {
+                best = v;
+            }
+        }
+    }
+    return best;
+}
+
+// Average the scene along the motion path (shutter closing at frame time)
+fn motion_blur(uv: vec2<f32>) -> vec3<f32> {
+    var streak = blur_velocity(uv) * params.motion_blur_shutter;
+    let len = length(streak);
+    if (len > MAX_BLUR) {
+        streak *= MAX_BLUR / len;
+    }
+
+    let n = params.motion_blur_samples;
+    var sum = vec3<f32>(0.0);
+    for (var i = 0u; i < n; i++) {
+        let t = f32(i) / f32(n - 1u);
+        sum += sample_scene(uv + streak * t);
+    }
+    return sum / f32(n);
+}
+
+// sRGB-encoded to linear
+fn srgb_to_linear(srgb: vec3<f32>) -> vec3<f32> {
+    let cutoff = srgb < vec3<f32>(0.04045);
+    let higher = pow((srgb + vec3<f32>(0.055)) / 1.055, vec3<f32>(2.4));
+    let lower = srgb / 12.92;
+    return select(higher, lower, cutoff);
+}
+
+// Encode linear output light with the output transfer function
+fn encode_output(linear: vec3<f32>) -> vec3<f32> {
+    switch params.output_transfer {
+        case 1u: {
+            return linear;
+        }
+        case 2u: {
+            let higher = vec3<f32>(1.099) * pow(linear, vec3<f32>(0.45)) - vec3<f32>(0.099);
+            return select(higher, linear * 4.5, linear < vec3<f32>(0.018));
+        }
+        case 3u: {
+            return pow(linear, vec3<f32>(1.0 / 2.2));
+        }
+        default: {
+            return linear_to_srgb(linear);
+        }
+    }
+}
+
+// Integer hash (PCG) mapped to [0, 1)
+fn hash(v: u32) -> f32 {
+    let state = v * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return f32((word >> 22u) ^ word) / 4294967296.0;
+}
+
+// Approximately Gaussian grain value (std 1) for a pixel and frame
+fn grain_noise(pixel: vec2<u32>) -> f32 {
+    let base = pixel.x * 1973u + pixel.y * 9277u + params.grain_seed * 26699u;
+    // Sum of four uniforms: mean 2, variance 1/3
+    let sum = hash(base) + hash(base ^ 0x9e3779b9u) + hash(base ^ 0x85ebca6bu) + hash(base ^ 0xc2b2ae35u);
+    return (sum - 2.0) * 1.7320508;
+}
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Lens distortion: where this output pixel looks in the pinhole render
+    let uv = undistort_uv(in.uv);
+
     // Sample HDR color
-    let hdr_color = textureSample(hdr_texture, hdr_sampler, in.uv).rgb;
+    var hdr_color: vec3<f32>;
+    if (params.motion_blur_samples > 1u && params.motion_blur_shutter > 0.0) {
+        hdr_color = motion_blur(uv);
+    } else {
+        hdr_color = sample_scene(uv);
+    }
 
     // Apply exposure
     let exposed = hdr_color * params.exposure;
 
     // Apply ACES tonemapping
-    let tonemapped = aces_tonemap(exposed);
+    var tonemapped = aces_tonemap(exposed);
+
+    // Vignetting (cos^4 law, raised to the configured strength)
+    let image_pos = uv_to_image(in.uv);
+    let r2 = dot(image_pos, image_pos);
+    tonemapped *= pow(1.0 / (1.0 + r2), 2.0 * params.vignette);
+
+    // Barrel distortion pulls in rays from outside the rendered field of view
+    let outside = any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0));
+    tonemapped = select(tonemapped, vec3<f32>(0.0), outside);
+
+    // Color grading and grain work on display-referred values
+    var display = linear_to_srgb(clamp(tonemapped, vec3<f32>(0.0), vec3<f32>(1.0)));
+    if (params.lut_strength > 0.0) {
+        let lut_coord = (display * (params.lut_size - 1.0) + vec3<f32>(0.5)) / params.lut_size;
+        let graded = textureSampleLevel(lut_texture, hdr_sampler, lut_coord, 0.0).rgb;
+        display = mix(display, graded, params.lut_strength);
+    }
+    if (params.grain > 0.0) {
+        // Grain is strongest in the midtones, like film
+        let luma = dot(display, vec3<f32>(0.2126, 0.7152, 0.0722));
+        let amount = params.grain * 4.0 * luma * (1.0 - luma);
+        display += vec3<f32>(grain_noise(vec2<u32>(in.position.xy)) * amount);
+    }
+    tonemapped = srgb_to_linear(clamp(display, vec3<f32>(0.0), vec3<f32>(1.0)));
 
     // Note: Output format is Rgba8UnormSrgb, which does sRGB conversion automatically
-    // So we output linear values and let the hardware handle gamma
+    // So we output linear values and let the hardware handle gamma. Other
+    // output color spaces write the sRGB-decoded form of their code values,
+    // which the hardware encodes back to exactly those codes
+    tonemapped = clamp(params.output_gamut * tonemapped, vec3<f32>(0.0), vec3<f32>(1.0));
+    if (params.output_transfer != 0u) {
+        tonemapped = srgb_to_linear(encode_output(tonemapped));
+    }
 
     return vec4<f32>(tonemapped, 1.0);
 }
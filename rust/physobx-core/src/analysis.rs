@@ -0,0 +1,310 @@
+//! Analysis of recorded trajectories
+//!
+//! Batch video generation can center clips on the action by asking where the
+//! largest impact, the first topple and the onset of a structure collapse
+//! happened in a recorded rollout. Two rollouts can also be compared body by
+//! body to measure how far they drift apart.
+
+use crate::physics::ImpactEvent;
+
+/// Kind of detected moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MomentKind {
+    /// Impact with the largest impulse
+    PeakImpulse,
+    /// First body whose up axis tilts past the topple angle
+    FirstTopple,
+    /// First frame where enough bodies have dropped from their starting height
+    CollapseOnset,
+}
+
+impl MomentKind {
+    /// Name used in exported labels
+    pub fn name(self) -> &'static str {
+        match self {
+            MomentKind::PeakImpulse => "peak_impulse",
+            MomentKind::FirstTopple => "first_topple",
+            MomentKind::CollapseOnset => "collapse_onset",
+        }
+    }
+}
+
+/// A detected moment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moment {
+    pub kind: MomentKind,
+    /// Simulation time of the moment
+    pub time: f32,
+    /// Strength: impulse (N·s), tilt angle (radians) or fraction of bodies
+    /// that eventually dropped
+    pub score: f32,
+    /// Body involved, if the moment is about one body
+    pub body: Option<usize>,
+}
+
+/// Thresholds for [`detect_moments`]
+#[derive(Debug, Clone, Copy)]
+pub struct MomentSettings {
+    /// Tilt of a body's up axis from its starting orientation that counts as
+    /// toppled (radians)
+    pub topple_angle: f32,
+    /// Drop below the starting height that counts a body as fallen (m)
+    pub collapse_drop: f32,
+    /// Fraction of bodies that must have fallen for a collapse
+    pub collapse_fraction: f32,
+}
+
+impl Default for MomentSettings {
+    fn default() -> Self {
+        Self {
+            topple_angle: std::f32::consts::FRAC_PI_4,
+            collapse_drop: 0.25,
+            collapse_fraction: 0.1,
+        }
+    }
+}
+
+/// Body poses sampled over a rollout
+#[derive(Debug, Clone, Default)]
+pub struct Trajectory {
+    /// Simulation time of each frame
+    pub times: Vec<f32>,
+    /// Positions of all bodies per frame
+    pub positions: Vec<Vec<[f32; 3]>>,
+    /// Rotations (x, y, z, w) of all bodies per frame
+    pub rotations: Vec<Vec<[f32; 4]>>,
+}
+
+impl Trajectory {
+    /// Append a frame
+    pub fn push(&mut self, time: f32, positions: &[[f32; 3]], rotations: &[[f32; 4]]) {
+        self.times.push(time);
+        self.positions.push(positions.to_vec());
+        self.rotations.push(rotations.to_vec());
+    }
+
+    /// Number of frames
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    /// Whether no frames were recorded
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+}
+
+/// A trajectory flattened to one row per body per frame, the long format
+/// that Arrow, Polars and pandas tables expect
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TrajectoryColumns {
+    /// Frame index of each row
+    pub frame: Vec<u32>,
+    /// Simulation time of each row
+    pub time: Vec<f32>,
+    /// Body index of each row
+    pub body: Vec<u32>,
+    /// x, y, z position columns
+    pub positions: [Vec<f32>; 3],
+    /// x, y, z, w quaternion columns
+    pub rotations: [Vec<f32>; 4],
+}
+
+impl TrajectoryColumns {
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.frame.len()
+    }
+
+    /// Whether there are no rows
+    pub fn is_empty(&self) -> bool {
+        self.frame.is_empty()
+    }
+}
+
+impl Trajectory {
+    /// Split the frames into columns, rows ordered by frame then body
+    pub fn to_columns(&self) -> TrajectoryColumns {
+        let rows: usize = self.positions.iter().map(Vec::len).sum();
+        let mut columns = TrajectoryColumns {
+            frame: Vec::with_capacity(rows),
+            time: Vec::with_capacity(rows),
+            body: Vec::with_capacity(rows),
+            positions: std::array::from_fn(|_| Vec::with_capacity(rows)),
+            rotations: std::array::from_fn(|_| Vec::with_capacity(rows)),
+        };
+        for (frame, ((&time, positions), rotations)) in self.times.iter().zip(&self.positions).zip(&self.rotations).enumerate() {
+            for (body, (p, r)) in positions.iter().zip(rotations).enumerate() {
+                columns.frame.push(frame as u32);
+                columns.time.push(time);
+                columns.body.push(body as u32);
+                for (column, &v) in columns.positions.iter_mut().zip(p) {
+                    column.push(v);
+                }
+                for (column, &v) in columns.rotations.iter_mut().zip(r) {
+                    column.push(v);
+                }
+            }
+        }
+        columns
+    }
+}
+
+/// Detect the peak impulse, first topple and collapse onset, sorted by time
+///
+/// Topples and drops are measured against each body's pose in the first
+/// frame. Moments that never happen are omitted.
+pub fn detect_moments(trajectory: &Trajectory, impacts: &[ImpactEvent], settings: &MomentSettings) -> Vec<Moment> {
+    let mut moments = Vec::new();
+
+    if let Some(peak) = impacts.iter().max_by(|a, b| a.impulse.total_cmp(&b.impulse)) {
+        moments.push(Moment {
+            kind: MomentKind::PeakImpulse,
+            time: peak.time,
+            score: peak.impulse,
+            body: peak.body_a.or(peak.body_b),
+        });
+    }
+
+    if let (Some(start_pos), Some(start_rot)) = (trajectory.positions.first(), trajectory.rotations.first()) {
+        let start_up: Vec<[f32; 3]> = start_rot.iter().map(|&q| up_axis(q)).collect();
+
+        // First topple: earliest frame where any body's up axis tilts too far
+        'topple: for (frame, rotations) in trajectory.rotations.iter().enumerate() {
+            for (body, (&q, up0)) in rotations.iter().zip(&start_up).enumerate() {
+                let up = up_axis(q);
+                let cos = (up[0] * up0[0] + up[1] * up0[1] + up[2] * up0[2]).clamp(-1.0, 1.0);
+                let tilt = cos.acos();
+                if tilt > settings.topple_angle {
+                    moments.push(Moment {
+                        kind: MomentKind::FirstTopple,
+                        time: trajectory.times[frame],
+                        score: tilt,
+                        body: Some(body),
+                    });
+                    break 'topple;
+                }
+            }
+        }
+
+        // Collapse onset: first frame where enough bodies have dropped
+        let count = start_pos.len();
+        if count > 0 {
+            let fallen_fraction = |positions: &[[f32; 3]]| {
+                let fallen = positions.iter().zip(start_pos)
+                    .filter(|(p, p0)| p0[1] - p[1] > settings.collapse_drop)
+                    .count();
+                fallen as f32 / count as f32
+            };
+            let onset = trajectory.positions.iter()
+                .position(|positions| fallen_fraction(positions) >= settings.collapse_fraction);
+            if let Some(frame) = onset {
+                let last = trajectory.positions.last().map_or(0.0, |p| fallen_fraction(p));
+                moments.push(Moment {
+                    kind: MomentKind::CollapseOnset,
+                    time: trajectory.times[frame],
+                    score: last,
+                    body: None,
+                });
+            }
+        }
+    }
+
+    moments.sort_by(|a, b| a.time.total_cmp(&b.time));
+    moments
+}
+
+/// Per-body positional divergence between two runs of the same scene
+#[derive(Debug, Clone, Default)]
+pub struct TrajectoryComparison {
+    /// Distance between matching bodies, per frame then per body (m)
+    pub divergence: Vec<Vec<f32>>,
+    /// Mean divergence over bodies, per frame
+    pub mean: Vec<f32>,
+    /// Largest divergence over bodies, per frame
+    pub max: Vec<f32>,
+    /// Largest divergence over frames, per body
+    pub per_body_max: Vec<f32>,
+    /// Mean divergence in the last compared frame
+    pub final_mean: f32,
+    /// Largest divergence anywhere
+    pub overall_max: f32,
+    /// First frame where any body diverges by more than the threshold
+    pub first_divergent_frame: Option<usize>,
+}
+
+/// Compare two trajectories frame by frame
+///
+/// Frames and bodies beyond the shorter trajectory are ignored. Useful to
+/// quantify non-determinism (same scene, two runs) or parameter sensitivity
+/// (one parameter changed).
+pub fn compare_trajectories(a: &Trajectory, b: &Trajectory, threshold: f32) -> TrajectoryComparison {
+    let divergence: Vec<Vec<f32>> = a.positions.iter().zip(&b.positions)
+        .map(|(pa, pb)| {
+            pa.iter().zip(pb)
+                .map(|(p, q)| ((p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2)).sqrt())
+                .collect()
+        })
+        .collect();
+
+    let mean: Vec<f32> = divergence.iter()
+        .map(|d| if d.is_empty() { 0.0 } else { d.iter().sum::<f32>() / d.len() as f32 })
+        .collect();
+    let max: Vec<f32> = divergence.iter()
+        .map(|d| d.iter().copied().fold(0.0, f32::max))
+        .collect();
+
+    let bodies = divergence.first().map_or(0, Vec::len);
+    let per_body_max = (0..bodies)
+        .map(|i| divergence.iter().filter_map(|d| d.get(i).copied()).fold(0.0, f32::max))
+        .collect();
+
+    TrajectoryComparison {
+        final_mean: mean.last().copied().unwrap_or(0.0),
+        overall_max: max.iter().copied().fold(0.0, f32::max),
+        first_divergent_frame: max.iter().position(|&m| m > threshold),
+        divergence,
+        mean,
+        max,
+        per_body_max,
+    }
+}
+
+/// Heat-map color for a divergence (blue = none, through yellow, to red at `max`)
+pub fn divergence_color(value: f32, max: f32) -> [f32; 3] {
+    let t = if max > 0.0 { (value / max).clamp(0.0, 1.0) } else { 0.0 };
+    if t < 0.5 {
+        let k = t * 2.0;
+        [k, k, 1.0 - k]
+    } else {
+        let k = (t - 0.5) * 2.0;
+        [1.0, 1.0 - k, 0.0]
+    }
+}
+
+/// Distinct color for an island id (golden-ratio hue steps, so neighboring
+/// ids contrast)
+pub fn island_color(id: usize) -> [f32; 3] {
+    let hue = (id as f32 * 0.618_034).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    // Slightly desaturated so shading stays readable
+    [0.15 + 0.8 * r, 0.15 + 0.8 * g, 0.15 + 0.8 * b]
+}
+
+/// Body-frame +Y axis in world space for rotation `q` (x, y, z, w)
+fn up_axis(q: [f32; 4]) -> [f32; 3] {
+    let [x, y, z, w] = q;
+    [
+        2.0 * (x * y - w * z),
+        1.0 - 2.0 * (x * x + z * z),
+        2.0 * (y * z + w * x),
+    ]
+}
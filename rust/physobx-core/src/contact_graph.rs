@@ -0,0 +1,103 @@
+//! Contact network export
+//!
+//! Granular assemblies carry load along force chains: strings of bodies
+//! pressed together much harder than their neighbors. A [`ContactGraph`] is
+//! one step's contact network (bodies as nodes, touching pairs as edges
+//! weighted by the normal impulse between them), written as a plain edge
+//! list or as GraphML for NetworkX, igraph or Gephi.
+
+use crate::physics::BodyContact;
+use std::fmt::Write as _;
+use std::io;
+
+/// Bodies and contacts of a simulation after one step
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ContactGraph {
+    /// Simulation time of the step
+    pub time: f32,
+    /// Position of every body (one node per body)
+    pub positions: Vec<[f32; 3]>,
+    /// Whether each body is static
+    pub statics: Vec<bool>,
+    /// Touching pairs sorted by body, ground contacts (`body_b` None) last
+    pub edges: Vec<BodyContact>,
+}
+
+impl ContactGraph {
+    /// Graph of `contacts` between bodies at `positions`
+    pub fn new(time: f32, positions: Vec<[f32; 3]>, statics: Vec<bool>, mut contacts: Vec<BodyContact>) -> Self {
+        contacts.sort_by_key(|c| (c.body_b.is_none(), c.body_a, c.body_b));
+        Self { time, positions, statics, edges: contacts }
+    }
+
+    /// Number of contacts of every body, ground included
+    pub fn degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.positions.len()];
+        for edge in &self.edges {
+            for body in std::iter::once(edge.body_a).chain(edge.body_b) {
+                if let Some(d) = degrees.get_mut(body) {
+                    *d += 1;
+                }
+            }
+        }
+        degrees
+    }
+
+    /// Whitespace-separated edge list, one `body_a body_b impulse` line per
+    /// contact after a `#` header; the ground is body -1
+    pub fn to_edge_list(&self) -> String {
+        let mut out = String::from("# body_a body_b impulse\n");
+        for edge in &self.edges {
+            let b = edge.body_b.map_or(-1, |b| b as i64);
+            let _ = writeln!(out, "{} {} {}", edge.body_a, b, edge.impulse);
+        }
+        out
+    }
+
+    /// GraphML document with a node per body (position and static flag as
+    /// attributes), a "ground" node if anything touches it and an undirected
+    /// edge per contact carrying its impulse
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        for (id, name) in [("x", "x"), ("y", "y"), ("z", "z")] {
+            let _ = writeln!(out, "  <key id=\"{id}\" for=\"node\" attr.name=\"{name}\" attr.type=\"float\"/>");
+        }
+        out.push_str("  <key id=\"static\" for=\"node\" attr.name=\"static\" attr.type=\"boolean\"/>\n");
+        out.push_str("  <key id=\"impulse\" for=\"edge\" attr.name=\"impulse\" attr.type=\"float\"/>\n");
+        let _ = writeln!(out, "  <graph id=\"contacts_t{}\" edgedefault=\"undirected\">", self.time);
+
+        for (i, p) in self.positions.iter().enumerate() {
+            let is_static = self.statics.get(i).copied().unwrap_or(false);
+            let _ = writeln!(
+                out,
+                "    <node id=\"b{i}\"><data key=\"x\">{}</data><data key=\"y\">{}</data><data key=\"z\">{}</data><data key=\"static\">{is_static}</data></node>",
+                p[0], p[1], p[2],
+            );
+        }
+        if self.edges.iter().any(|e| e.body_b.is_none()) {
+            out.push_str("    <node id=\"ground\"><data key=\"static\">true</data></node>\n");
+        }
+        for edge in &self.edges {
+            let target = edge.body_b.map_or_else(|| "ground".to_string(), |b| format!("b{b}"));
+            let _ = writeln!(
+                out,
+                "    <edge source=\"b{}\" target=\"{target}\"><data key=\"impulse\">{}</data></edge>",
+                edge.body_a, edge.impulse,
+            );
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    /// Write the edge list to `path`
+    pub fn save_edge_list(&self, path: &str) -> io::Result<()> {
+        std::fs::write(path, self.to_edge_list())
+    }
+
+    /// Write the GraphML document to `path`
+    pub fn save_graphml(&self, path: &str) -> io::Result<()> {
+        std::fs::write(path, self.to_graphml())
+    }
+}
@@ -0,0 +1,562 @@
+//! Instanced renderer for shapes symmetric about their local Y axis
+//!
+//! Capsules, cylinders and cones each have one unit mesh: every vertex is
+//! placed at `position * radius + cap * half_height` along Y, so a single
+//! mesh serves every radius and length. One [`AxialRenderer`] draws one
+//! shape of [`ShapeType::AXIAL`] (e.g. the cylinder renderer is the one
+//! built for [`ShapeType::Cylinder`]).
+//!
+//! A cone's slanted side tilts with its proportions, so its normals are
+//! marked to be rescaled per instance like the surface itself.
+
+use super::camera::{Camera, CameraUniform};
+use super::context::GpuContext;
+use super::shaders::{build_checked, ShaderError, AXIAL_SHADER};
+use super::render_target::{OffscreenTarget, HDR_FORMAT, VELOCITY_FORMAT};
+use super::shadow::ShadowRenderer;
+use super::instance_renderer::{surface_of, PrevTransform, ShadowUniform};
+use crate::scene::builder::ShapeType;
+use crate::simulator::AxialInstances;
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+/// Vertex data for an axial mesh
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct AxialVertex {
+    /// Position on a unit-radius shape
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    /// Offset along local Y in half heights
+    pub cap: f32,
+    /// 1 where the normal follows the instance's radius to half height
+    /// ratio (slanted sides), 0 where it is fixed
+    pub stretch: f32,
+}
+
+impl AxialVertex {
+    const ATTRIBS: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
+        0 => Float32x3,  // position
+        1 => Float32x3,  // normal
+        2 => Float32,    // cap
+        3 => Float32,    // stretch
+    ];
+
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<AxialVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+}
+
+/// Instance data for axial shapes (position + radius + rotation + color +
+/// half height + shading)
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct AxialInstanceData {
+    pub position: [f32; 3],
+    pub radius: f32,
+    pub rotation: [f32; 4],
+    pub color: [f32; 3],
+    pub half_height: f32,
+    pub roughness: f32,
+    pub metallic: f32,
+    pub _padding: [f32; 2],
+}
+
+impl AxialInstanceData {
+    /// Instance `i` of `instances`
+    pub(crate) fn of(instances: &AxialInstances, i: usize) -> Self {
+        let [roughness, metallic] = surface_of(&instances.surfaces, i);
+        Self {
+            position: instances.positions[i],
+            radius: instances.radii[i],
+            rotation: instances.rotations[i],
+            color: instances.colors[i],
+            half_height: instances.half_heights[i],
+            roughness,
+            metallic,
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+/// Vertex and index buffers of one shape's unit mesh
+pub(crate) struct AxialMesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+}
+
+impl AxialMesh {
+    pub fn new(ctx: &GpuContext, shape: ShapeType, label: &str) -> Self {
+        let (vertices, indices) = create_axial_geometry(shape);
+        let vertex_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{label} Vertex Buffer")),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(&format!("{label} Index Buffer")),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        Self { vertex_buffer, index_buffer, index_count: indices.len() as u32 }
+    }
+
+    /// Bind the mesh and draw `instances`
+    pub fn draw(&self, pass: &mut wgpu::RenderPass, instances: std::ops::Range<u32>) {
+        pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+        pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        pass.draw_indexed(0..self.index_count, 0, instances);
+    }
+}
+
+/// Axial shape instance renderer using GPU instancing
+pub struct AxialRenderer {
+    shape: ShapeType,
+    render_pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    mesh: AxialMesh,
+    instance_buffer: wgpu::Buffer,
+    prev_buffer: wgpu::Buffer,
+    camera_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    // Shadow bindings
+    shadow_bind_group_layout: wgpu::BindGroupLayout,
+    shadow_uniform_buffer: wgpu::Buffer,
+    shadow_bind_group: Option<wgpu::BindGroup>,
+    max_instances: u32,
+}
+
+impl AxialRenderer {
+    /// Create a renderer for one of [`ShapeType::AXIAL`]
+    pub fn new(ctx: &GpuContext, max_instances: u32, shape: ShapeType) -> Self {
+        let mesh = AxialMesh::new(ctx, shape, "Axial");
+
+        // Instance buffer
+        let instance_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Axial Instance Buffer"),
+            size: (max_instances as u64) * std::mem::size_of::<AxialInstanceData>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Previous-frame transforms (motion vectors)
+        let prev_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Axial Previous Transform Buffer"),
+            size: (max_instances as u64) * std::mem::size_of::<PrevTransform>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Camera uniform buffer
+        let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Axial Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Bind group layout
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Axial Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        // Bind group
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Axial Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: instance_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: prev_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Shadow bind group layout (group 1)
+        let shadow_bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Axial Shadow Bind Group Layout"),
+            entries: &[
+                // Shadow uniforms (light view-projection)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Shadow map texture
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Shadow sampler (comparison)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                    count: None,
+                },
+            ],
+        });
+
+        // Shadow uniform buffer
+        let shadow_uniform_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Axial Shadow Uniform Buffer"),
+            size: std::mem::size_of::<ShadowUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Pipeline layout (includes shadow bind group)
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Axial Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout, &shadow_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Render pipeline
+        let render_pipeline = Self::create_pipeline(ctx, &pipeline_layout, AXIAL_SHADER.source);
+
+        Self {
+            shape,
+            render_pipeline,
+            pipeline_layout,
+            mesh,
+            instance_buffer,
+            prev_buffer,
+            camera_buffer,
+            bind_group,
+            shadow_bind_group_layout,
+            shadow_uniform_buffer,
+            shadow_bind_group: None,
+            max_instances,
+        }
+    }
+
+    /// Shape drawn by this renderer
+    pub fn shape(&self) -> ShapeType {
+        self.shape
+    }
+
+    /// Compile `source` into the render pipeline
+    fn create_pipeline(ctx: &GpuContext, layout: &wgpu::PipelineLayout, source: &str) -> wgpu::RenderPipeline {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Axial Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Axial Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[AxialVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: HDR_FORMAT,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: VELOCITY_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Rebuild the pipeline from new shader source, keeping the current one
+    /// if it fails to compile
+    pub fn reload_shader(&mut self, ctx: &GpuContext, source: &str) -> Result<(), ShaderError> {
+        self.render_pipeline = build_checked(ctx, AXIAL_SHADER.file, || Self::create_pipeline(ctx, &self.pipeline_layout, source))?;
+        Ok(())
+    }
+
+    /// Upload instance data
+    pub fn upload_instances(&self, ctx: &GpuContext, instances: &AxialInstances) {
+        self.upload_instances_at(ctx, 0, instances);
+    }
+
+    /// Upload instance data into the slots starting at `first`
+    ///
+    /// Slots before `first` keep their contents (e.g. static geometry).
+    pub fn upload_instances_at(&self, ctx: &GpuContext, first: u32, instances: &AxialInstances) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = instances.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.instance_buffer, first, instance_count, |i| AxialInstanceData::of(instances, i));
+    }
+
+    /// Upload previous-frame transforms into the slots starting at `first`
+    pub fn upload_previous_at(&self, ctx: &GpuContext, first: u32, positions: &[[f32; 3]], rotations: &[[f32; 4]]) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = positions.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.prev_buffer, first, instance_count, |i| PrevTransform {
+            position: positions[i],
+            _padding: 0.0,
+            rotation: rotations[i],
+        });
+    }
+
+    /// Update camera uniform
+    pub fn update_camera(&self, ctx: &GpuContext, camera: &Camera) {
+        let uniform = camera.uniform();
+        ctx.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Setup shadow bind group with shadow renderer
+    pub fn setup_shadow(&mut self, ctx: &GpuContext, shadow_renderer: &ShadowRenderer) {
+        let shadow_bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Axial Shadow Bind Group"),
+            layout: &self.shadow_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.shadow_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&shadow_renderer.shadow_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&shadow_renderer.shadow_sampler),
+                },
+            ],
+        });
+        self.shadow_bind_group = Some(shadow_bind_group);
+    }
+
+    /// Update shadow uniforms (light view-projection matrix)
+    pub fn update_shadow(&self, ctx: &GpuContext, light_view_proj: [[f32; 4]; 4]) {
+        let uniform = ShadowUniform { light_view_proj };
+        ctx.queue.write_buffer(&self.shadow_uniform_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Render instances to HDR target
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &OffscreenTarget,
+        instance_count: u32,
+    ) {
+        if instance_count == 0 {
+            return;
+        }
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Axial Render Pass"),
+            color_attachments: &[
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.hdr_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.velocity_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+            ],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        target.apply_scissor(&mut render_pass);
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+
+        // Set shadow bind group if available
+        if let Some(ref shadow_bind_group) = self.shadow_bind_group {
+            render_pass.set_bind_group(1, shadow_bind_group, &[]);
+        }
+
+        self.mesh.draw(&mut render_pass, 0..instance_count);
+    }
+}
+
+/// Create the unit mesh of an axial shape
+///
+/// Cubes and spheres have meshes of their own and produce an empty mesh.
+pub(crate) fn create_axial_geometry(shape: ShapeType) -> (Vec<AxialVertex>, Vec<u16>) {
+    const SEGMENTS: u32 = 16;
+    const RINGS: u32 = 12;
+    let pi = std::f32::consts::PI;
+
+    // Profile from the top pole down: ring radius, height, cap offset,
+    // (radial, axial) normal and normal stretch
+    let mut profile: Vec<(f32, f32, f32, [f32; 2], f32)> = Vec::new();
+    match shape {
+        ShapeType::Capsule => {
+            // Two hemispheres, the equator repeated so the side spans the gap
+            for (rings, cap) in [(0..=RINGS / 2, 1.0), (RINGS / 2..=RINGS, -1.0)] {
+                for ring in rings {
+                    let phi = pi * ring as f32 / RINGS as f32;
+                    let (sin_phi, cos_phi) = phi.sin_cos();
+                    profile.push((sin_phi, cos_phi, cap, [sin_phi, cos_phi], 0.0));
+                }
+            }
+        }
+        ShapeType::Cylinder => {
+            // End disks and the side, each ring repeated where the normal changes
+            let up = [0.0, 1.0];
+            let down = [0.0, -1.0];
+            let out = [1.0, 0.0];
+            profile.extend([
+                (0.0, 0.0, 1.0, up, 0.0),
+                (1.0, 0.0, 1.0, up, 0.0),
+                (1.0, 0.0, 1.0, out, 0.0),
+                (1.0, 0.0, -1.0, out, 0.0),
+                (1.0, 0.0, -1.0, down, 0.0),
+                (0.0, 0.0, -1.0, down, 0.0),
+            ]);
+        }
+        ShapeType::Cone => {
+            // Side normal of the unit cone (radius 1, height 2), stretched
+            // by radius / half_height in the shader
+            let slant = [2.0 / 5f32.sqrt(), 1.0 / 5f32.sqrt()];
+            let down = [0.0, -1.0];
+            profile.extend([
+                (0.0, 0.0, 1.0, slant, 1.0),
+                (1.0, 0.0, -1.0, slant, 1.0),
+                (1.0, 0.0, -1.0, down, 0.0),
+                (0.0, 0.0, -1.0, down, 0.0),
+            ]);
+        }
+        ShapeType::Cube | ShapeType::Sphere => return (Vec::new(), Vec::new()),
+    }
+
+    lathe(&profile, SEGMENTS)
+}
+
+/// Revolve a profile about the Y axis
+fn lathe(profile: &[(f32, f32, f32, [f32; 2], f32)], segments: u32) -> (Vec<AxialVertex>, Vec<u16>) {
+    let mut vertices = Vec::with_capacity(profile.len() * (segments as usize + 1));
+    let mut indices = Vec::new();
+
+    for &(radius, y, cap, [normal_r, normal_y], stretch) in profile {
+        for seg in 0..=segments {
+            let theta = 2.0 * std::f32::consts::PI * seg as f32 / segments as f32;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+            vertices.push(AxialVertex {
+                position: [radius * cos_theta, y, radius * sin_theta],
+                normal: [normal_r * cos_theta, normal_y, normal_r * sin_theta],
+                cap,
+                stretch,
+            });
+        }
+    }
+
+    // Two triangles per quad, wound like the sphere mesh
+    for ring in 0..profile.len() as u32 - 1 {
+        for seg in 0..segments {
+            let current = ring * (segments + 1) + seg;
+            let next = current + segments + 1;
+
+            indices.push(current as u16);
+            indices.push(next as u16);
+            indices.push((current + 1) as u16);
+
+            indices.push((current + 1) as u16);
+            indices.push(next as u16);
+            indices.push((next + 1) as u16);
+        }
+    }
+
+    (vertices, indices)
+}
@@ -13,7 +13,19 @@ pub struct CameraUniform {
     pub eye_position: [f32; 4],
 }
 
-/// 3D camera with perspective projection
+/// A world point projected into the image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProjectedPoint {
+    /// Pixel coordinates (origin at the top-left image corner, +Y down); NaN
+    /// for points behind the camera
+    pub pixel: [f32; 2],
+    /// Distance in front of the camera along its view axis
+    pub depth: f32,
+    /// Inside the image and between the near and far planes
+    pub in_frustum: bool,
+}
+
+/// 3D camera with perspective or orthographic projection
 #[derive(Debug, Clone)]
 pub struct Camera {
     /// Camera position
@@ -30,6 +42,8 @@ pub struct Camera {
     pub near: f32,
     /// Far clipping plane
     pub far: f32,
+    /// Visible world height of an orthographic projection; None = perspective
+    pub ortho_height: Option<f32>,
 }
 
 impl Default for Camera {
@@ -42,6 +56,7 @@ impl Default for Camera {
             aspect: 16.0 / 9.0,
             near: 0.1,
             far: 1000.0,
+            ortho_height: None,
         }
     }
 }
@@ -57,6 +72,35 @@ impl Camera {
         }
     }
 
+    /// Orthographic camera looking down -Z that frames the xy rectangle
+    /// `min`..`max` (plus a 10% border), for planar scenes
+    pub fn planar(min: [f32; 2], max: [f32; 2], aspect: f32) -> Self {
+        let center = [(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5];
+        let height = (max[1] - min[1]).max((max[0] - min[0]) / aspect).max(1e-3) * 1.1;
+        Self {
+            eye: Point3::new(center[0], center[1], 100.0),
+            target: Point3::new(center[0], center[1], 0.0),
+            aspect,
+            ortho_height: Some(height),
+            ..Default::default()
+        }
+    }
+
+    /// Orthographic camera looking straight down at `center`, showing at
+    /// least `extent` meters in every direction (+X to the right, -Z up the
+    /// image), e.g. for minimaps
+    pub fn top_down(center: [f32; 3], extent: f32, aspect: f32) -> Self {
+        let height = (2.0 * extent).max(2.0 * extent / aspect).max(1e-3);
+        Self {
+            eye: Point3::new(center[0], center[1] + 100.0, center[2]),
+            target: Point3::new(center[0], center[1], center[2]),
+            up: Vector3::new(0.0, 0.0, -1.0),
+            aspect,
+            ortho_height: Some(height),
+            ..Default::default()
+        }
+    }
+
     /// Set aspect ratio from width and height
     pub fn set_aspect(&mut self, width: u32, height: u32) {
         self.aspect = width as f32 / height as f32;
@@ -69,7 +113,13 @@ impl Camera {
 
     /// Get projection matrix
     pub fn projection_matrix(&self) -> Matrix4<f32> {
-        Matrix4::new_perspective(self.aspect, self.fov_y, self.near, self.far)
+        match self.ortho_height {
+            Some(height) => {
+                let (h, w) = (height * 0.5, height * 0.5 * self.aspect);
+                Matrix4::new_orthographic(-w, w, -h, h, self.near, self.far)
+            }
+            None => Matrix4::new_perspective(self.aspect, self.fov_y, self.near, self.far),
+        }
     }
 
     /// Get combined view-projection matrix
@@ -77,6 +127,83 @@ impl Camera {
         self.projection_matrix() * self.view_matrix()
     }
 
+    /// Project a world point through the ideal pinhole (or orthographic) camera
+    pub fn project(&self, point: [f32; 3], width: u32, height: u32) -> ProjectedPoint {
+        let view = self.view_matrix().transform_point(&Point3::from(point));
+        let depth = -view.z;
+        let pixel = match self.ortho_scale() {
+            Some(scale) => self.normalized_to_pixel([view.x * scale, view.y * scale], width, height),
+            None if depth > 0.0 => self.normalized_to_pixel([view.x / depth, view.y / depth], width, height),
+            None => [f32::NAN; 2],
+        };
+        ProjectedPoint {
+            pixel,
+            depth,
+            in_frustum: self.in_frustum(pixel, depth, width, height),
+        }
+    }
+
+    /// World point at a pixel and depth (distance along the view axis)
+    ///
+    /// Inverse of [`Camera::project`] for the ideal camera.
+    pub fn unproject(&self, pixel: [f32; 2], depth: f32, width: u32, height: u32) -> [f32; 3] {
+        let [x, y] = self.pixel_to_normalized(pixel, width, height);
+        let view_point = match self.ortho_scale() {
+            Some(scale) => Point3::new(x / scale, y / scale, -depth),
+            None => Point3::new(x * depth, y * depth, -depth),
+        };
+        let world = self.view_matrix()
+            .try_inverse()
+            .map_or(view_point, |inv| inv.transform_point(&view_point));
+        world.into()
+    }
+
+    /// Ray from the eye through a pixel: (origin, unit direction)
+    ///
+    /// Orthographic rays start on the eye plane and run along the view axis.
+    pub fn screen_ray(&self, pixel: [f32; 2], width: u32, height: u32) -> ([f32; 3], [f32; 3]) {
+        if self.ortho_height.is_some() {
+            let direction = (self.target - self.eye).normalize();
+            return (self.unproject(pixel, 0.0, width, height), direction.into());
+        }
+        let through = Point3::from(self.unproject(pixel, 1.0, width, height));
+        let direction = (through - self.eye).normalize();
+        (self.eye.into(), direction.into())
+    }
+
+    /// Whether a projected pixel and depth lie inside the view frustum
+    pub fn in_frustum(&self, pixel: [f32; 2], depth: f32, width: u32, height: u32) -> bool {
+        depth >= self.near && depth <= self.far
+            && (0.0..=width as f32).contains(&pixel[0])
+            && (0.0..=height as f32).contains(&pixel[1])
+    }
+
+    /// Pixel coordinates of normalized image coordinates (x right, y up, at unit depth)
+    pub fn normalized_to_pixel(&self, p: [f32; 2], width: u32, height: u32) -> [f32; 2] {
+        let tan_half_fov = (self.fov_y * 0.5).tan();
+        [
+            (p[0] / (tan_half_fov * self.aspect) + 1.0) * 0.5 * width as f32,
+            (1.0 - p[1] / tan_half_fov) * 0.5 * height as f32,
+        ]
+    }
+
+    /// Normalized image coordinates (x right, y up, at unit depth) of a pixel
+    pub fn pixel_to_normalized(&self, pixel: [f32; 2], width: u32, height: u32) -> [f32; 2] {
+        let tan_half_fov = (self.fov_y * 0.5).tan();
+        [
+            (pixel[0] / width as f32 * 2.0 - 1.0) * tan_half_fov * self.aspect,
+            (1.0 - pixel[1] / height as f32 * 2.0) * tan_half_fov,
+        ]
+    }
+
+    /// Normalized image units per view-space unit of an orthographic camera
+    ///
+    /// Orthographic views share the normalized image coordinates (and so the
+    /// lens model) of a pinhole camera with the same field of view.
+    fn ortho_scale(&self) -> Option<f32> {
+        self.ortho_height.map(|h| (self.fov_y * 0.5).tan() / (h * 0.5).max(1e-6))
+    }
+
     /// Get camera uniform for GPU
     pub fn uniform(&self) -> CameraUniform {
         let view = self.view_matrix();
@@ -0,0 +1,296 @@
+//! Output color spaces: transfer function, primaries and ICC profiles
+//!
+//! Scenes are lit in linear light with Rec.709/sRGB primaries. The tonemap
+//! pass converts the tonemapped image to the output primaries and encodes it
+//! with the output transfer function, so 8-bit frames carry exactly the code
+//! values of the chosen space. Saved PNGs can embed a matching ICC profile
+//! so other tools interpret them correctly.
+
+/// Encoding of linear light into 8-bit code values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransferFunction {
+    /// IEC 61966-2-1 piecewise sRGB curve
+    #[default]
+    Srgb,
+    /// No encoding (linear light in 8 bits, for compositing)
+    Linear,
+    /// ITU-R BT.709 camera OETF
+    Rec709,
+    /// Pure 2.2 power law
+    Gamma22,
+}
+
+impl TransferFunction {
+    /// Parse a transfer function name ("srgb", "linear", "rec709" or "gamma2.2")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace(['-', '_', '.'], "").as_str() {
+            "srgb" => Some(TransferFunction::Srgb),
+            "linear" => Some(TransferFunction::Linear),
+            "rec709" | "bt709" => Some(TransferFunction::Rec709),
+            "gamma22" => Some(TransferFunction::Gamma22),
+            _ => None,
+        }
+    }
+
+    /// Id used by the tonemap shader
+    pub fn id(self) -> u32 {
+        match self {
+            TransferFunction::Srgb => 0,
+            TransferFunction::Linear => 1,
+            TransferFunction::Rec709 => 2,
+            TransferFunction::Gamma22 => 3,
+        }
+    }
+
+    /// Encode a linear value in [0, 1]
+    pub fn encode(self, linear: f32) -> f32 {
+        let l = linear.clamp(0.0, 1.0);
+        match self {
+            TransferFunction::Srgb if l < 0.0031308 => 12.92 * l,
+            TransferFunction::Srgb => 1.055 * l.powf(1.0 / 2.4) - 0.055,
+            TransferFunction::Linear => l,
+            TransferFunction::Rec709 if l < 0.018 => 4.5 * l,
+            TransferFunction::Rec709 => 1.099 * l.powf(0.45) - 0.099,
+            TransferFunction::Gamma22 => l.powf(1.0 / 2.2),
+        }
+    }
+
+    /// Decode a code value in [0, 1] back to linear
+    pub fn decode(self, encoded: f32) -> f32 {
+        let v = encoded.clamp(0.0, 1.0);
+        match self {
+            TransferFunction::Srgb if v < 0.04045 => v / 12.92,
+            TransferFunction::Srgb => ((v + 0.055) / 1.055).powf(2.4),
+            TransferFunction::Linear => v,
+            TransferFunction::Rec709 if v < 0.081 => v / 4.5,
+            TransferFunction::Rec709 => ((v + 0.099) / 1.099).powf(1.0 / 0.45),
+            TransferFunction::Gamma22 => v.powf(2.2),
+        }
+    }
+
+    /// ICC parametric curve (function type, parameters) of [`TransferFunction::decode`]
+    fn icc_curve(self) -> (u16, Vec<f64>) {
+        match self {
+            TransferFunction::Srgb => (3, vec![2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045]),
+            TransferFunction::Linear => (0, vec![1.0]),
+            TransferFunction::Rec709 => (3, vec![1.0 / 0.45, 1.0 / 1.099, 0.099 / 1.099, 1.0 / 4.5, 0.081]),
+            TransferFunction::Gamma22 => (0, vec![2.2]),
+        }
+    }
+}
+
+/// RGB primaries, all with a D65 white point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Primaries {
+    /// ITU-R BT.709, shared with sRGB (the rendering primaries)
+    #[default]
+    Rec709,
+    /// DCI-P3 primaries with a D65 white point
+    DisplayP3,
+    /// ITU-R BT.2020 wide gamut
+    Rec2020,
+}
+
+/// CIE xy chromaticity of the D65 white point
+const D65: [f64; 2] = [0.3127, 0.3290];
+
+/// XYZ of the ICC profile connection space illuminant (D50)
+const PCS_D50: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+impl Primaries {
+    /// Parse a primaries name ("rec709"/"srgb", "display-p3" or "rec2020")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace(['-', '_', '.'], "").as_str() {
+            "rec709" | "bt709" | "srgb" => Some(Primaries::Rec709),
+            "displayp3" | "p3" => Some(Primaries::DisplayP3),
+            "rec2020" | "bt2020" => Some(Primaries::Rec2020),
+            _ => None,
+        }
+    }
+
+    /// CIE xy chromaticities of the red, green and blue primaries
+    pub fn chromaticities(self) -> [[f64; 2]; 3] {
+        match self {
+            Primaries::Rec709 => [[0.640, 0.330], [0.300, 0.600], [0.150, 0.060]],
+            Primaries::DisplayP3 => [[0.680, 0.320], [0.265, 0.690], [0.150, 0.060]],
+            Primaries::Rec2020 => [[0.708, 0.292], [0.170, 0.797], [0.131, 0.046]],
+        }
+    }
+
+    /// Linear RGB to CIE XYZ (D65) matrix, row-major
+    pub fn to_xyz(self) -> [[f64; 3]; 3] {
+        let columns = self.chromaticities().map(xy_to_xyz);
+        let primaries = [0, 1, 2].map(|row| columns.map(|c| c[row]));
+        let scale = mul_vec(&invert(&primaries), xy_to_xyz(D65));
+        primaries.map(|row| [row[0] * scale[0], row[1] * scale[1], row[2] * scale[2]])
+    }
+}
+
+/// Color space of rendered frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OutputColorSpace {
+    pub primaries: Primaries,
+    pub transfer: TransferFunction,
+}
+
+impl OutputColorSpace {
+    /// sRGB, the default
+    pub const SRGB: Self = Self { primaries: Primaries::Rec709, transfer: TransferFunction::Srgb };
+    /// Linear light with sRGB primaries
+    pub const LINEAR_SRGB: Self = Self { primaries: Primaries::Rec709, transfer: TransferFunction::Linear };
+    /// HD video (BT.709 primaries and OETF)
+    pub const REC709: Self = Self { primaries: Primaries::Rec709, transfer: TransferFunction::Rec709 };
+    /// Display P3 (P3 primaries, sRGB curve)
+    pub const DISPLAY_P3: Self = Self { primaries: Primaries::DisplayP3, transfer: TransferFunction::Srgb };
+
+    /// Linear Rec.709 to linear output primaries, row-major
+    pub fn gamut_matrix(&self) -> [[f32; 3]; 3] {
+        let m = mul(&invert(&self.primaries.to_xyz()), &Primaries::Rec709.to_xyz());
+        m.map(|row| row.map(|v| v as f32))
+    }
+
+    /// Human-readable name, e.g. "sRGB" or "Display P3 (linear)"
+    pub fn description(&self) -> String {
+        let primaries = match self.primaries {
+            Primaries::Rec709 => "Rec.709",
+            Primaries::DisplayP3 => "Display P3",
+            Primaries::Rec2020 => "Rec.2020",
+        };
+        match (self.primaries, self.transfer) {
+            (Primaries::Rec709, TransferFunction::Srgb) => "sRGB".to_string(),
+            (Primaries::DisplayP3, TransferFunction::Srgb) => primaries.to_string(),
+            (_, TransferFunction::Srgb) => format!("{primaries} (sRGB curve)"),
+            (_, TransferFunction::Linear) => format!("{primaries} (linear)"),
+            (_, TransferFunction::Rec709) => format!("{primaries} (BT.709 curve)"),
+            (_, TransferFunction::Gamma22) => format!("{primaries} (gamma 2.2)"),
+        }
+    }
+
+    /// ICC v4 display profile describing frames in this color space
+    pub fn icc_profile(&self) -> Vec<u8> {
+        // Colorants are the primaries chromatically adapted to the D50 PCS
+        let adapt = bradford(xy_to_xyz(D65), PCS_D50);
+        let colorants = mul(&adapt, &self.primaries.to_xyz());
+        let colorant = |i: usize| xyz_tag([colorants[0][i], colorants[1][i], colorants[2][i]]);
+
+        let (function, params) = self.transfer.icc_curve();
+        let mut curve = b"para\0\0\0\0".to_vec();
+        curve.extend_from_slice(&function.to_be_bytes());
+        curve.extend_from_slice(&[0, 0]);
+        for p in params {
+            curve.extend_from_slice(&s15_fixed16(p));
+        }
+        let mut chad = b"sf32\0\0\0\0".to_vec();
+        for v in adapt.iter().flatten() {
+            chad.extend_from_slice(&s15_fixed16(*v));
+        }
+
+        let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+            (b"desc", mluc_tag(&self.description())),
+            (b"cprt", mluc_tag("No copyright, use freely")),
+            (b"wtpt", xyz_tag(PCS_D50)),
+            (b"chad", chad),
+            (b"rXYZ", colorant(0)),
+            (b"gXYZ", colorant(1)),
+            (b"bXYZ", colorant(2)),
+            (b"rTRC", curve.clone()),
+            (b"gTRC", curve.clone()),
+            (b"bTRC", curve),
+        ];
+
+        // Tag data follows the header and tag table, 4-byte aligned
+        let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+        let mut data = Vec::new();
+        let data_start = 128 + 4 + 12 * tags.len();
+        for (signature, bytes) in &tags {
+            table.extend_from_slice(*signature);
+            table.extend_from_slice(&((data_start + data.len()) as u32).to_be_bytes());
+            table.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+            data.extend_from_slice(bytes);
+            data.resize(data.len().next_multiple_of(4), 0);
+        }
+
+        let size = (data_start + data.len()) as u32;
+        let mut profile = Vec::with_capacity(size as usize);
+        profile.extend_from_slice(&size.to_be_bytes());
+        profile.extend_from_slice(&[0; 4]); // Preferred CMM
+        profile.extend_from_slice(&0x0430_0000u32.to_be_bytes()); // Version 4.3
+        profile.extend_from_slice(b"mntrRGB XYZ ");
+        for field in [2024u16, 1, 1, 0, 0, 0] {
+            profile.extend_from_slice(&field.to_be_bytes()); // Creation date
+        }
+        profile.extend_from_slice(b"acsp");
+        profile.extend_from_slice(&[0; 24]); // Platform, flags, device, attributes
+        profile.extend_from_slice(&[0; 4]); // Perceptual intent
+        profile.extend_from_slice(&xyz_tag(PCS_D50)[8..]);
+        profile.extend_from_slice(&[0; 4]); // Creator
+        profile.extend_from_slice(&[0; 16]); // Profile ID (not computed)
+        profile.extend_from_slice(&[0; 28]);
+        profile.extend_from_slice(&table);
+        profile.extend_from_slice(&data);
+        profile
+    }
+}
+
+/// ICC s15Fixed16Number
+fn s15_fixed16(v: f64) -> [u8; 4] {
+    ((v * 65536.0).round() as i32).to_be_bytes()
+}
+
+/// ICC XYZType holding one value
+fn xyz_tag(xyz: [f64; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for v in xyz {
+        tag.extend_from_slice(&s15_fixed16(v));
+    }
+    tag
+}
+
+/// ICC multiLocalizedUnicodeType with one en-US record
+fn mluc_tag(text: &str) -> Vec<u8> {
+    let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let mut tag = b"mluc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&1u32.to_be_bytes());
+    tag.extend_from_slice(&12u32.to_be_bytes());
+    tag.extend_from_slice(b"enUS");
+    tag.extend_from_slice(&(utf16.len() as u32).to_be_bytes());
+    tag.extend_from_slice(&28u32.to_be_bytes());
+    tag.extend_from_slice(&utf16);
+    tag
+}
+
+/// XYZ (Y = 1) of an xy chromaticity
+fn xy_to_xyz([x, y]: [f64; 2]) -> [f64; 3] {
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+/// Bradford chromatic adaptation from white `from` to white `to` (XYZ)
+fn bradford(from: [f64; 3], to: [f64; 3]) -> [[f64; 3]; 3] {
+    const CONE: [[f64; 3]; 3] = [
+        [0.8951, 0.2664, -0.1614],
+        [-0.7502, 1.7135, 0.0367],
+        [0.0389, -0.0685, 1.0296],
+    ];
+    let (src, dst) = (mul_vec(&CONE, from), mul_vec(&CONE, to));
+    let scale = [0, 1, 2].map(|i| CONE[i].map(|v| v * dst[i] / src[i]));
+    mul(&invert(&CONE), &scale)
+}
+
+fn mul(a: &[[f64; 3]; 3], b: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    [0, 1, 2].map(|i| [0, 1, 2].map(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+fn mul_vec(a: &[[f64; 3]; 3], v: [f64; 3]) -> [f64; 3] {
+    a.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn invert(m: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let cofactor = |r: usize, c: usize| {
+        let (r0, r1) = ((r + 1) % 3, (r + 2) % 3);
+        let (c0, c1) = ((c + 1) % 3, (c + 2) % 3);
+        m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0]
+    };
+    let det: f64 = (0..3).map(|c| m[0][c] * cofactor(0, c)).sum();
+    // Inverse is the transposed cofactor matrix over the determinant
+    [0, 1, 2].map(|i| [0, 1, 2].map(|j| cofactor(j, i) / det))
+}
@@ -0,0 +1,124 @@
+//! Layered frame compositing for diagnostic videos
+//!
+//! A composite frame is a stack of layers rendered from the same camera,
+//! each blended over the ones below with its own opacity and blend mode:
+//! the shaded frame, bodies tinted by instance, per-body heatmaps, or only
+//! the annotations (labels, measurements, grids). Blending happens on the
+//! final 8-bit RGBA images, starting from opaque black.
+
+use crate::analysis::island_color;
+
+/// What a layer shows
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayerSource {
+    /// The regular frame, including annotations
+    Color,
+    /// Only the annotations drawn over the frame, transparent elsewhere
+    Annotations,
+    /// Bodies as flat colors by index, transparent background
+    Segmentation,
+    /// Shaded frame with bodies colored by constraint island
+    Islands,
+    /// Shaded frame with bodies colored by temperature from `min` (blue) to
+    /// `max` (red)
+    Temperature { min: f32, max: f32 },
+    /// Shaded frame with bodies colored by one value each, from 0 (blue)
+    /// to `max` (red)
+    Heatmap { values: Vec<f32>, max: f32 },
+}
+
+/// How a layer combines with the image below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlendMode {
+    /// Layer over the image
+    #[default]
+    Normal,
+    /// Darken by the layer color
+    Multiply,
+    /// Lighten by the layer color
+    Screen,
+    /// Add the layer color
+    Add,
+}
+
+impl BlendMode {
+    /// Parse a blend mode name ("normal", "multiply", "screen" or "add")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "normal" => Some(BlendMode::Normal),
+            "multiply" => Some(BlendMode::Multiply),
+            "screen" => Some(BlendMode::Screen),
+            "add" => Some(BlendMode::Add),
+            _ => None,
+        }
+    }
+
+    /// Blended value of base `b` and layer `l` channels in [0, 1]
+    fn apply(self, b: f32, l: f32) -> f32 {
+        match self {
+            BlendMode::Normal => l,
+            BlendMode::Multiply => b * l,
+            BlendMode::Screen => 1.0 - (1.0 - b) * (1.0 - l),
+            BlendMode::Add => (b + l).min(1.0),
+        }
+    }
+}
+
+/// One layer of a composite frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layer {
+    pub source: LayerSource,
+    /// Opacity in [0, 1], multiplied with the layer's own alpha
+    pub opacity: f32,
+    pub blend: BlendMode,
+}
+
+impl Layer {
+    /// Fully opaque layer with normal blending
+    pub fn new(source: LayerSource) -> Self {
+        Self { source, opacity: 1.0, blend: BlendMode::Normal }
+    }
+}
+
+/// Blend RGBA `layer` into the RGBA image `base` with the given opacity
+pub fn blend_into(base: &mut [u8], layer: &[u8], opacity: f32, mode: BlendMode) {
+    let opacity = opacity.clamp(0.0, 1.0);
+    for (b, l) in base.chunks_exact_mut(4).zip(layer.chunks_exact(4)) {
+        let alpha = opacity * l[3] as f32 / 255.0;
+        if alpha <= 0.0 {
+            continue;
+        }
+        for k in 0..3 {
+            let (bk, lk) = (b[k] as f32 / 255.0, l[k] as f32 / 255.0);
+            let blended = mode.apply(bk, lk);
+            b[k] = ((bk + (blended - bk) * alpha) * 255.0).round() as u8;
+        }
+        b[3] = 255;
+    }
+}
+
+/// RGBA image of flat per-body colors from a body index image (-1 =
+/// background, left transparent)
+pub fn segmentation_pixels(ids: &[i32]) -> Vec<u8> {
+    let mut pixels = vec![0u8; ids.len() * 4];
+    for (pixel, &id) in pixels.chunks_exact_mut(4).zip(ids) {
+        if id < 0 {
+            continue;
+        }
+        let [r, g, b] = island_color(id as usize).map(|c| (c * 255.0).round() as u8);
+        pixel.copy_from_slice(&[r, g, b, 255]);
+    }
+    pixels
+}
+
+/// RGBA image keeping the pixels where `annotated` differs from `plain`
+/// (the same frame without annotations), transparent elsewhere
+pub fn annotation_pixels(annotated: &[u8], plain: &[u8]) -> Vec<u8> {
+    let mut pixels = vec![0u8; annotated.len()];
+    for ((pixel, a), p) in pixels.chunks_exact_mut(4).zip(annotated.chunks_exact(4)).zip(plain.chunks_exact(4)) {
+        if a[..3] != p[..3] {
+            pixel.copy_from_slice(&[a[0], a[1], a[2], 255]);
+        }
+    }
+    pixels
+}
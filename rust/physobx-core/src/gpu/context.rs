@@ -70,4 +70,28 @@ impl GpuContext {
             queue,
         })
     }
+
+    /// Write `count` values produced by `value(i)` into consecutive elements
+    /// of `buffer` starting at element `first`
+    ///
+    /// Values are encoded straight into the queue's staging memory, so
+    /// per-frame instance uploads need no intermediate Vec.
+    pub fn write_elements<T: bytemuck::Pod>(
+        &self,
+        buffer: &wgpu::Buffer,
+        first: usize,
+        count: usize,
+        value: impl Fn(usize) -> T,
+    ) {
+        let stride = std::mem::size_of::<T>();
+        let Some(size) = wgpu::BufferSize::new((count * stride) as u64) else {
+            return;
+        };
+        let Some(mut view) = self.queue.write_buffer_with(buffer, (first * stride) as u64, size) else {
+            return;
+        };
+        for (i, bytes) in view.chunks_exact_mut(stride).enumerate() {
+            bytes.copy_from_slice(bytemuck::bytes_of(&value(i)));
+        }
+    }
 }
@@ -0,0 +1,88 @@
+//! Persistent impact decals (scorch marks) on the ground plane
+
+use crate::physics::ImpactEvent;
+
+/// Decal texture resolution (texels per side)
+pub const DECAL_MAP_SIZE: u32 = 1024;
+
+/// Texture format of the decal map (single channel darkness)
+pub const DECAL_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+/// CPU-side darkness map covering the ground plane
+///
+/// Texel (0, 0) sits at world (-size, -size) and the map spans the full
+/// ground quad, matching the ground shader's UV mapping.
+pub struct DecalMap {
+    /// Darkness per texel (0 = clean, 255 = fully scorched)
+    pub texels: Vec<u8>,
+    /// Ground half-size covered by the map
+    pub ground_size: f32,
+    /// Minimum impact impulse that leaves a mark
+    pub min_impulse: f32,
+    /// Decal radius in world units for an impact at `min_impulse`
+    pub base_radius: f32,
+}
+
+impl DecalMap {
+    /// Create an empty decal map for a ground plane of the given half-size
+    pub fn new(ground_size: f32) -> Self {
+        Self {
+            texels: vec![0; (DECAL_MAP_SIZE * DECAL_MAP_SIZE) as usize],
+            ground_size,
+            min_impulse: 50.0,
+            base_radius: 0.5,
+        }
+    }
+
+    /// Remove all decals
+    pub fn clear(&mut self) {
+        self.texels.fill(0);
+    }
+
+    /// Stamp a round, soft-edged mark centered at world (x, z)
+    pub fn stamp(&mut self, x: f32, z: f32, radius: f32, strength: f32) {
+        let size = DECAL_MAP_SIZE as f32;
+        let texels_per_unit = size / (2.0 * self.ground_size);
+        let cx = (x + self.ground_size) * texels_per_unit;
+        let cz = (z + self.ground_size) * texels_per_unit;
+        let r = (radius * texels_per_unit).max(1.0);
+
+        let x0 = (cx - r).floor().max(0.0) as i32;
+        let x1 = (cx + r).ceil().min(size - 1.0) as i32;
+        let z0 = (cz - r).floor().max(0.0) as i32;
+        let z1 = (cz + r).ceil().min(size - 1.0) as i32;
+
+        for tz in z0..=z1 {
+            for tx in x0..=x1 {
+                let d = ((tx as f32 + 0.5 - cx).powi(2) + (tz as f32 + 0.5 - cz).powi(2)).sqrt() / r;
+                if d >= 1.0 {
+                    continue;
+                }
+                // Smooth falloff towards the rim; overlapping marks keep the darkest value
+                let falloff = 1.0 - d * d;
+                let darkness = (strength.clamp(0.0, 1.0) * falloff * 255.0) as u8;
+                let texel = &mut self.texels[(tz as u32 * DECAL_MAP_SIZE + tx as u32) as usize];
+                *texel = (*texel).max(darkness);
+            }
+        }
+    }
+
+    /// Stamp marks for ground impacts stronger than `min_impulse`
+    ///
+    /// Returns true if any mark was added.
+    pub fn stamp_impacts(&mut self, events: &[ImpactEvent]) -> bool {
+        let mut stamped = false;
+        for event in events {
+            let hits_ground = event.body_a.is_none() || event.body_b.is_none();
+            if !hits_ground || event.impulse < self.min_impulse {
+                continue;
+            }
+            // Radius grows with the square root of the impulse so big hits leave big marks
+            let scale = (event.impulse / self.min_impulse).sqrt();
+            let strength = 0.35 + 0.15 * scale;
+            self.stamp(event.point[0], event.point[2], self.base_radius * scale, strength);
+            stamped = true;
+        }
+        stamped
+    }
+}
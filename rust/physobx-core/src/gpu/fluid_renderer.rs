@@ -0,0 +1,309 @@
+//! Screen-space splat renderer for SPH liquids
+//!
+//! Every fluid particle is drawn as a quad facing the camera that ray-traces
+//! a sphere per pixel and writes the sphere's true depth, so splats cut into
+//! each other and into the scene exactly. Splats are drawn larger than the
+//! particles, which fuses neighbors into one continuous, slightly bumpy
+//! surface (a cheap metaball look). The surface is shaded as water: a
+//! fresnel blend of sky reflection over a tinted body plus a specular
+//! highlight, with fast particles fading to foam. Splats neither cast nor
+//! receive shadows.
+
+use super::camera::{Camera, CameraUniform};
+use super::context::GpuContext;
+use super::shaders::{build_checked, ShaderError, FLUID_SHADER};
+use super::render_target::{OffscreenTarget, HDR_FORMAT, VELOCITY_FORMAT};
+use crate::physics::FluidSystem;
+use bytemuck::{Pod, Zeroable};
+use parking_lot::Mutex;
+
+/// Splat slots allocated before the first upload
+const INITIAL_CAPACITY: usize = 1024;
+
+/// Per-particle splat data
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct FluidInstance {
+    pub position: [f32; 3],
+    pub radius: f32,
+    pub color: [f32; 3],
+    pub _padding: f32,
+}
+
+/// How fluid particles are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FluidDisplay {
+    /// Splat radius as a multiple of the particle radius; above 1 neighbors
+    /// merge into one surface
+    pub blob_scale: f32,
+    /// Body color of the liquid
+    pub color: [f32; 3],
+    /// Color of the fastest particles (spray and foam)
+    pub foam_color: [f32; 3],
+    /// Speed (m/s) at which particles are drawn fully in the foam color; 0
+    /// disables foam
+    pub foam_speed: f32,
+}
+
+impl Default for FluidDisplay {
+    fn default() -> Self {
+        Self {
+            blob_scale: 1.5,
+            color: [0.08, 0.32, 0.55],
+            foam_color: [0.9, 0.95, 1.0],
+            foam_speed: 4.0,
+        }
+    }
+}
+
+impl FluidDisplay {
+    /// Refill `out` with one splat per particle of `fluid`
+    pub fn instances_into(&self, fluid: &FluidSystem, out: &mut Vec<FluidInstance>) {
+        out.clear();
+        let radius = fluid.particle_radius() * self.blob_scale.max(0.0);
+        for (p, v) in fluid.positions().iter().zip(fluid.velocities()) {
+            let foam = if self.foam_speed > 0.0 {
+                (v.norm() / self.foam_speed).clamp(0.0, 1.0).powi(2)
+            } else {
+                0.0
+            };
+            out.push(FluidInstance {
+                position: (*p).into(),
+                radius,
+                color: std::array::from_fn(|k| self.color[k] + (self.foam_color[k] - self.color[k]) * foam),
+                _padding: 0.0,
+            });
+        }
+    }
+}
+
+/// Instance buffer, grown to fit the largest fluid uploaded so far
+struct SplatBuffer {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    capacity: usize,
+    /// Scratch splats reused by every upload
+    scratch: Vec<FluidInstance>,
+}
+
+/// Renders fluid particles as ray-traced sphere splats
+pub struct FluidRenderer {
+    pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
+    bind_group_layout: wgpu::BindGroupLayout,
+    camera_buffer: wgpu::Buffer,
+    splats: Mutex<SplatBuffer>,
+}
+
+impl FluidRenderer {
+    pub fn new(ctx: &GpuContext) -> Self {
+        let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Fluid Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Fluid Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Fluid Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = Self::create_pipeline(ctx, &pipeline_layout, FLUID_SHADER.source);
+        let splats = Self::create_splats(ctx, &bind_group_layout, &camera_buffer, INITIAL_CAPACITY);
+
+        Self {
+            pipeline,
+            pipeline_layout,
+            bind_group_layout,
+            camera_buffer,
+            splats: Mutex::new(splats),
+        }
+    }
+
+    /// Allocate an instance buffer for `capacity` splats and its bind group
+    fn create_splats(
+        ctx: &GpuContext,
+        layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+        capacity: usize,
+    ) -> SplatBuffer {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Fluid Instance Buffer"),
+            size: (capacity * std::mem::size_of::<FluidInstance>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Fluid Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        SplatBuffer { buffer, bind_group, capacity, scratch: Vec::new() }
+    }
+
+    /// Compile `source` into the render pipeline
+    fn create_pipeline(ctx: &GpuContext, layout: &wgpu::PipelineLayout, source: &str) -> wgpu::RenderPipeline {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Fluid Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Fluid Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: HDR_FORMAT,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: VELOCITY_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                ],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Rebuild the pipeline from new shader source, keeping the current one
+    /// if it fails to compile
+    pub fn reload_shader(&mut self, ctx: &GpuContext, source: &str) -> Result<(), ShaderError> {
+        self.pipeline = build_checked(ctx, FLUID_SHADER.file, || Self::create_pipeline(ctx, &self.pipeline_layout, source))?;
+        Ok(())
+    }
+
+    /// Update camera uniform
+    pub fn update_camera(&self, ctx: &GpuContext, camera: &Camera) {
+        let uniform = camera.uniform();
+        ctx.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Upload the splats of `fluid`, growing the instance buffer if needed;
+    /// returns the number of splats to draw
+    pub fn upload(&self, ctx: &GpuContext, fluid: &FluidSystem, display: &FluidDisplay) -> u32 {
+        let mut splats = self.splats.lock();
+        let mut scratch = std::mem::take(&mut splats.scratch);
+        display.instances_into(fluid, &mut scratch);
+        if scratch.len() > splats.capacity {
+            let capacity = scratch.len().next_power_of_two();
+            *splats = Self::create_splats(ctx, &self.bind_group_layout, &self.camera_buffer, capacity);
+        }
+        if !scratch.is_empty() {
+            ctx.queue.write_buffer(&splats.buffer, 0, bytemuck::cast_slice(&scratch));
+        }
+        let count = scratch.len() as u32;
+        splats.scratch = scratch;
+        count
+    }
+
+    /// Draw the first `count` uploaded splats into the HDR target
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget, count: u32) {
+        if count == 0 {
+            return;
+        }
+        let splats = self.splats.lock();
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Fluid Render Pass"),
+            color_attachments: &[
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.hdr_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.velocity_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+            ],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        target.apply_scissor(&mut render_pass);
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &splats.bind_group, &[]);
+        render_pass.draw(0..6, 0..count.min(splats.capacity as u32)); // One quad per splat
+    }
+}
@@ -0,0 +1,84 @@
+//! Contact forces drawn as force chains
+//!
+//! Every contact between two bodies becomes a rod of beads from one body
+//! center to the other, thicker and hotter in color the larger the impulse
+//! between them: the classic picture of load paths through a granular pile.
+//! Like springs, the beads are sphere instances, lit and shadowed with the
+//! rest of the scene. Contacts with the ground are not drawn.
+
+use crate::analysis::divergence_color;
+use crate::contact_graph::ContactGraph;
+
+/// Most beads drawn for a single contact
+const MAX_BEADS: usize = 128;
+
+/// How contact forces are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForceChainDisplay {
+    /// Rod diameter of the strongest contact (m); rods thin linearly with
+    /// impulse
+    pub thickness: f32,
+    /// Impulse drawn at full thickness and in red; None uses the strongest
+    /// contact of each frame
+    pub max_impulse: Option<f32>,
+    /// Contacts weaker than this fraction of the maximum are not drawn
+    pub min_fraction: f32,
+    /// Draw the dynamic bodies as well; off by default since the rods run
+    /// through the body centers (static bodies are always drawn)
+    pub show_bodies: bool,
+}
+
+impl Default for ForceChainDisplay {
+    fn default() -> Self {
+        Self {
+            thickness: 0.05,
+            max_impulse: None,
+            min_fraction: 0.05,
+            show_bodies: false,
+        }
+    }
+}
+
+impl ForceChainDisplay {
+    /// Append the beads of every body-body contact of `graph`
+    pub fn beads_into(
+        &self,
+        graph: &ContactGraph,
+        positions: &mut Vec<[f32; 3]>,
+        radii: &mut Vec<f32>,
+        colors: &mut Vec<[f32; 3]>,
+    ) {
+        let max = self.max_impulse.unwrap_or_else(|| {
+            graph.edges.iter().filter(|e| e.body_b.is_some()).map(|e| e.impulse).fold(0.0, f32::max)
+        });
+        if max <= 0.0 {
+            return;
+        }
+
+        for edge in &graph.edges {
+            let Some(b) = edge.body_b else {
+                continue;
+            };
+            let strength = (edge.impulse / max).min(1.0);
+            if strength <= 0.0 || strength < self.min_fraction {
+                continue;
+            }
+            let (Some(&a), Some(&b)) = (graph.positions.get(edge.body_a), graph.positions.get(b)) else {
+                continue;
+            };
+
+            // Beads half a diameter apart read as a solid rod
+            let radius = 0.5 * (self.thickness * strength).max(1e-4);
+            let axis = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let length = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+            let count = ((length / radius).ceil() as usize + 1).clamp(2, MAX_BEADS);
+            let color = divergence_color(strength, 1.0);
+            for k in 0..count {
+                let t = k as f32 / (count - 1) as f32;
+                positions.push(std::array::from_fn(|i| a[i] + axis[i] * t));
+                radii.push(radius);
+                colors.push(color);
+            }
+        }
+    }
+}
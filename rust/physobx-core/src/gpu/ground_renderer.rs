@@ -1,11 +1,21 @@
 //! Ground plane renderer with grid pattern and shadow support
+//!
+//! Besides the horizontal ground, the scene's static planes (ramps, funnel
+//! sides, ...) are drawn with the same grid, as instances of one quad.
 
 use super::camera::{Camera, CameraUniform};
 use super::context::GpuContext;
+use super::shaders::{build_checked, ShaderError, GROUND_SHADER};
 use super::render_target::{OffscreenTarget, HDR_FORMAT};
 use super::shadow::ShadowRenderer;
 use super::instance_renderer::ShadowUniform;
+use super::decals::{DecalMap, DECAL_FORMAT, DECAL_MAP_SIZE};
+use crate::scene::StaticPlane;
 use bytemuck::{Pod, Zeroable};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Static planes drawn besides the ground; later ones are not drawn
+pub const MAX_GROUND_PLANES: usize = 16;
 
 /// Ground plane uniform data
 #[repr(C)]
@@ -14,15 +24,43 @@ pub struct GroundUniform {
     pub ground_y: f32,
     pub ground_size: f32,
     pub grid_scale: f32,
-    pub _padding: f32,
+    /// Static planes drawn after the ground
+    pub plane_count: u32,
+}
+
+/// One static plane: center of the top face with the half size in `w`, and
+/// the unit directions of its sides
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, Pod, Zeroable)]
+pub struct PlaneUniform {
+    pub center: [f32; 4],
+    pub axis_u: [f32; 4],
+    pub axis_v: [f32; 4],
+}
+
+impl PlaneUniform {
+    pub fn new(plane: &StaticPlane) -> Self {
+        let (u, v) = plane.axes();
+        Self {
+            center: [plane.point[0], plane.point[1], plane.point[2], plane.size],
+            axis_u: [u[0], u[1], u[2], 0.0],
+            axis_v: [v[0], v[1], v[2], 0.0],
+        }
+    }
 }
 
 /// Renders a ground plane with grid pattern
 pub struct GroundRenderer {
     pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
     camera_buffer: wgpu::Buffer,
     ground_buffer: wgpu::Buffer,
+    plane_buffer: wgpu::Buffer,
+    /// Uploaded static planes
+    plane_count: AtomicU32,
     bind_group: wgpu::BindGroup,
+    // Impact decal texture (persistent across frames)
+    decal_texture: wgpu::Texture,
     // Shadow bindings
     shadow_bind_group_layout: wgpu::BindGroupLayout,
     shadow_uniform_buffer: wgpu::Buffer,
@@ -33,11 +71,6 @@ pub struct GroundRenderer {
 
 impl GroundRenderer {
     pub fn new(ctx: &GpuContext, ground_y: f32, ground_size: f32) -> Self {
-        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Ground Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/ground.wgsl").into()),
-        });
-
         // Camera buffer
         let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Ground Camera Buffer"),
@@ -54,6 +87,39 @@ impl GroundRenderer {
             mapped_at_creation: false,
         });
 
+        // Static plane buffer (fixed size uniform array)
+        let plane_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Ground Plane Buffer"),
+            size: (std::mem::size_of::<PlaneUniform>() * MAX_GROUND_PLANES) as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Decal texture, cleared to zero (no marks)
+        let decal_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Ground Decal Texture"),
+            size: wgpu::Extent3d {
+                width: DECAL_MAP_SIZE,
+                height: DECAL_MAP_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DECAL_FORMAT,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let decal_view = decal_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let decal_sampler = ctx.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Ground Decal Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
         let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Ground Bind Group Layout"),
             entries: &[
@@ -77,6 +143,35 @@ impl GroundRenderer {
                     },
                     count: None,
                 },
+                // Decal texture
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Decal sampler
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                // Static planes
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -92,6 +187,18 @@ impl GroundRenderer {
                     binding: 1,
                     resource: ground_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&decal_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&decal_sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: plane_buffer.as_entire_binding(),
+                },
             ],
         });
 
@@ -146,9 +253,35 @@ impl GroundRenderer {
             push_constant_ranges: &[],
         });
 
-        let pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let pipeline = Self::create_pipeline(ctx, &pipeline_layout, GROUND_SHADER.source);
+
+        Self {
+            pipeline,
+            pipeline_layout,
+            camera_buffer,
+            ground_buffer,
+            plane_buffer,
+            plane_count: AtomicU32::new(0),
+            bind_group,
+            decal_texture,
+            shadow_bind_group_layout,
+            shadow_uniform_buffer,
+            shadow_bind_group: None,
+            ground_y,
+            ground_size,
+        }
+    }
+
+    /// Compile `source` into the render pipeline
+    fn create_pipeline(ctx: &GpuContext, layout: &wgpu::PipelineLayout, source: &str) -> wgpu::RenderPipeline {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Ground Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Ground Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: Some("vs_main"),
@@ -180,19 +313,14 @@ impl GroundRenderer {
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        Self {
-            pipeline,
-            camera_buffer,
-            ground_buffer,
-            bind_group,
-            shadow_bind_group_layout,
-            shadow_uniform_buffer,
-            shadow_bind_group: None,
-            ground_y,
-            ground_size,
-        }
+    /// Rebuild the pipeline from new shader source, keeping the current one
+    /// if it fails to compile
+    pub fn reload_shader(&mut self, ctx: &GpuContext, source: &str) -> Result<(), ShaderError> {
+        self.pipeline = build_checked(ctx, GROUND_SHADER.file, || Self::create_pipeline(ctx, &self.pipeline_layout, source))?;
+        Ok(())
     }
 
     pub fn update_camera(&self, ctx: &GpuContext, camera: &Camera) {
@@ -205,11 +333,44 @@ impl GroundRenderer {
             ground_y,
             ground_size,
             grid_scale,
-            _padding: 0.0,
+            plane_count: self.plane_count.load(Ordering::Relaxed),
         };
         ctx.queue.write_buffer(&self.ground_buffer, 0, bytemuck::cast_slice(&[uniform]));
     }
 
+    /// Draw these static planes from now on (the first
+    /// [`MAX_GROUND_PLANES`]; an empty slice draws only the ground)
+    pub fn upload_planes(&self, ctx: &GpuContext, planes: &[StaticPlane]) {
+        let uniforms: Vec<PlaneUniform> = planes.iter().take(MAX_GROUND_PLANES).map(PlaneUniform::new).collect();
+        if !uniforms.is_empty() {
+            ctx.queue.write_buffer(&self.plane_buffer, 0, bytemuck::cast_slice(&uniforms));
+        }
+        self.plane_count.store(uniforms.len() as u32, Ordering::Relaxed);
+    }
+
+    /// Upload the CPU decal map to the ground decal texture
+    pub fn upload_decals(&self, ctx: &GpuContext, decals: &DecalMap) {
+        ctx.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.decal_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &decals.texels,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(DECAL_MAP_SIZE),
+                rows_per_image: Some(DECAL_MAP_SIZE),
+            },
+            wgpu::Extent3d {
+                width: DECAL_MAP_SIZE,
+                height: DECAL_MAP_SIZE,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
     /// Setup shadow bind group with shadow renderer
     pub fn setup_shadow(&mut self, ctx: &GpuContext, shadow_renderer: &ShadowRenderer) {
         let shadow_bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -261,6 +422,7 @@ impl GroundRenderer {
             timestamp_writes: None,
             occlusion_query_set: None,
         });
+        target.apply_scissor(&mut render_pass);
 
         render_pass.set_pipeline(&self.pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
@@ -270,7 +432,8 @@ impl GroundRenderer {
             render_pass.set_bind_group(1, shadow_bind_group, &[]);
         }
 
-        render_pass.draw(0..6, 0..1); // Two triangles for quad
+        // Two triangles per quad: the ground, then each static plane
+        render_pass.draw(0..6, 0..1 + self.plane_count.load(Ordering::Relaxed));
     }
 
     pub fn ground_y(&self) -> f32 {
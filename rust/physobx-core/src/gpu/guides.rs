@@ -0,0 +1,172 @@
+//! Reference grids and the world-axis gizmo
+//!
+//! Grids are squares of evenly spaced lines on any plane, for reading heights
+//! and offsets in debug renders where the ground shader's checker is not
+//! enough. The gizmo is a triad of the world X, Y and Z axes in a corner of
+//! the image that turns with the camera. Both are drawn with the overlay line
+//! renderer; the gizmo's axis letters with the label renderer.
+
+use super::camera::Camera;
+use super::labels::{push_text, LabelGlyph};
+use super::overlay_lines::OverlaySegment;
+
+/// Axis colors of the gizmo: X red, Y green, Z blue
+const AXIS_COLORS: [[f32; 4]; 3] = [
+    [0.95, 0.25, 0.25, 1.0],
+    [0.3, 0.85, 0.3, 1.0],
+    [0.3, 0.5, 1.0, 1.0],
+];
+
+/// Grids with more lines than this per direction are thinned out
+const MAX_GRID_LINES: usize = 512;
+
+/// Square of evenly spaced lines on a plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReferenceGrid {
+    /// Center of the grid
+    pub origin: [f32; 3],
+    /// Unit directions spanning the plane; lines run along each of them
+    pub axes: [[f32; 3]; 2],
+    /// Distance from the center to each edge along both axes
+    pub half_size: f32,
+    /// Distance between neighbouring lines
+    pub spacing: f32,
+    /// Line color (alpha blends with the frame)
+    pub color: [f32; 4],
+    /// Line width in pixels
+    pub line_width: f32,
+}
+
+impl Default for ReferenceGrid {
+    fn default() -> Self {
+        Self::horizontal(0.0, 10.0, 1.0)
+    }
+}
+
+impl ReferenceGrid {
+    /// Horizontal (XZ) grid at `height` around the world Y axis
+    pub fn horizontal(height: f32, half_size: f32, spacing: f32) -> Self {
+        Self::on_plane([0.0, height, 0.0], [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]], half_size, spacing)
+    }
+
+    /// Grid around `origin` spanned by `axes`, which are normalized
+    pub fn on_plane(origin: [f32; 3], axes: [[f32; 3]; 2], half_size: f32, spacing: f32) -> Self {
+        Self {
+            origin,
+            axes: axes.map(normalize),
+            half_size,
+            spacing,
+            color: [1.0, 1.0, 1.0, 0.35],
+            line_width: 1.0,
+        }
+    }
+
+    /// Append the grid lines to `out`; grids with a zero-length axis or
+    /// non-positive size or spacing add nothing
+    pub fn segments_into(&self, out: &mut Vec<OverlaySegment>) {
+        if !(self.half_size > 0.0 && self.spacing > 0.0) || self.axes.contains(&[0.0; 3]) {
+            return;
+        }
+        // Lines on whole multiples of the spacing from the center, so the
+        // center line is always drawn
+        let mut spacing = self.spacing;
+        while (self.half_size / spacing) as usize > MAX_GRID_LINES / 2 {
+            spacing *= 2.0;
+        }
+        let steps = (self.half_size / spacing).floor() as i32;
+        let width = self.line_width.max(0.5);
+        for (along, across) in [(0, 1), (1, 0)] {
+            let (u, v) = (self.axes[along], self.axes[across]);
+            for k in -steps..=steps {
+                let offset = k as f32 * spacing;
+                let point = |t: f32| std::array::from_fn(|i| self.origin[i] + v[i] * offset + u[i] * t);
+                out.push(OverlaySegment {
+                    start: point(-self.half_size),
+                    width,
+                    end: point(self.half_size),
+                    tick: 0.0,
+                    color: self.color,
+                });
+            }
+        }
+    }
+}
+
+/// World-axis triad drawn in the lower left corner of the image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AxisGizmo {
+    /// Length in pixels of an axis pointing across the image
+    pub size: f32,
+    /// Distance in pixels from the image edges to the triad's center, beyond
+    /// its size
+    pub margin: f32,
+    /// Line width in pixels
+    pub line_width: f32,
+    /// Draw "X", "Y" and "Z" at the axis tips
+    pub letters: bool,
+}
+
+impl Default for AxisGizmo {
+    fn default() -> Self {
+        Self {
+            size: 60.0,
+            margin: 20.0,
+            line_width: 3.0,
+            letters: true,
+        }
+    }
+}
+
+impl AxisGizmo {
+    /// Center of the triad and the world length of its axes for `camera`
+    /// rendering a `width` x `height` image
+    ///
+    /// The triad sits just beyond the near plane, sized so an axis across the
+    /// image spans `size` pixels.
+    fn placement(&self, camera: &Camera, (width, height): (u32, u32)) -> ([f32; 3], f32) {
+        let depth = camera.near * 2.0;
+        let reach = self.size + self.margin;
+        let pixel = [reach, height as f32 - reach];
+        let center = camera.unproject(pixel, depth, width, height);
+        let side = camera.unproject([pixel[0] + self.size, pixel[1]], depth, width, height);
+        let length = (0..3).map(|i| (side[i] - center[i]).powi(2)).sum::<f32>().sqrt();
+        (center, length)
+    }
+
+    /// Append the three axis lines to `out`
+    pub fn segments_into(&self, camera: &Camera, size: (u32, u32), out: &mut Vec<OverlaySegment>) {
+        let (center, length) = self.placement(camera, size);
+        for (axis, color) in AXIS_COLORS.iter().enumerate() {
+            let mut end = center;
+            end[axis] += length;
+            out.push(OverlaySegment {
+                start: center,
+                width: self.line_width.max(0.5),
+                end,
+                tick: 0.0,
+                color: *color,
+            });
+        }
+    }
+
+    /// Append the axis letters to `out` (nothing if `letters` is off)
+    pub fn glyphs_into(&self, camera: &Camera, size: (u32, u32), out: &mut Vec<LabelGlyph>) {
+        if !self.letters {
+            return;
+        }
+        let (center, length) = self.placement(camera, size);
+        for (axis, letter) in ["X", "Y", "Z"].iter().enumerate() {
+            let mut tip = center;
+            tip[axis] += length * 1.2;
+            push_text(out, tip, letter, -0.5);
+        }
+    }
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if length <= f32::EPSILON {
+        return [0.0; 3];
+    }
+    v.map(|x| x / length)
+}
@@ -0,0 +1,547 @@
+//! Instance-ID pass: per-pixel body IDs and per-body visibility
+//!
+//! Bodies are drawn with flat colors encoding their index (see
+//! [`instance_id_color`](super::metadata::instance_id_color)). A second,
+//! depth-test-free pass counts the pixels each body would cover if nothing
+//! occluded it, giving the denominator for visibility fractions.
+
+use super::camera::{Camera, CameraUniform};
+use super::context::GpuContext;
+use super::instance_renderer::{create_cube_geometry, Vertex};
+use super::metadata::body_from_id_color;
+use super::sphere_renderer::create_sphere_geometry;
+use super::axial_renderer::{AxialMesh, AxialVertex};
+use crate::physics::RigidBodyStorage;
+use crate::scene::builder::ShapeType;
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+/// Instance-ID texture format (body index + 1 in RGB)
+pub const ID_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Per-instance data for the ID pass
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct IdInstance {
+    position: [f32; 3],
+    half_height: f32,
+    rotation: [f32; 4],
+    scale: [f32; 3],
+    body: u32,
+}
+
+/// Instances uploaded per mesh, drawn in this order
+#[derive(Debug, Clone, Copy)]
+struct IdCounts {
+    cubes: u32,
+    spheres: u32,
+    /// One count per shape of [`ShapeType::AXIAL`]
+    axial: [u32; ShapeType::AXIAL.len()],
+}
+
+/// Renders instance IDs and measures occlusion
+pub struct IdRenderer {
+    id_pipeline: wgpu::RenderPipeline,
+    count_pipeline: wgpu::RenderPipeline,
+    axial_id_pipeline: wgpu::RenderPipeline,
+    axial_count_pipeline: wgpu::RenderPipeline,
+    bind_group: wgpu::BindGroup,
+    camera_buffer: wgpu::Buffer,
+    instance_buffer: wgpu::Buffer,
+    count_buffer: wgpu::Buffer,
+    count_readback: wgpu::Buffer,
+    id_texture: wgpu::Texture,
+    id_view: wgpu::TextureView,
+    depth_view: wgpu::TextureView,
+    id_readback: wgpu::Buffer,
+    cube_vertex_buffer: wgpu::Buffer,
+    cube_index_buffer: wgpu::Buffer,
+    cube_index_count: u32,
+    sphere_vertex_buffer: wgpu::Buffer,
+    sphere_index_buffer: wgpu::Buffer,
+    sphere_index_count: u32,
+    axial_meshes: [AxialMesh; ShapeType::AXIAL.len()],
+    max_instances: u32,
+    /// Half extent the cube mesh is baked with
+    half_extent: f32,
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+}
+
+impl IdRenderer {
+    /// Create an ID renderer matching the main renderer's resolution and meshes
+    pub fn new(ctx: &GpuContext, width: u32, height: u32, max_instances: u32, half_extent: f32) -> Self {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("ID Pass Shader"),
+            source: wgpu::ShaderSource::Wgsl(super::shaders::ID_SHADER.source.into()),
+        });
+
+        // Same meshes as the main pass so ID pixels line up with the image
+        let (cube_vertices, cube_indices) = create_cube_geometry(half_extent);
+        let (sphere_vertices, sphere_indices) = create_sphere_geometry(16, 12);
+        let cube_vertex_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("ID Cube Vertex Buffer"),
+            contents: bytemuck::cast_slice(&cube_vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let cube_index_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("ID Cube Index Buffer"),
+            contents: bytemuck::cast_slice(&cube_indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+        let sphere_vertex_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("ID Sphere Vertex Buffer"),
+            contents: bytemuck::cast_slice(&sphere_vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let sphere_index_buffer = ctx.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("ID Sphere Index Buffer"),
+            contents: bytemuck::cast_slice(&sphere_indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("ID Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let instance_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("ID Instance Buffer"),
+            size: (max_instances as u64) * std::mem::size_of::<IdInstance>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let count_size = (max_instances as u64) * 4;
+        let count_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Unoccluded Count Buffer"),
+            size: count_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let count_readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Unoccluded Count Readback"),
+            size: count_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        // ID color and depth targets
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let id_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Instance ID Target"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: ID_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let id_view = id_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("ID Depth Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let padded_bytes_per_row = (width * 4 + 255) & !255;
+        let id_readback = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Instance ID Readback"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ID Pass Bind Group Layout"),
+            entries: &[
+                // Camera uniform
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Instance storage buffer
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // Unoccluded pixel counts (atomics)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("ID Pass Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: instance_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: count_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("ID Pass Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Cube and sphere vertices share the same layout, so one pipeline
+        // draws both; axial meshes get their own
+        let make_pipeline = |label: &str, vertex: &str, buffer: wgpu::VertexBufferLayout, fragment: &str, targets: &[Option<wgpu::ColorTargetState>], depth: wgpu::DepthStencilState| {
+            ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: Some(vertex),
+                    buffers: &[buffer],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: Some(fragment),
+                    targets,
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: Some(wgpu::Face::Back),
+                    ..Default::default()
+                },
+                depth_stencil: Some(depth),
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        let id_targets = [Some(wgpu::ColorTargetState {
+            format: ID_FORMAT,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+        let id_depth = wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        };
+        let count_depth = wgpu::DepthStencilState {
+            format: wgpu::TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        };
+        let id_pipeline = make_pipeline("ID Pipeline", "vs_main", Vertex::desc(), "fs_id", &id_targets, id_depth.clone());
+        let count_pipeline = make_pipeline("Unoccluded Count Pipeline", "vs_main", Vertex::desc(), "fs_count", &[], count_depth.clone());
+        let axial_id_pipeline = make_pipeline("Axial ID Pipeline", "vs_axial", AxialVertex::desc(), "fs_id", &id_targets, id_depth);
+        let axial_count_pipeline = make_pipeline("Axial Unoccluded Count Pipeline", "vs_axial", AxialVertex::desc(), "fs_count", &[], count_depth);
+
+        Self {
+            id_pipeline,
+            count_pipeline,
+            axial_id_pipeline,
+            axial_count_pipeline,
+            bind_group,
+            camera_buffer,
+            instance_buffer,
+            count_buffer,
+            count_readback,
+            id_texture,
+            id_view,
+            depth_view,
+            id_readback,
+            cube_vertex_buffer,
+            cube_index_buffer,
+            cube_index_count: cube_indices.len() as u32,
+            sphere_vertex_buffer,
+            sphere_index_buffer,
+            sphere_index_count: sphere_indices.len() as u32,
+            axial_meshes: ShapeType::AXIAL.map(|shape| AxialMesh::new(ctx, shape, "ID Axial")),
+            max_instances,
+            half_extent,
+            width,
+            height,
+            padded_bytes_per_row,
+        }
+    }
+
+    /// Upload camera and instances (cubes first, then spheres, then each
+    /// axial shape); `cube_half_extents` gives the box size of a cube body,
+    /// and bodies for which `hidden` holds are left out
+    fn upload(
+        &self,
+        ctx: &GpuContext,
+        camera: &Camera,
+        storage: &RigidBodyStorage,
+        cube_half_extents: impl Fn(usize) -> [f32; 3],
+        hidden: impl Fn(usize) -> bool,
+    ) -> IdCounts {
+        ctx.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[camera.uniform()]));
+
+        let instance = |i: usize, scale: [f32; 3]| IdInstance {
+            position: storage.positions[i],
+            half_height: storage.half_heights[i],
+            rotation: storage.rotations[i],
+            scale,
+            body: i as u32,
+        };
+        let limit = self.max_instances as usize;
+        let mut instances: Vec<IdInstance> = storage.indices_of(ShapeType::Cube, None).iter()
+            .filter(|&&i| i < limit && !hidden(i))
+            .map(|&i| instance(i, cube_half_extents(i).map(|h| h / self.half_extent)))
+            .collect();
+        let cube_count = instances.len() as u32;
+        instances.extend(
+            storage.indices_of(ShapeType::Sphere, None).iter()
+                .filter(|&&i| i < limit && !hidden(i))
+                .map(|&i| instance(i, [storage.radii[i]; 3])),
+        );
+        let sphere_count = instances.len() as u32 - cube_count;
+        let axial = ShapeType::AXIAL.map(|shape| {
+            let before = instances.len();
+            instances.extend(
+                storage.indices_of(shape, None).iter()
+                    .filter(|&&i| i < limit && !hidden(i))
+                    .map(|&i| instance(i, [storage.radii[i]; 3])),
+            );
+            (instances.len() - before) as u32
+        });
+
+        ctx.queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+        IdCounts { cubes: cube_count, spheres: sphere_count, axial }
+    }
+
+    /// Draw cubes, spheres and axial shapes with the ID or the counting
+    /// pipelines
+    fn draw(&self, pass: &mut wgpu::RenderPass, counting: bool, counts: IdCounts) {
+        let (pipeline, axial_pipeline) = if counting {
+            (&self.count_pipeline, &self.axial_count_pipeline)
+        } else {
+            (&self.id_pipeline, &self.axial_id_pipeline)
+        };
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &self.bind_group, &[]);
+        let IdCounts { cubes: cube_count, spheres: sphere_count, axial } = counts;
+        if cube_count > 0 {
+            pass.set_vertex_buffer(0, self.cube_vertex_buffer.slice(..));
+            pass.set_index_buffer(self.cube_index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            pass.draw_indexed(0..self.cube_index_count, 0, 0..cube_count);
+        }
+        if sphere_count > 0 {
+            pass.set_vertex_buffer(0, self.sphere_vertex_buffer.slice(..));
+            pass.set_index_buffer(self.sphere_index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+            pass.draw_indexed(0..self.sphere_index_count, 0, cube_count..cube_count + sphere_count);
+        }
+        pass.set_pipeline(axial_pipeline);
+        let mut first = cube_count + sphere_count;
+        for (mesh, count) in self.axial_meshes.iter().zip(axial) {
+            if count > 0 {
+                mesh.draw(pass, first..first + count);
+            }
+            first += count;
+        }
+    }
+
+    /// Record the ID pass (depth-tested, into the ID texture)
+    fn encode_ids(&self, encoder: &mut wgpu::CommandEncoder, counts: IdCounts) {
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("ID Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.id_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        self.draw(&mut pass, false, counts);
+    }
+
+    /// Copy the ID texture into the readback buffer
+    fn copy_ids(&self, encoder: &mut wgpu::CommandEncoder) {
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture: &self.id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.id_readback,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_bytes_per_row),
+                    rows_per_image: Some(self.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Read back the ID image as tightly packed RGBA rows
+    fn read_ids(&self, ctx: &GpuContext) -> Vec<u8> {
+        let data = read_buffer(ctx, &self.id_readback);
+        let row = (self.width * 4) as usize;
+        data.chunks(self.padded_bytes_per_row as usize)
+            .take(self.height as usize)
+            .flat_map(|padded| &padded[..row])
+            .copied()
+            .collect()
+    }
+
+    /// Render the instance-ID image (RGBA; see [`instance_id_color`](super::metadata::instance_id_color))
+    ///
+    /// `cube_half_extents` gives the box size of each cube body; bodies for
+    /// which `hidden` holds are not drawn.
+    pub fn render_ids(
+        &self,
+        ctx: &GpuContext,
+        camera: &Camera,
+        storage: &RigidBodyStorage,
+        cube_half_extents: impl Fn(usize) -> [f32; 3],
+        hidden: impl Fn(usize) -> bool,
+    ) -> Vec<u8> {
+        let counts = self.upload(ctx, camera, storage, cube_half_extents, hidden);
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("ID Encoder"),
+        });
+        self.encode_ids(&mut encoder, counts);
+        self.copy_ids(&mut encoder);
+        ctx.queue.submit(std::iter::once(encoder.finish()));
+        self.read_ids(ctx)
+    }
+
+    /// Fraction of each body's unoccluded on-screen pixels that are visible
+    ///
+    /// Indexed by body; bodies entirely outside the frame report 0.
+    /// `cube_half_extents` gives the box size of each cube body; bodies for
+    /// which `hidden` holds neither occlude nor count as visible.
+    pub fn visibility_fractions(
+        &self,
+        ctx: &GpuContext,
+        camera: &Camera,
+        storage: &RigidBodyStorage,
+        cube_half_extents: impl Fn(usize) -> [f32; 3],
+        hidden: impl Fn(usize) -> bool,
+    ) -> Vec<f32> {
+        let counts = self.upload(ctx, camera, storage, cube_half_extents, hidden);
+        let mut encoder = ctx.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Visibility Encoder"),
+        });
+
+        // Unoccluded pixel counts: every body rasterized with no depth test
+        encoder.clear_buffer(&self.count_buffer, 0, None);
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Unoccluded Count Pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            self.draw(&mut pass, true, counts);
+        }
+        encoder.copy_buffer_to_buffer(&self.count_buffer, 0, &self.count_readback, 0, self.count_buffer.size());
+
+        // Visible pixels come from the depth-tested ID image
+        self.encode_ids(&mut encoder, counts);
+        self.copy_ids(&mut encoder);
+        ctx.queue.submit(std::iter::once(encoder.finish()));
+
+        let counts = read_buffer(ctx, &self.count_readback);
+        let unoccluded: &[u32] = bytemuck::cast_slice(&counts);
+        let mut visible = vec![0u32; storage.len()];
+        for px in self.read_ids(ctx).chunks_exact(4) {
+            if let Some(count) = body_from_id_color([px[0], px[1], px[2]]).and_then(|body| visible.get_mut(body)) {
+                *count += 1;
+            }
+        }
+
+        visible.iter().enumerate()
+            .map(|(body, &seen)| match unoccluded.get(body) {
+                Some(&total) if total > 0 => (seen as f32 / total as f32).min(1.0),
+                _ => 0.0,
+            })
+            .collect()
+    }
+}
+
+/// Map a readback buffer and copy its contents out (blocking)
+fn read_buffer(ctx: &GpuContext, buffer: &wgpu::Buffer) -> Vec<u8> {
+    let slice = buffer.slice(..);
+    let (tx, rx) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        tx.send(result).unwrap();
+    });
+    ctx.device.poll(wgpu::Maintain::Wait);
+    rx.recv().unwrap().unwrap();
+
+    let data = slice.get_mapped_range().to_vec();
+    buffer.unmap();
+    data
+}
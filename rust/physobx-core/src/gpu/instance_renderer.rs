@@ -2,8 +2,10 @@
 
 use super::camera::{Camera, CameraUniform};
 use super::context::GpuContext;
-use super::render_target::{OffscreenTarget, HDR_FORMAT};
+use super::shaders::{build_checked, ShaderError, CUBE_SHADER};
+use super::render_target::{OffscreenTarget, HDR_FORMAT, VELOCITY_FORMAT};
 use super::shadow::ShadowRenderer;
+use crate::scene::materials::SurfaceLook;
 use bytemuck::{Pod, Zeroable};
 
 /// Vertex data for a cube
@@ -20,7 +22,7 @@ impl Vertex {
         1 => Float32x3,  // normal
     ];
 
-    fn desc() -> wgpu::VertexBufferLayout<'static> {
+    pub(crate) fn desc() -> wgpu::VertexBufferLayout<'static> {
         wgpu::VertexBufferLayout {
             array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
             step_mode: wgpu::VertexStepMode::Vertex,
@@ -29,7 +31,7 @@ impl Vertex {
     }
 }
 
-/// Instance data (position + rotation + color)
+/// Instance data (position + rotation + color + scale + shading)
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct InstanceData {
@@ -37,7 +39,51 @@ pub struct InstanceData {
     pub _padding: f32,
     pub rotation: [f32; 4], // quaternion (x, y, z, w)
     pub color: [f32; 3],
-    pub _padding2: f32,
+    pub roughness: f32,
+    pub scale: [f32; 3], // per-axis scale of the baked cube mesh
+    pub metallic: f32,
+}
+
+impl InstanceData {
+    /// Instance `i` of the given arrays; `half_extents` entries scale the
+    /// mesh baked with `mesh_half_extent`, missing ones leave it as is, and
+    /// missing `surfaces` (roughness, metallic) get the default shading
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn of(
+        positions: &[[f32; 3]],
+        rotations: &[[f32; 4]],
+        colors: &[[f32; 3]],
+        half_extents: &[[f32; 3]],
+        surfaces: &[[f32; 2]],
+        mesh_half_extent: f32,
+        i: usize,
+    ) -> Self {
+        let [roughness, metallic] = surface_of(surfaces, i);
+        Self {
+            position: positions[i],
+            _padding: 0.0,
+            rotation: rotations[i],
+            color: colors[i],
+            roughness,
+            scale: half_extents.get(i).map_or([1.0; 3], |h| h.map(|x| x / mesh_half_extent)),
+            metallic,
+        }
+    }
+}
+
+/// Entry `i` of per-instance (roughness, metallic) pairs, the default
+/// shading where missing
+pub(crate) fn surface_of(surfaces: &[[f32; 2]], i: usize) -> [f32; 2] {
+    surfaces.get(i).copied().unwrap_or([SurfaceLook::DEFAULT_ROUGHNESS, SurfaceLook::DEFAULT_METALLIC])
+}
+
+/// Transform of an instance in the previously rendered frame (for motion blur)
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct PrevTransform {
+    pub position: [f32; 3],
+    pub _padding: f32,
+    pub rotation: [f32; 4], // quaternion (x, y, z, w)
 }
 
 /// Shadow uniform data (light view-projection matrix)
@@ -50,9 +96,11 @@ pub struct ShadowUniform {
 /// Instance renderer using GPU instancing
 pub struct InstanceRenderer {
     render_pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     instance_buffer: wgpu::Buffer,
+    prev_buffer: wgpu::Buffer,
     camera_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
     // Shadow bindings
@@ -67,12 +115,6 @@ pub struct InstanceRenderer {
 impl InstanceRenderer {
     /// Create a new instance renderer
     pub fn new(ctx: &GpuContext, max_instances: u32, half_extent: f32) -> Self {
-        // Create shader module
-        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Cube Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/cube_instance.wgsl").into()),
-        });
-
         // Create cube geometry
         let (vertices, indices) = create_cube_geometry(half_extent);
         let index_count = indices.len() as u32;
@@ -97,6 +139,14 @@ impl InstanceRenderer {
             mapped_at_creation: false,
         });
 
+        // Previous-frame transforms (motion vectors)
+        let prev_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Previous Transform Buffer"),
+            size: (max_instances as u64) * std::mem::size_of::<PrevTransform>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         // Camera uniform buffer
         let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Camera Buffer"),
@@ -131,6 +181,17 @@ impl InstanceRenderer {
                     },
                     count: None,
                 },
+                // Previous-frame transform storage buffer
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -147,6 +208,10 @@ impl InstanceRenderer {
                     binding: 1,
                     resource: instance_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: prev_buffer.as_entire_binding(),
+                },
             ],
         });
 
@@ -202,9 +267,36 @@ impl InstanceRenderer {
         });
 
         // Render pipeline
-        let render_pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let render_pipeline = Self::create_pipeline(ctx, &pipeline_layout, CUBE_SHADER.source);
+
+        Self {
+            render_pipeline,
+            pipeline_layout,
+            vertex_buffer,
+            index_buffer,
+            instance_buffer,
+            prev_buffer,
+            camera_buffer,
+            bind_group,
+            shadow_bind_group_layout,
+            shadow_uniform_buffer,
+            shadow_bind_group: None,
+            index_count,
+            max_instances,
+            half_extent,
+        }
+    }
+
+    /// Compile `source` into the render pipeline
+    fn create_pipeline(ctx: &GpuContext, layout: &wgpu::PipelineLayout, source: &str) -> wgpu::RenderPipeline {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Cube Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Render Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: Some("vs_main"),
@@ -214,11 +306,18 @@ impl InstanceRenderer {
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
                 entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: HDR_FORMAT,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: HDR_FORMAT,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: VELOCITY_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                ],
                 compilation_options: Default::default(),
             }),
             primitive: wgpu::PrimitiveState {
@@ -240,46 +339,66 @@ impl InstanceRenderer {
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        Self {
-            render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            instance_buffer,
-            camera_buffer,
-            bind_group,
-            shadow_bind_group_layout,
-            shadow_uniform_buffer,
-            shadow_bind_group: None,
-            index_count,
-            max_instances,
-            half_extent,
-        }
+    /// Rebuild the pipeline from new shader source, keeping the current one
+    /// if it fails to compile
+    pub fn reload_shader(&mut self, ctx: &GpuContext, source: &str) -> Result<(), ShaderError> {
+        self.render_pipeline = build_checked(ctx, CUBE_SHADER.file, || Self::create_pipeline(ctx, &self.pipeline_layout, source))?;
+        Ok(())
     }
 
-    /// Upload instance data from positions, rotations, and colors
+    /// Upload instance data from positions, rotations, colors, half extents
+    /// and (roughness, metallic) surfaces (instances without half extents
+    /// keep the renderer's cube size, without surfaces the default shading)
     pub fn upload_instances(
         &self,
         ctx: &GpuContext,
         positions: &[[f32; 3]],
         rotations: &[[f32; 4]],
         colors: &[[f32; 3]],
+        half_extents: &[[f32; 3]],
+        surfaces: &[[f32; 2]],
     ) {
-        let instance_count = positions.len().min(self.max_instances as usize);
-        let mut instances = Vec::with_capacity(instance_count);
-
-        for i in 0..instance_count {
-            instances.push(InstanceData {
-                position: positions[i],
-                _padding: 0.0,
-                rotation: rotations[i],
-                color: colors[i],
-                _padding2: 0.0,
-            });
-        }
+        self.upload_instances_at(ctx, 0, positions, rotations, colors, half_extents, surfaces);
+    }
+
+    /// Upload instance data into the slots starting at `first`
+    ///
+    /// Slots before `first` keep their contents (e.g. static geometry).
+    #[allow(clippy::too_many_arguments)]
+    pub fn upload_instances_at(
+        &self,
+        ctx: &GpuContext,
+        first: u32,
+        positions: &[[f32; 3]],
+        rotations: &[[f32; 4]],
+        colors: &[[f32; 3]],
+        half_extents: &[[f32; 3]],
+        surfaces: &[[f32; 2]],
+    ) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = positions.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.instance_buffer, first, instance_count, |i| {
+            InstanceData::of(positions, rotations, colors, half_extents, surfaces, self.half_extent, i)
+        });
+    }
+
+    /// Upload the transforms each instance had in the previous frame
+    pub fn upload_previous(&self, ctx: &GpuContext, positions: &[[f32; 3]], rotations: &[[f32; 4]]) {
+        self.upload_previous_at(ctx, 0, positions, rotations);
+    }
 
-        ctx.queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+    /// Upload previous-frame transforms into the slots starting at `first`
+    pub fn upload_previous_at(&self, ctx: &GpuContext, first: u32, positions: &[[f32; 3]], rotations: &[[f32; 4]]) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = positions.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.prev_buffer, first, instance_count, |i| PrevTransform {
+            position: positions[i],
+            _padding: 0.0,
+            rotation: rotations.get(i).copied().unwrap_or([0.0, 0.0, 0.0, 1.0]),
+        });
     }
 
     /// Update camera uniform
@@ -326,14 +445,25 @@ impl InstanceRenderer {
     ) {
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Cube Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &target.hdr_view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load, // Keep sky and ground
-                    store: wgpu::StoreOp::Store,
-                },
-            })],
+            color_attachments: &[
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.hdr_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load, // Keep sky and ground
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.velocity_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        // First pass writing velocity: sky and ground are static
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+            ],
             depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                 view: &target.depth_view,
                 depth_ops: Some(wgpu::Operations {
@@ -345,6 +475,7 @@ impl InstanceRenderer {
             timestamp_writes: None,
             occlusion_query_set: None,
         });
+        target.apply_scissor(&mut render_pass);
 
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
@@ -365,7 +496,7 @@ impl InstanceRenderer {
 /// Create cube vertex and index data with proper flat shading
 /// Each face has 4 unique vertices with the same normal (24 total)
 /// Winding is CCW when viewed from outside the cube
-fn create_cube_geometry(half_extent: f32) -> (Vec<Vertex>, Vec<u16>) {
+pub(crate) fn create_cube_geometry(half_extent: f32) -> (Vec<Vertex>, Vec<u16>) {
     let h = half_extent;
 
     let mut vertices = Vec::with_capacity(24);
@@ -0,0 +1,571 @@
+//! Text labels floating above bodies
+//!
+//! Each label is a row of glyph quads anchored just above its body's
+//! bounding box. Quads face the camera and keep a constant pixel size, so a
+//! label stays readable however far away its body is. Labels are drawn onto
+//! the finished (tonemapped and post-processed) frame, either depth tested
+//! against the scene so bodies in front hide them, or on top of everything.
+//! Glyphs come from a built-in 5x7 ASCII bitmap font (plus `°`); other
+//! characters are drawn as `?`.
+
+use std::ops::Range;
+
+use std::collections::BTreeMap;
+use super::camera::{Camera, CameraUniform};
+use super::context::GpuContext;
+use super::render_target::{OffscreenTarget, LDR_FORMAT};
+use crate::physics::RigidBodyStorage;
+use crate::scene::builder::ShapeType;
+use bytemuck::{Pod, Zeroable};
+use parking_lot::Mutex;
+
+/// Glyph slots allocated before the first upload
+const INITIAL_CAPACITY: usize = 1024;
+
+/// Font cell in atlas texels: a 5x7 glyph plus one texel of spacing
+const CELL_WIDTH: u32 = 6;
+const CELL_HEIGHT: u32 = 8;
+/// Glyph cells per atlas row
+const ATLAS_COLUMNS: u32 = 16;
+const ATLAS_WIDTH: u32 = ATLAS_COLUMNS * CELL_WIDTH;
+const ATLAS_HEIGHT: u32 = (FONT.len() as u32).div_ceil(ATLAS_COLUMNS) * CELL_HEIGHT;
+
+/// Printable ASCII (0x20..=0x7E) followed by the degree sign, five columns
+/// per glyph, bit 0 = top row
+const FONT: [[u8; 5]; 96] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x14, 0x08, 0x3E, 0x08, 0x14], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // F
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x07, 0x08, 0x70, 0x08, 0x07], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x10, 0x08, 0x08, 0x10, 0x08], // ~
+    [0x00, 0x06, 0x09, 0x09, 0x06], // °
+];
+
+/// Glyph index of the degree sign, after printable ASCII
+const DEGREE_GLYPH: u32 = 95;
+
+/// Glyph index of a character in [`FONT`] (`?` for anything not in the font)
+fn glyph_index(c: char) -> u32 {
+    match c {
+        ' '..='~' => c as u32 - ' ' as u32,
+        '°' => DEGREE_GLYPH,
+        _ => '?' as u32 - ' ' as u32,
+    }
+}
+
+/// Rasterize [`FONT`] into an 8-bit coverage atlas of [`ATLAS_COLUMNS`] cells per row
+fn font_atlas() -> Vec<u8> {
+    let mut texels = vec![0u8; (ATLAS_WIDTH * ATLAS_HEIGHT) as usize];
+    for (g, columns) in FONT.iter().enumerate() {
+        let g = g as u32;
+        let x0 = (g % ATLAS_COLUMNS) * CELL_WIDTH;
+        let y0 = (g / ATLAS_COLUMNS) * CELL_HEIGHT;
+        for (x, &bits) in columns.iter().enumerate() {
+            for y in 0..7 {
+                if (bits >> y) & 1 == 1 {
+                    texels[((y0 + y) * ATLAS_WIDTH + x0 + x as u32) as usize] = 255;
+                }
+            }
+        }
+    }
+    texels
+}
+
+/// What a body's label says
+#[derive(Debug, Clone, PartialEq)]
+pub enum LabelText {
+    /// The body's index, e.g. `42`
+    Index,
+    /// Shape name and index, e.g. `cube_0042` (as in Alembic and Blender exports)
+    Name,
+    /// Any string
+    Custom(String),
+}
+
+impl LabelText {
+    /// The label text of body `index`
+    pub fn text(&self, storage: &RigidBodyStorage, index: usize) -> String {
+        match self {
+            LabelText::Index => index.to_string(),
+            LabelText::Name => {
+                let shape = ShapeType::from_id(storage.shape_types[index]).unwrap_or(ShapeType::Cube);
+                format!("{}_{:04}", shape.name(), index)
+            }
+            LabelText::Custom(text) => text.clone(),
+        }
+    }
+}
+
+/// How body labels are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelDisplay {
+    /// Text height in pixels (one font cell, including line spacing)
+    pub pixel_height: f32,
+    /// Text color
+    pub color: [f32; 3],
+    /// Backdrop behind the text as RGBA; alpha 0 draws bare text
+    pub background: [f32; 4],
+    /// Hide labels behind nearer geometry; false draws them on top of everything
+    pub depth_test: bool,
+    /// Gap in meters between the top of a body's bounding box and its label
+    pub lift: f32,
+}
+
+impl Default for LabelDisplay {
+    fn default() -> Self {
+        Self {
+            pixel_height: 16.0,
+            color: [1.0, 1.0, 1.0],
+            background: [0.0, 0.0, 0.0, 0.6],
+            depth_test: true,
+            lift: 0.05,
+        }
+    }
+}
+
+impl LabelDisplay {
+    /// Append one glyph per character of every label to `out`; labels of
+    /// bodies past the end of `storage` are skipped
+    pub fn glyphs_into(&self, storage: &RigidBodyStorage, labels: &BTreeMap<usize, LabelText>, out: &mut Vec<LabelGlyph>) {
+        for (&body, label) in labels.range(..storage.len()) {
+            let (min, max) = storage.aabb(body);
+            let anchor = [(min[0] + max[0]) * 0.5, max[1] + self.lift, (min[2] + max[2]) * 0.5];
+            push_text(out, anchor, &label.text(storage, body), 0.0);
+        }
+    }
+}
+
+/// Append the glyphs of one line of `text` centered horizontally on
+/// `anchor`; `row` shifts it vertically in font cells (0 sits the text on the
+/// anchor, -0.5 centers it there)
+pub fn push_text(out: &mut Vec<LabelGlyph>, anchor: [f32; 3], text: &str, row: f32) {
+    let start = -(text.chars().count() as f32) * 0.5;
+    for (k, c) in text.chars().enumerate() {
+        out.push(LabelGlyph {
+            anchor,
+            glyph: glyph_index(c),
+            offset: [start + k as f32, row],
+            _padding: [0.0; 2],
+        });
+    }
+}
+
+/// One character of a label
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct LabelGlyph {
+    /// World point the label hangs from
+    pub anchor: [f32; 3],
+    /// Index into the built-in font
+    pub glyph: u32,
+    /// Position of the glyph's lower left corner relative to the anchor, in font cells
+    pub offset: [f32; 2],
+    pub _padding: [f32; 2],
+}
+
+/// Label style as laid out in the shader
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct LabelParams {
+    viewport: [f32; 2],
+    pixel_height: f32,
+    _padding: f32,
+    color: [f32; 4],
+    background: [f32; 4],
+}
+
+/// Instance buffer, grown to fit the most glyphs uploaded so far
+struct GlyphBuffer {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    capacity: usize,
+    /// Scratch glyphs reused by every upload
+    scratch: Vec<LabelGlyph>,
+}
+
+/// Renders body labels as camera-facing glyph quads
+pub struct LabelRenderer {
+    /// Pipeline hiding labels behind nearer geometry
+    depth_tested_pipeline: wgpu::RenderPipeline,
+    /// Pipeline drawing labels on top of everything
+    overlay_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    camera_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+    _atlas_texture: wgpu::Texture,
+    atlas_view: wgpu::TextureView,
+    glyphs: Mutex<GlyphBuffer>,
+}
+
+impl LabelRenderer {
+    pub fn new(ctx: &GpuContext) -> Self {
+        let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let params_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Params Buffer"),
+            size: std::mem::size_of::<LabelParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let size = wgpu::Extent3d {
+            width: ATLAS_WIDTH,
+            height: ATLAS_HEIGHT,
+            depth_or_array_layers: 1,
+        };
+        let atlas_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Label Font Atlas"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        ctx.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &atlas_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &font_atlas(),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(ATLAS_WIDTH),
+                rows_per_image: Some(ATLAS_HEIGHT),
+            },
+            size,
+        );
+        let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let uniform_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Label Bind Group Layout"),
+            entries: &[
+                uniform_entry(0),
+                uniform_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Label Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Label Shader"),
+            source: wgpu::ShaderSource::Wgsl(super::shaders::LABEL_SHADER.source.into()),
+        });
+        let depth_tested_pipeline = Self::create_pipeline(ctx, &pipeline_layout, &shader, wgpu::CompareFunction::LessEqual);
+        let overlay_pipeline = Self::create_pipeline(ctx, &pipeline_layout, &shader, wgpu::CompareFunction::Always);
+
+        let glyphs = Self::create_glyphs(ctx, &bind_group_layout, &camera_buffer, &params_buffer, &atlas_view, INITIAL_CAPACITY);
+
+        Self {
+            depth_tested_pipeline,
+            overlay_pipeline,
+            bind_group_layout,
+            camera_buffer,
+            params_buffer,
+            _atlas_texture: atlas_texture,
+            atlas_view,
+            glyphs: Mutex::new(glyphs),
+        }
+    }
+
+    /// Allocate an instance buffer for `capacity` glyphs and its bind group
+    fn create_glyphs(
+        ctx: &GpuContext,
+        layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+        params_buffer: &wgpu::Buffer,
+        atlas_view: &wgpu::TextureView,
+        capacity: usize,
+    ) -> GlyphBuffer {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Label Glyph Buffer"),
+            size: (capacity * std::mem::size_of::<LabelGlyph>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Label Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(atlas_view),
+                },
+            ],
+        });
+
+        GlyphBuffer { buffer, bind_group, capacity, scratch: Vec::new() }
+    }
+
+    /// Build a glyph pipeline that tests depth with `depth_compare` and
+    /// never writes it
+    fn create_pipeline(
+        ctx: &GpuContext,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        depth_compare: wgpu::CompareFunction,
+    ) -> wgpu::RenderPipeline {
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Label Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: LDR_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Update camera uniform
+    pub fn update_camera(&self, ctx: &GpuContext, camera: &Camera) {
+        let uniform = camera.uniform();
+        ctx.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Upload the glyphs written by `fill` (into an emptied scratch list) and
+    /// the style of `display` for a `width` x `height` image, growing the
+    /// instance buffer if needed; returns the number of glyphs to draw
+    pub fn upload(
+        &self,
+        ctx: &GpuContext,
+        display: &LabelDisplay,
+        (width, height): (u32, u32),
+        fill: impl FnOnce(&mut Vec<LabelGlyph>),
+    ) -> u32 {
+        let params = LabelParams {
+            viewport: [width as f32, height as f32],
+            pixel_height: display.pixel_height.max(1.0),
+            _padding: 0.0,
+            color: [display.color[0], display.color[1], display.color[2], 1.0],
+            background: display.background,
+        };
+        ctx.queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+
+        let mut glyphs = self.glyphs.lock();
+        let mut scratch = std::mem::take(&mut glyphs.scratch);
+        scratch.clear();
+        fill(&mut scratch);
+        if scratch.len() > glyphs.capacity {
+            let capacity = scratch.len().next_power_of_two();
+            *glyphs = Self::create_glyphs(ctx, &self.bind_group_layout, &self.camera_buffer, &self.params_buffer, &self.atlas_view, capacity);
+        }
+        if !scratch.is_empty() {
+            ctx.queue.write_buffer(&glyphs.buffer, 0, bytemuck::cast_slice(&scratch));
+        }
+        let count = scratch.len() as u32;
+        glyphs.scratch = scratch;
+        count
+    }
+
+    /// Draw ranges of the uploaded glyphs onto the LDR target; each range is
+    /// hidden behind nearer geometry if its flag is set
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget, batches: &[(Range<u32>, bool)]) {
+        if batches.iter().all(|(range, _)| range.is_empty()) {
+            return;
+        }
+        let glyphs = self.glyphs.lock();
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Label Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.ldr_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        target.apply_scissor(&mut render_pass);
+
+        render_pass.set_bind_group(0, &glyphs.bind_group, &[]);
+        let capacity = glyphs.capacity as u32;
+        for (range, depth_test) in batches {
+            if range.is_empty() {
+                continue;
+            }
+            render_pass.set_pipeline(if *depth_test { &self.depth_tested_pipeline } else { &self.overlay_pipeline });
+            render_pass.draw(0..6, range.start.min(capacity)..range.end.min(capacity)); // One quad per glyph
+        }
+    }
+}
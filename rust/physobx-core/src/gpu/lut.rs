@@ -0,0 +1,165 @@
+//! 3D color lookup tables loaded from Adobe/Resolve `.cube` files
+
+use thiserror::Error;
+
+/// Errors from reading a `.cube` LUT
+#[derive(Error, Debug)]
+pub enum LutError {
+    #[error("Failed to read LUT file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid .cube LUT at line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("LUT has {found} entries, expected {expected} for size {size}")]
+    Size { size: u32, expected: usize, found: usize },
+}
+
+/// A 3D LUT mapping display-referred RGB in [0, 1] to graded RGB
+#[derive(Debug, Clone)]
+pub struct ColorLut {
+    /// Entries per axis
+    pub size: u32,
+    /// RGB entries with red varying fastest, then green, then blue
+    pub data: Vec<[f32; 3]>,
+}
+
+impl ColorLut {
+    /// Identity LUT of the given size
+    pub fn identity(size: u32) -> Self {
+        let size = size.max(2);
+        let last = (size - 1) as f32;
+        let mut data = Vec::with_capacity((size as usize).pow(3));
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    data.push([r as f32 / last, g as f32 / last, b as f32 / last]);
+                }
+            }
+        }
+        Self { size, data }
+    }
+
+    /// Load a `.cube` file
+    pub fn load(path: &str) -> Result<Self, LutError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse the contents of a `.cube` file
+    ///
+    /// Only 3D LUTs are supported; a non-default DOMAIN_MIN/DOMAIN_MAX is
+    /// resampled onto [0, 1].
+    pub fn parse(text: &str) -> Result<Self, LutError> {
+        let mut size = None;
+        let mut domain_min = [0.0f32; 3];
+        let mut domain_max = [1.0f32; 3];
+        let mut data = Vec::new();
+
+        for (i, raw) in text.lines().enumerate() {
+            let line = i + 1;
+            let err = |message: &str| LutError::Parse { line, message: message.to_string() };
+            let content = raw.split('#').next().unwrap_or("").trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            let mut fields = content.split_whitespace();
+            let keyword = fields.next().unwrap_or("");
+            let values: Vec<&str> = fields.collect();
+            match keyword {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => return Err(err("1D LUTs are not supported")),
+                "LUT_3D_SIZE" => {
+                    let n: u32 = values.first()
+                        .and_then(|v| v.parse().ok())
+                        .filter(|&n| n >= 2)
+                        .ok_or_else(|| err("LUT_3D_SIZE must be an integer >= 2"))?;
+                    size = Some(n);
+                }
+                "DOMAIN_MIN" | "DOMAIN_MAX" => {
+                    let v = parse_triplet(&values).ok_or_else(|| err("expected three numbers"))?;
+                    if keyword == "DOMAIN_MIN" { domain_min = v } else { domain_max = v }
+                }
+                _ if keyword.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') => {
+                    let mut row = vec![keyword];
+                    row.extend(values);
+                    data.push(parse_triplet(&row).ok_or_else(|| err("expected three numbers"))?);
+                }
+                // Unknown keywords (e.g. LUT_IN_VIDEO_RANGE) are ignored
+                _ => {}
+            }
+        }
+
+        let size = size.ok_or(LutError::Parse { line: 0, message: "missing LUT_3D_SIZE".into() })?;
+        let expected = (size as usize).pow(3);
+        if data.len() != expected {
+            return Err(LutError::Size { size, expected, found: data.len() });
+        }
+
+        let lut = Self { size, data };
+        if domain_min == [0.0; 3] && domain_max == [1.0; 3] {
+            return Ok(lut);
+        }
+
+        // Fold a non-default domain into the table so lookups always use [0, 1]
+        let last = (size - 1) as f32;
+        let mut folded = lut.data.clone();
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    let input = [r, g, b].map(|c| c as f32 / last);
+                    let mapped: [f32; 3] = std::array::from_fn(|k| {
+                        let span = (domain_max[k] - domain_min[k]).max(1e-6);
+                        (input[k] - domain_min[k]) / span
+                    });
+                    folded[lut.index(r, g, b)] = lut.sample(mapped);
+                }
+            }
+        }
+        Ok(Self { size, data: folded })
+    }
+
+    /// Index of an entry in `data`
+    fn index(&self, r: u32, g: u32, b: u32) -> usize {
+        ((b * self.size + g) * self.size + r) as usize
+    }
+
+    /// Trilinearly interpolated lookup of an RGB value in [0, 1]
+    pub fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let last = (self.size - 1) as f32;
+        let pos = rgb.map(|c| c.clamp(0.0, 1.0) * last);
+        let lo = pos.map(|p| (p.floor() as u32).min(self.size - 2));
+        let f: [f32; 3] = std::array::from_fn(|k| pos[k] - lo[k] as f32);
+
+        let mut out = [0.0f32; 3];
+        for corner in 0..8u32 {
+            let d = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let w: f32 = (0..3).map(|k| if d[k] == 1 { f[k] } else { 1.0 - f[k] }).product();
+            let entry = self.data[self.index(lo[0] + d[0], lo[1] + d[1], lo[2] + d[2])];
+            for k in 0..3 {
+                out[k] += w * entry[k];
+            }
+        }
+        out
+    }
+
+    /// Texels for an Rgba8Unorm 3D texture (red along X, green along Y, blue along Z)
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.data.iter()
+            .flat_map(|c| {
+                let [r, g, b] = c.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8);
+                [r, g, b, 255]
+            })
+            .collect()
+    }
+}
+
+/// Parse three whitespace-separated floats
+fn parse_triplet(values: &[&str]) -> Option<[f32; 3]> {
+    if values.len() != 3 {
+        return None;
+    }
+    let mut out = [0.0; 3];
+    for (o, v) in out.iter_mut().zip(values) {
+        *o = v.parse().ok()?;
+    }
+    Some(out)
+}
@@ -0,0 +1,169 @@
+//! Render configuration manifests
+//!
+//! A manifest records everything that decides how a frame looks: crate
+//! version, GPU adapter, camera, render settings, and hashes of the shader
+//! sources in use. Saved PNGs carry it as a text chunk and metadata sidecars
+//! include it, so any output image can be traced back to its exact
+//! configuration.
+
+use super::metadata::CameraMetadata;
+use super::shaders::source_hash;
+use std::fmt::Write;
+
+/// PNG text chunk keyword the manifest is stored under
+pub const MANIFEST_PNG_KEYWORD: &str = "physobx:manifest";
+
+/// GPU adapter frames were rendered on
+#[derive(Debug, Clone)]
+pub struct AdapterMetadata {
+    pub name: String,
+    /// Graphics API (Metal, Vulkan, ...)
+    pub backend: String,
+    /// Discrete, integrated, software, ...
+    pub device_type: String,
+    pub driver: String,
+    pub driver_info: String,
+}
+
+impl AdapterMetadata {
+    pub fn from_info(info: &wgpu::AdapterInfo) -> Self {
+        Self {
+            name: info.name.clone(),
+            backend: format!("{:?}", info.backend),
+            device_type: format!("{:?}", info.device_type),
+            driver: info.driver.clone(),
+            driver_info: info.driver_info.clone(),
+        }
+    }
+}
+
+/// Complete render configuration of a renderer
+#[derive(Debug, Clone)]
+pub struct RenderManifest {
+    pub crate_version: &'static str,
+    pub adapter: AdapterMetadata,
+    pub camera: CameraMetadata,
+    /// Numeric render settings (exposure, motion blur, lens, post effects)
+    pub settings: Vec<(&'static str, f32)>,
+    /// Other settings as text (color space, enabled overlays, ...)
+    pub options: Vec<(&'static str, String)>,
+    /// [`source_hash`] of each shader in use, by file name
+    pub shaders: Vec<(&'static str, u64)>,
+    /// Source hashes of the custom post effects in stack order (None =
+    /// effect without a single shader source)
+    pub post_effects: Vec<Option<u64>>,
+}
+
+impl RenderManifest {
+    /// The manifest as a JSON object (ASCII only; hashes as hex strings)
+    pub fn to_json(&self) -> String {
+        let a = &self.adapter;
+        format!(
+            "{{\"adapter\":{{\"name\":{},\"backend\":{},\"device_type\":{},\"driver\":{},\"driver_info\":{}}},{},\"fingerprint\":\"{:016x}\"}}",
+            json_string(&a.name), json_string(&a.backend), json_string(&a.device_type),
+            json_string(&a.driver), json_string(&a.driver_info),
+            self.configuration_json(),
+            self.fingerprint(),
+        )
+    }
+
+    /// Hash of everything but the adapter: equal for identically configured
+    /// renderers on any machine
+    pub fn fingerprint(&self) -> u64 {
+        source_hash(&self.configuration_json())
+    }
+
+    /// JSON members of everything but the adapter
+    fn configuration_json(&self) -> String {
+        let mut out = format!("\"crate_version\":{}", json_string(self.crate_version));
+
+        let c = &self.camera;
+        let _ = write!(
+            out,
+            ",\"camera\":{{\"eye\":{},\"target\":{},\"up\":{},\"fov_y\":{},\"ortho_height\":{},\"near\":{},\"far\":{},\"width\":{},\"height\":{}}}",
+            json_floats(&c.eye), json_floats(&c.target), json_floats(&c.up), json_float(c.fov_y),
+            c.ortho_height.map_or("null".to_string(), json_float), json_float(c.near), json_float(c.far),
+            c.width, c.height,
+        );
+
+        let settings: Vec<String> = self.settings.iter()
+            .map(|(name, value)| format!("{}:{}", json_string(name), json_float(*value)))
+            .collect();
+        let _ = write!(out, ",\"settings\":{{{}}}", settings.join(","));
+
+        let options: Vec<String> = self.options.iter()
+            .map(|(name, value)| format!("{}:{}", json_string(name), json_string(value)))
+            .collect();
+        let _ = write!(out, ",\"options\":{{{}}}", options.join(","));
+
+        let shaders: Vec<String> = self.shaders.iter()
+            .map(|(file, hash)| format!("{}:\"{:016x}\"", json_string(file), hash))
+            .collect();
+        let _ = write!(out, ",\"shaders\":{{{}}}", shaders.join(","));
+
+        let effects: Vec<String> = self.post_effects.iter()
+            .map(|hash| hash.map_or("null".to_string(), |h| format!("\"{:016x}\"", h)))
+            .collect();
+        let _ = write!(out, ",\"post_effects\":[{}]", effects.join(","));
+        out
+    }
+}
+
+/// JSON string literal with non-ASCII characters escaped
+pub(super) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            ' '..='~' => out.push(c),
+            _ => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    let _ = write!(out, "\\u{:04x}", unit);
+                }
+            }
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// JSON number, or null for NaN and infinities
+pub(super) fn json_float(v: f32) -> String {
+    if v.is_finite() { format!("{}", v) } else { "null".to_string() }
+}
+
+pub(super) fn json_floats(v: &[f32]) -> String {
+    format!("[{}]", v.iter().map(|&x| json_float(x)).collect::<Vec<_>>().join(","))
+}
+
+/// Insert a `tEXt` chunk right after the IHDR chunk of an encoded PNG
+///
+/// `keyword` and `text` must be Latin-1; the keyword is 1-79 characters.
+pub(crate) fn insert_png_text(png: &mut Vec<u8>, keyword: &str, text: &str) {
+    // 8-byte signature, then IHDR: length, type, 13 data bytes, CRC
+    const AFTER_IHDR: usize = 8 + 4 + 4 + 13 + 4;
+    let mut data = b"tEXt".to_vec();
+    data.extend_from_slice(keyword.as_bytes());
+    data.push(0);
+    data.extend_from_slice(text.as_bytes());
+
+    let mut chunk = ((data.len() - 4) as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(&data);
+    chunk.extend_from_slice(&crc32(&data).to_be_bytes());
+    png.splice(AFTER_IHDR..AFTER_IHDR, chunk);
+}
+
+/// CRC-32 (ISO 3309) as used by PNG chunks
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
@@ -0,0 +1,221 @@
+//! Dimension lines and angle markers with live values
+//!
+//! A measurement connects body centers or fixed world points. Distances are
+//! drawn as a line with a tick across each end and the length at its middle;
+//! angles as two arms from the pivot, an arc between them and the angle in
+//! degrees beside it. Points on bodies are re-read every frame, so the values
+//! follow the simulation. Lines are drawn by the overlay line renderer and
+//! values by the label renderer.
+
+use super::labels::{push_text, LabelGlyph};
+use super::overlay_lines::OverlaySegment;
+use crate::physics::RigidBodyStorage;
+
+/// Straight pieces of an angle arc
+const ARC_SEGMENTS: usize = 16;
+
+/// Arc radius as a fraction of the shorter arm
+const ARC_FRACTION: f32 = 0.3;
+
+/// One end of a measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeasurePoint {
+    /// Center of a body, by index
+    Body(usize),
+    /// Fixed world point
+    World([f32; 3]),
+}
+
+impl From<usize> for MeasurePoint {
+    fn from(index: usize) -> Self {
+        MeasurePoint::Body(index)
+    }
+}
+
+impl From<[f32; 3]> for MeasurePoint {
+    fn from(point: [f32; 3]) -> Self {
+        MeasurePoint::World(point)
+    }
+}
+
+impl MeasurePoint {
+    /// Current world position (None for a body index past the end of `storage`)
+    pub fn position(&self, storage: &RigidBodyStorage) -> Option<[f32; 3]> {
+        match *self {
+            MeasurePoint::Body(index) => storage.positions.get(index).copied(),
+            MeasurePoint::World(point) => Some(point),
+        }
+    }
+}
+
+/// A quantity drawn into frames
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Measurement {
+    /// Distance between two points
+    Distance { a: MeasurePoint, b: MeasurePoint },
+    /// Angle at `pivot` between the arms towards `a` and `b`
+    Angle { a: MeasurePoint, pivot: MeasurePoint, b: MeasurePoint },
+}
+
+impl Measurement {
+    /// Current value: meters for distances, degrees for angles (None if a
+    /// point is missing or an angle arm has zero length)
+    pub fn value(&self, storage: &RigidBodyStorage) -> Option<f32> {
+        match self {
+            Measurement::Distance { a, b } => {
+                let (a, b) = (a.position(storage)?, b.position(storage)?);
+                Some(length(sub(b, a)))
+            }
+            Measurement::Angle { a, pivot, b } => {
+                let p = pivot.position(storage)?;
+                let (u, v) = (sub(a.position(storage)?, p), sub(b.position(storage)?, p));
+                let (lu, lv) = (length(u), length(v));
+                if lu <= f32::EPSILON || lv <= f32::EPSILON {
+                    return None;
+                }
+                Some((dot(u, v) / (lu * lv)).clamp(-1.0, 1.0).acos().to_degrees())
+            }
+        }
+    }
+}
+
+/// How measurements are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeasurementDisplay {
+    /// Line color
+    pub color: [f32; 3],
+    /// Line width in pixels
+    pub line_width: f32,
+    /// Length in pixels of the ticks across the ends of distance lines; 0
+    /// draws none
+    pub tick: f32,
+    /// Decimal places of the values
+    pub decimals: usize,
+    /// Hide lines and values behind nearer geometry; false draws them on top
+    /// of everything
+    pub depth_test: bool,
+}
+
+impl Default for MeasurementDisplay {
+    fn default() -> Self {
+        Self {
+            color: [1.0, 0.85, 0.2],
+            line_width: 2.0,
+            tick: 10.0,
+            decimals: 2,
+            depth_test: false,
+        }
+    }
+}
+
+impl MeasurementDisplay {
+    /// Append the line segments of `measurements` to `out`; measurements with
+    /// missing points are skipped
+    pub fn segments_into(&self, storage: &RigidBodyStorage, measurements: &[Measurement], out: &mut Vec<OverlaySegment>) {
+        let segment = |start, end, tick| OverlaySegment {
+            start,
+            width: self.line_width.max(0.5),
+            end,
+            tick,
+            color: [self.color[0], self.color[1], self.color[2], 1.0],
+        };
+        for measurement in measurements {
+            match measurement {
+                Measurement::Distance { a, b } => {
+                    let (Some(a), Some(b)) = (a.position(storage), b.position(storage)) else {
+                        continue;
+                    };
+                    out.push(segment(a, b, self.tick.max(0.0)));
+                }
+                Measurement::Angle { a, pivot, b } => {
+                    let Some((p, a, b)) = angle_points(storage, a, pivot, b) else {
+                        continue;
+                    };
+                    out.push(segment(p, a, 0.0));
+                    out.push(segment(p, b, 0.0));
+                    let arc = arc_points(p, a, b);
+                    for pair in arc.windows(2) {
+                        out.push(segment(pair[0], pair[1], 0.0));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Append the glyphs of the values of `measurements` to `out`
+    pub fn glyphs_into(&self, storage: &RigidBodyStorage, measurements: &[Measurement], out: &mut Vec<LabelGlyph>) {
+        for measurement in measurements {
+            let Some(value) = measurement.value(storage) else {
+                continue;
+            };
+            match measurement {
+                Measurement::Distance { a, b } => {
+                    let (Some(a), Some(b)) = (a.position(storage), b.position(storage)) else {
+                        continue;
+                    };
+                    let middle = std::array::from_fn(|k| (a[k] + b[k]) * 0.5);
+                    push_text(out, middle, &format!("{:.*} m", self.decimals, value), -0.5);
+                }
+                Measurement::Angle { a, pivot, b } => {
+                    let Some((p, a, b)) = angle_points(storage, a, pivot, b) else {
+                        continue;
+                    };
+                    // Just outside the middle of the arc (at the pivot if
+                    // there is none)
+                    let anchor = match arc_points(p, a, b).get(ARC_SEGMENTS / 2) {
+                        Some(middle) => std::array::from_fn(|k| p[k] + (middle[k] - p[k]) * 1.6),
+                        None => p,
+                    };
+                    push_text(out, anchor, &format!("{:.*}°", self.decimals, value), -0.5);
+                }
+            }
+        }
+    }
+}
+
+/// Resolved pivot and arm ends of an angle, None if a point is missing
+fn angle_points(
+    storage: &RigidBodyStorage,
+    a: &MeasurePoint,
+    pivot: &MeasurePoint,
+    b: &MeasurePoint,
+) -> Option<([f32; 3], [f32; 3], [f32; 3])> {
+    Some((pivot.position(storage)?, a.position(storage)?, b.position(storage)?))
+}
+
+/// Points along the arc from arm `a` to arm `b` around `pivot`, at
+/// [`ARC_FRACTION`] of the shorter arm (empty for degenerate or straight angles)
+fn arc_points(pivot: [f32; 3], a: [f32; 3], b: [f32; 3]) -> Vec<[f32; 3]> {
+    let (u, v) = (sub(a, pivot), sub(b, pivot));
+    let (lu, lv) = (length(u), length(v));
+    if lu <= f32::EPSILON || lv <= f32::EPSILON {
+        return Vec::new();
+    }
+    let (u, v) = (u.map(|x| x / lu), v.map(|x| x / lv));
+    let angle = dot(u, v).clamp(-1.0, 1.0).acos();
+    let sin = angle.sin();
+    if sin < 1e-4 {
+        return Vec::new();
+    }
+    let radius = ARC_FRACTION * lu.min(lv);
+    (0..=ARC_SEGMENTS)
+        .map(|k| {
+            // Spherical interpolation between the unit arms
+            let t = k as f32 / ARC_SEGMENTS as f32;
+            let (wu, wv) = (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin);
+            std::array::from_fn(|i| pivot[i] + (u[i] * wu + v[i] * wv) * radius)
+        })
+        .collect()
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn length(a: [f32; 3]) -> f32 {
+    dot(a, a).sqrt()
+}
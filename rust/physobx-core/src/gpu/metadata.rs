@@ -0,0 +1,148 @@
+//! Per-frame metadata describing how a frame was rendered
+//!
+//! Dataset exporters write this next to each frame so downstream code can
+//! recover camera geometry, body poses and instance-ID colors without
+//! re-deriving them from the scene.
+
+use super::camera::Camera;
+use crate::physics::RigidBodyStorage;
+use nalgebra::Matrix4;
+
+/// Color of a body in the instance-ID pass: body index + 1 as 24-bit little-endian RGB
+///
+/// Black (0, 0, 0) is reserved for the background.
+pub fn instance_id_color(body: usize) -> [u8; 3] {
+    let id = body as u32 + 1;
+    [(id & 0xff) as u8, ((id >> 8) & 0xff) as u8, ((id >> 16) & 0xff) as u8]
+}
+
+/// Body index encoded by an instance-ID color (None for the background)
+pub fn body_from_id_color(rgb: [u8; 3]) -> Option<usize> {
+    let id = rgb[0] as u32 | (rgb[1] as u32) << 8 | (rgb[2] as u32) << 16;
+    (id > 0).then(|| id as usize - 1)
+}
+
+/// Camera geometry of a frame (matrices are row-major)
+#[derive(Debug, Clone)]
+pub struct CameraMetadata {
+    pub eye: [f32; 3],
+    pub target: [f32; 3],
+    pub up: [f32; 3],
+    /// Vertical field of view in radians
+    pub fov_y: f32,
+    /// Visible world height of an orthographic camera; None = perspective
+    pub ortho_height: Option<f32>,
+    pub near: f32,
+    pub far: f32,
+    pub width: u32,
+    pub height: u32,
+    /// World to camera transform (camera looks down -Z, Y up)
+    pub view: [[f32; 4]; 4],
+    /// Camera to clip space projection
+    pub projection: [[f32; 4]; 4],
+    /// Pinhole intrinsics K in pixels (OpenCV convention: +Y down the image);
+    /// for orthographic cameras K maps camera-space x, y without the divide by depth
+    pub intrinsics: [[f32; 3]; 3],
+}
+
+impl CameraMetadata {
+    /// Describe a camera rendering at the given resolution
+    pub fn new(camera: &Camera, width: u32, height: u32) -> Self {
+        let focal = match camera.ortho_height {
+            Some(ortho_height) => height as f32 / ortho_height,
+            None => height as f32 * 0.5 / (camera.fov_y * 0.5).tan(),
+        };
+        Self {
+            eye: camera.eye.into(),
+            target: camera.target.into(),
+            up: camera.up.into(),
+            fov_y: camera.fov_y,
+            ortho_height: camera.ortho_height,
+            near: camera.near,
+            far: camera.far,
+            width,
+            height,
+            view: row_major(camera.view_matrix()),
+            projection: row_major(camera.projection_matrix()),
+            intrinsics: [
+                [focal, 0.0, width as f32 * 0.5],
+                [0.0, focal, height as f32 * 0.5],
+                [0.0, 0.0, 1.0],
+            ],
+        }
+    }
+}
+
+/// Pose and labels of one body in a frame
+#[derive(Debug, Clone)]
+pub struct BodyMetadata {
+    /// Body index (matches `get_positions()` rows)
+    pub index: usize,
+    /// Shape type id (see [`ShapeType::id`](crate::scene::builder::ShapeType::id))
+    pub shape: u8,
+    pub position: [f32; 3],
+    /// Quaternion (x, y, z, w)
+    pub rotation: [f32; 4],
+    /// Half extent (cube) or radius (other shapes)
+    pub size: f32,
+    /// Color of the body in the instance-ID pass
+    pub id_color: [u8; 3],
+}
+
+/// Oriented 3D bounding box of one body in the camera frame, with its
+/// corners projected into the rendered image
+///
+/// The camera frame follows the OpenCV convention of
+/// [`CameraMetadata::intrinsics`]: +X right, +Y down, +Z along the view
+/// direction. Corner `k` lies at local signs (x, y, z) given by bits 0, 1
+/// and 2 of `k` (clear = negative, set = positive).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundingBox3d {
+    /// Body index (matches `get_positions()` rows)
+    pub index: usize,
+    /// Box center in the camera frame
+    pub center: [f32; 3],
+    /// Half extents along the body's local axes
+    pub half_extents: [f32; 3],
+    /// Body to camera frame rotation (x, y, z, w)
+    pub rotation: [f32; 4],
+    /// Corners in the camera frame
+    pub corners: [[f32; 3]; 8],
+    /// Corner pixels in the rendered image (origin top-left, +Y down,
+    /// including lens distortion); NaN for corners behind the camera
+    pub pixels: [[f32; 2]; 8],
+    /// Whether any corner lies inside the image and the depth range
+    pub in_frustum: bool,
+}
+
+/// Everything needed to interpret a rendered frame
+#[derive(Debug, Clone)]
+pub struct FrameMetadata {
+    /// Simulation time of the frame
+    pub time: f32,
+    pub camera: CameraMetadata,
+    pub bodies: Vec<BodyMetadata>,
+    /// Render settings in effect (exposure, post effects, ...) as name/value pairs
+    pub settings: Vec<(&'static str, f32)>,
+}
+
+impl FrameMetadata {
+    /// Collect body poses and labels from storage
+    pub fn bodies_from(storage: &RigidBodyStorage) -> Vec<BodyMetadata> {
+        (0..storage.len())
+            .map(|i| BodyMetadata {
+                index: i,
+                shape: storage.shape_types[i],
+                position: storage.positions[i],
+                rotation: storage.rotations[i],
+                size: storage.radii[i],
+                id_color: instance_id_color(i),
+            })
+            .collect()
+    }
+}
+
+/// nalgebra matrix as row-major nested arrays
+fn row_major(m: Matrix4<f32>) -> [[f32; 4]; 4] {
+    std::array::from_fn(|r| std::array::from_fn(|c| m[(r, c)]))
+}
@@ -5,19 +5,61 @@ pub mod render_target;
 pub mod camera;
 pub mod instance_renderer;
 pub mod sphere_renderer;
+pub mod axial_renderer;
+pub mod fluid_renderer;
 pub mod sky_renderer;
 pub mod ground_renderer;
 pub mod tonemap;
+pub mod lut;
+pub mod color_space;
 pub mod shadow;
+pub mod decals;
+pub mod springs;
+pub mod force_chains;
+pub mod labels;
+pub mod overlay_lines;
+pub mod measurements;
+pub mod guides;
+pub mod shaders;
+pub mod post_effect;
+pub mod rolling_shutter;
+pub mod split_screen;
+pub mod picture_in_picture;
+pub mod compositor;
+pub mod metadata;
+pub mod manifest;
+pub mod id_pass;
+pub mod pose_export;
 pub mod renderer;
 
 pub use context::{GpuContext, GpuError};
-pub use render_target::{OffscreenTarget, HDR_FORMAT, LDR_FORMAT};
-pub use camera::Camera;
+pub use render_target::{OffscreenTarget, HDR_FORMAT, LDR_FORMAT, VELOCITY_FORMAT};
+pub use camera::{Camera, ProjectedPoint};
 pub use instance_renderer::InstanceRenderer;
 pub use sphere_renderer::SphereRenderer;
+pub use axial_renderer::AxialRenderer;
+pub use fluid_renderer::{FluidRenderer, FluidDisplay};
 pub use sky_renderer::SkyRenderer;
 pub use ground_renderer::GroundRenderer;
-pub use tonemap::TonemapRenderer;
+pub use tonemap::{TonemapRenderer, LensModel, PostEffects};
+pub use lut::{ColorLut, LutError};
+pub use color_space::{OutputColorSpace, Primaries, TransferFunction};
 pub use shadow::{ShadowRenderer, SHADOW_MAP_SIZE};
-pub use renderer::Renderer;
+pub use decals::DecalMap;
+pub use springs::SpringDisplay;
+pub use force_chains::ForceChainDisplay;
+pub use labels::{LabelRenderer, LabelDisplay, LabelText};
+pub use overlay_lines::{LineRenderer, OverlaySegment};
+pub use measurements::{MeasurementDisplay, Measurement, MeasurePoint};
+pub use guides::{ReferenceGrid, AxisGizmo};
+pub use shaders::{ShaderError, ShaderWatcher};
+pub use post_effect::{PostEffect, PostEffectInputs, PostEffectOutput, ComputeEffect, POST_FORMAT};
+pub use rolling_shutter::RollingShutter;
+pub use split_screen::SplitScreen;
+pub use picture_in_picture::{PictureInPicture, Corner, InsetRenderer};
+pub use compositor::{Layer, LayerSource, BlendMode};
+pub use metadata::{FrameMetadata, CameraMetadata, BodyMetadata, BoundingBox3d, instance_id_color, body_from_id_color};
+pub use manifest::{RenderManifest, AdapterMetadata, MANIFEST_PNG_KEYWORD};
+pub use id_pass::{IdRenderer, ID_FORMAT};
+pub use pose_export::{BopExporter, BopModel, ObjectPose, ShapeSymmetry};
+pub use renderer::{Renderer, PendingFrame};
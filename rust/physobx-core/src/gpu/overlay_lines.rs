@@ -0,0 +1,289 @@
+//! Constant-width lines drawn over the finished frame
+//!
+//! Segments are given in world space and drawn with a fixed pixel width,
+//! optionally with a tick across each end. Like labels they go onto the
+//! tonemapped, post-processed frame, either depth tested against the scene
+//! or on top of everything. Measurements, reference grids and the axis
+//! gizmo are all drawn with them.
+
+use std::ops::Range;
+use super::camera::{Camera, CameraUniform};
+use super::context::GpuContext;
+use super::render_target::{OffscreenTarget, LDR_FORMAT};
+use bytemuck::{Pod, Zeroable};
+use parking_lot::Mutex;
+
+/// Segment slots allocated before the first upload
+const INITIAL_CAPACITY: usize = 256;
+
+/// A world-space line segment drawn with a constant pixel width
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct OverlaySegment {
+    pub start: [f32; 3],
+    /// Line width in pixels
+    pub width: f32,
+    pub end: [f32; 3],
+    /// Length in pixels of the ticks across both ends (0 = none)
+    pub tick: f32,
+    pub color: [f32; 4],
+}
+
+/// Image size as laid out in the shader
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct OverlayParams {
+    viewport: [f32; 2],
+    _padding: [f32; 2],
+}
+
+/// Instance buffer, grown to fit the most segments uploaded so far
+struct SegmentBuffer {
+    buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    capacity: usize,
+    /// Scratch segments reused by every upload
+    scratch: Vec<OverlaySegment>,
+}
+
+/// Renders overlay line segments over the finished frame
+pub struct LineRenderer {
+    /// Pipeline hiding lines behind nearer geometry
+    depth_tested_pipeline: wgpu::RenderPipeline,
+    /// Pipeline drawing lines on top of everything
+    overlay_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    camera_buffer: wgpu::Buffer,
+    params_buffer: wgpu::Buffer,
+    segments: Mutex<SegmentBuffer>,
+}
+
+impl LineRenderer {
+    pub fn new(ctx: &GpuContext) -> Self {
+        let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Overlay Line Camera Buffer"),
+            size: std::mem::size_of::<CameraUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let params_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Overlay Line Params Buffer"),
+            size: std::mem::size_of::<OverlayParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let uniform_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Overlay Line Bind Group Layout"),
+            entries: &[
+                uniform_entry(0),
+                uniform_entry(1),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Overlay Line Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Overlay Line Shader"),
+            source: wgpu::ShaderSource::Wgsl(super::shaders::OVERLAY_LINE_SHADER.source.into()),
+        });
+        let depth_tested_pipeline = Self::create_pipeline(ctx, &pipeline_layout, &shader, wgpu::CompareFunction::LessEqual);
+        let overlay_pipeline = Self::create_pipeline(ctx, &pipeline_layout, &shader, wgpu::CompareFunction::Always);
+
+        let segments = Self::create_segments(ctx, &bind_group_layout, &camera_buffer, &params_buffer, INITIAL_CAPACITY);
+
+        Self {
+            depth_tested_pipeline,
+            overlay_pipeline,
+            bind_group_layout,
+            camera_buffer,
+            params_buffer,
+            segments: Mutex::new(segments),
+        }
+    }
+
+    /// Allocate an instance buffer for `capacity` segments and its bind group
+    fn create_segments(
+        ctx: &GpuContext,
+        layout: &wgpu::BindGroupLayout,
+        camera_buffer: &wgpu::Buffer,
+        params_buffer: &wgpu::Buffer,
+        capacity: usize,
+    ) -> SegmentBuffer {
+        let buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Overlay Line Segment Buffer"),
+            size: (capacity * std::mem::size_of::<OverlaySegment>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Overlay Line Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        SegmentBuffer { buffer, bind_group, capacity, scratch: Vec::new() }
+    }
+
+    /// Build a line pipeline that tests depth with `depth_compare` and
+    /// never writes it
+    fn create_pipeline(
+        ctx: &GpuContext,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        depth_compare: wgpu::CompareFunction,
+    ) -> wgpu::RenderPipeline {
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Overlay Line Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: LDR_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Update camera uniform
+    pub fn update_camera(&self, ctx: &GpuContext, camera: &Camera) {
+        let uniform = camera.uniform();
+        ctx.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
+    }
+
+    /// Upload the segments written by `fill` (into an emptied scratch list)
+    /// for a `width` x `height` image, growing the instance buffer if needed;
+    /// returns the number of segments to draw
+    pub fn upload(
+        &self,
+        ctx: &GpuContext,
+        (width, height): (u32, u32),
+        fill: impl FnOnce(&mut Vec<OverlaySegment>),
+    ) -> u32 {
+        let params = OverlayParams {
+            viewport: [width as f32, height as f32],
+            _padding: [0.0; 2],
+        };
+        ctx.queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+
+        let mut segments = self.segments.lock();
+        let mut scratch = std::mem::take(&mut segments.scratch);
+        scratch.clear();
+        fill(&mut scratch);
+        if scratch.len() > segments.capacity {
+            let capacity = scratch.len().next_power_of_two();
+            *segments = Self::create_segments(ctx, &self.bind_group_layout, &self.camera_buffer, &self.params_buffer, capacity);
+        }
+        if !scratch.is_empty() {
+            ctx.queue.write_buffer(&segments.buffer, 0, bytemuck::cast_slice(&scratch));
+        }
+        let count = scratch.len() as u32;
+        segments.scratch = scratch;
+        count
+    }
+
+    /// Draw ranges of the uploaded segments onto the LDR target; each range
+    /// is hidden behind nearer geometry if its flag is set
+    pub fn render(&self, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget, batches: &[(Range<u32>, bool)]) {
+        if batches.iter().all(|(range, _)| range.is_empty()) {
+            return;
+        }
+        let segments = self.segments.lock();
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Overlay Line Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.ldr_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &target.depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        target.apply_scissor(&mut render_pass);
+
+        render_pass.set_bind_group(0, &segments.bind_group, &[]);
+        let capacity = segments.capacity as u32;
+        for (range, depth_test) in batches {
+            if range.is_empty() {
+                continue;
+            }
+            render_pass.set_pipeline(if *depth_test { &self.depth_tested_pipeline } else { &self.overlay_pipeline });
+            render_pass.draw(0..18, range.start.min(capacity)..range.end.min(capacity)); // Line plus two end ticks per segment
+        }
+    }
+}
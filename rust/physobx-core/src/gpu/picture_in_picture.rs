@@ -0,0 +1,274 @@
+//! Picture-in-picture: a second camera's view inset into a corner
+//!
+//! The inset is rendered as a full frame from its own camera, copied aside,
+//! and drawn downscaled with a border into a corner of the finished main
+//! frame, e.g. a top-down minimap next to the main view.
+
+use super::camera::Camera;
+use super::context::GpuContext;
+use super::render_target::{OffscreenTarget, LDR_FORMAT};
+use bytemuck::{Pod, Zeroable};
+
+/// Corner of the frame holding the inset
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    /// Parse a corner name such as "top_right" or "bottom-left"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.replace('-', "_").as_str() {
+            "top_left" => Some(Corner::TopLeft),
+            "top_right" => Some(Corner::TopRight),
+            "bottom_left" => Some(Corner::BottomLeft),
+            "bottom_right" => Some(Corner::BottomRight),
+            _ => None,
+        }
+    }
+}
+
+/// Secondary view drawn into a corner of every simulator frame
+#[derive(Debug, Clone)]
+pub struct PictureInPicture {
+    /// Camera of the inset (its aspect is matched to the frame when rendering)
+    pub camera: Camera,
+    /// Corner holding the inset
+    pub corner: Corner,
+    /// Inset width as a fraction of the frame width; the inset keeps the
+    /// frame's aspect ratio
+    pub size: f32,
+    /// Distance in pixels from the frame edges
+    pub margin: u32,
+    /// Border width in pixels (0 = none)
+    pub border_width: u32,
+    /// Border color
+    pub border_color: [f32; 3],
+}
+
+impl PictureInPicture {
+    /// Inset showing `camera` in the top right corner at a quarter of the
+    /// frame width
+    pub fn new(camera: Camera) -> Self {
+        Self {
+            camera,
+            corner: Corner::TopRight,
+            size: 0.25,
+            margin: 16,
+            border_width: 2,
+            border_color: [1.0, 1.0, 1.0],
+        }
+    }
+
+    /// Pixel rectangle (x, y, width, height) of the inset including its
+    /// border in a `width` x `height` frame
+    pub fn rect(&self, width: u32, height: u32) -> [u32; 4] {
+        let size = self.size.clamp(0.0, 1.0);
+        let inner = [(width as f32 * size).round() as u32, (height as f32 * size).round() as u32];
+        let outer = [(inner[0] + 2 * self.border_width).min(width), (inner[1] + 2 * self.border_width).min(height)];
+        let far = |extent: u32, side: u32| extent.saturating_sub(side + self.margin);
+        let near = |extent: u32, side: u32| self.margin.min(extent.saturating_sub(side));
+        let (x, y) = match self.corner {
+            Corner::TopLeft => (near(width, outer[0]), near(height, outer[1])),
+            Corner::TopRight => (far(width, outer[0]), near(height, outer[1])),
+            Corner::BottomLeft => (near(width, outer[0]), far(height, outer[1])),
+            Corner::BottomRight => (far(width, outer[0]), far(height, outer[1])),
+        };
+        [x, y, outer[0], outer[1]]
+    }
+}
+
+/// Inset layout as laid out in the shader
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+struct InsetParams {
+    /// Border width as a fraction of the inset rectangle, per axis
+    border: [f32; 2],
+    /// Source texels per inset pixel, per axis
+    step: [f32; 2],
+    border_color: [f32; 4],
+}
+
+/// Keeps the inset frame and draws it onto the main frame
+pub struct InsetRenderer {
+    pipeline: wgpu::RenderPipeline,
+    params_buffer: wgpu::Buffer,
+    inset_texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+}
+
+impl InsetRenderer {
+    /// Create an inset renderer for frames of `target`'s size
+    pub fn new(ctx: &GpuContext, target: &OffscreenTarget) -> Self {
+        let inset_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Inset Texture"),
+            size: wgpu::Extent3d {
+                width: target.width,
+                height: target.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: LDR_FORMAT,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let inset_view = inset_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = ctx.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Inset Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            ..Default::default()
+        });
+        let params_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Inset Params Buffer"),
+            size: std::mem::size_of::<InsetParams>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Inset Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Inset Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: params_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&inset_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Inset Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Inset Shader"),
+            source: wgpu::ShaderSource::Wgsl(super::shaders::INSET_SHADER.source.into()),
+        });
+        let pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Inset Render Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: LDR_FORMAT,
+                    blend: None,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        Self { pipeline, params_buffer, inset_texture, bind_group }
+    }
+
+    /// Keep the finished frame in `target` as the inset
+    pub fn capture(&self, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget) {
+        encoder.copy_texture_to_texture(
+            target.ldr_texture.as_image_copy(),
+            self.inset_texture.as_image_copy(),
+            wgpu::Extent3d {
+                width: target.width,
+                height: target.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Draw the captured inset with its border into the main frame in `target`
+    pub fn render(&self, ctx: &GpuContext, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget, inset: &PictureInPicture) {
+        let [x, y, width, height] = inset.rect(target.width, target.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let border = inset.border_width as f32;
+        let [r, g, b] = inset.border_color;
+        let params = InsetParams {
+            border: [border / width as f32, border / height as f32],
+            step: [
+                target.width as f32 / (width as f32 - 2.0 * border).max(1.0),
+                target.height as f32 / (height as f32 - 2.0 * border).max(1.0),
+            ],
+            border_color: [r, g, b, 1.0],
+        };
+        ctx.queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Inset Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.ldr_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_viewport(x as f32, y as f32, width as f32, height as f32, 0.0, 1.0);
+        render_pass.draw(0..3, 0..1); // Fullscreen triangle clipped to the viewport
+    }
+}
@@ -0,0 +1,282 @@
+//! 6-DoF pose labels in the BOP benchmark layout
+//!
+//! [`BopExporter`] collects, frame by frame, the camera and the pose of
+//! every dynamic body in the camera frame, and writes them as the JSON files
+//! of a BOP dataset (https://bop.felk.cvut.cz):
+//!
+//! ```text
+//! <dir>/models/models_info.json         size, diameter and symmetries per model
+//! <dir>/<scene_id>/scene_camera.json    intrinsics and world-to-camera pose per frame
+//! <dir>/<scene_id>/scene_gt.json        model-to-camera pose of each body per frame
+//! ```
+//!
+//! Frames themselves are saved by the caller, as
+//! `<dir>/<scene_id>/rgb/<frame_id>.png` (see [`BopExporter::image_path`]).
+//! Bodies of the same shape and size share one model (`obj_id`); model
+//! meshes are not written. Lengths are in millimeters and rotations are
+//! row-major 3x3 matrices, with the camera frame following the OpenCV
+//! convention (+X right, +Y down, +Z forward), as BOP expects.
+
+use super::manifest::{json_float, json_floats, json_string};
+use super::renderer::Renderer;
+use crate::scene::builder::ShapeType;
+use crate::simulator::Simulator;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Rotations that leave a shape looking the same, in the form BOP's
+/// `models_info.json` expects
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShapeSymmetry {
+    /// Axes (through the model origin) of continuous rotational symmetry
+    pub continuous_axes: Vec<[f32; 3]>,
+    /// Discrete symmetry rotations (row-major), identity excluded
+    pub discrete: Vec<[[f32; 3]; 3]>,
+}
+
+impl ShapeSymmetry {
+    /// Symmetries of a shape whose local bounding box has `half_extents`
+    /// (see [`crate::scene::builder::shape_half_extents`])
+    ///
+    /// Spheres are fully symmetric; cubes have 24 symmetric rotations, and
+    /// boxes with unequal sides the subset that keeps their extents. Axial
+    /// shapes are symmetric about their Y axis, capsules and cylinders also
+    /// under flipping end over end.
+    pub fn of(shape: ShapeType, half_extents: [f32; 3]) -> Self {
+        let flip = [[1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, -1.0]];
+        match shape {
+            ShapeType::Sphere => Self {
+                continuous_axes: vec![[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+                discrete: Vec::new(),
+            },
+            ShapeType::Cube => Self { continuous_axes: Vec::new(), discrete: box_symmetries(half_extents) },
+            ShapeType::Capsule | ShapeType::Cylinder => Self { continuous_axes: vec![[0.0, 1.0, 0.0]], discrete: vec![flip] },
+            ShapeType::Cone => Self { continuous_axes: vec![[0.0, 1.0, 0.0]], discrete: Vec::new() },
+        }
+    }
+
+    /// Short description: "full", "axial", "axial+2-fold", "<n>-fold"
+    /// (identity included in n) or "none"
+    pub fn label(&self) -> String {
+        let order = self.discrete.len() + 1;
+        match (self.continuous_axes.len(), order) {
+            (0, 1) => "none".to_string(),
+            (0, n) => format!("{}-fold", n),
+            (1, 1) => "axial".to_string(),
+            (1, n) => format!("axial+{}-fold", n),
+            _ => "full".to_string(),
+        }
+    }
+}
+
+/// Proper rotations permuting the axes of a box (with signs) that map its
+/// extents onto themselves, identity excluded
+fn box_symmetries(half_extents: [f32; 3]) -> Vec<[[f32; 3]; 3]> {
+    const PERMUTATIONS: [[usize; 3]; 6] = [[0, 1, 2], [1, 2, 0], [2, 0, 1], [0, 2, 1], [2, 1, 0], [1, 0, 2]];
+    let tolerance = 1e-4 * half_extents.iter().fold(0.0f32, |a, &b| a.max(b.abs()));
+    let mut rotations = Vec::new();
+    for (p, permutation) in PERMUTATIONS.iter().enumerate() {
+        if (0..3).any(|i| (half_extents[permutation[i]] - half_extents[i]).abs() > tolerance) {
+            continue;
+        }
+        // Odd permutations need an odd number of sign flips to stay proper
+        let odd = p >= 3;
+        for signs in 0..8u32 {
+            if (signs.count_ones() % 2 == 1) != odd || (p == 0 && signs == 0) {
+                continue;
+            }
+            let mut m = [[0.0; 3]; 3];
+            for (i, row) in m.iter_mut().enumerate() {
+                row[permutation[i]] = if signs >> i & 1 == 1 { -1.0 } else { 1.0 };
+            }
+            rotations.push(m);
+        }
+    }
+    rotations
+}
+
+/// Pose of one body in the camera frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectPose {
+    /// Body index (matches `get_positions()` rows)
+    pub index: usize,
+    pub shape: ShapeType,
+    /// Half extents of the body's local bounding box (scene units)
+    pub half_extents: [f32; 3],
+    /// Body to camera rotation (row-major, OpenCV camera frame)
+    pub rotation: [[f32; 3]; 3],
+    /// Body center in the camera frame (scene units)
+    pub translation: [f32; 3],
+}
+
+/// A model of BOP's `models_info.json`: one shape and size
+#[derive(Debug, Clone, PartialEq)]
+pub struct BopModel {
+    pub shape: ShapeType,
+    /// Half extents of the local bounding box (mm)
+    pub half_extents: [f32; 3],
+    pub symmetry: ShapeSymmetry,
+}
+
+impl BopModel {
+    /// Largest distance between two points of the model (mm)
+    pub fn diameter(&self) -> f32 {
+        let [x, y, z] = self.half_extents;
+        match self.shape {
+            ShapeType::Cube => 2.0 * (x * x + y * y + z * z).sqrt(),
+            ShapeType::Sphere => 2.0 * x,
+            ShapeType::Capsule => 2.0 * y,
+            ShapeType::Cylinder => 2.0 * (x * x + y * y).sqrt(),
+            // Across the base, or from the apex to the rim
+            ShapeType::Cone => (2.0 * x).max((x * x + 4.0 * y * y).sqrt()),
+        }
+    }
+}
+
+/// One exported frame
+#[derive(Debug, Clone)]
+struct BopFrame {
+    /// Pinhole intrinsics K (row-major)
+    intrinsics: [[f32; 3]; 3],
+    /// World to camera rotation (row-major) and translation (mm)
+    world_to_camera: ([[f32; 3]; 3], [f32; 3]),
+    /// Model id and pose (translation in mm) of each body
+    poses: Vec<(u32, ObjectPose)>,
+}
+
+/// Collects per-frame 6-DoF pose labels and writes them in the BOP layout
+/// (see the [module docs](self))
+#[derive(Debug, Clone, Default)]
+pub struct BopExporter {
+    /// Scene folder the frames belong to (`<dir>/000000` for 0)
+    pub scene_id: u32,
+    models: Vec<BopModel>,
+    frames: Vec<BopFrame>,
+}
+
+impl BopExporter {
+    pub fn new(scene_id: u32) -> Self {
+        Self { scene_id, ..Default::default() }
+    }
+
+    /// Record the camera of `renderer` and the poses of all dynamic bodies
+    /// of `sim`, and return the frame id
+    ///
+    /// Frame ids count up from 0. Static bodies are treated as background.
+    /// The intrinsics describe the ideal pinhole camera (lens distortion is
+    /// not modeled, and orthographic cameras have no BOP equivalent).
+    pub fn add_frame(&mut self, renderer: &Renderer, sim: &Simulator) -> u32 {
+        let mm = 1000.0 * sim.scene().physics.length_scale;
+        let (rotation, translation) = renderer.world_to_camera();
+        let mut poses = Vec::new();
+        for mut pose in renderer.object_poses(sim) {
+            if sim.storage.is_static(pose.index) {
+                continue;
+            }
+            let half_extents = pose.half_extents.map(|h| h * mm);
+            let obj_id = self.model_id(pose.shape, half_extents);
+            pose.translation = pose.translation.map(|t| t * mm);
+            poses.push((obj_id, pose));
+        }
+        self.frames.push(BopFrame {
+            intrinsics: renderer.frame_metadata(sim).camera.intrinsics,
+            world_to_camera: (rotation, translation.map(|t| t * mm)),
+            poses,
+        });
+        self.frames.len() as u32 - 1
+    }
+
+    /// Id (from 1) of the model with this shape and size, added if new
+    fn model_id(&mut self, shape: ShapeType, half_extents: [f32; 3]) -> u32 {
+        let tolerance = 1e-3;
+        let found = self.models.iter().position(|model| {
+            model.shape == shape && (0..3).all(|k| (model.half_extents[k] - half_extents[k]).abs() <= tolerance)
+        });
+        let index = found.unwrap_or_else(|| {
+            self.models.push(BopModel { shape, half_extents, symmetry: ShapeSymmetry::of(shape, half_extents) });
+            self.models.len() - 1
+        });
+        index as u32 + 1
+    }
+
+    /// Models seen so far; `obj_id` is the position + 1
+    pub fn models(&self) -> &[BopModel] {
+        &self.models
+    }
+
+    /// Number of recorded frames
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Where to save the image of a frame below the dataset directory
+    pub fn image_path(&self, dir: impl AsRef<Path>, frame_id: u32) -> PathBuf {
+        dir.as_ref().join(format!("{:06}", self.scene_id)).join("rgb").join(format!("{:06}.png", frame_id))
+    }
+
+    /// Contents of `models_info.json`
+    pub fn models_info_json(&self) -> String {
+        let entries: Vec<String> = self.models.iter().enumerate().map(|(i, model)| {
+            let h = model.half_extents;
+            let mut out = format!(
+                "\"{}\":{{\"diameter\":{},\"min_x\":{},\"min_y\":{},\"min_z\":{},\"size_x\":{},\"size_y\":{},\"size_z\":{}",
+                i + 1, json_float(model.diameter()),
+                json_float(-h[0]), json_float(-h[1]), json_float(-h[2]),
+                json_float(2.0 * h[0]), json_float(2.0 * h[1]), json_float(2.0 * h[2]),
+            );
+            if !model.symmetry.discrete.is_empty() {
+                let discrete: Vec<String> = model.symmetry.discrete.iter().map(|m| {
+                    // 4x4 row-major with zero translation
+                    let [a, b, c] = *m;
+                    json_floats(&[a[0], a[1], a[2], 0.0, b[0], b[1], b[2], 0.0, c[0], c[1], c[2], 0.0, 0.0, 0.0, 0.0, 1.0])
+                }).collect();
+                let _ = write!(out, ",\"symmetries_discrete\":[{}]", discrete.join(","));
+            }
+            if !model.symmetry.continuous_axes.is_empty() {
+                let continuous: Vec<String> = model.symmetry.continuous_axes.iter()
+                    .map(|axis| format!("{{\"axis\":{},\"offset\":[0,0,0]}}", json_floats(axis)))
+                    .collect();
+                let _ = write!(out, ",\"symmetries_continuous\":[{}]", continuous.join(","));
+            }
+            let _ = write!(out, ",\"shape\":{},\"symmetry\":{}}}", json_string(model.shape.name()), json_string(&model.symmetry.label()));
+            out
+        }).collect();
+        format!("{{{}}}", entries.join(","))
+    }
+
+    /// Contents of `scene_camera.json`
+    pub fn scene_camera_json(&self) -> String {
+        let entries: Vec<String> = self.frames.iter().enumerate().map(|(id, frame)| {
+            let (rotation, translation) = &frame.world_to_camera;
+            format!(
+                "\"{}\":{{\"cam_K\":{},\"cam_R_w2c\":{},\"cam_t_w2c\":{}}}",
+                id, json_floats(&frame.intrinsics.concat()), json_floats(&rotation.concat()), json_floats(translation),
+            )
+        }).collect();
+        format!("{{{}}}", entries.join(","))
+    }
+
+    /// Contents of `scene_gt.json`; each entry also names its body index
+    pub fn scene_gt_json(&self) -> String {
+        let entries: Vec<String> = self.frames.iter().enumerate().map(|(id, frame)| {
+            let poses: Vec<String> = frame.poses.iter().map(|(obj_id, pose)| format!(
+                "{{\"obj_id\":{},\"cam_R_m2c\":{},\"cam_t_m2c\":{},\"body_index\":{}}}",
+                obj_id, json_floats(&pose.rotation.concat()), json_floats(&pose.translation), pose.index,
+            )).collect();
+            format!("\"{}\":[{}]", id, poses.join(","))
+        }).collect();
+        format!("{{{}}}", entries.join(","))
+    }
+
+    /// Write the JSON files below `dir`, creating folders as needed
+    pub fn write(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+        let models = dir.join("models");
+        let scene = dir.join(format!("{:06}", self.scene_id));
+        std::fs::create_dir_all(&models)?;
+        std::fs::create_dir_all(scene.join("rgb"))?;
+        std::fs::write(models.join("models_info.json"), self.models_info_json())?;
+        std::fs::write(scene.join("scene_camera.json"), self.scene_camera_json())?;
+        std::fs::write(scene.join("scene_gt.json"), self.scene_gt_json())
+    }
+}
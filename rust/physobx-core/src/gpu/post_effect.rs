@@ -0,0 +1,243 @@
+//! Custom post-processing passes run between tonemapping and readback
+//!
+//! Effects form an ordered stack on the [`Renderer`](super::Renderer). Each
+//! one reads the previous result and writes a new image of the same size;
+//! the last output is what gets read back. The images are display-referred
+//! (sRGB-encoded) [`POST_FORMAT`] textures that can be both sampled and
+//! written as storage textures, so most effects are a single compute
+//! dispatch. [`ComputeEffect`] wraps a WGSL compute shader for that case.
+
+use super::context::GpuContext;
+use super::render_target::OffscreenTarget;
+use super::shaders::{build_checked, source_hash, ShaderError, OUTLINE_SHADER};
+
+/// Format of the images passed between effects
+pub const POST_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Compute workgroup size (both axes) expected by [`ComputeEffect`] shaders
+pub const WORKGROUP_SIZE: u32 = 8;
+
+/// Images an effect can read
+pub struct PostEffectInputs<'a> {
+    /// Result of the previous effect (the tonemapped frame for the first)
+    pub color: &'a wgpu::TextureView,
+    /// Linear HDR scene color before tonemapping
+    pub hdr: &'a wgpu::TextureView,
+    /// Scene depth (`Depth32Float`)
+    pub depth: &'a wgpu::TextureView,
+    /// Screen-space velocity (UV offset since the previous frame)
+    pub velocity: &'a wgpu::TextureView,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Image an effect writes
+///
+/// Holds stale data from earlier frames, so an effect must write every pixel.
+pub struct PostEffectOutput<'a> {
+    pub texture: &'a wgpu::Texture,
+    pub view: &'a wgpu::TextureView,
+}
+
+/// A custom pass in the renderer's post-effect stack
+pub trait PostEffect: Send + Sync {
+    /// Record commands reading `inputs` and writing `output`
+    fn record(
+        &self,
+        ctx: &GpuContext,
+        encoder: &mut wgpu::CommandEncoder,
+        inputs: &PostEffectInputs,
+        output: &PostEffectOutput,
+    );
+
+    /// Hash of the effect's shader source, recorded in render manifests
+    /// (None if the effect has no single source)
+    fn source_hash(&self) -> Option<u64> {
+        None
+    }
+}
+
+/// A post effect running one WGSL compute shader over the image
+///
+/// The shader's entry point runs once per pixel in
+/// `@workgroup_size(8, 8)` groups covering the image (so it must skip ids
+/// past the edges) and may use these group 0 bindings:
+///
+/// - `@binding(0) var input: texture_2d<f32>;` result of the previous effect
+/// - `@binding(1) var output: texture_storage_2d<rgba8unorm, write>;`
+/// - `@binding(2) var depth: texture_depth_2d;`
+/// - `@binding(3) var hdr: texture_2d<f32>;`
+/// - `@binding(4) var velocity: texture_2d<f32>;`
+pub struct ComputeEffect {
+    pipeline: wgpu::ComputePipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    /// [`source_hash`] of the WGSL source
+    source_hash: u64,
+}
+
+impl ComputeEffect {
+    /// Compile a compute shader using the bindings above
+    pub fn new(ctx: &GpuContext, source: &str, entry_point: &str) -> Result<Self, ShaderError> {
+        let texture = |binding, sample_type| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let float = wgpu::TextureSampleType::Float { filterable: true };
+        let bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Compute Effect Bind Group Layout"),
+            entries: &[
+                texture(0, float),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: POST_FORMAT,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                texture(2, wgpu::TextureSampleType::Depth),
+                texture(3, float),
+                texture(4, float),
+            ],
+        });
+
+        let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Compute Effect Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = build_checked(ctx, "compute effect", || {
+            let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Compute Effect Shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            ctx.device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some("Compute Effect Pipeline"),
+                layout: Some(&pipeline_layout),
+                module: &shader,
+                entry_point: Some(entry_point),
+                compilation_options: Default::default(),
+                cache: None,
+            })
+        })?;
+
+        Ok(Self { pipeline, bind_group_layout, source_hash: source_hash(source) })
+    }
+
+    /// Dark ink outlines along silhouettes and sharp color changes
+    pub fn outline(ctx: &GpuContext) -> Self {
+        Self::new(ctx, OUTLINE_SHADER.source, "main").expect("built-in outline shader is valid")
+    }
+}
+
+impl PostEffect for ComputeEffect {
+    fn record(
+        &self,
+        ctx: &GpuContext,
+        encoder: &mut wgpu::CommandEncoder,
+        inputs: &PostEffectInputs,
+        output: &PostEffectOutput,
+    ) {
+        let views = [inputs.color, output.view, inputs.depth, inputs.hdr, inputs.velocity];
+        let entries: Vec<_> = views.iter().enumerate()
+            .map(|(binding, view)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: wgpu::BindingResource::TextureView(view),
+            })
+            .collect();
+        let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Compute Effect Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &entries,
+        });
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Compute Effect Pass"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(inputs.width.div_ceil(WORKGROUP_SIZE), inputs.height.div_ceil(WORKGROUP_SIZE), 1);
+    }
+
+    fn source_hash(&self) -> Option<u64> {
+        Some(self.source_hash)
+    }
+}
+
+/// Two images the effect stack alternates between
+pub(crate) struct PostTargets {
+    textures: [wgpu::Texture; 2],
+    views: [wgpu::TextureView; 2],
+}
+
+impl PostTargets {
+    pub(crate) fn new(ctx: &GpuContext, width: u32, height: u32) -> Self {
+        let textures = [0, 1].map(|_| ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Post Effect Target"),
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: POST_FORMAT,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                 | wgpu::TextureUsages::STORAGE_BINDING
+                 | wgpu::TextureUsages::COPY_SRC
+                 | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        }));
+        let views = [0, 1].map(|k| textures[k].create_view(&wgpu::TextureViewDescriptor::default()));
+        Self { textures, views }
+    }
+
+    /// Run `effects` in order on the target's tonemapped image, leaving the
+    /// result in its LDR texture for readback
+    pub(crate) fn apply(
+        &self,
+        ctx: &GpuContext,
+        encoder: &mut wgpu::CommandEncoder,
+        target: &OffscreenTarget,
+        effects: &[Box<dyn PostEffect>],
+    ) {
+        if effects.is_empty() {
+            return;
+        }
+        let size = wgpu::Extent3d { width: target.width, height: target.height, depth_or_array_layers: 1 };
+
+        // The LDR texture is sRGB; copying keeps the encoded bytes as they are
+        encoder.copy_texture_to_texture(
+            target.ldr_texture.as_image_copy(),
+            self.textures[0].as_image_copy(),
+            size,
+        );
+        for (k, effect) in effects.iter().enumerate() {
+            let inputs = PostEffectInputs {
+                color: &self.views[k % 2],
+                hdr: &target.hdr_view,
+                depth: &target.depth_view,
+                velocity: &target.velocity_view,
+                width: target.width,
+                height: target.height,
+            };
+            let output = PostEffectOutput {
+                texture: &self.textures[(k + 1) % 2],
+                view: &self.views[(k + 1) % 2],
+            };
+            effect.record(ctx, encoder, &inputs, &output);
+        }
+        encoder.copy_texture_to_texture(
+            self.textures[effects.len() % 2].as_image_copy(),
+            target.ldr_texture.as_image_copy(),
+            size,
+        );
+    }
+}
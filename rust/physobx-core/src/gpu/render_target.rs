@@ -1,11 +1,14 @@
 //! Offscreen render target for headless rendering with HDR support
 
 use super::context::GpuContext;
+use parking_lot::Mutex;
 
 /// HDR render target format
 pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
 /// LDR output format (for file output)
 pub const LDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+/// Screen-space velocity format (UV offset since the previous frame)
+pub const VELOCITY_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg16Float;
 
 /// Offscreen render target with HDR rendering and LDR output
 pub struct OffscreenTarget {
@@ -13,6 +16,10 @@ pub struct OffscreenTarget {
     pub hdr_texture: wgpu::Texture,
     /// HDR texture view for rendering
     pub hdr_view: wgpu::TextureView,
+    /// Per-pixel screen-space velocity (for motion blur)
+    pub velocity_texture: wgpu::Texture,
+    /// Velocity texture view
+    pub velocity_view: wgpu::TextureView,
     /// LDR output texture (tonemapped result)
     pub ldr_texture: wgpu::Texture,
     /// LDR texture view
@@ -23,12 +30,18 @@ pub struct OffscreenTarget {
     pub depth_view: wgpu::TextureView,
     /// Staging buffer for CPU readback
     pub output_buffer: wgpu::Buffer,
+    /// Second readback staging buffer, so one frame can be read back while the
+    /// next renders
+    pub pipeline_buffer: wgpu::Buffer,
     /// Width in pixels
     pub width: u32,
     /// Height in pixels
     pub height: u32,
     /// Padded bytes per row (aligned to 256)
     pub padded_bytes_per_row: u32,
+    /// Pixel rectangle (x, y, width, height) the scene passes are limited
+    /// to; None = whole target
+    scissor: Mutex<Option<[u32; 4]>>,
 }
 
 impl OffscreenTarget {
@@ -68,6 +81,25 @@ impl OffscreenTarget {
 
         let hdr_view = hdr_texture.create_view(&wgpu::TextureViewDescriptor::default());
 
+        // Create velocity texture (written by the object passes)
+        let velocity_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Velocity Target"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: VELOCITY_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                 | wgpu::TextureUsages::TEXTURE_BINDING,  // For motion blur sampling
+            view_formats: &[],
+        });
+
+        let velocity_view = velocity_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
         // Create LDR output texture (tonemapped result, for file output)
         let ldr_texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
             label: Some("LDR Output Target"),
@@ -80,7 +112,9 @@ impl OffscreenTarget {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: LDR_FORMAT,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                 | wgpu::TextureUsages::COPY_SRC
+                 | wgpu::TextureUsages::COPY_DST,  // Written back by post effects
             view_formats: &[],
         });
 
@@ -98,7 +132,8 @@ impl OffscreenTarget {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                 | wgpu::TextureUsages::TEXTURE_BINDING,  // Read by post effects
             view_formats: &[],
         });
 
@@ -112,41 +147,97 @@ impl OffscreenTarget {
             usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
             mapped_at_creation: false,
         });
+        let pipeline_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pipeline Output Buffer"),
+            size: buffer_size,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
 
         Self {
             hdr_texture,
             hdr_view,
+            velocity_texture,
+            velocity_view,
             ldr_texture,
             ldr_view,
             depth_texture,
             depth_view,
             output_buffer,
+            pipeline_buffer,
             width,
             height,
             padded_bytes_per_row,
+            scissor: Mutex::new(None),
+        }
+    }
+
+    /// Limit the scene passes to a pixel rectangle (x, y, width, height),
+    /// clamped to the target, or lift the limit with None
+    ///
+    /// Pixels outside the rectangle keep whatever the target held before.
+    pub fn set_scissor(&self, rect: Option<[u32; 4]>) {
+        *self.scissor.lock() = rect.map(|rect| self.clamp_rect(rect));
+    }
+
+    /// Current scissor rectangle, if any
+    pub fn scissor(&self) -> Option<[u32; 4]> {
+        *self.scissor.lock()
+    }
+
+    /// Apply the scissor rectangle, if any, to a render pass on this target
+    pub fn apply_scissor(&self, render_pass: &mut wgpu::RenderPass<'_>) {
+        if let Some([x, y, width, height]) = *self.scissor.lock() {
+            render_pass.set_scissor_rect(x, y, width, height);
+        }
+    }
+
+    /// Pixel rectangle clipped to the target (possibly empty)
+    pub fn clamp_rect(&self, [x, y, width, height]: [u32; 4]) -> [u32; 4] {
+        let (x, y) = (x.min(self.width), y.min(self.height));
+        [x, y, width.min(self.width - x), height.min(self.height - y)]
+    }
+
+    /// Get the staging buffer for a readback slot (0 or 1)
+    pub fn slot_buffer(&self, slot: usize) -> &wgpu::Buffer {
+        match slot % 2 {
+            0 => &self.output_buffer,
+            _ => &self.pipeline_buffer,
         }
     }
 
     /// Copy LDR texture to staging buffer (call after tonemapping)
     pub fn copy_to_buffer(&self, encoder: &mut wgpu::CommandEncoder) {
+        self.copy_to_slot(encoder, 0);
+    }
+
+    /// Copy LDR texture to the staging buffer of the given readback slot
+    pub fn copy_to_slot(&self, encoder: &mut wgpu::CommandEncoder, slot: usize) {
+        self.copy_region_to_slot(encoder, slot, [0, 0, self.width, self.height]);
+    }
+
+    /// Copy a pixel rectangle (x, y, width, height, within the target) of
+    /// the LDR texture to the staging buffer of a readback slot; read it with
+    /// [`OffscreenTarget::read_region_into`]
+    pub fn copy_region_to_slot(&self, encoder: &mut wgpu::CommandEncoder, slot: usize, [x, y, width, height]: [u32; 4]) {
         encoder.copy_texture_to_buffer(
             wgpu::ImageCopyTexture {
                 texture: &self.ldr_texture,
                 mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
+                origin: wgpu::Origin3d { x, y, z: 0 },
                 aspect: wgpu::TextureAspect::All,
             },
             wgpu::ImageCopyBuffer {
-                buffer: &self.output_buffer,
+                buffer: self.slot_buffer(slot),
                 layout: wgpu::ImageDataLayout {
                     offset: 0,
-                    bytes_per_row: Some(self.padded_bytes_per_row),
-                    rows_per_image: Some(self.height),
+                    bytes_per_row: Some(padded_row_bytes(width)),
+                    rows_per_image: Some(height),
                 },
             },
             wgpu::Extent3d {
-                width: self.width,
-                height: self.height,
+                width,
+                height,
                 depth_or_array_layers: 1,
             },
         );
@@ -154,7 +245,28 @@ impl OffscreenTarget {
 
     /// Read pixels from staging buffer (blocking)
     pub fn read_pixels(&self, ctx: &GpuContext) -> Vec<u8> {
-        let buffer_slice = self.output_buffer.slice(..);
+        self.read_slot(ctx, 0, wgpu::Maintain::Wait)
+    }
+
+    /// Read pixels from the staging buffer of a readback slot, blocking until
+    /// the GPU work selected by `maintain` has completed
+    pub fn read_slot(&self, ctx: &GpuContext, slot: usize, maintain: wgpu::Maintain) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.read_slot_into(ctx, slot, maintain, &mut output);
+        output
+    }
+
+    /// Like [`OffscreenTarget::read_slot`], but replaces the contents of
+    /// `output` so a reused buffer needs no new allocation
+    pub fn read_slot_into(&self, ctx: &GpuContext, slot: usize, maintain: wgpu::Maintain, output: &mut Vec<u8>) {
+        self.read_region_into(ctx, slot, maintain, self.width, self.height, output);
+    }
+
+    /// Read a `width` x `height` region copied with
+    /// [`OffscreenTarget::copy_region_to_slot`] from a readback slot
+    pub fn read_region_into(&self, ctx: &GpuContext, slot: usize, maintain: wgpu::Maintain, width: u32, height: u32, output: &mut Vec<u8>) {
+        let buffer = self.slot_buffer(slot);
+        let buffer_slice = buffer.slice(..);
 
         // Map buffer
         let (tx, rx) = std::sync::mpsc::channel();
@@ -163,7 +275,7 @@ impl OffscreenTarget {
         });
 
         // Wait for mapping
-        ctx.device.poll(wgpu::Maintain::Wait);
+        ctx.device.poll(maintain);
         rx.recv().unwrap().unwrap();
 
         // Read data
@@ -171,19 +283,25 @@ impl OffscreenTarget {
 
         // Remove padding and create output
         let bytes_per_pixel = 4;
-        let unpadded_bytes_per_row = self.width * bytes_per_pixel;
-        let mut output = Vec::with_capacity((unpadded_bytes_per_row * self.height) as usize);
+        let unpadded_bytes_per_row = width * bytes_per_pixel;
+        let padded_bytes_per_row = padded_row_bytes(width);
+        output.clear();
+        output.reserve((unpadded_bytes_per_row * height) as usize);
 
-        for y in 0..self.height {
-            let start = (y * self.padded_bytes_per_row) as usize;
+        for y in 0..height {
+            let start = (y * padded_bytes_per_row) as usize;
             let end = start + unpadded_bytes_per_row as usize;
             output.extend_from_slice(&data[start..end]);
         }
 
         // Unmap buffer
         drop(data);
-        self.output_buffer.unmap();
-
-        output
+        buffer.unmap();
     }
 }
+
+/// Bytes per row of RGBA8 readback rows `width` pixels wide, padded to the
+/// 256-byte alignment copies need
+fn padded_row_bytes(width: u32) -> u32 {
+    (width * 4 + 255) & !255
+}
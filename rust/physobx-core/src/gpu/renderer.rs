@@ -1,6 +1,65 @@
 //! Complete renderer combining all GPU components
 
-use super::{GpuContext, GpuError, OffscreenTarget, Camera, InstanceRenderer, SphereRenderer, SkyRenderer, GroundRenderer, TonemapRenderer, ShadowRenderer};
+use super::{GpuContext, GpuError, OffscreenTarget, Camera, ProjectedPoint, InstanceRenderer, SphereRenderer, AxialRenderer, FluidRenderer, FluidDisplay, SkyRenderer, GroundRenderer, TonemapRenderer, ShadowRenderer, DecalMap, SpringDisplay, ForceChainDisplay, LabelRenderer, LabelDisplay, LabelText, LineRenderer, MeasurementDisplay, Measurement, MeasurePoint, ReferenceGrid, AxisGizmo, ShaderError, ShaderWatcher, PostEffect, RollingShutter, LensModel, PostEffects, IdRenderer, SplitScreen, PictureInPicture, InsetRenderer, Layer, LayerSource, OutputColorSpace};
+use super::compositor::{annotation_pixels, blend_into, segmentation_pixels};
+use super::rolling_shutter::interpolate_poses;
+use super::metadata::{body_from_id_color, BoundingBox3d, CameraMetadata, FrameMetadata};
+use super::pose_export::ObjectPose;
+use super::manifest::{insert_png_text, AdapterMetadata, RenderManifest, MANIFEST_PNG_KEYWORD};
+use super::shaders;
+use super::post_effect::PostTargets;
+use crate::physics::{ImpactEvent, RigidBodyStorage};
+use crate::scene::builder::ShapeType;
+use crate::simulator::{AxialInstances, FrameInstances, Simulator};
+use crate::simd;
+use crate::analysis::island_color;
+use nalgebra::{Matrix3, Point3, Quaternion, Rotation3, UnitQuaternion, Vector3};
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+
+/// A frame submitted to the GPU whose pixels have not been read back yet
+pub struct PendingFrame {
+    slot: usize,
+    submission: wgpu::SubmissionIndex,
+}
+
+/// Instance transforms from the last rendered frame (motion blur history)
+#[derive(Default, Clone)]
+struct MotionHistory {
+    cube_positions: Vec<[f32; 3]>,
+    cube_rotations: Vec<[f32; 4]>,
+    sphere_positions: Vec<[f32; 3]>,
+    axial_positions: [Vec<[f32; 3]>; ShapeType::AXIAL.len()],
+    axial_rotations: [Vec<[f32; 4]>; ShapeType::AXIAL.len()],
+}
+
+/// Static bodies uploaded once into the first instance slots of each buffer
+#[derive(Default)]
+struct StaticGeometry {
+    /// Id of the simulator the geometry came from (None = nothing uploaded)
+    source: Option<u64>,
+    cube_count: u32,
+    sphere_count: u32,
+    /// Instances of each shape of [`ShapeType::AXIAL`]
+    axial_counts: [u32; ShapeType::AXIAL.len()],
+}
+
+/// Overlay items uploaded for a frame
+#[derive(Debug, Default, Clone, Copy)]
+struct OverlayCounts {
+    /// Glyphs of body labels, first in the glyph buffer
+    label_glyphs: u32,
+    /// Glyphs of measurement values, after the labels
+    value_glyphs: u32,
+    /// Glyphs of the axis gizmo letters, after the values
+    gizmo_glyphs: u32,
+    /// Reference grid segments, first in the segment buffer
+    grid_segments: u32,
+    /// Measurement line segments, after the grids
+    measurement_segments: u32,
+    /// Axis gizmo segments, after the measurements
+    gizmo_segments: u32,
+}
 
 /// Complete renderer for physics simulation
 pub struct Renderer {
@@ -10,9 +69,58 @@ pub struct Renderer {
     pub ground_renderer: GroundRenderer,
     pub instance_renderer: InstanceRenderer,
     pub sphere_renderer: SphereRenderer,
+    /// One renderer per shape of [`ShapeType::AXIAL`], in that order
+    pub axial_renderers: [AxialRenderer; ShapeType::AXIAL.len()],
+    pub fluid_renderer: FluidRenderer,
+    pub label_renderer: LabelRenderer,
+    pub line_renderer: LineRenderer,
     pub shadow_renderer: ShadowRenderer,
     pub tonemap_renderer: TonemapRenderer,
+    pub id_renderer: IdRenderer,
     pub camera: Camera,
+    pub decals: DecalMap,
+    /// How spring joints are drawn (None = hidden)
+    spring_display: Option<SpringDisplay>,
+    /// How contact forces are drawn (None = hidden)
+    force_chain_display: Option<ForceChainDisplay>,
+    /// How fluid particles are drawn (None = hidden)
+    fluid_display: Option<FluidDisplay>,
+    /// Text drawn above each labeled body, by body index
+    labels: BTreeMap<usize, LabelText>,
+    /// How body labels are drawn
+    label_display: LabelDisplay,
+    /// Distances and angles drawn into simulator frames
+    measurements: Vec<Measurement>,
+    /// How measurements are drawn
+    measurement_display: MeasurementDisplay,
+    /// Reference grids drawn into simulator frames
+    grids: Vec<ReferenceGrid>,
+    /// World-axis gizmo drawn into simulator frames, if any
+    axis_gizmo: Option<AxisGizmo>,
+    /// Second view inset into simulator frames, if any
+    picture_in_picture: Option<PictureInPicture>,
+    /// Inset frame and compositing pass (created with the first inset)
+    inset_renderer: Option<InsetRenderer>,
+    /// Shader directory reloaded on change (None = built-in shaders)
+    shader_watcher: Option<ShaderWatcher>,
+    /// Custom passes run in order after tonemapping
+    post_effects: Vec<Box<dyn PostEffect>>,
+    /// Images the post effects alternate between (allocated with the first effect)
+    post_targets: Option<PostTargets>,
+    /// Primaries and transfer function of rendered frames
+    output_color_space: OutputColorSpace,
+    /// Whether saved PNGs carry an ICC profile of the output color space
+    embed_icc: bool,
+    /// Whether saved PNGs carry the render manifest
+    embed_manifest: bool,
+    /// Source hash of each shader's current pipeline, by file name
+    shader_hashes: BTreeMap<&'static str, u64>,
+    motion_history: Mutex<MotionHistory>,
+    /// Motion history of the second state of split-screen frames
+    split_history: Mutex<MotionHistory>,
+    static_geometry: Mutex<StaticGeometry>,
+    /// Scratch instance arrays reused by every simulator frame
+    frame_instances: Mutex<FrameInstances>,
     ground_y: f32,
     ground_size: f32,
 }
@@ -33,12 +141,20 @@ impl Renderer {
         let mut ground_renderer = GroundRenderer::new(&ctx, ground_y, ground_size);
         let mut instance_renderer = InstanceRenderer::new(&ctx, max_instances, half_extent);
         let mut sphere_renderer = SphereRenderer::new(&ctx, max_instances);
+        let mut axial_renderers = ShapeType::AXIAL.map(|shape| AxialRenderer::new(&ctx, max_instances, shape));
+        let fluid_renderer = FluidRenderer::new(&ctx);
+        let label_renderer = LabelRenderer::new(&ctx);
+        let line_renderer = LineRenderer::new(&ctx);
         let shadow_renderer = ShadowRenderer::new(&ctx, max_instances, half_extent);
         let tonemap_renderer = TonemapRenderer::new(&ctx);
+        let id_renderer = IdRenderer::new(&ctx, width, height, max_instances, half_extent);
 
         // Setup shadow bind groups
         instance_renderer.setup_shadow(&ctx, &shadow_renderer);
         sphere_renderer.setup_shadow(&ctx, &shadow_renderer);
+        for axial_renderer in &mut axial_renderers {
+            axial_renderer.setup_shadow(&ctx, &shadow_renderer);
+        }
         ground_renderer.setup_shadow(&ctx, &shadow_renderer);
 
         let mut camera = Camera::default();
@@ -51,9 +167,39 @@ impl Renderer {
             ground_renderer,
             instance_renderer,
             sphere_renderer,
+            axial_renderers,
+            fluid_renderer,
+            label_renderer,
+            line_renderer,
             shadow_renderer,
             tonemap_renderer,
+            id_renderer,
             camera,
+            decals: DecalMap::new(ground_size),
+            spring_display: None,
+            force_chain_display: None,
+            fluid_display: Some(FluidDisplay::default()),
+            labels: BTreeMap::new(),
+            label_display: LabelDisplay::default(),
+            measurements: Vec::new(),
+            measurement_display: MeasurementDisplay::default(),
+            grids: Vec::new(),
+            axis_gizmo: None,
+            picture_in_picture: None,
+            inset_renderer: None,
+            shader_watcher: None,
+            post_effects: Vec::new(),
+            post_targets: None,
+            output_color_space: OutputColorSpace::SRGB,
+            embed_icc: false,
+            embed_manifest: true,
+            shader_hashes: shaders::RELOADABLE_SHADERS.into_iter().chain(shaders::FIXED_SHADERS)
+                .map(|shader| (shader.file, shaders::source_hash(shader.source)))
+                .collect(),
+            motion_history: Mutex::new(MotionHistory::default()),
+            split_history: Mutex::new(MotionHistory::default()),
+            static_geometry: Mutex::new(StaticGeometry::default()),
+            frame_instances: Mutex::new(FrameInstances::default()),
             ground_y,
             ground_size,
         })
@@ -75,6 +221,374 @@ impl Renderer {
         self.camera.target = target.into();
     }
 
+    /// Switch to an orthographic projection showing `height` world units
+    /// vertically, or back to perspective with None
+    pub fn set_orthographic(&mut self, height: Option<f32>) {
+        self.camera.ortho_height = height;
+    }
+
+    /// Look at the xy rectangle `min`..`max` head-on with an orthographic
+    /// camera (the default view of planar scenes)
+    pub fn frame_plane(&mut self, min: [f32; 2], max: [f32; 2]) {
+        self.camera = Camera::planar(min, max, self.camera.aspect);
+    }
+
+    /// Draw spring joints as coils or lines of beads, or hide them with None
+    ///
+    /// Beads are sphere instances and count against the renderer's instance
+    /// limit; beads past it are not drawn.
+    pub fn set_spring_display(&mut self, display: Option<SpringDisplay>) {
+        self.spring_display = display;
+    }
+
+    /// Draw the contact forces of simulators as force chains, or hide them
+    /// with None
+    ///
+    /// Beads are sphere instances and count against the renderer's instance
+    /// limit; beads past it are not drawn.
+    pub fn set_force_chain_display(&mut self, display: Option<ForceChainDisplay>) {
+        self.force_chain_display = display;
+    }
+
+    /// Draw a simulator's fluid with the given look, or hide it with None
+    /// (shown with the default look unless changed)
+    pub fn set_fluid_display(&mut self, display: Option<FluidDisplay>) {
+        self.fluid_display = display;
+    }
+
+    /// Float `text` above body `index` in simulator frames, or remove its
+    /// label with None
+    pub fn set_label(&mut self, index: usize, text: Option<LabelText>) {
+        match text {
+            Some(text) => self.labels.insert(index, text),
+            None => self.labels.remove(&index),
+        };
+    }
+
+    /// Remove every body label
+    pub fn clear_labels(&mut self) {
+        self.labels.clear();
+    }
+
+    /// Number of labeled bodies
+    pub fn label_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// Change how body labels are drawn
+    pub fn set_label_display(&mut self, display: LabelDisplay) {
+        self.label_display = display;
+    }
+
+    /// How body labels are drawn
+    pub fn label_display(&self) -> &LabelDisplay {
+        &self.label_display
+    }
+
+    /// Draw the distance between two points (body indices or world points)
+    /// into simulator frames; returns the measurement's index
+    pub fn measure_distance(&mut self, a: impl Into<MeasurePoint>, b: impl Into<MeasurePoint>) -> usize {
+        self.add_measurement(Measurement::Distance { a: a.into(), b: b.into() })
+    }
+
+    /// Draw the angle at `pivot` between the arms towards `a` and `b` into
+    /// simulator frames; returns the measurement's index
+    pub fn measure_angle(&mut self, a: impl Into<MeasurePoint>, pivot: impl Into<MeasurePoint>, b: impl Into<MeasurePoint>) -> usize {
+        self.add_measurement(Measurement::Angle { a: a.into(), pivot: pivot.into(), b: b.into() })
+    }
+
+    /// Draw `measurement` into simulator frames; returns its index
+    pub fn add_measurement(&mut self, measurement: Measurement) -> usize {
+        self.measurements.push(measurement);
+        self.measurements.len() - 1
+    }
+
+    /// Stop drawing the measurement at `index` (later ones move down one)
+    pub fn remove_measurement(&mut self, index: usize) -> Option<Measurement> {
+        (index < self.measurements.len()).then(|| self.measurements.remove(index))
+    }
+
+    /// Stop drawing all measurements
+    pub fn clear_measurements(&mut self) {
+        self.measurements.clear();
+    }
+
+    /// Measurements drawn into simulator frames, by index
+    pub fn measurements(&self) -> &[Measurement] {
+        &self.measurements
+    }
+
+    /// Change how measurements are drawn
+    pub fn set_measurement_display(&mut self, display: MeasurementDisplay) {
+        self.measurement_display = display;
+    }
+
+    /// How measurements are drawn
+    pub fn measurement_display(&self) -> &MeasurementDisplay {
+        &self.measurement_display
+    }
+
+    /// Draw `grid` into simulator frames; returns its index
+    pub fn add_grid(&mut self, grid: ReferenceGrid) -> usize {
+        self.grids.push(grid);
+        self.grids.len() - 1
+    }
+
+    /// Replace all reference grids
+    pub fn set_grids(&mut self, grids: Vec<ReferenceGrid>) {
+        self.grids = grids;
+    }
+
+    /// Stop drawing reference grids
+    pub fn clear_grids(&mut self) {
+        self.grids.clear();
+    }
+
+    /// Reference grids drawn into simulator frames, by index
+    pub fn grids(&self) -> &[ReferenceGrid] {
+        &self.grids
+    }
+
+    /// Draw a world-axis gizmo into simulator frames, or stop with `None`
+    pub fn set_axis_gizmo(&mut self, gizmo: Option<AxisGizmo>) {
+        self.axis_gizmo = gizmo;
+    }
+
+    /// World-axis gizmo drawn into simulator frames, if any
+    pub fn axis_gizmo(&self) -> Option<&AxisGizmo> {
+        self.axis_gizmo.as_ref()
+    }
+
+    /// Inset a second camera's view into a corner of simulator frames, or
+    /// stop with `None`
+    ///
+    /// The inset is a full extra render of the scene each frame, without
+    /// labels, measurements or other overlays.
+    pub fn set_picture_in_picture(&mut self, inset: Option<PictureInPicture>) {
+        if inset.is_some() && self.inset_renderer.is_none() {
+            self.inset_renderer = Some(InsetRenderer::new(&self.ctx, &self.target));
+        }
+        self.picture_in_picture = inset;
+    }
+
+    /// Second view inset into simulator frames, if any
+    pub fn picture_in_picture(&self) -> Option<&PictureInPicture> {
+        self.picture_in_picture.as_ref()
+    }
+
+    /// Load the sky, ground, cube, sphere, axial, fluid and tonemap shaders from `dir`
+    /// instead of the built-in copies, and keep watching it for edits
+    ///
+    /// Returns the shaders loaded. Files missing from `dir` keep their
+    /// built-in source. The shadow and id pass shaders are always built in.
+    pub fn watch_shaders(&mut self, dir: impl Into<std::path::PathBuf>) -> Result<Vec<&'static str>, ShaderError> {
+        self.shader_watcher = Some(ShaderWatcher::new(dir));
+        self.reload_changed_shaders()
+    }
+
+    /// Stop watching and go back to the built-in shaders
+    pub fn unwatch_shaders(&mut self) -> Result<(), ShaderError> {
+        if self.shader_watcher.take().is_some() {
+            for shader in shaders::RELOADABLE_SHADERS {
+                self.reload_shader(shader, shader.source)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Directory being watched for shader edits, if any
+    pub fn shader_dir(&self) -> Option<&std::path::Path> {
+        self.shader_watcher.as_ref().map(|w| w.dir())
+    }
+
+    /// Rebuild the pipelines of shaders whose files changed since they were
+    /// last loaded, returning their file names
+    ///
+    /// Cheap enough to call every frame: the directory is checked at most
+    /// four times a second. A shader that fails to compile is reported once
+    /// and its previous pipeline stays in use until the file changes again.
+    pub fn reload_changed_shaders(&mut self) -> Result<Vec<&'static str>, ShaderError> {
+        let changed = match self.shader_watcher.as_mut() {
+            Some(watcher) => watcher.changed(),
+            None => return Ok(Vec::new()),
+        };
+        let mut reloaded = Vec::new();
+        for shader in changed {
+            let Some(watcher) = self.shader_watcher.as_mut() else { break };
+            let source = watcher.read(shader)?;
+            self.reload_shader(shader, &source)?;
+            reloaded.push(shader.file);
+        }
+        Ok(reloaded)
+    }
+
+    /// Reload changed shaders, logging compile errors instead of returning them
+    pub fn poll_shaders(&mut self) {
+        match self.reload_changed_shaders() {
+            Ok(reloaded) => {
+                for file in reloaded {
+                    log::info!("Reloaded shader {}", file);
+                }
+            }
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+
+    /// Rebuild the pipeline that uses `shader` from `source`
+    fn reload_shader(&mut self, shader: shaders::BuiltinShader, source: &str) -> Result<(), ShaderError> {
+        let reloaded = match shader {
+            shaders::SKY_SHADER => self.sky_renderer.reload_shader(&self.ctx, source),
+            shaders::GROUND_SHADER => self.ground_renderer.reload_shader(&self.ctx, source),
+            shaders::CUBE_SHADER => self.instance_renderer.reload_shader(&self.ctx, source),
+            shaders::SPHERE_SHADER => self.sphere_renderer.reload_shader(&self.ctx, source),
+            shaders::AXIAL_SHADER => self.axial_renderers.iter_mut().try_for_each(|r| r.reload_shader(&self.ctx, source)),
+            shaders::FLUID_SHADER => self.fluid_renderer.reload_shader(&self.ctx, source),
+            shaders::TONEMAP_SHADER => self.tonemap_renderer.reload_shader(&self.ctx, source),
+            _ => return Ok(()),
+        };
+        if reloaded.is_ok() {
+            self.shader_hashes.insert(shader.file, shaders::source_hash(source));
+        }
+        reloaded
+    }
+
+    /// Add a custom pass at the end of the post-effect stack, returning its
+    /// position
+    ///
+    /// Effects run in stack order on the tonemapped frame, before any
+    /// readback.
+    pub fn push_post_effect(&mut self, effect: Box<dyn PostEffect>) -> usize {
+        self.insert_post_effect(self.post_effects.len(), effect)
+    }
+
+    /// Insert a custom pass at `index` in the post-effect stack (clamped to
+    /// its length), returning its position
+    pub fn insert_post_effect(&mut self, index: usize, effect: Box<dyn PostEffect>) -> usize {
+        if self.post_targets.is_none() {
+            self.post_targets = Some(PostTargets::new(&self.ctx, self.target.width, self.target.height));
+        }
+        let index = index.min(self.post_effects.len());
+        self.post_effects.insert(index, effect);
+        index
+    }
+
+    /// Remove the pass at `index` from the post-effect stack
+    pub fn remove_post_effect(&mut self, index: usize) -> Option<Box<dyn PostEffect>> {
+        (index < self.post_effects.len()).then(|| self.post_effects.remove(index))
+    }
+
+    /// Remove every custom pass and free their images
+    pub fn clear_post_effect_stack(&mut self) {
+        self.post_effects.clear();
+        self.post_targets = None;
+    }
+
+    /// Number of custom passes in the post-effect stack
+    pub fn post_effect_count(&self) -> usize {
+        self.post_effects.len()
+    }
+
+    /// Enable per-object motion blur from the previous frame's transforms
+    ///
+    /// `shutter` is the fraction of the frame interval the shutter stays open
+    /// (0.5 matches a 180 degree film shutter); `samples` <= 1 disables blur.
+    pub fn set_motion_blur(&mut self, shutter: f32, samples: u32) {
+        self.tonemap_renderer.set_motion_blur(shutter, samples);
+    }
+
+    /// Apply Brown-Conrady lens distortion and vignetting to rendered frames
+    pub fn set_lens(&mut self, lens: &LensModel) {
+        self.tonemap_renderer.set_lens(lens);
+    }
+
+    /// Set chromatic aberration, film grain and LUT color grading
+    pub fn set_post_effects(&mut self, effects: &PostEffects) {
+        self.tonemap_renderer.set_post_effects(&self.ctx, effects);
+    }
+
+    /// Set the primaries and transfer function of rendered frames
+    ///
+    /// Applied at the end of tonemapping; labels, measurements and other
+    /// overlays drawn afterwards keep their sRGB colors.
+    pub fn set_output_color_space(&mut self, space: OutputColorSpace) {
+        self.output_color_space = space;
+        self.tonemap_renderer.set_output_color_space(&space);
+    }
+
+    /// Color space of rendered frames
+    pub fn output_color_space(&self) -> OutputColorSpace {
+        self.output_color_space
+    }
+
+    /// Embed an ICC profile of the output color space in saved PNGs
+    pub fn set_embed_icc(&mut self, embed: bool) {
+        self.embed_icc = embed;
+    }
+
+    /// Forget the previous frame so the next frame renders without blur
+    /// (call after teleporting bodies or resetting the scene)
+    pub fn reset_motion_history(&self) {
+        *self.motion_history.lock() = MotionHistory::default();
+        *self.split_history.lock() = MotionHistory::default();
+    }
+
+    /// Upload a simulator's static bodies into the first instance slots
+    ///
+    /// Static geometry is drawn in every frame in front of the per-frame
+    /// (dynamic) instances until replaced or cleared, so large environments
+    /// are not re-uploaded each frame. Rendering a simulator uploads its
+    /// static bodies automatically the first time.
+    pub fn upload_static_geometry(&self, sim: &Simulator) {
+        let mut statics = FrameInstances::default();
+        sim.instances_into(Some(true), &mut statics);
+
+        self.instance_renderer.upload_instances(&self.ctx, &statics.cube_positions, &statics.cube_rotations, &statics.cube_colors, &statics.cube_half_extents, &statics.cube_surfaces);
+        self.instance_renderer.upload_previous(&self.ctx, &statics.cube_positions, &statics.cube_rotations);
+        self.sphere_renderer.upload_instances(&self.ctx, &statics.sphere_positions, &statics.sphere_radii, &statics.sphere_colors, &statics.sphere_surfaces);
+        self.sphere_renderer.upload_previous(&self.ctx, &statics.sphere_positions, &[]);
+        self.shadow_renderer.upload_cube_instances(&self.ctx, &statics.cube_positions, &statics.cube_rotations, &statics.cube_colors, &statics.cube_half_extents);
+        self.shadow_renderer.upload_sphere_instances(&self.ctx, &statics.sphere_positions, &statics.sphere_radii, &statics.sphere_colors);
+        for (renderer, instances) in self.axial_renderers.iter().zip(&statics.axial) {
+            renderer.upload_instances(&self.ctx, instances);
+            renderer.upload_previous_at(&self.ctx, 0, &instances.positions, &instances.rotations);
+            self.shadow_renderer.upload_axial_instances_at(&self.ctx, renderer.shape(), 0, instances);
+        }
+        self.ground_renderer.upload_planes(&self.ctx, &sim.scene().planes);
+
+        *self.static_geometry.lock() = StaticGeometry {
+            source: Some(sim.id()),
+            cube_count: statics.cube_positions.len() as u32,
+            sphere_count: statics.sphere_positions.len() as u32,
+            axial_counts: statics.axial.each_ref().map(|instances| instances.len() as u32),
+        };
+    }
+
+    /// Stop drawing uploaded static geometry
+    pub fn clear_static_geometry(&self) {
+        self.ground_renderer.upload_planes(&self.ctx, &[]);
+        *self.static_geometry.lock() = StaticGeometry::default();
+    }
+
+    /// Number of (cube, sphere) instances of the uploaded static geometry
+    pub fn static_instance_counts(&self) -> (u32, u32) {
+        let statics = self.static_geometry.lock();
+        (statics.cube_count, statics.sphere_count)
+    }
+
+    /// Number of instances of each shape of [`ShapeType::AXIAL`] in the
+    /// uploaded static geometry
+    pub fn static_axial_counts(&self) -> [u32; ShapeType::AXIAL.len()] {
+        self.static_geometry.lock().axial_counts
+    }
+
+    /// Upload the simulator's static bodies unless they are already resident
+    fn sync_static_geometry(&self, sim: &Simulator) {
+        let resident = self.static_geometry.lock().source == Some(sim.id());
+        if !resident {
+            self.upload_static_geometry(sim);
+        }
+    }
+
     /// Render a frame and return RGBA pixel data (cubes only, for backwards compatibility)
     pub fn render_frame(&self, positions: &[[f32; 3]], rotations: &[[f32; 4]]) -> Vec<u8> {
         // Use default terracotta color for backwards compatibility
@@ -83,6 +597,9 @@ impl Renderer {
     }
 
     /// Render a frame with both cubes and spheres (with colors)
+    ///
+    /// The given bodies are drawn in addition to any uploaded static geometry.
+    /// Cubes have the renderer's cube size.
     pub fn render_frame_with_shapes(
         &self,
         cube_positions: &[[f32; 3]],
@@ -92,19 +609,103 @@ impl Renderer {
         sphere_radii: &[f32],
         sphere_colors: &[[f32; 3]],
     ) -> Vec<u8> {
-        let cube_count = cube_positions.len() as u32;
-        let sphere_count = sphere_positions.len() as u32;
+        let no_axial: [AxialInstances; ShapeType::AXIAL.len()] = Default::default();
+        self.render_frame_with_axial(
+            cube_positions, cube_rotations, cube_colors, &[], &[],
+            sphere_positions, sphere_radii, sphere_colors, &[],
+            &no_axial,
+        )
+    }
+
+    /// Render a frame of cubes, spheres and axial shapes (`axial` holds the
+    /// instances of each shape of [`ShapeType::AXIAL`], in order; cubes
+    /// without `cube_half_extents` have the renderer's cube size, bodies
+    /// without (roughness, metallic) surfaces the default shading)
+    #[allow(clippy::too_many_arguments)]
+    fn render_frame_with_axial(
+        &self,
+        cube_positions: &[[f32; 3]],
+        cube_rotations: &[[f32; 4]],
+        cube_colors: &[[f32; 3]],
+        cube_half_extents: &[[f32; 3]],
+        cube_surfaces: &[[f32; 2]],
+        sphere_positions: &[[f32; 3]],
+        sphere_radii: &[f32],
+        sphere_colors: &[[f32; 3]],
+        sphere_surfaces: &[[f32; 2]],
+        axial: &[AxialInstances],
+    ) -> Vec<u8> {
+        let mut encoder = self.encode_frame(
+            &self.camera,
+            cube_positions, cube_rotations, cube_colors, cube_half_extents, cube_surfaces,
+            sphere_positions, sphere_radii, sphere_colors, sphere_surfaces,
+            axial,
+            0,
+            OverlayCounts::default(),
+        );
+
+        // Copy LDR result to staging buffer
+        self.target.copy_to_buffer(&mut encoder);
+
+        // Submit commands
+        self.ctx.queue.submit(std::iter::once(encoder.finish()));
+
+        // Read pixels
+        self.target.read_pixels(&self.ctx)
+    }
+
+    /// Upload instance data and record all render passes for a frame seen
+    /// from `camera`
+    ///
+    /// `axial` holds the instances of each shape of [`ShapeType::AXIAL`];
+    /// `fluid_count` splats already uploaded to the fluid renderer are drawn
+    /// after the solid bodies, and the uploaded labels and measurements of
+    /// `overlays` over the finished frame. Returns the encoder with the tonemapped frame in
+    /// the LDR target; the caller decides where to copy it and when to submit.
+    #[allow(clippy::too_many_arguments)]
+    fn encode_frame(
+        &self,
+        camera: &Camera,
+        cube_positions: &[[f32; 3]],
+        cube_rotations: &[[f32; 4]],
+        cube_colors: &[[f32; 3]],
+        cube_half_extents: &[[f32; 3]],
+        cube_surfaces: &[[f32; 2]],
+        sphere_positions: &[[f32; 3]],
+        sphere_radii: &[f32],
+        sphere_colors: &[[f32; 3]],
+        sphere_surfaces: &[[f32; 2]],
+        axial: &[AxialInstances],
+        fluid_count: u32,
+        overlays: OverlayCounts,
+    ) -> wgpu::CommandEncoder {
+        // Dynamic instances follow the static geometry in every buffer
+        let (static_cubes, static_spheres) = self.static_instance_counts();
+        let static_axial = self.static_axial_counts();
+        let cube_count = static_cubes + cube_positions.len() as u32;
+        let sphere_count = static_spheres + sphere_positions.len() as u32;
+        let mut axial_counts = static_axial;
+        for (count, instances) in axial_counts.iter_mut().zip(axial) {
+            *count += instances.len() as u32;
+        }
 
         // Calculate scene center for shadow frustum
-        let scene_center = self.compute_scene_center(cube_positions, sphere_positions);
+        let scene_center = self.compute_scene_center(cube_positions, sphere_positions, axial);
 
         // Upload instance data to main renderers
-        self.instance_renderer.upload_instances(&self.ctx, cube_positions, cube_rotations, cube_colors);
-        self.sphere_renderer.upload_instances(&self.ctx, sphere_positions, sphere_radii, sphere_colors);
+        self.instance_renderer.upload_instances_at(&self.ctx, static_cubes, cube_positions, cube_rotations, cube_colors, cube_half_extents, cube_surfaces);
+        self.sphere_renderer.upload_instances_at(&self.ctx, static_spheres, sphere_positions, sphere_radii, sphere_colors, sphere_surfaces);
+        for ((renderer, instances), &first) in self.axial_renderers.iter().zip(axial).zip(&static_axial) {
+            renderer.upload_instances_at(&self.ctx, first, instances);
+        }
+        self.upload_motion_history(static_cubes, static_spheres, &static_axial, cube_positions, cube_rotations, sphere_positions, axial);
 
         // Upload instance data to shadow renderer
-        self.shadow_renderer.upload_cube_instances(&self.ctx, cube_positions, cube_rotations, cube_colors);
-        self.shadow_renderer.upload_sphere_instances(&self.ctx, sphere_positions, sphere_radii, sphere_colors);
+        self.shadow_renderer.upload_cube_instances_at(&self.ctx, static_cubes, cube_positions, cube_rotations, cube_colors, cube_half_extents);
+        self.shadow_renderer.upload_sphere_instances_at(&self.ctx, static_spheres, sphere_positions, sphere_radii, sphere_colors);
+        for ((shape, instances), &first) in ShapeType::AXIAL.into_iter().zip(axial).zip(&static_axial) {
+            self.shadow_renderer.upload_axial_instances_at(&self.ctx, shape, first, instances);
+        }
 
         // Update light camera for shadow pass
         self.shadow_renderer.update_light_camera(&self.ctx, scene_center);
@@ -115,12 +716,21 @@ impl Renderer {
         // Update shadow uniforms for main renderers
         self.instance_renderer.update_shadow(&self.ctx, light_view_proj);
         self.sphere_renderer.update_shadow(&self.ctx, light_view_proj);
+        for renderer in &self.axial_renderers {
+            renderer.update_shadow(&self.ctx, light_view_proj);
+        }
         self.ground_renderer.update_shadow(&self.ctx, light_view_proj);
 
         // Update camera for all renderers
-        self.instance_renderer.update_camera(&self.ctx, &self.camera);
-        self.sphere_renderer.update_camera(&self.ctx, &self.camera);
-        self.ground_renderer.update_camera(&self.ctx, &self.camera);
+        self.instance_renderer.update_camera(&self.ctx, camera);
+        self.sphere_renderer.update_camera(&self.ctx, camera);
+        for renderer in &self.axial_renderers {
+            renderer.update_camera(&self.ctx, camera);
+        }
+        self.fluid_renderer.update_camera(&self.ctx, camera);
+        self.label_renderer.update_camera(&self.ctx, camera);
+        self.line_renderer.update_camera(&self.ctx, camera);
+        self.ground_renderer.update_camera(&self.ctx, camera);
         self.ground_renderer.update_ground(&self.ctx, self.ground_y, self.ground_size, 5.0);
 
         // Create command encoder
@@ -129,29 +739,805 @@ impl Renderer {
         });
 
         // Shadow pass first
-        self.shadow_renderer.render(&mut encoder, cube_count, sphere_count);
+        self.shadow_renderer.render(&mut encoder, cube_count, sphere_count, &axial_counts);
 
-        // Render order: sky -> ground -> cubes -> spheres (all to HDR target)
+        // Render order: sky -> ground -> cubes -> spheres -> axial shapes -> fluid (all to HDR target)
         self.sky_renderer.render(&mut encoder, &self.target);
         self.ground_renderer.render(&mut encoder, &self.target);
         self.instance_renderer.render(&mut encoder, &self.target, cube_count);
         self.sphere_renderer.render(&mut encoder, &self.target, sphere_count);
+        for (renderer, &count) in self.axial_renderers.iter().zip(&axial_counts) {
+            renderer.render(&mut encoder, &self.target, count);
+        }
+        self.fluid_renderer.render(&mut encoder, &self.target, fluid_count);
 
         // Tonemap pass: HDR -> LDR
-        self.tonemap_renderer.render(&self.ctx, &mut encoder, &self.target);
+        self.tonemap_renderer.render(&self.ctx, &mut encoder, &self.target, camera);
 
-        // Copy LDR result to staging buffer
-        self.target.copy_to_buffer(&mut encoder);
+        // Custom post effects: LDR -> LDR
+        if let Some(targets) = &self.post_targets {
+            targets.apply(&self.ctx, &mut encoder, &self.target, &self.post_effects);
+        }
 
-        // Submit commands
+        // Guides, labels and measurements: on the finished LDR frame, so
+        // exposure and effects leave them legible; text goes on top of lines
+        // and the gizmo on top of everything
+        let depth_test = self.measurement_display.depth_test;
+        let grids_end = overlays.grid_segments;
+        let measurements_end = grids_end + overlays.measurement_segments;
+        self.line_renderer.render(&mut encoder, &self.target, &[
+            (0..grids_end, true),
+            (grids_end..measurements_end, depth_test),
+            (measurements_end..measurements_end + overlays.gizmo_segments, false),
+        ]);
+        let labels_end = overlays.label_glyphs;
+        let values_end = labels_end + overlays.value_glyphs;
+        self.label_renderer.render(&mut encoder, &self.target, &[
+            (0..labels_end, self.label_display.depth_test),
+            (labels_end..values_end, depth_test),
+            (values_end..values_end + overlays.gizmo_glyphs, false),
+        ]);
+
+        encoder
+    }
+
+    /// Upload last frame's transforms as motion vectors and remember this frame's
+    ///
+    /// Instances without history (first frame, or the body count changed)
+    /// reuse their current transform and so render unblurred. Static
+    /// instances keep the transforms uploaded with them.
+    #[allow(clippy::too_many_arguments)]
+    fn upload_motion_history(
+        &self,
+        first_cube: u32,
+        first_sphere: u32,
+        first_axial: &[u32],
+        cube_positions: &[[f32; 3]],
+        cube_rotations: &[[f32; 4]],
+        sphere_positions: &[[f32; 3]],
+        axial: &[AxialInstances],
+    ) {
+        let mut history = self.motion_history.lock();
+        if !self.tonemap_renderer.motion_blur_enabled() {
+            *history = MotionHistory::default();
+            return;
+        }
+
+        if history.cube_positions.len() == cube_positions.len() {
+            self.instance_renderer.upload_previous_at(&self.ctx, first_cube, &history.cube_positions, &history.cube_rotations);
+        } else {
+            self.instance_renderer.upload_previous_at(&self.ctx, first_cube, cube_positions, cube_rotations);
+        }
+        if history.sphere_positions.len() == sphere_positions.len() {
+            self.sphere_renderer.upload_previous_at(&self.ctx, first_sphere, &history.sphere_positions, &[]);
+        } else {
+            self.sphere_renderer.upload_previous_at(&self.ctx, first_sphere, sphere_positions, &[]);
+        }
+        let history = &mut *history;
+        let axial_history = history.axial_positions.iter_mut().zip(&mut history.axial_rotations);
+        for (((renderer, instances), &first), (positions, rotations)) in self.axial_renderers.iter().zip(axial).zip(first_axial).zip(axial_history) {
+            if positions.len() == instances.len() {
+                renderer.upload_previous_at(&self.ctx, first, positions, rotations);
+            } else {
+                renderer.upload_previous_at(&self.ctx, first, &instances.positions, &instances.rotations);
+            }
+            positions.clear();
+            positions.extend_from_slice(&instances.positions);
+            rotations.clear();
+            rotations.extend_from_slice(&instances.rotations);
+        }
+
+        history.cube_positions.clear();
+        history.cube_positions.extend_from_slice(cube_positions);
+        history.cube_rotations.clear();
+        history.cube_rotations.extend_from_slice(cube_rotations);
+        history.sphere_positions.clear();
+        history.sphere_positions.extend_from_slice(sphere_positions);
+    }
+
+    /// Submit a frame of the simulator's current state without waiting for it
+    ///
+    /// The frame is copied into the given readback slot (0 or 1), so the
+    /// simulation can be stepped and the next frame submitted into the other
+    /// slot while this one is still rendering and being read back; collect the
+    /// pixels with [`Renderer::finish_frame`]. Only readback is double-buffered:
+    /// every slot uploads into and draws from the same instance and uniform
+    /// buffers, so on the GPU the next frame's upload waits for this frame's
+    /// render instead of overlapping it.
+    pub fn submit_simulator(&self, sim: &Simulator, slot: usize) -> PendingFrame {
+        let mut encoder = self.encode_simulator(sim, true);
+        self.target.copy_to_slot(&mut encoder, slot);
+        let submission = self.ctx.queue.submit(std::iter::once(encoder.finish()));
+
+        PendingFrame { slot, submission }
+    }
+
+    /// Wait for a submitted frame to finish and read back its RGBA pixels
+    pub fn finish_frame(&self, pending: PendingFrame) -> Vec<u8> {
+        self.target.read_slot(
+            &self.ctx,
+            pending.slot,
+            wgpu::Maintain::WaitForSubmissionIndex(pending.submission),
+        )
+    }
+
+    /// Like [`Renderer::finish_frame`], but into a reused pixel buffer
+    pub fn finish_frame_into(&self, pending: PendingFrame, pixels: &mut Vec<u8>) {
+        self.target.read_slot_into(
+            &self.ctx,
+            pending.slot,
+            wgpu::Maintain::WaitForSubmissionIndex(pending.submission),
+            pixels,
+        );
+    }
+
+    /// Render the current state of a simulator (cubes and spheres with colors)
+    pub fn render_simulator(&self, sim: &Simulator) -> Vec<u8> {
+        let mut pixels = Vec::new();
+        self.render_simulator_into(sim, &mut pixels);
+        pixels
+    }
+
+    /// Render two simulators from the same camera as one split-screen frame
+    ///
+    /// `first` fills the image left of the split and `second` the rest, with
+    /// a divider between them (see [`SplitScreen`]). Each side keeps its own
+    /// motion history, so motion blur never mixes the two states. Static
+    /// geometry is re-uploaded when the simulators differ, so scenes with
+    /// many static bodies render slower this way.
+    pub fn render_side_by_side(&self, first: &Simulator, second: &Simulator, layout: &SplitScreen) -> Vec<u8> {
+        let mut pixels = self.render_simulator(first);
+        let second_history = std::mem::take(&mut *self.split_history.lock());
+        let first_history = std::mem::replace(&mut *self.motion_history.lock(), second_history);
+        let second_pixels = self.render_simulator(second);
+        *self.split_history.lock() = std::mem::replace(&mut *self.motion_history.lock(), first_history);
+
+        let (width, height) = self.dimensions();
+        layout.composite(&mut pixels, &second_pixels, width, height);
+        pixels
+    }
+
+    /// Render the current state of a simulator into a reused pixel buffer
+    ///
+    /// Instance extraction and uploads go through scratch buffers kept by
+    /// the renderer, so with a reused `pixels` buffer steady-state frames do
+    /// not allocate on the CPU side.
+    pub fn render_simulator_into(&self, sim: &Simulator, pixels: &mut Vec<u8>) {
+        self.render_simulator_layer_into(sim, true, pixels);
+    }
+
+    /// Render only the pixel rectangle (x, y, width, height) of the
+    /// simulator's current frame and return its RGBA pixels
+    ///
+    /// The camera is unchanged, so the patch matches the same rectangle of a
+    /// full [`Renderer::render_simulator`] frame; the scene and tonemap
+    /// passes are scissored to it and only the patch is read back. Only
+    /// fragment shading and readback shrink: the projection is not narrowed
+    /// to the patch, so vertex, shadow and post passes still run over the
+    /// full-size target. The
+    /// rectangle is clipped to the image (see [`OffscreenTarget::clamp_rect`]),
+    /// and the returned pixels have the clipped size. Bloom and motion blur
+    /// see nothing outside the patch, so they can differ near its edges.
+    pub fn render_roi(&self, sim: &Simulator, x: u32, y: u32, width: u32, height: u32) -> Vec<u8> {
+        let rect = self.target.clamp_rect([x, y, width, height]);
+        if rect[2] == 0 || rect[3] == 0 {
+            return Vec::new();
+        }
+        let scissor = self.target.scissor();
+        self.target.set_scissor(Some(rect));
+        let mut encoder = self.encode_simulator(sim, true);
+        self.target.set_scissor(scissor);
+        self.target.copy_region_to_slot(&mut encoder, 0, rect);
         self.ctx.queue.submit(std::iter::once(encoder.finish()));
+        let mut pixels = Vec::new();
+        self.target.read_region_into(&self.ctx, 0, wgpu::Maintain::Wait, rect[2], rect[3], &mut pixels);
+        pixels
+    }
 
-        // Read pixels
-        self.target.read_pixels(&self.ctx)
+    /// Render a square close-up of each body in `body_ids` and return the
+    /// crops' RGBA pixels one after another, e.g. as classification or
+    /// pose-estimation training chips
+    ///
+    /// Each crop is `crop_size` pixels wide and high (at most the image's
+    /// smaller side) and seen along the main camera's viewing direction,
+    /// with the body centered and its bounding sphere filling the crop.
+    /// Labels, measurements and the picture-in-picture inset are left out,
+    /// and the motion history of the main view is kept, so motion blur does
+    /// not smear crops. Crops of ids past the body count stay transparent
+    /// black.
+    pub fn render_object_crops(&self, sim: &Simulator, body_ids: &[usize], crop_size: u32) -> Vec<u8> {
+        let (width, height) = self.dimensions();
+        let size = crop_size.min(width).min(height);
+        let crop_bytes = (size * size * 4) as usize;
+        let mut pixels = vec![0; body_ids.len() * crop_bytes];
+        if size == 0 {
+            return pixels;
+        }
+        let rect = [(width - size) / 2, (height - size) / 2, size, size];
+        // Share of the image height the crop covers
+        let fraction = size as f32 / height as f32;
+        let direction = (self.camera.target - self.camera.eye).try_normalize(1e-6).unwrap_or(-Vector3::z());
+
+        let history = self.motion_history.lock().clone();
+        let scissor = self.target.scissor();
+        self.target.set_scissor(Some(rect));
+        let mut crop = Vec::new();
+        for (&index, out) in body_ids.iter().zip(pixels.chunks_exact_mut(crop_bytes)) {
+            if index >= sim.storage.len() {
+                continue;
+            }
+            let center = Point3::from(sim.storage.positions[index]);
+            // 10% border around the bounding sphere
+            let radius = Vector3::from(sim.local_half_extents(index)).norm().max(1e-3) * 1.1;
+            let mut camera = self.camera.clone();
+            camera.target = center;
+            match camera.ortho_height {
+                Some(_) => {
+                    camera.ortho_height = Some(2.0 * radius / fraction);
+                    camera.eye = center - direction * (radius + 100.0);
+                }
+                None => {
+                    let half_fov = ((0.5 * camera.fov_y).tan() * fraction).atan();
+                    let distance = radius / half_fov.sin();
+                    camera.eye = center - direction * distance;
+                    camera.near = camera.near.min(0.5 * (distance - radius)).max(1e-4);
+                }
+            }
+            // Looking straight down or up: pick an up vector like a top-down view
+            if camera.up.cross(&direction).norm() < 1e-6 {
+                camera.up = -Vector3::z();
+            }
+
+            // Camera uniforms are written at submit time, so each crop needs
+            // its own submission
+            let mut encoder = self.encode_simulator_from(sim, &camera, false);
+            self.target.copy_region_to_slot(&mut encoder, 0, rect);
+            self.ctx.queue.submit(std::iter::once(encoder.finish()));
+            self.target.read_region_into(&self.ctx, 0, wgpu::Maintain::Wait, size, size, &mut crop);
+            out.copy_from_slice(&crop);
+        }
+        self.target.set_scissor(scissor);
+        *self.motion_history.lock() = history;
+        pixels
+    }
+
+    /// Render the simulator, with or without labels, measurements, grids and
+    /// the axis gizmo
+    fn render_simulator_layer_into(&self, sim: &Simulator, annotated: bool, pixels: &mut Vec<u8>) {
+        let mut encoder = self.encode_simulator(sim, annotated);
+        self.target.copy_to_buffer(&mut encoder);
+        self.ctx.queue.submit(std::iter::once(encoder.finish()));
+        self.target.read_slot_into(&self.ctx, 0, wgpu::Maintain::Wait, pixels);
+    }
+
+    /// Render a composite of `layers` of the simulator's current state
+    ///
+    /// Layers are blended bottom to top over opaque black (see
+    /// [`Layer`]). All layers share the camera and the motion history of the
+    /// previous frame, so motion blur matches between them.
+    pub fn render_layers(&self, sim: &Simulator, layers: &[Layer]) -> Vec<u8> {
+        let (width, height) = self.dimensions();
+        let mut pixels = [0, 0, 0, 255].repeat(width as usize * height as usize);
+        let history = self.motion_history.lock().clone();
+        for layer in layers {
+            *self.motion_history.lock() = history.clone();
+            let layer_pixels = match &layer.source {
+                LayerSource::Color => self.render_simulator(sim),
+                LayerSource::Annotations => {
+                    let mut plain = Vec::new();
+                    self.render_simulator_layer_into(sim, false, &mut plain);
+                    *self.motion_history.lock() = history.clone();
+                    annotation_pixels(&self.render_simulator(sim), &plain)
+                }
+                LayerSource::Segmentation => segmentation_pixels(&self.render_instance_ids(sim)),
+                LayerSource::Islands => self.render_islands(sim),
+                LayerSource::Temperature { min, max } => self.render_temperature(sim, *min, *max),
+                LayerSource::Heatmap { values, max } => self.render_divergence(sim, values, *max),
+            };
+            blend_into(&mut pixels, &layer_pixels, layer.opacity, layer.blend);
+        }
+        pixels
+    }
+
+    /// Record a frame of the simulator's dynamic bodies and fluid on top of
+    /// its static geometry, with its annotations when `annotated` is set
+    fn encode_simulator(&self, sim: &Simulator, annotated: bool) -> wgpu::CommandEncoder {
+        self.encode_simulator_from(sim, &self.camera, annotated)
+    }
+
+    /// Like [`Renderer::encode_simulator`], but seen from `camera`
+    fn encode_simulator_from(&self, sim: &Simulator, camera: &Camera, annotated: bool) -> wgpu::CommandEncoder {
+        self.sync_static_geometry(sim);
+        let mut frame = self.frame_instances.lock();
+        sim.instances_into(Some(false), &mut frame);
+        if let Some(display) = &self.force_chain_display {
+            let frame = &mut *frame;
+            if !display.show_bodies {
+                frame.clear();
+            }
+            display.beads_into(&sim.contact_graph(), &mut frame.sphere_positions, &mut frame.sphere_radii, &mut frame.sphere_colors);
+        }
+        if let Some(display) = &self.spring_display {
+            let frame = &mut *frame;
+            for (a, b) in sim.spring_endpoints() {
+                display.beads_into(a, b, &mut frame.sphere_positions, &mut frame.sphere_radii, &mut frame.sphere_colors);
+            }
+        }
+        let fluid_count = match (sim.fluid(), &self.fluid_display) {
+            (Some(fluid), Some(display)) => self.fluid_renderer.upload(&self.ctx, fluid, display),
+            _ => 0,
+        };
+        let overlays = if annotated { self.upload_overlays(&sim.storage) } else { OverlayCounts::default() };
+
+        // The inset goes first in its own submission: camera uniforms are
+        // written at submit time, so both views cannot share one encoder
+        let inset = self.picture_in_picture.as_ref().zip(self.inset_renderer.as_ref());
+        if let Some((pip, inset_renderer)) = inset {
+            let inset_camera = Camera { aspect: camera.aspect, ..pip.camera.clone() };
+            // The inset must not advance the motion history of the main view
+            let history = self.motion_history.lock().clone();
+            // The inset is captured whole even when the main view is scissored
+            let scissor = self.target.scissor();
+            self.target.set_scissor(None);
+            let mut encoder = self.encode_frame(
+                &inset_camera,
+                &frame.cube_positions, &frame.cube_rotations, &frame.cube_colors, &frame.cube_half_extents, &frame.cube_surfaces,
+                &frame.sphere_positions, &frame.sphere_radii, &frame.sphere_colors, &frame.sphere_surfaces,
+                &frame.axial,
+                fluid_count,
+                OverlayCounts::default(),
+            );
+            inset_renderer.capture(&mut encoder, &self.target);
+            self.ctx.queue.submit(std::iter::once(encoder.finish()));
+            *self.motion_history.lock() = history;
+            self.target.set_scissor(scissor);
+        }
+
+        let mut encoder = self.encode_frame(
+            camera,
+            &frame.cube_positions, &frame.cube_rotations, &frame.cube_colors, &frame.cube_half_extents, &frame.cube_surfaces,
+            &frame.sphere_positions, &frame.sphere_radii, &frame.sphere_colors, &frame.sphere_surfaces,
+            &frame.axial,
+            fluid_count,
+            overlays,
+        );
+        if let Some((pip, inset_renderer)) = inset {
+            inset_renderer.render(&self.ctx, &mut encoder, &self.target, pip);
+        }
+        encoder
+    }
+
+    /// Upload the grids, gizmo, label glyphs and measurement lines and values
+    /// of the current state
+    fn upload_overlays(&self, storage: &RigidBodyStorage) -> OverlayCounts {
+        let mut overlays = OverlayCounts::default();
+        let size = self.dimensions();
+        if !self.grids.is_empty() || !self.measurements.is_empty() || self.axis_gizmo.is_some() {
+            self.line_renderer.upload(&self.ctx, size, |segments| {
+                for grid in &self.grids {
+                    grid.segments_into(segments);
+                }
+                overlays.grid_segments = segments.len() as u32;
+                self.measurement_display.segments_into(storage, &self.measurements, segments);
+                overlays.measurement_segments = segments.len() as u32 - overlays.grid_segments;
+                if let Some(gizmo) = &self.axis_gizmo {
+                    let start = segments.len();
+                    gizmo.segments_into(&self.camera, size, segments);
+                    overlays.gizmo_segments = (segments.len() - start) as u32;
+                }
+            });
+        }
+        if !self.labels.is_empty() || !self.measurements.is_empty() || self.axis_gizmo.is_some() {
+            self.label_renderer.upload(&self.ctx, &self.label_display, size, |glyphs| {
+                self.label_display.glyphs_into(storage, &self.labels, glyphs);
+                overlays.label_glyphs = glyphs.len() as u32;
+                self.measurement_display.glyphs_into(storage, &self.measurements, glyphs);
+                overlays.value_glyphs = glyphs.len() as u32 - overlays.label_glyphs;
+                if let Some(gizmo) = &self.axis_gizmo {
+                    let start = glyphs.len();
+                    gizmo.glyphs_into(&self.camera, size, glyphs);
+                    overlays.gizmo_glyphs = (glyphs.len() - start) as u32;
+                }
+            });
+        }
+        overlays
+    }
+
+    /// Render the simulator with a rolling shutter
+    ///
+    /// `start_positions` / `start_rotations` are the poses of all bodies at the
+    /// start of the frame interval; each band of rows is rendered with poses
+    /// interpolated towards the simulator's current state. Bodies without a
+    /// start pose (added since) render at their current pose.
+    pub fn render_rolling_shutter(
+        &self,
+        sim: &Simulator,
+        start_positions: &[[f32; 3]],
+        start_rotations: &[[f32; 4]],
+        shutter: &RollingShutter,
+    ) -> Vec<u8> {
+        self.sync_static_geometry(sim);
+        let storage = &sim.storage;
+        let (_, _, cube_colors) = sim.partition_cube_data(false);
+        let (_, sphere_radii, sphere_colors) = sim.partition_sphere_data(false);
+        let cube_indices = storage.indices_of(ShapeType::Cube, Some(false));
+        let cube_half_extents: Vec<_> = cube_indices.iter().map(|&i| sim.cube_half_extents(i)).collect();
+        let cube_surfaces: Vec<_> = cube_indices.iter().map(|&i| storage.surfaces[i]).collect();
+        let sphere_indices = storage.indices_of(ShapeType::Sphere, Some(false));
+        let sphere_surfaces: Vec<_> = sphere_indices.iter().map(|&i| storage.surfaces[i]).collect();
+        let mut axial = <[AxialInstances; ShapeType::AXIAL.len()]>::default();
+        for (shape, instances) in ShapeType::AXIAL.into_iter().zip(&mut axial) {
+            for &i in storage.indices_of(shape, Some(false)) {
+                instances.radii.push(storage.radii[i]);
+                instances.half_heights.push(storage.half_heights[i]);
+                instances.colors.push(storage.colors[i]);
+                instances.surfaces.push(storage.surfaces[i]);
+            }
+        }
+
+        let row_bytes = (self.target.width * 4) as usize;
+        let mut output = vec![0u8; row_bytes * self.target.height as usize];
+
+        // Every band should blur against the previous frame, not the previous band
+        let history = self.motion_history.lock().clone();
+
+        for band in 0..shutter.bands.max(1) {
+            let rows = shutter.band_rows(band, self.target.height);
+            if rows.is_empty() {
+                continue;
+            }
+
+            let (positions, rotations) = interpolate_poses(
+                start_positions,
+                start_rotations,
+                &storage.positions,
+                &storage.rotations,
+                shutter.band_time(band),
+            );
+            let pose = |i: usize| {
+                (
+                    positions.get(i).copied().unwrap_or(storage.positions[i]),
+                    rotations.get(i).copied().unwrap_or(storage.rotations[i]),
+                )
+            };
+            let (cube_positions, cube_rotations): (Vec<_>, Vec<_>) =
+                cube_indices.iter().map(|&i| pose(i)).unzip();
+            let sphere_positions: Vec<_> = sphere_indices.iter().map(|&i| pose(i).0).collect();
+            for (shape, instances) in ShapeType::AXIAL.into_iter().zip(&mut axial) {
+                (instances.positions, instances.rotations) =
+                    storage.indices_of(shape, Some(false)).iter().map(|&i| pose(i)).unzip();
+            }
+
+            *self.motion_history.lock() = history.clone();
+            let pixels = self.render_frame_with_axial(
+                &cube_positions, &cube_rotations, &cube_colors, &cube_half_extents, &cube_surfaces,
+                &sphere_positions, &sphere_radii, &sphere_colors, &sphere_surfaces,
+                &axial,
+            );
+
+            let span = rows.start as usize * row_bytes..rows.end as usize * row_bytes;
+            output[span.clone()].copy_from_slice(&pixels[span]);
+        }
+
+        output
+    }
+
+    /// Render the simulator's current state with bodies colored by divergence
+    ///
+    /// `divergence` holds one value per body (e.g. a row of
+    /// [`TrajectoryComparison::divergence`](crate::analysis::TrajectoryComparison));
+    /// colors run from blue (0) through yellow to red (`max`).
+    pub fn render_divergence(&self, sim: &Simulator, divergence: &[f32], max: f32) -> Vec<u8> {
+        let mut values = divergence.to_vec();
+        values.resize(sim.storage.len(), 0.0);
+        let mut colors = vec![[0.0; 3]; sim.storage.len()];
+        simd::colormap_into(&values, max, &mut colors);
+        self.render_recolored(sim, &colors)
+    }
+
+    /// Render the simulator's current state with bodies colored by
+    /// temperature (see [`Simulator::temperatures`]): blue at `min` through
+    /// yellow to red at `max`
+    pub fn render_temperature(&self, sim: &Simulator, min: f32, max: f32) -> Vec<u8> {
+        let values: Vec<f32> = sim.temperatures().iter().map(|t| t - min).collect();
+        let mut colors = vec![[0.0; 3]; values.len()];
+        simd::colormap_into(&values, max - min, &mut colors);
+        self.render_recolored(sim, &colors)
+    }
+
+    /// Render the simulator's current state with bodies colored by
+    /// constraint island (see [`Simulator::island_ids`])
+    pub fn render_islands(&self, sim: &Simulator) -> Vec<u8> {
+        let colors: Vec<[f32; 3]> = sim.island_ids().into_iter()
+            .map(|id| id.map_or([0.5, 0.5, 0.5], island_color))
+            .collect();
+        self.render_recolored(sim, &colors)
+    }
+
+    /// Render the dynamic bodies with per-body `colors`
+    fn render_recolored(&self, sim: &Simulator, colors: &[[f32; 3]]) -> Vec<u8> {
+        self.sync_static_geometry(sim);
+        let storage = &sim.storage;
+
+        // Static bodies keep their own colors
+        let cubes = storage.indices_of(ShapeType::Cube, Some(false));
+        let spheres = storage.indices_of(ShapeType::Sphere, Some(false));
+        let mut axial = <[AxialInstances; ShapeType::AXIAL.len()]>::default();
+        for (shape, instances) in ShapeType::AXIAL.into_iter().zip(&mut axial) {
+            for &i in storage.indices_of(shape, Some(false)) {
+                instances.push(storage, i, colors[i]);
+            }
+        }
+        self.render_frame_with_axial(
+            &cubes.iter().map(|&i| storage.positions[i]).collect::<Vec<_>>(),
+            &cubes.iter().map(|&i| storage.rotations[i]).collect::<Vec<_>>(),
+            &cubes.iter().map(|&i| colors[i]).collect::<Vec<_>>(),
+            &cubes.iter().map(|&i| sim.cube_half_extents(i)).collect::<Vec<_>>(),
+            &cubes.iter().map(|&i| storage.surfaces[i]).collect::<Vec<_>>(),
+            &spheres.iter().map(|&i| storage.positions[i]).collect::<Vec<_>>(),
+            &spheres.iter().map(|&i| storage.radii[i]).collect::<Vec<_>>(),
+            &spheres.iter().map(|&i| colors[i]).collect::<Vec<_>>(),
+            &spheres.iter().map(|&i| storage.surfaces[i]).collect::<Vec<_>>(),
+            &axial,
+        )
+    }
+
+    /// Camera, body poses, instance-ID colors and render settings for a frame
+    /// of the simulator's current state
+    pub fn frame_metadata(&self, sim: &Simulator) -> FrameMetadata {
+        FrameMetadata {
+            time: sim.time,
+            camera: CameraMetadata::new(&self.camera, self.target.width, self.target.height),
+            bodies: FrameMetadata::bodies_from(&sim.storage),
+            settings: self.render_settings(),
+        }
+    }
+
+    /// Every setting that affects rendered frames, with the crate version,
+    /// GPU adapter and hashes of the shaders in use
+    ///
+    /// Embedded in saved PNGs (see [`Renderer::set_embed_manifest`]) so an
+    /// image can be traced back to the configuration that produced it.
+    pub fn config_manifest(&self) -> RenderManifest {
+        let on_off = |enabled: bool| if enabled { "on" } else { "off" }.to_string();
+        let mut options = vec![
+            ("output_color_space", self.output_color_space.description()),
+            ("embed_icc", on_off(self.embed_icc)),
+            ("shader_dir", self.shader_dir().map_or("built-in".to_string(), |dir| dir.display().to_string())),
+            ("springs", on_off(self.spring_display.is_some())),
+            ("force_chains", on_off(self.force_chain_display.is_some())),
+            ("fluid", on_off(self.fluid_display.is_some())),
+            ("labels", self.labels.len().to_string()),
+            ("measurements", self.measurements.len().to_string()),
+            ("grids", self.grids.len().to_string()),
+            ("axis_gizmo", on_off(self.axis_gizmo.is_some())),
+            ("picture_in_picture", on_off(self.picture_in_picture.is_some())),
+            ("ground", format!("y {} size {}", self.ground_y, self.ground_size)),
+        ];
+        if let Some(pip) = &self.picture_in_picture {
+            options.push(("picture_in_picture_camera", format!("{:?} -> {:?}", pip.camera.eye, pip.camera.target)));
+        }
+        RenderManifest {
+            crate_version: crate::version(),
+            adapter: AdapterMetadata::from_info(&self.ctx.adapter.get_info()),
+            camera: CameraMetadata::new(&self.camera, self.target.width, self.target.height),
+            settings: self.render_settings(),
+            options,
+            shaders: self.shader_hashes.iter().map(|(&file, &hash)| (file, hash)).collect(),
+            post_effects: self.post_effects.iter().map(|effect| effect.source_hash()).collect(),
+        }
+    }
+
+    /// Embed the render manifest in saved PNGs (on by default)
+    pub fn set_embed_manifest(&mut self, embed: bool) {
+        self.embed_manifest = embed;
+    }
+
+    /// Numeric tonemap and post-effect settings as name/value pairs
+    fn render_settings(&self) -> Vec<(&'static str, f32)> {
+        let p = self.tonemap_renderer.params();
+        vec![
+            ("exposure", p.exposure),
+            ("motion_blur_shutter", p.motion_blur_shutter),
+            ("motion_blur_samples", p.motion_blur_samples as f32),
+            ("k1", p.radial[0]),
+            ("k2", p.radial[1]),
+            ("k3", p.radial[2]),
+            ("p1", p.tangential[0]),
+            ("p2", p.tangential[1]),
+            ("vignette", p.vignette),
+            ("chromatic_aberration", p.chromatic_aberration),
+            ("grain", p.grain),
+            ("lut_strength", p.lut_strength),
+        ]
+    }
+
+    /// Project world points into the rendered image with the current camera
+    ///
+    /// Pixel coordinates follow the saved image (origin top-left, +Y down)
+    /// and include the current lens distortion.
+    pub fn project_points(&self, points: &[[f32; 3]]) -> Vec<ProjectedPoint> {
+        let (width, height) = self.dimensions();
+        let lens = self.tonemap_renderer.lens();
+        points.iter()
+            .map(|&point| {
+                let mut projected = self.camera.project(point, width, height);
+                if lens.is_distorting() && projected.depth > 0.0 {
+                    let p = self.camera.pixel_to_normalized(projected.pixel, width, height);
+                    projected.pixel = self.camera.normalized_to_pixel(lens.distort(p), width, height);
+                    projected.in_frustum = self.camera.in_frustum(projected.pixel, projected.depth, width, height);
+                }
+                projected
+            })
+            .collect()
+    }
+
+    /// Oriented 3D bounding box of every body in the camera frame, with its
+    /// corners projected into the rendered image (see [`BoundingBox3d`])
+    ///
+    /// Boxes tightly enclose each body's own shape along its local axes
+    /// (child colliders are not included), for 3D detection labels that
+    /// line up with frames rendered from the current camera.
+    pub fn bounding_boxes_3d(&self, sim: &Simulator) -> Vec<BoundingBox3d> {
+        let (view_rotation, view_translation) = self.opencv_view();
+        let storage = &sim.storage;
+
+        (0..storage.len())
+            .map(|index| {
+                let half_extents = sim.local_half_extents(index);
+                let [x, y, z, w] = storage.rotations[index];
+                let body_rotation = UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z));
+                let rotation = view_rotation * body_rotation.to_rotation_matrix().matrix();
+                let center = view_rotation * Vector3::from(storage.positions[index]) + view_translation;
+                let world_corners: [[f32; 3]; 8] = std::array::from_fn(|k| {
+                    let side = |axis: usize| if k >> axis & 1 == 1 { half_extents[axis] } else { -half_extents[axis] };
+                    let local = Vector3::new(side(0), side(1), side(2));
+                    (Vector3::from(storage.positions[index]) + body_rotation * local).into()
+                });
+                let projected = self.project_points(&world_corners);
+                let camera_rotation = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(rotation));
+                BoundingBox3d {
+                    index,
+                    center: center.into(),
+                    half_extents,
+                    rotation: camera_rotation.coords.into(),
+                    corners: world_corners.map(|corner| (view_rotation * Vector3::from(corner) + view_translation).into()),
+                    pixels: std::array::from_fn(|k| projected[k].pixel),
+                    in_frustum: projected.iter().any(|p| p.in_frustum),
+                }
+            })
+            .collect()
+    }
+
+    /// World to camera rotation and translation of the current camera in
+    /// the OpenCV convention (+X right, +Y down, +Z forward)
+    fn opencv_view(&self) -> (Matrix3<f32>, Vector3<f32>) {
+        // OpenGL view (camera looks down -Z, +Y up) to OpenCV (+Z forward, +Y down)
+        let flip = Matrix3::from_diagonal(&Vector3::new(1.0, -1.0, -1.0));
+        let view = self.camera.view_matrix();
+        (flip * view.fixed_view::<3, 3>(0, 0), flip * view.fixed_view::<3, 1>(0, 3))
+    }
+
+    /// World to camera rotation (row-major) and translation of the current
+    /// camera, in the camera frame of [`CameraMetadata::intrinsics`]
+    pub fn world_to_camera(&self) -> ([[f32; 3]; 3], [f32; 3]) {
+        let (rotation, translation) = self.opencv_view();
+        (std::array::from_fn(|r| std::array::from_fn(|c| rotation[(r, c)])), translation.into())
+    }
+
+    /// 6-DoF pose of every body in the camera frame of
+    /// [`CameraMetadata::intrinsics`] (see [`ObjectPose`]), e.g. for pose
+    /// estimation labels
+    pub fn object_poses(&self, sim: &Simulator) -> Vec<ObjectPose> {
+        let (view_rotation, view_translation) = self.opencv_view();
+        let storage = &sim.storage;
+        (0..storage.len())
+            .map(|index| {
+                let [x, y, z, w] = storage.rotations[index];
+                let body_rotation = UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z));
+                let rotation = view_rotation * body_rotation.to_rotation_matrix().matrix();
+                ObjectPose {
+                    index,
+                    shape: ShapeType::from_id(storage.shape_types[index]).unwrap_or(ShapeType::Cube),
+                    half_extents: sim.local_half_extents(index),
+                    rotation: std::array::from_fn(|r| std::array::from_fn(|c| rotation[(r, c)])),
+                    translation: (view_rotation * Vector3::from(storage.positions[index]) + view_translation).into(),
+                }
+            })
+            .collect()
+    }
+
+    /// Ideal pinhole pixel of a pixel in the rendered (lens-distorted) image
+    fn undistort_pixel(&self, pixel: [f32; 2]) -> [f32; 2] {
+        let (width, height) = self.dimensions();
+        let lens = self.tonemap_renderer.lens();
+        if !lens.is_distorting() {
+            return pixel;
+        }
+        let p = self.camera.pixel_to_normalized(pixel, width, height);
+        self.camera.normalized_to_pixel(lens.undistort(p), width, height)
+    }
+
+    /// World point seen at a pixel of the rendered image at the given depth
+    /// (distance along the view axis); inverse of [`Renderer::project_points`]
+    pub fn unproject(&self, pixel: [f32; 2], depth: f32) -> [f32; 3] {
+        let (width, height) = self.dimensions();
+        self.camera.unproject(self.undistort_pixel(pixel), depth, width, height)
+    }
+
+    /// Camera ray through a pixel of the rendered image: (origin, unit direction)
+    pub fn screen_ray(&self, pixel: [f32; 2]) -> ([f32; 3], [f32; 3]) {
+        let (width, height) = self.dimensions();
+        self.camera.screen_ray(self.undistort_pixel(pixel), width, height)
+    }
+
+    /// Per-pixel body index of the simulator's current state (-1 = background)
+    ///
+    /// Row-major `height * width`, matching the rendered image.
+    pub fn render_instance_ids(&self, sim: &Simulator) -> Vec<i32> {
+        self.id_renderer.render_ids(&self.ctx, &self.camera, &sim.storage, |i| sim.cube_half_extents(i), |i| sim.is_hidden(i))
+            .chunks_exact(4)
+            .map(|px| body_from_id_color([px[0], px[1], px[2]]).map_or(-1, |body| body as i32))
+            .collect()
+    }
+
+    /// Fraction of each body visible in the current frame
+    ///
+    /// Pixels visible after occlusion divided by the pixels the body would
+    /// cover if nothing else were drawn. Bodies outside the frame report 0.
+    pub fn visibility_fractions(&self, sim: &Simulator) -> Vec<f32> {
+        self.id_renderer.visibility_fractions(&self.ctx, &self.camera, &sim.storage, |i| sim.cube_half_extents(i), |i| sim.is_hidden(i))
+    }
+
+    /// Stamp ground decals for impacts above the decal impulse threshold
+    ///
+    /// Decals persist across frames until [`Renderer::clear_decals`] is called.
+    pub fn stamp_impacts(&mut self, events: &[ImpactEvent]) {
+        if self.decals.stamp_impacts(events) {
+            self.ground_renderer.upload_decals(&self.ctx, &self.decals);
+        }
+    }
+
+    /// Remove all ground decals
+    pub fn clear_decals(&mut self) {
+        self.decals.clear();
+        self.ground_renderer.upload_decals(&self.ctx, &self.decals);
+    }
+
+    /// Top-down orthographic occupancy grid computed from body AABBs (CPU only)
+    ///
+    /// Returns `resolution * resolution` cells covering the ground plane
+    /// (row-major, rows along +Z, columns along +X). A cell is 1 when the AABB
+    /// of a body whose vertical extent overlaps `height_range` (min, max)
+    /// touches it, 0 otherwise.
+    pub fn render_occupancy(&self, storage: &RigidBodyStorage, resolution: u32, height_range: [f32; 2]) -> Vec<u8> {
+        let res = resolution as usize;
+        let mut grid = vec![0u8; res * res];
+        if res == 0 {
+            return grid;
+        }
+
+        let size = self.ground_size;
+        let cell = 2.0 * size / resolution as f32;
+        let to_cell = |v: f32| ((v + size) / cell).floor().clamp(0.0, (res - 1) as f32) as usize;
+
+        let (mins, maxs) = storage.aabbs();
+        for (min, max) in mins.into_iter().zip(maxs) {
+            let outside = max[1] < height_range[0] || min[1] > height_range[1]
+                || max[0] < -size || min[0] > size
+                || max[2] < -size || min[2] > size;
+            if outside {
+                continue;
+            }
+            for row in to_cell(min[2])..=to_cell(max[2]) {
+                grid[row * res + to_cell(min[0])..=row * res + to_cell(max[0])].fill(1);
+            }
+        }
+        grid
     }
 
     /// Compute approximate scene center for shadow frustum positioning
-    fn compute_scene_center(&self, cube_positions: &[[f32; 3]], sphere_positions: &[[f32; 3]]) -> [f32; 3] {
+    fn compute_scene_center(&self, cube_positions: &[[f32; 3]], sphere_positions: &[[f32; 3]], axial: &[AxialInstances]) -> [f32; 3] {
         let mut sum = [0.0f32; 3];
         let mut count = 0;
 
@@ -162,7 +1548,8 @@ impl Renderer {
             count += 1;
         }
 
-        for pos in sphere_positions {
+        let axial_positions = axial.iter().flat_map(|instances| &instances.positions);
+        for pos in sphere_positions.iter().chain(axial_positions) {
             sum[0] += pos[0];
             sum[1] += pos[1];
             sum[2] += pos[2];
@@ -179,14 +1566,7 @@ impl Renderer {
     /// Save frame as PNG (cubes only)
     pub fn save_png(&self, positions: &[[f32; 3]], rotations: &[[f32; 4]], path: &str) -> Result<(), image::ImageError> {
         let pixels = self.render_frame(positions, rotations);
-
-        image::save_buffer(
-            path,
-            &pixels,
-            self.target.width,
-            self.target.height,
-            image::ColorType::Rgba8,
-        )
+        self.save_pixels(&pixels, path)
     }
 
     /// Save frame as PNG with both cubes and spheres (with colors)
@@ -204,14 +1584,40 @@ impl Renderer {
             cube_positions, cube_rotations, cube_colors,
             sphere_positions, sphere_radii, sphere_colors
         );
+        self.save_pixels(&pixels, path)
+    }
 
-        image::save_buffer(
-            path,
-            &pixels,
-            self.target.width,
-            self.target.height,
-            image::ColorType::Rgba8,
-        )
+    /// Save already rendered RGBA pixels (e.g. from [`Renderer::render_rolling_shutter`]) as PNG
+    ///
+    /// PNG files carry the render manifest as a `physobx:manifest` text
+    /// chunk (see [`Renderer::config_manifest`]) and, with
+    /// [`Renderer::set_embed_icc`] enabled, an ICC profile of the output
+    /// color space.
+    pub fn save_pixels(&self, pixels: &[u8], path: &str) -> Result<(), image::ImageError> {
+        use image::ImageEncoder;
+
+        let is_png = std::path::Path::new(path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+        if !(is_png && (self.embed_icc || self.embed_manifest)) {
+            return image::save_buffer(
+                path,
+                pixels,
+                self.target.width,
+                self.target.height,
+                image::ColorType::Rgba8,
+            );
+        }
+        let mut png = Vec::new();
+        let mut encoder = image::codecs::png::PngEncoder::new(&mut png);
+        if self.embed_icc {
+            encoder.set_icc_profile(self.output_color_space.icc_profile())
+                .map_err(image::ImageError::Unsupported)?;
+        }
+        encoder.write_image(pixels, self.target.width, self.target.height, image::ExtendedColorType::Rgba8)?;
+        if self.embed_manifest {
+            insert_png_text(&mut png, MANIFEST_PNG_KEYWORD, &self.config_manifest().to_json());
+        }
+        std::fs::write(path, png)?;
+        Ok(())
     }
 
     /// Get dimensions
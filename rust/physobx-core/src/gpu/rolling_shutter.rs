@@ -0,0 +1,65 @@
+//! Rolling-shutter capture: image rows exposed at staggered sub-frame times
+//!
+//! CMOS sensors read rows out top to bottom, so fast motion shears and wobbles
+//! in the image. The frame is split into horizontal bands; each band is
+//! rendered with body poses interpolated to the time its rows were read out.
+
+use std::ops::Range;
+
+/// Rolling-shutter readout model
+#[derive(Debug, Clone, Copy)]
+pub struct RollingShutter {
+    /// Number of row bands rendered at distinct times (more = smoother shear)
+    pub bands: u32,
+    /// Fraction of the frame interval spent reading out rows (0 = global shutter)
+    pub readout: f32,
+}
+
+impl Default for RollingShutter {
+    fn default() -> Self {
+        Self { bands: 16, readout: 1.0 }
+    }
+}
+
+impl RollingShutter {
+    /// Interpolation time of a band in [0, 1] (0 = frame start, 1 = current state)
+    ///
+    /// The bottom row is read out last, at the current state.
+    pub fn band_time(&self, band: u32) -> f32 {
+        let bands = self.bands.max(1) as f32;
+        let readout = self.readout.clamp(0.0, 1.0);
+        1.0 - readout * (1.0 - (band as f32 + 0.5) / bands)
+    }
+
+    /// Image rows covered by a band
+    pub fn band_rows(&self, band: u32, height: u32) -> Range<u32> {
+        let bands = self.bands.max(1);
+        (band * height / bands)..((band + 1) * height / bands)
+    }
+}
+
+/// Interpolate body poses between two states (positions lerped, rotations nlerped)
+pub fn interpolate_poses(
+    start_positions: &[[f32; 3]],
+    start_rotations: &[[f32; 4]],
+    end_positions: &[[f32; 3]],
+    end_rotations: &[[f32; 4]],
+    t: f32,
+) -> (Vec<[f32; 3]>, Vec<[f32; 4]>) {
+    let positions = start_positions.iter().zip(end_positions)
+        .map(|(a, b)| std::array::from_fn(|k| a[k] + (b[k] - a[k]) * t))
+        .collect();
+
+    let rotations = start_rotations.iter().zip(end_rotations)
+        .map(|(a, b)| {
+            // Take the short way around (q and -q are the same rotation)
+            let dot: f32 = (0..4).map(|k| a[k] * b[k]).sum();
+            let sign = if dot < 0.0 { -1.0 } else { 1.0 };
+            let q: [f32; 4] = std::array::from_fn(|k| a[k] + (sign * b[k] - a[k]) * t);
+            let norm = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+            if norm > 1e-6 { q.map(|c| c / norm) } else { *b }
+        })
+        .collect();
+
+    (positions, rotations)
+}
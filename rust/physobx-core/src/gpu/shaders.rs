@@ -0,0 +1,163 @@
+//! WGSL shader sources and hot reloading from disk
+//!
+//! Shaders are compiled into the crate. For shader development, a renderer
+//! can instead read them from a directory (normally `rust/physobx-core/shaders`)
+//! and rebuild the affected pipelines whenever a file's modification time
+//! changes, so edits show up without recompiling the crate. A shader that
+//! fails to compile leaves the previous pipeline in place.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use thiserror::Error;
+use super::context::GpuContext;
+
+/// Minimum time between two checks of the shader files
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Errors from loading or compiling a shader
+#[derive(Error, Debug)]
+pub enum ShaderError {
+    #[error("Failed to read shader {path}: {source}")]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Shader {file} failed to compile: {message}")]
+    Invalid { file: String, message: String },
+}
+
+/// A shader compiled into the crate, by file name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinShader {
+    /// File name inside the shader directory
+    pub file: &'static str,
+    /// Source embedded at build time
+    pub source: &'static str,
+}
+
+pub const SKY_SHADER: BuiltinShader = BuiltinShader {
+    file: "fullscreen.wgsl",
+    source: include_str!("../../shaders/fullscreen.wgsl"),
+};
+pub const GROUND_SHADER: BuiltinShader = BuiltinShader {
+    file: "ground.wgsl",
+    source: include_str!("../../shaders/ground.wgsl"),
+};
+pub const CUBE_SHADER: BuiltinShader = BuiltinShader {
+    file: "cube_instance.wgsl",
+    source: include_str!("../../shaders/cube_instance.wgsl"),
+};
+pub const SPHERE_SHADER: BuiltinShader = BuiltinShader {
+    file: "sphere_instance.wgsl",
+    source: include_str!("../../shaders/sphere_instance.wgsl"),
+};
+pub const AXIAL_SHADER: BuiltinShader = BuiltinShader {
+    file: "axial_instance.wgsl",
+    source: include_str!("../../shaders/axial_instance.wgsl"),
+};
+pub const FLUID_SHADER: BuiltinShader = BuiltinShader {
+    file: "fluid.wgsl",
+    source: include_str!("../../shaders/fluid.wgsl"),
+};
+pub const TONEMAP_SHADER: BuiltinShader = BuiltinShader {
+    file: "tonemap.wgsl",
+    source: include_str!("../../shaders/tonemap.wgsl"),
+};
+
+pub const SHADOW_SHADER: BuiltinShader = BuiltinShader {
+    file: "shadow_depth.wgsl",
+    source: include_str!("../../shaders/shadow_depth.wgsl"),
+};
+pub const ID_SHADER: BuiltinShader = BuiltinShader {
+    file: "id_pass.wgsl",
+    source: include_str!("../../shaders/id_pass.wgsl"),
+};
+pub const LABEL_SHADER: BuiltinShader = BuiltinShader {
+    file: "label.wgsl",
+    source: include_str!("../../shaders/label.wgsl"),
+};
+pub const OVERLAY_LINE_SHADER: BuiltinShader = BuiltinShader {
+    file: "overlay_line.wgsl",
+    source: include_str!("../../shaders/overlay_line.wgsl"),
+};
+pub const INSET_SHADER: BuiltinShader = BuiltinShader {
+    file: "inset.wgsl",
+    source: include_str!("../../shaders/inset.wgsl"),
+};
+pub const OUTLINE_SHADER: BuiltinShader = BuiltinShader {
+    file: "outline.wgsl",
+    source: include_str!("../../shaders/outline.wgsl"),
+};
+
+/// Shaders that can be hot reloaded
+pub const RELOADABLE_SHADERS: [BuiltinShader; 7] = [SKY_SHADER, GROUND_SHADER, CUBE_SHADER, SPHERE_SHADER, AXIAL_SHADER, FLUID_SHADER, TONEMAP_SHADER];
+
+/// Shaders that are always compiled in (overlays, shadows, ID pass, ...)
+pub const FIXED_SHADERS: [BuiltinShader; 6] = [SHADOW_SHADER, ID_SHADER, LABEL_SHADER, OVERLAY_LINE_SHADER, INSET_SHADER, OUTLINE_SHADER];
+
+/// Stable 64-bit FNV-1a hash of a shader source, for render manifests
+pub fn source_hash(source: &str) -> u64 {
+    source.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3))
+}
+
+/// Shader directory watched for edits
+#[derive(Debug)]
+pub struct ShaderWatcher {
+    dir: PathBuf,
+    /// Modification time of each file when it was last loaded
+    loaded: HashMap<&'static str, SystemTime>,
+    last_poll: Option<Instant>,
+}
+
+impl ShaderWatcher {
+    /// Watch `dir`; every shader counts as changed until first loaded
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into(), loaded: HashMap::new(), last_poll: None }
+    }
+
+    /// Directory the shaders are read from
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Shaders whose files changed since they were last loaded
+    ///
+    /// Returns nothing if called again within a quarter second, so it can be
+    /// polled every step. Missing files are skipped (the built-in or last
+    /// loaded source stays in use).
+    pub fn changed(&mut self) -> Vec<BuiltinShader> {
+        let now = Instant::now();
+        if self.last_poll.is_some_and(|t| now.duration_since(t) < POLL_INTERVAL) {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+        RELOADABLE_SHADERS.into_iter()
+            .filter(|shader| {
+                let Ok(modified) = std::fs::metadata(self.dir.join(shader.file)).and_then(|m| m.modified()) else {
+                    return false;
+                };
+                self.loaded.get(shader.file) != Some(&modified)
+            })
+            .collect()
+    }
+
+    /// Read a shader's source from the directory and mark it loaded
+    pub fn read(&mut self, shader: BuiltinShader) -> Result<String, ShaderError> {
+        let path = self.dir.join(shader.file);
+        let io = |source| ShaderError::Io { path: path.clone(), source };
+        let modified = std::fs::metadata(&path).and_then(|m| m.modified()).map_err(io)?;
+        let source = std::fs::read_to_string(&path).map_err(io)?;
+        // A failed compile is not retried until the file changes again
+        self.loaded.insert(shader.file, modified);
+        Ok(source)
+    }
+}
+
+/// Run `build` (shader module and pipeline creation) and report any
+/// validation error instead of letting wgpu panic
+pub(crate) fn build_checked<T>(ctx: &GpuContext, file: &str, build: impl FnOnce() -> T) -> Result<T, ShaderError> {
+    ctx.device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let built = build();
+    match pollster::block_on(ctx.device.pop_error_scope()) {
+        Some(error) => Err(ShaderError::Invalid { file: file.to_string(), message: error.to_string() }),
+        None => Ok(built),
+    }
+}
@@ -3,6 +3,9 @@
 use super::context::GpuContext;
 use super::instance_renderer::InstanceData;
 use super::sphere_renderer::SphereInstanceData;
+use super::axial_renderer::{AxialInstanceData, AxialMesh, AxialVertex};
+use crate::scene::builder::ShapeType;
+use crate::simulator::AxialInstances;
 use bytemuck::{Pod, Zeroable};
 use wgpu::util::DeviceExt;
 
@@ -39,6 +42,13 @@ impl ShadowVertex {
     }
 }
 
+/// Shadow pass of one axial shape
+struct AxialShadowPass {
+    mesh: AxialMesh,
+    instance_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
 /// Shadow map renderer
 pub struct ShadowRenderer {
     // Shadow map texture
@@ -62,6 +72,10 @@ pub struct ShadowRenderer {
     sphere_instance_buffer: wgpu::Buffer,
     sphere_bind_group: wgpu::BindGroup,
 
+    // Axial shape shadow passes, one per shape of ShapeType::AXIAL
+    axial_pipeline: wgpu::RenderPipeline,
+    axial_passes: [AxialShadowPass; ShapeType::AXIAL.len()],
+
     // Shared light camera buffer
     light_camera_buffer: wgpu::Buffer,
 
@@ -119,7 +133,7 @@ impl ShadowRenderer {
         // Create shader module
         let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Shadow Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/shadow_depth.wgsl").into()),
+            source: wgpu::ShaderSource::Wgsl(super::shaders::SHADOW_SHADER.source.into()),
         });
 
         // === Cube shadow pipeline ===
@@ -314,6 +328,100 @@ impl ShadowRenderer {
             cache: None,
         });
 
+        // === Axial shape shadow pipeline ===
+        // Bindings 0 and 3 only: the instances of one shape per bind group
+        let axial_bind_group_layout = ctx.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Shadow Axial Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let axial_passes = ShapeType::AXIAL.map(|shape| {
+            let instance_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Shadow Axial Instance Buffer"),
+                size: (max_instances as u64) * std::mem::size_of::<AxialInstanceData>() as u64,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Shadow Axial Bind Group"),
+                layout: &axial_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: light_camera_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: instance_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+            AxialShadowPass {
+                mesh: AxialMesh::new(ctx, shape, "Shadow Axial"),
+                instance_buffer,
+                bind_group,
+            }
+        });
+
+        let axial_pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Shadow Axial Pipeline Layout"),
+            bind_group_layouts: &[&axial_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let axial_pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shadow Axial Pipeline"),
+            layout: Some(&axial_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_axial"),
+                buffers: &[AxialVertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: None, // Depth-only
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: Some(wgpu::Face::Back),
+                ..Default::default()
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: wgpu::TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState {
+                    constant: 2,
+                    slope_scale: 2.0,
+                    clamp: 0.0,
+                },
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
         // Default light direction (same as key light in shaders)
         let light_dir = normalize([-0.5, 0.9, 0.6]);
 
@@ -333,6 +441,8 @@ impl ShadowRenderer {
             sphere_index_count,
             sphere_instance_buffer,
             sphere_bind_group,
+            axial_pipeline,
+            axial_passes,
             light_camera_buffer,
             light_dir,
             frustum_size: 100.0,
@@ -358,21 +468,26 @@ impl ShadowRenderer {
         positions: &[[f32; 3]],
         rotations: &[[f32; 4]],
         colors: &[[f32; 3]],
+        half_extents: &[[f32; 3]],
     ) {
-        let instance_count = positions.len().min(self.max_instances as usize);
-        let mut instances = Vec::with_capacity(instance_count);
-
-        for i in 0..instance_count {
-            instances.push(InstanceData {
-                position: positions[i],
-                _padding: 0.0,
-                rotation: rotations[i],
-                color: colors[i],
-                _padding2: 0.0,
-            });
-        }
+        self.upload_cube_instances_at(ctx, 0, positions, rotations, colors, half_extents);
+    }
 
-        ctx.queue.write_buffer(&self.cube_instance_buffer, 0, bytemuck::cast_slice(&instances));
+    /// Upload cube instances for shadow rendering into the slots starting at `first`
+    pub fn upload_cube_instances_at(
+        &self,
+        ctx: &GpuContext,
+        first: u32,
+        positions: &[[f32; 3]],
+        rotations: &[[f32; 4]],
+        colors: &[[f32; 3]],
+        half_extents: &[[f32; 3]],
+    ) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = positions.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.cube_instance_buffer, first, instance_count, |i| {
+            InstanceData::of(positions, rotations, colors, half_extents, &[], self.half_extent, i)
+        });
     }
 
     /// Upload sphere instances for shadow rendering
@@ -383,20 +498,34 @@ impl ShadowRenderer {
         radii: &[f32],
         colors: &[[f32; 3]],
     ) {
-        let instance_count = positions.len().min(self.max_instances as usize);
-        let mut instances = Vec::with_capacity(instance_count);
-
-        for i in 0..instance_count {
-            instances.push(SphereInstanceData {
-                position: positions[i],
-                radius: radii[i],
-                rotation: [0.0, 0.0, 0.0, 1.0],
-                color: colors[i],
-                _padding: 0.0,
-            });
-        }
+        self.upload_sphere_instances_at(ctx, 0, positions, radii, colors);
+    }
+
+    /// Upload sphere instances for shadow rendering into the slots starting at `first`
+    pub fn upload_sphere_instances_at(
+        &self,
+        ctx: &GpuContext,
+        first: u32,
+        positions: &[[f32; 3]],
+        radii: &[f32],
+        colors: &[[f32; 3]],
+    ) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = positions.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.sphere_instance_buffer, first, instance_count, |i| {
+            SphereInstanceData::of(positions, radii, colors, &[], i)
+        });
+    }
 
-        ctx.queue.write_buffer(&self.sphere_instance_buffer, 0, bytemuck::cast_slice(&instances));
+    /// Upload instances of an axial shape for shadow rendering into the
+    /// slots starting at `first` (ignored for cubes and spheres)
+    pub fn upload_axial_instances_at(&self, ctx: &GpuContext, shape: ShapeType, first: u32, instances: &AxialInstances) {
+        let Some(slot) = shape.axial_slot() else {
+            return;
+        };
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = instances.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.axial_passes[slot].instance_buffer, first, instance_count, |i| AxialInstanceData::of(instances, i));
     }
 
     /// Update light camera for shadow pass (orthographic projection from light direction)
@@ -435,11 +564,15 @@ impl ShadowRenderer {
     }
 
     /// Render shadow map
+    ///
+    /// `axial_counts` holds the instance count of each shape of
+    /// [`ShapeType::AXIAL`], in order.
     pub fn render(
         &self,
         encoder: &mut wgpu::CommandEncoder,
         cube_count: u32,
         sphere_count: u32,
+        axial_counts: &[u32],
     ) {
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Shadow Render Pass"),
@@ -473,6 +606,15 @@ impl ShadowRenderer {
             render_pass.set_index_buffer(self.sphere_index_buffer.slice(..), wgpu::IndexFormat::Uint16);
             render_pass.draw_indexed(0..self.sphere_index_count, 0, 0..sphere_count);
         }
+
+        // Render axial shapes to shadow map
+        render_pass.set_pipeline(&self.axial_pipeline);
+        for (pass, &count) in self.axial_passes.iter().zip(axial_counts) {
+            if count > 0 {
+                render_pass.set_bind_group(0, &pass.bind_group, &[]);
+                pass.mesh.draw(&mut render_pass, 0..count);
+            }
+        }
     }
 }
 
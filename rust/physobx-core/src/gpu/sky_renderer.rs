@@ -1,29 +1,38 @@
 //! Sky gradient background renderer
 
 use super::context::GpuContext;
+use super::shaders::{build_checked, ShaderError, SKY_SHADER};
 use super::render_target::{OffscreenTarget, HDR_FORMAT};
 
 /// Renders a sky gradient background
 pub struct SkyRenderer {
     pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
 }
 
 impl SkyRenderer {
     pub fn new(ctx: &GpuContext) -> Self {
-        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Sky Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/fullscreen.wgsl").into()),
-        });
-
         let pipeline_layout = ctx.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Sky Pipeline Layout"),
             bind_group_layouts: &[],
             push_constant_ranges: &[],
         });
 
-        let pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let pipeline = Self::create_pipeline(ctx, &pipeline_layout, SKY_SHADER.source);
+
+        Self { pipeline, pipeline_layout }
+    }
+
+    /// Compile `source` into the render pipeline
+    fn create_pipeline(ctx: &GpuContext, layout: &wgpu::PipelineLayout, source: &str) -> wgpu::RenderPipeline {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Sky Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Sky Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: Some("vs_main"),
@@ -48,9 +57,14 @@ impl SkyRenderer {
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        Self { pipeline }
+    /// Rebuild the pipeline from new shader source, keeping the current one
+    /// if it fails to compile
+    pub fn reload_shader(&mut self, ctx: &GpuContext, source: &str) -> Result<(), ShaderError> {
+        self.pipeline = build_checked(ctx, SKY_SHADER.file, || Self::create_pipeline(ctx, &self.pipeline_layout, source))?;
+        Ok(())
     }
 
     pub fn render(&self, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget) {
@@ -68,6 +82,7 @@ impl SkyRenderer {
             timestamp_writes: None,
             occlusion_query_set: None,
         });
+        target.apply_scissor(&mut render_pass);
 
         render_pass.set_pipeline(&self.pipeline);
         render_pass.draw(0..3, 0..1); // Fullscreen triangle
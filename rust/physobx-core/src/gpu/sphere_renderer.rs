@@ -2,9 +2,10 @@
 
 use super::camera::{Camera, CameraUniform};
 use super::context::GpuContext;
-use super::render_target::{OffscreenTarget, HDR_FORMAT};
+use super::shaders::{build_checked, ShaderError, SPHERE_SHADER};
+use super::render_target::{OffscreenTarget, HDR_FORMAT, VELOCITY_FORMAT};
 use super::shadow::ShadowRenderer;
-use super::instance_renderer::ShadowUniform;
+use super::instance_renderer::{surface_of, PrevTransform, ShadowUniform};
 use bytemuck::{Pod, Zeroable};
 
 /// Vertex data for a sphere
@@ -30,7 +31,7 @@ impl SphereVertex {
     }
 }
 
-/// Instance data for spheres (position + radius + color)
+/// Instance data for spheres (position + radius + color + shading)
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct SphereInstanceData {
@@ -38,15 +39,36 @@ pub struct SphereInstanceData {
     pub radius: f32,
     pub rotation: [f32; 4], // unused but kept for consistency
     pub color: [f32; 3],
-    pub _padding: f32,
+    pub roughness: f32,
+    pub metallic: f32,
+    pub _padding: [f32; 3],
+}
+
+impl SphereInstanceData {
+    /// Instance `i` of the given arrays (missing `surfaces` get the
+    /// default shading)
+    pub(crate) fn of(positions: &[[f32; 3]], radii: &[f32], colors: &[[f32; 3]], surfaces: &[[f32; 2]], i: usize) -> Self {
+        let [roughness, metallic] = surface_of(surfaces, i);
+        Self {
+            position: positions[i],
+            radius: radii[i],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            color: colors[i],
+            roughness,
+            metallic,
+            _padding: [0.0; 3],
+        }
+    }
 }
 
 /// Sphere instance renderer using GPU instancing
 pub struct SphereRenderer {
     render_pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     instance_buffer: wgpu::Buffer,
+    prev_buffer: wgpu::Buffer,
     camera_buffer: wgpu::Buffer,
     bind_group: wgpu::BindGroup,
     // Shadow bindings
@@ -60,12 +82,6 @@ pub struct SphereRenderer {
 impl SphereRenderer {
     /// Create a new sphere renderer
     pub fn new(ctx: &GpuContext, max_instances: u32) -> Self {
-        // Create shader module
-        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Sphere Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/sphere_instance.wgsl").into()),
-        });
-
         // Create sphere geometry (UV sphere)
         let (vertices, indices) = create_sphere_geometry(16, 12);
         let index_count = indices.len() as u32;
@@ -90,6 +106,14 @@ impl SphereRenderer {
             mapped_at_creation: false,
         });
 
+        // Previous-frame transforms (motion vectors)
+        let prev_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Sphere Previous Transform Buffer"),
+            size: (max_instances as u64) * std::mem::size_of::<PrevTransform>() as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         // Camera uniform buffer
         let camera_buffer = ctx.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Sphere Camera Buffer"),
@@ -122,6 +146,16 @@ impl SphereRenderer {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -138,6 +172,10 @@ impl SphereRenderer {
                     binding: 1,
                     resource: instance_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: prev_buffer.as_entire_binding(),
+                },
             ],
         });
 
@@ -193,9 +231,35 @@ impl SphereRenderer {
         });
 
         // Render pipeline
-        let render_pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let render_pipeline = Self::create_pipeline(ctx, &pipeline_layout, SPHERE_SHADER.source);
+
+        Self {
+            render_pipeline,
+            pipeline_layout,
+            vertex_buffer,
+            index_buffer,
+            instance_buffer,
+            prev_buffer,
+            camera_buffer,
+            bind_group,
+            shadow_bind_group_layout,
+            shadow_uniform_buffer,
+            shadow_bind_group: None,
+            index_count,
+            max_instances,
+        }
+    }
+
+    /// Compile `source` into the render pipeline
+    fn create_pipeline(ctx: &GpuContext, layout: &wgpu::PipelineLayout, source: &str) -> wgpu::RenderPipeline {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Sphere Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Sphere Render Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: Some("vs_main"),
@@ -205,11 +269,18 @@ impl SphereRenderer {
             fragment: Some(wgpu::FragmentState {
                 module: &shader,
                 entry_point: Some("fs_main"),
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: HDR_FORMAT,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
+                targets: &[
+                    Some(wgpu::ColorTargetState {
+                        format: HDR_FORMAT,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                    Some(wgpu::ColorTargetState {
+                        format: VELOCITY_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    }),
+                ],
                 compilation_options: Default::default(),
             }),
             primitive: wgpu::PrimitiveState {
@@ -231,45 +302,62 @@ impl SphereRenderer {
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        Self {
-            render_pipeline,
-            vertex_buffer,
-            index_buffer,
-            instance_buffer,
-            camera_buffer,
-            bind_group,
-            shadow_bind_group_layout,
-            shadow_uniform_buffer,
-            shadow_bind_group: None,
-            index_count,
-            max_instances,
-        }
+    /// Rebuild the pipeline from new shader source, keeping the current one
+    /// if it fails to compile
+    pub fn reload_shader(&mut self, ctx: &GpuContext, source: &str) -> Result<(), ShaderError> {
+        self.render_pipeline = build_checked(ctx, SPHERE_SHADER.file, || Self::create_pipeline(ctx, &self.pipeline_layout, source))?;
+        Ok(())
     }
 
-    /// Upload sphere instance data
+    /// Upload sphere instance data (spheres without (roughness, metallic)
+    /// `surfaces` get the default shading)
     pub fn upload_instances(
         &self,
         ctx: &GpuContext,
         positions: &[[f32; 3]],
         radii: &[f32],
         colors: &[[f32; 3]],
+        surfaces: &[[f32; 2]],
     ) {
-        let instance_count = positions.len().min(self.max_instances as usize);
-        let mut instances = Vec::with_capacity(instance_count);
-
-        for i in 0..instance_count {
-            instances.push(SphereInstanceData {
-                position: positions[i],
-                radius: radii[i],
-                rotation: [0.0, 0.0, 0.0, 1.0],
-                color: colors[i],
-                _padding: 0.0,
-            });
-        }
+        self.upload_instances_at(ctx, 0, positions, radii, colors, surfaces);
+    }
 
-        ctx.queue.write_buffer(&self.instance_buffer, 0, bytemuck::cast_slice(&instances));
+    /// Upload instance data into the slots starting at `first`
+    ///
+    /// Slots before `first` keep their contents (e.g. static geometry).
+    pub fn upload_instances_at(
+        &self,
+        ctx: &GpuContext,
+        first: u32,
+        positions: &[[f32; 3]],
+        radii: &[f32],
+        colors: &[[f32; 3]],
+        surfaces: &[[f32; 2]],
+    ) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = positions.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.instance_buffer, first, instance_count, |i| {
+            SphereInstanceData::of(positions, radii, colors, surfaces, i)
+        });
+    }
+
+    /// Upload the transforms each instance had in the previous frame
+    pub fn upload_previous(&self, ctx: &GpuContext, positions: &[[f32; 3]], rotations: &[[f32; 4]]) {
+        self.upload_previous_at(ctx, 0, positions, rotations);
+    }
+
+    /// Upload previous-frame transforms into the slots starting at `first`
+    pub fn upload_previous_at(&self, ctx: &GpuContext, first: u32, positions: &[[f32; 3]], rotations: &[[f32; 4]]) {
+        let first = first.min(self.max_instances) as usize;
+        let instance_count = positions.len().min(self.max_instances as usize - first);
+        ctx.write_elements(&self.prev_buffer, first, instance_count, |i| PrevTransform {
+            position: positions[i],
+            _padding: 0.0,
+            rotation: rotations.get(i).copied().unwrap_or([0.0, 0.0, 0.0, 1.0]),
+        });
     }
 
     /// Update camera uniform
@@ -320,14 +408,24 @@ impl SphereRenderer {
 
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Sphere Render Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &target.hdr_view,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
-                    store: wgpu::StoreOp::Store,
-                },
-            })],
+            color_attachments: &[
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.hdr_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+                Some(wgpu::RenderPassColorAttachment {
+                    view: &target.velocity_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                }),
+            ],
             depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
                 view: &target.depth_view,
                 depth_ops: Some(wgpu::Operations {
@@ -339,6 +437,7 @@ impl SphereRenderer {
             timestamp_writes: None,
             occlusion_query_set: None,
         });
+        target.apply_scissor(&mut render_pass);
 
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &self.bind_group, &[]);
@@ -356,7 +455,7 @@ impl SphereRenderer {
 }
 
 /// Create UV sphere geometry (unit sphere)
-fn create_sphere_geometry(segments: u32, rings: u32) -> (Vec<SphereVertex>, Vec<u16>) {
+pub(crate) fn create_sphere_geometry(segments: u32, rings: u32) -> (Vec<SphereVertex>, Vec<u16>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
 
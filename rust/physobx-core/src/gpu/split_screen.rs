@@ -0,0 +1,53 @@
+//! Split-screen comparison frames
+//!
+//! Two states rendered from the same camera are joined at a vertical split:
+//! the left part of the image shows the first, the right part the second,
+//! with a divider between them. Used for before/after comparisons of
+//! parameter changes in generated videos.
+
+/// Layout of a split-screen frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitScreen {
+    /// Position of the split as a fraction of the image width (0 = all
+    /// second state, 1 = all first state)
+    pub split: f32,
+    /// Divider width in pixels (0 = none)
+    pub divider_width: u32,
+    /// Divider color
+    pub divider_color: [f32; 3],
+}
+
+impl Default for SplitScreen {
+    fn default() -> Self {
+        Self {
+            split: 0.5,
+            divider_width: 4,
+            divider_color: [1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl SplitScreen {
+    /// Column where the second state starts in a `width` pixel wide image
+    pub fn split_column(&self, width: u32) -> u32 {
+        (self.split.clamp(0.0, 1.0) * width as f32).round() as u32
+    }
+
+    /// Copy the right part of `right` into `left` and draw the divider
+    ///
+    /// Both are RGBA images of `width` x `height` pixels.
+    pub fn composite(&self, left: &mut [u8], right: &[u8], width: u32, height: u32) {
+        let row = width as usize * 4;
+        let split = self.split_column(width) as usize;
+        let half = self.divider_width as usize / 2;
+        let divider = split.saturating_sub(half)..(split + self.divider_width as usize - half).min(width as usize);
+        let [r, g, b] = self.divider_color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+        for y in 0..height as usize {
+            let start = y * row;
+            left[start + split * 4..start + row].copy_from_slice(&right[start + split * 4..start + row]);
+            for x in divider.clone() {
+                left[start + x * 4..start + x * 4 + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+    }
+}
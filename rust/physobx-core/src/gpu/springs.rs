@@ -0,0 +1,82 @@
+//! Spring joints drawn as chains of small spheres
+//!
+//! Each spring becomes a row of touching beads from one anchor to the other,
+//! either straight or wound into a coil that stretches with the spring. The
+//! beads go through the sphere renderer, so they are lit and shadowed like
+//! any other body and share its instance buffer.
+
+/// Most beads drawn for a single spring
+const MAX_BEADS: usize = 512;
+
+/// How spring joints are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpringDisplay {
+    /// Coil radius (m); 0 draws a straight line
+    pub coil_radius: f32,
+    /// Wire thickness (bead diameter, m)
+    pub thickness: f32,
+    /// Number of coil turns between the anchors
+    pub turns: f32,
+    pub color: [f32; 3],
+}
+
+impl Default for SpringDisplay {
+    fn default() -> Self {
+        Self {
+            coil_radius: 0.1,
+            thickness: 0.03,
+            turns: 8.0,
+            color: [0.7, 0.7, 0.75],
+        }
+    }
+}
+
+impl SpringDisplay {
+    /// Append the beads of a spring between world points `a` and `b`
+    pub fn beads_into(
+        &self,
+        a: [f32; 3],
+        b: [f32; 3],
+        positions: &mut Vec<[f32; 3]>,
+        radii: &mut Vec<f32>,
+        colors: &mut Vec<[f32; 3]>,
+    ) {
+        let axis = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        let length = (axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]).sqrt();
+        let thickness = self.thickness.max(1e-4);
+        let (coil_radius, turns) = if self.coil_radius > 0.0 && length > 1e-6 {
+            (self.coil_radius, self.turns.max(0.0))
+        } else {
+            (0.0, 0.0)
+        };
+
+        // Beads one thickness apart along the coil
+        let wire = (std::f32::consts::TAU * coil_radius * turns).hypot(length);
+        let count = ((wire / thickness).ceil() as usize + 1).clamp(2, MAX_BEADS);
+
+        let (u, v) = perpendicular_basis(axis, length);
+        for k in 0..count {
+            let t = k as f32 / (count - 1) as f32;
+            let (s, c) = (std::f32::consts::TAU * turns * t).sin_cos();
+            // The coil tapers to the axis at both ends so it meets the anchors
+            let r = coil_radius * (std::f32::consts::PI * t).sin().max(0.0).sqrt();
+            positions.push(std::array::from_fn(|i| a[i] + axis[i] * t + r * (c * u[i] + s * v[i])));
+            radii.push(0.5 * thickness);
+            colors.push(self.color);
+        }
+    }
+}
+
+/// Two unit vectors perpendicular to `axis` and to each other
+fn perpendicular_basis(axis: [f32; 3], length: f32) -> ([f32; 3], [f32; 3]) {
+    if length <= 1e-6 {
+        return ([1.0, 0.0, 0.0], [0.0, 0.0, 1.0]);
+    }
+    let n = axis.map(|c| c / length);
+    let helper = if n[0].abs() < 0.9 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+    let cross = |p: [f32; 3], q: [f32; 3]| [p[1] * q[2] - p[2] * q[1], p[2] * q[0] - p[0] * q[2], p[0] * q[1] - p[1] * q[0]];
+    let u = cross(n, helper);
+    let norm = (u[0] * u[0] + u[1] * u[1] + u[2] * u[2]).sqrt();
+    let u = u.map(|c| c / norm);
+    (u, cross(n, u))
+}
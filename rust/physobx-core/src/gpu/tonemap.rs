@@ -1,44 +1,146 @@
 //! Tonemapping post-process pass
 
+use super::camera::Camera;
 use super::context::GpuContext;
+use super::shaders::{build_checked, ShaderError, TONEMAP_SHADER};
+use super::lut::ColorLut;
+use super::color_space::{OutputColorSpace, TransferFunction};
 use super::render_target::{OffscreenTarget, LDR_FORMAT};
 use bytemuck::{Pod, Zeroable};
+use std::sync::atomic::{AtomicU32, Ordering};
 
 /// Tonemap parameters uniform
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Pod, Zeroable)]
 pub struct TonemapParams {
     pub exposure: f32,
-    pub _padding: [f32; 3],
+    /// Fraction of the frame interval the shutter is open (0.5 = 180 degrees)
+    pub motion_blur_shutter: f32,
+    /// Samples along each pixel's motion path (0 or 1 disables motion blur)
+    pub motion_blur_samples: u32,
+    pub _padding: f32,
+    /// Radial distortion coefficients (k1, k2, k3, unused)
+    pub radial: [f32; 4],
+    /// Tangential distortion coefficients (p1, p2)
+    pub tangential: [f32; 2],
+    /// Normalized image extent at unit depth: tan(fov_y / 2) * (aspect, 1)
+    pub focal_scale: [f32; 2],
+    /// Vignetting strength (0 = off, 1 = natural cos^4 falloff)
+    pub vignette: f32,
+    /// Red/blue radial scale difference at the image corners (0 = off)
+    pub chromatic_aberration: f32,
+    /// Film grain standard deviation in display units (0 = off)
+    pub grain: f32,
+    /// Changes every frame so the grain pattern does not freeze
+    pub grain_seed: u32,
+    /// Blend between the ungraded (0) and LUT-graded (1) image
+    pub lut_strength: f32,
+    /// Entries per axis of the bound LUT
+    pub lut_size: f32,
+    /// Output transfer function ([`TransferFunction::id`])
+    pub output_transfer: u32,
+    pub _padding2: f32,
+    /// Linear Rec.709 to output primaries, as padded columns
+    pub output_gamut: [[f32; 4]; 3],
+}
+
+/// Camera lens model applied to the final image
+///
+/// Distortion uses the Brown-Conrady model on normalized image coordinates
+/// (pixel offset from the principal point divided by the focal length in
+/// pixels), so coefficients from an OpenCV calibration can be used directly.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LensModel {
+    pub k1: f32,
+    pub k2: f32,
+    pub k3: f32,
+    pub p1: f32,
+    pub p2: f32,
+    /// Vignetting strength (0 = off, 1 = natural cos^4 falloff, 2 = cos^8)
+    pub vignette: f32,
+}
+
+impl LensModel {
+    /// Apply the distortion to normalized image coordinates (x right, y up)
+    pub fn distort(&self, p: [f32; 2]) -> [f32; 2] {
+        let [x, y] = p;
+        let r2 = x * x + y * y;
+        let radial = 1.0 + r2 * (self.k1 + r2 * (self.k2 + r2 * self.k3));
+        [
+            x * radial + 2.0 * self.p1 * x * y + self.p2 * (r2 + 2.0 * x * x),
+            y * radial + self.p1 * (r2 + 2.0 * y * y) + 2.0 * self.p2 * x * y,
+        ]
+    }
+
+    /// Invert [`LensModel::distort`] by fixed-point iteration (as the tonemap pass does)
+    pub fn undistort(&self, p: [f32; 2]) -> [f32; 2] {
+        let mut q = p;
+        for _ in 0..8 {
+            let d = self.distort(q);
+            q = [q[0] + p[0] - d[0], q[1] + p[1] - d[1]];
+        }
+        q
+    }
+
+    /// Whether the model bends straight lines at all
+    pub fn is_distorting(&self) -> bool {
+        [self.k1, self.k2, self.k3, self.p1, self.p2].iter().any(|&c| c != 0.0)
+    }
 }
 
 impl Default for TonemapParams {
     fn default() -> Self {
         Self {
             exposure: 1.0,
-            _padding: [0.0; 3],
+            motion_blur_shutter: 0.0,
+            motion_blur_samples: 0,
+            _padding: 0.0,
+            radial: [0.0; 4],
+            tangential: [0.0; 2],
+            focal_scale: [1.0; 2],
+            vignette: 0.0,
+            chromatic_aberration: 0.0,
+            grain: 0.0,
+            grain_seed: 0,
+            lut_strength: 0.0,
+            lut_size: 2.0,
+            output_transfer: TransferFunction::Srgb.id(),
+            _padding2: 0.0,
+            output_gamut: [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0]],
         }
     }
 }
 
+/// Film-look stylization applied in the tonemap pass
+#[derive(Debug, Clone, Default)]
+pub struct PostEffects {
+    /// Red/blue fringe strength: radial scale difference at the corners
+    /// (0.005 is subtle, 0.02 is strong)
+    pub chromatic_aberration: f32,
+    /// Film grain standard deviation in display units (0.02 is subtle)
+    pub grain: f32,
+    /// Color grading LUT applied to the display-referred image
+    pub lut: Option<ColorLut>,
+    /// Blend between the ungraded (0) and graded (1) image
+    pub lut_strength: f32,
+}
+
 /// Tonemapping renderer
 pub struct TonemapRenderer {
     render_pipeline: wgpu::RenderPipeline,
+    pipeline_layout: wgpu::PipelineLayout,
     bind_group_layout: wgpu::BindGroupLayout,
     sampler: wgpu::Sampler,
     params_buffer: wgpu::Buffer,
     params: TonemapParams,
+    lut_view: wgpu::TextureView,
+    /// Frames rendered so far (reseeds the film grain)
+    frame: AtomicU32,
 }
 
 impl TonemapRenderer {
     /// Create a new tonemap renderer
     pub fn new(ctx: &GpuContext) -> Self {
-        // Create shader module
-        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Tonemap Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/tonemap.wgsl").into()),
-        });
-
         // Create sampler
         let sampler = ctx.device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("Tonemap Sampler"),
@@ -93,6 +195,28 @@ impl TonemapRenderer {
                     },
                     count: None,
                 },
+                // Velocity texture (motion blur)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // Color grading LUT (sampled with the HDR sampler)
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D3,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         });
 
@@ -104,9 +228,32 @@ impl TonemapRenderer {
         });
 
         // Render pipeline
-        let render_pipeline = ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let render_pipeline = Self::create_pipeline(ctx, &pipeline_layout, TONEMAP_SHADER.source);
+
+        let lut_view = create_lut_view(ctx, &ColorLut::identity(2));
+
+        Self {
+            render_pipeline,
+            pipeline_layout,
+            bind_group_layout,
+            sampler,
+            params_buffer,
+            params,
+            lut_view,
+            frame: AtomicU32::new(0),
+        }
+    }
+
+    /// Compile `source` into the render pipeline
+    fn create_pipeline(ctx: &GpuContext, layout: &wgpu::PipelineLayout, source: &str) -> wgpu::RenderPipeline {
+        let shader = ctx.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Tonemap Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+
+        ctx.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Tonemap Pipeline"),
-            layout: Some(&pipeline_layout),
+            layout: Some(layout),
             vertex: wgpu::VertexState {
                 module: &shader,
                 entry_point: Some("vs_main"),
@@ -131,15 +278,14 @@ impl TonemapRenderer {
             multisample: wgpu::MultisampleState::default(),
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        Self {
-            render_pipeline,
-            bind_group_layout,
-            sampler,
-            params_buffer,
-            params,
-        }
+    /// Rebuild the pipeline from new shader source, keeping the current one
+    /// if it fails to compile
+    pub fn reload_shader(&mut self, ctx: &GpuContext, source: &str) -> Result<(), ShaderError> {
+        self.render_pipeline = build_checked(ctx, TONEMAP_SHADER.file, || Self::create_pipeline(ctx, &self.pipeline_layout, source))?;
+        Ok(())
     }
 
     /// Set exposure value
@@ -147,10 +293,71 @@ impl TonemapRenderer {
         self.params.exposure = exposure;
     }
 
+    /// Configure velocity-buffer motion blur (`samples` <= 1 disables it)
+    pub fn set_motion_blur(&mut self, shutter: f32, samples: u32) {
+        self.params.motion_blur_shutter = shutter.clamp(0.0, 1.0);
+        self.params.motion_blur_samples = samples;
+    }
+
+    /// Whether motion blur is applied
+    pub fn motion_blur_enabled(&self) -> bool {
+        self.params.motion_blur_samples > 1 && self.params.motion_blur_shutter > 0.0
+    }
+
+    /// Current tonemap and post-effect parameters
+    pub fn params(&self) -> &TonemapParams {
+        &self.params
+    }
+
+    /// Current lens model
+    pub fn lens(&self) -> LensModel {
+        let p = &self.params;
+        LensModel {
+            k1: p.radial[0],
+            k2: p.radial[1],
+            k3: p.radial[2],
+            p1: p.tangential[0],
+            p2: p.tangential[1],
+            vignette: p.vignette,
+        }
+    }
+
+    /// Set lens distortion and vignetting
+    pub fn set_lens(&mut self, lens: &LensModel) {
+        self.params.radial = [lens.k1, lens.k2, lens.k3, 0.0];
+        self.params.tangential = [lens.p1, lens.p2];
+        self.params.vignette = lens.vignette.max(0.0);
+    }
+
+    /// Set chromatic aberration, film grain and color grading
+    pub fn set_post_effects(&mut self, ctx: &GpuContext, effects: &PostEffects) {
+        self.params.chromatic_aberration = effects.chromatic_aberration;
+        self.params.grain = effects.grain.max(0.0);
+        match &effects.lut {
+            Some(lut) => {
+                self.lut_view = create_lut_view(ctx, lut);
+                self.params.lut_size = lut.size as f32;
+                self.params.lut_strength = effects.lut_strength.clamp(0.0, 1.0);
+            }
+            None => self.params.lut_strength = 0.0,
+        }
+    }
+
+    /// Set the primaries and transfer function of the output image
+    pub fn set_output_color_space(&mut self, space: &OutputColorSpace) {
+        let m = space.gamut_matrix();
+        self.params.output_transfer = space.transfer.id();
+        self.params.output_gamut = [0, 1, 2].map(|c| [m[0][c], m[1][c], m[2][c], 0.0]);
+    }
+
     /// Render tonemap pass (HDR -> LDR)
-    pub fn render(&self, ctx: &GpuContext, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget) {
-        // Update params buffer
-        ctx.queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[self.params]));
+    pub fn render(&self, ctx: &GpuContext, encoder: &mut wgpu::CommandEncoder, target: &OffscreenTarget, camera: &Camera) {
+        // Update params buffer (lens coordinates follow the current field of view)
+        let mut params = self.params;
+        let tan_half_fov = (camera.fov_y * 0.5).tan();
+        params.focal_scale = [tan_half_fov * camera.aspect, tan_half_fov];
+        params.grain_seed = self.frame.fetch_add(1, Ordering::Relaxed);
+        ctx.queue.write_buffer(&self.params_buffer, 0, bytemuck::cast_slice(&[params]));
 
         // Create bind group with current HDR texture
         let bind_group = ctx.device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -169,6 +376,14 @@ impl TonemapRenderer {
                     binding: 2,
                     resource: self.params_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&target.velocity_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&self.lut_view),
+                },
             ],
         });
 
@@ -187,9 +402,47 @@ impl TonemapRenderer {
             timestamp_writes: None,
             occlusion_query_set: None,
         });
+        target.apply_scissor(&mut render_pass);
 
         render_pass.set_pipeline(&self.render_pipeline);
         render_pass.set_bind_group(0, &bind_group, &[]);
         render_pass.draw(0..3, 0..1);  // Fullscreen triangle
     }
 }
+
+/// Upload a LUT as an Rgba8Unorm 3D texture
+fn create_lut_view(ctx: &GpuContext, lut: &ColorLut) -> wgpu::TextureView {
+    let size = wgpu::Extent3d {
+        width: lut.size,
+        height: lut.size,
+        depth_or_array_layers: lut.size,
+    };
+    let texture = ctx.device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Color LUT"),
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D3,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+
+    ctx.queue.write_texture(
+        wgpu::ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        &lut.to_rgba8(),
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(lut.size * 4),
+            rows_per_image: Some(lut.size),
+        },
+        size,
+    );
+
+    texture.create_view(&wgpu::TextureViewDescriptor::default())
+}
@@ -0,0 +1,125 @@
+//! Finite-difference gradients of a loss over the final simulation state
+//!
+//! Each chosen scene parameter is nudged up and down, the scene is rerun from
+//! scratch, and the change in loss gives a central-difference derivative.
+//! Enough for simple trajectory optimization without a differentiable engine.
+
+use crate::scene::{MassMode, SceneBuilder};
+use crate::simulator::Simulator;
+
+/// A scalar scene parameter that can be perturbed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneParameter {
+    /// Initial linear velocity component (axis 0..3 = x, y, z) of a body
+    Velocity { body: usize, axis: usize },
+    /// Initial angular velocity component (axis 0..3 = x, y, z) of a body
+    AngularVelocity { body: usize, axis: usize },
+    /// Initial position component (axis 0..3 = x, y, z) of a body
+    Position { body: usize, axis: usize },
+    /// Mass of a body, as it is built (see
+    /// [`RigidBodyConfig::resolved_mass`](crate::scene::builder::RigidBodyConfig::resolved_mass))
+    ///
+    /// When a material or [`MassMode::Density`] decides the mass, setting it
+    /// scales that density instead, so the inertia follows the shape.
+    Mass { body: usize },
+}
+
+impl SceneParameter {
+    /// Current value in a scene (None if the body or axis does not exist)
+    pub fn get(&self, scene: &SceneBuilder) -> Option<f32> {
+        match *self {
+            SceneParameter::Velocity { body, axis } => scene.bodies.get(body)?.velocity.get(axis).copied(),
+            SceneParameter::AngularVelocity { body, axis } => scene.bodies.get(body)?.angular_velocity.get(axis).copied(),
+            SceneParameter::Position { body, axis } => scene.bodies.get(body)?.position.get(axis).copied(),
+            SceneParameter::Mass { body } => scene.bodies.get(body).map(|b| b.resolved_mass(scene.physics.length_scale)),
+        }
+    }
+
+    /// Set the value in a scene; returns false if the body or axis does not exist
+    pub fn set(&self, scene: &mut SceneBuilder, value: f32) -> bool {
+        let slot = match *self {
+            SceneParameter::Velocity { body, axis } => scene.bodies.get_mut(body).and_then(|b| b.velocity.get_mut(axis)),
+            SceneParameter::AngularVelocity { body, axis } => scene.bodies.get_mut(body).and_then(|b| b.angular_velocity.get_mut(axis)),
+            SceneParameter::Position { body, axis } => scene.bodies.get_mut(body).and_then(|b| b.position.get_mut(axis)),
+            SceneParameter::Mass { body } => return set_mass(scene, body, value),
+        };
+        match slot {
+            Some(slot) => {
+                *slot = value;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Give body `body` the built mass `value`, through its material's or mass
+/// mode's density when one decides the mass; false if the body does not exist
+fn set_mass(scene: &mut SceneBuilder, body: usize, value: f32) -> bool {
+    let length_scale = scene.physics.length_scale;
+    let Some(b) = scene.bodies.get_mut(body) else {
+        return false;
+    };
+    let density = value / (b.total_volume() * length_scale.powi(3));
+    match (&mut b.material, b.mass_mode) {
+        (Some(material), _) => material.density = density,
+        (None, MassMode::Density(_)) => b.mass_mode = MassMode::Density(density),
+        (None, MassMode::Mass) => b.mass = value,
+    }
+    true
+}
+
+/// Loss at the unperturbed parameters and its gradient
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Gradient {
+    /// Loss of the base scene
+    pub loss: f32,
+    /// d loss / d parameter, in the order the parameters were given
+    pub gradient: Vec<f32>,
+}
+
+/// Simulate a scene for `steps` steps of `dt` from scratch
+pub fn rollout(scene: &SceneBuilder, steps: u32, dt: f32) -> Simulator {
+    let mut sim = Simulator::new(scene);
+    for _ in 0..steps {
+        sim.step(dt);
+    }
+    sim
+}
+
+/// Central-difference gradient of `loss(final state)` with respect to `params`
+///
+/// Runs `2 * params.len() + 1` rollouts. `epsilon` is the absolute
+/// perturbation; parameters that do not exist in the scene get a zero
+/// gradient. The loss may fail (e.g. a Python exception), which aborts the
+/// computation. Results are only meaningful if the simulation is
+/// deterministic for identical scenes.
+pub fn finite_difference_gradient<E>(
+    base: &SceneBuilder,
+    params: &[SceneParameter],
+    steps: u32,
+    dt: f32,
+    epsilon: f32,
+    mut loss: impl FnMut(&Simulator) -> Result<f32, E>,
+) -> Result<Gradient, E> {
+    let base_loss = loss(&rollout(base, steps, dt))?;
+
+    let mut gradient = Vec::with_capacity(params.len());
+    for param in params {
+        let Some(value) = param.get(base) else {
+            gradient.push(0.0);
+            continue;
+        };
+
+        let mut plus = base.clone();
+        param.set(&mut plus, value + epsilon);
+        let mut minus = base.clone();
+        param.set(&mut minus, value - epsilon);
+
+        let loss_plus = loss(&rollout(&plus, steps, dt))?;
+        let loss_minus = loss(&rollout(&minus, steps, dt))?;
+        gradient.push((loss_plus - loss_minus) / (2.0 * epsilon));
+    }
+
+    Ok(Gradient { loss: base_loss, gradient })
+}
@@ -5,15 +5,29 @@
 //! - Rapier physics integration
 //! - wgpu-based GPU rendering with Metal backend
 //! - Headless offscreen rendering
+//! - Simulated sensors (IMU, force-torque)
 
 pub mod physics;
 pub mod scene;
 pub mod gpu;
 pub mod simulator;
+pub mod sensors;
+pub mod time_ramp;
+pub mod analysis;
+pub mod sweep;
+pub mod gradient;
+pub mod sysid;
+pub mod spatial;
+pub mod contact_graph;
+pub mod stream;
+pub mod shared_state;
+pub mod simd;
 
-pub use physics::{RigidBodyStorage, RapierBridge};
+pub use physics::{RigidBodyStorage, RapierBridge, PhysicsSettings};
 pub use scene::SceneBuilder;
-pub use simulator::Simulator;
+pub use simulator::{SceneUpdate, Simulator};
+pub use time_ramp::TimeRamp;
+pub use contact_graph::ContactGraph;
 pub use gpu::{GpuContext, GpuError, OffscreenTarget, Camera, InstanceRenderer};
 
 /// Library version
@@ -0,0 +1,186 @@
+//! Aerodynamic drag and lift against a wind field
+//!
+//! A quasi-steady model, cheap enough to evaluate for every body every step.
+//! Bodies with [`AeroCoefficients`] feel the relative air velocity `u` (the
+//! [`WindField`] at their center minus their own velocity):
+//!
+//! - drag `½ ρ C_d A |u| u`, with `A` the area the body presents to the flow
+//!   (the projected area of the box, or π r² for spheres)
+//! - lift across the flow: for boxes `½ ρ C_l A_face |u|² sin 2α` from the
+//!   angle of attack `α` of the broadest face, for spheres the Magnus force
+//!   `½ ρ C_l A r u × ω` of their spin
+//! - for flat boxes, both act at the center of pressure a quarter chord
+//!   upwind of the center, which swings plates broadside to the flow, and
+//!   spinning boxes are damped by the drag on their faces. Together this
+//!   makes falling planks flutter and tumble.
+
+use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+use std::f32::consts::PI;
+use crate::scene::builder::ShapeType;
+
+/// Aerodynamic coefficients of a body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AeroCoefficients {
+    /// Drag coefficient (about 0.47 for spheres, 1.05 for cubes, 1.2 for plates)
+    pub drag: f32,
+    /// Lift coefficient: about 1 for plates; for spheres the Magnus
+    /// coefficient, about 0.5
+    pub lift: f32,
+    /// Reference area for drag (m²); None uses the area presented to the flow
+    pub area: Option<f32>,
+}
+
+impl AeroCoefficients {
+    /// Typical coefficients of a shape: drag only, no lift
+    pub fn for_shape(shape: ShapeType) -> Self {
+        let drag = match shape {
+            ShapeType::Cube => 1.05,
+            ShapeType::Sphere => 0.47,
+            ShapeType::Capsule => 0.6,
+            // Side-on, the way a rolling cylinder meets the air
+            ShapeType::Cylinder => 0.82,
+            ShapeType::Cone => 0.5,
+        };
+        Self { drag, lift: 0.0, area: None }
+    }
+}
+
+/// Air velocity over the scene
+///
+/// The wind blows at `velocity` at the reference height above the ground
+/// and follows a power law with height (zero at and below the ground).
+/// Gusts are bands of faster and slower air that travel with the wind.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindField {
+    /// Wind velocity at the reference height (m/s)
+    pub velocity: [f32; 3],
+    /// Air density (kg/m³)
+    pub air_density: f32,
+    /// Exponent of the wind's growth with height; 0 is uniform, about 0.14
+    /// over open ground
+    pub shear_exponent: f32,
+    /// Height above the ground at which the wind blows at `velocity` (m)
+    pub reference_height: f32,
+    /// Gust speed as a fraction of the wind speed (0 = steady)
+    pub gust_strength: f32,
+    /// Distance between gusts (m)
+    pub gust_wavelength: f32,
+}
+
+impl Default for WindField {
+    fn default() -> Self {
+        Self {
+            velocity: [0.0; 3],
+            air_density: 1.225,
+            shear_exponent: 0.0,
+            reference_height: 10.0,
+            gust_strength: 0.0,
+            gust_wavelength: 20.0,
+        }
+    }
+}
+
+impl WindField {
+    /// Wind velocity at `position` and `time`, over ground at height `ground_y`
+    pub fn velocity_at(&self, position: [f32; 3], ground_y: f32, time: f32) -> [f32; 3] {
+        let base = Vector3::from(self.velocity);
+        let speed = base.norm();
+        if speed < 1e-9 {
+            return [0.0; 3];
+        }
+        let mut scale = 1.0;
+        if self.shear_exponent != 0.0 {
+            let height = position[1] - ground_y;
+            if height <= 0.0 {
+                return [0.0; 3];
+            }
+            scale = (height / self.reference_height.max(1e-3)).powf(self.shear_exponent);
+        }
+        if self.gust_strength != 0.0 {
+            let p = Vector3::from(position);
+            let along = base / speed;
+            let across = along.cross(&Vector3::y());
+            let k = 2.0 * PI / self.gust_wavelength.max(1e-3);
+            // Fronts sweep downwind at the wind speed and vary along their length
+            let gust = (k * (p.dot(&along) - speed * time)).sin() * (0.5 * k * p.dot(&across)).cos();
+            scale *= 1.0 + self.gust_strength * gust;
+        }
+        (base * scale).into()
+    }
+}
+
+/// Pose, velocity and size of a body as seen by the air
+#[derive(Debug, Clone, Copy)]
+pub struct AeroBody {
+    pub shape: ShapeType,
+    /// Half extents of a box (or of the box bounding an axial shape), or the
+    /// radius (in every slot) of a sphere
+    pub half_extents: [f32; 3],
+    /// Orientation quaternion (x, y, z, w)
+    pub rotation: [f32; 4],
+    pub linear_velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+}
+
+/// Aerodynamic force and torque (about the body's center) on a body in
+/// air moving at `wind`
+pub fn aero_wrench(coefficients: &AeroCoefficients, body: &AeroBody, wind: [f32; 3], air_density: f32) -> ([f32; 3], [f32; 3]) {
+    let u = Vector3::from(wind) - Vector3::from(body.linear_velocity);
+    let omega = Vector3::from(body.angular_velocity);
+    let q = 0.5 * air_density;
+    let speed = u.norm();
+
+    match body.shape {
+        ShapeType::Sphere => {
+            let r = body.half_extents[0];
+            let area = PI * r * r;
+            let drag = u * (q * coefficients.drag * coefficients.area.unwrap_or(area) * speed);
+            let magnus = u.cross(&omega) * (q * coefficients.lift * area * r);
+            ((drag + magnus).into(), [0.0; 3])
+        }
+        ShapeType::Cube | ShapeType::Capsule | ShapeType::Cylinder | ShapeType::Cone => {
+            let [x, y, z, w] = body.rotation;
+            let rotation = UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z));
+            let h = Vector3::from(body.half_extents);
+
+            // Thinnest local axis is the plate normal; the other two span its face
+            let thin = h.imin();
+            let (a, b) = ((thin + 1) % 3, (thin + 2) % 3);
+            let face_area = 4.0 * h[a] * h[b];
+            let normal = rotation * Vector3::ith(thin, 1.0);
+            let reach = h.max();
+            let spin_damping = omega * (-q * coefficients.drag * face_area * reach.powi(3) * omega.norm() / 4.0);
+            if speed < 1e-6 {
+                return ([0.0; 3], spin_damping.into());
+            }
+
+            let dir = u / speed;
+            let local = rotation.inverse_transform_vector(&dir);
+            let projected = 4.0 * (h.y * h.z * local.x.abs() + h.x * h.z * local.y.abs() + h.x * h.y * local.z.abs());
+            let drag = u * (q * coefficients.drag * coefficients.area.unwrap_or(projected) * speed);
+            // |2 c (n - c d)| = sin 2α, pointing away from the windward face
+            let c = dir.dot(&normal);
+            let lift = (normal - dir * c) * (2.0 * c * q * coefficients.lift * face_area * speed * speed);
+            let force = drag + lift;
+
+            // Center of pressure a quarter chord toward the leading edge,
+            // fading out as the box becomes a cube
+            let upwind = -(dir - normal * c);
+            let mut torque = spin_damping;
+            let flatness = 1.0 - thin_ratio(&h, thin);
+            if upwind.norm() > 1e-6 && flatness > 0.0 {
+                let edge = upwind.normalize();
+                let edge_local = rotation.inverse_transform_vector(&edge);
+                let half_chord = edge_local[a].abs() * h[a] + edge_local[b].abs() * h[b];
+                torque += (edge * (0.5 * half_chord * flatness)).cross(&force);
+            }
+            (force.into(), torque.into())
+        }
+    }
+}
+
+/// Thinnest half extent over the middle one (1 for a cube, near 0 for a plate)
+fn thin_ratio(h: &Vector3<f32>, thin: usize) -> f32 {
+    let middle = h[(thin + 1) % 3].min(h[(thin + 2) % 3]);
+    if middle > 0.0 { (h[thin] / middle).min(1.0) } else { 1.0 }
+}
@@ -0,0 +1,370 @@
+//! Physics backend abstraction
+//!
+//! [`Simulator`](crate::Simulator) drives its world only through
+//! [`PhysicsBackend`], so the engine behind it can be swapped without
+//! changing simulator users. [`RapierBridge`] is the default and supports
+//! everything. Another backend (GPU spheres, custom PBD, double precision
+//! Rapier) must build a world from a scene, step it and write the bodies
+//! back to storage; the remaining methods are optional features whose
+//! defaults report them as unsupported (None, false or nothing).
+
+use super::events::{BodyContact, ImpactEvent};
+use super::lod::SimulationLod;
+use super::pbd::PbdBackend;
+use super::rapier_bridge::{RapierBridge, RayHit, TimeOfImpact};
+use super::storage::RigidBodyStorage;
+use crate::scene::builder::SceneBuilder;
+use crate::scene::report::SceneIssue;
+
+/// A physics engine simulating a scene's bodies
+pub trait PhysicsBackend: Send + Sync {
+    /// Short name of the backend
+    fn name(&self) -> &'static str;
+
+    /// The backend as a Rapier world, for Rapier-only features; None for
+    /// other backends
+    fn as_rapier(&self) -> Option<&RapierBridge> {
+        None
+    }
+
+    /// Mutable form of [`PhysicsBackend::as_rapier`]
+    fn as_rapier_mut(&mut self) -> Option<&mut RapierBridge> {
+        None
+    }
+
+    /// Replace the world with the scene's bodies, joints, ground and static
+    /// planes, filling `storage` with the bodies' initial state
+    fn build_from_scene(&mut self, scene: &SceneBuilder, storage: &mut RigidBodyStorage);
+
+    /// Advance the world by `dt` seconds
+    fn step(&mut self, dt: f32);
+
+    /// Write the state of moving bodies back to `storage`
+    fn sync_to_storage(&self, storage: &mut RigidBodyStorage);
+
+    /// Gravity vector
+    fn gravity(&self) -> [f32; 3];
+
+    /// Number of bodies in the world
+    fn body_count(&self) -> usize;
+
+    /// Re-create the bodies listed in `changed` from `scene`, drop bodies past
+    /// its end and add new ones, keeping the others' state
+    ///
+    /// Returns false if the backend cannot update in place; the caller then
+    /// rebuilds the whole world.
+    fn update_bodies(&mut self, _scene: &SceneBuilder, _changed: &[usize], _storage: &mut RigidBodyStorage) -> bool {
+        false
+    }
+
+    /// Append the impacts of the last step to `out` (times left at 0.0)
+    fn take_impacts_into(&self, _out: &mut Vec<ImpactEvent>) {}
+
+    /// Append every pair of bodies, and of a body and the ground, touching
+    /// after the last step to `out`
+    fn contacts_into(&self, _out: &mut Vec<BodyContact>) {}
+
+    /// Mass, linear momentum and angular momentum (about the center of mass)
+    /// of a body, world-space
+    fn momentum(&self, _index: usize) -> Option<(f32, [f32; 3], [f32; 3])> {
+        None
+    }
+
+    /// Closest body or ground hit along a ray
+    fn cast_ray(&self, _origin: [f32; 3], _direction: [f32; 3], _max_distance: f32) -> Option<RayHit> {
+        None
+    }
+
+    /// Predicted first contact of two bodies within `max_t` seconds
+    fn time_of_impact(&self, _a: usize, _b: usize, _max_t: f32) -> Option<TimeOfImpact> {
+        None
+    }
+
+    /// Next new contact of a body with anything within `max_t` seconds
+    fn predict_impact(&self, _index: usize, _max_t: f32) -> Option<TimeOfImpact> {
+        None
+    }
+
+    /// Pull a body's `grab_point` toward a target; returns the joint id
+    fn add_mouse_joint(&mut self, _index: usize, _grab_point: [f32; 3], _stiffness: f32, _damping: f32) -> Option<usize> {
+        None
+    }
+
+    /// Move the target of a mouse joint
+    fn set_mouse_joint_target(&mut self, _id: usize, _target: [f32; 3]) -> bool {
+        false
+    }
+
+    /// Remove a mouse joint
+    fn remove_mouse_joint(&mut self, _id: usize) -> bool {
+        false
+    }
+
+    /// Rigidly attach body `b` to body `a`; returns the attachment id
+    fn attach(&mut self, _a: usize, _b: usize, _relative_pose: Option<([f32; 3], [f32; 4])>) -> Option<usize> {
+        None
+    }
+
+    /// Remove an attachment
+    fn detach(&mut self, _id: usize) -> bool {
+        false
+    }
+
+    /// Drive a wheel joint's spin toward `speed` (rad/s)
+    fn set_wheel_drive(&mut self, _joint: usize, _speed: f32, _max_torque: f32) -> bool {
+        false
+    }
+
+    /// Turn a wheel joint's axle by `angle` (rad)
+    fn set_wheel_steering(&mut self, _joint: usize, _angle: f32) -> bool {
+        false
+    }
+
+    /// World-space end points of every spring joint, in joint order
+    fn spring_endpoints(&self) -> Vec<([f32; 3], [f32; 3])> {
+        Vec::new()
+    }
+
+    /// Add a linear impulse through the center of mass and an angular
+    /// impulse to a dynamic body
+    fn apply_impulse(&mut self, _index: usize, _linear: [f32; 3], _angular: [f32; 3]) -> bool {
+        false
+    }
+
+    /// Lock or unlock movement of a body along world axes
+    fn lock_translations(&mut self, _index: usize, _axes: [bool; 3]) -> bool {
+        false
+    }
+
+    /// Lock or unlock rotation of a body about world axes
+    fn lock_rotations(&mut self, _index: usize, _axes: [bool; 3]) -> bool {
+        false
+    }
+
+    /// Constraint island of every body (None for static bodies)
+    fn island_ids(&self) -> Vec<Option<usize>> {
+        vec![None; self.body_count()]
+    }
+
+    /// Whether a body is asleep
+    fn is_sleeping(&self, _index: usize) -> bool {
+        false
+    }
+
+    /// Turn a dynamic body into a fixed one until unfrozen
+    fn freeze(&mut self, _index: usize) -> bool {
+        false
+    }
+
+    /// Make a frozen body dynamic again
+    fn unfreeze(&mut self, _index: usize) -> bool {
+        false
+    }
+
+    /// Whether a body is frozen
+    fn is_frozen(&self, _index: usize) -> bool {
+        false
+    }
+
+    /// Simplify bodies far from a focus point, or stop with None; returns
+    /// false if unsupported
+    fn set_lod(&mut self, _lod: Option<SimulationLod>) -> bool {
+        false
+    }
+
+    /// Current level of simulation
+    fn lod(&self) -> Option<SimulationLod> {
+        None
+    }
+
+    /// Whether a body is simplified by the level of simulation
+    fn is_coarse(&self, _index: usize) -> bool {
+        false
+    }
+
+    /// Problems with the scene's conditioning found while building it
+    fn build_warnings(&self) -> &[SceneIssue] {
+        &[]
+    }
+}
+
+/// Physics backends available by name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PhysicsBackendKind {
+    /// Rapier in single precision ([`RapierBridge`])
+    #[default]
+    Rapier,
+    /// Position-based granular spheres ([`PbdBackend`])
+    Pbd,
+}
+
+impl PhysicsBackendKind {
+    /// Parse a backend name ("rapier", "pbd")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "rapier" => Some(PhysicsBackendKind::Rapier),
+            "pbd" | "granular" => Some(PhysicsBackendKind::Pbd),
+            _ => None,
+        }
+    }
+
+    /// Short name of the backend
+    pub fn name(self) -> &'static str {
+        match self {
+            PhysicsBackendKind::Rapier => "rapier",
+            PhysicsBackendKind::Pbd => "pbd",
+        }
+    }
+
+    /// Create an empty world of this backend
+    pub fn create(self) -> Box<dyn PhysicsBackend> {
+        match self {
+            PhysicsBackendKind::Rapier => Box::new(RapierBridge::new()),
+            PhysicsBackendKind::Pbd => Box::new(PbdBackend::default()),
+        }
+    }
+}
+
+impl PhysicsBackend for RapierBridge {
+    fn name(&self) -> &'static str {
+        PhysicsBackendKind::Rapier.name()
+    }
+
+    fn as_rapier(&self) -> Option<&RapierBridge> {
+        Some(self)
+    }
+
+    fn as_rapier_mut(&mut self) -> Option<&mut RapierBridge> {
+        Some(self)
+    }
+
+    fn build_from_scene(&mut self, scene: &SceneBuilder, storage: &mut RigidBodyStorage) {
+        RapierBridge::build_from_scene(self, scene, storage);
+    }
+
+    fn step(&mut self, dt: f32) {
+        RapierBridge::step(self, dt);
+    }
+
+    fn sync_to_storage(&self, storage: &mut RigidBodyStorage) {
+        RapierBridge::sync_to_storage(self, storage);
+    }
+
+    fn gravity(&self) -> [f32; 3] {
+        RapierBridge::gravity(self)
+    }
+
+    fn body_count(&self) -> usize {
+        RapierBridge::body_count(self)
+    }
+
+    fn update_bodies(&mut self, scene: &SceneBuilder, changed: &[usize], storage: &mut RigidBodyStorage) -> bool {
+        RapierBridge::update_bodies(self, scene, changed, storage);
+        true
+    }
+
+    fn take_impacts_into(&self, out: &mut Vec<ImpactEvent>) {
+        RapierBridge::take_impacts_into(self, out);
+    }
+
+    fn contacts_into(&self, out: &mut Vec<BodyContact>) {
+        RapierBridge::contacts_into(self, out);
+    }
+
+    fn momentum(&self, index: usize) -> Option<(f32, [f32; 3], [f32; 3])> {
+        RapierBridge::momentum(self, index)
+    }
+
+    fn cast_ray(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32) -> Option<RayHit> {
+        RapierBridge::cast_ray(self, origin, direction, max_distance)
+    }
+
+    fn time_of_impact(&self, a: usize, b: usize, max_t: f32) -> Option<TimeOfImpact> {
+        RapierBridge::time_of_impact(self, a, b, max_t)
+    }
+
+    fn predict_impact(&self, index: usize, max_t: f32) -> Option<TimeOfImpact> {
+        RapierBridge::predict_impact(self, index, max_t)
+    }
+
+    fn add_mouse_joint(&mut self, index: usize, grab_point: [f32; 3], stiffness: f32, damping: f32) -> Option<usize> {
+        RapierBridge::add_mouse_joint(self, index, grab_point, stiffness, damping)
+    }
+
+    fn set_mouse_joint_target(&mut self, id: usize, target: [f32; 3]) -> bool {
+        RapierBridge::set_mouse_joint_target(self, id, target)
+    }
+
+    fn remove_mouse_joint(&mut self, id: usize) -> bool {
+        RapierBridge::remove_mouse_joint(self, id)
+    }
+
+    fn attach(&mut self, a: usize, b: usize, relative_pose: Option<([f32; 3], [f32; 4])>) -> Option<usize> {
+        RapierBridge::attach(self, a, b, relative_pose)
+    }
+
+    fn detach(&mut self, id: usize) -> bool {
+        RapierBridge::detach(self, id)
+    }
+
+    fn set_wheel_drive(&mut self, joint: usize, speed: f32, max_torque: f32) -> bool {
+        RapierBridge::set_wheel_drive(self, joint, speed, max_torque)
+    }
+
+    fn set_wheel_steering(&mut self, joint: usize, angle: f32) -> bool {
+        RapierBridge::set_wheel_steering(self, joint, angle)
+    }
+
+    fn spring_endpoints(&self) -> Vec<([f32; 3], [f32; 3])> {
+        RapierBridge::spring_endpoints(self).collect()
+    }
+
+    fn apply_impulse(&mut self, index: usize, linear: [f32; 3], angular: [f32; 3]) -> bool {
+        RapierBridge::apply_impulse(self, index, linear, angular)
+    }
+
+    fn lock_translations(&mut self, index: usize, axes: [bool; 3]) -> bool {
+        RapierBridge::lock_translations(self, index, axes)
+    }
+
+    fn lock_rotations(&mut self, index: usize, axes: [bool; 3]) -> bool {
+        RapierBridge::lock_rotations(self, index, axes)
+    }
+
+    fn island_ids(&self) -> Vec<Option<usize>> {
+        RapierBridge::island_ids(self)
+    }
+
+    fn is_sleeping(&self, index: usize) -> bool {
+        RapierBridge::is_sleeping(self, index)
+    }
+
+    fn freeze(&mut self, index: usize) -> bool {
+        RapierBridge::freeze(self, index)
+    }
+
+    fn unfreeze(&mut self, index: usize) -> bool {
+        RapierBridge::unfreeze(self, index)
+    }
+
+    fn is_frozen(&self, index: usize) -> bool {
+        RapierBridge::is_frozen(self, index)
+    }
+
+    fn set_lod(&mut self, lod: Option<SimulationLod>) -> bool {
+        RapierBridge::set_lod(self, lod);
+        true
+    }
+
+    fn lod(&self) -> Option<SimulationLod> {
+        RapierBridge::lod(self)
+    }
+
+    fn is_coarse(&self, index: usize) -> bool {
+        RapierBridge::is_coarse(self, index)
+    }
+
+    fn build_warnings(&self) -> &[SceneIssue] {
+        RapierBridge::build_warnings(self)
+    }
+}
@@ -0,0 +1,184 @@
+//! Broad-phase selection: Rapier's sweep-and-prune or a BVH over fattened boxes
+//!
+//! Sweep-and-prune sorts box endpoints along each axis, which degrades when
+//! many bodies share the same projection (very flat, wide granular beds put
+//! thousands of boxes on one y interval). The BVH alternative stores each
+//! collider's box enlarged by a margin and only re-queries colliders that
+//! leave their enlarged box, so slow settling piles do almost no work.
+
+use std::collections::HashSet;
+use rapier3d::geometry::{Aabb, BroadPhase, BroadPhasePairEvent, ColliderHandle, ColliderPair, ColliderSet};
+use rapier3d::dynamics::RigidBodySet;
+use rapier3d::math::Real;
+use rapier3d::parry::bounding_volume::BoundingVolume;
+use rapier3d::parry::partitioning::{IndexedData, Qbvh};
+
+/// Broad-phase algorithm used to find candidate collision pairs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BroadPhaseKind {
+    /// Rapier's multi-grid sweep-and-prune
+    #[default]
+    SweepAndPrune,
+    /// Bounding volume hierarchy over margin-enlarged boxes ([`BvhBroadPhase`])
+    Bvh,
+}
+
+impl BroadPhaseKind {
+    /// Parse an algorithm name ("sap", "bvh")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sap" | "sweep_and_prune" => Some(BroadPhaseKind::SweepAndPrune),
+            "bvh" | "qbvh" => Some(BroadPhaseKind::Bvh),
+            _ => None,
+        }
+    }
+
+    /// Short name of the algorithm
+    pub fn name(self) -> &'static str {
+        match self {
+            BroadPhaseKind::SweepAndPrune => "sap",
+            BroadPhaseKind::Bvh => "bvh",
+        }
+    }
+}
+
+/// One collider tracked by the BVH broad-phase
+#[derive(Debug, Clone)]
+struct Proxy {
+    handle: ColliderHandle,
+    /// Enlarged box stored in the tree
+    fat: Aabb,
+    /// Colliders whose enlarged boxes overlap this one (pairs already reported)
+    neighbors: HashSet<ColliderHandle>,
+}
+
+/// Broad-phase over a BVH of margin-enlarged collider boxes
+///
+/// A collider is re-queried only when its box (plus prediction distance)
+/// leaves its enlarged box; pairs are reported while the enlarged boxes
+/// overlap. Larger margins mean fewer tree rebuilds but more candidate pairs.
+pub struct BvhBroadPhase {
+    margin: Real,
+    tree: Qbvh<ColliderHandle>,
+    /// Proxies by collider arena index
+    proxies: Vec<Option<Proxy>>,
+    changed: Vec<ColliderHandle>,
+    leaves: Vec<(ColliderHandle, Aabb)>,
+    hits: Vec<ColliderHandle>,
+}
+
+impl BvhBroadPhase {
+    /// Create an empty broad-phase enlarging boxes by `margin` (m)
+    pub fn new(margin: Real) -> Self {
+        Self {
+            margin: margin.max(0.0),
+            tree: Qbvh::new(),
+            proxies: Vec::new(),
+            changed: Vec::new(),
+            leaves: Vec::new(),
+            hits: Vec::new(),
+        }
+    }
+
+    fn proxy_mut(&mut self, handle: ColliderHandle) -> Option<&mut Proxy> {
+        self.proxies.get_mut(handle.index())?.as_mut().filter(|p| p.handle == handle)
+    }
+
+    /// Forget a collider; the narrow-phase drops its pairs on its own
+    fn remove(&mut self, handle: ColliderHandle) {
+        let Some(proxy) = self.proxies.get_mut(handle.index()).and_then(|p| p.take_if(|p| p.handle == handle)) else {
+            return;
+        };
+        for other in proxy.neighbors {
+            if let Some(other) = self.proxy_mut(other) {
+                other.neighbors.remove(&handle);
+            }
+        }
+    }
+}
+
+impl BroadPhase for BvhBroadPhase {
+    fn update(
+        &mut self,
+        _dt: Real,
+        prediction_distance: Real,
+        colliders: &mut ColliderSet,
+        _bodies: &RigidBodySet,
+        modified_colliders: &[ColliderHandle],
+        removed_colliders: &[ColliderHandle],
+        events: &mut Vec<BroadPhasePairEvent>,
+    ) {
+        for &handle in removed_colliders {
+            self.remove(handle);
+        }
+
+        // Refresh the enlarged boxes of colliders that escaped them
+        self.changed.clear();
+        for &handle in modified_colliders {
+            let Some(collider) = colliders.get(handle) else {
+                continue;
+            };
+            if !collider.is_enabled() {
+                self.remove(handle);
+                continue;
+            }
+            let tight = collider.compute_aabb().loosened(prediction_distance / 2.0);
+            let index = handle.index();
+            if index >= self.proxies.len() {
+                self.proxies.resize_with(index + 1, || None);
+            }
+            match &mut self.proxies[index] {
+                Some(proxy) if proxy.handle == handle => {
+                    if proxy.fat.contains(&tight) {
+                        continue;
+                    }
+                    proxy.fat = tight.loosened(self.margin);
+                }
+                slot => {
+                    *slot = Some(Proxy { handle, fat: tight.loosened(self.margin), neighbors: HashSet::new() });
+                }
+            }
+            self.changed.push(handle);
+        }
+        if self.changed.is_empty() {
+            return;
+        }
+
+        self.leaves.clear();
+        self.leaves.extend(self.proxies.iter().flatten().map(|p| (p.handle, p.fat)));
+        self.tree.clear_and_rebuild(self.leaves.drain(..), 0.0);
+
+        // Overlap status only changes for pairs involving a changed collider
+        for i in 0..self.changed.len() {
+            let handle = self.changed[i];
+            let Some(fat) = self.proxy_mut(handle).map(|p| p.fat) else {
+                continue;
+            };
+            self.hits.clear();
+            self.tree.intersect_aabb(&fat, &mut self.hits);
+
+            let mut neighbors = std::mem::take(&mut self.proxy_mut(handle).unwrap().neighbors);
+            neighbors.retain(|&other| {
+                if self.hits.contains(&other) {
+                    return true;
+                }
+                if let Some(proxy) = self.proxies.get_mut(other.index()).and_then(Option::as_mut).filter(|p| p.handle == other) {
+                    proxy.neighbors.remove(&handle);
+                }
+                events.push(BroadPhasePairEvent::DeletePair(ColliderPair::new(handle, other)));
+                false
+            });
+            for &other in &self.hits {
+                if other == handle || neighbors.contains(&other) {
+                    continue;
+                }
+                if let Some(proxy) = self.proxies.get_mut(other.index()).and_then(Option::as_mut).filter(|p| p.handle == other) {
+                    proxy.neighbors.insert(handle);
+                }
+                neighbors.insert(other);
+                events.push(BroadPhasePairEvent::AddPair(ColliderPair::new(handle, other)));
+            }
+            self.proxy_mut(handle).unwrap().neighbors = neighbors;
+        }
+    }
+}
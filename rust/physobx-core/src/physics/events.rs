@@ -0,0 +1,163 @@
+//! Impact event collection for procedural sound and analysis
+//!
+//! Rapier reports when two colliders start touching; the collector turns each
+//! of those into an [`ImpactEvent`] carrying the closing speed at the contact.
+
+use parking_lot::Mutex;
+use rapier3d::prelude::*;
+
+/// Collider user data marking the ground plane and other static geometry
+pub const STATIC_USER_DATA: u128 = u128::MAX;
+
+/// A new contact between two bodies (or a body and static geometry)
+#[derive(Debug, Clone, Copy)]
+pub struct ImpactEvent {
+    /// Simulation time at the end of the step that produced the impact
+    pub time: f32,
+    /// SOA index of the first body (None = ground/static geometry)
+    pub body_a: Option<usize>,
+    /// SOA index of the second body (None = ground/static geometry)
+    pub body_b: Option<usize>,
+    /// World-space contact point
+    pub point: [f32; 3],
+    /// World-space contact normal (from body A towards body B)
+    pub normal: [f32; 3],
+    /// Closing speed along the contact normal (m/s)
+    pub relative_speed: f32,
+    /// Estimated impulse magnitude (reduced mass * closing speed)
+    pub impulse: f32,
+}
+
+/// Two bodies (or a body and static geometry) touching after a step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyContact {
+    /// SOA index of the first body (the lower index of two bodies)
+    pub body_a: usize,
+    /// SOA index of the second body (None = ground/static geometry)
+    pub body_b: Option<usize>,
+    /// Total normal impulse over the contact points in the last step (N·s)
+    pub impulse: f32,
+}
+
+/// A single entry of an audio cue list
+#[derive(Debug, Clone)]
+pub struct AudioCue {
+    /// Time of the cue in seconds
+    pub time: f32,
+    /// Loudness in [0, 1], normalized to the strongest impact in the list
+    pub intensity: f32,
+    /// Material pair, e.g. "cube-ground"
+    pub material: String,
+}
+
+/// Convert impact events into an audio cue list
+///
+/// Impacts slower than `min_speed` are dropped. `material_of` names the
+/// material of a body (None = ground/static geometry).
+pub fn audio_cues<F>(events: &[ImpactEvent], min_speed: f32, material_of: F) -> Vec<AudioCue>
+where
+    F: Fn(Option<usize>) -> String,
+{
+    let loudest = events.iter()
+        .filter(|e| e.relative_speed >= min_speed)
+        .map(|e| e.impulse)
+        .fold(0.0f32, f32::max);
+
+    events.iter()
+        .filter(|e| e.relative_speed >= min_speed)
+        .map(|e| {
+            // Sort the pair so "cube-ground" and "ground-cube" share a sound
+            let mut pair = [material_of(e.body_a), material_of(e.body_b)];
+            pair.sort();
+            AudioCue {
+                time: e.time,
+                intensity: if loudest > 0.0 { e.impulse / loudest } else { 0.0 },
+                material: pair.join("-"),
+            }
+        })
+        .collect()
+}
+
+/// Rapier event handler that records contact starts as impacts
+#[derive(Default)]
+pub struct ImpactCollector {
+    events: Mutex<Vec<ImpactEvent>>,
+}
+
+impl ImpactCollector {
+    /// Take all impacts collected since the last call
+    pub fn drain(&self) -> Vec<ImpactEvent> {
+        std::mem::take(&mut *self.events.lock())
+    }
+
+    /// Move all impacts collected since the last call to the end of `out`,
+    /// keeping both buffers' allocations
+    pub fn drain_into(&self, out: &mut Vec<ImpactEvent>) {
+        out.append(&mut self.events.lock());
+    }
+}
+
+/// Map a collider to its SOA body index
+fn body_index(collider: &Collider) -> Option<usize> {
+    match collider.user_data {
+        STATIC_USER_DATA => None,
+        index => Some(index as usize),
+    }
+}
+
+impl EventHandler for ImpactCollector {
+    fn handle_collision_event(
+        &self,
+        bodies: &RigidBodySet,
+        colliders: &ColliderSet,
+        event: CollisionEvent,
+        contact_pair: Option<&ContactPair>,
+    ) {
+        let (CollisionEvent::Started(h1, h2, _), Some(pair)) = (event, contact_pair) else {
+            return;
+        };
+        let (Some(c1), Some(c2)) = (colliders.get(h1), colliders.get(h2)) else {
+            return;
+        };
+        let Some((manifold, contact)) = pair.find_deepest_contact() else {
+            return;
+        };
+
+        let point = c1.position() * contact.local_p1;
+        let normal = manifold.data.normal;
+
+        let b1 = c1.parent().and_then(|h| bodies.get(h));
+        let b2 = c2.parent().and_then(|h| bodies.get(h));
+        let v1 = b1.map(|b| b.velocity_at_point(&point)).unwrap_or_else(Vector::zeros);
+        let v2 = b2.map(|b| b.velocity_at_point(&point)).unwrap_or_else(Vector::zeros);
+        let relative_speed = (v1 - v2).dot(&normal).abs();
+
+        // Static bodies have infinite mass, so only dynamic masses count
+        let inv_mass = |b: Option<&RigidBody>| match b {
+            Some(b) if b.is_dynamic() && b.mass() > 0.0 => 1.0 / b.mass(),
+            _ => 0.0,
+        };
+        let inv_sum = inv_mass(b1) + inv_mass(b2);
+        let reduced_mass = if inv_sum > 0.0 { 1.0 / inv_sum } else { 0.0 };
+
+        self.events.lock().push(ImpactEvent {
+            time: 0.0,
+            body_a: body_index(c1),
+            body_b: body_index(c2),
+            point: [point.x, point.y, point.z],
+            normal: [normal.x, normal.y, normal.z],
+            relative_speed,
+            impulse: reduced_mass * relative_speed,
+        });
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: Real,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &ContactPair,
+        _total_force_magnitude: Real,
+    ) {
+    }
+}
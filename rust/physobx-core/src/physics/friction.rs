@@ -0,0 +1,86 @@
+//! Direction-dependent (anisotropic) friction via contact modification
+//!
+//! Rapier's solver uses a single, isotropic friction coefficient per contact.
+//! For skate or sled style surfaces the hook below gives the solver the
+//! sideways coefficient and uses the contact's target tangent velocity to let
+//! the body keep sliding along its skate axis, slowed only by Coulomb
+//! friction with the (smaller) along-axis coefficient.
+
+use rapier3d::prelude::*;
+use super::events::STATIC_USER_DATA;
+
+/// Friction that differs along and across a body-local axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnisotropicFriction {
+    /// Skate axis in the body's local frame (normalized on use)
+    pub axis: [f32; 3],
+    /// Friction coefficient when sliding along the axis
+    pub along: f32,
+    /// Friction coefficient when sliding across the axis
+    pub across: f32,
+}
+
+/// Physics hooks applying anisotropic friction, indexed by SOA body index
+#[derive(Default)]
+pub struct FrictionHooks {
+    pub(crate) surfaces: Vec<Option<AnisotropicFriction>>,
+    /// Time step of the current step
+    pub(crate) dt: f32,
+    /// Gravity vector, used to estimate the normal load
+    pub(crate) gravity: Vector<Real>,
+}
+
+impl FrictionHooks {
+    /// Anisotropic surface of a collider's body, if any
+    fn surface(&self, collider: &Collider) -> Option<&AnisotropicFriction> {
+        match collider.user_data {
+            STATIC_USER_DATA => None,
+            index => self.surfaces.get(index as usize)?.as_ref(),
+        }
+    }
+}
+
+impl PhysicsHooks for FrictionHooks {
+    fn modify_solver_contacts(&self, context: &mut ContactModificationContext) {
+        let c1 = &context.colliders[context.collider1];
+        let c2 = &context.colliders[context.collider2];
+
+        // The first anisotropic body of the pair decides the friction
+        let (surface, body) = match (self.surface(c1), self.surface(c2)) {
+            (Some(s), _) => (s, context.rigid_body1),
+            (None, Some(s)) => (s, context.rigid_body2),
+            (None, None) => return,
+        };
+        let Some(body) = body.and_then(|h| context.bodies.get(h)) else {
+            return;
+        };
+
+        let normal = *context.normal;
+        let axis = body.rotation() * vector![surface.axis[0], surface.axis[1], surface.axis[2]];
+        let axis = axis - normal * axis.dot(&normal);
+        let Some(axis) = axis.try_normalize(1e-6) else {
+            // Axis points into the contact plane: no preferred direction
+            return;
+        };
+
+        let b1 = context.rigid_body1.and_then(|h| context.bodies.get(h));
+        let b2 = context.rigid_body2.and_then(|h| context.bodies.get(h));
+
+        // Deceleration from along-axis friction, assuming the contact carries
+        // the body's weight (exact for sleds on flat or sloped ground)
+        let along_decel = surface.along * normal.dot(&self.gravity).abs() * self.dt;
+
+        for contact in context.solver_contacts.iter_mut() {
+            let v1 = b1.map(|b| b.velocity_at_point(&contact.point)).unwrap_or_else(Vector::zeros);
+            let v2 = b2.map(|b| b.velocity_at_point(&contact.point)).unwrap_or_else(Vector::zeros);
+            let slide = (v1 - v2).dot(&axis);
+
+            // The solver drives the relative tangent velocity towards
+            // -tangent_velocity, so only the sideways slip gets the full
+            // friction while the along-axis slip just loses `along_decel`
+            let kept = slide.signum() * (slide.abs() - along_decel).max(0.0);
+            contact.friction = surface.across;
+            contact.tangent_velocity = -axis * kept;
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Dense uniform grid for particle neighbor searches
+//!
+//! Shared by the particle solvers (PBD granular backend, SPH fluid). The
+//! grid is rebuilt from scratch every substep with a counting sort, which is
+//! cheaper than maintaining a hash when every particle moves.
+
+use nalgebra::Vector3;
+
+/// Particle indices sorted by cell of a dense grid over their bounding box
+#[derive(Debug, Default)]
+pub(crate) struct ParticleGrid {
+    cell_size: f32,
+    origin: Vector3<f32>,
+    dims: [usize; 3],
+    /// Start of each cell's particles in `sorted`, plus the end
+    starts: Vec<u32>,
+    sorted: Vec<u32>,
+}
+
+impl ParticleGrid {
+    /// Bucket `positions` into cells at least `min_cell` wide (counting sort)
+    ///
+    /// Cells grow past `min_cell` when the particles are spread out, so the
+    /// grid never has more than a few cells per particle.
+    pub(crate) fn build(&mut self, positions: &[Vector3<f32>], min_cell: f32) {
+        let (mut lo, mut hi) = (Vector3::repeat(f32::INFINITY), Vector3::repeat(f32::NEG_INFINITY));
+        for p in positions {
+            lo = lo.inf(p);
+            hi = hi.sup(p);
+        }
+        if positions.is_empty() {
+            (lo, hi) = (Vector3::zeros(), Vector3::zeros());
+        }
+        let max_cells = 8 * positions.len() + 64;
+        let mut cell = min_cell.max(1e-6);
+        loop {
+            self.dims = [0, 1, 2].map(|k| ((hi[k] - lo[k]) / cell) as usize + 1);
+            if self.dims.iter().product::<usize>() <= max_cells {
+                break;
+            }
+            cell *= 1.5;
+        }
+        self.cell_size = cell;
+        self.origin = lo;
+
+        let cells = self.dims.iter().product::<usize>();
+        self.starts.clear();
+        self.starts.resize(cells + 1, 0);
+        for p in positions {
+            let c = self.index(self.cell(p));
+            self.starts[c + 1] += 1;
+        }
+        for k in 0..cells {
+            self.starts[k + 1] += self.starts[k];
+        }
+        self.sorted.resize(positions.len(), 0);
+        let mut fill = self.starts.clone();
+        for (i, p) in positions.iter().enumerate() {
+            let c = self.index(self.cell(p));
+            self.sorted[fill[c] as usize] = i as u32;
+            fill[c] += 1;
+        }
+    }
+
+    /// Cell coordinates of `p`, clamped to the grid
+    fn cell(&self, p: &Vector3<f32>) -> [usize; 3] {
+        [0, 1, 2].map(|k| (((p[k] - self.origin[k]) / self.cell_size).max(0.0) as usize).min(self.dims[k] - 1))
+    }
+
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        (z * self.dims[1] + y) * self.dims[0] + x
+    }
+
+    /// Call `f` with every particle in the 27 cells around `p`
+    pub(crate) fn for_each_near(&self, p: &Vector3<f32>, mut f: impl FnMut(usize)) {
+        let [cx, cy, cz] = self.cell(p);
+        let (x0, x1) = (cx.saturating_sub(1), (cx + 1).min(self.dims[0] - 1));
+        for z in cz.saturating_sub(1)..=(cz + 1).min(self.dims[2] - 1) {
+            for y in cy.saturating_sub(1)..=(cy + 1).min(self.dims[1] - 1) {
+                // The three cells along x are contiguous
+                let start = self.starts[self.index([x0, y, z])] as usize;
+                let end = self.starts[self.index([x1, y, z]) + 1] as usize;
+                for &j in &self.sorted[start..end] {
+                    f(j as usize);
+                }
+            }
+        }
+    }
+
+    /// Call `f` with every particle in the cells overlapping the box `lo`..`hi`
+    pub(crate) fn for_each_in_box(&self, lo: &Vector3<f32>, hi: &Vector3<f32>, mut f: impl FnMut(usize)) {
+        if self.sorted.is_empty() {
+            return;
+        }
+        let outside = (0..3).any(|k| {
+            hi[k] < self.origin[k] || lo[k] > self.origin[k] + self.dims[k] as f32 * self.cell_size
+        });
+        if outside {
+            return;
+        }
+        let ([x0, y0, z0], [x1, y1, z1]) = (self.cell(lo), self.cell(hi));
+        for z in z0..=z1 {
+            for y in y0..=y1 {
+                let start = self.starts[self.index([x0, y, z])] as usize;
+                let end = self.starts[self.index([x1, y, z]) + 1] as usize;
+                for &j in &self.sorted[start..end] {
+                    f(j as usize);
+                }
+            }
+        }
+    }
+}
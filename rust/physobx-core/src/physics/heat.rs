@@ -0,0 +1,71 @@
+//! Heat exchanged between touching bodies
+//!
+//! A visual metaphor rather than thermodynamics: every body carries one
+//! temperature, and each pair in contact relaxes toward a common value at
+//! the scene's conductance, regardless of mass, material or contact area.
+//! Static bodies and the ground hold their temperature, acting as heat
+//! sources and sinks. Colored with the heat-map ramp, temperatures show
+//! which bodies touch and how far a hot spot has spread through a pile.
+
+use super::events::BodyContact;
+
+/// How temperature spreads through contacts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeatSettings {
+    /// Rate at which touching bodies approach each other's temperature
+    /// (1/s); 0 disables heat exchange
+    pub conductance: f32,
+    /// Fixed temperature of the ground; None keeps the ground out of the
+    /// exchange
+    pub ground_temperature: Option<f32>,
+}
+
+impl Default for HeatSettings {
+    fn default() -> Self {
+        Self { conductance: 0.0, ground_temperature: None }
+    }
+}
+
+impl HeatSettings {
+    /// Whether temperatures change at all
+    pub fn enabled(&self) -> bool {
+        self.conductance > 0.0
+    }
+
+    /// Exchange heat across `contacts` for `dt` seconds
+    ///
+    /// Bodies for which `fixed` is true keep their temperature. Pairs are
+    /// relaxed one after the other with exact exponential decay, so any
+    /// conductance and step size stay stable.
+    pub fn exchange(&self, temperatures: &mut [f32], contacts: &[BodyContact], fixed: impl Fn(usize) -> bool, dt: f32) {
+        if !self.enabled() || dt <= 0.0 {
+            return;
+        }
+        let toward_fixed = 1.0 - (-self.conductance * dt).exp();
+        let toward_mean = 0.5 * (1.0 - (-2.0 * self.conductance * dt).exp());
+        for contact in contacts {
+            let a = contact.body_a;
+            if a >= temperatures.len() {
+                continue;
+            }
+            match contact.body_b {
+                Some(b) if b < temperatures.len() => match (fixed(a), fixed(b)) {
+                    (false, false) => {
+                        let flow = (temperatures[b] - temperatures[a]) * toward_mean;
+                        temperatures[a] += flow;
+                        temperatures[b] -= flow;
+                    }
+                    (false, true) => temperatures[a] += (temperatures[b] - temperatures[a]) * toward_fixed,
+                    (true, false) => temperatures[b] += (temperatures[a] - temperatures[b]) * toward_fixed,
+                    (true, true) => {}
+                },
+                Some(_) => {}
+                None => {
+                    if let (Some(ground), false) = (self.ground_temperature, fixed(a)) {
+                        temperatures[a] += (ground - temperatures[a]) * toward_fixed;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Level of simulation: far-away bodies fly ballistically
+//!
+//! Huge scattered scenes spend most of their solver time on bodies far from
+//! where the camera looks. With a [`SimulationLod`] set, awake dynamic
+//! bodies farther than `radius + margin` from the focus lose their
+//! collisions and just follow their velocity under gravity, stopping when
+//! they reach the ground; bodies coming within `radius` are simulated in
+//! full again. The margin keeps bodies near the boundary from switching
+//! back and forth every step.
+
+/// Distance-based level of simulation around a focus point
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationLod {
+    /// Center of the fully simulated region, e.g. the camera target
+    pub focus: [f32; 3],
+    /// Bodies within this distance of the focus are fully simulated
+    pub radius: f32,
+    /// Extra distance beyond `radius` a body must reach before it is
+    /// simplified
+    pub margin: f32,
+}
+
+impl SimulationLod {
+    /// Full simulation within `radius` of `focus`, with a margin of 10% of
+    /// the radius
+    pub fn new(focus: [f32; 3], radius: f32) -> Self {
+        Self { focus, radius, margin: 0.1 * radius }
+    }
+
+    /// Whether a fully simulated body at `position` should be simplified
+    pub fn is_far(&self, position: [f32; 3]) -> bool {
+        self.distance_squared(position) > (self.radius + self.margin).powi(2)
+    }
+
+    /// Whether a simplified body at `position` should be simulated in full
+    pub fn is_near(&self, position: [f32; 3]) -> bool {
+        self.distance_squared(position) < self.radius * self.radius
+    }
+
+    fn distance_squared(&self, position: [f32; 3]) -> f32 {
+        (0..3).map(|k| (position[k] - self.focus[k]).powi(2)).sum()
+    }
+}
@@ -2,6 +2,27 @@
 
 pub mod storage;
 pub mod rapier_bridge;
+pub mod backend;
+pub mod pbd;
+pub mod fluid;
+pub mod aero;
+pub mod heat;
+pub(crate) mod grid;
+pub mod events;
+pub mod settings;
+pub mod friction;
+pub mod broad_phase;
+pub mod lod;
 
 pub use storage::RigidBodyStorage;
-pub use rapier_bridge::RapierBridge;
+pub use rapier_bridge::{RapierBridge, RayHit, TimeOfImpact};
+pub use backend::{PhysicsBackend, PhysicsBackendKind};
+pub use pbd::{PbdBackend, PbdSettings};
+pub use fluid::{FluidConfig, FluidSettings, FluidSystem};
+pub use aero::{AeroCoefficients, WindField};
+pub use heat::HeatSettings;
+pub use events::{BodyContact, ImpactEvent, AudioCue};
+pub use settings::{PhysicsSettings, CombineRule};
+pub use friction::AnisotropicFriction;
+pub use broad_phase::{BroadPhaseKind, BvhBroadPhase};
+pub use lod::SimulationLod;
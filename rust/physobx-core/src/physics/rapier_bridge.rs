@@ -1,12 +1,122 @@
 //! Bridge between SOA storage and Rapier physics engine
 
 use rapier3d::prelude::*;
+use rapier3d::na::{Matrix3, Quaternion, Rotation3, UnitQuaternion};
+use rapier3d::parry::query::{self, NonlinearRigidMotion};
+use std::collections::HashSet;
 use std::num::NonZeroUsize;
 use super::storage::RigidBodyStorage;
-use crate::scene::builder::{SceneBuilder, RigidBodyConfig, ShapeType};
+use super::events::{BodyContact, ImpactCollector, ImpactEvent, STATIC_USER_DATA};
+use super::settings::{iterations_for_mass_ratio, CombineRule, PhysicsSettings};
+use super::friction::FrictionHooks;
+use super::broad_phase::{BroadPhaseKind, BvhBroadPhase};
+use super::lod::SimulationLod;
+use crate::scene::builder::{SceneBuilder, RigidBodyConfig, ShapeType, PLANE_HALF_THICKNESS};
+use crate::scene::joints::{JointConfig, JointKind};
+use crate::scene::motion_path::MotionPath;
+use crate::scene::report::{mass_ratio_issues, SceneIssue, MAX_MASS_RATIO};
 
-/// Velocity threshold for enabling CCD (m/s)
-const CCD_VELOCITY_THRESHOLD: f32 = 10.0;
+/// Largest gap between a falling body's parabola and the straight segments
+/// it is swept along in time-of-impact queries (scene units)
+const TOI_ARC_TOLERANCE: f32 = 1e-3;
+
+/// Closest hit of a ray cast into the world
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Body index, or None for the ground / static geometry
+    pub body: Option<usize>,
+    /// Distance along the (normalized) ray
+    pub distance: f32,
+    /// World-space hit point
+    pub point: [f32; 3],
+    /// World-space surface normal at the hit point
+    pub normal: [f32; 3],
+}
+
+/// Predicted first contact of a body with another body or the ground
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeOfImpact {
+    /// Seconds from now until the shapes touch (0 if already touching)
+    pub time: f32,
+    /// The other body's index, or None for the ground
+    pub body: Option<usize>,
+    /// World-space contact point on the queried body at impact
+    pub point: [f32; 3],
+    /// World-space contact normal at impact, pointing out of the queried body
+    pub normal: [f32; 3],
+}
+
+/// Ballistic motion of a collider: its current velocities plus gravity
+struct Sweep {
+    /// Constant-velocity part of the motion
+    motion: NonlinearRigidMotion,
+    /// Acceleration of the center of mass
+    gravity: Vector<Real>,
+    /// Center of mass now (world space)
+    center: Point<Real>,
+    /// Radius around the center of mass enclosing the shape
+    radius: Real,
+}
+
+impl Sweep {
+    /// Motion over `[t0, t0 + h]` along the chord of the parabola, as a
+    /// constant-velocity motion starting at local time 0
+    fn segment(&self, t0: Real, h: Real) -> NonlinearRigidMotion {
+        let mut start = self.motion.position_at_time(t0);
+        start.translation.vector += self.gravity * (0.5 * t0 * t0);
+        NonlinearRigidMotion::new(
+            start,
+            self.motion.local_center,
+            self.motion.linvel + self.gravity * (t0 + 0.5 * h),
+            self.motion.angvel,
+        )
+    }
+
+    /// World-space box containing the shape over `[0, max_t]`
+    fn bounds(&self, max_t: Real) -> (Point<Real>, Point<Real>) {
+        let (v, g) = (self.motion.linvel, self.gravity);
+        let mut min = self.center;
+        let mut max = self.center;
+        for axis in 0..3 {
+            // Extremes of the center's path: the ends and the apex, if inside
+            let mut times = [0.0, max_t, 0.0];
+            if g[axis].abs() > 1e-9 {
+                times[2] = (-v[axis] / g[axis]).clamp(0.0, max_t);
+            }
+            for t in times {
+                let c = self.center[axis] + v[axis] * t + 0.5 * g[axis] * t * t;
+                min[axis] = min[axis].min(c);
+                max[axis] = max[axis].max(c);
+            }
+            min[axis] -= self.radius;
+            max[axis] += self.radius;
+        }
+        (min, max)
+    }
+}
+
+/// A spring pulling a point of a body toward a movable target (mouse drag)
+struct MouseJoint {
+    /// Kinematic body placed at the target
+    anchor: RigidBodyHandle,
+    /// Dynamic body being dragged
+    body: RigidBodyHandle,
+}
+
+/// A scene joint created in Rapier
+struct SceneJoint {
+    handle: ImpulseJointHandle,
+    /// Wheel frame on body A before any steering; None for springs
+    base_frame: Option<Isometry<Real>>,
+}
+
+/// A kinematic body moved along its motion path every step
+struct PathFollower {
+    handle: RigidBodyHandle,
+    path: MotionPath,
+    /// Distance travelled along the path (m)
+    distance: f32,
+}
 
 /// Bridge for syncing with Rapier physics
 pub struct RapierBridge {
@@ -19,7 +129,7 @@ pub struct RapierBridge {
     /// Island manager
     island_manager: IslandManager,
     /// Broad phase
-    broad_phase: DefaultBroadPhase,
+    broad_phase: Box<dyn BroadPhase>,
     /// Narrow phase
     narrow_phase: NarrowPhase,
     /// Impulse joints
@@ -38,6 +148,35 @@ pub struct RapierBridge {
     body_handles: Vec<RigidBodyHandle>,
     /// Mapping from SOA index to Collider handle
     collider_handles: Vec<ColliderHandle>,
+    /// SOA indices of dynamic bodies (static bodies are never synced)
+    dynamic_indices: Vec<usize>,
+    /// Collects impacts reported during the step
+    impact_collector: ImpactCollector,
+    /// Bodies that receive gyroscopic torque each step
+    gyroscopic_handles: Vec<RigidBodyHandle>,
+    /// Contact hooks for anisotropic friction
+    friction_hooks: FrictionHooks,
+    /// Runtime mouse joints by id (None once removed)
+    mouse_joints: Vec<Option<MouseJoint>>,
+    /// Scene joints by joint index (None if a body index was invalid)
+    scene_joints: Vec<Option<SceneJoint>>,
+    /// Runtime fixed joints by attachment id (None once detached)
+    attachments: Vec<Option<ImpulseJointHandle>>,
+    /// Kinematic bodies driven along motion paths
+    path_followers: Vec<PathFollower>,
+    /// Conditioning problems found by the last build
+    build_warnings: Vec<SceneIssue>,
+    /// Dynamic bodies made fixed by [`RapierBridge::freeze`]
+    frozen: Vec<RigidBodyHandle>,
+    /// Level of simulation, if far bodies are simplified
+    lod: Option<SimulationLod>,
+    /// Bodies simplified by the level of simulation (kinematic, colliders
+    /// disabled)
+    coarse: HashSet<RigidBodyHandle>,
+    /// Top and half size of the ground, which coarse bodies land on
+    ground: Option<(f32, f32)>,
+    /// Whether the scene keeps dynamic bodies in their xy plane
+    planar: bool,
 }
 
 impl Default for RapierBridge {
@@ -61,7 +200,7 @@ impl RapierBridge {
             collider_set: ColliderSet::new(),
             physics_pipeline: PhysicsPipeline::new(),
             island_manager: IslandManager::new(),
-            broad_phase: DefaultBroadPhase::new(),
+            broad_phase: Box::new(DefaultBroadPhase::new()),
             narrow_phase: NarrowPhase::new(),
             impulse_joint_set: ImpulseJointSet::new(),
             multibody_joint_set: MultibodyJointSet::new(),
@@ -71,6 +210,20 @@ impl RapierBridge {
             integration_parameters,
             body_handles: Vec::new(),
             collider_handles: Vec::new(),
+            dynamic_indices: Vec::new(),
+            impact_collector: ImpactCollector::default(),
+            gyroscopic_handles: Vec::new(),
+            friction_hooks: FrictionHooks::default(),
+            mouse_joints: Vec::new(),
+            scene_joints: Vec::new(),
+            attachments: Vec::new(),
+            path_followers: Vec::new(),
+            build_warnings: Vec::new(),
+            frozen: Vec::new(),
+            lod: None,
+            coarse: HashSet::new(),
+            ground: None,
+            planar: false,
         }
     }
 
@@ -81,8 +234,51 @@ impl RapierBridge {
         self.collider_set = ColliderSet::new();
         self.body_handles.clear();
         self.collider_handles.clear();
+        self.dynamic_indices.clear();
+        self.gyroscopic_handles.clear();
+        self.friction_hooks.surfaces.clear();
+        self.impulse_joint_set = ImpulseJointSet::new();
+        self.mouse_joints.clear();
+        self.scene_joints.clear();
+        self.attachments.clear();
+        self.path_followers.clear();
+        self.frozen.clear();
+        self.coarse.clear();
+        self.ground = scene.ground_y.map(|y| (y + 0.1, scene.ground_size));
+        self.planar = scene.physics.planar;
         storage.clear();
 
+        let [gx, gy, gz] = scene.physics.gravity;
+        self.gravity = vector![gx, gy, gz];
+        self.broad_phase = match scene.physics.broad_phase {
+            BroadPhaseKind::SweepAndPrune => Box::new(DefaultBroadPhase::new()),
+            BroadPhaseKind::Bvh => Box::new(BvhBroadPhase::new(scene.physics.bvh_margin)),
+        };
+        self.narrow_phase = NarrowPhase::new();
+        self.island_manager = IslandManager::new();
+        self.integration_parameters.normalized_prediction_distance = scene.physics.prediction_distance;
+        // Extreme mass ratios converge slowly: give the worst pair the
+        // iterations auto-tuning would
+        self.build_warnings = mass_ratio_issues(scene);
+        let worst = self.build_warnings.iter()
+            .filter_map(|issue| match *issue {
+                SceneIssue::MassRatio { ratio, .. } => Some(ratio),
+                _ => None,
+            })
+            .fold(1.0f32, f32::max);
+        let iterations = scene.physics.solver_iterations.max(1);
+        let raised = iterations.max(iterations_for_mass_ratio(worst));
+        if !self.build_warnings.is_empty() {
+            log::warn!(
+                "{} body pairs with mass ratios above {}:1 (worst {:.0}:1); using {} solver iterations",
+                self.build_warnings.len(),
+                MAX_MASS_RATIO,
+                worst,
+                raised,
+            );
+        }
+        self.integration_parameters.num_solver_iterations = NonZeroUsize::new(raised as usize).unwrap();
+
         // Add ground if specified
         if let Some(ground_y) = scene.ground_y {
             let ground = RigidBodyBuilder::fixed()
@@ -95,20 +291,67 @@ impl RapierBridge {
                 0.1,
                 scene.ground_size,
             )
-            .restitution(0.3)
-            .friction(0.5)
+            .restitution(scene.physics.ground_restitution)
+            .friction(scene.physics.ground_friction)
+            .friction_combine_rule(combine_rule(scene.physics.friction_combine))
+            .restitution_combine_rule(combine_rule(scene.physics.restitution_combine))
+            .user_data(STATIC_USER_DATA)
             .build();
             self.collider_set.insert_with_parent(ground_collider, ground_handle, &mut self.rigid_body_set);
         }
+        for plane in &scene.planes {
+            let [x, y, z, w] = plane.rotation();
+            let rotation = UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z));
+            let [cx, cy, cz] = plane.center();
+            let plane_body = RigidBodyBuilder::fixed()
+                .position(Isometry::from_parts(vector![cx, cy, cz].into(), rotation))
+                .build();
+            let plane_handle = self.rigid_body_set.insert(plane_body);
+            let plane_collider = ColliderBuilder::cuboid(plane.size, PLANE_HALF_THICKNESS, plane.size)
+                .restitution(scene.physics.ground_restitution)
+                .friction(scene.physics.ground_friction)
+                .friction_combine_rule(combine_rule(scene.physics.friction_combine))
+                .restitution_combine_rule(combine_rule(scene.physics.restitution_combine))
+                .user_data(STATIC_USER_DATA)
+                .build();
+            self.collider_set.insert_with_parent(plane_collider, plane_handle, &mut self.rigid_body_set);
+        }
 
-        // Add dynamic bodies
+        // Add bodies (dynamic and static)
         for config in &scene.bodies {
-            self.add_body(config, storage);
+            self.add_body(config, &scene.physics, storage);
+        }
+        for joint in &scene.joints {
+            let created = self.add_joint(joint);
+            self.scene_joints.push(created);
         }
+
+        // Ray casts work before the first step
+        self.query_pipeline.update(&self.collider_set);
     }
 
     /// Add a single rigid body
-    fn add_body(&mut self, config: &RigidBodyConfig, storage: &mut RigidBodyStorage) {
+    fn add_body(&mut self, config: &RigidBodyConfig, settings: &PhysicsSettings, storage: &mut RigidBodyStorage) {
+        let index = storage.len();
+        let (body_handle, collider_handle) = self.create_body(index, config, settings);
+
+        // Add to SOA storage with shape info
+        storage.push_with_shape(config.position, config.rotation, config.resolved_mass(settings.length_scale), config.shape, config.radius, config.half_extents[0], config.half_height, config.color);
+        storage.surfaces[index] = [config.roughness, config.metallic];
+        if config.is_static {
+            storage.set_static(index, true);
+        } else {
+            self.dynamic_indices.push(index);
+        }
+        self.friction_hooks.surfaces.push(config.anisotropic_friction);
+
+        // Store handles
+        self.body_handles.push(body_handle);
+        self.collider_handles.push(collider_handle);
+    }
+
+    /// Create the Rapier body and collider of the body with SOA index `index`
+    fn create_body(&mut self, index: usize, config: &RigidBodyConfig, settings: &PhysicsSettings) -> (RigidBodyHandle, ColliderHandle) {
         // Calculate velocity magnitude for CCD decision
         let velocity_magnitude = (
             config.velocity[0].powi(2) +
@@ -116,79 +359,335 @@ impl RapierBridge {
             config.velocity[2].powi(2)
         ).sqrt();
 
+        // Surface speed from spin counts too (fast spinners skip through contacts)
+        let angular_speed = (
+            config.angular_velocity[0].powi(2) +
+            config.angular_velocity[1].powi(2) +
+            config.angular_velocity[2].powi(2)
+        ).sqrt();
+        // Farthest point of the body's own shape or any child from its center
+        let bounding_radius = config.children.iter()
+            .map(|child| {
+                let [x, y, z] = child.offset;
+                (x * x + y * y + z * z).sqrt() + shape_bounding_radius(child.shape, child.half_extents, child.radius, child.half_height)
+            })
+            .fold(shape_bounding_radius(config.shape, config.half_extents, config.radius, config.half_height), f32::max);
+        let surface_speed = velocity_magnitude + angular_speed * bounding_radius;
+
         // Create Rapier body with optional initial velocity
-        let mut body_builder = RigidBodyBuilder::dynamic()
-            .translation(vector![config.position[0], config.position[1], config.position[2]])
-            .rotation(vector![
-                config.rotation[0],
-                config.rotation[1],
-                config.rotation[2],
-            ]);
+        let rotation = UnitQuaternion::from_quaternion(Quaternion::new(
+            config.rotation[3],
+            config.rotation[0],
+            config.rotation[1],
+            config.rotation[2],
+        ));
+        let mut body_builder = if config.is_static {
+            RigidBodyBuilder::fixed()
+        } else if config.motion_path.is_some() {
+            RigidBodyBuilder::kinematic_position_based()
+        } else {
+            RigidBodyBuilder::dynamic()
+        }
+            .position(Isometry::from_parts(
+                vector![config.position[0], config.position[1], config.position[2]].into(),
+                rotation,
+            ));
 
-        // Set initial velocity if non-zero
-        if config.velocity != [0.0, 0.0, 0.0] {
-            body_builder = body_builder.linvel(vector![
-                config.velocity[0],
-                config.velocity[1],
-                config.velocity[2],
+        let (locked_translations, locked_rotations) =
+            with_planar_locks(settings.planar, config.locked_translations, config.locked_rotations);
+
+        // Set initial velocity if non-zero (locked axes and path followers start at rest)
+        let (mut velocity, mut angular_velocity) = (
+            unlocked(config.velocity, locked_translations),
+            unlocked(config.angular_velocity, locked_rotations),
+        );
+        if config.motion_path.is_some() {
+            (velocity, angular_velocity) = ([0.0; 3], [0.0; 3]);
+        }
+        if velocity != [0.0, 0.0, 0.0] {
+            body_builder = body_builder.linvel(vector![velocity[0], velocity[1], velocity[2]]);
+        }
+        if angular_velocity != [0.0, 0.0, 0.0] {
+            body_builder = body_builder.angvel(vector![
+                angular_velocity[0],
+                angular_velocity[1],
+                angular_velocity[2],
             ]);
         }
+        body_builder = body_builder.locked_axes(locked_axes(locked_translations, locked_rotations));
 
         // Enable CCD for fast-moving bodies to prevent tunneling
-        if velocity_magnitude > CCD_VELOCITY_THRESHOLD {
+        if surface_speed >= settings.ccd_speed && !config.is_static && config.motion_path.is_none() {
             body_builder = body_builder.ccd_enabled(true);
         }
 
-        let body = body_builder.build();
-        let body_handle = self.rigid_body_set.insert(body);
-
-        // Create collider based on shape type
-        let collider = match config.shape {
-            ShapeType::Cube => {
-                let volume = 8.0 * config.half_extents[0] * config.half_extents[1] * config.half_extents[2];
-                ColliderBuilder::cuboid(
-                    config.half_extents[0],
-                    config.half_extents[1],
-                    config.half_extents[2],
-                )
-                .restitution(config.restitution)
-                .friction(config.friction)
-                .density(config.mass / volume)
-                .build()
-            }
-            ShapeType::Sphere => {
-                let volume = (4.0 / 3.0) * std::f32::consts::PI * config.radius.powi(3);
-                ColliderBuilder::ball(config.radius)
-                    .restitution(config.restitution)
-                    .friction(config.friction)
-                    .density(config.mass / volume)
-                    .build()
+        // Create the body's own collider and those of its children. With a
+        // density (mass mode or material) Rapier computes mass and inertia
+        // from the collider shapes; a given mass is shared in proportion to
+        // their volumes, unless they have no volume to share it over
+        let mass = config.resolved_mass(settings.length_scale);
+        let (friction, restitution) = config.resolved_surface();
+        let volume = config.total_volume();
+        let density = match config.resolved_density() {
+            Some(density) => density * settings.length_scale.powi(3),
+            None if volume > 0.0 && (mass / volume).is_finite() => mass / volume,
+            None => 0.0,
+        };
+        let with_material = |builder: ColliderBuilder| {
+            let mut collider = builder
+                .restitution(restitution)
+                .friction(friction)
+                .density(density)
+                .user_data(index as u128)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .friction_combine_rule(combine_rule(config.friction_combine.unwrap_or(settings.friction_combine)))
+                .restitution_combine_rule(combine_rule(config.restitution_combine.unwrap_or(settings.restitution_combine)));
+            if config.anisotropic_friction.is_some() {
+                collider = collider.active_hooks(ActiveHooks::MODIFY_SOLVER_CONTACTS);
             }
+            collider.build()
         };
+        let mut collider = with_material(shape_collider(config.shape, config.half_extents, config.radius, config.half_height));
+        let mut children: Vec<Collider> = config.children.iter()
+            .map(|child| {
+                let [x, y, z, w] = child.rotation;
+                let rotation = UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z));
+                let offset = Isometry::from_parts(vector![child.offset[0], child.offset[1], child.offset[2]].into(), rotation);
+                with_material(shape_collider(child.shape, child.half_extents, child.radius, child.half_height).position(offset))
+            })
+            .collect();
+
+        // Custom center of mass / inertia: the body carries all the mass and
+        // the collider only contributes geometry
+        if config.com_offset.is_some() || config.inertia.is_some() {
+            let shape_props = children.iter()
+                .map(|child| child.mass_properties().transform_by(child.position()))
+                .fold(collider.mass_properties(), |sum, props| sum + props);
+            let local_com = config.com_offset
+                .map_or(shape_props.local_com, |c| point![c[0], c[1], c[2]]);
+            let (inertia, frame) = match config.inertia {
+                Some(i) => (vector![i[0], i[1], i[2]], UnitQuaternion::identity()),
+                None => (shape_props.principal_inertia(), shape_props.principal_inertia_local_frame),
+            };
+            body_builder = body_builder.additional_mass_properties(
+                MassProperties::with_principal_inertia_frame(local_com, mass, inertia, frame),
+            );
+            collider.set_density(0.0);
+            for child in &mut children {
+                child.set_density(0.0);
+            }
+        } else if density == 0.0 && mass > 0.0 {
+            // Degenerate size: a point mass with the inertia of a small ball
+            let r = bounding_radius.max(1e-3);
+            let inertia = 0.4 * mass * r * r;
+            body_builder = body_builder.additional_mass_properties(
+                MassProperties::new(point![0.0, 0.0, 0.0], mass, vector![inertia, inertia, inertia]),
+            );
+        }
+
+        let body = body_builder.build();
+        let body_handle = self.rigid_body_set.insert(body);
 
         let collider_handle = self.collider_set.insert_with_parent(
             collider,
             body_handle,
             &mut self.rigid_body_set,
         );
+        for child in children {
+            self.collider_set.insert_with_parent(child, body_handle, &mut self.rigid_body_set);
+        }
 
-        // Add to SOA storage with shape info
-        storage.push_with_shape(config.position, config.rotation, config.mass, config.shape, config.radius, config.half_extents[0], config.color);
+        if config.gyroscopic {
+            self.gyroscopic_handles.push(body_handle);
+        }
+        if let Some(path) = &config.motion_path {
+            self.path_followers.push(PathFollower { handle: body_handle, path: path.clone(), distance: 0.0 });
+        }
+        (body_handle, collider_handle)
+    }
 
-        // Store handles
-        self.body_handles.push(body_handle);
-        self.collider_handles.push(collider_handle);
+    /// Bring the world's bodies in line with `scene.bodies` without
+    /// rebuilding: re-create the bodies listed in `changed` from their
+    /// configs, drop bodies past the end of the scene and add new ones
+    ///
+    /// Other bodies keep their simulated state. Joints attached to a
+    /// re-created or dropped body are removed with it.
+    pub fn update_bodies(&mut self, scene: &SceneBuilder, changed: &[usize], storage: &mut RigidBodyStorage) {
+        let len = scene.bodies.len();
+        for &index in changed {
+            let (Some(config), Some(&old)) = (scene.bodies.get(index), self.body_handles.get(index)) else {
+                continue;
+            };
+            self.remove_rapier_body(old);
+            let (body_handle, collider_handle) = self.create_body(index, config, &scene.physics);
+            storage.replace_with_shape(index, config.position, config.rotation, config.resolved_mass(scene.physics.length_scale), config.shape, config.radius, config.half_extents[0], config.half_height, config.color);
+            storage.surfaces[index] = [config.roughness, config.metallic];
+            storage.set_static(index, config.is_static);
+            self.friction_hooks.surfaces[index] = config.anisotropic_friction;
+            self.body_handles[index] = body_handle;
+            self.collider_handles[index] = collider_handle;
+        }
+
+        while self.body_handles.len() > len {
+            let handle = self.body_handles.pop().unwrap();
+            self.remove_rapier_body(handle);
+        }
+        self.collider_handles.truncate(len);
+        self.friction_hooks.surfaces.truncate(len);
+        storage.truncate(len);
+
+        for config in scene.bodies.iter().skip(storage.len()) {
+            self.add_body(config, &scene.physics, storage);
+        }
+        self.dynamic_indices = (0..len).filter(|&i| !storage.is_static(i)).collect();
+        self.query_pipeline.update(&self.collider_set);
+    }
+
+    /// Remove a body with its collider and joints, and stop driving it
+    fn remove_rapier_body(&mut self, handle: RigidBodyHandle) {
+        self.rigid_body_set.remove(
+            handle,
+            &mut self.island_manager,
+            &mut self.collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            true,
+        );
+        self.gyroscopic_handles.retain(|&h| h != handle);
+        self.path_followers.retain(|f| f.handle != handle);
+        self.frozen.retain(|&h| h != handle);
+        self.coarse.remove(&handle);
+    }
+
+    /// Create a scene joint between two added bodies, or a body and the world
+    fn add_joint(&mut self, config: &JointConfig) -> Option<SceneJoint> {
+        let a = *self.body_handles.get(config.body_a)?;
+        let (body1, body2, joint, base_frame) = match config.kind {
+            JointKind::Wheel { anchor, axle, up, travel, stiffness, damping } => {
+                let b = *self.body_handles.get(config.body_b?)?;
+                // Joint X = axle, Y = suspension direction
+                let x = vector![axle[0], axle[1], axle[2]].normalize();
+                let up = vector![up[0], up[1], up[2]];
+                let y = (up - x * x.dot(&up)).normalize();
+                let rotation = UnitQuaternion::from_rotation_matrix(
+                    &Rotation3::from_matrix_unchecked(Matrix3::from_columns(&[x, y, x.cross(&y)])),
+                );
+                let frame = Isometry::from_parts(vector![anchor[0], anchor[1], anchor[2]].into(), rotation);
+                // Body B's frame is wherever the joint frame sits on it initially
+                let frame_b = self.rigid_body_set[b].position().inverse() * self.rigid_body_set[a].position() * frame;
+                let joint = GenericJointBuilder::new(
+                    JointAxesMask::LIN_X | JointAxesMask::LIN_Z | JointAxesMask::ANG_Y | JointAxesMask::ANG_Z,
+                )
+                .limits(JointAxis::LinY, [-travel, travel])
+                .motor_position(JointAxis::LinY, 0.0, stiffness, damping)
+                .motor_model(JointAxis::LinY, MotorModel::ForceBased)
+                .motor_model(JointAxis::AngX, MotorModel::ForceBased)
+                .local_frame1(frame)
+                .local_frame2(frame_b)
+                .contacts_enabled(false)
+                .build();
+                (a, b, joint, Some(frame))
+            }
+            JointKind::Spring { anchor_a, anchor_b, rest_length, stiffness, damping } => {
+                let spring = |anchor1: [f32; 3], anchor2: [f32; 3]| {
+                    SpringJointBuilder::new(rest_length.max(0.0), stiffness.max(0.0), damping.max(0.0))
+                        .local_anchor1(point![anchor1[0], anchor1[1], anchor1[2]])
+                        .local_anchor2(point![anchor2[0], anchor2[1], anchor2[2]])
+                        .build()
+                        .data
+                };
+                // A world anchor is a fixed body at the anchor point
+                let (b, anchor_b) = match config.body_b {
+                    Some(b) => (*self.body_handles.get(b)?, anchor_b),
+                    None => {
+                        let anchor = RigidBodyBuilder::fixed()
+                            .translation(vector![anchor_b[0], anchor_b[1], anchor_b[2]])
+                            .build();
+                        (self.rigid_body_set.insert(anchor), [0.0; 3])
+                    }
+                };
+                // Rapier ignores the rest length of springs whose second body
+                // is fixed, so a fixed end goes first
+                if self.rigid_body_set[b].is_fixed() {
+                    (b, a, spring(anchor_b, anchor_a), None)
+                } else {
+                    (a, b, spring(anchor_a, anchor_b), None)
+                }
+            }
+        };
+        let handle = self.impulse_joint_set.insert(body1, body2, joint, true);
+        Some(SceneJoint { handle, base_frame })
+    }
+
+    /// World-space end points of every spring joint, in joint order
+    pub fn spring_endpoints(&self) -> impl Iterator<Item = ([f32; 3], [f32; 3])> + '_ {
+        self.scene_joints.iter().flatten().filter(|j| j.base_frame.is_none()).filter_map(|j| {
+            let joint = self.impulse_joint_set.get(j.handle)?;
+            let a = self.rigid_body_set.get(joint.body1)?.position() * joint.data.local_anchor1();
+            let b = self.rigid_body_set.get(joint.body2)?.position() * joint.data.local_anchor2();
+            Some(([a.x, a.y, a.z], [b.x, b.y, b.z]))
+        })
+    }
+
+    /// Drive a wheel joint's spin toward `speed` (rad/s) with at most
+    /// `max_torque` (N·m); zero torque lets the wheel roll freely
+    ///
+    /// Returns false for an unknown joint or one that is not a wheel.
+    pub fn set_wheel_drive(&mut self, joint: usize, speed: f32, max_torque: f32) -> bool {
+        let Some(Some(SceneJoint { handle, base_frame: Some(_) })) = self.scene_joints.get(joint) else {
+            return false;
+        };
+        let Some(joint) = self.impulse_joint_set.get_mut(*handle, true) else {
+            return false;
+        };
+        // Stiff velocity servo: full torque from 1 rad/s of error
+        let max_torque = max_torque.max(0.0);
+        joint.data
+            .set_motor_velocity(JointAxis::AngX, speed, max_torque)
+            .set_motor_max_force(JointAxis::AngX, max_torque);
+        true
+    }
+
+    /// Turn a wheel joint's axle by `angle` (rad) about its suspension axis
+    ///
+    /// Returns false for an unknown joint or one that is not a wheel.
+    pub fn set_wheel_steering(&mut self, joint: usize, angle: f32) -> bool {
+        let Some(Some(SceneJoint { handle, base_frame: Some(base_frame) })) = self.scene_joints.get(joint) else {
+            return false;
+        };
+        let frame = base_frame * UnitQuaternion::from_axis_angle(&Vector::y_axis(), angle);
+        let Some(joint) = self.impulse_joint_set.get_mut(*handle, true) else {
+            return false;
+        };
+        joint.data.set_local_frame1(frame);
+        true
     }
 
     /// Step the physics simulation
     pub fn step(&mut self, dt: f32) {
         self.integration_parameters.dt = dt;
+        self.friction_hooks.dt = dt;
+        self.friction_hooks.gravity = self.gravity;
+
+        for handle in &self.gyroscopic_handles {
+            if let Some(body) = self.rigid_body_set.get_mut(*handle).filter(|b| b.is_dynamic()) {
+                apply_gyroscopic_torque(body, dt);
+            }
+        }
+        for follower in &mut self.path_followers {
+            follower.distance += follower.path.speed * dt;
+            if let Some(body) = self.rigid_body_set.get_mut(follower.handle) {
+                let [x, y, z] = follower.path.position_at(follower.distance);
+                body.set_next_kinematic_translation(vector![x, y, z]);
+            }
+        }
+        self.update_lod(dt);
 
         self.physics_pipeline.step(
             &self.gravity,
             &self.integration_parameters,
             &mut self.island_manager,
-            &mut self.broad_phase,
+            &mut *self.broad_phase,
             &mut self.narrow_phase,
             &mut self.rigid_body_set,
             &mut self.collider_set,
@@ -196,15 +695,561 @@ impl RapierBridge {
             &mut self.multibody_joint_set,
             &mut self.ccd_solver,
             Some(&mut self.query_pipeline),
-            &(),
-            &(),
+            &self.friction_hooks,
+            &self.impact_collector,
         );
     }
 
-    /// Sync Rapier state back to SOA storage
+    /// Simplify bodies far from the focus (see [`SimulationLod`]), or
+    /// simulate every body in full again with None
+    pub fn set_lod(&mut self, lod: Option<SimulationLod>) {
+        self.lod = lod;
+        if lod.is_none() {
+            for handle in std::mem::take(&mut self.coarse) {
+                self.set_coarse(handle, false);
+            }
+        }
+    }
+
+    /// Current level of simulation
+    pub fn lod(&self) -> Option<SimulationLod> {
+        self.lod
+    }
+
+    /// Whether a body is currently simplified by the level of simulation
+    pub fn is_coarse(&self, index: usize) -> bool {
+        self.body_handles.get(index).is_some_and(|handle| self.coarse.contains(handle))
+    }
+
+    /// Switch bodies between full and ballistic simulation by their
+    /// distance from the focus, then give coarse bodies one step of gravity
+    ///
+    /// Only awake dynamic bodies without joints are simplified. Coarse
+    /// bodies that would sink into the ground stop on it.
+    fn update_lod(&mut self, dt: f32) {
+        let Some(lod) = self.lod else { return };
+        let mut switches = Vec::new();
+        for &index in &self.dynamic_indices {
+            let handle = self.body_handles[index];
+            let Some(body) = self.rigid_body_set.get(handle) else { continue };
+            let t = body.translation();
+            let position = [t.x, t.y, t.z];
+            if self.coarse.contains(&handle) {
+                if lod.is_near(position) {
+                    switches.push((handle, false));
+                }
+            } else if body.is_dynamic()
+                && !body.is_sleeping()
+                && lod.is_far(position)
+                && self.impulse_joint_set.attached_joints(handle).next().is_none()
+            {
+                switches.push((handle, true));
+            }
+        }
+        for (handle, coarse) in switches {
+            if coarse {
+                self.coarse.insert(handle);
+            } else {
+                self.coarse.remove(&handle);
+            }
+            self.set_coarse(handle, coarse);
+        }
+
+        for &handle in &self.coarse {
+            let Some(body) = self.rigid_body_set.get_mut(handle) else { continue };
+            let linvel = *body.linvel() + self.gravity * dt;
+            let Some((top, half_size)) = self.ground else {
+                body.set_linvel(linvel, true);
+                continue;
+            };
+            let bottom = body.colliders().iter()
+                .filter_map(|&c| self.collider_set.get(c))
+                .map(|c| c.compute_aabb().mins.y)
+                .fold(f32::INFINITY, f32::min);
+            let t = *body.translation();
+            let over_ground = t.x.abs() <= half_size && t.z.abs() <= half_size;
+            if over_ground && linvel.y < 0.0 && bottom + linvel.y * dt <= top {
+                body.set_translation(t + vector![0.0, (top - bottom).max(0.0), 0.0], true);
+                body.set_linvel(Vector::zeros(), true);
+                body.set_angvel(Vector::zeros(), true);
+            } else {
+                body.set_linvel(linvel, true);
+            }
+        }
+    }
+
+    /// Make a body ballistic (kinematic, moving at its velocity, colliders
+    /// disabled) or fully simulated again, keeping its velocity
+    fn set_coarse(&mut self, handle: RigidBodyHandle, coarse: bool) {
+        let Some(body) = self.rigid_body_set.get_mut(handle) else { return };
+        let (linvel, angvel) = (*body.linvel(), *body.angvel());
+        let body_type = if coarse { RigidBodyType::KinematicVelocityBased } else { RigidBodyType::Dynamic };
+        body.set_body_type(body_type, true);
+        body.set_linvel(linvel, true);
+        body.set_angvel(angvel, true);
+        for &collider in body.colliders() {
+            if let Some(collider) = self.collider_set.get_mut(collider) {
+                collider.set_enabled(!coarse);
+            }
+        }
+    }
+
+    /// Gravity vector
+    pub fn gravity(&self) -> [f32; 3] {
+        [self.gravity.x, self.gravity.y, self.gravity.z]
+    }
+
+    /// Mass, linear momentum and angular momentum (about the center of mass)
+    /// of a body, world-space
+    pub fn momentum(&self, index: usize) -> Option<(f32, [f32; 3], [f32; 3])> {
+        let body = self.rigid_body_set.get(*self.body_handles.get(index)?)?;
+        let mprops = &body.mass_properties().local_mprops;
+        let frame = body.rotation() * mprops.principal_inertia_local_frame;
+        let w = frame.inverse_transform_vector(body.angvel());
+        let l = frame * mprops.principal_inertia().component_mul(&w);
+        let p = body.linvel() * body.mass();
+        Some((body.mass(), [p.x, p.y, p.z], [l.x, l.y, l.z]))
+    }
+
+    /// Append every pair of colliders with an active contact to `out`, with
+    /// the normal impulse the solver applied between them in the last step
+    pub fn contacts_into(&self, out: &mut Vec<BodyContact>) {
+        let user_data = |h: ColliderHandle| self.collider_set.get(h).map(|c| c.user_data);
+        for pair in self.narrow_phase.contact_pairs().filter(|p| p.has_any_active_contact) {
+            let (Some(u1), Some(u2)) = (user_data(pair.collider1), user_data(pair.collider2)) else {
+                continue;
+            };
+            let (body_a, body_b) = match (u1 != STATIC_USER_DATA, u2 != STATIC_USER_DATA) {
+                (true, true) => (u1.min(u2) as usize, Some(u1.max(u2) as usize)),
+                (true, false) => (u1 as usize, None),
+                (false, true) => (u2 as usize, None),
+                (false, false) => continue,
+            };
+            let impulse = pair.manifolds.iter()
+                .flat_map(|m| m.points.iter())
+                .map(|p| p.data.impulse)
+                .sum();
+            out.push(BodyContact { body_a, body_b, impulse });
+        }
+    }
+
+    /// Cast a ray and return the closest hit within `max_distance`
+    pub fn cast_ray(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32) -> Option<RayHit> {
+        let dir = vector![direction[0], direction[1], direction[2]];
+        let norm = dir.norm();
+        if norm < 1e-9 {
+            return None;
+        }
+        let ray = Ray::new(point![origin[0], origin[1], origin[2]], dir / norm);
+        let (handle, hit) = self.query_pipeline.cast_ray_and_get_normal(
+            &self.rigid_body_set,
+            &self.collider_set,
+            &ray,
+            max_distance,
+            true,
+            QueryFilter::default(),
+        )?;
+
+        let user_data = self.collider_set.get(handle)?.user_data;
+        let point = ray.point_at(hit.time_of_impact);
+        Some(RayHit {
+            body: (user_data != STATIC_USER_DATA).then_some(user_data as usize),
+            distance: hit.time_of_impact,
+            point: [point.x, point.y, point.z],
+            normal: [hit.normal.x, hit.normal.y, hit.normal.z],
+        })
+    }
+
+    /// Predict when body `a` first touches body `b` within `max_t` seconds
+    ///
+    /// Both bodies follow their current linear and angular velocities, and
+    /// awake dynamic bodies also fall under gravity; contacts, damping and
+    /// everything else are ignored. Sleeping and static bodies stay put.
+    /// Bodies touching now report time 0.
+    pub fn time_of_impact(&self, a: usize, b: usize, max_t: f32) -> Option<TimeOfImpact> {
+        let ha = *self.collider_handles.get(a)?;
+        let hb = *self.collider_handles.get(b)?;
+        if a == b {
+            return None;
+        }
+        if self.in_contact(ha, hb) {
+            let (ca, cb) = (self.collider_set.get(ha)?, self.collider_set.get(hb)?);
+            let contact = query::contact(ca.position(), ca.shape(), cb.position(), cb.shape(), 0.0).ok()??;
+            return Some(TimeOfImpact {
+                time: 0.0,
+                body: Some(b),
+                point: contact.point1.into(),
+                normal: contact.normal1.into_inner().into(),
+            });
+        }
+        self.sweep_pair(ha, hb, max_t)
+    }
+
+    /// Predict the next new contact of body `index` with any other body or
+    /// the ground within `max_t` seconds
+    ///
+    /// Uses the same motion model as [`RapierBridge::time_of_impact`] for
+    /// every collider. Contacts the body already has (e.g. the ground it
+    /// rests on) are skipped, so the result is the next upcoming collision.
+    pub fn predict_impact(&self, index: usize, max_t: f32) -> Option<TimeOfImpact> {
+        let handle = *self.collider_handles.get(index)?;
+        let sweep = self.sweep(handle)?;
+        let (min, max) = sweep.bounds(max_t);
+
+        let mut best: Option<TimeOfImpact> = None;
+        for (other, collider) in self.collider_set.iter() {
+            if other == handle || collider.parent().is_none() || self.in_contact(handle, other) {
+                continue;
+            }
+            let Some(other_sweep) = self.sweep(other) else {
+                continue;
+            };
+            // Skip colliders whose swept boxes never meet
+            let (other_min, other_max) = other_sweep.bounds(max_t);
+            if (0..3).any(|k| other_max[k] < min[k] || other_min[k] > max[k]) {
+                continue;
+            }
+            let limit = best.map_or(max_t, |hit| hit.time);
+            if let Some(hit) = self.sweep_pair(handle, other, limit) {
+                best = Some(hit);
+            }
+        }
+        best
+    }
+
+    /// Whether two colliders currently have an active contact
+    fn in_contact(&self, a: ColliderHandle, b: ColliderHandle) -> bool {
+        self.narrow_phase.contact_pair(a, b).is_some_and(|pair| pair.has_any_active_contact)
+    }
+
+    /// Ballistic motion of a collider's parent body
+    fn sweep(&self, handle: ColliderHandle) -> Option<Sweep> {
+        let collider = self.collider_set.get(handle)?;
+        let body = self.rigid_body_set.get(collider.parent()?)?;
+        let moving = body.is_dynamic() && !body.is_sleeping();
+        let (linvel, angvel, gravity) = if moving {
+            (*body.linvel(), *body.angvel(), self.gravity * body.gravity_scale())
+        } else {
+            (Vector::zeros(), Vector::zeros(), Vector::zeros())
+        };
+
+        let pose = *collider.position();
+        let center = *body.center_of_mass();
+        let bounding = collider.shape().compute_local_bounding_sphere();
+        let radius = bounding.radius() + (pose * bounding.center() - center).norm();
+        Some(Sweep {
+            motion: NonlinearRigidMotion::new(pose, pose.inverse_transform_point(&center), linvel, angvel),
+            gravity,
+            center,
+            radius,
+        })
+    }
+
+    /// First time of impact of two colliders within `max_t`, ignoring
+    /// contacts that are separating or resting at the start
+    fn sweep_pair(&self, a: ColliderHandle, b: ColliderHandle, max_t: f32) -> Option<TimeOfImpact> {
+        let (ca, cb) = (self.collider_set.get(a)?, self.collider_set.get(b)?);
+        let (sa, sb) = (self.sweep(a)?, self.sweep(b)?);
+        if max_t <= 0.0 {
+            return None;
+        }
+
+        // Chords of a parabola stay within g h^2 / 8 of it
+        let g = sa.gravity.norm().max(sb.gravity.norm());
+        let h = if g > 1e-6 { (8.0 * TOI_ARC_TOLERANCE / g).sqrt() } else { max_t };
+        let segments = (max_t / h).ceil().clamp(1.0, 4096.0) as u32;
+        let h = max_t / segments as f32;
+
+        for k in 0..segments {
+            let t0 = k as f32 * h;
+            let (ma, mb) = (sa.segment(t0, h), sb.segment(t0, h));
+            let Ok(Some(hit)) = query::cast_shapes_nonlinear(&ma, ca.shape(), &mb, cb.shape(), 0.0, h, false) else {
+                continue;
+            };
+            let pose = ma.position_at_time(hit.time_of_impact);
+            let point = pose * hit.witness1;
+            let normal = pose * hit.normal1.into_inner();
+            let user_data = cb.user_data;
+            return Some(TimeOfImpact {
+                time: t0 + hit.time_of_impact,
+                body: (user_data != STATIC_USER_DATA).then_some(user_data as usize),
+                point: point.into(),
+                normal: normal.into(),
+            });
+        }
+        None
+    }
+
+    /// Attach a mouse joint: a spring pulling `grab_point` (world space, fixed
+    /// to the body) toward a target that starts at the same point
+    ///
+    /// Stiffness and damping are per unit mass (1/s^2 and 1/s), so the same
+    /// values drag light and heavy bodies alike. Returns the joint id, or None
+    /// for an invalid or static body.
+    pub fn add_mouse_joint(&mut self, index: usize, grab_point: [f32; 3], stiffness: f32, damping: f32) -> Option<usize> {
+        let body_handle = *self.body_handles.get(index)?;
+        let body = self.rigid_body_set.get(body_handle).filter(|b| b.is_dynamic())?;
+        let target = point![grab_point[0], grab_point[1], grab_point[2]];
+        let local_anchor = body.position().inverse_transform_point(&target);
+
+        let anchor = self.rigid_body_set.insert(
+            RigidBodyBuilder::kinematic_position_based()
+                .translation(target.coords)
+                .build(),
+        );
+
+        // Independent spring per linear axis; rotation stays free
+        let mut joint = GenericJointBuilder::new(JointAxesMask::empty())
+            .local_anchor2(local_anchor)
+            .contacts_enabled(false);
+        for axis in [JointAxis::LinX, JointAxis::LinY, JointAxis::LinZ] {
+            joint = joint
+                .motor_position(axis, 0.0, stiffness, damping)
+                .motor_model(axis, MotorModel::AccelerationBased);
+        }
+        self.impulse_joint_set.insert(anchor, body_handle, joint, true);
+
+        self.mouse_joints.push(Some(MouseJoint { anchor, body: body_handle }));
+        Some(self.mouse_joints.len() - 1)
+    }
+
+    /// Move a mouse joint's target; returns false for an unknown id
+    pub fn set_mouse_joint_target(&mut self, id: usize, target: [f32; 3]) -> bool {
+        let Some(Some(joint)) = self.mouse_joints.get(id) else {
+            return false;
+        };
+        let (anchor, body) = (joint.anchor, joint.body);
+        if let Some(anchor) = self.rigid_body_set.get_mut(anchor) {
+            anchor.set_next_kinematic_translation(vector![target[0], target[1], target[2]]);
+        }
+        if let Some(body) = self.rigid_body_set.get_mut(body) {
+            body.wake_up(true);
+        }
+        true
+    }
+
+    /// Rigidly attach body `b` to body `a`; returns the attachment id
+    ///
+    /// `relative_pose` is B's position and rotation (x, y, z, w) in A's
+    /// frame; None keeps their current relative pose. A pose away from the
+    /// current one pulls B into place over the next steps. The attached
+    /// bodies stop colliding with each other. Returns None for an invalid
+    /// body index or if `a == b`.
+    pub fn attach(&mut self, a: usize, b: usize, relative_pose: Option<([f32; 3], [f32; 4])>) -> Option<usize> {
+        if a == b {
+            return None;
+        }
+        let (handle_a, handle_b) = (*self.body_handles.get(a)?, *self.body_handles.get(b)?);
+        let frame = match relative_pose {
+            Some((p, q)) => Isometry::from_parts(
+                vector![p[0], p[1], p[2]].into(),
+                UnitQuaternion::from_quaternion(Quaternion::new(q[3], q[0], q[1], q[2])),
+            ),
+            None => self.rigid_body_set[handle_a].position().inverse() * self.rigid_body_set[handle_b].position(),
+        };
+        let joint = FixedJointBuilder::new()
+            .local_frame1(frame)
+            .contacts_enabled(false);
+        let handle = self.impulse_joint_set.insert(handle_a, handle_b, joint, true);
+        self.attachments.push(Some(handle));
+        Some(self.attachments.len() - 1)
+    }
+
+    /// Remove an attachment; returns false for an unknown or detached id
+    pub fn detach(&mut self, id: usize) -> bool {
+        let Some(handle) = self.attachments.get_mut(id).and_then(Option::take) else {
+            return false;
+        };
+        self.impulse_joint_set.remove(handle, true);
+        true
+    }
+
+    /// Add a linear impulse through the center of mass and an angular impulse
+    /// to a dynamic body, waking it
+    ///
+    /// Returns false for an invalid or static body.
+    pub fn apply_impulse(&mut self, index: usize, linear: [f32; 3], angular: [f32; 3]) -> bool {
+        let Some(body) = self.dynamic_body_mut(index) else {
+            return false;
+        };
+        body.apply_impulse(vector![linear[0], linear[1], linear[2]], true);
+        body.apply_torque_impulse(vector![angular[0], angular[1], angular[2]], true);
+        true
+    }
+
+    /// Lock or unlock movement of a dynamic body along world axes (x, y, z)
+    ///
+    /// Velocity along newly locked axes is removed. In a planar scene z
+    /// stays locked. Returns false for an invalid or static body.
+    pub fn lock_translations(&mut self, index: usize, axes: [bool; 3]) -> bool {
+        let planar = self.planar;
+        let Some(body) = self.dynamic_body_mut(index) else {
+            return false;
+        };
+        let (axes, rotations) = with_planar_locks(planar, axes, locked_rotations(body.locked_axes()));
+        body.set_locked_axes(locked_axes(axes, rotations), true);
+        let v = unlocked((*body.linvel()).into(), axes);
+        body.set_linvel(vector![v[0], v[1], v[2]], true);
+        true
+    }
+
+    /// Lock or unlock rotation of a dynamic body about world axes (x, y, z)
+    ///
+    /// Spin about newly locked axes is removed. In a planar scene x and y
+    /// stay locked. Returns false for an invalid or static body.
+    pub fn lock_rotations(&mut self, index: usize, axes: [bool; 3]) -> bool {
+        let planar = self.planar;
+        let Some(body) = self.dynamic_body_mut(index) else {
+            return false;
+        };
+        let (translations, axes) = with_planar_locks(planar, locked_translations(body.locked_axes()), axes);
+        body.set_locked_axes(locked_axes(translations, axes), true);
+        let w = unlocked((*body.angvel()).into(), axes);
+        body.set_angvel(vector![w[0], w[1], w[2]], true);
+        true
+    }
+
+    /// Rapier body of a dynamic SOA index
+    fn dynamic_body_mut(&mut self, index: usize) -> Option<&mut RigidBody> {
+        let handle = *self.body_handles.get(index)?;
+        self.rigid_body_set.get_mut(handle).filter(|b| b.is_dynamic())
+    }
+
+    /// Remove a mouse joint and its target anchor; returns false for an unknown id
+    pub fn remove_mouse_joint(&mut self, id: usize) -> bool {
+        let Some(joint) = self.mouse_joints.get_mut(id).and_then(Option::take) else {
+            return false;
+        };
+        // Removing the anchor also removes the joint attached to it
+        self.rigid_body_set.remove(
+            joint.anchor,
+            &mut self.island_manager,
+            &mut self.collider_set,
+            &mut self.impulse_joint_set,
+            &mut self.multibody_joint_set,
+            true,
+        );
+        true
+    }
+
+    /// Constraint island of every body, numbered by lowest body index
+    ///
+    /// Dynamic bodies linked through active contacts or joints share an
+    /// island, the unit Rapier wakes and puts to sleep together. As in
+    /// Rapier, static bodies and the ground do not link islands; they get
+    /// None.
+    pub fn island_ids(&self) -> Vec<Option<usize>> {
+        let n = self.body_handles.len();
+        let mut parent: Vec<usize> = (0..n).collect();
+        let dynamic = |i: usize| self.rigid_body_set.get(self.body_handles[i]).is_some_and(|b| b.is_dynamic());
+        let mut link = |a: usize, b: usize| {
+            if a < n && b < n && dynamic(a) && dynamic(b) {
+                let (ra, rb) = (find_root(&mut parent, a), find_root(&mut parent, b));
+                parent[ra.max(rb)] = ra.min(rb);
+            }
+        };
+
+        let index_of_collider = |h: ColliderHandle| {
+            self.collider_set.get(h)
+                .map(|c| c.user_data)
+                .filter(|&u| u != STATIC_USER_DATA)
+                .map(|u| u as usize)
+        };
+        for pair in self.narrow_phase.contact_pairs().filter(|p| p.has_any_active_contact) {
+            if let (Some(a), Some(b)) = (index_of_collider(pair.collider1), index_of_collider(pair.collider2)) {
+                link(a, b);
+            }
+        }
+        let index_of_body = |h: RigidBodyHandle| self.body_handles.iter().position(|&b| b == h);
+        for (_, joint) in self.impulse_joint_set.iter() {
+            if let (Some(a), Some(b)) = (index_of_body(joint.body1), index_of_body(joint.body2)) {
+                link(a, b);
+            }
+        }
+
+        // Dense ids in order of each island's lowest body index
+        let mut ids = vec![None; n];
+        let mut next = 0;
+        for i in 0..n {
+            if !dynamic(i) {
+                continue;
+            }
+            let root = find_root(&mut parent, i);
+            if root == i {
+                ids[i] = Some(next);
+                next += 1;
+            } else {
+                ids[i] = ids[root];
+            }
+        }
+        ids
+    }
+
+    /// Turn a dynamic body into a fixed one until [`RapierBridge::unfreeze`]
+    ///
+    /// The body keeps its pose and costs the solver nothing; bodies landing
+    /// on it collide as with static geometry and, unlike a sleeping body,
+    /// never wake it. Returns false for invalid, static, kinematic or
+    /// already frozen bodies.
+    pub fn freeze(&mut self, index: usize) -> bool {
+        let Some(&handle) = self.body_handles.get(index) else { return false };
+        let Some(body) = self.rigid_body_set.get_mut(handle) else { return false };
+        if !body.is_dynamic() {
+            return false;
+        }
+        body.set_body_type(RigidBodyType::Fixed, false);
+        self.frozen.push(handle);
+        true
+    }
+
+    /// Make a body frozen with [`RapierBridge::freeze`] dynamic again, at
+    /// rest and awake; returns false if it was not frozen
+    pub fn unfreeze(&mut self, index: usize) -> bool {
+        let Some(&handle) = self.body_handles.get(index) else { return false };
+        let Some(slot) = self.frozen.iter().position(|&h| h == handle) else { return false };
+        self.frozen.swap_remove(slot);
+        if let Some(body) = self.rigid_body_set.get_mut(handle) {
+            body.set_body_type(RigidBodyType::Dynamic, true);
+        }
+        true
+    }
+
+    /// Whether a body is frozen (see [`RapierBridge::freeze`])
+    pub fn is_frozen(&self, index: usize) -> bool {
+        self.body_handles.get(index).is_some_and(|handle| self.frozen.contains(handle))
+    }
+
+    /// Pairs of neighboring or jointed bodies with extreme mass ratios
+    /// found by the last [`RapierBridge::build_from_scene`]
+    ///
+    /// Such stacks and chains jitter or sag with few solver iterations, so
+    /// the build raises the iterations to what the worst pair needs
+    /// (16 at 100:1, up to 32) if the scene's setting is lower.
+    pub fn build_warnings(&self) -> &[SceneIssue] {
+        &self.build_warnings
+    }
+
+    /// Whether a body is asleep (static bodies count as asleep)
+    pub fn is_sleeping(&self, index: usize) -> bool {
+        self.body_handles.get(index)
+            .and_then(|&h| self.rigid_body_set.get(h))
+            .is_none_or(|b| !b.is_dynamic() || b.is_sleeping())
+    }
+
+    /// Take the impacts recorded since the last call (times are left at 0.0)
+    pub fn take_impacts(&self) -> Vec<ImpactEvent> {
+        self.impact_collector.drain()
+    }
+
+    /// Append the impacts recorded since the last call to `out` (times are left at 0.0)
+    pub fn take_impacts_into(&self, out: &mut Vec<ImpactEvent>) {
+        self.impact_collector.drain_into(out);
+    }
+
+    /// Sync Rapier state of dynamic bodies back to SOA storage
     pub fn sync_to_storage(&self, storage: &mut RigidBodyStorage) {
-        for (i, handle) in self.body_handles.iter().enumerate() {
-            if let Some(body) = self.rigid_body_set.get(*handle) {
+        for &i in &self.dynamic_indices {
+            if let Some(body) = self.rigid_body_set.get(self.body_handles[i]) {
                 let pos = body.translation();
                 let rot = body.rotation();
                 let lin_vel = body.linvel();
@@ -223,3 +1268,103 @@ impl RapierBridge {
         self.body_handles.len()
     }
 }
+
+/// Collider of one shape, before material, mass and user data are set
+fn shape_collider(shape: ShapeType, half_extents: [f32; 3], radius: f32, half_height: f32) -> ColliderBuilder {
+    match shape {
+        ShapeType::Cube => ColliderBuilder::cuboid(half_extents[0], half_extents[1], half_extents[2]),
+        ShapeType::Sphere => ColliderBuilder::ball(radius),
+        ShapeType::Capsule => ColliderBuilder::capsule_y(half_height, radius),
+        ShapeType::Cylinder => ColliderBuilder::cylinder(half_height, radius),
+        // Centered halfway up the axis; Rapier puts the center of mass a
+        // quarter of the height above the base
+        ShapeType::Cone => ColliderBuilder::cone(half_height, radius),
+    }
+}
+
+/// Distance from a shape's center to its farthest point
+fn shape_bounding_radius(shape: ShapeType, half_extents: [f32; 3], radius: f32, half_height: f32) -> f32 {
+    match shape {
+        ShapeType::Cube => (half_extents[0].powi(2) + half_extents[1].powi(2) + half_extents[2].powi(2)).sqrt(),
+        ShapeType::Sphere => radius,
+        ShapeType::Capsule => half_height + radius,
+        ShapeType::Cylinder | ShapeType::Cone => half_height.hypot(radius),
+    }
+}
+
+/// Root of `i` in a union-find forest, compressing the path
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Map a scene combine rule to Rapier's
+fn combine_rule(rule: CombineRule) -> CoefficientCombineRule {
+    match rule {
+        CombineRule::Average => CoefficientCombineRule::Average,
+        CombineRule::Min => CoefficientCombineRule::Min,
+        CombineRule::Multiply => CoefficientCombineRule::Multiply,
+        CombineRule::Max => CoefficientCombineRule::Max,
+    }
+}
+
+/// Rapier lock flags for per-axis translation and rotation locks
+fn locked_axes(translations: [bool; 3], rotations: [bool; 3]) -> LockedAxes {
+    let mut flags = LockedAxes::empty();
+    flags.set(LockedAxes::TRANSLATION_LOCKED_X, translations[0]);
+    flags.set(LockedAxes::TRANSLATION_LOCKED_Y, translations[1]);
+    flags.set(LockedAxes::TRANSLATION_LOCKED_Z, translations[2]);
+    flags.set(LockedAxes::ROTATION_LOCKED_X, rotations[0]);
+    flags.set(LockedAxes::ROTATION_LOCKED_Y, rotations[1]);
+    flags.set(LockedAxes::ROTATION_LOCKED_Z, rotations[2]);
+    flags
+}
+
+/// Translation and rotation locks with those of a planar scene added: z
+/// movement and x / y rotation
+fn with_planar_locks(planar: bool, mut translations: [bool; 3], mut rotations: [bool; 3]) -> ([bool; 3], [bool; 3]) {
+    if planar {
+        translations[2] = true;
+        rotations[0] = true;
+        rotations[1] = true;
+    }
+    (translations, rotations)
+}
+
+fn locked_translations(flags: LockedAxes) -> [bool; 3] {
+    [LockedAxes::TRANSLATION_LOCKED_X, LockedAxes::TRANSLATION_LOCKED_Y, LockedAxes::TRANSLATION_LOCKED_Z]
+        .map(|axis| flags.contains(axis))
+}
+
+fn locked_rotations(flags: LockedAxes) -> [bool; 3] {
+    [LockedAxes::ROTATION_LOCKED_X, LockedAxes::ROTATION_LOCKED_Y, LockedAxes::ROTATION_LOCKED_Z]
+        .map(|axis| flags.contains(axis))
+}
+
+/// A vector with its locked components zeroed
+fn unlocked(v: [f32; 3], locked: [bool; 3]) -> [f32; 3] {
+    std::array::from_fn(|k| if locked[k] { 0.0 } else { v[k] })
+}
+
+/// Integrate the gyroscopic term w x (I w) implicitly over one step
+///
+/// Rapier keeps angular velocity constant between contacts; without this term
+/// a spinning top keeps its spin axis instead of precessing. One Newton
+/// iteration of the implicit update in the principal frame stays stable at
+/// high spin rates where the explicit torque would blow up.
+fn apply_gyroscopic_torque(body: &mut RigidBody, dt: f32) {
+    let mprops = &body.mass_properties().local_mprops;
+    let inertia = Matrix3::from_diagonal(&mprops.principal_inertia());
+    let frame = body.rotation() * mprops.principal_inertia_local_frame;
+
+    let w = frame.inverse_transform_vector(body.angvel());
+    let residual = dt * w.cross(&(inertia * w));
+    let jacobian = inertia + dt * (w.cross_matrix() * inertia - (inertia * w).cross_matrix());
+    if let Some(inverse) = jacobian.try_inverse() {
+        let w = w - inverse * residual;
+        body.set_angvel(frame * w, true);
+    }
+}
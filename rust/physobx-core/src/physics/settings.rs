@@ -0,0 +1,187 @@
+//! Global physics settings applied when building a scene
+
+use super::aero::WindField;
+use super::broad_phase::BroadPhaseKind;
+use crate::scene::builder::{RigidBodyConfig, SceneBuilder};
+use crate::simulator::DEFAULT_FIXED_DT;
+
+/// How the friction or restitution coefficients of two touching bodies combine
+///
+/// When the two bodies use different rules, the one listed last wins
+/// (Max beats Multiply beats Min beats Average).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CombineRule {
+    /// (a + b) / 2
+    #[default]
+    Average,
+    /// min(a, b)
+    Min,
+    /// a * b
+    Multiply,
+    /// max(a, b)
+    Max,
+}
+
+impl CombineRule {
+    /// Parse a rule name ("average", "min", "multiply", "max")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "average" | "avg" => Some(CombineRule::Average),
+            "min" => Some(CombineRule::Min),
+            "multiply" | "mul" => Some(CombineRule::Multiply),
+            "max" => Some(CombineRule::Max),
+            _ => None,
+        }
+    }
+}
+
+/// Scene-wide physics settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhysicsSettings {
+    /// Default friction combine rule (ground and bodies without an override)
+    pub friction_combine: CombineRule,
+    /// Default restitution combine rule (ground and bodies without an override)
+    pub restitution_combine: CombineRule,
+    /// Meters per scene unit (1 = SI); see
+    /// [`crate::SceneBuilder::set_length_scale`]
+    pub length_scale: f32,
+    /// Gravity vector (scene units/s^2)
+    pub gravity: [f32; 3],
+    /// Friction coefficient of the ground plane
+    pub ground_friction: f32,
+    /// Restitution of the ground plane
+    pub ground_restitution: f32,
+    /// Broad-phase algorithm
+    pub broad_phase: BroadPhaseKind,
+    /// Margin by which the BVH broad-phase enlarges collider boxes (scene
+    /// units)
+    pub bvh_margin: f32,
+    /// Distance below which contacts are predicted, as a fraction of the
+    /// length unit (Rapier's `normalized_prediction_distance`)
+    pub prediction_distance: f32,
+    /// Constrain every dynamic body to its xy plane, rotating only about z
+    pub planar: bool,
+    /// Air moving over the scene, felt by bodies with aerodynamic coefficients
+    pub wind: WindField,
+    /// Physics step size (s) simulators of the scene start with (see
+    /// [`crate::Simulator::set_fixed_dt`])
+    pub fixed_dt: f32,
+    /// Velocity iterations of the Rapier solver per step; more keep stacks
+    /// of very different masses and long joint chains stiff. Raised when
+    /// building scenes with extreme mass ratios (see
+    /// [`crate::RapierBridge::build_warnings`]).
+    pub solver_iterations: u32,
+    /// Bodies starting at least this fast (scene units/s, spin counted at
+    /// their surface) use continuous collision detection; 0 gives it to
+    /// every dynamic body
+    pub ccd_speed: f32,
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        Self {
+            friction_combine: CombineRule::default(),
+            restitution_combine: CombineRule::default(),
+            length_scale: 1.0,
+            gravity: [0.0, -9.81, 0.0],
+            ground_friction: 0.5,
+            ground_restitution: 0.3,
+            broad_phase: BroadPhaseKind::default(),
+            bvh_margin: 0.05,
+            prediction_distance: 0.002,
+            planar: false,
+            wind: WindField::default(),
+            fixed_dt: DEFAULT_FIXED_DT,
+            solver_iterations: 8,
+            ccd_speed: 10.0,
+        }
+    }
+}
+
+/// Shortest step [`PhysicsSettings::auto_tune`] picks (2 kHz)
+const MIN_AUTO_DT: f32 = 1.0 / 2000.0;
+
+/// Most solver iterations [`PhysicsSettings::auto_tune`] and mass-ratio
+/// warnings pick
+const MAX_AUTO_ITERATIONS: u32 = 32;
+
+impl PhysicsSettings {
+    /// Settings of `scene` with the step size, CCD speed and solver
+    /// iterations picked for its dynamic bodies
+    ///
+    /// - The step is short enough that no body moves more than half the
+    ///   smallest body's size per step, counting the speed it gains falling
+    ///   to the ground, and that gravity moves a resting body by under 1% of
+    ///   that size per step. It lies between about 1/2000 s and the default
+    ///   1/240 s and divides a 60 fps frame into whole steps.
+    /// - CCD goes to every dynamic body when even the shortest step is too
+    ///   long for that, and to bodies too fast for the step otherwise.
+    /// - Solver iterations grow with the ratio of the heaviest to the
+    ///   lightest dynamic body: 8 for equal masses, 16 at 100:1, at most 32.
+    ///
+    /// Scenes without dynamic bodies keep their settings.
+    pub fn auto_tune(scene: &SceneBuilder) -> PhysicsSettings {
+        let mut settings = scene.physics.clone();
+        let dynamic: Vec<&RigidBodyConfig> = scene.bodies.iter()
+            .filter(|b| !b.is_static && b.motion_path.is_none())
+            .filter(|b| {
+                let mass = b.resolved_mass(scene.physics.length_scale);
+                mass.is_finite() && mass > 0.0 && b.position.iter().all(|p| p.is_finite())
+            })
+            .collect();
+        let smallest = dynamic.iter()
+            .map(|b| 2.0 * b.bounding_half_extents().into_iter().fold(f32::INFINITY, f32::min))
+            .filter(|size| size.is_finite() && *size > 0.0)
+            .fold(f32::INFINITY, f32::min);
+        if !smallest.is_finite() {
+            return settings;
+        }
+
+        // Bodies fall to the ground, or to the lowest body without one
+        let gravity = settings.gravity.iter().map(|g| g * g).sum::<f32>().sqrt();
+        let floor = match scene.ground_y {
+            Some(y) => y + 0.1,
+            None => scene.bodies.iter()
+                .map(|b| b.position[1] - b.bounding_half_extents()[1])
+                .filter(|y| y.is_finite())
+                .fold(f32::INFINITY, f32::min),
+        };
+        let fastest = dynamic.iter()
+            .map(|b| {
+                let h = b.bounding_half_extents();
+                let length = |v: [f32; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+                let drop = (b.position[1] - h[1] - floor).max(0.0);
+                length(b.velocity) + length(b.angular_velocity) * length(h) + (2.0 * gravity * drop).sqrt()
+            })
+            .filter(|speed| speed.is_finite())
+            .fold(0.0f32, f32::max);
+
+        let mut dt = DEFAULT_FIXED_DT;
+        if gravity > 0.0 {
+            dt = dt.min((0.01 * smallest / gravity).sqrt());
+        }
+        let motion_dt = if fastest > 0.0 { 0.5 * smallest / fastest } else { f32::INFINITY };
+        dt = dt.min(motion_dt).max(MIN_AUTO_DT);
+        // Whole steps per 60 fps frame (the tolerance keeps 1/240 at 4)
+        let steps = ((1.0 / 60.0) / dt - 1e-3).ceil().max(1.0);
+        settings.fixed_dt = 1.0 / (60.0 * steps);
+        settings.ccd_speed = if motion_dt < MIN_AUTO_DT {
+            0.0
+        } else {
+            0.5 * smallest / settings.fixed_dt
+        };
+
+        let (lightest, heaviest) = dynamic.iter()
+            .map(|b| b.resolved_mass(scene.physics.length_scale))
+            .fold((f32::INFINITY, 0.0f32), |(lo, hi), mass| (lo.min(mass), hi.max(mass)));
+        settings.solver_iterations = iterations_for_mass_ratio(heaviest / lightest);
+        settings
+    }
+}
+
+/// Solver iterations for bodies whose masses differ by `ratio`: 8 for
+/// equal masses, 16 at 100:1, at most 32
+pub(crate) fn iterations_for_mass_ratio(ratio: f32) -> u32 {
+    let iterations = (8.0 * (1.0 + 0.5 * ratio.max(1.0).log10())).ceil() as u32;
+    iterations.clamp(8, MAX_AUTO_ITERATIONS)
+}
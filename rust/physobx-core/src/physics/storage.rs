@@ -1,8 +1,10 @@
 //! SOA (Structure of Arrays) storage for rigid body data
 //!
-//! This provides cache-friendly, SIMD-optimized storage for physics state.
+//! This provides cache-friendly storage for physics state; bulk kernels over
+//! these arrays live in [`crate::simd`].
 
 use crate::scene::builder::ShapeType;
+use crate::scene::materials::SurfaceLook;
 
 /// SOA storage for rigid body state
 #[derive(Debug, Default)]
@@ -17,12 +19,82 @@ pub struct RigidBodyStorage {
     pub angular_velocities: Vec<[f32; 3]>,
     /// Masses
     pub masses: Vec<f32>,
-    /// Shape types (0 = cube, 1 = sphere)
+    /// Shape type ids (see [`ShapeType::id`])
     pub shape_types: Vec<u8>,
-    /// Radii (for spheres) or half-extents (for cubes)
+    /// Radii (for spheres and axial shapes) or half-extents (for cubes)
     pub radii: Vec<f32>,
+    /// Half length along local Y of axial shapes (0 for cubes and spheres)
+    pub half_heights: Vec<f32>,
     /// Colors (RGB)
     pub colors: Vec<[f32; 3]>,
+    /// Shading (roughness, metallic), see [`SurfaceLook`]
+    pub surfaces: Vec<[f32; 2]>,
+    /// Static (fixed) bodies: never move, skipped by sync and per-frame uploads
+    ///
+    /// Change with [`RigidBodyStorage::set_static`] so the shape partitions
+    /// stay in sync.
+    pub statics: Vec<bool>,
+    /// Persistent index lists of every shape, by shape id
+    partitions: [ShapePartition; ShapeType::ALL.len()],
+}
+
+/// Sorted indices of one shape's bodies, maintained as bodies are added
+///
+/// Render extraction reads these instead of rescanning `shape_types` and
+/// `statics` every frame.
+#[derive(Debug, Default)]
+struct ShapePartition {
+    all: Vec<usize>,
+    dynamic: Vec<usize>,
+    fixed: Vec<usize>,
+}
+
+impl ShapePartition {
+    fn clear(&mut self) {
+        self.all.clear();
+        self.dynamic.clear();
+        self.fixed.clear();
+    }
+
+    /// Add a new dynamic `index` at its sorted position
+    fn insert(&mut self, index: usize) {
+        for list in [&mut self.all, &mut self.dynamic] {
+            if let Err(pos) = list.binary_search(&index) {
+                list.insert(pos, index);
+            }
+        }
+    }
+
+    /// Drop `index` from every list
+    fn remove(&mut self, index: usize) {
+        for list in [&mut self.all, &mut self.dynamic, &mut self.fixed] {
+            if let Ok(pos) = list.binary_search(&index) {
+                list.remove(pos);
+            }
+        }
+    }
+
+    /// Drop every index from `len` on
+    fn truncate(&mut self, len: usize) {
+        for list in [&mut self.all, &mut self.dynamic, &mut self.fixed] {
+            list.truncate(list.partition_point(|&i| i < len));
+        }
+    }
+
+    /// Move `index` between the dynamic and static lists
+    fn set_static(&mut self, index: usize, is_static: bool) {
+        let (from, to) = if is_static {
+            (&mut self.dynamic, &mut self.fixed)
+        } else {
+            (&mut self.fixed, &mut self.dynamic)
+        };
+        if let Ok(pos) = from.binary_search(&index) {
+            from.remove(pos);
+        }
+        if let Err(pos) = to.binary_search(&index) {
+            to.insert(pos, index);
+        }
+    }
 }
 
 impl RigidBodyStorage {
@@ -36,7 +108,11 @@ impl RigidBodyStorage {
             masses: Vec::with_capacity(capacity),
             shape_types: Vec::with_capacity(capacity),
             radii: Vec::with_capacity(capacity),
+            half_heights: Vec::with_capacity(capacity),
             colors: Vec::with_capacity(capacity),
+            surfaces: Vec::with_capacity(capacity),
+            statics: Vec::with_capacity(capacity),
+            partitions: Default::default(),
         }
     }
 
@@ -57,10 +133,11 @@ impl RigidBodyStorage {
         rotation: [f32; 4],
         mass: f32,
     ) -> usize {
-        self.push_with_shape(position, rotation, mass, ShapeType::Cube, 0.5, 0.5, [0.82, 0.32, 0.12])
+        self.push_with_shape(position, rotation, mass, ShapeType::Cube, 0.5, 0.5, 0.0, [0.82, 0.32, 0.12])
     }
 
     /// Add a new rigid body with shape info
+    #[allow(clippy::too_many_arguments)]
     pub fn push_with_shape(
         &mut self,
         position: [f32; 3],
@@ -69,6 +146,7 @@ impl RigidBodyStorage {
         shape: ShapeType,
         radius: f32,
         half_extent: f32,
+        half_height: f32,
         color: [f32; 3],
     ) -> usize {
         let index = self.positions.len();
@@ -77,18 +155,79 @@ impl RigidBodyStorage {
         self.linear_velocities.push([0.0, 0.0, 0.0]);
         self.angular_velocities.push([0.0, 0.0, 0.0]);
         self.masses.push(mass);
-        self.shape_types.push(match shape {
-            ShapeType::Cube => 0,
-            ShapeType::Sphere => 1,
-        });
+        self.shape_types.push(shape.id());
         self.radii.push(match shape {
-            ShapeType::Sphere => radius,
             ShapeType::Cube => half_extent,
+            _ => radius,
         });
+        self.half_heights.push(if shape.axial_slot().is_some() { half_height } else { 0.0 });
         self.colors.push(color);
+        self.surfaces.push([SurfaceLook::DEFAULT_ROUGHNESS, SurfaceLook::DEFAULT_METALLIC]);
+        self.statics.push(false);
+
+        // Indices only grow, so pushing keeps the lists sorted
+        let partition = self.partition_mut(shape);
+        partition.all.push(index);
+        partition.dynamic.push(index);
         index
     }
 
+    /// Overwrite body `index` with a new body (dynamic, at rest), which may
+    /// have a different shape
+    #[allow(clippy::too_many_arguments)]
+    pub fn replace_with_shape(
+        &mut self,
+        index: usize,
+        position: [f32; 3],
+        rotation: [f32; 4],
+        mass: f32,
+        shape: ShapeType,
+        radius: f32,
+        half_extent: f32,
+        half_height: f32,
+        color: [f32; 3],
+    ) {
+        if index >= self.len() {
+            return;
+        }
+        if let Some(old) = ShapeType::from_id(self.shape_types[index]) {
+            self.partition_mut(old).remove(index);
+        }
+        self.positions[index] = position;
+        self.rotations[index] = rotation;
+        self.linear_velocities[index] = [0.0, 0.0, 0.0];
+        self.angular_velocities[index] = [0.0, 0.0, 0.0];
+        self.masses[index] = mass;
+        self.shape_types[index] = shape.id();
+        self.radii[index] = match shape {
+            ShapeType::Cube => half_extent,
+            _ => radius,
+        };
+        self.half_heights[index] = if shape.axial_slot().is_some() { half_height } else { 0.0 };
+        self.colors[index] = color;
+        self.surfaces[index] = [SurfaceLook::DEFAULT_ROUGHNESS, SurfaceLook::DEFAULT_METALLIC];
+        self.statics[index] = false;
+        self.partition_mut(shape).insert(index);
+    }
+
+    /// Remove all bodies from `len` on
+    pub fn truncate(&mut self, len: usize) {
+        self.positions.truncate(len);
+        self.rotations.truncate(len);
+        self.linear_velocities.truncate(len);
+        self.angular_velocities.truncate(len);
+        self.masses.truncate(len);
+        self.shape_types.truncate(len);
+        self.radii.truncate(len);
+        self.half_heights.truncate(len);
+        self.colors.truncate(len);
+        self.surfaces.truncate(len);
+        self.statics.truncate(len);
+        for partition in &mut self.partitions {
+            partition.truncate(len);
+        }
+    }
+
     /// Clear all bodies
     pub fn clear(&mut self) {
         self.positions.clear();
@@ -98,22 +237,113 @@ impl RigidBodyStorage {
         self.masses.clear();
         self.shape_types.clear();
         self.radii.clear();
+        self.half_heights.clear();
         self.colors.clear();
+        self.surfaces.clear();
+        self.statics.clear();
+        for partition in &mut self.partitions {
+            partition.clear();
+        }
     }
 
     /// Get cube indices
     pub fn cube_indices(&self) -> Vec<usize> {
-        self.shape_types.iter().enumerate()
-            .filter(|(_, &t)| t == 0)
-            .map(|(i, _)| i)
-            .collect()
+        self.indices_of(ShapeType::Cube, None).to_vec()
     }
 
     /// Get sphere indices
     pub fn sphere_indices(&self) -> Vec<usize> {
-        self.shape_types.iter().enumerate()
-            .filter(|(_, &t)| t == 1)
+        self.indices_of(ShapeType::Sphere, None).to_vec()
+    }
+
+    /// Sorted indices of all bodies of a shape, or only its static
+    /// (`Some(true)`) or dynamic (`Some(false)`) ones
+    ///
+    /// The lists are maintained as bodies are added, so this does not scan.
+    pub fn indices_of(&self, shape: ShapeType, statics: Option<bool>) -> &[usize] {
+        let partition = &self.partitions[shape.id() as usize];
+        match statics {
+            None => &partition.all,
+            Some(false) => &partition.dynamic,
+            Some(true) => &partition.fixed,
+        }
+    }
+
+    fn partition_mut(&mut self, shape: ShapeType) -> &mut ShapePartition {
+        &mut self.partitions[shape.id() as usize]
+    }
+
+    /// Whether a body is static
+    pub fn is_static(&self, index: usize) -> bool {
+        self.statics.get(index).copied().unwrap_or(false)
+    }
+
+    /// Mark a body static or dynamic in storage (does not change its Rapier body)
+    pub fn set_static(&mut self, index: usize, is_static: bool) {
+        let Some(flag) = self.statics.get_mut(index) else {
+            return;
+        };
+        if *flag == is_static {
+            return;
+        }
+        *flag = is_static;
+        if let Some(shape) = ShapeType::from_id(self.shape_types[index]) {
+            self.partition_mut(shape).set_static(index, is_static);
+        }
+    }
+
+    /// Indices of static bodies (the partition uploaded once for rendering)
+    pub fn static_indices(&self) -> Vec<usize> {
+        self.statics.iter().enumerate()
+            .filter(|(_, &s)| s)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Indices of dynamic bodies (the partition synced and uploaded every frame)
+    pub fn dynamic_indices(&self) -> Vec<usize> {
+        self.statics.iter().enumerate()
+            .filter(|(_, &s)| !s)
             .map(|(i, _)| i)
             .collect()
     }
+
+    /// World-space axis-aligned bounding box (min, max) of a body
+    pub fn aabb(&self, index: usize) -> ([f32; 3], [f32; 3]) {
+        let p = self.positions[index];
+        let (half_extents, round) = bound_terms(self.shape_types[index], self.radii[index], self.half_heights[index]);
+        // Rotated box: each world axis spans the projections of all local
+        // half extents, plus the rounding radius
+        let [x, y, z, w] = self.rotations[index];
+        let m = [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+        ];
+        let extent = m.map(|row| round + (0..3).map(|k| row[k].abs() * half_extents[k]).sum::<f32>());
+        (
+            [p[0] - extent[0], p[1] - extent[1], p[2] - extent[2]],
+            [p[0] + extent[0], p[1] + extent[1], p[2] + extent[2]],
+        )
+    }
+
+    /// World-space AABBs (mins, maxs) of every body, computed with SIMD
+    pub fn aabbs(&self) -> (Vec<[f32; 3]>, Vec<[f32; 3]>) {
+        let mut min = vec![[0.0; 3]; self.len()];
+        let mut max = vec![[0.0; 3]; self.len()];
+        crate::simd::aabbs_into(&self.positions, &self.rotations, &self.radii, &self.half_heights, &self.shape_types, &mut min, &mut max);
+        (min, max)
+    }
+}
+
+/// Local half extents and rounding radius whose rotated box, grown by the
+/// radius, bounds a body: cubes, cylinders and cones are all box, spheres
+/// all rounding and capsules a segment along local Y swept by their radius
+pub(crate) fn bound_terms(shape_type: u8, radius: f32, half_height: f32) -> ([f32; 3], f32) {
+    match ShapeType::from_id(shape_type) {
+        Some(ShapeType::Sphere) => ([0.0; 3], radius),
+        Some(ShapeType::Capsule) => ([0.0, half_height, 0.0], radius),
+        Some(ShapeType::Cylinder | ShapeType::Cone) => ([radius, half_height, radius], 0.0),
+        _ => ([radius; 3], 0.0),
+    }
 }
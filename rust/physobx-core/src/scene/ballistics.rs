@@ -0,0 +1,179 @@
+//! Projectile aiming: launch velocities that hit a target point
+//!
+//! The closed-form solution assumes an ideal parabola. The refined solver
+//! simulates the projectile alone with the scene's physics and corrects the
+//! aim point by the miss, absorbing integrator error and damping.
+
+use super::builder::SceneBuilder;
+use crate::simulator::Simulator;
+
+/// Miss distance at which the refined solver stops (m)
+pub const AIM_TOLERANCE: f32 = 1e-3;
+
+/// Correction rounds of the refined solver
+const REFINE_ITERATIONS: u32 = 6;
+
+/// Launch velocity of magnitude `speed` that carries a point from `start`
+/// through `target` under constant `gravity`
+///
+/// Picks the flat (`high_arc = false`) or lobbed trajectory. Returns None if
+/// the target is out of reach at this speed.
+pub fn launch_velocity(
+    start: [f32; 3],
+    target: [f32; 3],
+    speed: f32,
+    gravity: [f32; 3],
+    high_arc: bool,
+) -> Option<[f32; 3]> {
+    let d = sub(target, start);
+    let distance = norm(d);
+    if speed <= 0.0 || distance < 1e-6 {
+        return None;
+    }
+
+    let g = norm(gravity);
+    if g < 1e-6 {
+        return Some(scale(d, speed / distance));
+    }
+
+    // Split the offset into height along "up" (against gravity) and a horizontal part
+    let up = scale(gravity, -1.0 / g);
+    let y = dot(d, up);
+    let horizontal = sub(d, scale(up, y));
+    let x = norm(horizontal);
+
+    let v2 = speed * speed;
+    let discriminant = v2 * v2 - g * (g * x * x + 2.0 * y * v2);
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    if x < 1e-6 {
+        // Straight up or down
+        return Some(scale(up, if y >= 0.0 { speed } else { -speed }));
+    }
+
+    let root = discriminant.sqrt();
+    let tan = if high_arc { (v2 + root) / (g * x) } else { (v2 - root) / (g * x) };
+    let cos = 1.0 / (1.0 + tan * tan).sqrt();
+    let sin = tan * cos;
+    Some(add(scale(horizontal, speed * cos / x), scale(up, speed * sin)))
+}
+
+/// Time for a projectile launched with `velocity` to cover the horizontal
+/// distance to `target` (or the straight-line distance without gravity)
+pub fn flight_time(start: [f32; 3], target: [f32; 3], velocity: [f32; 3], gravity: [f32; 3]) -> f32 {
+    let d = sub(target, start);
+    let g = norm(gravity);
+    if g < 1e-6 {
+        return norm(d) / norm(velocity).max(1e-6);
+    }
+    let up = scale(gravity, -1.0 / g);
+    let horizontal = norm(sub(d, scale(up, dot(d, up))));
+    let v_horizontal = norm(sub(velocity, scale(up, dot(velocity, up))));
+    if horizontal < 1e-6 || v_horizontal < 1e-6 {
+        // Vertical shot: time until the vertical motion reaches the target height
+        let (y, vy) = (dot(d, up), dot(velocity, up));
+        let disc = (vy * vy - 2.0 * g * y).max(0.0);
+        return (vy - disc.sqrt()) / g;
+    }
+    horizontal / v_horizontal
+}
+
+/// Launch velocity for body `index` of `scene` that passes its center
+/// through `target`, refined by simulation
+///
+/// Starts from the flat closed-form solution and repeatedly simulates the
+/// body alone (no ground, no other bodies) with the scene's gravity and
+/// step size `dt`, shifting the aim point against the miss. Returns None if
+/// the body does not exist or the target is out of reach.
+pub fn refine_launch_velocity(
+    scene: &SceneBuilder,
+    index: usize,
+    target: [f32; 3],
+    speed: f32,
+    dt: f32,
+) -> Option<[f32; 3]> {
+    let body = scene.bodies.get(index)?;
+    let gravity = scene.physics.gravity;
+    let start = body.position;
+
+    let mut aim = target;
+    let mut best: Option<([f32; 3], f32)> = None;
+    for _ in 0..REFINE_ITERATIONS {
+        let Some(velocity) = launch_velocity(start, aim, speed, gravity, false) else {
+            break;
+        };
+
+        let mut solo = SceneBuilder {
+            bodies: vec![body.clone()],
+            ground_y: None,
+            ground_size: 0.0,
+            physics: scene.physics.clone(),
+            joints: Vec::new(),
+            vehicles: Vec::new(),
+            ..Default::default()
+        };
+        solo.bodies[0].velocity = velocity;
+        let closest = closest_approach(&solo, target, 2.0 * flight_time(start, aim, velocity, gravity) + 1.0, dt);
+
+        let miss = sub(closest, target);
+        let miss_distance = norm(miss);
+        if best.is_none_or(|(_, d)| miss_distance < d) {
+            best = Some((velocity, miss_distance));
+        }
+        if miss_distance < AIM_TOLERANCE {
+            break;
+        }
+        aim = sub(aim, miss);
+    }
+
+    best.map(|(velocity, _)| velocity)
+}
+
+/// Point of body 0's path closest to `target` within `duration` seconds
+fn closest_approach(scene: &SceneBuilder, target: [f32; 3], duration: f32, dt: f32) -> [f32; 3] {
+    let mut sim = Simulator::new(scene);
+    let mut previous = sim.storage.positions[0];
+    let mut closest = previous;
+    let mut closest_distance = norm(sub(previous, target));
+
+    let steps = (duration / dt).ceil().clamp(1.0, 100_000.0) as u32;
+    for _ in 0..steps {
+        sim.step(dt);
+        let current = sim.storage.positions[0];
+
+        // Closest point on the segment travelled this step
+        let segment = sub(current, previous);
+        let length_sq = dot(segment, segment);
+        let t = if length_sq > 0.0 { (dot(sub(target, previous), segment) / length_sq).clamp(0.0, 1.0) } else { 0.0 };
+        let point = add(previous, scale(segment, t));
+        let distance = norm(sub(point, target));
+        if distance < closest_distance {
+            closest = point;
+            closest_distance = distance;
+        }
+        previous = current;
+    }
+    closest
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f32; 3], s: f32) -> [f32; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn norm(a: [f32; 3]) -> f32 {
+    dot(a, a).sqrt()
+}
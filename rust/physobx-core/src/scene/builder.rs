@@ -1,25 +1,322 @@
 //! Scene builder for constructing physics scenes
 
+use crate::physics::aero::{AeroCoefficients, WindField};
+use crate::physics::friction::AnisotropicFriction;
+use crate::physics::broad_phase::BroadPhaseKind;
+use crate::physics::fluid::{FluidConfig, FluidSettings};
+use crate::physics::heat::HeatSettings;
+use crate::physics::settings::{CombineRule, PhysicsSettings};
+use crate::sensors::Rng;
+use super::ballistics;
+use super::joints::{JointConfig, JointKind};
+use super::materials::{Material, MaterialPreset, SurfaceLook};
+use super::motion_path::MotionPath;
+use super::report::{scene_report, SceneReport, SceneWarning};
+use super::vehicle::{Vehicle, VehicleConfig};
+
 /// Shape type for rigid bodies
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ShapeType {
     Cube,
     Sphere,
+    /// Cylinder with hemispherical ends, its axis along local Y
+    Capsule,
+    /// Flat-ended cylinder, its axis along local Y
+    Cylinder,
+    /// Cone with its apex up local Y and its base down
+    Cone,
+}
+
+impl ShapeType {
+    /// Every shape, in id order
+    pub const ALL: [ShapeType; 5] = [ShapeType::Cube, ShapeType::Sphere, ShapeType::Capsule, ShapeType::Cylinder, ShapeType::Cone];
+
+    /// Shapes symmetric about their local Y axis, sized by a radius and a
+    /// half height along the axis
+    pub const AXIAL: [ShapeType; 3] = [ShapeType::Capsule, ShapeType::Cylinder, ShapeType::Cone];
+
+    /// Numeric id used in SOA storage and exported arrays
+    pub fn id(self) -> u8 {
+        match self {
+            ShapeType::Cube => 0,
+            ShapeType::Sphere => 1,
+            ShapeType::Capsule => 2,
+            ShapeType::Cylinder => 3,
+            ShapeType::Cone => 4,
+        }
+    }
+
+    /// Look up a shape type from its numeric id
+    pub fn from_id(id: u8) -> Option<Self> {
+        Self::ALL.get(id as usize).copied()
+    }
+
+    /// Lowercase name of the shape
+    pub fn name(self) -> &'static str {
+        match self {
+            ShapeType::Cube => "cube",
+            ShapeType::Sphere => "sphere",
+            ShapeType::Capsule => "capsule",
+            ShapeType::Cylinder => "cylinder",
+            ShapeType::Cone => "cone",
+        }
+    }
+
+    /// Look up a shape type from its lowercase name
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|shape| shape.name() == name)
+    }
+
+    /// Position of the shape in [`ShapeType::AXIAL`], None for other shapes
+    pub fn axial_slot(self) -> Option<usize> {
+        Self::AXIAL.iter().position(|&shape| shape == self)
+    }
+}
+
+/// How a body's mass is given
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MassMode {
+    /// [`RigidBodyConfig::mass`], spread over the colliders by volume
+    #[default]
+    Mass,
+    /// Density (kg/m³) of every collider; the physics engine computes mass
+    /// and inertia from the collider shapes, and `mass` is ignored
+    Density(f32),
 }
 
 /// Configuration for a rigid body
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RigidBodyConfig {
     pub position: [f32; 3],
     pub rotation: [f32; 4],
     pub velocity: [f32; 3],
+    /// Initial angular velocity (rad/s, world frame)
+    pub angular_velocity: [f32; 3],
     pub half_extents: [f32; 3],
     pub radius: f32,
+    /// Half length along local Y of the straight section (capsules) or of
+    /// the whole body (cylinders, cones)
+    pub half_height: f32,
     pub shape: ShapeType,
     pub mass: f32,
+    /// Whether the mass comes from `mass` or from a density
+    pub mass_mode: MassMode,
     pub restitution: f32,
     pub friction: f32,
+    /// Physical material; when set, its density (times the volume) gives
+    /// the mass and its friction and restitution replace the body's own
+    pub material: Option<Material>,
     pub color: [f32; 3],  // RGB color
+    /// Shading roughness, 0 (sharp highlights) to 1 (matte)
+    pub roughness: f32,
+    /// Shading metalness, 0 (dielectric) to 1 (metal)
+    pub metallic: f32,
+    /// Center of mass offset from the shape center (local frame); None = geometric center
+    pub com_offset: Option<[f32; 3]>,
+    /// Principal moments of inertia (local frame); None = derived from shape and mass
+    pub inertia: Option<[f32; 3]>,
+    /// Apply gyroscopic torque (needed for precession of fast-spinning bodies)
+    pub gyroscopic: bool,
+    /// Friction combine rule override; None = scene default
+    pub friction_combine: Option<CombineRule>,
+    /// Restitution combine rule override; None = scene default
+    pub restitution_combine: Option<CombineRule>,
+    /// Direction-dependent friction (overrides `friction` and combine rules)
+    pub anisotropic_friction: Option<AnisotropicFriction>,
+    /// Fixed in place (environment geometry); mass and velocities are ignored
+    pub is_static: bool,
+    /// Static body that collides but is not drawn (rendered frames, shadows
+    /// and ID masks), e.g. an invisible container wall; ignored for
+    /// dynamic bodies
+    pub hidden: bool,
+    /// World axes (x, y, z) along which the body cannot move
+    pub locked_translations: [bool; 3],
+    /// World axes (x, y, z) about which the body cannot rotate
+    pub locked_rotations: [bool; 3],
+    /// Path the body follows as a kinematic body; mass and velocities are ignored
+    pub motion_path: Option<MotionPath>,
+    /// Drag and lift against the scene's wind; None = no air forces
+    pub aero: Option<AeroCoefficients>,
+    /// Initial temperature (arbitrary units, see [`HeatSettings`])
+    pub temperature: f32,
+    /// Extra colliders rigidly attached to the body's own shape, making it a
+    /// compound body (e.g. an L-shaped piece from several boxes)
+    pub children: Vec<ChildCollider>,
+}
+
+/// Extra collider of a compound body, placed in the body's local frame
+///
+/// The mass of a compound body is spread over its own shape and its
+/// children in proportion to their volumes; overlapping pieces count twice.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChildCollider {
+    pub shape: ShapeType,
+    /// Center of the child in the body's local frame
+    pub offset: [f32; 3],
+    /// Rotation relative to the body (x, y, z, w)
+    pub rotation: [f32; 4],
+    pub half_extents: [f32; 3],
+    pub radius: f32,
+    /// Half length along the child's local Y (see [`RigidBodyConfig::half_height`])
+    pub half_height: f32,
+    /// Render color; None = the body's color
+    pub color: Option<[f32; 3]>,
+}
+
+impl ChildCollider {
+    /// Box child with the given half extents
+    pub fn cube(offset: [f32; 3], half_extents: [f32; 3]) -> Self {
+        Self::shaped(ShapeType::Cube, offset, half_extents, 0.5, 0.5)
+    }
+
+    /// Sphere child
+    pub fn sphere(offset: [f32; 3], radius: f32) -> Self {
+        Self::shaped(ShapeType::Sphere, offset, [radius; 3], radius, 0.0)
+    }
+
+    /// Capsule, cylinder or cone child (axis along the child's local Y)
+    pub fn axial(shape: ShapeType, offset: [f32; 3], half_height: f32, radius: f32) -> Self {
+        Self::shaped(shape, offset, [radius, half_height, radius], radius, half_height)
+    }
+
+    fn shaped(shape: ShapeType, offset: [f32; 3], half_extents: [f32; 3], radius: f32, half_height: f32) -> Self {
+        Self {
+            shape,
+            offset,
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            half_extents,
+            radius,
+            half_height,
+            color: None,
+        }
+    }
+
+    /// Volume of the child's shape
+    pub fn volume(&self) -> f32 {
+        shape_volume(self.shape, self.half_extents, self.radius, self.half_height)
+    }
+}
+
+/// Volume of a shape with the given dimensions
+pub fn shape_volume(shape: ShapeType, half_extents: [f32; 3], radius: f32, half_height: f32) -> f32 {
+    use std::f32::consts::PI;
+    match shape {
+        ShapeType::Cube => 8.0 * half_extents[0] * half_extents[1] * half_extents[2],
+        ShapeType::Sphere => (4.0 / 3.0) * PI * radius.powi(3),
+        // Cylinder between the end caps plus one whole sphere
+        ShapeType::Capsule => PI * radius * radius * (2.0 * half_height + (4.0 / 3.0) * radius),
+        ShapeType::Cylinder => PI * radius * radius * 2.0 * half_height,
+        ShapeType::Cone => PI * radius * radius * 2.0 * half_height / 3.0,
+    }
+}
+
+/// Local half extents and rounding radius bounding a shape
+fn local_bound_terms(shape: ShapeType, half_extents: [f32; 3], radius: f32, half_height: f32) -> ([f32; 3], f32) {
+    match shape {
+        ShapeType::Sphere => ([0.0; 3], radius),
+        ShapeType::Cube => (half_extents, 0.0),
+        // Axis segment swept by a sphere
+        ShapeType::Capsule => ([0.0, half_height, 0.0], radius),
+        ShapeType::Cylinder | ShapeType::Cone => ([radius, half_height, radius], 0.0),
+    }
+}
+
+/// Half extents of the smallest box along the local axes enclosing a shape
+pub fn shape_half_extents(shape: ShapeType, half_extents: [f32; 3], radius: f32, half_height: f32) -> [f32; 3] {
+    let (half_extents, round) = local_bound_terms(shape, half_extents, radius, half_height);
+    half_extents.map(|h| h + round)
+}
+
+/// World half extents of a box with local `half_extents` (grown by `round`)
+/// under `rotation`
+fn rotated_half_extents(rotation: [f32; 4], half_extents: [f32; 3], round: f32) -> [f32; 3] {
+    // |R| * h: each world axis sees the projections of all local half extents
+    let [x, y, z, w] = rotation;
+    let rotation = [
+        [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+        [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+        [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+    ];
+    rotation.map(|row| {
+        round + row.iter().zip(&half_extents).map(|(r, h)| r.abs() * h).sum::<f32>()
+    })
+}
+
+/// Rotate `v` by the (x, y, z, w) quaternion `q`
+pub(crate) fn quat_rotate(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    // v + w t + q x t, with t = 2 q x v
+    let [x, y, z, w] = q;
+    let t = [
+        2.0 * (y * v[2] - z * v[1]),
+        2.0 * (z * v[0] - x * v[2]),
+        2.0 * (x * v[1] - y * v[0]),
+    ];
+    [
+        v[0] + w * t[0] + (y * t[2] - z * t[1]),
+        v[1] + w * t[1] + (z * t[0] - x * t[2]),
+        v[2] + w * t[2] + (x * t[1] - y * t[0]),
+    ]
+}
+
+/// Hamilton product `a * b` of (x, y, z, w) quaternions
+pub(crate) fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let [ax, ay, az, aw] = a;
+    let [bx, by, bz, bw] = b;
+    [
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+        aw * bw - ax * bx - ay * by - az * bz,
+    ]
+}
+
+/// (x, y, z, w) quaternion of Euler angles in radians, applied about the
+/// fixed X, then Y, then Z axes
+pub fn quat_from_euler(angles: [f32; 3]) -> [f32; 4] {
+    let [x, y, z] = angles.map(|a| ((a * 0.5).sin(), (a * 0.5).cos()));
+    let qx = [x.0, 0.0, 0.0, x.1];
+    let qy = [0.0, y.0, 0.0, y.1];
+    let qz = [0.0, 0.0, z.0, z.1];
+    quat_mul(qz, quat_mul(qy, qx))
+}
+
+/// Centers of a `count` grid of cells `spacing` apart around `center`, in
+/// x-major order (z varies fastest)
+fn grid_positions(center: [f32; 3], spacing: f32, count: [u32; 3]) -> impl Iterator<Item = [f32; 3]> {
+    let offset = count.map(|n| (n as f32 - 1.0) * spacing / 2.0);
+    (0..count[0]).flat_map(move |ix| {
+        (0..count[1]).flat_map(move |iy| {
+            (0..count[2]).map(move |iz| {
+                let cell = [ix, iy, iz];
+                std::array::from_fn(|k| center[k] + cell[k] as f32 * spacing - offset[k])
+            })
+        })
+    })
+}
+
+/// Uniformly distributed random rotation
+///
+/// Normalizes a point drawn uniformly from the unit 4-ball by rejection, so
+/// unlike the usual trigonometric formula the result is bit-identical on
+/// every platform.
+pub(crate) fn random_rotation(rng: &mut Rng) -> [f32; 4] {
+    loop {
+        let q: [f32; 4] = std::array::from_fn(|_| 2.0 * rng.uniform() - 1.0);
+        let norm2 = q.iter().map(|c| c * c).sum::<f32>();
+        if norm2 > 1e-4 && norm2 <= 1.0 {
+            let norm = norm2.sqrt();
+            return q.map(|c| c / norm);
+        }
+    }
+}
+
+/// `q` scaled to unit length (identity for a zero quaternion)
+pub fn quat_normalize(q: [f32; 4]) -> [f32; 4] {
+    let length = q.iter().map(|c| c * c).sum::<f32>().sqrt();
+    if length <= f32::EPSILON {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+    q.map(|c| c / length)
 }
 
 impl Default for RigidBodyConfig {
@@ -28,23 +325,178 @@ impl Default for RigidBodyConfig {
             position: [0.0, 0.0, 0.0],
             rotation: [0.0, 0.0, 0.0, 1.0], // Identity quaternion
             velocity: [0.0, 0.0, 0.0],
+            angular_velocity: [0.0, 0.0, 0.0],
             half_extents: [0.5, 0.5, 0.5],
             radius: 0.5,
+            half_height: 0.5,
             shape: ShapeType::Cube,
             mass: 1.0,
+            mass_mode: MassMode::Mass,
             restitution: 0.3,
             friction: 0.5,
+            material: None,
             color: [0.82, 0.32, 0.12],  // Default terracotta
+            roughness: SurfaceLook::DEFAULT_ROUGHNESS,
+            metallic: SurfaceLook::DEFAULT_METALLIC,
+            com_offset: None,
+            inertia: None,
+            gyroscopic: false,
+            friction_combine: None,
+            restitution_combine: None,
+            anisotropic_friction: None,
+            is_static: false,
+            hidden: false,
+            locked_translations: [false; 3],
+            locked_rotations: [false; 3],
+            motion_path: None,
+            aero: None,
+            temperature: 0.0,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl RigidBodyConfig {
+    /// Half extents of the world-space bounding box at the initial pose,
+    /// centered on `position` and covering any child colliders
+    pub fn bounding_half_extents(&self) -> [f32; 3] {
+        let (half_extents, round) = local_bound_terms(self.shape, self.half_extents, self.radius, self.half_height);
+        let mut bounds = rotated_half_extents(self.rotation, half_extents, round);
+        for child in &self.children {
+            let (half_extents, round) = local_bound_terms(child.shape, child.half_extents, child.radius, child.half_height);
+            let child_bounds = rotated_half_extents(quat_mul(self.rotation, child.rotation), half_extents, round);
+            let center = self.local_to_world(child.offset);
+            for k in 0..3 {
+                bounds[k] = bounds[k].max((center[k] - self.position[k]).abs() + child_bounds[k]);
+            }
+        }
+        bounds
+    }
+
+    /// Density (kg/m³) the body's colliders are built with: its material's
+    /// when set, else that of [`MassMode::Density`]; None = mass given
+    /// directly
+    pub fn resolved_density(&self) -> Option<f32> {
+        match (self.material, self.mass_mode) {
+            (Some(material), _) => Some(material.density),
+            (None, MassMode::Density(density)) => Some(density),
+            (None, MassMode::Mass) => None,
+        }
+    }
+
+    /// Mass the body is built with: the resolved density (see
+    /// [`RigidBodyConfig::resolved_density`]) times the volume of its shape
+    /// and children, else `mass`
+    ///
+    /// `length_scale` is the scene's meters per unit (see
+    /// [`SceneBuilder::set_length_scale`]).
+    pub fn resolved_mass(&self, length_scale: f32) -> f32 {
+        match self.resolved_density() {
+            Some(density) => density * self.total_volume() * length_scale.powi(3),
+            None => self.mass,
+        }
+    }
+
+    /// Friction and restitution the body is built with (its material's when set)
+    pub fn resolved_surface(&self) -> (f32, f32) {
+        match self.material {
+            Some(material) => (material.friction, material.restitution),
+            None => (self.friction, self.restitution),
+        }
+    }
+
+    /// Volume of the body's own shape and all its children
+    pub fn total_volume(&self) -> f32 {
+        shape_volume(self.shape, self.half_extents, self.radius, self.half_height)
+            + self.children.iter().map(ChildCollider::volume).sum::<f32>()
+    }
+
+    /// World position of a point given in the body's local frame, at the
+    /// initial pose
+    pub fn local_to_world(&self, p: [f32; 3]) -> [f32; 3] {
+        let r = quat_rotate(self.rotation, p);
+        [self.position[0] + r[0], self.position[1] + r[1], self.position[2] + r[2]]
+    }
+
+    /// Whether the initial bounding boxes of two bodies overlap
+    fn bounds_overlap(&self, other: &RigidBodyConfig) -> bool {
+        let (a, b) = (self.bounding_half_extents(), other.bounding_half_extents());
+        (0..3).all(|i| (self.position[i] - other.position[i]).abs() < a[i] + b[i])
+    }
+}
+
+/// Largest time step recommended for spinning tops (use substeps to reach it)
+pub const SPINNING_TOP_MAX_DT: f32 = 1.0 / 480.0;
+
+/// Gap between neighboring blocks in a row of [`SceneBuilder::add_pyramid`]
+/// or [`SceneBuilder::add_wall`], as a fraction of the block length, so they
+/// don't start in contact
+pub const STACK_GAP: f32 = 0.02;
+
+/// Random spots [`SceneBuilder::scatter_random`] tries per body before
+/// leaving it out
+pub const SCATTER_ATTEMPTS: u32 = 100;
+
+/// Default color of the walls of [`SceneBuilder::add_container`]
+pub const CONTAINER_COLOR: [f32; 3] = [0.6, 0.62, 0.65];
+
+/// Half thickness of the slab behind a [`StaticPlane`] (matches the ground)
+pub const PLANE_HALF_THICKNESS: f32 = 0.1;
+
+/// Bounded static plane at any orientation, e.g. a ramp or a funnel side
+/// (see [`SceneBuilder::add_plane`])
+///
+/// It collides as a square slab [`PLANE_HALF_THICKNESS`] thick on each side
+/// of its center, whose top face passes through `point`, and renders like
+/// the ground.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StaticPlane {
+    /// Center of the top face
+    pub point: [f32; 3],
+    /// Unit normal of the top face
+    pub normal: [f32; 3],
+    /// Half the side length of the square
+    pub size: f32,
+}
+
+impl StaticPlane {
+    /// Rotation (x, y, z, w) taking +Y onto the normal along the shortest arc
+    pub fn rotation(&self) -> [f32; 4] {
+        let [x, y, z] = self.normal;
+        if y < -1.0 + 1e-6 {
+            // Upside down: turn half way around X
+            return [1.0, 0.0, 0.0, 0.0];
         }
+        quat_normalize([z, 0.0, -x, 1.0 + y])
+    }
+
+    /// Center of the slab, half its thickness below the top face
+    pub fn center(&self) -> [f32; 3] {
+        [0, 1, 2].map(|k| self.point[k] - self.normal[k] * PLANE_HALF_THICKNESS)
+    }
+
+    /// Unit directions of the square's sides (the rotated X and Z axes)
+    pub fn axes(&self) -> ([f32; 3], [f32; 3]) {
+        let rotation = self.rotation();
+        (quat_rotate(rotation, [1.0, 0.0, 0.0]), quat_rotate(rotation, [0.0, 0.0, 1.0]))
     }
 }
 
 /// Builder for constructing scenes
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct SceneBuilder {
     pub bodies: Vec<RigidBodyConfig>,
     pub ground_y: Option<f32>,
     pub ground_size: f32,
+    /// Static planes besides the ground
+    pub planes: Vec<StaticPlane>,
+    pub physics: PhysicsSettings,
+    pub joints: Vec<JointConfig>,
+    pub vehicles: Vec<Vehicle>,
+    /// SPH liquid particles (none by default)
+    pub fluid: FluidConfig,
+    /// Heat exchange between touching bodies (off by default)
+    pub heat: HeatSettings,
 }
 
 impl SceneBuilder {
@@ -60,6 +512,21 @@ impl SceneBuilder {
         self
     }
 
+    /// Add a static plane whose top face is centered on `point` and faces
+    /// `normal`, `size` meters from its center to each side (like
+    /// `ground_size`)
+    ///
+    /// Any number of planes can be added besides the ground, at any tilt;
+    /// a zero normal adds nothing. Planes share the ground's friction and
+    /// restitution (see [`SceneBuilder::set_ground_material`]).
+    pub fn add_plane(&mut self, point: [f32; 3], normal: [f32; 3], size: f32) -> &mut Self {
+        let length = normal.iter().map(|c| c * c).sum::<f32>().sqrt();
+        if length > f32::EPSILON {
+            self.planes.push(StaticPlane { point, normal: normal.map(|c| c / length), size });
+        }
+        self
+    }
+
     /// Add a single cube
     pub fn add_cube(
         &mut self,
@@ -76,6 +543,25 @@ impl SceneBuilder {
         self
     }
 
+    /// Add a single cube with an initial orientation (normalized (x, y, z, w)
+    /// quaternion, see [`quat_from_euler`] for Euler angles)
+    pub fn add_cube_oriented(
+        &mut self,
+        position: [f32; 3],
+        rotation: [f32; 4],
+        half_extent: f32,
+        mass: f32,
+    ) -> &mut Self {
+        self.bodies.push(RigidBodyConfig {
+            position,
+            rotation: quat_normalize(rotation),
+            half_extents: [half_extent, half_extent, half_extent],
+            mass,
+            ..Default::default()
+        });
+        self
+    }
+
     /// Add a single cube with custom color
     pub fn add_cube_colored(
         &mut self,
@@ -94,6 +580,38 @@ impl SceneBuilder {
         self
     }
 
+    /// Add a single cube weighing `density` (kg/m³) times its volume (see
+    /// [`super::density::DENSITIES`] for common materials)
+    pub fn add_cube_density(
+        &mut self,
+        position: [f32; 3],
+        half_extent: f32,
+        density: f32,
+    ) -> &mut Self {
+        let mass = self.mass_from_density(density, 8.0 * half_extent.powi(3));
+        self.add_cube(position, half_extent, mass)
+    }
+
+    /// Add a box with separate half extents along its local axes
+    pub fn add_box(
+        &mut self,
+        position: [f32; 3],
+        rotation: [f32; 4],
+        half_extents: [f32; 3],
+        mass: f32,
+        color: [f32; 3],
+    ) -> &mut Self {
+        self.bodies.push(RigidBodyConfig {
+            position,
+            rotation: quat_normalize(rotation),
+            half_extents,
+            mass,
+            color,
+            ..Default::default()
+        });
+        self
+    }
+
     /// Add a grid of cubes
     pub fn add_cube_grid(
         &mut self,
@@ -103,24 +621,289 @@ impl SceneBuilder {
         half_extent: f32,
         mass: f32,
     ) -> &mut Self {
-        let [cx, cy, cz] = count;
-        let offset_x = (cx as f32 - 1.0) * spacing / 2.0;
-        let offset_y = (cy as f32 - 1.0) * spacing / 2.0;
-        let offset_z = (cz as f32 - 1.0) * spacing / 2.0;
+        for position in grid_positions(center, spacing, count) {
+            self.add_cube(position, half_extent, mass);
+        }
+        self
+    }
+
+    /// Add a grid of spheres, e.g. for a ball pit
+    pub fn add_sphere_grid(
+        &mut self,
+        center: [f32; 3],
+        spacing: f32,
+        count: [u32; 3],
+        radius: f32,
+        mass: f32,
+    ) -> &mut Self {
+        for position in grid_positions(center, spacing, count) {
+            self.add_sphere(position, radius, mass);
+        }
+        self
+    }
+
+    /// Add a grid whose cells cycle through `shapes` in order
+    ///
+    /// `size` is the half extent of cubes and the radius and half height of
+    /// the other shapes (see [`SceneBuilder::add_sized`]). Does nothing if
+    /// `shapes` is empty.
+    pub fn add_mixed_grid(
+        &mut self,
+        center: [f32; 3],
+        spacing: f32,
+        count: [u32; 3],
+        shapes: &[ShapeType],
+        size: f32,
+        mass: f32,
+    ) -> &mut Self {
+        for (position, &shape) in grid_positions(center, spacing, count).zip(shapes.iter().cycle()) {
+            self.add_sized(shape, position, size, mass);
+        }
+        self
+    }
+
+    /// Add a body of any shape from a single size: the half extent of a
+    /// cube, the radius of a sphere, or both the radius and half height of
+    /// a capsule, cylinder or cone
+    pub fn add_sized(&mut self, shape: ShapeType, position: [f32; 3], size: f32, mass: f32) -> &mut Self {
+        match shape {
+            ShapeType::Cube => self.add_cube(position, size, mass),
+            ShapeType::Sphere => self.add_sphere(position, size, mass),
+            ShapeType::Capsule => self.add_capsule(position, size, size, mass),
+            ShapeType::Cylinder => self.add_cylinder(position, size, size, mass),
+            ShapeType::Cone => self.add_cone(position, size, size, mass),
+        }
+    }
+
+    /// Add a 2D pyramid of cubes: `base_count` cubes in the bottom row along
+    /// X and one fewer in each row above, each centered on the row below
+    ///
+    /// `base_center` is the middle of the bottom row's underside, e.g. the
+    /// top of the ground (`y + 0.1` for `add_ground(y, ...)`). Rows touch
+    /// exactly; cubes within a row are [`STACK_GAP`] apart.
+    pub fn add_pyramid(
+        &mut self,
+        base_center: [f32; 3],
+        base_count: u32,
+        half_extent: f32,
+        mass: f32,
+    ) -> &mut Self {
+        let pitch = 2.0 * half_extent * (1.0 + STACK_GAP);
+        for row in 0..base_count {
+            let count = base_count - row;
+            let y = base_center[1] + (2 * row + 1) as f32 * half_extent;
+            let start = base_center[0] - (count - 1) as f32 * pitch / 2.0;
+            for i in 0..count {
+                self.add_cube([start + i as f32 * pitch, y, base_center[2]], half_extent, mass);
+            }
+        }
+        self
+    }
+
+    /// Add a vertical tower of `height` cubes, each resting exactly on the
+    /// one below
+    ///
+    /// `base_center` is the middle of the bottom cube's underside (see
+    /// [`SceneBuilder::add_pyramid`]).
+    pub fn add_tower(
+        &mut self,
+        base_center: [f32; 3],
+        height: u32,
+        half_extent: f32,
+        mass: f32,
+    ) -> &mut Self {
+        for level in 0..height {
+            let y = base_center[1] + (2 * level + 1) as f32 * half_extent;
+            self.add_cube([base_center[0], y, base_center[2]], half_extent, mass);
+        }
+        self
+    }
+
+    /// Add a wall of `rows` x `cols` bricks running along X
+    ///
+    /// `origin` is the middle of the wall's underside and `brick_half_extents`
+    /// the half size of one brick (length along X, height, thickness).
+    /// Staggered walls shift every other row by half a brick, closing the
+    /// row ends with half bricks (of half the mass) so the wall stays
+    /// rectangular. Rows touch exactly; bricks within a row are
+    /// [`STACK_GAP`] apart.
+    pub fn add_wall(
+        &mut self,
+        origin: [f32; 3],
+        rows: u32,
+        cols: u32,
+        brick_half_extents: [f32; 3],
+        mass: f32,
+        staggered: bool,
+    ) -> &mut Self {
+        if cols == 0 {
+            return self;
+        }
+        let [hx, hy, hz] = brick_half_extents;
+        let color = RigidBodyConfig::default().color;
+        let pitch = 2.0 * hx * (1.0 + STACK_GAP);
+        let first = origin[0] - (cols - 1) as f32 * pitch / 2.0;
+        let last = first + (cols - 1) as f32 * pitch;
+        for row in 0..rows {
+            let y = origin[1] + (2 * row + 1) as f32 * hy;
+            if staggered && row % 2 == 1 {
+                let half_brick = [hx / 2.0, hy, hz];
+                self.add_box([first - hx / 2.0, y, origin[2]], [0.0, 0.0, 0.0, 1.0], half_brick, mass / 2.0, color);
+                for i in 0..cols - 1 {
+                    let x = first + pitch / 2.0 + i as f32 * pitch;
+                    self.add_box([x, y, origin[2]], [0.0, 0.0, 0.0, 1.0], brick_half_extents, mass, color);
+                }
+                self.add_box([last + hx / 2.0, y, origin[2]], [0.0, 0.0, 0.0, 1.0], half_brick, mass / 2.0, color);
+            } else {
+                for i in 0..cols {
+                    let x = first + i as f32 * pitch;
+                    self.add_box([x, y, origin[2]], [0.0, 0.0, 0.0, 1.0], brick_half_extents, mass, color);
+                }
+            }
+        }
+        self
+    }
+
+    /// Scatter `count` bodies of `shape` with random sizes, masses and
+    /// orientations over the box `region` (min, max corners), e.g. to drop
+    /// them onto the ground
+    ///
+    /// Sizes (half extent of cubes, radius and half height of the other
+    /// shapes) and masses are uniform in their (min, max) ranges; spheres
+    /// keep the identity rotation. Bodies lie inside the region where it is
+    /// large enough and their bounding boxes overlap neither each other nor
+    /// bodies already in the scene; a body without a free spot after
+    /// [`SCATTER_ATTEMPTS`] tries is left out. The same seed scatters the
+    /// same bodies on every run and platform.
+    pub fn scatter_random(
+        &mut self,
+        region: ([f32; 3], [f32; 3]),
+        count: u32,
+        shape: ShapeType,
+        size_range: (f32, f32),
+        mass_range: (f32, f32),
+        seed: u64,
+    ) -> &mut Self {
+        let (min, max) = region;
+        let mut rng = Rng::new(seed);
+        let uniform = |rng: &mut Rng, (lo, hi): (f32, f32)| lo + (hi - lo) * rng.uniform();
+        let mut boxes: Vec<([f32; 3], [f32; 3])> = self.bodies.iter()
+            .map(|body| (body.position, body.bounding_half_extents()))
+            .collect();
+
+        for _ in 0..count {
+            let size = uniform(&mut rng, size_range);
+            let mass = uniform(&mut rng, mass_range);
+            let rotation = if shape == ShapeType::Sphere { [0.0, 0.0, 0.0, 1.0] } else { random_rotation(&mut rng) };
+            self.add_sized(shape, [0.0; 3], size, mass);
+            let Some(body) = self.bodies.last_mut() else { break };
+            body.rotation = rotation;
+            let h = body.bounding_half_extents();
 
-        for ix in 0..cx {
-            for iy in 0..cy {
-                for iz in 0..cz {
-                    let x = center[0] + ix as f32 * spacing - offset_x;
-                    let y = center[1] + iy as f32 * spacing - offset_y;
-                    let z = center[2] + iz as f32 * spacing - offset_z;
-                    self.add_cube([x, y, z], half_extent, mass);
+            let spot = (0..SCATTER_ATTEMPTS)
+                .map(|_| {
+                    std::array::from_fn(|k| {
+                        let (lo, hi) = (min[k] + h[k], max[k] - h[k]);
+                        let t = rng.uniform();
+                        if lo <= hi { lo + (hi - lo) * t } else { (min[k] + max[k]) / 2.0 }
+                    })
+                })
+                .find(|p: &[f32; 3]| {
+                    !boxes.iter().any(|(q, g)| (0..3).all(|k| (p[k] - q[k]).abs() < h[k] + g[k]))
+                });
+            match spot {
+                Some(position) => {
+                    body.position = position;
+                    boxes.push((position, h));
+                }
+                None => {
+                    self.bodies.pop();
                 }
             }
         }
         self
     }
 
+    /// Add a box of static walls that keeps bodies from rolling off the edge
+    /// of the world
+    ///
+    /// `center` is the middle of the space inside and `inner_extents` its
+    /// half size along x, y and z; walls `wall_thickness` thick surround it
+    /// on the outside. A floor and four side walls are always added, and a
+    /// lid on top with `lid`. Walls get [`CONTAINER_COLOR`]; hide them with
+    /// [`SceneBuilder::set_hidden`] to see inside. The walls are the last
+    /// five (or six) bodies, floor first.
+    pub fn add_container(
+        &mut self,
+        center: [f32; 3],
+        inner_extents: [f32; 3],
+        wall_thickness: f32,
+        lid: bool,
+    ) -> &mut Self {
+        let [cx, cy, cz] = center;
+        let [ex, ey, ez] = inner_extents;
+        let t = wall_thickness * 0.5;
+        // Floor and lid cover the side walls' edges, x walls the z walls' ends
+        let mut walls = vec![
+            ([cx, cy - ey - t, cz], [ex + 2.0 * t, t, ez + 2.0 * t]),
+            ([cx - ex - t, cy, cz], [t, ey, ez + 2.0 * t]),
+            ([cx + ex + t, cy, cz], [t, ey, ez + 2.0 * t]),
+            ([cx, cy, cz - ez - t], [ex, ey, t]),
+            ([cx, cy, cz + ez + t], [ex, ey, t]),
+        ];
+        if lid {
+            walls.push(([cx, cy + ey + t, cz], [ex + 2.0 * t, t, ez + 2.0 * t]));
+        }
+        for (position, half_extents) in walls {
+            self.bodies.push(RigidBodyConfig {
+                position,
+                half_extents,
+                color: CONTAINER_COLOR,
+                is_static: true,
+                ..Default::default()
+            });
+        }
+        self
+    }
+
+    /// Add a static cube (environment geometry that never moves)
+    ///
+    /// Static bodies collide like the ground and are uploaded to the
+    /// renderer once instead of every frame.
+    pub fn add_static_cube(
+        &mut self,
+        position: [f32; 3],
+        half_extent: f32,
+        color: [f32; 3],
+    ) -> &mut Self {
+        self.bodies.push(RigidBodyConfig {
+            position,
+            half_extents: [half_extent, half_extent, half_extent],
+            color,
+            is_static: true,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Add a static sphere (environment geometry that never moves)
+    pub fn add_static_sphere(
+        &mut self,
+        position: [f32; 3],
+        radius: f32,
+        color: [f32; 3],
+    ) -> &mut Self {
+        self.bodies.push(RigidBodyConfig {
+            position,
+            radius,
+            shape: ShapeType::Sphere,
+            color,
+            is_static: true,
+            ..Default::default()
+        });
+        self
+    }
+
     /// Add a single sphere
     pub fn add_sphere(
         &mut self,
@@ -140,6 +923,22 @@ impl SceneBuilder {
         self
     }
 
+    /// Add a single sphere with an initial orientation (normalized (x, y, z, w)
+    /// quaternion), e.g. to set the frame of body-mounted sensors
+    pub fn add_sphere_oriented(
+        &mut self,
+        position: [f32; 3],
+        rotation: [f32; 4],
+        radius: f32,
+        mass: f32,
+    ) -> &mut Self {
+        self.add_sphere(position, radius, mass);
+        if let Some(body) = self.bodies.last_mut() {
+            body.rotation = quat_normalize(rotation);
+        }
+        self
+    }
+
     /// Add a single sphere with custom color
     pub fn add_sphere_colored(
         &mut self,
@@ -160,6 +959,18 @@ impl SceneBuilder {
         self
     }
 
+    /// Add a single sphere weighing `density` (kg/m³) times its volume
+    pub fn add_sphere_density(
+        &mut self,
+        position: [f32; 3],
+        radius: f32,
+        density: f32,
+    ) -> &mut Self {
+        let volume = shape_volume(ShapeType::Sphere, [0.0; 3], radius, 0.0);
+        let mass = self.mass_from_density(density, volume);
+        self.add_sphere(position, radius, mass)
+    }
+
     /// Add a sphere with initial velocity
     pub fn add_sphere_with_velocity(
         &mut self,
@@ -203,6 +1014,811 @@ impl SceneBuilder {
         self
     }
 
+    /// Add a single capsule standing upright
+    ///
+    /// The capsule is a cylinder of `radius` whose straight section reaches
+    /// `half_height` above and below `position` along local Y, capped by
+    /// hemispheres (total height `2 * (half_height + radius)`).
+    pub fn add_capsule(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+    ) -> &mut Self {
+        self.bodies.push(RigidBodyConfig {
+            position,
+            radius,
+            half_height,
+            shape: ShapeType::Capsule,
+            mass,
+            restitution: 0.4,
+            color: [0.3, 0.65, 0.45],  // Default green for capsules
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Add a single capsule with custom color
+    pub fn add_capsule_colored(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+        color: [f32; 3],
+    ) -> &mut Self {
+        self.add_capsule(position, half_height, radius, mass);
+        if let Some(body) = self.bodies.last_mut() {
+            body.color = color;
+        }
+        self
+    }
+
+    /// Add a single cylinder standing upright, its flat ends `half_height`
+    /// above and below `position`
+    pub fn add_cylinder(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+    ) -> &mut Self {
+        self.bodies.push(RigidBodyConfig {
+            position,
+            radius,
+            half_height,
+            shape: ShapeType::Cylinder,
+            mass,
+            restitution: 0.3,
+            color: [0.6, 0.42, 0.25],  // Default wood brown for cylinders
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Add a single cylinder with custom color
+    pub fn add_cylinder_colored(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+        color: [f32; 3],
+    ) -> &mut Self {
+        self.add_cylinder(position, half_height, radius, mass);
+        if let Some(body) = self.bodies.last_mut() {
+            body.color = color;
+        }
+        self
+    }
+
+    /// Add a cylinder lying on its side (axis along X), free to roll along Z
+    pub fn add_barrel(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+    ) -> &mut Self {
+        self.add_cylinder(position, half_height, radius, mass);
+        if let Some(body) = self.bodies.last_mut() {
+            // Quarter turn about Z takes local Y to world -X
+            let s = std::f32::consts::FRAC_1_SQRT_2;
+            body.rotation = [0.0, 0.0, s, s];
+        }
+        self
+    }
+
+    /// Add a single cone standing on its base, its apex `half_height` above
+    /// `position` and its base `half_height` below
+    pub fn add_cone(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+    ) -> &mut Self {
+        self.bodies.push(RigidBodyConfig {
+            position,
+            radius,
+            half_height,
+            shape: ShapeType::Cone,
+            mass,
+            restitution: 0.3,
+            color: [0.95, 0.4, 0.1],  // Default traffic-cone orange
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Add a single cone with custom color
+    pub fn add_cone_colored(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+        color: [f32; 3],
+    ) -> &mut Self {
+        self.add_cone(position, half_height, radius, mass);
+        if let Some(body) = self.bodies.last_mut() {
+            body.color = color;
+        }
+        self
+    }
+
+    /// Override the center of mass and/or principal inertia of an added body
+    ///
+    /// Useful for loaded dice, flywheels and other unbalanced objects. Does
+    /// nothing if `index` is out of range.
+    pub fn set_mass_properties(
+        &mut self,
+        index: usize,
+        com_offset: Option<[f32; 3]>,
+        inertia: Option<[f32; 3]>,
+    ) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.com_offset = com_offset;
+            body.inertia = inertia;
+        }
+        self
+    }
+
+    /// Attach an extra collider to an added body, making it a compound body
+    ///
+    /// The child moves rigidly with the body and is rendered with it. Does
+    /// nothing if `index` is out of range.
+    pub fn add_child_collider(&mut self, index: usize, child: ChildCollider) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.children.push(child);
+        }
+        self
+    }
+
+    /// Add an L-shaped block of three equal cubes: one at `position`, one
+    /// beside it along +X and one on top of that along +Y, all rotated by
+    /// `rotation`
+    pub fn add_l_block(
+        &mut self,
+        position: [f32; 3],
+        rotation: [f32; 4],
+        half_extent: f32,
+        mass: f32,
+    ) -> &mut Self {
+        let size = 2.0 * half_extent;
+        self.bodies.push(RigidBodyConfig {
+            position,
+            rotation,
+            half_extents: [half_extent; 3],
+            shape: ShapeType::Cube,
+            mass,
+            color: [0.25, 0.55, 0.85],
+            children: vec![
+                ChildCollider::cube([size, 0.0, 0.0], [half_extent; 3]),
+                ChildCollider::cube([size, size, 0.0], [half_extent; 3]),
+            ],
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Add a spinning top resting on its tip
+    ///
+    /// The top carries the mass and inertia of a disk of `radius` whose center
+    /// of mass sits `radius` above a small spherical tip (the only collider,
+    /// and what gets rendered). `position` is the tip center. The top is
+    /// tilted by `tilt` radians about the X axis and spins at `spin` rad/s
+    /// about its own axis, so gravity makes it precess at roughly
+    /// `m g l / (I3 spin)` (slower on a floor with friction, where the
+    /// sliding tip adds a torque). Step with dt <= [`SPINNING_TOP_MAX_DT`].
+    pub fn add_spinning_top(
+        &mut self,
+        position: [f32; 3],
+        radius: f32,
+        mass: f32,
+        spin: f32,
+        tilt: f32,
+    ) -> &mut Self {
+        let tip_radius = 0.15 * radius;
+        let (s, c) = (0.5 * tilt).sin_cos();
+        let (ts, tc) = tilt.sin_cos();
+        // Spin axis is the body's local Y axis rotated about X by the tilt
+        let axis = [0.0, tc, ts];
+
+        self.bodies.push(RigidBodyConfig {
+            position,
+            rotation: [s, 0.0, 0.0, c],
+            angular_velocity: [axis[0] * spin, axis[1] * spin, axis[2] * spin],
+            radius: tip_radius,
+            shape: ShapeType::Sphere,
+            mass,
+            restitution: 0.1,
+            friction: 0.3,
+            color: [0.85, 0.7, 0.2],
+            com_offset: Some([0.0, radius, 0.0]),
+            inertia: Some([
+                0.25 * mass * radius * radius,
+                0.5 * mass * radius * radius,
+                0.25 * mass * radius * radius,
+            ]),
+            gyroscopic: true,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Connect two added bodies with a joint; returns the joint index
+    pub fn add_joint(&mut self, joint: JointConfig) -> usize {
+        self.joints.push(joint);
+        self.joints.len() - 1
+    }
+
+    /// Connect two added bodies, or a body and a world point, with a
+    /// spring-damper; returns the joint index
+    ///
+    /// `anchor_a` is in body A's local frame; `anchor_b` is in body B's
+    /// local frame, or a world point when `body_b` is None. A `rest_length`
+    /// of None uses the anchors' initial distance. Does nothing (and returns
+    /// None) if a body index is out of range.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_spring(
+        &mut self,
+        body_a: usize,
+        body_b: Option<usize>,
+        anchor_a: [f32; 3],
+        anchor_b: [f32; 3],
+        rest_length: Option<f32>,
+        stiffness: f32,
+        damping: f32,
+    ) -> Option<usize> {
+        let world_a = self.bodies.get(body_a)?.local_to_world(anchor_a);
+        let world_b = match body_b {
+            Some(b) => self.bodies.get(b)?.local_to_world(anchor_b),
+            None => anchor_b,
+        };
+        let rest_length = rest_length.unwrap_or_else(|| {
+            (0..3).map(|k| (world_b[k] - world_a[k]).powi(2)).sum::<f32>().sqrt()
+        });
+        Some(self.add_joint(JointConfig {
+            body_a,
+            body_b,
+            kind: JointKind::Spring { anchor_a, anchor_b, rest_length, stiffness, damping },
+        }))
+    }
+
+    /// Add a four-wheeled vehicle; returns the vehicle index
+    ///
+    /// Adds the chassis (a box) and four sphere wheels as bodies, connected
+    /// by suspension wheel joints. Drive it with
+    /// [`Simulator::set_vehicle_controls`](crate::Simulator::set_vehicle_controls).
+    pub fn add_vehicle(&mut self, config: &VehicleConfig) -> usize {
+        let (s, c) = (0.5 * config.heading).sin_cos();
+        let rotation = [0.0, s, 0.0, c];
+        let (sin, cos) = config.heading.sin_cos();
+        // Chassis frame to world: rotation about +Y by the heading
+        let to_world = |p: [f32; 3]| [
+            config.position[0] + cos * p[0] + sin * p[2],
+            config.position[1] + p[1],
+            config.position[2] - sin * p[0] + cos * p[2],
+        ];
+
+        let chassis = self.bodies.len();
+        self.bodies.push(RigidBodyConfig {
+            position: config.position,
+            rotation,
+            half_extents: config.chassis_half_extents,
+            shape: ShapeType::Cube,
+            mass: config.chassis_mass,
+            color: config.chassis_color,
+            ..Default::default()
+        });
+
+        let anchors = config.wheel_anchors();
+        let mut wheels = [0; 4];
+        let mut wheel_joints = [0; 4];
+        for (k, anchor) in anchors.into_iter().enumerate() {
+            wheels[k] = self.bodies.len();
+            self.bodies.push(RigidBodyConfig {
+                position: to_world(anchor),
+                rotation,
+                radius: config.wheel_radius,
+                shape: ShapeType::Sphere,
+                mass: config.wheel_mass,
+                restitution: 0.1,
+                friction: 1.2,
+                color: config.wheel_color,
+                ..Default::default()
+            });
+            // Forward rolling is positive spin about the chassis' -Z
+            wheel_joints[k] = self.add_joint(JointConfig {
+                body_a: chassis,
+                body_b: Some(wheels[k]),
+                kind: JointKind::Wheel {
+                    anchor,
+                    axle: [0.0, 0.0, -1.0],
+                    up: [0.0, 1.0, 0.0],
+                    travel: config.suspension_travel,
+                    stiffness: config.suspension_stiffness,
+                    damping: config.suspension_damping,
+                },
+            });
+        }
+
+        self.vehicles.push(Vehicle {
+            chassis,
+            wheels,
+            wheel_joints,
+            max_wheel_speed: config.max_speed / config.wheel_radius.max(1e-3),
+            motor_torque: config.motor_torque,
+            max_steering_angle: config.max_steering_angle,
+            all_wheel_drive: config.all_wheel_drive,
+        });
+        self.vehicles.len() - 1
+    }
+
+    /// Fix an added body in place (or make it dynamic again)
+    ///
+    /// Does nothing if `index` is out of range.
+    pub fn set_static(&mut self, index: usize, is_static: bool) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.is_static = is_static;
+        }
+        self
+    }
+
+    /// Hide an added static body from rendered frames, shadows and ID masks
+    /// while it keeps colliding (or show it again)
+    ///
+    /// Only static bodies can be hidden; the flag has no effect on dynamic
+    /// ones. Does nothing if `index` is out of range.
+    pub fn set_hidden(&mut self, index: usize, hidden: bool) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.hidden = hidden;
+        }
+        self
+    }
+
+    /// Drive an added body along a path as a kinematic body, or make it
+    /// dynamic again with None
+    ///
+    /// The body is moved to the path's first waypoint. Does nothing if
+    /// `index` is out of range.
+    pub fn set_motion_path(&mut self, index: usize, path: Option<MotionPath>) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            if let Some(&start) = path.as_ref().and_then(|p| p.waypoints.first()) {
+                body.position = start;
+            }
+            body.motion_path = path;
+        }
+        self
+    }
+
+    /// Set the scene-wide friction and restitution combine rules
+    pub fn set_combine_rules(&mut self, friction: CombineRule, restitution: CombineRule) -> &mut Self {
+        self.physics.friction_combine = friction;
+        self.physics.restitution_combine = restitution;
+        self
+    }
+
+    /// Set the broad-phase algorithm, the BVH box margin (m) and the
+    /// normalized contact prediction distance
+    pub fn set_broad_phase(&mut self, kind: BroadPhaseKind, bvh_margin: f32, prediction_distance: f32) -> &mut Self {
+        self.physics.broad_phase = kind;
+        self.physics.bvh_margin = bvh_margin;
+        self.physics.prediction_distance = prediction_distance;
+        self
+    }
+
+    /// Constrain every dynamic body to its xy plane (2D mode)
+    ///
+    /// Bodies keep their starting z, move only in x and y and rotate only
+    /// about z. Renderers frame planar scenes with an orthographic camera.
+    pub fn set_planar(&mut self, planar: bool) -> &mut Self {
+        self.physics.planar = planar;
+        self
+    }
+
+    /// Bounding rectangle (min, max) of all bodies and the ground top in the
+    /// xy plane; None for an empty scene
+    pub fn xy_bounds(&self) -> Option<([f32; 2], [f32; 2])> {
+        let mut min = [f32::INFINITY; 2];
+        let mut max = [f32::NEG_INFINITY; 2];
+        for body in &self.bodies {
+            let h = body.bounding_half_extents();
+            for k in 0..2 {
+                min[k] = min[k].min(body.position[k] - h[k]);
+                max[k] = max[k].max(body.position[k] + h[k]);
+            }
+        }
+        if self.bodies.is_empty() {
+            return None;
+        }
+        if let Some(ground_y) = self.ground_y {
+            min[1] = min[1].min(ground_y);
+        }
+        Some((min, max))
+    }
+
+    /// Fill the box `min`..`max` with liquid moving at `velocity`; returns
+    /// the number of fluid particles added
+    ///
+    /// Particles sit on a lattice at the fluid's rest spacing (see
+    /// [`SceneBuilder::set_fluid_settings`]).
+    pub fn add_fluid_block(&mut self, min: [f32; 3], max: [f32; 3], velocity: [f32; 3]) -> usize {
+        self.fluid.add_block(min, max, velocity)
+    }
+
+    /// Set the fluid's material parameters
+    ///
+    /// The spacing applies to blocks added afterwards, so set it first.
+    pub fn set_fluid_settings(&mut self, settings: FluidSettings) -> &mut Self {
+        self.fluid.settings = settings;
+        self
+    }
+
+    /// Set the length of one scene unit in meters (default 1; 0.01 for a
+    /// scene modeled in centimeters)
+    ///
+    /// Positions, sizes, velocities and every other length are then in scene
+    /// units; mass stays in kg and time in seconds. Gravity, wind and fluid
+    /// settings made so far are converted into the new unit (Earth gravity
+    /// becomes 981 units/s² in centimeters) and densities keep meaning
+    /// kg/m³, so call this before the other setters. Solver tolerances are
+    /// in scene units and suit bodies around one unit across, so a unit that
+    /// makes the bodies that size simulates small objects accurately.
+    ///
+    /// Does nothing unless `meters_per_unit` is positive and finite.
+    pub fn set_length_scale(&mut self, meters_per_unit: f32) -> &mut Self {
+        if !(meters_per_unit > 0.0 && meters_per_unit.is_finite()) {
+            return self;
+        }
+        // Old lengths times `ratio` are lengths in the new unit
+        let ratio = self.physics.length_scale / meters_per_unit;
+        self.physics.length_scale = meters_per_unit;
+        self.physics.gravity = self.physics.gravity.map(|g| g * ratio);
+        let wind = &mut self.physics.wind;
+        wind.velocity = wind.velocity.map(|v| v * ratio);
+        wind.reference_height *= ratio;
+        wind.gust_wavelength *= ratio;
+        wind.air_density /= ratio.powi(3);
+        let fluid = &mut self.fluid.settings;
+        fluid.spacing *= ratio;
+        fluid.rest_density /= ratio.powi(3);
+        fluid.sound_speed *= ratio;
+        fluid.viscosity *= ratio * ratio;
+        self
+    }
+
+    /// Set the step size simulators start with (s), the Rapier solver
+    /// iterations per step and the speed from which bodies use continuous
+    /// collision detection (defaults 1/240, 8 and 10)
+    pub fn set_solver(&mut self, fixed_dt: f32, solver_iterations: u32, ccd_speed: f32) -> &mut Self {
+        self.physics.fixed_dt = fixed_dt;
+        self.physics.solver_iterations = solver_iterations;
+        self.physics.ccd_speed = ccd_speed;
+        self
+    }
+
+    /// Pick the step size, solver iterations and CCD speed for the bodies
+    /// added so far (see [`PhysicsSettings::auto_tune`])
+    pub fn auto_tune(&mut self) -> &mut Self {
+        self.physics = PhysicsSettings::auto_tune(self);
+        self
+    }
+
+    /// Set the gravity vector (default (0, -9.81, 0))
+    pub fn set_gravity(&mut self, gravity: [f32; 3]) -> &mut Self {
+        self.physics.gravity = gravity;
+        self
+    }
+
+    /// Set the air moving over the scene (still air by default)
+    ///
+    /// Only bodies given coefficients with [`SceneBuilder::set_aero`] feel it.
+    pub fn set_wind(&mut self, wind: WindField) -> &mut Self {
+        self.physics.wind = wind;
+        self
+    }
+
+    /// Give an added body aerodynamic drag and lift, or remove them with None
+    ///
+    /// Light bodies then drift with the wind and flat boxes flutter and
+    /// tumble as they fall. Does nothing if `index` is out of range.
+    pub fn set_aero(&mut self, index: usize, coefficients: Option<AeroCoefficients>) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.aero = coefficients;
+        }
+        self
+    }
+
+    /// Set how temperature spreads between touching bodies
+    pub fn set_heat(&mut self, heat: HeatSettings) -> &mut Self {
+        self.heat = heat;
+        self
+    }
+
+    /// Set the initial temperature of an added body
+    ///
+    /// Static bodies keep their temperature, heating or cooling whatever
+    /// touches them. Does nothing if `index` is out of range.
+    pub fn set_temperature(&mut self, index: usize, temperature: f32) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.temperature = temperature;
+        }
+        self
+    }
+
+    /// Set the initial angular velocity of an added body (rad/s, world frame)
+    ///
+    /// For gyroscopes, spinning tops or tumbling debris. Axes locked with
+    /// [`SceneBuilder::lock_rotations`] and bodies following a motion path
+    /// start without spin. Does nothing if `index` is out of range.
+    pub fn set_angular_velocity(&mut self, index: usize, angular_velocity: [f32; 3]) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.angular_velocity = angular_velocity;
+        }
+        self
+    }
+
+    /// Set the friction and restitution of the ground plane and the static
+    /// planes (default 0.5, 0.3)
+    pub fn set_ground_material(&mut self, friction: f32, restitution: f32) -> &mut Self {
+        self.physics.ground_friction = friction;
+        self.physics.ground_restitution = restitution;
+        self
+    }
+
+    /// Lock movement of an added body along world axes (x, y, z)
+    ///
+    /// Locking z keeps the body in its starting xy plane, for 2D-style
+    /// experiments. Does nothing if `index` is out of range.
+    pub fn lock_translations(&mut self, index: usize, axes: [bool; 3]) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.locked_translations = axes;
+        }
+        self
+    }
+
+    /// Lock rotation of an added body about world axes (x, y, z)
+    ///
+    /// Locking all three gives a body that slides but never tumbles. Does
+    /// nothing if `index` is out of range.
+    pub fn lock_rotations(&mut self, index: usize, axes: [bool; 3]) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.locked_rotations = axes;
+        }
+        self
+    }
+
+    /// Set the mass of an added body to `density` (kg/m³) times the volume
+    /// of its shape and child colliders
+    ///
+    /// Works for every shape; call it after adding children to a compound
+    /// body. Does nothing if `index` is out of range.
+    pub fn set_density(&mut self, index: usize, density: f32) -> &mut Self {
+        if let Some(volume) = self.bodies.get(index).map(RigidBodyConfig::total_volume) {
+            self.bodies[index].mass = self.mass_from_density(density, volume);
+        }
+        self
+    }
+
+    /// Choose how an added body's mass is given (see [`MassMode`])
+    ///
+    /// With [`MassMode::Density`] the mass follows the body's current size
+    /// and children whenever the simulator is built, instead of being fixed
+    /// when it is set like with [`SceneBuilder::set_density`]. Does nothing
+    /// if `index` is out of range.
+    pub fn set_mass_mode(&mut self, index: usize, mode: MassMode) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.mass_mode = mode;
+        }
+        self
+    }
+
+    /// Mass of `volume` (scene units³) of a material of `density` (kg/m³)
+    fn mass_from_density(&self, density: f32, volume: f32) -> f32 {
+        density * volume * self.physics.length_scale.powi(3)
+    }
+
+    /// Set the friction coefficient and restitution of an added body
+    ///
+    /// Shapes come with their own defaults (e.g. spheres bounce more than
+    /// cubes); contact values are combined with the other body's by the
+    /// combine rules. Does nothing if `index` is out of range.
+    pub fn set_material(&mut self, index: usize, friction: f32, restitution: f32) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.friction = friction;
+            body.restitution = restitution;
+        }
+        self
+    }
+
+    /// Set the friction coefficient of the most recently added body, e.g.
+    /// `builder.add_cube(...).with_friction(0.9)`
+    pub fn with_friction(&mut self, friction: f32) -> &mut Self {
+        if let Some(body) = self.bodies.last_mut() {
+            body.friction = friction;
+        }
+        self
+    }
+
+    /// Set the restitution of the most recently added body
+    pub fn with_restitution(&mut self, restitution: f32) -> &mut Self {
+        if let Some(body) = self.bodies.last_mut() {
+            body.restitution = restitution;
+        }
+        self
+    }
+
+    /// Give an added body a physical material (None = back to its own
+    /// mass, friction and restitution)
+    ///
+    /// The material decides the body's mass (from its density and the
+    /// volume of the body and its children), friction and restitution when
+    /// the physics world is built, so later size changes keep the density.
+    /// Does nothing if `index` is out of range.
+    pub fn set_body_material(&mut self, index: usize, material: Option<Material>) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.material = material;
+        }
+        self
+    }
+
+    /// Give the most recently added body a physical material, e.g.
+    /// `builder.add_cube(...).with_body_material(Material::ICE)`
+    pub fn with_body_material(&mut self, material: Material) -> &mut Self {
+        if let Some(body) = self.bodies.last_mut() {
+            body.material = Some(material);
+        }
+        self
+    }
+
+    /// Make an added body of `preset`'s material (see
+    /// [`MaterialPreset::apply_to`]); does nothing if `index` is out of
+    /// range
+    pub fn set_preset(&mut self, index: usize, preset: &MaterialPreset) -> &mut Self {
+        let length_scale = self.physics.length_scale;
+        if let Some(body) = self.bodies.get_mut(index) {
+            preset.apply_to(body, length_scale);
+        }
+        self
+    }
+
+    /// Make the most recently added body of `preset`'s material
+    pub fn with_preset(&mut self, preset: &MaterialPreset) -> &mut Self {
+        let length_scale = self.physics.length_scale;
+        if let Some(body) = self.bodies.last_mut() {
+            preset.apply_to(body, length_scale);
+        }
+        self
+    }
+
+    /// Set how the renderer shades an added body (see [`SurfaceLook`]);
+    /// does nothing if `index` is out of range
+    pub fn set_surface(&mut self, index: usize, roughness: f32, metallic: f32) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.roughness = roughness.clamp(0.0, 1.0);
+            body.metallic = metallic.clamp(0.0, 1.0);
+        }
+        self
+    }
+
+    /// Override the combine rules of an added body (None = scene default)
+    ///
+    /// Does nothing if `index` is out of range.
+    pub fn set_body_combine_rules(
+        &mut self,
+        index: usize,
+        friction: Option<CombineRule>,
+        restitution: Option<CombineRule>,
+    ) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.friction_combine = friction;
+            body.restitution_combine = restitution;
+        }
+        self
+    }
+
+    /// Give an added body skate-like friction: `along` when sliding along
+    /// its local `axis`, `across` when sliding sideways
+    ///
+    /// Does nothing if `index` is out of range.
+    pub fn set_anisotropic_friction(
+        &mut self,
+        index: usize,
+        axis: [f32; 3],
+        along: f32,
+        across: f32,
+    ) -> &mut Self {
+        if let Some(body) = self.bodies.get_mut(index) {
+            body.anisotropic_friction = Some(AnisotropicFriction { axis, along, across });
+        }
+        self
+    }
+
+    /// Launch velocity of magnitude `speed` from `start` through `target`
+    /// under `gravity` (flat arc, ideal parabola)
+    ///
+    /// Returns None if the target is out of reach at this speed. See
+    /// [`ballistics::launch_velocity`] for the lobbed solution.
+    pub fn aim_projectile(
+        &self,
+        start: [f32; 3],
+        target: [f32; 3],
+        speed: f32,
+        gravity: [f32; 3],
+    ) -> Option<[f32; 3]> {
+        ballistics::launch_velocity(start, target, speed, gravity, false)
+    }
+
+    /// Set the initial velocity of an added body so its center passes
+    /// through `target` when stepped with `dt`
+    ///
+    /// The closed-form aim is refined by simulating the body alone with the
+    /// scene's gravity. Returns the velocity, or None (body unchanged) if
+    /// `index` is out of range or the target is out of reach.
+    pub fn aim_body(&mut self, index: usize, target: [f32; 3], speed: f32, dt: f32) -> Option<[f32; 3]> {
+        let velocity = ballistics::refine_launch_velocity(self, index, target, speed, dt)?;
+        self.bodies[index].velocity = velocity;
+        Some(velocity)
+    }
+
+    /// Add `body` resting on top of the added body `below`
+    ///
+    /// The x/z components of `body.position` are a horizontal offset from
+    /// the center of `below`; the height is computed so the bounding boxes
+    /// (at the bodies' initial rotations) touch exactly. If the new body
+    /// would overlap any other body it is raised onto that one instead, so
+    /// stacks never start interpenetrating. Exact for axis-aligned boxes;
+    /// rotated bodies rest on their bounding boxes. Does nothing if `below`
+    /// is out of range.
+    pub fn stack_on(&mut self, below: usize, mut body: RigidBodyConfig) -> &mut Self {
+        let Some(base) = self.bodies.get(below) else {
+            return self;
+        };
+        let half_height = body.bounding_half_extents()[1];
+        body.position = [
+            base.position[0] + body.position[0],
+            base.position[1] + base.bounding_half_extents()[1] + half_height,
+            base.position[2] + body.position[2],
+        ];
+
+        // Each pass moves above at least one more body, so this terminates
+        for _ in 0..self.bodies.len() {
+            let top = self.bodies.iter()
+                .filter(|other| body.bounds_overlap(other))
+                .map(|other| other.position[1] + other.bounding_half_extents()[1])
+                .fold(f32::NEG_INFINITY, f32::max);
+            if top == f32::NEG_INFINITY {
+                break;
+            }
+            body.position[1] = top + half_height;
+        }
+
+        self.bodies.push(body);
+        self
+    }
+
+    /// Statistics of the scene and any suspicious parameters (invalid mass,
+    /// NaN positions, degenerate sizes, interpenetrating bodies); print it
+    /// to get a readable summary
+    pub fn report(&self) -> SceneReport {
+        scene_report(self)
+    }
+
+    /// Problems of the scene found before it is simulated: interpenetrating
+    /// bodies, bodies starting below the ground, zero or negative masses,
+    /// NaN poses and the other issues of [`SceneBuilder::report`]
+    ///
+    /// An empty list means the scene is safe to build. Creating a simulator
+    /// only logs the severe problems (see
+    /// [`SceneIssue::is_severe`](super::SceneIssue::is_severe)) of single
+    /// bodies; overlaps are only found here.
+    pub fn validate(&self) -> Vec<SceneWarning> {
+        scene_report(self).issues
+    }
+
     /// Get counts of each shape type
     pub fn shape_counts(&self) -> (usize, usize) {
         let cubes = self.bodies.iter().filter(|b| b.shape == ShapeType::Cube).count();
@@ -0,0 +1,41 @@
+//! Densities of common materials
+//!
+//! Giving bodies a density instead of a mass keeps mass and inertia in
+//! proportion to size, so a large wooden crate and a small one behave like
+//! the same material. Values are typical room-temperature densities.
+
+// Densities the material presets share with the table
+
+/// Density of wood (kg/m³)
+pub const WOOD_DENSITY: f32 = 700.0;
+/// Density of ice (kg/m³)
+pub const ICE_DENSITY: f32 = 917.0;
+/// Density of rubber (kg/m³)
+pub const RUBBER_DENSITY: f32 = 1100.0;
+/// Density of concrete (kg/m³)
+pub const CONCRETE_DENSITY: f32 = 2400.0;
+/// Density of steel (kg/m³)
+pub const STEEL_DENSITY: f32 = 7850.0;
+
+/// Material names and densities (kg/m³), lightest first
+pub const DENSITIES: [(&str, f32); 14] = [
+    ("foam", 30.0),
+    ("balsa", 160.0),
+    ("cork", 240.0),
+    ("wood", WOOD_DENSITY),
+    ("ice", ICE_DENSITY),
+    ("plastic", 950.0),
+    ("water", 1000.0),
+    ("rubber", RUBBER_DENSITY),
+    ("brick", 1900.0),
+    ("concrete", CONCRETE_DENSITY),
+    ("glass", 2500.0),
+    ("aluminum", 2700.0),
+    ("steel", STEEL_DENSITY),
+    ("lead", 11340.0),
+];
+
+/// Density (kg/m³) of a material from [`DENSITIES`] by name
+pub fn density_of(name: &str) -> Option<f32> {
+    DENSITIES.iter().find(|(material, _)| *material == name).map(|&(_, density)| density)
+}
@@ -0,0 +1,55 @@
+//! Joints connecting two scene bodies
+//!
+//! Joints are stored on the scene by index and created after all bodies when
+//! a simulator is built.
+
+/// Kind of joint and its parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointKind {
+    /// Wheel hub: body B slides along A's local `up` on a spring-damper
+    /// (suspension) and spins about A's local `axle`
+    ///
+    /// Positive spin follows the right-hand rule about `axle`. The spin can
+    /// be driven by a velocity motor and the axle steered about `up` at
+    /// runtime (see [`RapierBridge::set_wheel_drive`](crate::physics::RapierBridge::set_wheel_drive)).
+    /// The wheel does not collide with body A.
+    Wheel {
+        /// Hub position in A's local frame
+        anchor: [f32; 3],
+        /// Spin axis in A's local frame (unit length)
+        axle: [f32; 3],
+        /// Suspension direction in A's local frame (unit, perpendicular to `axle`)
+        up: [f32; 3],
+        /// Suspension travel either side of the rest position (m)
+        travel: f32,
+        /// Suspension spring stiffness (N/m)
+        stiffness: f32,
+        /// Suspension damping (N·s/m)
+        damping: f32,
+    },
+    /// Spring-damper pulling two anchor points toward `rest_length` apart
+    ///
+    /// Acts along the line between the anchors in both directions; the
+    /// bodies still collide with each other.
+    Spring {
+        /// Anchor in A's local frame
+        anchor_a: [f32; 3],
+        /// Anchor in B's local frame, or a world point when B is the world
+        anchor_b: [f32; 3],
+        /// Distance at which the spring exerts no force (m)
+        rest_length: f32,
+        /// Spring stiffness (N/m)
+        stiffness: f32,
+        /// Damping (N·s/m)
+        damping: f32,
+    },
+}
+
+/// A joint between bodies `body_a` and `body_b` of a scene
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointConfig {
+    pub body_a: usize,
+    /// None attaches body A to a fixed point in the world
+    pub body_b: Option<usize>,
+    pub kind: JointKind,
+}
@@ -0,0 +1,144 @@
+//! Named material presets
+//!
+//! A preset bundles what a body made of one material needs to behave and
+//! look right: density (mass follows from the body's volume), friction,
+//! restitution and a surface look. Applying a preset overwrites those
+//! settings of a body; change any of them afterwards to customize it.
+//!
+//! A [`Material`] is just the physical part. Set on a body (see
+//! [`super::SceneBuilder::set_body_material`]), it stays attached and
+//! decides the body's mass, friction and restitution when the physics
+//! world is built, so resizing the body later keeps its density.
+
+use super::builder::RigidBodyConfig;
+use super::density::{CONCRETE_DENSITY, ICE_DENSITY, RUBBER_DENSITY, STEEL_DENSITY, WOOD_DENSITY};
+
+/// Surface of a body as the renderer shades it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SurfaceLook {
+    /// Base color (linear RGB)
+    pub color: [f32; 3],
+    /// Microsurface roughness from 0 (mirror-sharp highlights) to 1 (matte)
+    pub roughness: f32,
+    /// 0 = dielectric, 1 = metal (highlights tinted by the base color,
+    /// little diffuse light)
+    pub metallic: f32,
+}
+
+impl SurfaceLook {
+    /// Roughness of bodies without a preset
+    pub const DEFAULT_ROUGHNESS: f32 = 0.5;
+    /// Metallic of bodies without a preset
+    pub const DEFAULT_METALLIC: f32 = 0.0;
+}
+
+/// Physical properties of a material: what a body's mass, friction and
+/// restitution follow from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Material {
+    /// Density (kg/m³)
+    pub density: f32,
+    pub friction: f32,
+    pub restitution: f32,
+}
+
+impl Material {
+    pub const ICE: Material = ICE.material();
+    pub const RUBBER: Material = RUBBER.material();
+    pub const WOOD: Material = WOOD.material();
+    pub const STEEL: Material = STEEL.material();
+    pub const CONCRETE: Material = CONCRETE.material();
+
+    /// Custom material
+    pub const fn new(density: f32, friction: f32, restitution: f32) -> Self {
+        Self { density, friction, restitution }
+    }
+
+    /// Physical part of the preset from [`MATERIAL_PRESETS`] by name
+    pub fn named(name: &str) -> Option<Self> {
+        material_preset(name).map(MaterialPreset::material)
+    }
+}
+
+/// Physical and visual properties of one material
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialPreset {
+    pub name: &'static str,
+    /// Density (kg/m³)
+    pub density: f32,
+    pub friction: f32,
+    pub restitution: f32,
+    pub look: SurfaceLook,
+}
+
+pub const WOOD: MaterialPreset = MaterialPreset {
+    name: "wood",
+    density: WOOD_DENSITY,
+    friction: 0.5,
+    restitution: 0.35,
+    look: SurfaceLook { color: [0.55, 0.36, 0.2], roughness: 0.75, metallic: 0.0 },
+};
+
+pub const STEEL: MaterialPreset = MaterialPreset {
+    name: "steel",
+    density: STEEL_DENSITY,
+    friction: 0.4,
+    restitution: 0.55,
+    look: SurfaceLook { color: [0.62, 0.64, 0.67], roughness: 0.3, metallic: 1.0 },
+};
+
+pub const RUBBER: MaterialPreset = MaterialPreset {
+    name: "rubber",
+    density: RUBBER_DENSITY,
+    friction: 1.0,
+    restitution: 0.8,
+    look: SurfaceLook { color: [0.12, 0.12, 0.13], roughness: 0.9, metallic: 0.0 },
+};
+
+pub const ICE: MaterialPreset = MaterialPreset {
+    name: "ice",
+    density: ICE_DENSITY,
+    friction: 0.03,
+    restitution: 0.1,
+    look: SurfaceLook { color: [0.75, 0.88, 0.95], roughness: 0.1, metallic: 0.0 },
+};
+
+pub const CONCRETE: MaterialPreset = MaterialPreset {
+    name: "concrete",
+    density: CONCRETE_DENSITY,
+    friction: 0.7,
+    restitution: 0.15,
+    look: SurfaceLook { color: [0.58, 0.57, 0.55], roughness: 0.95, metallic: 0.0 },
+};
+
+/// Every preset, selectable by name with [`material_preset`]
+pub static MATERIAL_PRESETS: [MaterialPreset; 5] = [WOOD, STEEL, RUBBER, ICE, CONCRETE];
+
+/// Preset from [`MATERIAL_PRESETS`] by name
+pub fn material_preset(name: &str) -> Option<&'static MaterialPreset> {
+    MATERIAL_PRESETS.iter().find(|preset| preset.name == name)
+}
+
+impl MaterialPreset {
+    /// The preset's physical properties
+    pub const fn material(&self) -> Material {
+        Material { density: self.density, friction: self.friction, restitution: self.restitution }
+    }
+
+    /// Give `body` this material: mass from the density and the body's
+    /// volume, friction, restitution, color, roughness and metallic
+    /// (detaching any [`Material`] set on it)
+    ///
+    /// `length_scale` is the scene's meters per unit (see
+    /// [`super::SceneBuilder::set_length_scale`]).
+    pub fn apply_to(&self, body: &mut RigidBodyConfig, length_scale: f32) {
+        // The preset's values are the body's own from now on
+        body.material = None;
+        body.mass = self.density * body.total_volume() * length_scale.powi(3);
+        body.friction = self.friction;
+        body.restitution = self.restitution;
+        body.color = self.look.color;
+        body.roughness = self.look.roughness;
+        body.metallic = self.look.metallic;
+    }
+}
@@ -1,5 +1,18 @@
 //! Scene module - Scene building and management
 
 pub mod builder;
+pub mod ballistics;
+pub mod density;
+pub mod joints;
+pub mod materials;
+pub mod motion_path;
+pub mod report;
+pub mod vehicle;
 
-pub use builder::SceneBuilder;
+pub use builder::{MassMode, SceneBuilder, StaticPlane};
+pub use density::{density_of, DENSITIES};
+pub use joints::{JointConfig, JointKind};
+pub use materials::{material_preset, Material, MaterialPreset, SurfaceLook, MATERIAL_PRESETS};
+pub use motion_path::{MotionPath, PathLoop};
+pub use report::{SceneIssue, SceneReport, SceneWarning, MAX_MASS_RATIO};
+pub use vehicle::{Vehicle, VehicleConfig};
@@ -0,0 +1,92 @@
+//! Waypoint paths that drive kinematic bodies (elevators, moving obstacles)
+//!
+//! A body with a path is kinematic: it moves along the waypoints at a fixed
+//! speed every step, pushes dynamic bodies out of its way and carries bodies
+//! resting on it, but is not affected by gravity or contacts itself.
+
+/// What a body does when it reaches the last waypoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathLoop {
+    /// Stop at the last waypoint
+    Once,
+    /// Continue from the last waypoint straight back to the first
+    #[default]
+    Loop,
+    /// Reverse and retrace the path back to the first waypoint
+    PingPong,
+}
+
+impl PathLoop {
+    /// Parse a loop mode name ("once", "loop", "ping_pong")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "once" => Some(PathLoop::Once),
+            "loop" => Some(PathLoop::Loop),
+            "ping_pong" | "pingpong" => Some(PathLoop::PingPong),
+            _ => None,
+        }
+    }
+}
+
+/// World-space waypoints a kinematic body follows at constant speed
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionPath {
+    /// Positions of the body's center; it starts at the first one
+    pub waypoints: Vec<[f32; 3]>,
+    /// Speed along the path (m/s)
+    pub speed: f32,
+    pub loop_mode: PathLoop,
+}
+
+impl MotionPath {
+    /// Length of one pass along the path (including the closing segment for
+    /// [`PathLoop::Loop`])
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(a, b)| distance(a, b)).sum()
+    }
+
+    /// Position after travelling `distance` along the path from the start
+    pub fn position_at(&self, distance: f32) -> [f32; 3] {
+        let Some(&first) = self.waypoints.first() else {
+            return [0.0; 3];
+        };
+        let length = self.length();
+        if length <= 0.0 {
+            return first;
+        }
+        let mut d = match self.loop_mode {
+            PathLoop::Once => distance.clamp(0.0, length),
+            PathLoop::Loop => distance.rem_euclid(length),
+            PathLoop::PingPong => {
+                let d = distance.rem_euclid(2.0 * length);
+                if d > length { 2.0 * length - d } else { d }
+            }
+        };
+        let mut last = first;
+        for (a, b) in self.segments() {
+            let segment = self::distance(a, b);
+            if d <= segment && segment > 0.0 {
+                let t = d / segment;
+                return std::array::from_fn(|k| a[k] + (b[k] - a[k]) * t);
+            }
+            d -= segment;
+            last = b;
+        }
+        last
+    }
+
+    /// Consecutive waypoint pairs travelled in one pass
+    fn segments(&self) -> impl Iterator<Item = ([f32; 3], [f32; 3])> + '_ {
+        let closing = match self.loop_mode {
+            PathLoop::Loop if self.waypoints.len() > 1 => self.waypoints.last().zip(self.waypoints.first()),
+            _ => None,
+        };
+        self.waypoints.windows(2)
+            .map(|w| (w[0], w[1]))
+            .chain(closing.map(|(&a, &b)| (a, b)))
+    }
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2) + (b[2] - a[2]).powi(2)).sqrt()
+}
@@ -0,0 +1,464 @@
+//! Scene statistics and validation
+//!
+//! A report summarizes a built scene (body counts, mass, extent, spacing)
+//! and lists parameters that would make a simulation misbehave: missing
+//! mass, NaN positions, degenerate sizes, bodies starting inside each other
+//! or below the ground, bodies too small for the length unit, neighbors of
+//! very different mass. Checking it is cheap compared to simulating and
+//! rendering a broken scene.
+
+use std::collections::HashSet;
+use std::fmt;
+use crate::spatial::SpatialHash;
+use super::builder::{RigidBodyConfig, SceneBuilder, ShapeType};
+
+/// Fraction of the smaller body's size two bodies may overlap before the
+/// report flags them (absorbs rounding in packed grids and stacks)
+const OVERLAP_TOLERANCE: f32 = 0.01;
+
+/// Mass ratio of neighboring or jointed dynamic bodies above which the
+/// solver converges slowly and stacks jitter
+pub const MAX_MASS_RATIO: f32 = 100.0;
+
+/// Typical body size (scene units) below which the solver tolerances are a
+/// noticeable fraction of the bodies
+const MIN_TYPICAL_SIZE: f32 = 0.02;
+
+/// Problem with one body or pair of bodies
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneIssue {
+    /// Dynamic body with zero, negative or non-finite mass
+    InvalidMass { index: usize, mass: f32 },
+    /// NaN or infinite position, rotation or velocity
+    NonFinite { index: usize },
+    /// Zero or negative extent, radius or half height
+    InvalidSize { index: usize },
+    /// Negative friction or restitution
+    InvalidMaterial { index: usize },
+    /// Bodies whose initial bounding volumes overlap by `depth`
+    Overlapping { a: usize, b: usize, depth: f32 },
+    /// Body extending `depth` below the height passed to
+    /// [`SceneBuilder::add_ground`] (the middle of the ground slab)
+    BelowGround { index: usize, depth: f32 },
+    /// Dynamic bodies typically `typical_size` scene units across, too
+    /// small for the solver tolerances; model the scene in a smaller unit
+    /// with [`SceneBuilder::set_length_scale`]
+    SmallScale { typical_size: f32 },
+    /// Dynamic bodies, neighbors or (`jointed`) connected by a joint, whose
+    /// masses differ by `ratio`, more than [`MAX_MASS_RATIO`]
+    MassRatio { heavy: usize, light: usize, ratio: f32, jointed: bool },
+}
+
+/// Problem found by [`SceneBuilder::validate`]
+pub type SceneWarning = SceneIssue;
+
+impl SceneIssue {
+    /// Snake-case name of the issue type
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SceneIssue::InvalidMass { .. } => "invalid_mass",
+            SceneIssue::NonFinite { .. } => "non_finite",
+            SceneIssue::InvalidSize { .. } => "invalid_size",
+            SceneIssue::InvalidMaterial { .. } => "invalid_material",
+            SceneIssue::Overlapping { .. } => "overlapping",
+            SceneIssue::BelowGround { .. } => "below_ground",
+            SceneIssue::SmallScale { .. } => "small_scale",
+            SceneIssue::MassRatio { .. } => "mass_ratio",
+        }
+    }
+
+    /// Whether the issue typically makes the simulation explode or bodies
+    /// vanish on the first steps (invalid mass, non-finite pose, degenerate
+    /// size, interpenetration, starting below the ground), as opposed to
+    /// degrading its accuracy
+    pub fn is_severe(&self) -> bool {
+        matches!(
+            self,
+            SceneIssue::InvalidMass { .. }
+                | SceneIssue::NonFinite { .. }
+                | SceneIssue::InvalidSize { .. }
+                | SceneIssue::Overlapping { .. }
+                | SceneIssue::BelowGround { .. }
+        )
+    }
+
+    /// Indices of the bodies involved (none for scene-wide issues)
+    pub fn bodies(&self) -> Vec<usize> {
+        match *self {
+            SceneIssue::SmallScale { .. } => Vec::new(),
+            SceneIssue::Overlapping { a, b, .. } => vec![a, b],
+            SceneIssue::MassRatio { heavy, light, .. } => vec![heavy, light],
+            SceneIssue::InvalidMass { index, .. }
+            | SceneIssue::NonFinite { index }
+            | SceneIssue::InvalidSize { index }
+            | SceneIssue::InvalidMaterial { index }
+            | SceneIssue::BelowGround { index, .. } => vec![index],
+        }
+    }
+}
+
+impl fmt::Display for SceneIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            SceneIssue::InvalidMass { index, mass } => write!(f, "body {} has invalid mass {}", index, mass),
+            SceneIssue::NonFinite { index } => write!(f, "body {} has a NaN or infinite position, rotation or velocity", index),
+            SceneIssue::InvalidSize { index } => write!(f, "body {} has a zero or negative size", index),
+            SceneIssue::InvalidMaterial { index } => write!(f, "body {} has negative friction or restitution", index),
+            SceneIssue::Overlapping { a, b, depth } => write!(f, "bodies {} and {} overlap by {:.4}", a, b, depth),
+            SceneIssue::BelowGround { index, depth } => write!(f, "body {} extends {:.4} below the ground", index, depth),
+            SceneIssue::SmallScale { typical_size } => write!(
+                f,
+                "bodies are typically {:.4} units across, too small for the solver tolerances; use a smaller length unit",
+                typical_size,
+            ),
+            SceneIssue::MassRatio { heavy, light, ratio, jointed } => write!(
+                f,
+                "body {} is {:.0}x heavier than {} body {}",
+                heavy,
+                ratio,
+                if jointed { "jointed" } else { "neighboring" },
+                light,
+            ),
+        }
+    }
+}
+
+/// Statistics and suspicious parameters of a scene
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneReport {
+    pub body_count: usize,
+    pub static_count: usize,
+    /// Bodies of each shape, in [`ShapeType::ALL`] order
+    pub shape_counts: [usize; 5],
+    pub joint_count: usize,
+    /// Total mass of the dynamic bodies (kg)
+    pub total_mass: f32,
+    /// World bounding box (min, max) of all bodies at their initial pose;
+    /// None for an empty scene
+    pub bounds: Option<([f32; 3], [f32; 3])>,
+    /// Closest pair of body centers (distance, a, b); None for fewer than
+    /// two bodies
+    pub min_spacing: Option<(f32, usize, usize)>,
+    /// Problems found, by body index
+    pub issues: Vec<SceneIssue>,
+}
+
+impl SceneReport {
+    /// Whether no issues were found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Issues printed before the rest are summarized as a count
+const MAX_PRINTED_ISSUES: usize = 20;
+
+impl fmt::Display for SceneReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Scene: {} bodies ({} static), {} joints", self.body_count, self.static_count, self.joint_count)?;
+        for (shape, &count) in ShapeType::ALL.iter().zip(&self.shape_counts) {
+            if count > 0 {
+                writeln!(f, "  {:<9} {}", shape.name(), count)?;
+            }
+        }
+        writeln!(f, "Total dynamic mass: {:.3} kg", self.total_mass)?;
+        if let Some((min, max)) = self.bounds {
+            writeln!(
+                f,
+                "Bounds: [{:.3}, {:.3}, {:.3}] to [{:.3}, {:.3}, {:.3}]",
+                min[0], min[1], min[2], max[0], max[1], max[2],
+            )?;
+        }
+        if let Some((distance, a, b)) = self.min_spacing {
+            writeln!(f, "Min spacing: {:.4} (bodies {} and {})", distance, a, b)?;
+        }
+        if self.issues.is_empty() {
+            return write!(f, "No issues found");
+        }
+        write!(f, "{} issues:", self.issues.len())?;
+        for issue in self.issues.iter().take(MAX_PRINTED_ISSUES) {
+            write!(f, "\n  - {}", issue)?;
+        }
+        if self.issues.len() > MAX_PRINTED_ISSUES {
+            write!(f, "\n  ... and {} more", self.issues.len() - MAX_PRINTED_ISSUES)?;
+        }
+        Ok(())
+    }
+}
+
+/// Build the report of a scene (see [`SceneBuilder::report`])
+pub(crate) fn scene_report(scene: &SceneBuilder) -> SceneReport {
+    let bodies = &scene.bodies;
+    let mut shape_counts = [0usize; 5];
+    let mut total_mass = 0.0;
+    let mut min = [f32::INFINITY; 3];
+    let mut max = [f32::NEG_INFINITY; 3];
+    let mut issues = Vec::new();
+
+    for (index, body) in bodies.iter().enumerate() {
+        shape_counts[body.shape.id() as usize] += 1;
+        if !body.is_static && body.motion_path.is_none() {
+            let mass = body.resolved_mass(scene.physics.length_scale);
+            if mass.is_finite() && mass > 0.0 {
+                total_mass += mass;
+            }
+        }
+        body_issues(scene, index, &mut issues);
+        if !is_finite_pose(body) {
+            continue;
+        }
+
+        let h = body.bounding_half_extents();
+        for k in 0..3 {
+            min[k] = min[k].min(body.position[k] - h[k]);
+            max[k] = max[k].max(body.position[k] + h[k]);
+        }
+    }
+
+    let (min_spacing, overlaps) = spacing_and_overlaps(bodies);
+    issues.extend(overlaps);
+    issues.extend(mass_ratio_issues(scene));
+    if let Some(typical_size) = typical_size(bodies) {
+        if typical_size < MIN_TYPICAL_SIZE {
+            issues.push(SceneIssue::SmallScale { typical_size });
+        }
+    }
+
+    SceneReport {
+        body_count: bodies.len(),
+        static_count: bodies.iter().filter(|b| b.is_static).count(),
+        shape_counts,
+        joint_count: scene.joints.len(),
+        total_mass,
+        bounds: (min[0] <= max[0]).then_some((min, max)),
+        min_spacing,
+        issues,
+    }
+}
+
+/// Problems of single bodies only (no pairs), in body order: a check that
+/// stays linear in the body count for every scene
+pub(crate) fn single_body_issues(scene: &SceneBuilder) -> Vec<SceneIssue> {
+    let mut issues = Vec::new();
+    for index in 0..scene.bodies.len() {
+        body_issues(scene, index, &mut issues);
+    }
+    issues
+}
+
+/// Push the problems of body `index` that don't involve other bodies
+fn body_issues(scene: &SceneBuilder, index: usize, issues: &mut Vec<SceneIssue>) {
+    let body = &scene.bodies[index];
+    if !body.is_static && body.motion_path.is_none() {
+        let mass = body.resolved_mass(scene.physics.length_scale);
+        if !(mass.is_finite() && mass > 0.0) {
+            issues.push(SceneIssue::InvalidMass { index, mass });
+        }
+    }
+    if !is_finite_pose(body) {
+        issues.push(SceneIssue::NonFinite { index });
+        return;
+    }
+    if !has_valid_size(body) {
+        issues.push(SceneIssue::InvalidSize { index });
+    }
+    let (friction, restitution) = body.resolved_surface();
+    if friction < 0.0 || restitution < 0.0 {
+        issues.push(SceneIssue::InvalidMaterial { index });
+    }
+    if let Some(ground_y) = scene.ground_y {
+        let depth = ground_y - (body.position[1] - body.bounding_half_extents()[1]);
+        if !body.is_static && depth > OVERLAP_TOLERANCE * smallest_size(body) {
+            issues.push(SceneIssue::BelowGround { index, depth });
+        }
+    }
+}
+
+fn is_finite_pose(body: &RigidBodyConfig) -> bool {
+    body.position.iter()
+        .chain(&body.rotation)
+        .chain(&body.velocity)
+        .chain(&body.angular_velocity)
+        .all(|v| v.is_finite())
+}
+
+fn has_valid_size(body: &RigidBodyConfig) -> bool {
+    match body.shape {
+        ShapeType::Cube => body.half_extents.iter().all(|&h| h > 0.0),
+        ShapeType::Sphere => body.radius > 0.0,
+        // A capsule with no straight section is a sphere
+        ShapeType::Capsule => body.radius > 0.0 && body.half_height >= 0.0,
+        ShapeType::Cylinder | ShapeType::Cone => body.radius > 0.0 && body.half_height > 0.0,
+    }
+}
+
+/// Smallest initial bounding half extent of a body
+fn smallest_size(body: &RigidBodyConfig) -> f32 {
+    body.bounding_half_extents().into_iter().fold(f32::INFINITY, f32::min)
+}
+
+/// Pairs of dynamic bodies whose masses differ by more than
+/// [`MAX_MASS_RATIO`] and that are connected by a joint or could touch
+/// (bounding boxes closer than the smaller body's half size)
+///
+/// Jointed pairs come first, each pair is listed once.
+pub(crate) fn mass_ratio_issues(scene: &SceneBuilder) -> Vec<SceneIssue> {
+    let bodies = &scene.bodies;
+    let masses: Vec<f32> = bodies.iter().map(|body| body.resolved_mass(scene.physics.length_scale)).collect();
+    let dynamic: Vec<usize> = (0..bodies.len())
+        .filter(|&i| {
+            let body = &bodies[i];
+            !body.is_static && body.motion_path.is_none() && masses[i].is_finite() && masses[i] > 0.0 && is_finite_pose(body)
+        })
+        .collect();
+    let is_dynamic = |i: usize| dynamic.binary_search(&i).is_ok();
+    let issue = |i: usize, j: usize, jointed: bool| {
+        let (heavy, light) = if masses[i] >= masses[j] { (i, j) } else { (j, i) };
+        let ratio = masses[heavy] / masses[light];
+        (ratio > MAX_MASS_RATIO).then_some(SceneIssue::MassRatio { heavy, light, ratio, jointed })
+    };
+
+    let mut seen = HashSet::new();
+    let mut issues = Vec::new();
+    for joint in &scene.joints {
+        let (a, Some(b)) = (joint.body_a, joint.body_b) else { continue };
+        if a != b && is_dynamic(a) && is_dynamic(b) && seen.insert((a.min(b), a.max(b))) {
+            issues.extend(issue(a, b, true));
+        }
+    }
+
+    if dynamic.len() < 2 {
+        return issues;
+    }
+    let positions: Vec<[f32; 3]> = dynamic.iter().map(|&i| bodies[i].position).collect();
+    let extents: Vec<[f32; 3]> = dynamic.iter().map(|&i| bodies[i].bounding_half_extents()).collect();
+    let margins: Vec<f32> = dynamic.iter().map(|&i| smallest_size(&bodies[i])).collect();
+    for (i, j) in near_pairs(&positions, &extents, &margins, &vec![false; dynamic.len()]) {
+        let (a, b) = (dynamic[i], dynamic[j]);
+        if seen.insert((a, b)) {
+            issues.extend(issue(a, b, false));
+        }
+    }
+    issues
+}
+
+/// Median largest extent of the dynamic bodies; None without any
+fn typical_size(bodies: &[RigidBodyConfig]) -> Option<f32> {
+    let mut sizes: Vec<f32> = bodies.iter()
+        .filter(|b| !b.is_static && b.motion_path.is_none() && is_finite_pose(b))
+        .map(|b| 2.0 * b.bounding_half_extents().into_iter().fold(0.0f32, f32::max))
+        .collect();
+    if sizes.is_empty() {
+        return None;
+    }
+    let middle = sizes.len() / 2;
+    Some(*sizes.select_nth_unstable_by(middle, f32::total_cmp).1)
+}
+
+/// Closest pair of centers and all overlapping pairs among bodies with
+/// finite positions
+///
+/// Spheres overlap by the distance between their surfaces; other pairs by
+/// their initial bounding boxes, so rotated or round bodies can be
+/// flagged while only touching.
+fn spacing_and_overlaps(bodies: &[RigidBodyConfig]) -> (Option<(f32, usize, usize)>, Vec<SceneIssue>) {
+    let finite: Vec<usize> = (0..bodies.len()).filter(|&i| is_finite_pose(&bodies[i])).collect();
+    if finite.len() < 2 {
+        return (None, Vec::new());
+    }
+    let positions: Vec<[f32; 3]> = finite.iter().map(|&i| bodies[i].position).collect();
+    let extents: Vec<[f32; 3]> = finite.iter().map(|&i| bodies[i].bounding_half_extents()).collect();
+    let is_static: Vec<bool> = finite.iter().map(|&i| bodies[i].is_static).collect();
+
+    let mut overlaps = Vec::new();
+    for (i, j) in near_pairs(&positions, &extents, &vec![0.0; finite.len()], &is_static) {
+        let (a, b) = (&bodies[finite[i]], &bodies[finite[j]]);
+        let depth = if a.shape == ShapeType::Sphere && b.shape == ShapeType::Sphere && a.children.is_empty() && b.children.is_empty() {
+            let (p, q) = (positions[i], positions[j]);
+            a.radius + b.radius - ((p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2)).sqrt()
+        } else {
+            (0..3)
+                .map(|k| extents[i][k] + extents[j][k] - (positions[i][k] - positions[j][k]).abs())
+                .fold(f32::INFINITY, f32::min)
+        };
+        if depth > OVERLAP_TOLERANCE * smallest_size(a).min(smallest_size(b)) {
+            overlaps.push(SceneIssue::Overlapping { a: finite[i], b: finite[j], depth });
+        }
+    }
+
+    // Touching bodies of typical size are about a body diagonal apart
+    let mut diagonals: Vec<f32> = extents.iter()
+        .map(|h| (h[0] * h[0] + h[1] * h[1] + h[2] * h[2]).sqrt())
+        .collect();
+    let middle = diagonals.len() / 2;
+    let typical = *diagonals.select_nth_unstable_by(middle, f32::total_cmp).1;
+    let closest = closest_pair(&positions, 2.0 * typical).map(|(d, i, j)| (d, finite[i], finite[j]));
+    (closest, overlaps)
+}
+
+/// Pairs (i < j), not both static, whose bounding boxes (centered at
+/// `positions` with half sizes `extents`) are closer than the smaller of
+/// their `margins` on every axis, sorted
+///
+/// The grid is sized from the median body and each body searches it only
+/// as far as its own size reaches, finding the bodies no larger than
+/// itself. Large bodies (walls, slabs) thus find their neighbors with one
+/// wide query instead of widening every cell, which would make the search
+/// quadratic.
+fn near_pairs(positions: &[[f32; 3]], extents: &[[f32; 3]], margins: &[f32], is_static: &[bool]) -> Vec<(usize, usize)> {
+    let diagonals: Vec<f32> = extents.iter()
+        .map(|h| (h[0] * h[0] + h[1] * h[1] + h[2] * h[2]).sqrt())
+        .collect();
+    if diagonals.is_empty() {
+        return Vec::new();
+    }
+    // Boxes within `margin` on every axis have centers within the sum of
+    // their half diagonals plus the margin's diagonal
+    let reach = |i: usize| 2.0 * diagonals[i] + 3f32.sqrt() * margins[i];
+    let mut reaches: Vec<f32> = (0..positions.len()).map(reach).collect();
+    let middle = reaches.len() / 2;
+    let cell_size = reaches.select_nth_unstable_by(middle, f32::total_cmp).1.max(1e-3);
+    let hash = SpatialHash::build(positions, cell_size);
+
+    let mut pairs = Vec::new();
+    for i in 0..positions.len() {
+        for j in hash.query_point(positions[i], reach(i)) {
+            let smaller = diagonals[j] < diagonals[i] || (diagonals[j] == diagonals[i] && j > i);
+            if !smaller || (is_static[i] && is_static[j]) {
+                continue;
+            }
+            let margin = margins[i].min(margins[j]);
+            if (0..3).all(|k| (positions[i][k] - positions[j][k]).abs() - extents[i][k] - extents[j][k] < margin) {
+                pairs.push((i.min(j), i.max(j)));
+            }
+        }
+    }
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Closest pair of `positions` and their distance; None with fewer than two
+///
+/// The search starts at radius `start` and widens until it finds a pair,
+/// regridding each time so cells stay about as large as the radius.
+fn closest_pair(positions: &[[f32; 3]], start: f32) -> Option<(f32, usize, usize)> {
+    if positions.len() < 2 {
+        return None;
+    }
+    let distance = |i: usize, j: usize| {
+        let (p, q) = (positions[i], positions[j]);
+        ((p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2)).sqrt()
+    };
+    let mut radius = start.max(1e-3);
+    let mut hash = SpatialHash::default();
+    loop {
+        hash.rebuild(positions, radius);
+        let closest = hash.all_pairs_within(radius).into_iter()
+            .map(|(i, j)| (distance(i, j), i, j))
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        if closest.is_some() || !radius.is_finite() {
+            return closest;
+        }
+        radius *= 4.0;
+    }
+}
@@ -0,0 +1,92 @@
+//! Four-wheeled vehicles built from a chassis, sphere wheels and wheel joints
+//!
+//! Each wheel hangs from the chassis on a [`JointKind::Wheel`] joint: a
+//! sprung suspension slide plus a motor-driven axle. The front wheels steer
+//! by turning their axles about the chassis' up axis.
+//!
+//! [`JointKind::Wheel`]: super::joints::JointKind::Wheel
+
+/// Shape, mass and drive parameters of a vehicle
+///
+/// The chassis' local +X is forward, +Y up and +Z to the right.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VehicleConfig {
+    /// Chassis center
+    pub position: [f32; 3],
+    /// Rotation about +Y (rad); 0 faces +X
+    pub heading: f32,
+    /// Chassis half extents (length, height, width)
+    pub chassis_half_extents: [f32; 3],
+    pub chassis_mass: f32,
+    pub wheel_radius: f32,
+    pub wheel_mass: f32,
+    /// Distance between front and rear axles (m)
+    pub wheelbase: f32,
+    /// Distance between left and right wheel centers (m)
+    pub track: f32,
+    /// Suspension travel either side of the rest position (m)
+    pub suspension_travel: f32,
+    /// Suspension stiffness per wheel (N/m)
+    pub suspension_stiffness: f32,
+    /// Suspension damping per wheel (N·s/m)
+    pub suspension_damping: f32,
+    /// Ground speed at full throttle (m/s)
+    pub max_speed: f32,
+    /// Torque limit of each driven wheel's motor (N·m)
+    pub motor_torque: f32,
+    /// Front wheel angle at full steering (rad)
+    pub max_steering_angle: f32,
+    /// Drive all four wheels instead of the rear two
+    pub all_wheel_drive: bool,
+    pub chassis_color: [f32; 3],
+    pub wheel_color: [f32; 3],
+}
+
+impl Default for VehicleConfig {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 1.0, 0.0],
+            heading: 0.0,
+            chassis_half_extents: [1.0, 0.25, 0.5],
+            chassis_mass: 200.0,
+            wheel_radius: 0.3,
+            wheel_mass: 10.0,
+            wheelbase: 1.6,
+            track: 1.4,
+            suspension_travel: 0.15,
+            suspension_stiffness: 20_000.0,
+            suspension_damping: 1_500.0,
+            max_speed: 10.0,
+            motor_torque: 300.0,
+            max_steering_angle: 0.5,
+            all_wheel_drive: false,
+            chassis_color: [0.75, 0.15, 0.1],
+            wheel_color: [0.12, 0.12, 0.12],
+        }
+    }
+}
+
+impl VehicleConfig {
+    /// Wheel hub positions in the chassis frame: front-left, front-right,
+    /// rear-left, rear-right
+    pub fn wheel_anchors(&self) -> [[f32; 3]; 4] {
+        let (x, y, z) = (0.5 * self.wheelbase, -self.chassis_half_extents[1], 0.5 * self.track);
+        [[x, y, -z], [x, y, z], [-x, y, -z], [-x, y, z]]
+    }
+}
+
+/// A vehicle added to a scene
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vehicle {
+    /// Body index of the chassis
+    pub chassis: usize,
+    /// Body indices of the wheels: front-left, front-right, rear-left, rear-right
+    pub wheels: [usize; 4],
+    /// Joint indices of the wheels, in the same order
+    pub wheel_joints: [usize; 4],
+    /// Wheel spin at full throttle (rad/s)
+    pub max_wheel_speed: f32,
+    pub motor_torque: f32,
+    pub max_steering_angle: f32,
+    pub all_wheel_drive: bool,
+}
@@ -0,0 +1,87 @@
+//! Force-torque sensor measuring the external wrench on a body
+
+use super::to_body_frame;
+
+/// Contact wrench acting on a body during one step, in the body frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Wrench {
+    /// Simulation time at the end of the step
+    pub time: f32,
+    /// Net non-gravitational force (N)
+    pub force: [f32; 3],
+    /// Net torque about the center of mass (N·m)
+    pub torque: [f32; 3],
+}
+
+/// Force-torque sensor on a body
+///
+/// The wrench is recovered from the change in momentum over each step with
+/// gravity removed, so it is the sum of all contact forces (and any other
+/// external pushes) acting on the body - what a load cell between the body
+/// and the world would read.
+#[derive(Debug, Clone)]
+pub struct ForceTorqueSensor {
+    /// SOA index of the body carrying the sensor
+    pub body: usize,
+    prev: Option<([f32; 3], [f32; 3])>,
+    history: Vec<Wrench>,
+}
+
+impl ForceTorqueSensor {
+    /// Create a sensor on a body
+    pub fn new(body: usize) -> Self {
+        Self {
+            body,
+            prev: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// Record the wrench of the step that just finished
+    ///
+    /// `momentum` is (mass, linear momentum, angular momentum about the
+    /// center of mass), world-space as returned by
+    /// [`RapierBridge::momentum`](crate::physics::RapierBridge::momentum);
+    /// `rotation` is the body orientation (x, y, z, w). The first call only
+    /// primes the sensor.
+    pub fn update(
+        &mut self,
+        time: f32,
+        dt: f32,
+        momentum: (f32, [f32; 3], [f32; 3]),
+        rotation: [f32; 4],
+        gravity: [f32; 3],
+    ) {
+        let (mass, linear_momentum, angular_momentum) = momentum;
+        if let Some((p0, l0)) = self.prev {
+            if dt > 0.0 {
+                let force = [
+                    (linear_momentum[0] - p0[0]) / dt - mass * gravity[0],
+                    (linear_momentum[1] - p0[1]) / dt - mass * gravity[1],
+                    (linear_momentum[2] - p0[2]) / dt - mass * gravity[2],
+                ];
+                let torque = [
+                    (angular_momentum[0] - l0[0]) / dt,
+                    (angular_momentum[1] - l0[1]) / dt,
+                    (angular_momentum[2] - l0[2]) / dt,
+                ];
+                self.history.push(Wrench {
+                    time,
+                    force: to_body_frame(rotation, force),
+                    torque: to_body_frame(rotation, torque),
+                });
+            }
+        }
+        self.prev = Some((linear_momentum, angular_momentum));
+    }
+
+    /// Most recent wrench
+    pub fn last(&self) -> Option<Wrench> {
+        self.history.last().copied()
+    }
+
+    /// Take the wrench history recorded since the last call
+    pub fn take_history(&mut self) -> Vec<Wrench> {
+        std::mem::take(&mut self.history)
+    }
+}
@@ -0,0 +1,116 @@
+//! Inertial measurement unit attached to a body
+
+use super::noise::{NoiseChain, NoiseModel};
+use super::to_body_frame;
+
+/// White noise added to IMU readings (standard deviations)
+///
+/// Shorthand for Gaussian [`NoiseChain`]s; use [`ImuSensor::with_noise_chains`]
+/// for bias drift, dropout or quantization.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImuNoise {
+    /// Accelerometer noise (m/s^2)
+    pub accel_std: f32,
+    /// Gyroscope noise (rad/s)
+    pub gyro_std: f32,
+}
+
+/// A single IMU measurement, in the body frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImuReading {
+    /// Simulation time of the measurement
+    pub time: f32,
+    /// Specific force (acceleration minus gravity), as an accelerometer reports it
+    pub linear_acceleration: [f32; 3],
+    /// Angular velocity
+    pub angular_velocity: [f32; 3],
+}
+
+/// IMU sensor reporting body-frame acceleration and angular velocity
+///
+/// Acceleration is finite-differenced from the body's linear velocity
+/// between consecutive updates, so the first reading reports gravity only.
+/// Readings dropped by a noise chain are NaN.
+#[derive(Debug, Clone)]
+pub struct ImuSensor {
+    /// SOA index of the body carrying the sensor
+    pub body: usize,
+    /// Noise applied to the accelerometer
+    pub accel_noise: NoiseChain,
+    /// Noise applied to the gyroscope
+    pub gyro_noise: NoiseChain,
+    prev_velocity: Option<[f32; 3]>,
+    last: ImuReading,
+}
+
+impl ImuSensor {
+    /// Create a noiseless IMU on a body
+    pub fn new(body: usize) -> Self {
+        Self {
+            body,
+            accel_noise: NoiseChain::new(0),
+            gyro_noise: NoiseChain::new(1),
+            prev_velocity: None,
+            last: ImuReading::default(),
+        }
+    }
+
+    /// Add white noise drawn from generators seeded with `seed`
+    pub fn with_noise(self, noise: ImuNoise, seed: u64) -> Self {
+        let mut accel = NoiseChain::new(seed);
+        if noise.accel_std > 0.0 {
+            accel = accel.with(NoiseModel::Gaussian { std: noise.accel_std });
+        }
+        let mut gyro = NoiseChain::new(seed.wrapping_add(1));
+        if noise.gyro_std > 0.0 {
+            gyro = gyro.with(NoiseModel::Gaussian { std: noise.gyro_std });
+        }
+        self.with_noise_chains(accel, gyro)
+    }
+
+    /// Use arbitrary noise chains for the accelerometer and gyroscope
+    pub fn with_noise_chains(mut self, accel: NoiseChain, gyro: NoiseChain) -> Self {
+        self.accel_noise = accel;
+        self.gyro_noise = gyro;
+        self
+    }
+
+    /// Take a measurement from the body's current state
+    ///
+    /// `velocity`, `angular_velocity` and `gravity` are world-space; `rotation`
+    /// is the body orientation (x, y, z, w). Call once per simulation step.
+    pub fn update(
+        &mut self,
+        time: f32,
+        dt: f32,
+        velocity: [f32; 3],
+        angular_velocity: [f32; 3],
+        rotation: [f32; 4],
+        gravity: [f32; 3],
+    ) -> ImuReading {
+        let accel = match self.prev_velocity {
+            Some(prev) if dt > 0.0 => [
+                (velocity[0] - prev[0]) / dt,
+                (velocity[1] - prev[1]) / dt,
+                (velocity[2] - prev[2]) / dt,
+            ],
+            _ => [0.0; 3],
+        };
+        self.prev_velocity = Some(velocity);
+
+        let specific_force = [accel[0] - gravity[0], accel[1] - gravity[1], accel[2] - gravity[2]];
+        let mut linear_acceleration = to_body_frame(rotation, specific_force);
+        let mut angular_velocity = to_body_frame(rotation, angular_velocity);
+
+        self.accel_noise.apply(&mut linear_acceleration, dt);
+        self.gyro_noise.apply(&mut angular_velocity, dt);
+
+        self.last = ImuReading { time, linear_acceleration, angular_velocity };
+        self.last
+    }
+
+    /// Most recent reading
+    pub fn reading(&self) -> ImuReading {
+        self.last
+    }
+}
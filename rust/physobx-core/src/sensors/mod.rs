@@ -0,0 +1,28 @@
+//! Sensors - simulated measurements attached to bodies
+
+pub mod rng;
+pub mod noise;
+pub mod imu;
+pub mod force_torque;
+
+pub use rng::Rng;
+pub use noise::{NoiseModel, NoiseChain, ImageNoise};
+pub use imu::{ImuSensor, ImuNoise, ImuReading};
+pub use force_torque::{ForceTorqueSensor, Wrench};
+
+/// Rotate a world-space vector into the frame of rotation `q` (x, y, z, w)
+pub(crate) fn to_body_frame(q: [f32; 4], v: [f32; 3]) -> [f32; 3] {
+    // Inverse rotation = rotation by the conjugate quaternion
+    let (qx, qy, qz, qw) = (-q[0], -q[1], -q[2], q[3]);
+    // t = 2 * cross(q.xyz, v); v' = v + w * t + cross(q.xyz, t)
+    let t = [
+        2.0 * (qy * v[2] - qz * v[1]),
+        2.0 * (qz * v[0] - qx * v[2]),
+        2.0 * (qx * v[1] - qy * v[0]),
+    ];
+    [
+        v[0] + qw * t[0] + (qy * t[2] - qz * t[1]),
+        v[1] + qw * t[1] + (qz * t[0] - qx * t[2]),
+        v[2] + qw * t[2] + (qx * t[1] - qy * t[0]),
+    ]
+}
@@ -0,0 +1,153 @@
+//! Noise models for sensor outputs and rendered images
+//!
+//! All randomness comes from a seeded [`Rng`], so the same seed reproduces
+//! the same corrupted data.
+
+use super::Rng;
+
+/// A single corruption applied to sensor values
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseModel {
+    /// Zero-mean white noise with the given standard deviation
+    Gaussian { std: f32 },
+    /// Random-walk bias; the bias std grows by `rate` per sqrt(second)
+    BiasDrift { rate: f32 },
+    /// Whole reading lost with the given probability (values become NaN)
+    Dropout { probability: f32 },
+    /// Round to multiples of `step` (ADC resolution)
+    Quantization { step: f32 },
+}
+
+/// An ordered chain of noise models with its own RNG and drift state
+#[derive(Debug, Clone)]
+pub struct NoiseChain {
+    pub models: Vec<NoiseModel>,
+    rng: Rng,
+    bias: Vec<f32>,
+}
+
+impl NoiseChain {
+    /// Create an empty (noiseless) chain
+    pub fn new(seed: u64) -> Self {
+        Self {
+            models: Vec::new(),
+            rng: Rng::new(seed),
+            bias: Vec::new(),
+        }
+    }
+
+    /// Append a model to the chain
+    pub fn with(mut self, model: NoiseModel) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// Whether the chain changes values at all
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// Corrupt a reading in place; `dt` is the time since the previous reading
+    ///
+    /// Returns false if the reading was dropped.
+    pub fn apply(&mut self, values: &mut [f32], dt: f32) -> bool {
+        if self.bias.len() != values.len() {
+            self.bias = vec![0.0; values.len()];
+        }
+
+        for model in &self.models {
+            match *model {
+                NoiseModel::Gaussian { std } => {
+                    for v in values.iter_mut() {
+                        *v += std * self.rng.gaussian();
+                    }
+                }
+                NoiseModel::BiasDrift { rate } => {
+                    let step = rate * dt.max(0.0).sqrt();
+                    for (v, b) in values.iter_mut().zip(&mut self.bias) {
+                        *b += step * self.rng.gaussian();
+                        *v += *b;
+                    }
+                }
+                NoiseModel::Dropout { probability } => {
+                    if self.rng.uniform() < probability {
+                        values.fill(f32::NAN);
+                        return false;
+                    }
+                }
+                NoiseModel::Quantization { step } => {
+                    if step > 0.0 {
+                        for v in values.iter_mut() {
+                            *v = (*v / step).round() * step;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Camera-style corruption of rendered RGBA frames
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageNoise {
+    /// Read noise standard deviation in 8-bit levels
+    pub gaussian_std: f32,
+    /// Photons per 8-bit level for shot noise (0 = off; lower = noisier)
+    pub shot_gain: f32,
+    /// Motion blur streak length in pixels (0 = off)
+    pub blur_length: f32,
+    /// Motion blur direction in radians (0 = along +X)
+    pub blur_angle: f32,
+}
+
+impl ImageNoise {
+    /// Corrupt an RGBA8 frame in place (alpha is left untouched)
+    pub fn apply(&self, pixels: &mut [u8], width: u32, height: u32, rng: &mut Rng) {
+        if self.blur_length >= 1.0 {
+            self.motion_blur(pixels, width, height);
+        }
+        if self.gaussian_std <= 0.0 && self.shot_gain <= 0.0 {
+            return;
+        }
+
+        for px in pixels.chunks_exact_mut(4) {
+            for c in &mut px[..3] {
+                let level = *c as f32;
+                let mut std2 = self.gaussian_std * self.gaussian_std;
+                if self.shot_gain > 0.0 {
+                    // Poisson noise on the photon count, approximated as Gaussian
+                    std2 += level / self.shot_gain;
+                }
+                *c = (level + std2.sqrt() * rng.gaussian()).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+
+    /// Average each pixel with samples along the blur direction
+    fn motion_blur(&self, pixels: &mut [u8], width: u32, height: u32) {
+        let (w, h) = (width as i32, height as i32);
+        let taps = self.blur_length.round() as i32;
+        let (dy, dx) = self.blur_angle.sin_cos();
+        let source = pixels.to_vec();
+
+        for y in 0..h {
+            for x in 0..w {
+                let mut sum = [0u32; 3];
+                for t in 0..taps {
+                    let offset = t as f32 - (taps - 1) as f32 * 0.5;
+                    let sx = (x as f32 + dx * offset).round().clamp(0.0, (w - 1) as f32) as usize;
+                    let sy = (y as f32 + dy * offset).round().clamp(0.0, (h - 1) as f32) as usize;
+                    let i = (sy * width as usize + sx) * 4;
+                    sum[0] += source[i] as u32;
+                    sum[1] += source[i + 1] as u32;
+                    sum[2] += source[i + 2] as u32;
+                }
+                let i = (y as usize * width as usize + x as usize) * 4;
+                for c in 0..3 {
+                    pixels[i + c] = (sum[c] / taps as u32) as u8;
+                }
+            }
+        }
+    }
+}
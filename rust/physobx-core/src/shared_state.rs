@@ -0,0 +1,236 @@
+//! Body state published through shared memory
+//!
+//! A [`SharedStatePublisher`] keeps the SOA state arrays in a memory-mapped
+//! file (put it under `/dev/shm` to stay in RAM), rewritten in place after
+//! every step. Other processes on the same machine map the same file and
+//! read poses directly, with no encoding or socket in between.
+//!
+//! The file is a 64 byte header followed by four arrays sized for
+//! `capacity` bodies, of which the first `count` are valid (native-endian,
+//! little-endian on every supported platform):
+//!
+//! | Offset | Layout |
+//! |--------|--------|
+//! | 0 | magic `b"PBXS"`, version `u32` |
+//! | 8 | sequence `u64` |
+//! | 16 | capacity `u32`, count `u32` |
+//! | 24 | step `u64`, time `f32`, reserved to 64 |
+//! | 64 | positions `capacity × 3 f32` |
+//! | | rotations `capacity × 4 f32` quaternions (x, y, z, w) |
+//! | | linear velocities `capacity × 3 f32` |
+//! | | angular velocities `capacity × 3 f32` |
+//!
+//! Writes are guarded by a seqlock: the sequence is odd while a step is
+//! being written and even otherwise. A reader loads the sequence, skips
+//! the read if it is odd, copies what it needs and loads the sequence
+//! again; the copy is consistent if both loads match.
+
+use crate::physics::RigidBodyStorage;
+use crate::simulator::Simulator;
+use memmap2::{Mmap, MmapMut};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{fence, AtomicU64, Ordering};
+use thiserror::Error;
+
+/// First bytes of a shared state file
+pub const SHARED_MAGIC: [u8; 4] = *b"PBXS";
+/// Version of the file layout
+pub const SHARED_VERSION: u32 = 1;
+/// Bytes before the positions array
+pub const SHARED_HEADER_SIZE: usize = 64;
+/// Offset of the sequence counter
+const SEQUENCE_OFFSET: usize = 8;
+/// Floats stored per body (position, rotation, two velocities)
+const FLOATS_PER_BODY: usize = 3 + 4 + 3 + 3;
+/// Reads retried before a reader gives up on a busy writer
+const MAX_READ_ATTEMPTS: usize = 10_000;
+
+/// Errors from publishing or reading shared state
+#[derive(Error, Debug)]
+pub enum SharedStateError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("not a shared state file (bad magic)")]
+    BadMagic,
+    #[error("unsupported shared state version {0}")]
+    UnsupportedVersion(u32),
+    #[error("file holds {found} bytes, {needed} needed")]
+    Truncated { needed: usize, found: usize },
+    #[error("{count} bodies exceed the capacity of {capacity}")]
+    CapacityExceeded { count: usize, capacity: usize },
+    #[error("writer kept the state busy")]
+    Busy,
+}
+
+/// Size in bytes of a file holding `capacity` bodies
+pub fn file_size(capacity: usize) -> usize {
+    SHARED_HEADER_SIZE + capacity * FLOATS_PER_BODY * 4
+}
+
+/// Byte ranges of the four arrays for `capacity` bodies
+fn array_offsets(capacity: usize) -> [usize; 4] {
+    let positions = SHARED_HEADER_SIZE;
+    let rotations = positions + capacity * 12;
+    let linear = rotations + capacity * 16;
+    let angular = linear + capacity * 12;
+    [positions, rotations, linear, angular]
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+/// The sequence counter inside a mapping
+fn sequence(bytes: &[u8]) -> &AtomicU64 {
+    // Mappings are page aligned, so the counter at offset 8 is 8-byte aligned
+    unsafe { &*(bytes.as_ptr().add(SEQUENCE_OFFSET) as *const AtomicU64) }
+}
+
+/// Writes a simulator's body state into a shared memory-mapped file
+pub struct SharedStatePublisher {
+    map: MmapMut,
+    capacity: usize,
+    step: u64,
+}
+
+impl SharedStatePublisher {
+    /// Create (or truncate) the file at `path` with room for `capacity`
+    /// bodies
+    pub fn create(path: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        file.set_len(file_size(capacity) as u64)?;
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        map[0..4].copy_from_slice(&SHARED_MAGIC);
+        map[4..8].copy_from_slice(&SHARED_VERSION.to_le_bytes());
+        map[16..20].copy_from_slice(&(capacity as u32).to_le_bytes());
+        Ok(Self { map, capacity, step: 0 })
+    }
+
+    /// Bodies the file has room for
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of states published so far
+    pub fn steps_published(&self) -> u64 {
+        self.step
+    }
+
+    /// Publish the simulator's current state
+    pub fn publish(&mut self, sim: &Simulator) -> Result<(), SharedStateError> {
+        self.publish_storage(sim.time, &sim.storage)
+    }
+
+    /// Publish `storage` at simulation time `time`
+    pub fn publish_storage(&mut self, time: f32, storage: &RigidBodyStorage) -> Result<(), SharedStateError> {
+        let count = storage.len();
+        if count > self.capacity {
+            return Err(SharedStateError::CapacityExceeded { count, capacity: self.capacity });
+        }
+
+        let seq = sequence(&self.map).load(Ordering::Relaxed);
+        sequence(&self.map).store(seq + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        self.step += 1;
+        self.map[20..24].copy_from_slice(&(count as u32).to_le_bytes());
+        self.map[24..32].copy_from_slice(&self.step.to_le_bytes());
+        self.map[32..36].copy_from_slice(&time.to_le_bytes());
+        let arrays: [&[f32]; 4] = [
+            storage.positions.as_flattened(),
+            storage.rotations.as_flattened(),
+            storage.linear_velocities.as_flattened(),
+            storage.angular_velocities.as_flattened(),
+        ];
+        for (offset, values) in array_offsets(self.capacity).into_iter().zip(arrays) {
+            let bytes: &[u8] = bytemuck::cast_slice(values);
+            self.map[offset..offset + bytes.len()].copy_from_slice(bytes);
+        }
+
+        sequence(&self.map).store(seq + 2, Ordering::Release);
+        Ok(())
+    }
+}
+
+/// One consistent copy of the shared state
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SharedSnapshot {
+    /// Number of the publish this state came from (starting at 1)
+    pub step: u64,
+    pub time: f32,
+    pub positions: Vec<[f32; 3]>,
+    pub rotations: Vec<[f32; 4]>,
+    pub linear_velocities: Vec<[f32; 3]>,
+    pub angular_velocities: Vec<[f32; 3]>,
+}
+
+/// Reads state written by a [`SharedStatePublisher`], possibly in another
+/// process
+pub struct SharedStateReader {
+    map: Mmap,
+    capacity: usize,
+}
+
+impl SharedStateReader {
+    /// Map the shared state file at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SharedStateError> {
+        let file = File::open(path)?;
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < SHARED_HEADER_SIZE {
+            return Err(SharedStateError::Truncated { needed: SHARED_HEADER_SIZE, found: map.len() });
+        }
+        if map[0..4] != SHARED_MAGIC {
+            return Err(SharedStateError::BadMagic);
+        }
+        let version = read_u32(&map, 4);
+        if version != SHARED_VERSION {
+            return Err(SharedStateError::UnsupportedVersion(version));
+        }
+        let capacity = read_u32(&map, 16) as usize;
+        if map.len() < file_size(capacity) {
+            return Err(SharedStateError::Truncated { needed: file_size(capacity), found: map.len() });
+        }
+        Ok(Self { map, capacity })
+    }
+
+    /// Copy the latest published state, retrying while a step is being
+    /// written
+    pub fn read(&self) -> Result<SharedSnapshot, SharedStateError> {
+        for _ in 0..MAX_READ_ATTEMPTS {
+            let before = sequence(&self.map).load(Ordering::Acquire);
+            if before % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let snapshot = self.copy();
+            fence(Ordering::Acquire);
+            if sequence(&self.map).load(Ordering::Relaxed) == before {
+                return Ok(snapshot);
+            }
+        }
+        Err(SharedStateError::Busy)
+    }
+
+    /// Copy the mapped state without checking the sequence
+    fn copy(&self) -> SharedSnapshot {
+        let count = (read_u32(&self.map, 20) as usize).min(self.capacity);
+        let [positions, rotations, linear, angular] = array_offsets(self.capacity);
+        SharedSnapshot {
+            step: u64::from_le_bytes(self.map[24..32].try_into().unwrap()),
+            time: f32::from_le_bytes(self.map[32..36].try_into().unwrap()),
+            positions: self.array(positions, count),
+            rotations: self.array(rotations, count),
+            linear_velocities: self.array(linear, count),
+            angular_velocities: self.array(angular, count),
+        }
+    }
+
+    fn array<const K: usize>(&self, offset: usize, count: usize) -> Vec<[f32; K]> {
+        self.map[offset..offset + count * K * 4]
+            .chunks_exact(K * 4)
+            .map(|c| std::array::from_fn(|k| f32::from_ne_bytes(c[4 * k..4 * k + 4].try_into().unwrap())))
+            .collect()
+    }
+}
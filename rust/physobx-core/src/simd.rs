@@ -0,0 +1,179 @@
+//! Explicit SIMD kernels over the SOA body arrays
+//!
+//! Bodies are processed eight at a time with `wide::f32x8` (AVX on x86,
+//! paired NEON on ARM, scalar emulation elsewhere) and a scalar tail handles
+//! the remainder. Syncing from Rapier stays scalar: it is bound by per-body
+//! handle lookups in Rapier's arenas, not arithmetic.
+//!
+//! Run `cargo bench -p physobx-core --bench kernels` for timings against the
+//! scalar versions.
+
+use wide::f32x8;
+use crate::physics::storage::bound_terms;
+
+/// Bodies per SIMD batch
+pub const LANES: usize = 8;
+
+/// Component `k` of `LANES` consecutive quaternions
+#[inline(always)]
+fn lane4(v: &[[f32; 4]], k: usize) -> f32x8 {
+    f32x8::new(std::array::from_fn(|i| v[i][k]))
+}
+
+/// Translational kinetic energy, sum of m |v|² / 2 (J)
+pub fn kinetic_energy(velocities: &[[f32; 3]], masses: &[f32]) -> f32 {
+    let n = velocities.len().min(masses.len());
+    let split = n - n % LANES;
+
+    // Same interleaved loads as linear_momentum: squares of all components
+    // weighted by the owning body's mass
+    let flat: &[f32] = bytemuck::cast_slice(&velocities[..split]);
+    let mut sum = f32x8::splat(0.0);
+    for (v, m) in flat.chunks_exact(3 * LANES).zip(masses[..split].chunks_exact(LANES)) {
+        for j in 0..3 {
+            let v = f32x8::new(std::array::from_fn(|i| v[j * LANES + i]));
+            let m = f32x8::new(std::array::from_fn(|i| m[(j * LANES + i) / 3]));
+            sum = (m * v).mul_add(v, sum);
+        }
+    }
+
+    let tail: f32 = velocities[split..n].iter().zip(&masses[split..n])
+        .map(|(v, m)| m * (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]))
+        .sum();
+    0.5 * (sum.reduce_add() + tail)
+}
+
+/// Total linear momentum, sum of m v (kg·m/s)
+pub fn linear_momentum(velocities: &[[f32; 3]], masses: &[f32]) -> [f32; 3] {
+    let n = velocities.len().min(masses.len());
+    let split = n - n % LANES;
+
+    // Eight interleaved (x, y, z) velocities are three contiguous f32x8
+    // loads; each accumulator lane always sees the same component
+    let flat: &[f32] = bytemuck::cast_slice(&velocities[..split]);
+    let mut sum = [f32x8::splat(0.0); 3];
+    for (v, m) in flat.chunks_exact(3 * LANES).zip(masses[..split].chunks_exact(LANES)) {
+        for (j, s) in sum.iter_mut().enumerate() {
+            let v = f32x8::new(std::array::from_fn(|i| v[j * LANES + i]));
+            let m = f32x8::new(std::array::from_fn(|i| m[(j * LANES + i) / 3]));
+            *s = m.mul_add(v, *s);
+        }
+    }
+
+    let mut total = [0.0f32; 3];
+    for (j, s) in sum.iter().enumerate() {
+        for (i, value) in s.to_array().into_iter().enumerate() {
+            total[(j * LANES + i) % 3] += value;
+        }
+    }
+    for (v, m) in velocities[split..n].iter().zip(&masses[split..n]) {
+        for k in 0..3 {
+            total[k] += m * v[k];
+        }
+    }
+    total
+}
+
+/// World-space AABBs of all bodies, written into `min` / `max`
+///
+/// Same result as [`RigidBodyStorage::aabb`](crate::physics::RigidBodyStorage::aabb)
+/// per body. `radii` holds sphere and axial shape radii or cube half extents,
+/// `half_heights` the axial shape half heights.
+pub fn aabbs_into(
+    positions: &[[f32; 3]],
+    rotations: &[[f32; 4]],
+    radii: &[f32],
+    half_heights: &[f32],
+    shape_types: &[u8],
+    min: &mut [[f32; 3]],
+    max: &mut [[f32; 3]],
+) {
+    let n = positions.len()
+        .min(rotations.len())
+        .min(radii.len())
+        .min(half_heights.len())
+        .min(shape_types.len())
+        .min(min.len())
+        .min(max.len());
+    let split = n - n % LANES;
+    let (one, two) = (f32x8::splat(1.0), f32x8::splat(2.0));
+
+    for start in (0..split).step_by(LANES) {
+        let range = start..start + LANES;
+        let q = &rotations[range.clone()];
+        let (x, y, z, w) = (lane4(q, 0), lane4(q, 1), lane4(q, 2), lane4(q, 3));
+        let terms: [([f32; 3], f32); LANES] = std::array::from_fn(|i| {
+            let b = start + i;
+            bound_terms(shape_types[b], radii[b], half_heights[b])
+        });
+        let h: [f32x8; 3] = std::array::from_fn(|k| f32x8::new(std::array::from_fn(|i| terms[i].0[k])));
+        let round = f32x8::new(std::array::from_fn(|i| terms[i].1));
+
+        // Rows of the rotation matrix; a box spans sum |row| * h along each
+        // axis, grown by the rounding radius
+        let rows = [
+            [one - two * (y * y + z * z), two * (x * y - w * z), two * (x * z + w * y)],
+            [two * (x * y + w * z), one - two * (x * x + z * z), two * (y * z - w * x)],
+            [two * (x * z - w * y), two * (y * z + w * x), one - two * (x * x + y * y)],
+        ];
+        let extents = rows.map(|r| (round + r[0].abs() * h[0] + r[1].abs() * h[1] + r[2].abs() * h[2]).to_array());
+
+        // Back to interleaved (x, y, z) order: three contiguous f32x8 per output
+        let extents: [f32; 3 * LANES] = std::array::from_fn(|j| extents[j % 3][j / 3]);
+        let p: &[f32] = bytemuck::cast_slice(&positions[range.clone()]);
+        let lo: &mut [f32] = bytemuck::cast_slice_mut(&mut min[range.clone()]);
+        for j in 0..3 {
+            let e = f32x8::new(std::array::from_fn(|i| extents[j * LANES + i]));
+            let c = f32x8::new(std::array::from_fn(|i| p[j * LANES + i]));
+            lo[j * LANES..(j + 1) * LANES].copy_from_slice(&(c - e).to_array());
+        }
+        let hi: &mut [f32] = bytemuck::cast_slice_mut(&mut max[range]);
+        for j in 0..3 {
+            let e = f32x8::new(std::array::from_fn(|i| extents[j * LANES + i]));
+            let c = f32x8::new(std::array::from_fn(|i| p[j * LANES + i]));
+            hi[j * LANES..(j + 1) * LANES].copy_from_slice(&(c + e).to_array());
+        }
+    }
+
+    for i in split..n {
+        let (h, round) = bound_terms(shape_types[i], radii[i], half_heights[i]);
+        let [x, y, z, w] = rotations[i];
+        let rows = [
+            [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y - w * z), 2.0 * (x * z + w * y)],
+            [2.0 * (x * y + w * z), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z - w * x)],
+            [2.0 * (x * z - w * y), 2.0 * (y * z + w * x), 1.0 - 2.0 * (x * x + y * y)],
+        ];
+        let extent = rows.map(|r| round + r[0].abs() * h[0] + r[1].abs() * h[1] + r[2].abs() * h[2]);
+        for k in 0..3 {
+            min[i][k] = positions[i][k] - extent[k];
+            max[i][k] = positions[i][k] + extent[k];
+        }
+    }
+}
+
+/// Heat-map colors for many values, written into `out`
+///
+/// Same ramp as [`divergence_color`](crate::analysis::divergence_color):
+/// blue at 0, through yellow, to red at `max`.
+pub fn colormap_into(values: &[f32], max: f32, out: &mut [[f32; 3]]) {
+    let n = values.len().min(out.len());
+    let split = n - n % LANES;
+    let scale = if max > 0.0 { 2.0 / max } else { 0.0 };
+    let (zero, one, two) = (f32x8::splat(0.0), f32x8::splat(1.0), f32x8::splat(2.0));
+
+    for (v, o) in values[..split].chunks_exact(LANES).zip(out[..split].chunks_exact_mut(LANES)) {
+        // s = 2t with t clamped to [0, 1]
+        let s = (f32x8::new(std::array::from_fn(|i| v[i])) * f32x8::splat(scale)).max(zero).min(two);
+        let r = s.min(one).to_array();
+        let g = s.min(two - s).to_array();
+        let b = (one - s).max(zero).to_array();
+        for i in 0..LANES {
+            o[i] = [r[i], g[i], b[i]];
+        }
+    }
+
+    for (v, o) in values[split..n].iter().zip(&mut out[split..n]) {
+        let s = (v * scale).clamp(0.0, 2.0);
+        *o = [s.min(1.0), s.min(2.0 - s), (1.0 - s).max(0.0)];
+    }
+}
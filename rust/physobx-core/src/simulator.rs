@@ -1,37 +1,849 @@
 //! Simulator - Main simulation orchestration
 
-use crate::physics::{RigidBodyStorage, RapierBridge};
-use crate::scene::SceneBuilder;
+use crate::physics::{RigidBodyStorage, PhysicsBackend, PhysicsBackendKind, RayHit, TimeOfImpact, ImpactEvent, AudioCue, BodyContact, FluidSystem, RapierBridge, SimulationLod};
+use crate::physics::events;
+use crate::physics::aero::{self, AeroBody};
+use crate::scene::{SceneBuilder, SceneIssue, Vehicle};
+use crate::scene::report::single_body_issues;
+use crate::scene::builder::{quat_mul, quat_rotate, shape_half_extents, ShapeType};
+use crate::spatial::{self, CellStats, SpatialHash};
+use crate::contact_graph::ContactGraph;
+use crate::simd;
+use crate::sensors::{ImuSensor, ImuReading, ForceTorqueSensor, Wrench};
+use crate::time_ramp::TimeRamp;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Default physics step size for [`Simulator::advance`] (240 Hz)
+pub const DEFAULT_FIXED_DT: f32 = 1.0 / 240.0;
+
+/// Source of [`Simulator::id`] values
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Cube instance arrays: positions, rotations (x, y, z, w) and colors
+pub type CubeData = (Vec<[f32; 3]>, Vec<[f32; 4]>, Vec<[f32; 3]>);
+
+/// Per-shape instance arrays for rendering, refilled in place every frame
+///
+/// Filled by [`Simulator::instances_into`]; once the vectors have grown to
+/// the scene's size, refilling them does not allocate.
+#[derive(Debug, Clone, Default)]
+pub struct FrameInstances {
+    pub cube_positions: Vec<[f32; 3]>,
+    pub cube_rotations: Vec<[f32; 4]>,
+    pub cube_colors: Vec<[f32; 3]>,
+    /// Half extents of each cube along its local axes
+    pub cube_half_extents: Vec<[f32; 3]>,
+    /// Shading (roughness, metallic) of each cube
+    pub cube_surfaces: Vec<[f32; 2]>,
+    pub sphere_positions: Vec<[f32; 3]>,
+    pub sphere_radii: Vec<f32>,
+    pub sphere_colors: Vec<[f32; 3]>,
+    /// Shading (roughness, metallic) of each sphere
+    pub sphere_surfaces: Vec<[f32; 2]>,
+    /// Instances of each shape in [`ShapeType::AXIAL`], in that order
+    pub axial: [AxialInstances; ShapeType::AXIAL.len()],
+}
+
+impl FrameInstances {
+    /// Empty all arrays, keeping their capacity
+    pub fn clear(&mut self) {
+        self.cube_positions.clear();
+        self.cube_rotations.clear();
+        self.cube_colors.clear();
+        self.cube_half_extents.clear();
+        self.cube_surfaces.clear();
+        self.sphere_positions.clear();
+        self.sphere_radii.clear();
+        self.sphere_colors.clear();
+        self.sphere_surfaces.clear();
+        for axial in &mut self.axial {
+            axial.clear();
+        }
+    }
+
+    /// Instances of an axial shape (empty for cubes and spheres)
+    pub fn axial_of(&self, shape: ShapeType) -> &AxialInstances {
+        static EMPTY: AxialInstances = AxialInstances::new();
+        shape.axial_slot().map_or(&EMPTY, |slot| &self.axial[slot])
+    }
+}
+
+/// Instance arrays of one axial shape (capsules, cylinders, cones), see [`ShapeType::AXIAL`]
+#[derive(Debug, Clone, Default)]
+pub struct AxialInstances {
+    pub positions: Vec<[f32; 3]>,
+    pub rotations: Vec<[f32; 4]>,
+    pub radii: Vec<f32>,
+    /// Half length along the local Y axis
+    pub half_heights: Vec<f32>,
+    pub colors: Vec<[f32; 3]>,
+    /// Shading (roughness, metallic)
+    pub surfaces: Vec<[f32; 2]>,
+}
+
+impl AxialInstances {
+    pub const fn new() -> Self {
+        Self {
+            positions: Vec::new(),
+            rotations: Vec::new(),
+            radii: Vec::new(),
+            half_heights: Vec::new(),
+            colors: Vec::new(),
+            surfaces: Vec::new(),
+        }
+    }
+
+    /// Empty all arrays, keeping their capacity
+    pub fn clear(&mut self) {
+        self.positions.clear();
+        self.rotations.clear();
+        self.radii.clear();
+        self.half_heights.clear();
+        self.colors.clear();
+        self.surfaces.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Append body `i` of `storage` drawn in `color`
+    pub(crate) fn push(&mut self, storage: &RigidBodyStorage, i: usize, color: [f32; 3]) {
+        self.push_pose(storage.positions[i], storage.rotations[i], storage.radii[i], storage.half_heights[i], color, storage.surfaces[i]);
+    }
+
+    /// Append one instance
+    pub(crate) fn push_pose(
+        &mut self,
+        position: [f32; 3],
+        rotation: [f32; 4],
+        radius: f32,
+        half_height: f32,
+        color: [f32; 3],
+        surface: [f32; 2],
+    ) {
+        self.positions.push(position);
+        self.rotations.push(rotation);
+        self.radii.push(radius);
+        self.half_heights.push(half_height);
+        self.colors.push(color);
+        self.surfaces.push(surface);
+    }
+}
+
+/// What [`Simulator::apply_scene_update`] changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SceneUpdate {
+    /// Bodies appended to the world
+    pub added: usize,
+    /// Bodies removed from the end of the world
+    pub removed: usize,
+    /// Existing bodies re-created from changed configs
+    pub changed: usize,
+    /// The whole world was rebuilt (ground, planes, physics settings, joints or
+    /// vehicles changed, or a changed body had a joint)
+    pub rebuilt: bool,
+}
 
 /// Main physics simulator
 pub struct Simulator {
     /// SOA storage for rigid body data
     pub storage: RigidBodyStorage,
-    /// Rapier physics bridge
-    pub physics: RapierBridge,
+    /// Physics engine (Rapier unless chosen with [`Simulator::with_backend`])
+    physics: Box<dyn PhysicsBackend>,
     /// Current simulation time
     pub time: f32,
+    /// Unique per simulator instance
+    id: u64,
+    /// Whether impacts are kept for [`Simulator::take_impacts`]
+    record_impacts: bool,
+    /// Impacts recorded since the last drain
+    impacts: Vec<ImpactEvent>,
+    /// Impacts produced by the most recent step
+    last_impacts: Vec<ImpactEvent>,
+    /// Contacts of the most recent step, kept for heat exchange
+    heat_contacts: Vec<BodyContact>,
+    /// IMUs updated after every step
+    imus: Vec<ImuSensor>,
+    /// Force-torque sensors updated after every step
+    force_torque_sensors: Vec<ForceTorqueSensor>,
+    /// Whether [`Simulator::advance`] is frozen
+    paused: bool,
+    /// Simulated seconds per real second in [`Simulator::advance`]
+    time_scale: f32,
+    /// Physics step size used by [`Simulator::advance`] and [`Simulator::single_step`]
+    fixed_dt: f32,
+    /// Real time not yet consumed by whole steps
+    accumulator: f32,
+    /// Speed ramp overriding `time_scale` in [`Simulator::advance`]
+    time_ramp: Option<TimeRamp>,
+    /// Vehicles of the scene
+    vehicles: Vec<Vehicle>,
+    /// SPH liquid of the scene, if it has any fluid particles
+    fluid: Option<FluidSystem>,
+    /// Temperature of every body
+    temperatures: Vec<f32>,
+    /// Scene as last built or applied, diffed by [`Simulator::apply_scene_update`]
+    scene: SceneBuilder,
+    /// Bodies with child colliders, whose children are rendered with them
+    compound_bodies: Vec<usize>,
 }
 
 impl Simulator {
     /// Create a new simulator from a scene
     pub fn new(scene: &SceneBuilder) -> Self {
+        Self::with_backend(scene, PhysicsBackendKind::default().create())
+    }
+
+    /// Create a simulator running the scene on the given physics backend
+    ///
+    /// Features the backend does not support (see [`PhysicsBackend`]) fail
+    /// the way an invalid body or joint would.
+    pub fn with_backend(scene: &SceneBuilder, mut physics: Box<dyn PhysicsBackend>) -> Self {
+        Self::warn_severe_issues(scene);
         let mut storage = RigidBodyStorage::with_capacity(scene.bodies.len());
-        let mut physics = RapierBridge::new();
         physics.build_from_scene(scene, &mut storage);
 
         Self {
             storage,
             physics,
             time: 0.0,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            record_impacts: false,
+            impacts: Vec::new(),
+            last_impacts: Vec::new(),
+            heat_contacts: Vec::new(),
+            imus: Vec::new(),
+            force_torque_sensors: Vec::new(),
+            paused: false,
+            time_scale: 1.0,
+            fixed_dt: if scene.physics.fixed_dt > 0.0 { scene.physics.fixed_dt } else { DEFAULT_FIXED_DT },
+            accumulator: 0.0,
+            time_ramp: None,
+            vehicles: scene.vehicles.clone(),
+            fluid: Self::build_fluid(scene),
+            temperatures: scene.bodies.iter().map(|b| b.temperature).collect(),
+            scene: scene.clone(),
+            compound_bodies: Self::compound_bodies_of(scene),
+        }
+    }
+
+    /// Log the problems of single bodies that would make the scene blow up
+    /// on the first steps
+    ///
+    /// Pairs of bodies (overlaps) are left to [`SceneBuilder::validate`],
+    /// which is too slow to run on every build of a large scene.
+    fn warn_severe_issues(scene: &SceneBuilder) {
+        let severe: Vec<SceneIssue> = single_body_issues(scene).into_iter().filter(SceneIssue::is_severe).collect();
+        if let Some(first) = severe.first() {
+            log::warn!(
+                "scene has {} problems that can make the simulation explode (first: {}); see SceneBuilder::validate",
+                severe.len(),
+                first,
+            );
+        }
+    }
+
+    /// Indices of the bodies of a scene that have child colliders
+    fn compound_bodies_of(scene: &SceneBuilder) -> Vec<usize> {
+        scene.bodies.iter().enumerate()
+            .filter(|(_, body)| !body.children.is_empty())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Fluid particles of a scene, or None without any
+    fn build_fluid(scene: &SceneBuilder) -> Option<FluidSystem> {
+        (!scene.fluid.is_empty()).then(|| FluidSystem::new(&scene.fluid, scene.ground_y, scene.ground_size))
+    }
+
+    /// Bring the world in line with an edited copy of its scene, touching
+    /// only what changed
+    ///
+    /// Bodies are matched by index: bodies whose config differs are
+    /// re-created from it (restarting from their initial pose and velocity),
+    /// bodies past the end of `scene` are removed and new ones are added; all
+    /// other bodies keep their simulated state. Removing a body from the
+    /// middle of the scene therefore re-creates every body after it. Changes
+    /// to the ground, physics settings, joints or vehicles, or to a body with
+    /// a joint, rebuild the whole world and restart the clock, as does any
+    /// edit on a backend that cannot update bodies in place. The fluid
+    /// restarts from its initial particles when it changed or the world was
+    /// rebuilt. Sensors on removed bodies are dropped, which renumbers later
+    /// sensors.
+    pub fn apply_scene_update(&mut self, scene: &SceneBuilder) -> SceneUpdate {
+        let old = &self.scene;
+        let fluid_changed = old.fluid != scene.fluid;
+        let heat_changed = old.heat != scene.heat;
+        let common = old.bodies.len().min(scene.bodies.len());
+        let changed: Vec<usize> = (0..common).filter(|&i| old.bodies[i] != scene.bodies[i]).collect();
+        let removed = old.bodies.len() - common;
+        let added = scene.bodies.len() - common;
+        let structural = old.joints != scene.joints
+            || old.vehicles != scene.vehicles
+            || old.ground_y != scene.ground_y
+            || old.ground_size != scene.ground_size
+            || old.planes != scene.planes
+            || old.physics != scene.physics;
+        if !structural && !fluid_changed && !heat_changed && changed.is_empty() && removed == 0 && added == 0 {
+            return SceneUpdate::default();
+        }
+
+        let jointed = |i: usize| scene.joints.iter().any(|j| j.body_a == i || j.body_b == Some(i));
+        let rebuilt = structural
+            || changed.iter().copied().chain(common..old.bodies.len()).any(jointed)
+            || !self.physics.update_bodies(scene, &changed, &mut self.storage);
+        if rebuilt {
+            if self.scene.physics.fixed_dt != scene.physics.fixed_dt {
+                self.set_fixed_dt(scene.physics.fixed_dt);
+            }
+            self.physics.build_from_scene(scene, &mut self.storage);
+            self.time = 0.0;
+            self.accumulator = 0.0;
+            self.vehicles = scene.vehicles.clone();
+        }
+        if rebuilt || fluid_changed {
+            self.fluid = Self::build_fluid(scene);
+        }
+        if rebuilt {
+            self.temperatures = scene.bodies.iter().map(|b| b.temperature).collect();
+        } else {
+            self.temperatures.truncate(common);
+            for &i in &changed {
+                self.temperatures[i] = scene.bodies[i].temperature;
+            }
+            self.temperatures.extend(scene.bodies[common..].iter().map(|b| b.temperature));
+        }
+
+        let len = scene.bodies.len();
+        self.imus.retain(|imu| imu.body < len);
+        self.force_torque_sensors.retain(|sensor| sensor.body < len);
+        // Static geometry may have changed; renderers re-upload for a new id
+        self.id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        self.scene = scene.clone();
+        self.compound_bodies = Self::compound_bodies_of(scene);
+        SceneUpdate { added, removed, changed: changed.len(), rebuilt }
+    }
+
+    /// Scene as last built or applied with [`Simulator::apply_scene_update`]
+    pub fn scene(&self) -> &SceneBuilder {
+        &self.scene
+    }
+
+    /// Physics engine simulating the scene
+    pub fn physics(&self) -> &dyn PhysicsBackend {
+        self.physics.as_ref()
+    }
+
+    /// Mutable access to the physics engine; changes made here bypass
+    /// storage until the next step syncs it
+    pub fn physics_mut(&mut self) -> &mut dyn PhysicsBackend {
+        self.physics.as_mut()
+    }
+
+    /// The Rapier world, for Rapier-only features; None when another
+    /// backend was chosen with [`Simulator::with_backend`]
+    pub fn rapier(&self) -> Option<&RapierBridge> {
+        self.physics.as_rapier()
+    }
+
+    /// Mutable form of [`Simulator::rapier`]
+    pub fn rapier_mut(&mut self) -> Option<&mut RapierBridge> {
+        self.physics.as_rapier_mut()
+    }
+
+    /// Push bodies with aerodynamic coefficients by the wind for `dt` seconds
+    fn apply_aero(&mut self, dt: f32) {
+        let wind = self.scene.physics.wind;
+        let ground_y = self.scene.ground_y.unwrap_or(0.0);
+        for (i, config) in self.scene.bodies.iter().enumerate() {
+            let Some(coefficients) = &config.aero else {
+                continue;
+            };
+            if config.is_static || config.motion_path.is_some() {
+                continue;
+            }
+            let body = AeroBody {
+                shape: config.shape,
+                half_extents: match config.shape {
+                    ShapeType::Cube => config.half_extents,
+                    ShapeType::Sphere => [config.radius; 3],
+                    ShapeType::Capsule => [config.radius, config.half_height + config.radius, config.radius],
+                    ShapeType::Cylinder | ShapeType::Cone => [config.radius, config.half_height, config.radius],
+                },
+                rotation: self.storage.rotations[i],
+                linear_velocity: self.storage.linear_velocities[i],
+                angular_velocity: self.storage.angular_velocities[i],
+            };
+            let air = wind.velocity_at(self.storage.positions[i], ground_y, self.time);
+            let (force, torque) = aero::aero_wrench(coefficients, &body, air, wind.air_density);
+            self.physics.apply_impulse(i, force.map(|f| f * dt), torque.map(|t| t * dt));
         }
     }
 
     /// Step the simulation forward by dt seconds
     pub fn step(&mut self, dt: f32) {
+        self.apply_aero(dt);
         self.physics.step(dt);
         self.physics.sync_to_storage(&mut self.storage);
+        if let Some(fluid) = &mut self.fluid {
+            fluid.step(dt, self.physics.gravity(), &self.storage);
+            if fluid.settings().two_way {
+                for (index, [linear, angular]) in fluid.body_impulses().iter().enumerate() {
+                    if linear.norm_squared() > 0.0 || angular.norm_squared() > 0.0 {
+                        self.physics.apply_impulse(index, (*linear).into(), (*angular).into());
+                    }
+                }
+            }
+        }
+        if self.scene.heat.enabled() {
+            self.heat_contacts.clear();
+            self.physics.contacts_into(&mut self.heat_contacts);
+            let storage = &self.storage;
+            self.scene.heat.exchange(&mut self.temperatures, &self.heat_contacts, |i| storage.is_static(i), dt);
+        }
         self.time += dt;
+
+        let time = self.time;
+        self.last_impacts.clear();
+        self.physics.take_impacts_into(&mut self.last_impacts);
+        for e in &mut self.last_impacts {
+            e.time = time;
+        }
+        if self.record_impacts {
+            self.impacts.extend_from_slice(&self.last_impacts);
+        }
+
+        let gravity = self.physics.gravity();
+        for imu in &mut self.imus {
+            let i = imu.body;
+            imu.update(
+                time,
+                dt,
+                self.storage.linear_velocities[i],
+                self.storage.angular_velocities[i],
+                self.storage.rotations[i],
+                gravity,
+            );
+        }
+        for sensor in &mut self.force_torque_sensors {
+            if let Some(momentum) = self.physics.momentum(sensor.body) {
+                sensor.update(time, dt, momentum, self.storage.rotations[sensor.body], gravity);
+            }
+        }
+    }
+
+    /// Advance by `dt` seconds of real (wall-clock or video) time
+    ///
+    /// Time is scaled by the time scale (or speed ramp) and consumed in whole
+    /// fixed-size steps, so slow motion takes fewer steps per call rather than
+    /// smaller ones; leftover time carries over to the next call. Does nothing
+    /// while paused. Returns the number of steps taken.
+    pub fn advance(&mut self, dt: f32) -> u32 {
+        self.advance_with(dt, |_| {})
+    }
+
+    /// [`Simulator::advance`], calling `on_step` after every physics step
+    pub fn advance_with(&mut self, dt: f32, mut on_step: impl FnMut(&Self)) -> u32 {
+        if self.paused || self.effective_time_scale() <= 0.0 {
+            return 0;
+        }
+        self.accumulator += dt.max(0.0);
+
+        // Each step costs fixed_dt / scale of real time; re-evaluating the
+        // scale per step lets a speed ramp change within a single call
+        let mut steps = 0;
+        loop {
+            let scale = self.effective_time_scale();
+            if scale <= 0.0 {
+                break;
+            }
+            let cost = self.fixed_dt / scale;
+            // Tolerance keeps float round-off from dropping a step at exact multiples
+            if self.accumulator < cost * (1.0 - 1e-4) {
+                break;
+            }
+            self.step(self.fixed_dt);
+            self.accumulator = (self.accumulator - cost).max(0.0);
+            on_step(self);
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Take exactly one fixed-size step, even while paused (frame-by-frame examination)
+    pub fn single_step(&mut self) {
+        self.step(self.fixed_dt);
+    }
+
+    /// Freeze or resume [`Simulator::advance`]
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Whether [`Simulator::advance`] is frozen
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Set simulated seconds per real second (0.1 = 10x slow motion, 2 = double speed)
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale = scale.max(0.0);
+    }
+
+    /// Simulated seconds per real second
+    pub fn time_scale(&self) -> f32 {
+        self.time_scale
+    }
+
+    /// Follow a speed ramp keyed on simulation time instead of the constant
+    /// time scale (None restores the constant scale)
+    pub fn set_time_ramp(&mut self, ramp: Option<TimeRamp>) {
+        self.time_ramp = ramp;
+    }
+
+    /// Speed ramp in effect, if any
+    pub fn time_ramp(&self) -> Option<&TimeRamp> {
+        self.time_ramp.as_ref()
+    }
+
+    /// Time scale [`Simulator::advance`] uses at the current simulation time
+    pub fn effective_time_scale(&self) -> f32 {
+        self.time_ramp.as_ref().map_or(self.time_scale, |ramp| ramp.scale_at(self.time))
+    }
+
+    /// Set the physics step size used by [`Simulator::advance`] and [`Simulator::single_step`]
+    ///
+    /// Simulators start with the scene's [`crate::PhysicsSettings::fixed_dt`].
+    pub fn set_fixed_dt(&mut self, dt: f32) {
+        if dt > 0.0 {
+            self.fixed_dt = dt;
+        }
+    }
+
+    /// Physics step size used by [`Simulator::advance`] and [`Simulator::single_step`]
+    pub fn fixed_dt(&self) -> f32 {
+        self.fixed_dt
+    }
+
+    /// Attach an IMU to a body; returns the sensor id, or None for an invalid body
+    pub fn add_imu(&mut self, imu: ImuSensor) -> Option<usize> {
+        if imu.body >= self.storage.len() {
+            return None;
+        }
+        self.imus.push(imu);
+        Some(self.imus.len() - 1)
+    }
+
+    /// Attach a force-torque sensor to a body; returns the sensor id, or None for an invalid body
+    pub fn add_force_torque_sensor(&mut self, sensor: ForceTorqueSensor) -> Option<usize> {
+        let momentum = self.physics.momentum(sensor.body)?;
+        let mut sensor = sensor;
+        // Prime with the current state so the next step yields a wrench
+        sensor.update(self.time, 0.0, momentum, self.storage.rotations[sensor.body], self.physics.gravity());
+        self.force_torque_sensors.push(sensor);
+        Some(self.force_torque_sensors.len() - 1)
+    }
+
+    /// Take the wrenches a force-torque sensor recorded since the last call
+    pub fn take_wrenches(&mut self, id: usize) -> Option<Vec<Wrench>> {
+        self.force_torque_sensors.get_mut(id).map(|s| s.take_history())
+    }
+
+    /// Latest reading of an IMU
+    pub fn imu_reading(&self, id: usize) -> Option<ImuReading> {
+        self.imus.get(id).map(|imu| imu.reading())
+    }
+
+    /// Enable or disable impact recording (off by default)
+    pub fn set_impact_recording(&mut self, enabled: bool) {
+        self.record_impacts = enabled;
+        if !enabled {
+            self.impacts.clear();
+        }
+    }
+
+    /// Whether impacts are being recorded
+    pub fn impact_recording(&self) -> bool {
+        self.record_impacts
+    }
+
+    /// Take the impacts recorded since the last call
+    ///
+    /// Calling this once per rendered frame buckets the impacts of all
+    /// substeps into that frame.
+    pub fn take_impacts(&mut self) -> Vec<ImpactEvent> {
+        std::mem::take(&mut self.impacts)
+    }
+
+    /// Impacts produced by the most recent step (always available, not drained)
+    pub fn last_impacts(&self) -> &[ImpactEvent] {
+        &self.last_impacts
+    }
+
+    /// Material name of a body for sound cues (None = ground/static geometry)
+    pub fn material_name(&self, body: Option<usize>) -> String {
+        body.and_then(|i| self.storage.shape_types.get(i))
+            .and_then(|&id| ShapeType::from_id(id))
+            .map_or("ground", |shape| shape.name())
+            .to_string()
+    }
+
+    /// Convert impact events into an audio cue list (time, intensity, material)
+    pub fn audio_cues(&self, events: &[ImpactEvent], min_speed: f32) -> Vec<AudioCue> {
+        events::audio_cues(events, min_speed, |body| self.material_name(body))
+    }
+
+    /// Closest body or ground hit along a ray (e.g. from [`Camera::screen_ray`](crate::gpu::Camera::screen_ray))
+    pub fn raycast(&self, origin: [f32; 3], direction: [f32; 3], max_distance: f32) -> Option<RayHit> {
+        self.physics.cast_ray(origin, direction, max_distance)
+    }
+
+    /// Predicted first contact of two bodies within `max_t` seconds, without stepping
+    ///
+    /// See [`RapierBridge::time_of_impact`] for the motion model.
+    pub fn predict_toi(&self, body_a: usize, body_b: usize, max_t: f32) -> Option<TimeOfImpact> {
+        self.physics.time_of_impact(body_a, body_b, max_t)
+    }
+
+    /// Next new contact of a body with anything in the scene within `max_t` seconds
+    pub fn predict_impact(&self, body: usize, max_t: f32) -> Option<TimeOfImpact> {
+        self.physics.predict_impact(body, max_t)
+    }
+
+    /// Start dragging a body by a world-space point; returns the mouse joint id
+    ///
+    /// See [`RapierBridge::add_mouse_joint`] for the stiffness and damping units.
+    pub fn grab(&mut self, body: usize, point: [f32; 3], stiffness: f32, damping: f32) -> Option<usize> {
+        self.physics.add_mouse_joint(body, point, stiffness, damping)
+    }
+
+    /// Move the point a grabbed body is pulled toward
+    pub fn drag(&mut self, joint: usize, target: [f32; 3]) -> bool {
+        self.physics.set_mouse_joint_target(joint, target)
+    }
+
+    /// Let go of a grabbed body
+    pub fn release(&mut self, joint: usize) -> bool {
+        self.physics.remove_mouse_joint(joint)
+    }
+
+    /// Rigidly attach body `b` to body `a` (e.g. an object to a gripper);
+    /// returns the attachment id
+    ///
+    /// See [`RapierBridge::attach`] for the pose convention.
+    pub fn attach(&mut self, a: usize, b: usize, relative_pose: Option<([f32; 3], [f32; 4])>) -> Option<usize> {
+        self.physics.attach(a, b, relative_pose)
+    }
+
+    /// Release an attachment made with [`Simulator::attach`]
+    pub fn detach(&mut self, attachment: usize) -> bool {
+        self.physics.detach(attachment)
+    }
+
+    /// Drive a vehicle: `throttle` and `steering` in [-1, 1]
+    ///
+    /// Positive throttle drives forward, negative reverses and zero lets the
+    /// wheels roll freely; positive steering turns left. Returns false for an
+    /// unknown vehicle.
+    pub fn set_vehicle_controls(&mut self, vehicle: usize, throttle: f32, steering: f32) -> bool {
+        let Some(vehicle) = self.vehicles.get(vehicle) else {
+            return false;
+        };
+        let (throttle, steering) = (throttle.clamp(-1.0, 1.0), steering.clamp(-1.0, 1.0));
+        let torque = if throttle == 0.0 { 0.0 } else { vehicle.motor_torque };
+        for (k, &joint) in vehicle.wheel_joints.iter().enumerate() {
+            let front = k < 2;
+            if front {
+                self.physics.set_wheel_steering(joint, steering * vehicle.max_steering_angle);
+            }
+            if !front || vehicle.all_wheel_drive {
+                self.physics.set_wheel_drive(joint, throttle * vehicle.max_wheel_speed, torque);
+            }
+        }
+        true
+    }
+
+    /// World-space end points of every spring joint, in joint order
+    pub fn spring_endpoints(&self) -> impl Iterator<Item = ([f32; 3], [f32; 3])> + '_ {
+        self.physics.spring_endpoints().into_iter()
+    }
+
+    /// SPH liquid of the scene (None if it has no fluid particles)
+    pub fn fluid(&self) -> Option<&FluidSystem> {
+        self.fluid.as_ref()
+    }
+
+    /// Vehicles of the scene, by vehicle index
+    pub fn vehicles(&self) -> &[Vehicle] {
+        &self.vehicles
+    }
+
+    /// Lock or unlock movement of a body along world axes (x, y, z)
+    ///
+    /// In a planar scene z stays locked. Returns false for an invalid or static body.
+    pub fn lock_translations(&mut self, body: usize, axes: [bool; 3]) -> bool {
+        self.physics.lock_translations(body, axes)
+    }
+
+    /// Lock or unlock rotation of a body about world axes (x, y, z)
+    ///
+    /// In a planar scene x and y stay locked. Returns false for an invalid or static body.
+    pub fn lock_rotations(&mut self, body: usize, axes: [bool; 3]) -> bool {
+        self.physics.lock_rotations(body, axes)
+    }
+
+    /// Spatial hash over the current body positions with cells of `cell_size`
+    ///
+    /// Build once and query many times when running several queries on the
+    /// same state.
+    pub fn spatial_hash(&self, cell_size: f32) -> SpatialHash {
+        SpatialHash::build(&self.storage.positions, cell_size)
+    }
+
+    /// Bodies whose centers are within `radius` of body `index`'s center
+    ///
+    /// Builds a fresh hash (O(N)); use [`Simulator::spatial_hash`] for many queries.
+    pub fn neighbors_within(&self, index: usize, radius: f32) -> Vec<usize> {
+        self.spatial_hash(radius).neighbors_within(index, radius)
+    }
+
+    /// Every pair (i < j) of bodies whose centers are within `radius`
+    pub fn all_pairs_within(&self, radius: f32) -> Vec<(usize, usize)> {
+        self.spatial_hash(radius).all_pairs_within(radius)
+    }
+
+    /// Per-cell body counts, mean speeds and masses over a grid of `cell_size` cells
+    pub fn aggregate_by_grid(&self, cell_size: f32) -> Vec<CellStats> {
+        spatial::aggregate_by_grid(&self.storage, cell_size)
+    }
+
+    /// Total translational kinetic energy of all bodies (J)
+    pub fn kinetic_energy(&self) -> f32 {
+        simd::kinetic_energy(&self.storage.linear_velocities, &self.storage.masses)
+    }
+
+    /// Total linear momentum of all bodies (kg·m/s)
+    pub fn linear_momentum(&self) -> [f32; 3] {
+        simd::linear_momentum(&self.storage.linear_velocities, &self.storage.masses)
+    }
+
+    /// Pairs of bodies with extreme mass ratios found when the world was
+    /// last built (see [`RapierBridge::build_warnings`])
+    pub fn build_warnings(&self) -> &[SceneIssue] {
+        self.physics.build_warnings()
+    }
+
+    /// Constraint island of every body (None for static bodies)
+    ///
+    /// See [`RapierBridge::island_ids`]; reflects the contacts of the last step.
+    pub fn island_ids(&self) -> Vec<Option<usize>> {
+        self.physics.island_ids()
+    }
+
+    /// Whether each body is asleep
+    pub fn sleeping(&self) -> Vec<bool> {
+        (0..self.storage.len()).map(|i| self.physics.is_sleeping(i)).collect()
+    }
+
+    /// Freeze every sleeping dynamic body; returns the indices frozen
+    ///
+    /// Frozen bodies become fixed (see [`RapierBridge::freeze`]), so settled
+    /// piles cost nothing however large they grow while new bodies keep
+    /// landing on them. Impacts do not wake frozen bodies: call
+    /// [`Simulator::unfreeze`] or [`Simulator::unfreeze_all`] to let them
+    /// move again, e.g. before knocking a pile over. Nothing is frozen on
+    /// backends without sleeping or freezing.
+    pub fn freeze_sleeping(&mut self) -> Vec<usize> {
+        (0..self.storage.len())
+            .filter(|&i| !self.storage.is_static(i) && self.physics.is_sleeping(i) && self.physics.freeze(i))
+            .collect()
+    }
+
+    /// Freeze a dynamic body whether or not it sleeps; returns false for
+    /// invalid, static, path-following or already frozen bodies
+    pub fn freeze(&mut self, index: usize) -> bool {
+        self.physics.freeze(index)
+    }
+
+    /// Let a frozen body move again; returns false if it was not frozen
+    pub fn unfreeze(&mut self, index: usize) -> bool {
+        self.physics.unfreeze(index)
+    }
+
+    /// Let every frozen body move again; returns how many were frozen
+    pub fn unfreeze_all(&mut self) -> usize {
+        (0..self.storage.len()).filter(|&i| self.physics.unfreeze(i)).count()
+    }
+
+    /// Indices of the frozen bodies
+    pub fn frozen_bodies(&self) -> Vec<usize> {
+        (0..self.storage.len()).filter(|&i| self.physics.is_frozen(i)).collect()
+    }
+
+    /// Simulate only bodies near a focus point in full, or every body again
+    /// with None; returns false if the backend does not support it
+    ///
+    /// Awake bodies farther than `radius + margin` from the focus stop
+    /// colliding and fly ballistically until they land on the ground or
+    /// come back within `radius` (see [`SimulationLod`]). Jointed bodies are
+    /// always simulated in full.
+    pub fn set_lod(&mut self, lod: Option<SimulationLod>) -> bool {
+        self.physics.set_lod(lod)
+    }
+
+    /// Move the focus of the level of simulation, e.g. with the camera;
+    /// does nothing while it is off
+    pub fn set_lod_focus(&mut self, focus: [f32; 3]) {
+        if let Some(mut lod) = self.physics.lod() {
+            lod.focus = focus;
+            self.physics.set_lod(Some(lod));
+        }
+    }
+
+    /// Indices of the bodies currently simplified by the level of simulation
+    pub fn coarse_bodies(&self) -> Vec<usize> {
+        (0..self.storage.len()).filter(|&i| self.physics.is_coarse(i)).collect()
+    }
+
+    /// Contact network after the last step: every touching pair of bodies
+    /// (or body and ground) with the normal impulse between them
+    ///
+    /// Empty on backends that do not report contacts.
+    pub fn contact_graph(&self) -> ContactGraph {
+        let mut contacts = Vec::new();
+        self.physics.contacts_into(&mut contacts);
+        ContactGraph::new(self.time, self.storage.positions.clone(), self.storage.statics.clone(), contacts)
+    }
+
+    /// Current temperature of every body (see [`HeatSettings`](crate::physics::HeatSettings))
+    pub fn temperatures(&self) -> &[f32] {
+        &self.temperatures
+    }
+
+    /// Set a body's current temperature; returns false for an invalid index
+    pub fn set_temperature(&mut self, index: usize, temperature: f32) -> bool {
+        let Some(t) = self.temperatures.get_mut(index) else {
+            return false;
+        };
+        *t = temperature;
+        true
+    }
+
+    /// Unique id of this simulator (lets renderers cache its static geometry)
+    ///
+    /// A new id is assigned whenever a scene update is applied.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Number of static bodies
+    pub fn static_count(&self) -> usize {
+        ShapeType::ALL.iter().map(|&shape| self.storage.indices_of(shape, Some(true)).len()).sum()
     }
 
     /// Get number of bodies
@@ -49,7 +861,7 @@ impl Simulator {
         &self.storage.rotations
     }
 
-    /// Get shape types (0 = cube, 1 = sphere)
+    /// Get shape types (0 = cube, 1 = sphere, 2 = capsule, 3 = cylinder, 4 = cone)
     pub fn shape_types(&self) -> &[u8] {
         &self.storage.shape_types
     }
@@ -59,18 +871,121 @@ impl Simulator {
         &self.storage.radii
     }
 
+    /// Get half heights along the local Y axis (0 for cubes and spheres)
+    pub fn half_heights(&self) -> &[f32] {
+        &self.storage.half_heights
+    }
+
+    /// Half extents of a box body along its local axes
+    ///
+    /// Storage keeps one size per body, so the full extents come from the
+    /// scene; bodies not in it are treated as cubes of their stored size.
+    pub fn cube_half_extents(&self, index: usize) -> [f32; 3] {
+        self.scene.bodies.get(index).map_or([self.storage.radii[index]; 3], |body| body.half_extents)
+    }
+
+    /// Half extents of the smallest box along a body's local axes enclosing
+    /// its own shape (child colliders are not included)
+    pub fn local_half_extents(&self, index: usize) -> [f32; 3] {
+        let shape = ShapeType::from_id(self.storage.shape_types[index]).unwrap_or(ShapeType::Cube);
+        let radius = self.storage.radii[index];
+        shape_half_extents(shape, self.cube_half_extents(index), radius, self.storage.half_heights[index])
+    }
+
     /// Get cube data (positions, rotations, and colors for cubes only)
-    pub fn cube_data(&self) -> (Vec<[f32; 3]>, Vec<[f32; 4]>, Vec<[f32; 3]>) {
-        let indices = self.storage.cube_indices();
+    pub fn cube_data(&self) -> CubeData {
+        self.cube_data_of(self.storage.indices_of(ShapeType::Cube, None))
+    }
+
+    /// Get sphere data (positions, radii, and colors for spheres only)
+    pub fn sphere_data(&self) -> (Vec<[f32; 3]>, Vec<f32>, Vec<[f32; 3]>) {
+        self.sphere_data_of(self.storage.indices_of(ShapeType::Sphere, None))
+    }
+
+    /// Cube data of static or dynamic cubes only
+    pub fn partition_cube_data(&self, statics: bool) -> CubeData {
+        self.cube_data_of(self.storage.indices_of(ShapeType::Cube, Some(statics)))
+    }
+
+    /// Sphere data of static or dynamic spheres only
+    pub fn partition_sphere_data(&self, statics: bool) -> (Vec<[f32; 3]>, Vec<f32>, Vec<[f32; 3]>) {
+        self.sphere_data_of(self.storage.indices_of(ShapeType::Sphere, Some(statics)))
+    }
+
+    /// Whether a static body is left out of rendering (see
+    /// [`SceneBuilder::set_hidden`])
+    pub fn is_hidden(&self, index: usize) -> bool {
+        self.storage.is_static(index) && self.scene.bodies.get(index).is_some_and(|body| body.hidden)
+    }
+
+    /// Refill `out` with the instance data of all visible bodies, or only the
+    /// static (`Some(true)`) or dynamic (`Some(false)`) ones
+    pub fn instances_into(&self, statics: Option<bool>, out: &mut FrameInstances) {
+        out.clear();
+        let storage = &self.storage;
+        for &i in storage.indices_of(ShapeType::Cube, statics).iter().filter(|&&i| !self.is_hidden(i)) {
+            out.cube_positions.push(storage.positions[i]);
+            out.cube_rotations.push(storage.rotations[i]);
+            out.cube_colors.push(storage.colors[i]);
+            out.cube_half_extents.push(self.cube_half_extents(i));
+            out.cube_surfaces.push(storage.surfaces[i]);
+        }
+        for &i in storage.indices_of(ShapeType::Sphere, statics).iter().filter(|&&i| !self.is_hidden(i)) {
+            out.sphere_positions.push(storage.positions[i]);
+            out.sphere_radii.push(storage.radii[i]);
+            out.sphere_colors.push(storage.colors[i]);
+            out.sphere_surfaces.push(storage.surfaces[i]);
+        }
+        for (shape, axial) in ShapeType::AXIAL.into_iter().zip(&mut out.axial) {
+            for &i in storage.indices_of(shape, statics).iter().filter(|&&i| !self.is_hidden(i)) {
+                axial.push(storage, i, storage.colors[i]);
+            }
+        }
+
+        // Children of compound bodies follow their parent's pose
+        for &i in &self.compound_bodies {
+            if i >= storage.len() || statics.is_some_and(|s| s != storage.is_static(i)) || self.is_hidden(i) {
+                continue;
+            }
+            let (position, rotation) = (storage.positions[i], storage.rotations[i]);
+            for child in &self.scene.bodies[i].children {
+                let offset = quat_rotate(rotation, child.offset);
+                let child_position = [position[0] + offset[0], position[1] + offset[1], position[2] + offset[2]];
+                let child_rotation = quat_mul(rotation, child.rotation);
+                let color = child.color.unwrap_or(storage.colors[i]);
+                let surface = storage.surfaces[i];
+                match child.shape {
+                    ShapeType::Cube => {
+                        out.cube_positions.push(child_position);
+                        out.cube_rotations.push(child_rotation);
+                        out.cube_colors.push(color);
+                        out.cube_half_extents.push(child.half_extents);
+                        out.cube_surfaces.push(surface);
+                    }
+                    ShapeType::Sphere => {
+                        out.sphere_positions.push(child_position);
+                        out.sphere_radii.push(child.radius);
+                        out.sphere_colors.push(color);
+                        out.sphere_surfaces.push(surface);
+                    }
+                    shape => {
+                        if let Some(slot) = shape.axial_slot() {
+                            out.axial[slot].push_pose(child_position, child_rotation, child.radius, child.half_height, color, surface);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn cube_data_of(&self, indices: &[usize]) -> CubeData {
         let positions: Vec<_> = indices.iter().map(|&i| self.storage.positions[i]).collect();
         let rotations: Vec<_> = indices.iter().map(|&i| self.storage.rotations[i]).collect();
         let colors: Vec<_> = indices.iter().map(|&i| self.storage.colors[i]).collect();
         (positions, rotations, colors)
     }
 
-    /// Get sphere data (positions, radii, and colors for spheres only)
-    pub fn sphere_data(&self) -> (Vec<[f32; 3]>, Vec<f32>, Vec<[f32; 3]>) {
-        let indices = self.storage.sphere_indices();
+    fn sphere_data_of(&self, indices: &[usize]) -> (Vec<[f32; 3]>, Vec<f32>, Vec<[f32; 3]>) {
         let positions: Vec<_> = indices.iter().map(|&i| self.storage.positions[i]).collect();
         let radii: Vec<_> = indices.iter().map(|&i| self.storage.radii[i]).collect();
         let colors: Vec<_> = indices.iter().map(|&i| self.storage.colors[i]).collect();
@@ -0,0 +1,180 @@
+//! Uniform-grid spatial hash over body positions
+//!
+//! Analysis code (clustering, contact prediction, force fields) often needs
+//! every body near a point or every close pair. Bucketing positions into
+//! cubic cells turns those O(N²) scans into near-linear ones. The hash is a
+//! snapshot: rebuild it after stepping. The same grid also aggregates
+//! per-cell statistics for density heatmaps of granular flows.
+
+use std::collections::HashMap;
+use crate::physics::RigidBodyStorage;
+
+/// Bodies bucketed by grid cell
+#[derive(Debug, Clone, Default)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<[i32; 3], Vec<usize>>,
+    positions: Vec<[f32; 3]>,
+}
+
+impl SpatialHash {
+    /// Bucket `positions` into cells of edge `cell_size`
+    ///
+    /// Queries are fastest when the cell size is close to the query radius.
+    pub fn build(positions: &[[f32; 3]], cell_size: f32) -> Self {
+        let mut hash = Self::default();
+        hash.rebuild(positions, cell_size);
+        hash
+    }
+
+    /// Re-bucket new positions, reusing the cell allocations
+    pub fn rebuild(&mut self, positions: &[[f32; 3]], cell_size: f32) {
+        self.cell_size = cell_size.max(1e-6);
+        self.cells.values_mut().for_each(Vec::clear);
+        self.positions.clear();
+        self.positions.extend_from_slice(positions);
+        for (i, &p) in positions.iter().enumerate() {
+            let cell = self.cell_of(p);
+            self.cells.entry(cell).or_default().push(i);
+        }
+    }
+
+    /// Cell edge length
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Number of bodies hashed
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Whether no bodies were hashed
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Bodies whose centers lie within `radius` of `point`, in index order
+    pub fn query_point(&self, point: [f32; 3], radius: f32) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.visit_near(point, radius, |i| found.push(i));
+        found.sort_unstable();
+        found
+    }
+
+    /// Bodies within `radius` of body `index` (excluding itself), in index order
+    pub fn neighbors_within(&self, index: usize, radius: f32) -> Vec<usize> {
+        let Some(&point) = self.positions.get(index) else {
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        self.visit_near(point, radius, |i| {
+            if i != index {
+                found.push(i);
+            }
+        });
+        found.sort_unstable();
+        found
+    }
+
+    /// Every pair (i < j) of bodies whose centers are within `radius`, sorted
+    pub fn all_pairs_within(&self, radius: f32) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::new();
+        for (i, &p) in self.positions.iter().enumerate() {
+            self.visit_near(p, radius, |j| {
+                if i < j {
+                    pairs.push((i, j));
+                }
+            });
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Call `visit` for every body within `radius` of `point`
+    fn visit_near(&self, point: [f32; 3], radius: f32, mut visit: impl FnMut(usize)) {
+        if radius < 0.0 {
+            return;
+        }
+        let min = self.cell_of([point[0] - radius, point[1] - radius, point[2] - radius]);
+        let max = self.cell_of([point[0] + radius, point[1] + radius, point[2] + radius]);
+        let radius_sq = radius * radius;
+        let mut check = |bodies: &[usize]| {
+            for &i in bodies {
+                let q = self.positions[i];
+                let d = [q[0] - point[0], q[1] - point[1], q[2] - point[2]];
+                if d[0] * d[0] + d[1] * d[1] + d[2] * d[2] <= radius_sq {
+                    visit(i);
+                }
+            }
+        };
+
+        // With a radius much larger than the cells, scanning the occupied
+        // cells beats probing every cell in range
+        let span = |axis: usize| (max[axis] as i64 - min[axis] as i64 + 1) as u64;
+        if span(0).saturating_mul(span(1)).saturating_mul(span(2)) > self.cells.len() as u64 {
+            for (cell, bodies) in &self.cells {
+                if (0..3).all(|a| (min[a]..=max[a]).contains(&cell[a])) {
+                    check(bodies);
+                }
+            }
+            return;
+        }
+
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    if let Some(bodies) = self.cells.get(&[x, y, z]) {
+                        check(bodies);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Grid cell containing a point
+    fn cell_of(&self, p: [f32; 3]) -> [i32; 3] {
+        p.map(|c| (c / self.cell_size).floor() as i32)
+    }
+}
+
+/// Bodies in one grid cell, summarized
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellStats {
+    /// Integer cell coordinates (cell spans `cell * size .. (cell + 1) * size`)
+    pub cell: [i32; 3],
+    /// Bodies whose centers lie in the cell
+    pub count: u32,
+    /// Mean linear speed of those bodies (m/s)
+    pub mean_speed: f32,
+    /// Total mass of those bodies (kg)
+    pub mass: f32,
+}
+
+/// Per-cell body counts, mean speeds and masses, sorted by cell
+///
+/// One pass over the SOA arrays; only occupied cells are returned.
+pub fn aggregate_by_grid(storage: &RigidBodyStorage, cell_size: f32) -> Vec<CellStats> {
+    let cell_size = cell_size.max(1e-6);
+    let mut cells: HashMap<[i32; 3], (u32, f32, f32)> = HashMap::new();
+
+    for ((p, v), &m) in storage.positions.iter().zip(&storage.linear_velocities).zip(&storage.masses) {
+        let cell = p.map(|c| (c / cell_size).floor() as i32);
+        let speed = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+        let entry = cells.entry(cell).or_insert((0, 0.0, 0.0));
+        entry.0 += 1;
+        entry.1 += speed;
+        entry.2 += m;
+    }
+
+    let mut stats: Vec<CellStats> = cells.into_iter()
+        .map(|(cell, (count, speed_sum, mass))| CellStats {
+            cell,
+            count,
+            mean_speed: speed_sum / count as f32,
+            mass,
+        })
+        .collect();
+    stats.sort_unstable_by_key(|s| s.cell);
+    stats
+}
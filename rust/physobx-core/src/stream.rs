@@ -0,0 +1,297 @@
+//! Binary frame streaming to external viewers
+//!
+//! A headless simulation can be watched live by a separate process: every
+//! published step is encoded as one compact little-endian frame and written
+//! to a [`FrameSink`] (a TCP or Unix socket). Frames are prefixed with their
+//! length in bytes as a `u32`, so a viewer reads 4 bytes, then that many.
+//!
+//! A frame is a fixed header followed by sections (all values little-endian,
+//! `N` bodies, `M` events):
+//!
+//! | Section | Layout |
+//! |---------|--------|
+//! | header (32 B) | magic `b"PBXF"`, version `u16`, flags `u16`, frame `u64`, time `f32`, `N` `u32`, `M` `u32`, reserved `u32` |
+//! | positions | `N × 3 f32` |
+//! | rotations | `N × 4 f32` quaternions (x, y, z, w) |
+//! | shapes (flag bit 0) | `N u8` kinds (low 4 bits shape id, bit 7 static) padded to 4 bytes, `N f32` sizes, `N × 3 f32` colors |
+//! | events | `M ×` (body a `i32`, body b `i32`, point `3 f32`, normal `3 f32`, relative speed `f32`, impulse `f32`); -1 is the ground |
+//!
+//! Shapes are sent with the first frame and again whenever the bodies
+//! change (a scene update or a different body count); viewers keep the last
+//! ones they received.
+
+use crate::physics::{ImpactEvent, RigidBodyStorage};
+use crate::simulator::Simulator;
+use std::io::{self, BufWriter, Write};
+use std::net::TcpStream;
+use thiserror::Error;
+
+/// First bytes of every frame
+pub const FRAME_MAGIC: [u8; 4] = *b"PBXF";
+/// Version of the frame layout
+pub const FRAME_VERSION: u16 = 1;
+/// Bytes before the first section
+pub const HEADER_SIZE: usize = 32;
+/// Flag: the frame carries the shapes section
+pub const FLAG_SHAPES: u16 = 1;
+/// Bytes per encoded event
+const EVENT_SIZE: usize = 40;
+/// Bit of a body kind marking a static body
+const KIND_STATIC: u8 = 0x80;
+
+/// Errors from decoding a frame
+#[derive(Error, Debug, PartialEq)]
+pub enum StreamError {
+    #[error("not a frame (bad magic)")]
+    BadMagic,
+    #[error("unsupported frame version {0}")]
+    UnsupportedVersion(u16),
+    #[error("frame truncated: {needed} bytes needed, {found} found")]
+    Truncated { needed: usize, found: usize },
+}
+
+/// Shape of every body, sent when the bodies change
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FrameShapes {
+    /// Shape id per body (see [`ShapeType::id`](crate::scene::builder::ShapeType::id))
+    pub shape_types: Vec<u8>,
+    pub statics: Vec<bool>,
+    /// Half extent of cubes, radius of spheres, capsules, cylinders and cones
+    pub sizes: Vec<f32>,
+    pub colors: Vec<[f32; 3]>,
+}
+
+/// A decoded frame
+#[derive(Debug, Clone, Default)]
+pub struct DecodedFrame {
+    pub frame: u64,
+    pub time: f32,
+    pub positions: Vec<[f32; 3]>,
+    pub rotations: Vec<[f32; 4]>,
+    /// Present on frames flagged [`FLAG_SHAPES`]
+    pub shapes: Option<FrameShapes>,
+    pub events: Vec<ImpactEvent>,
+}
+
+fn put_f32s(out: &mut Vec<u8>, values: &[f32]) {
+    for v in values {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+}
+
+/// Encode one frame of `storage` into `out` (replacing its contents)
+pub fn encode_frame(frame: u64, time: f32, storage: &RigidBodyStorage, events: &[ImpactEvent], shapes: bool, out: &mut Vec<u8>) {
+    let n = storage.len();
+    out.clear();
+    out.extend_from_slice(&FRAME_MAGIC);
+    out.extend_from_slice(&FRAME_VERSION.to_le_bytes());
+    out.extend_from_slice(&(if shapes { FLAG_SHAPES } else { 0 }).to_le_bytes());
+    out.extend_from_slice(&frame.to_le_bytes());
+    out.extend_from_slice(&time.to_le_bytes());
+    out.extend_from_slice(&(n as u32).to_le_bytes());
+    out.extend_from_slice(&(events.len() as u32).to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes());
+
+    put_f32s(out, storage.positions.as_flattened());
+    put_f32s(out, storage.rotations.as_flattened());
+
+    if shapes {
+        out.extend((0..n).map(|i| storage.shape_types[i] | if storage.statics[i] { KIND_STATIC } else { 0 }));
+        out.resize(out.len() + (4 - n % 4) % 4, 0);
+        put_f32s(out, &storage.radii);
+        put_f32s(out, storage.colors.as_flattened());
+    }
+
+    for e in events {
+        let index = |b: Option<usize>| b.map_or(-1, |b| b as i32);
+        out.extend_from_slice(&index(e.body_a).to_le_bytes());
+        out.extend_from_slice(&index(e.body_b).to_le_bytes());
+        put_f32s(out, &e.point);
+        put_f32s(out, &e.normal);
+        put_f32s(out, &[e.relative_speed, e.impulse]);
+    }
+}
+
+/// Little-endian reader over a frame
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], StreamError> {
+        let end = self.at + len;
+        let slice = self.bytes.get(self.at..end).ok_or(StreamError::Truncated { needed: end, found: self.bytes.len() })?;
+        self.at = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, StreamError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, StreamError> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32s<const K: usize>(&mut self, count: usize) -> Result<Vec<[f32; K]>, StreamError> {
+        let bytes = self.take(count * K * 4)?;
+        Ok(bytes.chunks_exact(K * 4)
+            .map(|c| std::array::from_fn(|k| f32::from_le_bytes(c[4 * k..4 * k + 4].try_into().unwrap())))
+            .collect())
+    }
+}
+
+/// Decode a frame (without its length prefix)
+pub fn decode_frame(bytes: &[u8]) -> Result<DecodedFrame, StreamError> {
+    let mut r = Reader { bytes, at: 0 };
+    if r.take(4)? != FRAME_MAGIC {
+        return Err(StreamError::BadMagic);
+    }
+    let header = r.take(4)?;
+    let version = u16::from_le_bytes([header[0], header[1]]);
+    let flags = u16::from_le_bytes([header[2], header[3]]);
+    if version != FRAME_VERSION {
+        return Err(StreamError::UnsupportedVersion(version));
+    }
+    let frame = u64::from_le_bytes(r.take(8)?.try_into().unwrap());
+    let time = r.f32()?;
+    let n = r.u32()? as usize;
+    let m = r.u32()? as usize;
+    r.u32()?;
+
+    let positions = r.f32s::<3>(n)?;
+    let rotations = r.f32s::<4>(n)?;
+    let shapes = if flags & FLAG_SHAPES != 0 {
+        let kinds = r.take(n)?.to_vec();
+        r.take((4 - n % 4) % 4)?;
+        Some(FrameShapes {
+            shape_types: kinds.iter().map(|k| k & !KIND_STATIC).collect(),
+            statics: kinds.iter().map(|k| k & KIND_STATIC != 0).collect(),
+            sizes: r.f32s::<1>(n)?.into_iter().map(|[s]| s).collect(),
+            colors: r.f32s::<3>(n)?,
+        })
+    } else {
+        None
+    };
+
+    let mut events = Vec::with_capacity(m);
+    for _ in 0..m {
+        let record = r.take(EVENT_SIZE)?;
+        let mut e = Reader { bytes: record, at: 0 };
+        let body = |v: u32| ((v as i32) >= 0).then_some(v as usize);
+        let (a, b) = (e.u32()?, e.u32()?);
+        let point = e.f32s::<3>(1)?[0];
+        let normal = e.f32s::<3>(1)?[0];
+        events.push(ImpactEvent {
+            time,
+            body_a: body(a),
+            body_b: body(b),
+            point,
+            normal,
+            relative_speed: e.f32()?,
+            impulse: e.f32()?,
+        });
+    }
+
+    Ok(DecodedFrame { frame, time, positions, rotations, shapes, events })
+}
+
+/// Destination of encoded frames
+pub trait FrameSink: Send {
+    /// Deliver one encoded frame
+    fn send_frame(&mut self, frame: &[u8]) -> io::Result<()>;
+}
+
+/// Sink writing length-prefixed frames to a byte stream (socket, pipe, file)
+pub struct StreamSink<W: Write + Send> {
+    writer: BufWriter<W>,
+}
+
+impl<W: Write + Send> StreamSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer: BufWriter::new(writer) }
+    }
+}
+
+impl StreamSink<TcpStream> {
+    /// Connect to a viewer listening on a TCP address ("host:port")
+    pub fn connect_tcp(address: &str) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream))
+    }
+}
+
+#[cfg(unix)]
+impl StreamSink<std::os::unix::net::UnixStream> {
+    /// Connect to a viewer listening on a Unix socket
+    pub fn connect_unix(path: &str) -> io::Result<Self> {
+        Ok(Self::new(std::os::unix::net::UnixStream::connect(path)?))
+    }
+}
+
+impl<W: Write + Send> FrameSink for StreamSink<W> {
+    fn send_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.writer.write_all(&(frame.len() as u32).to_le_bytes())?;
+        self.writer.write_all(frame)?;
+        self.writer.flush()
+    }
+}
+
+/// Connect to a viewer at "tcp://host:port", "unix:///path" or "host:port"
+pub fn connect(address: &str) -> io::Result<Box<dyn FrameSink>> {
+    if let Some(path) = address.strip_prefix("unix://") {
+        #[cfg(unix)]
+        return Ok(Box::new(StreamSink::connect_unix(path)?));
+        #[cfg(not(unix))]
+        return Err(io::Error::new(io::ErrorKind::Unsupported, format!("Unix sockets are not available ({path})")));
+    }
+    let address = address.strip_prefix("tcp://").unwrap_or(address);
+    Ok(Box::new(StreamSink::connect_tcp(address)?))
+}
+
+/// Publishes a simulator's steps to a sink as numbered frames
+pub struct FrameStreamer {
+    sink: Box<dyn FrameSink>,
+    frame: u64,
+    /// Simulator id and body count the last shapes were sent for
+    shapes_sent: Option<(u64, usize)>,
+    /// Impacts since the last frame
+    events: Vec<ImpactEvent>,
+    /// Encoded frame, reused
+    buffer: Vec<u8>,
+}
+
+impl FrameStreamer {
+    pub fn new(sink: Box<dyn FrameSink>) -> Self {
+        Self { sink, frame: 0, shapes_sent: None, events: Vec::new(), buffer: Vec::new() }
+    }
+
+    /// Stream to a viewer at an address accepted by [`connect`]
+    pub fn connect(address: &str) -> io::Result<Self> {
+        Ok(Self::new(connect(address)?))
+    }
+
+    /// Number of frames published so far
+    pub fn frames_sent(&self) -> u64 {
+        self.frame
+    }
+
+    /// Queue impacts for the next frame (e.g. those of every substep)
+    pub fn record_events(&mut self, events: &[ImpactEvent]) {
+        self.events.extend_from_slice(events);
+    }
+
+    /// Send the simulator's current state with the queued impacts
+    pub fn publish(&mut self, sim: &Simulator) -> io::Result<()> {
+        let source = (sim.id(), sim.storage.len());
+        let shapes = self.shapes_sent != Some(source);
+        encode_frame(self.frame, sim.time, &sim.storage, &self.events, shapes, &mut self.buffer);
+        self.events.clear();
+        self.sink.send_frame(&self.buffer)?;
+        self.frame += 1;
+        self.shapes_sent = Some(source);
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+//! Parameter sweeps: run a base scene over a grid of physics parameters
+//!
+//! Each grid point is simulated independently (in parallel) and summarized by
+//! a few outcome metrics, so ablation studies need no orchestration scripts.
+
+use rayon::prelude::*;
+use crate::scene::SceneBuilder;
+use crate::simulator::Simulator;
+
+/// Speed below which a body counts as settled at the end of a run (m/s)
+pub const SETTLED_SPEED: f32 = 0.05;
+
+/// Values to try per parameter; an empty axis keeps the base scene's value
+#[derive(Debug, Clone, Default)]
+pub struct SweepGrid {
+    /// Gravity magnitudes (m/s^2) along the base scene's gravity direction
+    pub gravity: Vec<f32>,
+    /// Friction coefficients applied to every body and the ground
+    pub friction: Vec<f32>,
+    /// Restitution coefficients applied to every body and the ground
+    pub restitution: Vec<f32>,
+    /// Step sizes (s); empty = 1/60
+    pub dt: Vec<f32>,
+}
+
+/// One combination of parameters from a [`SweepGrid`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepPoint {
+    /// Gravity magnitude (m/s^2)
+    pub gravity: f32,
+    /// Friction override (None = base scene values)
+    pub friction: Option<f32>,
+    /// Restitution override (None = base scene values)
+    pub restitution: Option<f32>,
+    /// Step size (s)
+    pub dt: f32,
+}
+
+/// Outcome of one simulated grid point
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SweepMetrics {
+    /// Physics steps taken
+    pub steps: u32,
+    /// Mean distance bodies moved from their start positions (m)
+    pub mean_displacement: f32,
+    /// Largest distance a body moved from its start position (m)
+    pub max_displacement: f32,
+    /// Lowest body center at the end (catches tunneling through the ground)
+    pub min_height: f32,
+    /// Translational kinetic energy at the end (J)
+    pub final_kinetic_energy: f32,
+    /// Fraction of bodies slower than [`SETTLED_SPEED`] at the end
+    pub settled_fraction: f32,
+    /// Number of impacts recorded
+    pub impact_count: u32,
+    /// Largest impact impulse (N·s)
+    pub peak_impulse: f32,
+}
+
+/// A grid point and its outcome
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepResult {
+    pub point: SweepPoint,
+    pub metrics: SweepMetrics,
+}
+
+impl SweepGrid {
+    /// Every combination of the axis values (gravity varies slowest, dt fastest)
+    pub fn points(&self, base: &SceneBuilder) -> Vec<SweepPoint> {
+        let [gx, gy, gz] = base.physics.gravity;
+        let base_gravity = (gx * gx + gy * gy + gz * gz).sqrt();
+
+        let gravity = axis_or(&self.gravity, base_gravity);
+        let friction: Vec<Option<f32>> = optional_axis(&self.friction);
+        let restitution: Vec<Option<f32>> = optional_axis(&self.restitution);
+        let dt = axis_or(&self.dt, 1.0 / 60.0);
+
+        let mut points = Vec::with_capacity(gravity.len() * friction.len() * restitution.len() * dt.len());
+        for &g in &gravity {
+            for &f in &friction {
+                for &r in &restitution {
+                    for &d in &dt {
+                        points.push(SweepPoint { gravity: g, friction: f, restitution: r, dt: d });
+                    }
+                }
+            }
+        }
+        points
+    }
+}
+
+impl SweepPoint {
+    /// The base scene with this point's parameters applied
+    pub fn apply(&self, base: &SceneBuilder) -> SceneBuilder {
+        let mut scene = base.clone();
+
+        let [gx, gy, gz] = scene.physics.gravity;
+        let norm = (gx * gx + gy * gy + gz * gz).sqrt();
+        let dir = if norm > 0.0 { [gx / norm, gy / norm, gz / norm] } else { [0.0, -1.0, 0.0] };
+        scene.physics.gravity = dir.map(|c| c * self.gravity);
+
+        if let Some(friction) = self.friction {
+            scene.physics.ground_friction = friction;
+            for body in &mut scene.bodies {
+                body.friction = friction;
+                if let Some(material) = &mut body.material {
+                    material.friction = friction;
+                }
+            }
+        }
+        if let Some(restitution) = self.restitution {
+            scene.physics.ground_restitution = restitution;
+            for body in &mut scene.bodies {
+                body.restitution = restitution;
+                if let Some(material) = &mut body.material {
+                    material.restitution = restitution;
+                }
+            }
+        }
+        scene
+    }
+}
+
+/// Simulate every grid point for `duration` seconds, in parallel
+///
+/// Results are in [`SweepGrid::points`] order.
+pub fn run_sweep(base: &SceneBuilder, grid: &SweepGrid, duration: f32) -> Vec<SweepResult> {
+    grid.points(base)
+        .into_par_iter()
+        .map(|point| SweepResult { point, metrics: simulate(&point.apply(base), point.dt, duration) })
+        .collect()
+}
+
+/// Run one scene and summarize the outcome
+fn simulate(scene: &SceneBuilder, dt: f32, duration: f32) -> SweepMetrics {
+    let mut sim = Simulator::new(scene);
+    sim.set_impact_recording(true);
+    let start = sim.positions().to_vec();
+
+    let steps = if dt > 0.0 { (duration / dt).ceil().max(0.0) as u32 } else { 0 };
+    for _ in 0..steps {
+        sim.step(dt);
+    }
+
+    let impacts = sim.take_impacts();
+    let storage = &sim.storage;
+    let count = storage.len().max(1) as f32;
+    let displacements: Vec<f32> = storage.positions.iter().zip(&start)
+        .map(|(p, p0)| ((p[0] - p0[0]).powi(2) + (p[1] - p0[1]).powi(2) + (p[2] - p0[2]).powi(2)).sqrt())
+        .collect();
+    let speeds_sq: Vec<f32> = storage.linear_velocities.iter()
+        .map(|v| v[0] * v[0] + v[1] * v[1] + v[2] * v[2])
+        .collect();
+
+    SweepMetrics {
+        steps,
+        mean_displacement: displacements.iter().sum::<f32>() / count,
+        max_displacement: displacements.iter().copied().fold(0.0, f32::max),
+        min_height: storage.positions.iter().map(|p| p[1]).fold(f32::INFINITY, f32::min),
+        final_kinetic_energy: crate::simd::kinetic_energy(&storage.linear_velocities, &storage.masses),
+        settled_fraction: speeds_sq.iter().filter(|&&v2| v2 < SETTLED_SPEED * SETTLED_SPEED).count() as f32 / count,
+        impact_count: impacts.len() as u32,
+        peak_impulse: impacts.iter().map(|e| e.impulse).fold(0.0, f32::max),
+    }
+}
+
+/// Axis values, or the single fallback when the axis is empty
+fn axis_or(values: &[f32], fallback: f32) -> Vec<f32> {
+    if values.is_empty() { vec![fallback] } else { values.to_vec() }
+}
+
+/// Axis values as overrides, or a single "no override" when empty
+fn optional_axis(values: &[f32]) -> Vec<Option<f32>> {
+    if values.is_empty() { vec![None] } else { values.iter().map(|&v| Some(v)).collect() }
+}
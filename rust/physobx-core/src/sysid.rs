@@ -0,0 +1,253 @@
+//! System identification: fit material parameters to an observed trajectory
+//!
+//! Users matching real drop-test footage track a few bodies over time; this
+//! module searches friction / restitution with Nelder-Mead so the simulated
+//! bodies follow the observed positions. Simplex vertices are simulated in
+//! parallel.
+
+use rayon::prelude::*;
+use crate::scene::SceneBuilder;
+use crate::simulator::Simulator;
+
+/// A material parameter applied to every body and the ground
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitParameter {
+    /// Friction coefficient, searched in [0, 2]
+    Friction,
+    /// Restitution, searched in [0, 1]
+    Restitution,
+}
+
+impl FitParameter {
+    /// Name used in exported results
+    pub fn name(self) -> &'static str {
+        match self {
+            FitParameter::Friction => "friction",
+            FitParameter::Restitution => "restitution",
+        }
+    }
+
+    /// Valid range of the parameter
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            FitParameter::Friction => (0.0, 2.0),
+            FitParameter::Restitution => (0.0, 1.0),
+        }
+    }
+
+    /// Value in the base scene (ground material)
+    pub fn base_value(self, scene: &SceneBuilder) -> f32 {
+        match self {
+            FitParameter::Friction => scene.physics.ground_friction,
+            FitParameter::Restitution => scene.physics.ground_restitution,
+        }
+    }
+
+    /// Set the parameter on every body and the ground (clamped to its range)
+    ///
+    /// Bodies with a material get it set on the material, which takes
+    /// precedence over their own values; the material's density is kept.
+    pub fn apply(self, scene: &mut SceneBuilder, value: f32) {
+        let (lo, hi) = self.range();
+        let value = value.clamp(lo, hi);
+        match self {
+            FitParameter::Friction => {
+                scene.physics.ground_friction = value;
+                for body in &mut scene.bodies {
+                    body.friction = value;
+                    if let Some(material) = &mut body.material {
+                        material.friction = value;
+                    }
+                }
+            }
+            FitParameter::Restitution => {
+                scene.physics.ground_restitution = value;
+                for body in &mut scene.bodies {
+                    body.restitution = value;
+                    if let Some(material) = &mut body.material {
+                        material.restitution = value;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Observed positions of tracked bodies over time
+#[derive(Debug, Clone, Default)]
+pub struct Observation {
+    /// Tracked body indices
+    pub bodies: Vec<usize>,
+    /// Observation times (simulation time, ascending)
+    pub times: Vec<f32>,
+    /// Positions per time, then per tracked body
+    pub positions: Vec<Vec<[f32; 3]>>,
+}
+
+/// Search settings for [`fit_parameters`]
+#[derive(Debug, Clone, Copy)]
+pub struct FitOptions {
+    /// Simulation step size (s)
+    pub dt: f32,
+    /// Nelder-Mead iterations
+    pub max_iterations: u32,
+    /// Stop when the simplex's error spread falls below this (m)
+    pub tolerance: f32,
+}
+
+impl Default for FitOptions {
+    fn default() -> Self {
+        Self { dt: 1.0 / 60.0, max_iterations: 100, tolerance: 1e-4 }
+    }
+}
+
+/// Best-fit parameter values
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitResult {
+    /// Fitted values, in the order the parameters were given
+    pub values: Vec<f32>,
+    /// RMS position error of the fit (m)
+    pub rms_error: f32,
+    /// Simulations run
+    pub evaluations: u32,
+}
+
+/// RMS distance between simulated and observed positions of the tracked bodies
+///
+/// The scene is stepped with `dt` and each observation is compared against
+/// the first step at or after its time.
+pub fn trajectory_error(scene: &SceneBuilder, observation: &Observation, dt: f32) -> f32 {
+    let mut sim = Simulator::new(scene);
+    let mut sum = 0.0f32;
+    let mut count = 0usize;
+
+    for (&time, observed) in observation.times.iter().zip(&observation.positions) {
+        while sim.time < time - dt * 0.5 {
+            sim.step(dt);
+        }
+        for (&body, p) in observation.bodies.iter().zip(observed) {
+            let Some(q) = sim.storage.positions.get(body) else {
+                continue;
+            };
+            sum += (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2) + (p[2] - q[2]).powi(2);
+            count += 1;
+        }
+    }
+
+    if count == 0 { 0.0 } else { (sum / count as f32).sqrt() }
+}
+
+/// Fit material parameters so the scene reproduces an observed trajectory
+///
+/// Starts from `initial` (or the scene's ground material) and runs
+/// Nelder-Mead on the RMS position error.
+pub fn fit_parameters(
+    base: &SceneBuilder,
+    params: &[FitParameter],
+    initial: Option<&[f32]>,
+    observation: &Observation,
+    options: &FitOptions,
+) -> FitResult {
+    let error = |x: &[f32]| {
+        let mut scene = base.clone();
+        for (param, &value) in params.iter().zip(x) {
+            param.apply(&mut scene, value);
+        }
+        trajectory_error(&scene, observation, options.dt)
+    };
+
+    let x0: Vec<f32> = match initial {
+        Some(values) => values.to_vec(),
+        None => params.iter().map(|p| p.base_value(base)).collect(),
+    };
+    // Initial simplex spans a tenth of each parameter's range
+    let steps: Vec<f32> = params.iter().map(|p| (p.range().1 - p.range().0) * 0.1).collect();
+
+    let (x, rms_error, evaluations) = nelder_mead(error, &x0, &steps, options.max_iterations, options.tolerance);
+    let values = params.iter().zip(&x)
+        .map(|(p, &v)| v.clamp(p.range().0, p.range().1))
+        .collect();
+    FitResult { values, rms_error, evaluations }
+}
+
+/// Minimize `f` with the Nelder-Mead simplex method
+///
+/// Returns the best point, its value and the number of evaluations.
+/// Independent evaluations (initial simplex, shrink) run in parallel.
+pub fn nelder_mead(
+    f: impl Fn(&[f32]) -> f32 + Sync,
+    x0: &[f32],
+    steps: &[f32],
+    max_iterations: u32,
+    tolerance: f32,
+) -> (Vec<f32>, f32, u32) {
+    let n = x0.len();
+    if n == 0 {
+        return (Vec::new(), f(&[]), 1);
+    }
+
+    let mut simplex: Vec<Vec<f32>> = std::iter::once(x0.to_vec())
+        .chain((0..n).map(|i| {
+            let mut x = x0.to_vec();
+            x[i] += steps.get(i).copied().unwrap_or(0.1);
+            x
+        }))
+        .collect();
+    let mut values: Vec<f32> = simplex.par_iter().map(|x| f(x)).collect();
+    let mut evaluations = values.len() as u32;
+
+    let lerp = |a: &[f32], b: &[f32], t: f32| -> Vec<f32> {
+        a.iter().zip(b).map(|(a, b)| a + (b - a) * t).collect()
+    };
+
+    for _ in 0..max_iterations {
+        // Order vertices best to worst
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+        simplex = order.iter().map(|&i| simplex[i].clone()).collect();
+        values = order.iter().map(|&i| values[i]).collect();
+
+        if values[n] - values[0] < tolerance {
+            break;
+        }
+
+        let centroid: Vec<f32> = (0..n)
+            .map(|k| simplex[..n].iter().map(|x| x[k]).sum::<f32>() / n as f32)
+            .collect();
+
+        let reflected = lerp(&centroid, &simplex[n], -1.0);
+        let fr = f(&reflected);
+        evaluations += 1;
+
+        if fr < values[0] {
+            let expanded = lerp(&centroid, &simplex[n], -2.0);
+            let fe = f(&expanded);
+            evaluations += 1;
+            (simplex[n], values[n]) = if fe < fr { (expanded, fe) } else { (reflected, fr) };
+        } else if fr < values[n - 1] {
+            (simplex[n], values[n]) = (reflected, fr);
+        } else {
+            // Contract toward the better of the worst and reflected points
+            let (toward, f_toward) = if fr < values[n] { (reflected, fr) } else { (simplex[n].clone(), values[n]) };
+            let contracted = lerp(&centroid, &toward, 0.5);
+            let fc = f(&contracted);
+            evaluations += 1;
+
+            if fc < f_toward {
+                (simplex[n], values[n]) = (contracted, fc);
+            } else {
+                // Shrink everything toward the best vertex
+                let best = simplex[0].clone();
+                for x in &mut simplex[1..] {
+                    *x = lerp(&best, x, 0.5);
+                }
+                let shrunk: Vec<f32> = simplex[1..].par_iter().map(|x| f(x)).collect();
+                values[1..].copy_from_slice(&shrunk);
+                evaluations += n as u32;
+            }
+        }
+    }
+
+    let best = (0..=n).min_by(|&a, &b| values[a].total_cmp(&values[b])).unwrap_or(0);
+    (simplex[best].clone(), values[best], evaluations)
+}
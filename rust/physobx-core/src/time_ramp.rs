@@ -0,0 +1,71 @@
+//! Speed ramps: time scale as a function of simulation time
+//!
+//! Used by [`Simulator::advance`](crate::Simulator::advance) to record
+//! slow-motion sections (e.g. around an impact) while physics keeps stepping
+//! at a fixed dt.
+
+/// Smallest scale a ramp will report, so a ramp can never freeze time forever
+pub const MIN_RAMP_SCALE: f32 = 1e-3;
+
+/// Piecewise time-scale curve keyed on simulation time
+///
+/// Between keys the scale eases with a smoothstep, so playback speed changes
+/// without visible jerks. Before the first key and after the last the end
+/// values hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeRamp {
+    /// (simulation time, time scale) pairs sorted by time
+    keys: Vec<(f32, f32)>,
+}
+
+impl TimeRamp {
+    /// Build a ramp from (simulation time, time scale) keys in any order
+    pub fn new(keys: &[(f32, f32)]) -> Self {
+        let mut keys: Vec<(f32, f32)> = keys.iter()
+            .map(|&(t, scale)| (t, scale.max(MIN_RAMP_SCALE)))
+            .collect();
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { keys }
+    }
+
+    /// Slow to `scale` for `hold` seconds centered on `time`, easing in and
+    /// out over `ramp` seconds on either side; normal speed elsewhere
+    pub fn slow_around(time: f32, scale: f32, hold: f32, ramp: f32) -> Self {
+        let half = hold.max(0.0) * 0.5;
+        let ramp = ramp.max(0.0);
+        Self::new(&[
+            (time - half - ramp, 1.0),
+            (time - half, scale),
+            (time + half, scale),
+            (time + half + ramp, 1.0),
+        ])
+    }
+
+    /// Keys sorted by simulation time
+    pub fn keys(&self) -> &[(f32, f32)] {
+        &self.keys
+    }
+
+    /// Time scale at a simulation time (1.0 for an empty ramp)
+    pub fn scale_at(&self, time: f32) -> f32 {
+        let (Some(first), Some(last)) = (self.keys.first(), self.keys.last()) else {
+            return 1.0;
+        };
+        if time <= first.0 {
+            return first.1;
+        }
+        if time >= last.0 {
+            return last.1;
+        }
+
+        let next = self.keys.partition_point(|k| k.0 <= time);
+        let (t0, s0) = self.keys[next - 1];
+        let (t1, s1) = self.keys[next];
+        let span = t1 - t0;
+        if span <= 0.0 {
+            return s1;
+        }
+        let x = (time - t0) / span;
+        s0 + (s1 - s0) * x * x * (3.0 - 2.0 * x)
+    }
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the integration tests
+
+use physobx_core::Simulator;
+
+/// Built mass, principal inertia, friction and restitution of body `index`
+pub fn built_body(sim: &Simulator, index: usize) -> (f32, [f32; 3], f32, f32) {
+    let rapier = sim.rapier().expect("default backend is Rapier");
+    let collider = rapier.collider_set.iter()
+        .map(|(_, collider)| collider)
+        .find(|collider| collider.user_data == index as u128)
+        .expect("body has a collider");
+    let body = &rapier.rigid_body_set[collider.parent().expect("collider is attached")];
+    let inertia = body.mass_properties().local_mprops.principal_inertia();
+    (body.mass(), [inertia.x, inertia.y, inertia.z], collider.friction(), collider.restitution())
+}
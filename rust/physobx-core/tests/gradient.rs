@@ -0,0 +1,38 @@
+//! Mass parameters of bodies whose density decides their mass
+
+use physobx_core::gradient::SceneParameter;
+use physobx_core::scene::{MassMode, Material, SceneBuilder};
+use physobx_core::Simulator;
+
+mod common;
+use common::built_body;
+
+/// Inertia of a cube of `mass` and half extent `h` about each axis
+fn cube_inertia(mass: f32, h: f32) -> f32 {
+    mass * (2.0 * h) * (2.0 * h) / 6.0
+}
+
+#[test]
+fn mass_parameter_goes_through_the_density() {
+    // 1000 kg/m³ over a 0.2 m cube is 8 kg, by material and by mass mode
+    let mut scene = SceneBuilder::new();
+    scene.add_cube([0.0, 1.0, 0.0], 0.1, 1.0).with_body_material(Material::new(1000.0, 0.8, 0.5));
+    scene.add_cube([1.0, 1.0, 0.0], 0.1, 1.0).set_mass_mode(1, MassMode::Density(1000.0));
+
+    for body in 0..2 {
+        let mass = SceneParameter::Mass { body };
+        assert!((mass.get(&scene).unwrap() - 8.0).abs() < 1e-3);
+        assert!(mass.set(&mut scene, 12.0));
+        assert!((mass.get(&scene).unwrap() - 12.0).abs() < 1e-3);
+    }
+    assert_eq!(scene.bodies[0].resolved_surface(), (0.8, 0.5));
+
+    let sim = Simulator::new(&scene);
+    for body in 0..2 {
+        let (mass, inertia, _, _) = built_body(&sim, body);
+        assert!((mass - 12.0).abs() < 1e-3, "body {body} mass {mass}");
+        for axis in inertia {
+            assert!((axis - cube_inertia(12.0, 0.1)).abs() < 1e-4, "body {body} inertia {inertia:?}");
+        }
+    }
+}
@@ -0,0 +1,30 @@
+//! Planar scenes keep bodies in their plane when locks change at runtime
+
+use physobx_core::physics::PhysicsBackendKind;
+use physobx_core::scene::SceneBuilder;
+use physobx_core::Simulator;
+
+#[test]
+fn unlocking_keeps_planar_locks() {
+    for kind in [PhysicsBackendKind::Rapier, PhysicsBackendKind::Pbd] {
+        let mut scene = SceneBuilder::new();
+        scene.set_planar(true);
+        scene.add_sphere([0.0, 2.0, 0.0], 0.2, 1.0);
+        let mut sim = Simulator::with_backend(&scene, kind.create());
+
+        assert!(sim.lock_translations(0, [false; 3]));
+        // PBD spheres don't spin, so only Rapier has rotation locks
+        assert_eq!(sim.lock_rotations(0, [false; 3]), kind == PhysicsBackendKind::Rapier);
+        assert!(sim.physics_mut().apply_impulse(0, [1.0, 0.0, 2.0], [0.5, 0.5, 0.5]));
+        for _ in 0..60 {
+            sim.step(1.0 / 60.0);
+        }
+
+        let [x, _, z] = sim.positions()[0];
+        assert!(x > 0.5, "{kind:?}: body should move along x, got {x}");
+        assert!(z.abs() < 1e-5, "{kind:?}: body left its plane, z = {z}");
+        // Rotation only about z
+        let [qx, qy, _, _] = sim.rotations()[0];
+        assert!(qx.abs() < 1e-5 && qy.abs() < 1e-5, "{kind:?}: rotation {:?}", sim.rotations()[0]);
+    }
+}
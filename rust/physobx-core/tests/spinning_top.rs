@@ -0,0 +1,48 @@
+//! A spinning top precesses at the gyroscopic rate
+
+use nalgebra::{Quaternion, UnitQuaternion, Vector3};
+use physobx_core::scene::builder::SPINNING_TOP_MAX_DT;
+use physobx_core::scene::SceneBuilder;
+use physobx_core::Simulator;
+
+/// Heading of the top's spin axis (its local Y) in the ground plane
+fn axis_heading(rotation: [f32; 4]) -> f32 {
+    let [x, y, z, w] = rotation;
+    let axis = UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z)) * Vector3::y();
+    axis.z.atan2(axis.x)
+}
+
+#[test]
+fn top_precesses_at_gyroscopic_rate() {
+    let (radius, mass, spin, tilt) = (0.1, 1.0, 150.0, 0.3);
+    let mut scene = SceneBuilder::new();
+    scene.add_ground(0.0, 10.0);
+    // The ground slab's top is at 0.1; the tip sphere is 0.15 radius
+    scene.add_spinning_top([0.0, 0.1 + 0.15 * radius, 0.0], radius, mass, spin, tilt);
+    // Friction on the sliding tip adds a torque the estimate leaves out
+    let top = scene.bodies.len() - 1;
+    scene.set_ground_material(0.0, 0.1).set_material(top, 0.0, 0.1);
+    let mut sim = Simulator::new(&scene);
+
+    // Fast top: Ω ≈ m g l / (I ω) with l = radius and I = m r² / 2
+    let expected = mass * 9.81 * radius / (0.5 * mass * radius * radius * spin);
+    let duration = 1.0;
+    let steps = (duration / SPINNING_TOP_MAX_DT).round() as u32;
+    let mut heading = axis_heading(sim.rotations()[0]);
+    let mut turned = 0.0;
+    for _ in 0..steps {
+        sim.step(SPINNING_TOP_MAX_DT);
+        let next = axis_heading(sim.rotations()[0]);
+        let mut delta = next - heading;
+        if delta > std::f32::consts::PI {
+            delta -= std::f32::consts::TAU;
+        } else if delta < -std::f32::consts::PI {
+            delta += std::f32::consts::TAU;
+        }
+        turned += delta;
+        heading = next;
+    }
+
+    let rate = turned.abs() / (steps as f32 * SPINNING_TOP_MAX_DT);
+    assert!((rate - expected).abs() < 0.05 * expected, "precession {rate} rad/s, expected {expected}");
+}
@@ -0,0 +1,27 @@
+//! Fitted surface parameters reach bodies whose material decides them
+
+use physobx_core::scene::{Material, SceneBuilder};
+use physobx_core::sysid::FitParameter;
+use physobx_core::Simulator;
+
+mod common;
+use common::built_body;
+
+#[test]
+fn fit_parameters_override_the_body_material() {
+    let mut scene = SceneBuilder::new();
+    scene.add_cube([0.0, 1.0, 0.0], 0.1, 1.0).with_body_material(Material::new(1000.0, 0.8, 0.5));
+
+    FitParameter::Friction.apply(&mut scene, 0.3);
+    FitParameter::Restitution.apply(&mut scene, 0.1);
+    assert_eq!(scene.bodies[0].resolved_surface(), (0.3, 0.1));
+
+    // The material's density still decides mass and inertia: 1000 kg/m³
+    // over a 0.2 m cube is 8 kg, with inertia m (2h)² / 6 about each axis
+    let (mass, inertia, friction, restitution) = built_body(&Simulator::new(&scene), 0);
+    assert!((mass - 8.0).abs() < 1e-3, "mass {mass}");
+    for axis in inertia {
+        assert!((axis - 8.0 * 0.04 / 6.0).abs() < 1e-4, "inertia {inertia:?}");
+    }
+    assert_eq!((friction, restitution), (0.3, 0.1));
+}
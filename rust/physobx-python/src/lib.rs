@@ -1,10 +1,170 @@
 //! Python bindings for Physobx physics sandbox
 
 use pyo3::prelude::*;
-use pyo3::exceptions::PyRuntimeError;
-use numpy::{PyArray1, PyArray2, PyArray3, PyArrayMethods, ToPyArray};
-use physobx_core::{SceneBuilder, Simulator as CoreSimulator};
-use physobx_core::gpu::Renderer;
+use pyo3::types::{PyBytes, PyDict, PySlice, PyTuple};
+use pyo3::exceptions::{PyIOError, PyIndexError, PyRuntimeError, PyValueError};
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyArray3, PyArray4, PyArrayMethods, PyUntypedArrayMethods, ToPyArray};
+use physobx_core::{SceneBuilder, Simulator as CoreSimulator, TimeRamp};
+use physobx_core::stream::FrameStreamer;
+use physobx_core::shared_state::{SharedStatePublisher, SharedStateReader};
+use physobx_core::gpu::{Renderer, PendingFrame, RollingShutter, LensModel, PostEffects, ColorLut, LutError, OutputColorSpace, Primaries, TransferFunction, FrameMetadata, SpringDisplay, ForceChainDisplay, FluidDisplay, LabelDisplay, LabelText, MeasurementDisplay, MeasurePoint, ReferenceGrid, AxisGizmo, SplitScreen, PictureInPicture, Corner, Camera, ComputeEffect, Layer, LayerSource, BlendMode, BopExporter, ShapeSymmetry};
+use physobx_core::scene::ballistics;
+use physobx_core::scene::{density_of, material_preset, Material, MaterialPreset, MotionPath, PathLoop, SceneIssue, VehicleConfig, DENSITIES, MATERIAL_PRESETS};
+use physobx_core::scene::builder::{quat_from_euler, ChildCollider, MassMode, RigidBodyConfig, ShapeType};
+use physobx_core::physics::{AeroCoefficients, BroadPhaseKind, CombineRule, FluidSettings, HeatSettings, ImpactEvent, PhysicsBackendKind, RayHit, SimulationLod, TimeOfImpact, WindField};
+use physobx_core::analysis::{self, MomentSettings, Trajectory, TrajectoryColumns};
+use physobx_core::sweep::{run_sweep, SweepGrid, SweepResult};
+use physobx_core::gradient::{self, SceneParameter};
+use physobx_core::sysid::{fit_parameters, FitOptions, FitParameter, Observation};
+use physobx_core::sensors::{ImuSensor, ImuNoise, ForceTorqueSensor, NoiseChain, NoiseModel, ImageNoise, Rng};
+
+/// Parse a combine rule name, raising ValueError for unknown names
+fn parse_combine_rule(name: &str) -> PyResult<CombineRule> {
+    CombineRule::from_name(name).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "unknown combine rule '{}' (expected average, min, multiply or max)",
+            name
+        ))
+    })
+}
+
+/// Parse a set of world axes such as "xz" ("" unlocks all)
+fn parse_axes(axes: &str) -> PyResult<[bool; 3]> {
+    let mut locked = [false; 3];
+    for c in axes.chars() {
+        match c.to_ascii_lowercase() {
+            'x' => locked[0] = true,
+            'y' => locked[1] = true,
+            'z' => locked[2] = true,
+            _ => return Err(PyValueError::new_err(format!("unknown axis '{}' in '{}' (expected x, y or z)", c, axes))),
+        }
+    }
+    Ok(locked)
+}
+
+/// Parse a measurement end: a body index or an (x, y, z) world point
+fn parse_measure_point(point: &Bound<'_, PyAny>, body_count: usize) -> PyResult<MeasurePoint> {
+    if let Ok(index) = point.extract::<usize>() {
+        if index >= body_count {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        return Ok(MeasurePoint::Body(index));
+    }
+    Ok(MeasurePoint::World(point.extract()?))
+}
+
+/// Initial orientation from a quaternion or Euler angles (identity if neither)
+fn parse_orientation(rotation: Option<[f32; 4]>, euler: Option<[f32; 3]>) -> PyResult<[f32; 4]> {
+    match (rotation, euler) {
+        (Some(_), Some(_)) => Err(PyValueError::new_err("pass either rotation or euler, not both")),
+        (Some(rotation), None) => Ok(rotation),
+        (None, Some(euler)) => Ok(quat_from_euler(euler)),
+        (None, None) => Ok([0.0, 0.0, 0.0, 1.0]),
+    }
+}
+
+/// Parse a compositor layer dict (see `Simulator.render_layers`)
+fn parse_layer(layer: &Bound<'_, PyDict>, sim: &CoreSimulator) -> PyResult<Layer> {
+    let item = |key: &str| layer.get_item(key);
+    let source: String = item("source")?
+        .ok_or_else(|| PyValueError::new_err("layer needs a 'source'"))?
+        .extract()?;
+    let source = match source.as_str() {
+        "color" => LayerSource::Color,
+        "annotations" => LayerSource::Annotations,
+        "segmentation" => LayerSource::Segmentation,
+        "islands" => LayerSource::Islands,
+        "temperature" => {
+            let temperatures = sim.temperatures();
+            let min = match item("min")? {
+                Some(min) => min.extract()?,
+                None => temperatures.iter().copied().fold(f32::INFINITY, f32::min),
+            };
+            let max = match item("max")? {
+                Some(max) => max.extract()?,
+                None => temperatures.iter().copied().fold(f32::NEG_INFINITY, f32::max),
+            };
+            LayerSource::Temperature { min, max }
+        }
+        "heatmap" => {
+            let values: Vec<f32> = item("values")?
+                .ok_or_else(|| PyValueError::new_err("heatmap layer needs 'values'"))?
+                .extract()?;
+            let max = match item("max")? {
+                Some(max) => max.extract()?,
+                None => values.iter().copied().fold(0.0, f32::max),
+            };
+            LayerSource::Heatmap { values, max }
+        }
+        other => return Err(PyValueError::new_err(format!(
+            "unknown layer source '{other}', expected color, annotations, segmentation, islands, temperature or heatmap"
+        ))),
+    };
+    let opacity = match item("opacity")? {
+        Some(opacity) => opacity.extract()?,
+        None => 1.0,
+    };
+    let blend = match item("blend")? {
+        Some(blend) => {
+            let name: String = blend.extract()?;
+            BlendMode::from_name(&name).ok_or_else(|| PyValueError::new_err(format!(
+                "unknown blend mode '{name}', expected normal, multiply, screen or add"
+            )))?
+        }
+        None => BlendMode::Normal,
+    };
+    Ok(Layer { source, opacity, blend })
+}
+
+/// Reject negative friction or restitution
+fn check_material(friction: Option<f32>, restitution: Option<f32>) -> PyResult<()> {
+    if friction.is_some_and(|f| f < 0.0) || restitution.is_some_and(|r| r < 0.0) {
+        return Err(PyValueError::new_err("friction and restitution must be non-negative"));
+    }
+    Ok(())
+}
+
+/// Density in kg/m³ given as a number or a material name from `densities()`
+fn parse_density(density: &Bound<'_, PyAny>) -> PyResult<f32> {
+    let value = match density.extract::<f32>() {
+        Ok(value) => value,
+        Err(_) => {
+            let name: String = density.extract()?;
+            density_of(&name).ok_or_else(|| {
+                let names: Vec<&str> = DENSITIES.iter().map(|(name, _)| *name).collect();
+                PyValueError::new_err(format!("unknown material '{}' (expected one of {})", name, names.join(", ")))
+            })?
+        }
+    };
+    if !(value > 0.0 && value.is_finite()) {
+        return Err(PyValueError::new_err("density must be positive"));
+    }
+    Ok(value)
+}
+
+/// Material preset by name from `material_presets()`
+fn parse_preset(name: &str) -> PyResult<&'static MaterialPreset> {
+    material_preset(name).ok_or_else(|| {
+        let names: Vec<&str> = MATERIAL_PRESETS.iter().map(|preset| preset.name).collect();
+        PyValueError::new_err(format!("unknown material preset '{}' (expected one of {})", name, names.join(", ")))
+    })
+}
+
+/// Shape by name ("cube", "sphere", "capsule", "cylinder" or "cone")
+fn parse_shape(name: &str) -> PyResult<ShapeType> {
+    ShapeType::from_name(name).ok_or_else(|| PyValueError::new_err(format!(
+        "unknown shape '{}' (expected cube, sphere, capsule, cylinder or cone)", name
+    )))
+}
+
+/// Scene issue as a dict with its kind, bodies and message
+fn issue_dict<'py>(py: Python<'py>, issue: &SceneIssue) -> PyResult<Bound<'py, PyDict>> {
+    let item = PyDict::new(py);
+    item.set_item("kind", issue.kind())?;
+    item.set_item("bodies", issue.bodies())?;
+    item.set_item("message", issue.to_string())?;
+    Ok(item)
+}
 
 /// Get the library version
 #[pyfunction]
@@ -12,6 +172,40 @@ fn version() -> &'static str {
     physobx_core::version()
 }
 
+/// Densities of common materials, usable by name wherever a density is taken
+///
+/// Returns:
+///     Dict of material name to density in kg/m³
+#[pyfunction]
+fn densities<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (name, density) in DENSITIES {
+        dict.set_item(name, density)?;
+    }
+    Ok(dict)
+}
+
+/// Material presets selectable by name with `Scene.set_preset`
+///
+/// Returns:
+///     Dict of preset name to a dict with density (kg/m³), friction,
+///     restitution, color, roughness and metallic
+#[pyfunction]
+fn material_presets<'py>(py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for preset in &MATERIAL_PRESETS {
+        let entry = PyDict::new(py);
+        entry.set_item("density", preset.density)?;
+        entry.set_item("friction", preset.friction)?;
+        entry.set_item("restitution", preset.restitution)?;
+        entry.set_item("color", preset.look.color.to_vec())?;
+        entry.set_item("roughness", preset.look.roughness)?;
+        entry.set_item("metallic", preset.look.metallic)?;
+        dict.set_item(preset.name, entry)?;
+    }
+    Ok(dict)
+}
+
 /// Python wrapper for SceneBuilder
 #[pyclass(name = "Scene")]
 pub struct PyScene {
@@ -32,9 +226,113 @@ impl PyScene {
         self.inner.add_ground(y, size);
     }
 
+    /// Add a static plane at any tilt, e.g. a ramp or a funnel side
+    ///
+    /// Args:
+    ///     point: Center of the plane's top face
+    ///     normal: Direction the top face points (need not be unit length,
+    ///         must not be zero)
+    ///     size: Half the side length of the square plane
+    fn add_plane(&mut self, point: [f32; 3], normal: [f32; 3], size: f32) -> PyResult<()> {
+        if normal.iter().all(|&c| c == 0.0) {
+            return Err(PyValueError::new_err("plane normal must not be zero"));
+        }
+        self.inner.add_plane(point, normal, size);
+        Ok(())
+    }
+
     /// Add a single cube
-    fn add_cube(&mut self, position: [f32; 3], half_extent: f32, mass: f32) {
+    ///
+    /// Args:
+    ///     position: Center of the cube
+    ///     half_extent: Half the side length
+    ///     mass: Mass in kg
+    ///     angular_velocity: Initial spin in rad/s about the world axes
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_extent, mass, angular_velocity=None, friction=None, restitution=None))]
+    fn add_cube(
+        &mut self,
+        position: [f32; 3],
+        half_extent: f32,
+        mass: f32,
+        angular_velocity: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
         self.inner.add_cube(position, half_extent, mass);
+        self.spin_last(angular_velocity);
+        self.material_last(friction, restitution);
+        Ok(())
+    }
+
+    /// Add a single cube with an initial orientation, e.g. a pre-tilted domino
+    ///
+    /// Args:
+    ///     position: Center of the cube
+    ///     half_extent: Half the side length
+    ///     mass: Mass in kg
+    ///     rotation: Orientation quaternion [x, y, z, w]
+    ///     euler: Orientation as rotations in radians about the world X, then
+    ///         Y, then Z axes (instead of `rotation`)
+    ///     color: RGB color (default: the cube color)
+    ///     angular_velocity: Initial spin in rad/s about the world axes
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_extent, mass, rotation=None, euler=None, color=None, angular_velocity=None, friction=None, restitution=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_cube_oriented(
+        &mut self,
+        position: [f32; 3],
+        half_extent: f32,
+        mass: f32,
+        rotation: Option<[f32; 4]>,
+        euler: Option<[f32; 3]>,
+        color: Option<[f32; 3]>,
+        angular_velocity: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        let rotation = parse_orientation(rotation, euler)?;
+        check_material(friction, restitution)?;
+        self.inner.add_cube_oriented(position, rotation, half_extent, mass);
+        if let (Some(color), Some(body)) = (color, self.inner.bodies.last_mut()) {
+            body.color = color;
+        }
+        self.spin_last(angular_velocity);
+        self.material_last(friction, restitution);
+        Ok(())
+    }
+
+    /// Add a single cube with its mass from a density, so mass and inertia
+    /// scale with size
+    ///
+    /// Args:
+    ///     position: Center of the cube
+    ///     half_extent: Half the side length
+    ///     density: Density in kg/m³, or a material name from `densities()`
+    ///     color: RGB color (default: the cube color)
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_extent, density, color=None, friction=None, restitution=None))]
+    fn add_cube_density(
+        &mut self,
+        position: [f32; 3],
+        half_extent: f32,
+        density: &Bound<'_, PyAny>,
+        color: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        let density = parse_density(density)?;
+        check_material(friction, restitution)?;
+        self.inner.add_cube_density(position, half_extent, density);
+        if let (Some(color), Some(body)) = (color, self.inner.bodies.last_mut()) {
+            body.color = color;
+        }
+        self.material_last(friction, restitution);
+        Ok(())
     }
 
     /// Add a single cube with custom color
@@ -43,6 +341,38 @@ impl PyScene {
         self.inner.add_cube_colored(position, half_extent, mass, color);
     }
 
+    /// Add a box with separate half extents along its local X, Y and Z axes
+    ///
+    /// Args:
+    ///     position: Center of the box
+    ///     half_extents: Half size along each local axis
+    ///     mass: Mass in kg
+    ///     rotation: Orientation quaternion [x, y, z, w]
+    ///     color: RGB color (default: the cube color)
+    ///     angular_velocity: Initial spin in rad/s about the world axes
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_extents, mass, rotation=[0.0, 0.0, 0.0, 1.0], color=None, angular_velocity=None, friction=None, restitution=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_box(
+        &mut self,
+        position: [f32; 3],
+        half_extents: [f32; 3],
+        mass: f32,
+        rotation: [f32; 4],
+        color: Option<[f32; 3]>,
+        angular_velocity: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
+        let color = color.unwrap_or_else(|| RigidBodyConfig::default().color);
+        self.inner.add_box(position, rotation, half_extents, mass, color);
+        self.spin_last(angular_velocity);
+        self.material_last(friction, restitution);
+        Ok(())
+    }
+
     /// Add a grid of cubes
     #[pyo3(signature = (center, spacing, count, half_extent, mass))]
     fn add_cube_grid(
@@ -56,9 +386,549 @@ impl PyScene {
         self.inner.add_cube_grid(center, spacing, count, half_extent, mass);
     }
 
+    /// Add a grid of spheres, e.g. for a ball pit
+    ///
+    /// Args:
+    ///     center: Center of the grid
+    ///     spacing: Distance between sphere centers
+    ///     count: Number of spheres along X, Y and Z
+    ///     radius: Radius of each sphere
+    ///     mass: Mass of each sphere in kg
+    ///     color: RGB color (default: the sphere color)
+    ///
+    /// Returns:
+    ///     Indices of the added spheres
+    #[pyo3(signature = (center, spacing, count, radius, mass, color=None))]
+    fn add_sphere_grid(
+        &mut self,
+        center: [f32; 3],
+        spacing: f32,
+        count: [u32; 3],
+        radius: f32,
+        mass: f32,
+        color: Option<[f32; 3]>,
+    ) -> Vec<usize> {
+        let first = self.inner.bodies.len();
+        self.inner.add_sphere_grid(center, spacing, count, radius, mass);
+        self.color_from(first, color)
+    }
+
+    /// Add a grid whose cells cycle through a list of shapes
+    ///
+    /// Args:
+    ///     center: Center of the grid
+    ///     spacing: Distance between body centers
+    ///     count: Number of bodies along X, Y and Z
+    ///     shapes: Shape names used in turn, cell by cell ("cube",
+    ///         "sphere", "capsule", "cylinder", "cone")
+    ///     size: Half extent of cubes, radius and half height of the other
+    ///         shapes
+    ///     mass: Mass of each body in kg
+    ///
+    /// Returns:
+    ///     Indices of the added bodies
+    #[pyo3(signature = (center, spacing, count, shapes=vec!["cube".to_string(), "sphere".to_string()], size=0.5, mass=1.0))]
+    fn add_mixed_grid(
+        &mut self,
+        center: [f32; 3],
+        spacing: f32,
+        count: [u32; 3],
+        shapes: Vec<String>,
+        size: f32,
+        mass: f32,
+    ) -> PyResult<Vec<usize>> {
+        let shapes = shapes.iter().map(|name| parse_shape(name)).collect::<PyResult<Vec<_>>>()?;
+        if shapes.is_empty() {
+            return Err(PyValueError::new_err("shapes must not be empty"));
+        }
+        let first = self.inner.bodies.len();
+        self.inner.add_mixed_grid(center, spacing, count, &shapes, size, mass);
+        Ok(self.color_from(first, None))
+    }
+
+    /// Add a 2D pyramid of cubes, the classic stacking test
+    ///
+    /// The bottom row has `base_count` cubes along X, each row above one
+    /// fewer. Rows touch exactly; cubes within a row are 2% of their size
+    /// apart.
+    ///
+    /// Args:
+    ///     base_center: Middle of the bottom row's underside, e.g. the top
+    ///         of the ground (y + 0.1 for add_ground(y, ...))
+    ///     base_count: Cubes in the bottom row
+    ///     half_extent: Half the side length of each cube
+    ///     mass: Mass of each cube in kg
+    ///     color: RGB color (default: the cube color)
+    ///
+    /// Returns:
+    ///     Indices of the added cubes, bottom row first
+    #[pyo3(signature = (base_center, base_count, half_extent=0.5, mass=1.0, color=None))]
+    fn add_pyramid(
+        &mut self,
+        base_center: [f32; 3],
+        base_count: u32,
+        half_extent: f32,
+        mass: f32,
+        color: Option<[f32; 3]>,
+    ) -> Vec<usize> {
+        let first = self.inner.bodies.len();
+        self.inner.add_pyramid(base_center, base_count, half_extent, mass);
+        self.color_from(first, color)
+    }
+
+    /// Add a vertical tower of cubes, each resting exactly on the one below
+    ///
+    /// Args:
+    ///     base_center: Middle of the bottom cube's underside
+    ///     height: Number of cubes
+    ///     half_extent: Half the side length of each cube
+    ///     mass: Mass of each cube in kg
+    ///     color: RGB color (default: the cube color)
+    ///
+    /// Returns:
+    ///     Indices of the added cubes, bottom first
+    #[pyo3(signature = (base_center, height, half_extent=0.5, mass=1.0, color=None))]
+    fn add_tower(
+        &mut self,
+        base_center: [f32; 3],
+        height: u32,
+        half_extent: f32,
+        mass: f32,
+        color: Option<[f32; 3]>,
+    ) -> Vec<usize> {
+        let first = self.inner.bodies.len();
+        self.inner.add_tower(base_center, height, half_extent, mass);
+        self.color_from(first, color)
+    }
+
+    /// Add a brick wall running along X, e.g. for destruction demos
+    ///
+    /// Staggered walls shift every other row by half a brick and close the
+    /// row ends with half bricks (of half the mass), so the wall stays
+    /// rectangular.
+    ///
+    /// Args:
+    ///     origin: Middle of the wall's underside, e.g. the top of the
+    ///         ground (y + 0.1 for add_ground(y, ...))
+    ///     rows: Number of brick rows
+    ///     cols: Bricks per row (full bricks in unstaggered rows)
+    ///     brick_half_extents: Half length (along X), height and thickness
+    ///         of one brick
+    ///     mass: Mass of each full brick in kg
+    ///     staggered: Offset alternate rows like real masonry
+    ///     color: RGB color (default: the cube color)
+    ///
+    /// Returns:
+    ///     Indices of the added bricks, bottom row first
+    #[pyo3(signature = (origin, rows, cols, brick_half_extents=[0.4, 0.2, 0.2], mass=1.0, staggered=true, color=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_wall(
+        &mut self,
+        origin: [f32; 3],
+        rows: u32,
+        cols: u32,
+        brick_half_extents: [f32; 3],
+        mass: f32,
+        staggered: bool,
+        color: Option<[f32; 3]>,
+    ) -> Vec<usize> {
+        let first = self.inner.bodies.len();
+        self.inner.add_wall(origin, rows, cols, brick_half_extents, mass, staggered);
+        self.color_from(first, color)
+    }
+
+    /// Scatter randomly sized, weighted and oriented bodies over a box,
+    /// reproducibly for a given seed
+    ///
+    /// Bodies lie inside the region and don't overlap each other or the
+    /// bodies already added; bodies without room after 100 tries are left
+    /// out.
+    ///
+    /// Args:
+    ///     region_min: Minimum corner of the region
+    ///     region_max: Maximum corner of the region
+    ///     count: Number of bodies to scatter
+    ///     shape: "cube", "sphere", "capsule", "cylinder" or "cone"
+    ///     size_range: (min, max) half extent of cubes, radius and half
+    ///         height of the other shapes
+    ///     mass_range: (min, max) mass in kg
+    ///     seed: Random seed; the same seed gives the same bodies on every
+    ///         run and platform
+    ///     color: RGB color (default: the shape's color)
+    ///
+    /// Returns:
+    ///     Indices of the added bodies
+    #[pyo3(signature = (region_min, region_max, count, shape="cube", size_range=(0.2, 0.5), mass_range=(0.5, 2.0), seed=0, color=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn scatter_random(
+        &mut self,
+        region_min: [f32; 3],
+        region_max: [f32; 3],
+        count: u32,
+        shape: &str,
+        size_range: (f32, f32),
+        mass_range: (f32, f32),
+        seed: u64,
+        color: Option<[f32; 3]>,
+    ) -> PyResult<Vec<usize>> {
+        let shape = parse_shape(shape)?;
+        if !(size_range.0 > 0.0 && size_range.0 <= size_range.1) || !(mass_range.0 > 0.0 && mass_range.0 <= mass_range.1) {
+            return Err(PyValueError::new_err("size_range and mass_range must be positive (min, max) pairs"));
+        }
+        let first = self.inner.bodies.len();
+        self.inner.scatter_random((region_min, region_max), count, shape, size_range, mass_range, seed);
+        Ok(self.color_from(first, color))
+    }
+
+    /// Add a box of static walls around a region, e.g. to keep bodies from
+    /// rolling away
+    ///
+    /// Args:
+    ///     center: Middle of the space inside the container
+    ///     inner_extents: Half size of the inside along x, y and z
+    ///     wall_thickness: Thickness of each wall, added outside the inside
+    ///     lid: Also close the top (default: open box of five walls)
+    ///     hidden: Keep the walls out of rendered frames, shadows and ID
+    ///         masks so the contents stay visible
+    ///     color: RGB color of the walls (default: light gray)
+    ///
+    /// Returns:
+    ///     Indices of the walls, floor first
+    #[pyo3(signature = (center, inner_extents, wall_thickness=0.1, lid=false, hidden=false, color=None))]
+    fn add_container(
+        &mut self,
+        center: [f32; 3],
+        inner_extents: [f32; 3],
+        wall_thickness: f32,
+        lid: bool,
+        hidden: bool,
+        color: Option<[f32; 3]>,
+    ) -> Vec<usize> {
+        let first = self.inner.bodies.len();
+        self.inner.add_container(center, inner_extents, wall_thickness, lid);
+        let walls = self.color_from(first, color);
+        for &index in &walls {
+            self.inner.set_hidden(index, hidden);
+        }
+        walls
+    }
+
+    /// Hide a static body from rendered frames, shadows and ID masks while
+    /// it keeps colliding (no effect on dynamic bodies)
+    fn set_hidden(&mut self, index: usize, hidden: bool) {
+        self.inner.set_hidden(index, hidden);
+    }
+
+    /// Add a static cube (environment geometry that never moves)
+    ///
+    /// Static bodies collide like the ground, are never synced back from the
+    /// physics engine and are uploaded to the renderer once.
+    ///
+    /// Args:
+    ///     position: Center of the cube
+    ///     half_extent: Half the side length
+    ///     color: RGB color (default: gray)
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_extent, color=None, friction=None, restitution=None))]
+    fn add_static_cube(
+        &mut self,
+        position: [f32; 3],
+        half_extent: f32,
+        color: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
+        self.inner.add_static_cube(position, half_extent, color.unwrap_or([0.6, 0.6, 0.6]));
+        self.material_last(friction, restitution);
+        Ok(())
+    }
+
+    /// Add a static sphere (environment geometry that never moves)
+    ///
+    /// Args:
+    ///     position: Center of the sphere
+    ///     radius: Sphere radius
+    ///     color: RGB color (default: gray)
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, radius, color=None, friction=None, restitution=None))]
+    fn add_static_sphere(
+        &mut self,
+        position: [f32; 3],
+        radius: f32,
+        color: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
+        self.inner.add_static_sphere(position, radius, color.unwrap_or([0.6, 0.6, 0.6]));
+        self.material_last(friction, restitution);
+        Ok(())
+    }
+
+    /// Add a four-wheeled vehicle: a box chassis on four sphere wheels with
+    /// sprung suspension
+    ///
+    /// The vehicle faces +X rotated by `heading` (rad) about +Y. Drive it
+    /// with Simulator.set_vehicle_controls.
+    ///
+    /// Args:
+    ///     position: Chassis center
+    ///     heading: Rotation about +Y (rad)
+    ///     chassis_half_extents: Chassis half length, height and width
+    ///     chassis_mass: Chassis mass (kg)
+    ///     wheel_radius: Wheel radius (m)
+    ///     wheel_mass: Mass of each wheel (kg)
+    ///     wheelbase: Distance between front and rear axles (m)
+    ///     track: Distance between left and right wheels (m)
+    ///     suspension_travel: Suspension travel either side of rest (m)
+    ///     suspension_stiffness: Spring stiffness per wheel (N/m)
+    ///     suspension_damping: Damping per wheel (N·s/m)
+    ///     max_speed: Ground speed at full throttle (m/s)
+    ///     motor_torque: Torque limit of each driven wheel (N·m)
+    ///     max_steering_angle: Front wheel angle at full steering (rad)
+    ///     all_wheel_drive: Drive all wheels instead of the rear two
+    ///
+    /// Returns:
+    ///     Vehicle index
+    #[pyo3(signature = (
+        position,
+        heading=0.0,
+        chassis_half_extents=[1.0, 0.25, 0.5],
+        chassis_mass=200.0,
+        wheel_radius=0.3,
+        wheel_mass=10.0,
+        wheelbase=1.6,
+        track=1.4,
+        suspension_travel=0.15,
+        suspension_stiffness=20000.0,
+        suspension_damping=1500.0,
+        max_speed=10.0,
+        motor_torque=300.0,
+        max_steering_angle=0.5,
+        all_wheel_drive=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_vehicle(
+        &mut self,
+        position: [f32; 3],
+        heading: f32,
+        chassis_half_extents: [f32; 3],
+        chassis_mass: f32,
+        wheel_radius: f32,
+        wheel_mass: f32,
+        wheelbase: f32,
+        track: f32,
+        suspension_travel: f32,
+        suspension_stiffness: f32,
+        suspension_damping: f32,
+        max_speed: f32,
+        motor_torque: f32,
+        max_steering_angle: f32,
+        all_wheel_drive: bool,
+    ) -> usize {
+        self.inner.add_vehicle(&VehicleConfig {
+            position,
+            heading,
+            chassis_half_extents,
+            chassis_mass,
+            wheel_radius,
+            wheel_mass,
+            wheelbase,
+            track,
+            suspension_travel,
+            suspension_stiffness,
+            suspension_damping,
+            max_speed,
+            motor_torque,
+            max_steering_angle,
+            all_wheel_drive,
+            ..Default::default()
+        })
+    }
+
+    /// Body indices of a vehicle as a dict with "chassis" and "wheels"
+    /// (front-left, front-right, rear-left, rear-right)
+    fn vehicle_bodies<'py>(&self, py: Python<'py>, vehicle: usize) -> PyResult<Bound<'py, PyDict>> {
+        let vehicle = self.inner.vehicles.get(vehicle)
+            .ok_or_else(|| PyIndexError::new_err(format!("vehicle index {} out of range", vehicle)))?;
+        let dict = PyDict::new(py);
+        dict.set_item("chassis", vehicle.chassis)?;
+        dict.set_item("wheels", vehicle.wheels)?;
+        Ok(dict)
+    }
+
+    /// Connect body `a` to body `b`, or to a fixed world point when `b` is
+    /// None, with a spring-damper
+    ///
+    /// Args:
+    ///     a: Body index
+    ///     b: Body index, or None for a world anchor
+    ///     rest_length: Unstretched length (m); None uses the initial anchor distance
+    ///     stiffness: Spring stiffness (N/m)
+    ///     damping: Damping (N·s/m)
+    ///     anchor_a: Attachment point in a's local frame
+    ///     anchor_b: Attachment point in b's local frame, or the world point when b is None
+    ///
+    /// Returns:
+    ///     Joint index
+    #[pyo3(signature = (a, b=None, rest_length=None, stiffness=100.0, damping=1.0, anchor_a=[0.0, 0.0, 0.0], anchor_b=[0.0, 0.0, 0.0]))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_spring(
+        &mut self,
+        a: usize,
+        b: Option<usize>,
+        rest_length: Option<f32>,
+        stiffness: f32,
+        damping: f32,
+        anchor_a: [f32; 3],
+        anchor_b: [f32; 3],
+    ) -> PyResult<usize> {
+        for index in std::iter::once(a).chain(b) {
+            if index >= self.inner.bodies.len() {
+                return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+            }
+        }
+        if b == Some(a) {
+            return Err(PyValueError::new_err("a spring needs two different bodies"));
+        }
+        Ok(self.inner
+            .add_spring(a, b, anchor_a, anchor_b, rest_length, stiffness, damping)
+            .expect("body indices checked above"))
+    }
+
+    /// Fix an added body in place (or make it dynamic again)
+    #[pyo3(signature = (index, is_static=true))]
+    fn set_static(&mut self, index: usize, is_static: bool) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.set_static(index, is_static);
+        Ok(())
+    }
+
+    /// Move an added body along waypoints as a kinematic body (elevators,
+    /// moving obstacles)
+    ///
+    /// The body starts at the first waypoint and is moved every step; it
+    /// pushes and carries dynamic bodies but ignores gravity and contacts.
+    ///
+    /// Args:
+    ///     index: Body index
+    ///     waypoints: World positions of the body's center, or None to make
+    ///         the body dynamic again
+    ///     speed: Speed along the path (m/s)
+    ///     loop_mode: "once" (stop at the end), "loop" (return to the first
+    ///         waypoint and repeat) or "ping_pong" (go back and forth)
+    #[pyo3(signature = (index, waypoints, speed=1.0, loop_mode="loop"))]
+    fn set_motion_path(&mut self, index: usize, waypoints: Option<Vec<[f32; 3]>>, speed: f32, loop_mode: &str) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        let loop_mode = PathLoop::from_name(loop_mode).ok_or_else(|| {
+            PyValueError::new_err(format!("unknown loop mode '{}' (expected once, loop or ping_pong)", loop_mode))
+        })?;
+        if waypoints.as_ref().is_some_and(Vec::is_empty) {
+            return Err(PyValueError::new_err("a motion path needs at least one waypoint"));
+        }
+        let path = waypoints.map(|waypoints| MotionPath { waypoints, speed, loop_mode });
+        self.inner.set_motion_path(index, path);
+        Ok(())
+    }
+
     /// Add a single sphere
-    fn add_sphere(&mut self, position: [f32; 3], radius: f32, mass: f32) {
+    ///
+    /// Args:
+    ///     position: Center of the sphere
+    ///     radius: Sphere radius
+    ///     mass: Mass in kg
+    ///     angular_velocity: Initial spin in rad/s about the world axes
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, radius, mass, angular_velocity=None, friction=None, restitution=None))]
+    fn add_sphere(
+        &mut self,
+        position: [f32; 3],
+        radius: f32,
+        mass: f32,
+        angular_velocity: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
         self.inner.add_sphere(position, radius, mass);
+        self.spin_last(angular_velocity);
+        self.material_last(friction, restitution);
+        Ok(())
+    }
+
+    /// Add a single sphere with an initial orientation
+    ///
+    /// Args:
+    ///     position: Center of the sphere
+    ///     radius: Sphere radius
+    ///     mass: Mass in kg
+    ///     rotation: Orientation quaternion [x, y, z, w]
+    ///     euler: Orientation as rotations in radians about the world X, then
+    ///         Y, then Z axes (instead of `rotation`)
+    ///     color: RGB color (default: the sphere color)
+    ///     angular_velocity: Initial spin in rad/s about the world axes
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, radius, mass, rotation=None, euler=None, color=None, angular_velocity=None, friction=None, restitution=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_sphere_oriented(
+        &mut self,
+        position: [f32; 3],
+        radius: f32,
+        mass: f32,
+        rotation: Option<[f32; 4]>,
+        euler: Option<[f32; 3]>,
+        color: Option<[f32; 3]>,
+        angular_velocity: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        let rotation = parse_orientation(rotation, euler)?;
+        check_material(friction, restitution)?;
+        self.inner.add_sphere_oriented(position, rotation, radius, mass);
+        if let (Some(color), Some(body)) = (color, self.inner.bodies.last_mut()) {
+            body.color = color;
+        }
+        self.spin_last(angular_velocity);
+        self.material_last(friction, restitution);
+        Ok(())
+    }
+
+    /// Add a single sphere with its mass from a density
+    ///
+    /// Args:
+    ///     position: Center of the sphere
+    ///     radius: Sphere radius
+    ///     density: Density in kg/m³, or a material name from `densities()`
+    ///     color: RGB color (default: the sphere color)
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, radius, density, color=None, friction=None, restitution=None))]
+    fn add_sphere_density(
+        &mut self,
+        position: [f32; 3],
+        radius: f32,
+        density: &Bound<'_, PyAny>,
+        color: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        let density = parse_density(density)?;
+        check_material(friction, restitution)?;
+        self.inner.add_sphere_density(position, radius, density);
+        if let (Some(color), Some(body)) = (color, self.inner.bodies.last_mut()) {
+            body.color = color;
+        }
+        self.material_last(friction, restitution);
+        Ok(())
     }
 
     /// Add a single sphere with custom color
@@ -67,16 +937,25 @@ impl PyScene {
         self.inner.add_sphere_colored(position, radius, mass, color);
     }
 
-    /// Add a sphere with initial velocity
-    #[pyo3(signature = (position, velocity, radius, mass))]
+    /// Add a sphere with initial velocity (and optionally spin, e.g. a
+    /// ball thrown with topspin)
+    #[pyo3(signature = (position, velocity, radius, mass, angular_velocity=None, friction=None, restitution=None))]
+    #[allow(clippy::too_many_arguments)]
     fn add_sphere_with_velocity(
         &mut self,
         position: [f32; 3],
         velocity: [f32; 3],
         radius: f32,
         mass: f32,
-    ) {
+        angular_velocity: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
         self.inner.add_sphere_with_velocity(position, velocity, radius, mass);
+        self.spin_last(angular_velocity);
+        self.material_last(friction, restitution);
+        Ok(())
     }
 
     /// Add a sphere with initial velocity and custom color
@@ -92,153 +971,3552 @@ impl PyScene {
         self.inner.add_sphere_with_velocity_colored(position, velocity, radius, mass, color);
     }
 
-    /// Get the number of bodies in the scene
-    fn body_count(&self) -> usize {
-        self.inner.bodies.len()
+    /// Add a single capsule standing upright
+    ///
+    /// Args:
+    ///     position: Center of the capsule
+    ///     half_height: Half length of the straight section along local Y
+    ///     radius: Radius of the cylinder and its hemispherical caps
+    ///     mass: Mass in kg
+    ///     color: RGB color (default: green)
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_height, radius, mass, color=None, friction=None, restitution=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_capsule(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+        color: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
+        match color {
+            Some(color) => self.inner.add_capsule_colored(position, half_height, radius, mass, color),
+            None => self.inner.add_capsule(position, half_height, radius, mass),
+        };
+        self.material_last(friction, restitution);
+        Ok(())
     }
 
-    /// Get counts of (cubes, spheres)
-    fn shape_counts(&self) -> (usize, usize) {
-        self.inner.shape_counts()
+    /// Add a single cylinder standing upright
+    ///
+    /// Args:
+    ///     position: Center of the cylinder
+    ///     half_height: Half length along local Y
+    ///     radius: Radius of the end disks
+    ///     mass: Mass in kg
+    ///     color: RGB color (default: brown)
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_height, radius, mass, color=None, friction=None, restitution=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_cylinder(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+        color: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
+        match color {
+            Some(color) => self.inner.add_cylinder_colored(position, half_height, radius, mass, color),
+            None => self.inner.add_cylinder(position, half_height, radius, mass),
+        };
+        self.material_last(friction, restitution);
+        Ok(())
     }
-}
-
-/// Python wrapper for Simulator with optional rendering
-#[pyclass(name = "Simulator")]
-pub struct PySimulator {
-    inner: CoreSimulator,
-    renderer: Option<Renderer>,
-    half_extent: f32,
-}
 
-#[pymethods]
-impl PySimulator {
-    /// Create a new simulator
+    /// Add a cylinder lying on its side (axis along X), free to roll along Z
     ///
     /// Args:
-    ///     scene: The scene to simulate
-    ///     width: Render width (default 1920)
-    ///     height: Render height (default 1080)
-    #[new]
-    #[pyo3(signature = (scene, width=1920, height=1080))]
-    fn new(scene: &PyScene, width: u32, height: u32) -> PyResult<Self> {
-        // Get half_extent from first body or default
-        let half_extent = scene.inner.bodies.first()
-            .map(|b| b.half_extents[0])
-            .unwrap_or(0.5);
+    ///     position: Center of the barrel
+    ///     half_height: Half length along the barrel's axis
+    ///     radius: Radius of the end disks
+    ///     mass: Mass in kg
+    fn add_barrel(&mut self, position: [f32; 3], half_height: f32, radius: f32, mass: f32) {
+        self.inner.add_barrel(position, half_height, radius, mass);
+    }
 
-        let max_instances = scene.inner.bodies.len().max(1000) as u32;
+    /// Add a single cone standing on its base
+    ///
+    /// Args:
+    ///     position: Center of the cone's axis (halfway between base and apex)
+    ///     half_height: Half the distance from base to apex
+    ///     radius: Radius of the base
+    ///     mass: Mass in kg
+    ///     color: RGB color (default: orange)
+    ///     friction: Friction coefficient (default: the shape's default)
+    ///     restitution: Bounciness, 0 = none (default: the shape's default)
+    #[pyo3(signature = (position, half_height, radius, mass, color=None, friction=None, restitution=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_cone(
+        &mut self,
+        position: [f32; 3],
+        half_height: f32,
+        radius: f32,
+        mass: f32,
+        color: Option<[f32; 3]>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        check_material(friction, restitution)?;
+        match color {
+            Some(color) => self.inner.add_cone_colored(position, half_height, radius, mass, color),
+            None => self.inner.add_cone(position, half_height, radius, mass),
+        };
+        self.material_last(friction, restitution);
+        Ok(())
+    }
 
-        // Get ground parameters from scene
+    /// Attach an extra collider to an added body, making it a compound body
+    ///
+    /// The child moves rigidly with the body, collides and is rendered with
+    /// it. The body's mass is spread over its own shape and all children in
+    /// proportion to their volumes.
+    ///
+    /// Args:
+    ///     body: Index of the body
+    ///     shape: "cube", "sphere", "capsule", "cylinder" or "cone"
+    ///     offset: Center of the child in the body's local frame
+    ///     half_extents: Half extents of a cube child
+    ///     radius: Radius of a sphere or axial child
+    ///     half_height: Half length along the child's local Y of an axial child
+    ///     rotation: Rotation relative to the body as (x, y, z, w)
+    ///     color: RGB color (default: the body's color)
+    #[pyo3(signature = (body, shape, offset, half_extents=[0.5, 0.5, 0.5], radius=0.5, half_height=0.5, rotation=[0.0, 0.0, 0.0, 1.0], color=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_child_collider(
+        &mut self,
+        body: usize,
+        shape: &str,
+        offset: [f32; 3],
+        half_extents: [f32; 3],
+        radius: f32,
+        half_height: f32,
+        rotation: [f32; 4],
+        color: Option<[f32; 3]>,
+    ) -> PyResult<()> {
+        if body >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", body)));
+        }
+        let shape = parse_shape(shape)?;
+        let mut child = match shape {
+            ShapeType::Cube => ChildCollider::cube(offset, half_extents),
+            ShapeType::Sphere => ChildCollider::sphere(offset, radius),
+            _ => ChildCollider::axial(shape, offset, half_height, radius),
+        };
+        child.rotation = rotation;
+        child.color = color;
+        self.inner.add_child_collider(body, child);
+        Ok(())
+    }
+
+    /// Add an L-shaped block of three equal cubes (a compound body)
+    ///
+    /// Args:
+    ///     position: Center of the corner cube
+    ///     half_extent: Half size of each cube
+    ///     mass: Total mass in kg
+    ///     rotation: Rotation as (x, y, z, w); unrotated, the second cube
+    ///         sits along +X and the third on top of it
+    #[pyo3(signature = (position, half_extent, mass, rotation=[0.0, 0.0, 0.0, 1.0]))]
+    fn add_l_block(&mut self, position: [f32; 3], half_extent: f32, mass: f32, rotation: [f32; 4]) {
+        self.inner.add_l_block(position, rotation, half_extent, mass);
+    }
+
+    /// Add a spinning top resting on its tip
+    ///
+    /// Args:
+    ///     position: Center of the tip sphere
+    ///     radius: Radius of the disk whose mass and inertia the top carries; its
+    ///         center of mass sits this far above the tip, whose radius is
+    ///         0.15 * radius
+    ///     mass: Mass in kg
+    ///     spin: Spin rate about the top's axis (rad/s)
+    ///     tilt: Initial tilt of the axis from vertical (radians)
+    ///
+    /// Step with dt <= 1/480 s (e.g. substeps=8 at 60 fps) for stable precession.
+    #[pyo3(signature = (position, radius, mass, spin, tilt=0.2))]
+    fn add_spinning_top(&mut self, position: [f32; 3], radius: f32, mass: f32, spin: f32, tilt: f32) {
+        self.inner.add_spinning_top(position, radius, mass, spin, tilt);
+    }
+
+    /// Override the center of mass and/or inertia of an added body
+    ///
+    /// Args:
+    ///     index: Body index (in the order bodies were added)
+    ///     com_offset: Center of mass offset from the shape center (local frame)
+    ///     inertia: Principal moments of inertia [Ixx, Iyy, Izz] (local frame)
+    #[pyo3(signature = (index, com_offset=None, inertia=None))]
+    fn set_mass_properties(
+        &mut self,
+        index: usize,
+        com_offset: Option<[f32; 3]>,
+        inertia: Option<[f32; 3]>,
+    ) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.set_mass_properties(index, com_offset, inertia);
+        Ok(())
+    }
+
+    /// Set how friction and restitution of touching bodies combine
+    ///
+    /// Args:
+    ///     friction: "average", "min", "multiply" or "max" (default "average")
+    ///     restitution: "average", "min", "multiply" or "max" (default "average")
+    ///
+    /// When two bodies use different rules, max > multiply > min > average wins.
+    #[pyo3(signature = (friction="average", restitution="average"))]
+    fn set_combine_rules(&mut self, friction: &str, restitution: &str) -> PyResult<()> {
+        let friction = parse_combine_rule(friction)?;
+        let restitution = parse_combine_rule(restitution)?;
+        self.inner.set_combine_rules(friction, restitution);
+        Ok(())
+    }
+
+    /// Choose the broad-phase algorithm used to find candidate contacts
+    ///
+    /// Args:
+    ///     kind: "sap" (sweep-and-prune, default) or "bvh"
+    ///     bvh_margin: How far the BVH enlarges collider boxes (m); larger
+    ///         values rebuild the tree less often but test more pairs
+    ///     prediction_distance: Contact prediction distance, normalized by
+    ///         the length unit (default 0.002)
+    #[pyo3(signature = (kind="sap", bvh_margin=0.05, prediction_distance=0.002))]
+    fn set_broad_phase(&mut self, kind: &str, bvh_margin: f32, prediction_distance: f32) -> PyResult<()> {
+        let kind = BroadPhaseKind::from_name(kind).ok_or_else(|| {
+            PyValueError::new_err(format!("unknown broad-phase '{}' (expected sap or bvh)", kind))
+        })?;
+        self.inner.set_broad_phase(kind, bvh_margin, prediction_distance);
+        Ok(())
+    }
+
+    /// Constrain all dynamic bodies to their xy plane (2D mode)
+    ///
+    /// Bodies keep their starting z, move only in x and y and rotate only
+    /// about z. Simulators of planar scenes start with an orthographic
+    /// camera framing the bodies.
+    #[pyo3(signature = (enabled=true))]
+    fn set_planar(&mut self, enabled: bool) {
+        self.inner.set_planar(enabled);
+    }
+
+    /// Set the length of one scene unit in meters
+    ///
+    /// Positions, sizes, velocities and gravity are then in scene units while
+    /// mass stays in kg and densities in kg/m³. Gravity, wind and fluid
+    /// settings made so far are converted, so call this first. Solver
+    /// tolerances suit bodies about one unit across: model small objects
+    /// in a small unit.
+    ///
+    /// Args:
+    ///     meters_per_unit: 1 for meters (default), 0.01 for centimeters,
+    ///         0.001 for millimeters
+    fn set_length_scale(&mut self, meters_per_unit: f32) -> PyResult<()> {
+        if !(meters_per_unit > 0.0 && meters_per_unit.is_finite()) {
+            return Err(PyValueError::new_err("meters_per_unit must be positive"));
+        }
+        self.inner.set_length_scale(meters_per_unit);
+        Ok(())
+    }
+
+    /// Meters per scene unit (see set_length_scale)
+    fn length_scale(&self) -> f32 {
+        self.inner.physics.length_scale
+    }
+
+    /// Set solver settings; omitted values are left unchanged
+    ///
+    /// Args:
+    ///     fixed_dt: Step size simulators of this scene start with (default
+    ///         1/240 s)
+    ///     solver_iterations: Rapier solver iterations per step (default 8)
+    ///     ccd_speed: Bodies starting at least this fast (units/s) use
+    ///         continuous collision detection; 0 = all (default 10)
+    #[pyo3(signature = (fixed_dt=None, solver_iterations=None, ccd_speed=None))]
+    fn set_solver(&mut self, fixed_dt: Option<f32>, solver_iterations: Option<u32>, ccd_speed: Option<f32>) -> PyResult<()> {
+        let physics = &self.inner.physics;
+        let fixed_dt = fixed_dt.unwrap_or(physics.fixed_dt);
+        let solver_iterations = solver_iterations.unwrap_or(physics.solver_iterations);
+        let ccd_speed = ccd_speed.unwrap_or(physics.ccd_speed);
+        if !(fixed_dt > 0.0) || solver_iterations == 0 || ccd_speed < 0.0 {
+            return Err(PyValueError::new_err(
+                "fixed_dt and solver_iterations must be positive and ccd_speed non-negative",
+            ));
+        }
+        self.inner.set_solver(fixed_dt, solver_iterations, ccd_speed);
+        Ok(())
+    }
+
+    /// Pick the step size, solver iterations and CCD speed for the bodies
+    /// added so far
+    ///
+    /// The step keeps the fastest body (including the speed it gains
+    /// falling to the ground) from moving more than half the smallest body
+    /// per step; iterations grow with the heaviest-to-lightest mass ratio.
+    /// Call it after adding bodies.
+    ///
+    /// Returns:
+    ///     Dict with the chosen fixed_dt, solver_iterations and ccd_speed
+    fn auto_tune<'py>(&mut self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.inner.auto_tune();
+        let physics = &self.inner.physics;
+        let dict = PyDict::new(py);
+        dict.set_item("fixed_dt", physics.fixed_dt)?;
+        dict.set_item("solver_iterations", physics.solver_iterations)?;
+        dict.set_item("ccd_speed", physics.ccd_speed)?;
+        Ok(dict)
+    }
+
+    /// Set the gravity vector (default (0, -9.81, 0))
+    fn set_gravity(&mut self, x: f32, y: f32, z: f32) {
+        self.inner.set_gravity([x, y, z]);
+    }
+
+    /// Set the wind blowing over the scene
+    ///
+    /// Only bodies given drag with set_aero feel the wind.
+    ///
+    /// Args:
+    ///     velocity: Wind velocity at the reference height (m/s)
+    ///     air_density: Density of the air (kg/m³)
+    ///     shear_exponent: Growth of the wind with height above the ground
+    ///         (0 = uniform, about 0.14 over open ground)
+    ///     reference_height: Height above the ground where the wind blows
+    ///         at `velocity` (m)
+    ///     gust_strength: Gust speed as a fraction of the wind speed
+    ///     gust_wavelength: Distance between gusts (m)
+    #[pyo3(signature = (
+        velocity,
+        air_density=1.225,
+        shear_exponent=0.0,
+        reference_height=10.0,
+        gust_strength=0.0,
+        gust_wavelength=20.0
+    ))]
+    fn set_wind(
+        &mut self,
+        velocity: [f32; 3],
+        air_density: f32,
+        shear_exponent: f32,
+        reference_height: f32,
+        gust_strength: f32,
+        gust_wavelength: f32,
+    ) -> PyResult<()> {
+        if air_density < 0.0 || reference_height <= 0.0 || gust_wavelength <= 0.0 {
+            return Err(PyValueError::new_err(
+                "air_density must be non-negative, reference_height and gust_wavelength positive",
+            ));
+        }
+        self.inner.set_wind(WindField {
+            velocity,
+            air_density,
+            shear_exponent,
+            reference_height,
+            gust_strength,
+            gust_wavelength,
+        });
+        Ok(())
+    }
+
+    /// Give an added body aerodynamic drag and lift against the wind
+    ///
+    /// Args:
+    ///     index: Body index (in the order bodies were added)
+    ///     drag: Drag coefficient (default: 1.05 for cubes, 0.47 for spheres)
+    ///     lift: Lift coefficient; about 1 makes flat boxes glide and
+    ///         flutter, about 0.5 gives spinning spheres a Magnus curve
+    ///     area: Reference area for drag (m²; default: the area facing
+    ///         the flow)
+    ///     enabled: False removes the body's air forces
+    #[pyo3(signature = (index, drag=None, lift=0.0, area=None, enabled=true))]
+    fn set_aero(&mut self, index: usize, drag: Option<f32>, lift: f32, area: Option<f32>, enabled: bool) -> PyResult<()> {
+        let Some(body) = self.inner.bodies.get(index) else {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        };
+        let mut coefficients = AeroCoefficients::for_shape(body.shape);
+        if let Some(drag) = drag {
+            coefficients.drag = drag;
+        }
+        coefficients.lift = lift;
+        coefficients.area = area;
+        self.inner.set_aero(index, enabled.then_some(coefficients));
+        Ok(())
+    }
+
+    /// Let temperature spread between touching bodies
+    ///
+    /// Each touching pair approaches a common temperature at `conductance`
+    /// per second; static bodies keep theirs. Render the result with
+    /// Simulator.render_temperature.
+    ///
+    /// Args:
+    ///     conductance: Exchange rate (1/s); 0 turns heat exchange off
+    ///     ground_temperature: Fixed temperature of the ground (default:
+    ///         the ground exchanges no heat)
+    #[pyo3(signature = (conductance=1.0, ground_temperature=None))]
+    fn set_heat(&mut self, conductance: f32, ground_temperature: Option<f32>) -> PyResult<()> {
+        if conductance < 0.0 {
+            return Err(PyValueError::new_err("conductance must be non-negative"));
+        }
+        self.inner.set_heat(HeatSettings { conductance, ground_temperature });
+        Ok(())
+    }
+
+    /// Set the mass of an added body from a density and the volume of its
+    /// shape and child colliders (works for every shape)
+    ///
+    /// Args:
+    ///     index: Body index
+    ///     density: Density in kg/m³, or a material name from `densities()`
+    fn set_density(&mut self, index: usize, density: &Bound<'_, PyAny>) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.set_density(index, parse_density(density)?);
+        Ok(())
+    }
+
+    /// Give an added body a density the physics engine computes its mass
+    /// and inertia from, or go back to its mass
+    ///
+    /// Unlike `set_density`, the mass is not fixed when this is called: it
+    /// follows the body's size and child colliders when the simulator is
+    /// built.
+    ///
+    /// Args:
+    ///     index: Body index
+    ///     density: Density in kg/m³ or a material name from `densities()`;
+    ///         None uses the body's mass again
+    #[pyo3(signature = (index, density=None))]
+    fn set_mass_mode(&mut self, index: usize, density: Option<&Bound<'_, PyAny>>) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        let mode = match density {
+            Some(density) => MassMode::Density(parse_density(density)?),
+            None => MassMode::Mass,
+        };
+        self.inner.set_mass_mode(index, mode);
+        Ok(())
+    }
+
+    /// Make an added body of a preset material: sets its mass from the
+    /// preset density and its volume, friction, restitution, color,
+    /// roughness and metallic
+    ///
+    /// Args:
+    ///     index: Body index
+    ///     preset: Preset name from `material_presets()` (wood, steel,
+    ///         rubber, ice or concrete)
+    fn set_preset(&mut self, index: usize, preset: &str) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.set_preset(index, parse_preset(preset)?);
+        Ok(())
+    }
+
+    /// Attach a physical material to an added body: while set, its mass
+    /// follows from the material density and the body's volume, and the
+    /// material's friction and restitution replace the body's own
+    ///
+    /// Unlike `set_preset`, the material stays attached, so later size
+    /// changes keep the density. Call with only the index to detach it.
+    ///
+    /// Args:
+    ///     index: Body index
+    ///     material: Preset name from `material_presets()` (ice, rubber,
+    ///         wood, steel or concrete) to start from
+    ///     density: Density in kg/m³ or a name from `densities()`
+    ///         (default: the preset's; required without a preset)
+    ///     friction: Friction coefficient (default: the preset's, else the
+    ///         body's own)
+    ///     restitution: Bounciness (default: the preset's, else the body's own)
+    #[pyo3(signature = (index, material=None, density=None, friction=None, restitution=None))]
+    fn set_body_material(
+        &mut self,
+        index: usize,
+        material: Option<&str>,
+        density: Option<&Bound<'_, PyAny>>,
+        friction: Option<f32>,
+        restitution: Option<f32>,
+    ) -> PyResult<()> {
+        let Some(body) = self.inner.bodies.get(index) else {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        };
+        check_material(friction, restitution)?;
+        let density = density.map(parse_density).transpose()?;
+        let base = match (material, density) {
+            (None, None) if friction.is_none() && restitution.is_none() => {
+                self.inner.set_body_material(index, None);
+                return Ok(());
+            }
+            (Some(name), _) => parse_preset(name)?.material(),
+            (None, Some(density)) => Material::new(density, body.friction, body.restitution),
+            (None, None) => return Err(PyValueError::new_err("a custom material needs a density")),
+        };
+        self.inner.set_body_material(index, Some(Material {
+            density: density.unwrap_or(base.density),
+            friction: friction.unwrap_or(base.friction),
+            restitution: restitution.unwrap_or(base.restitution),
+        }));
+        Ok(())
+    }
+
+    /// Set how the renderer shades an added body
+    ///
+    /// Args:
+    ///     index: Body index
+    ///     roughness: 0 (sharp, bright highlights) to 1 (matte); default 0.5
+    ///     metallic: 0 (plastic, wood, stone) to 1 (metal, highlights tinted
+    ///         by the body color); default 0
+    fn set_surface(&mut self, index: usize, roughness: f32, metallic: f32) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        if !(0.0..=1.0).contains(&roughness) || !(0.0..=1.0).contains(&metallic) {
+            return Err(PyValueError::new_err("roughness and metallic must be between 0 and 1"));
+        }
+        self.inner.set_surface(index, roughness, metallic);
+        Ok(())
+    }
+
+    /// Set the initial temperature of an added body
+    fn set_temperature(&mut self, index: usize, temperature: f32) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.set_temperature(index, temperature);
+        Ok(())
+    }
+
+    /// Set the initial angular velocity of an added body
+    ///
+    /// Args:
+    ///     index: Body index
+    ///     angular_velocity: Spin in rad/s about the world X, Y and Z axes;
+    ///         locked rotation axes and path followers start without spin
+    fn set_angular_velocity(&mut self, index: usize, angular_velocity: [f32; 3]) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.set_angular_velocity(index, angular_velocity);
+        Ok(())
+    }
+
+    /// Set the friction and restitution of the ground plane
+    #[pyo3(signature = (friction=0.5, restitution=0.3))]
+    fn set_ground_material(&mut self, friction: f32, restitution: f32) {
+        self.inner.set_ground_material(friction, restitution);
+    }
+
+    /// Fill a box with liquid (SPH fluid particles)
+    ///
+    /// Particles sit on a lattice at the fluid spacing (see set_fluid).
+    /// Bodies and the ground push the fluid around, and the fluid pushes
+    /// back on bodies, so they float or sink by their density.
+    ///
+    /// Args:
+    ///     min: Lower corner of the box
+    ///     max: Upper corner of the box
+    ///     velocity: Initial velocity of the liquid
+    ///
+    /// Returns:
+    ///     Number of particles added
+    #[pyo3(signature = (min, max, velocity=[0.0, 0.0, 0.0]))]
+    fn add_fluid_block(&mut self, min: [f32; 3], max: [f32; 3], velocity: [f32; 3]) -> usize {
+        self.inner.add_fluid_block(min, max, velocity)
+    }
+
+    /// Set the fluid's material parameters (call before add_fluid_block)
+    ///
+    /// Args:
+    ///     spacing: Particle spacing at rest (m)
+    ///     rest_density: Density of the liquid (kg/m³)
+    ///     sound_speed: Numerical speed of sound (m/s); higher is less
+    ///         compressible but slower to simulate
+    ///     viscosity: Kinematic viscosity (m²/s)
+    ///     boundary_friction: Fraction of sliding velocity lost on contact
+    ///     two_way: Whether the fluid pushes back on bodies (buoyancy and
+    ///         drag); if False bodies move as if the fluid were not there
+    #[pyo3(signature = (spacing=0.05, rest_density=1000.0, sound_speed=20.0, viscosity=0.01, boundary_friction=0.1, two_way=true))]
+    fn set_fluid(
+        &mut self,
+        spacing: f32,
+        rest_density: f32,
+        sound_speed: f32,
+        viscosity: f32,
+        boundary_friction: f32,
+        two_way: bool,
+    ) -> PyResult<()> {
+        if spacing <= 0.0 || rest_density <= 0.0 || sound_speed <= 0.0 {
+            return Err(PyValueError::new_err("spacing, rest_density and sound_speed must be positive"));
+        }
+        self.inner.set_fluid_settings(FluidSettings { spacing, rest_density, sound_speed, viscosity, boundary_friction, two_way });
+        Ok(())
+    }
+
+    /// Number of fluid particles in the scene
+    fn fluid_count(&self) -> usize {
+        self.inner.fluid.len()
+    }
+
+    /// Set the friction and restitution of an added body
+    ///
+    /// Args:
+    ///     index: Body index (in the order bodies were added)
+    ///     friction: Friction coefficient (None = unchanged)
+    ///     restitution: Bounciness, 0 = none (None = unchanged)
+    #[pyo3(signature = (index, friction=None, restitution=None))]
+    fn set_material(&mut self, index: usize, friction: Option<f32>, restitution: Option<f32>) -> PyResult<()> {
+        let body = self.inner.bodies.get(index)
+            .ok_or_else(|| PyIndexError::new_err(format!("body index {} out of range", index)))?;
+        check_material(friction, restitution)?;
+        let (friction, restitution) = (friction.unwrap_or(body.friction), restitution.unwrap_or(body.restitution));
+        self.inner.set_material(index, friction, restitution);
+        Ok(())
+    }
+
+    /// Override the combine rules of an added body (None = scene default)
+    #[pyo3(signature = (index, friction=None, restitution=None))]
+    fn set_body_combine_rules(
+        &mut self,
+        index: usize,
+        friction: Option<&str>,
+        restitution: Option<&str>,
+    ) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        let friction = friction.map(parse_combine_rule).transpose()?;
+        let restitution = restitution.map(parse_combine_rule).transpose()?;
+        self.inner.set_body_combine_rules(index, friction, restitution);
+        Ok(())
+    }
+
+    /// Lock movement of an added body along world axes
+    ///
+    /// Args:
+    ///     index: Body index (in the order bodies were added)
+    ///     axes: Locked axes, e.g. "z" to keep the body in its xy plane
+    ///         ("" unlocks all)
+    fn lock_translations(&mut self, index: usize, axes: &str) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.lock_translations(index, parse_axes(axes)?);
+        Ok(())
+    }
+
+    /// Lock rotation of an added body about world axes
+    ///
+    /// Args:
+    ///     index: Body index (in the order bodies were added)
+    ///     axes: Locked axes, e.g. "xyz" for a body that never tumbles or
+    ///         "xy" to only spin about z ("" unlocks all)
+    fn lock_rotations(&mut self, index: usize, axes: &str) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.lock_rotations(index, parse_axes(axes)?);
+        Ok(())
+    }
+
+    /// Give an added body direction-dependent (skate/sled) friction
+    ///
+    /// Args:
+    ///     index: Body index (in the order bodies were added)
+    ///     axis: Skate axis in the body's local frame
+    ///     along: Friction coefficient when sliding along the axis
+    ///     across: Friction coefficient when sliding across the axis
+    #[pyo3(signature = (index, axis, along, across))]
+    fn set_anisotropic_friction(
+        &mut self,
+        index: usize,
+        axis: [f32; 3],
+        along: f32,
+        across: f32,
+    ) -> PyResult<()> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.set_anisotropic_friction(index, axis, along, across);
+        Ok(())
+    }
+
+    /// Add a body resting exactly on top of an added body
+    ///
+    /// Args:
+    ///     below: Index of the body to stack on
+    ///     shape: "cube" or "sphere"
+    ///     size: Half extent (cube) or radius (sphere)
+    ///     mass: Mass in kg
+    ///     offset: Horizontal (x, z) offset from the center of `below`
+    ///     color: RGB color (default: the shape's default color)
+    ///
+    /// Returns:
+    ///     Index of the new body
+    ///
+    /// The new body is raised further if it would overlap another body, so
+    /// stacks of mixed sizes start without interpenetration.
+    #[pyo3(signature = (below, shape="cube", size=0.5, mass=1.0, offset=(0.0, 0.0), color=None))]
+    fn stack_on(
+        &mut self,
+        below: usize,
+        shape: &str,
+        size: f32,
+        mass: f32,
+        offset: (f32, f32),
+        color: Option<[f32; 3]>,
+    ) -> PyResult<usize> {
+        if below >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", below)));
+        }
+        let position = [offset.0, 0.0, offset.1];
+        let body = match shape {
+            "cube" => RigidBodyConfig {
+                position,
+                half_extents: [size; 3],
+                mass,
+                color: color.unwrap_or(RigidBodyConfig::default().color),
+                ..Default::default()
+            },
+            "sphere" => RigidBodyConfig {
+                position,
+                radius: size,
+                shape: ShapeType::Sphere,
+                mass,
+                restitution: 0.6,
+                color: color.unwrap_or([0.35, 0.5, 0.75]),
+                ..Default::default()
+            },
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown shape '{}' (expected cube or sphere)",
+                    other
+                )))
+            }
+        };
+        self.inner.stack_on(below, body);
+        Ok(self.inner.bodies.len() - 1)
+    }
+
+    /// Launch velocity of magnitude `speed` from `start` through `target`
+    ///
+    /// Closed-form ballistic solution for an ideal parabola.
+    ///
+    /// Args:
+    ///     start: Launch point
+    ///     target: Point to hit
+    ///     speed: Launch speed (m/s)
+    ///     gravity: Gravity vector (default: the scene's gravity)
+    ///     high_arc: Take the lobbed instead of the flat trajectory
+    ///
+    /// Returns:
+    ///     Velocity (vx, vy, vz), or None if the target is out of reach
+    #[pyo3(signature = (start, target, speed, gravity=None, high_arc=false))]
+    fn aim_projectile(
+        &self,
+        start: [f32; 3],
+        target: [f32; 3],
+        speed: f32,
+        gravity: Option<[f32; 3]>,
+        high_arc: bool,
+    ) -> Option<[f32; 3]> {
+        let gravity = gravity.unwrap_or(self.inner.physics.gravity);
+        ballistics::launch_velocity(start, target, speed, gravity, high_arc)
+    }
+
+    /// Set an added body's initial velocity so its center passes through `target`
+    ///
+    /// The closed-form aim is refined by simulating the body alone, so the
+    /// hit is exact for the given step size.
+    ///
+    /// Args:
+    ///     index: Body index (in the order bodies were added)
+    ///     target: Point to hit
+    ///     speed: Launch speed (m/s)
+    ///     dt: Step size the scene will be simulated with (default 1/60)
+    ///
+    /// Returns:
+    ///     Velocity (vx, vy, vz), or None (body unchanged) if out of reach
+    #[pyo3(signature = (index, target, speed, dt=1.0 / 60.0))]
+    fn aim_body(&mut self, index: usize, target: [f32; 3], speed: f32, dt: f32) -> PyResult<Option<[f32; 3]>> {
+        if index >= self.inner.bodies.len() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        if dt <= 0.0 {
+            return Err(PyValueError::new_err("dt must be positive"));
+        }
+        Ok(self.inner.aim_body(index, target, speed, dt))
+    }
+
+    /// Get the number of bodies in the scene
+    fn body_count(&self) -> usize {
+        self.inner.bodies.len()
+    }
+
+    /// Get counts of (cubes, spheres)
+    fn shape_counts(&self) -> (usize, usize) {
+        self.inner.shape_counts()
+    }
+
+    /// Statistics of the scene and suspicious parameters, checked before
+    /// simulating
+    ///
+    /// Args:
+    ///     print_summary: Also print the report in readable form (to sys.stdout)
+    ///
+    /// Returns:
+    ///     Dict with "body_count", "static_count", "shapes" (count per shape
+    ///     name), "joint_count", "total_mass" (dynamic bodies, kg), "bounds"
+    ///     ((min, max) corners, None if empty), "min_spacing" ((distance,
+    ///     a, b) of the closest body centers, None for fewer than two
+    ///     bodies), "issues" (list of dicts with "kind", "bodies" and
+    ///     "message"), "valid" (no issues) and "summary" (the readable form)
+    #[pyo3(signature = (print_summary=false))]
+    fn report<'py>(&self, py: Python<'py>, print_summary: bool) -> PyResult<Bound<'py, PyDict>> {
+        let report = self.inner.report();
+        let summary = report.to_string();
+        if print_summary {
+            // Through Python's print, so sys.stdout redirection and notebooks see it
+            py.import("builtins")?.getattr("print")?.call1((summary.as_str(),))?;
+        }
+
+        let dict = PyDict::new(py);
+        dict.set_item("body_count", report.body_count)?;
+        dict.set_item("static_count", report.static_count)?;
+        let shapes = PyDict::new(py);
+        for (shape, count) in ShapeType::ALL.iter().zip(report.shape_counts) {
+            shapes.set_item(shape.name(), count)?;
+        }
+        dict.set_item("shapes", shapes)?;
+        dict.set_item("joint_count", report.joint_count)?;
+        dict.set_item("total_mass", report.total_mass)?;
+        dict.set_item("bounds", report.bounds)?;
+        dict.set_item("min_spacing", report.min_spacing)?;
+        let issues = report.issues.iter()
+            .map(|issue| issue_dict(py, issue))
+            .collect::<PyResult<Vec<_>>>()?;
+        dict.set_item("issues", issues)?;
+        dict.set_item("valid", report.is_valid())?;
+        dict.set_item("summary", summary)?;
+        Ok(dict)
+    }
+
+    /// Problems that would make the scene misbehave once simulated:
+    /// interpenetrating bodies, bodies below the ground, zero or negative
+    /// masses, NaN positions and the other issues of `report`
+    ///
+    /// Returns:
+    ///     List of dicts with "kind", "bodies", "message" and "severe" (likely
+    ///     to make the simulation explode); empty for a valid scene
+    fn validate<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.inner.validate().iter()
+            .map(|issue| {
+                let dict = issue_dict(py, issue)?;
+                dict.set_item("severe", issue.is_severe())?;
+                Ok(dict)
+            })
+            .collect()
+    }
+}
+
+impl PyScene {
+    /// Recolor the bodies from index `first` on (if a color is given) and
+    /// return their indices
+    fn color_from(&mut self, first: usize, color: Option<[f32; 3]>) -> Vec<usize> {
+        if let Some(color) = color {
+            for body in &mut self.inner.bodies[first..] {
+                body.color = color;
+            }
+        }
+        (first..self.inner.bodies.len()).collect()
+    }
+
+    /// Override the friction and restitution of the most recently added body
+    fn material_last(&mut self, friction: Option<f32>, restitution: Option<f32>) {
+        if let Some(friction) = friction {
+            self.inner.with_friction(friction);
+        }
+        if let Some(restitution) = restitution {
+            self.inner.with_restitution(restitution);
+        }
+    }
+
+    /// Give the most recently added body an initial angular velocity
+    fn spin_last(&mut self, angular_velocity: Option<[f32; 3]>) {
+        if let Some(angular_velocity) = angular_velocity {
+            let last = self.inner.bodies.len() - 1;
+            self.inner.set_angular_velocity(last, angular_velocity);
+        }
+    }
+}
+
+/// Positions and rotations of all bodies at one instant
+type Poses = (Vec<[f32; 3]>, Vec<[f32; 4]>);
+
+/// Projected pixels, depths and in-frustum flags
+type Projection<'py> = (Bound<'py, PyArray2<f32>>, Bound<'py, PyArray1<f32>>, Bound<'py, PyArray1<bool>>);
+
+/// Ray origin and unit direction
+type Ray = ((f32, f32, f32), (f32, f32, f32));
+
+/// Ray hit as a Python dict
+fn ray_hit_dict<'py>(py: Python<'py>, hit: &RayHit) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("body", hit.body)?;
+    dict.set_item("distance", hit.distance)?;
+    dict.set_item("point", hit.point)?;
+    dict.set_item("normal", hit.normal)?;
+    Ok(dict)
+}
+
+/// Time of impact as a Python dict
+fn toi_dict<'py>(py: Python<'py>, hit: &TimeOfImpact) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("time", hit.time)?;
+    dict.set_item("body", hit.body)?;
+    dict.set_item("point", hit.point)?;
+    dict.set_item("normal", hit.normal)?;
+    Ok(dict)
+}
+
+/// Create an empty HDF5 dataset that grows along its first axis
+///
+/// `row` is the shape of one entry; chunks hold `chunk_rows` entries.
+fn create_growing_dataset<'py>(
+    file: &Bound<'py, PyAny>,
+    name: &str,
+    row: &[usize],
+    chunk_rows: usize,
+    dtype: &str,
+    options: &Bound<'py, PyDict>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = file.py();
+    let shape: Vec<usize> = std::iter::once(0).chain(row.iter().copied()).collect();
+    let maxshape: Vec<Option<usize>> = std::iter::once(None).chain(row.iter().map(|&d| Some(d))).collect();
+    let chunks: Vec<usize> = std::iter::once(chunk_rows).chain(row.iter().copied()).collect();
+    let kwargs = options.copy()?;
+    kwargs.set_item("shape", PyTuple::new(py, shape)?)?;
+    kwargs.set_item("maxshape", PyTuple::new(py, maxshape)?)?;
+    kwargs.set_item("chunks", PyTuple::new(py, chunks)?)?;
+    kwargs.set_item("dtype", dtype)?;
+    file.call_method("create_dataset", (name,), Some(&kwargs))
+}
+
+/// Append entries of shape `row` to a dataset made by [`create_growing_dataset`]
+fn append_rows<T: numpy::Element>(dataset: &Bound<'_, PyAny>, values: Vec<T>, row: &[usize]) -> PyResult<()> {
+    let py = dataset.py();
+    let count = values.len() / row.iter().product::<usize>();
+    if count == 0 {
+        return Ok(());
+    }
+    let start = dataset.len()?;
+    let shape: Vec<usize> = std::iter::once(count).chain(row.iter().copied()).collect();
+    let values = values.into_pyarray(py).reshape(shape)?;
+    dataset.call_method1("resize", (start + count, 0))?;
+    dataset.set_item(PySlice::new(py, start as isize, (start + count) as isize, 1), values)
+}
+
+/// Rows of the Imath matrix placing a body at a pose
+///
+/// Imath multiplies row vectors, so the rotation is transposed and the
+/// translation sits in the last row.
+fn imath_rows(position: [f32; 3], rotation: [f32; 4]) -> [[f64; 4]; 4] {
+    let [x, y, z, w] = rotation.map(f64::from);
+    let [px, py, pz] = position.map(f64::from);
+    [
+        [1.0 - 2.0 * (y * y + z * z), 2.0 * (x * y + z * w), 2.0 * (x * z - y * w), 0.0],
+        [2.0 * (x * y - z * w), 1.0 - 2.0 * (x * x + z * z), 2.0 * (y * z + x * w), 0.0],
+        [2.0 * (x * z + y * w), 2.0 * (y * z - x * w), 1.0 - 2.0 * (x * x + y * y), 0.0],
+        [px, py, pz, 1.0],
+    ]
+}
+
+/// Recorded entries waiting to be appended to an HDF5 file
+#[derive(Default)]
+struct Hdf5Chunk {
+    steps: Vec<u32>,
+    times: Vec<f32>,
+    positions: Vec<f32>,
+    rotations: Vec<f32>,
+    linear_velocities: Vec<f32>,
+    angular_velocities: Vec<f32>,
+    frames: Vec<u8>,
+    /// Recorded frame index and impact
+    events: Vec<(u32, ImpactEvent)>,
+}
+
+impl Hdf5Chunk {
+    /// Append the buffered entries to `file` and clear them
+    fn flush(&mut self, file: &Bound<'_, PyAny>, bodies: usize, frame: Option<(u32, u32)>) -> PyResult<()> {
+        let take = |v: &mut Vec<f32>| std::mem::take(v);
+        append_rows(&file.get_item("step")?, std::mem::take(&mut self.steps), &[])?;
+        append_rows(&file.get_item("time")?, take(&mut self.times), &[])?;
+        append_rows(&file.get_item("poses/positions")?, take(&mut self.positions), &[bodies, 3])?;
+        append_rows(&file.get_item("poses/rotations")?, take(&mut self.rotations), &[bodies, 4])?;
+        append_rows(&file.get_item("velocities/linear")?, take(&mut self.linear_velocities), &[bodies, 3])?;
+        append_rows(&file.get_item("velocities/angular")?, take(&mut self.angular_velocities), &[bodies, 3])?;
+        if let Some((width, height)) = frame {
+            append_rows(&file.get_item("frames")?, std::mem::take(&mut self.frames), &[height as usize, width as usize, 4])?;
+        }
+
+        let events = std::mem::take(&mut self.events);
+        let body = |b: Option<usize>| b.map_or(-1, |b| b as i32);
+        append_rows(&file.get_item("events/frame")?, events.iter().map(|(f, _)| *f).collect(), &[])?;
+        append_rows(&file.get_item("events/time")?, events.iter().map(|(_, e)| e.time).collect(), &[])?;
+        append_rows(&file.get_item("events/body_a")?, events.iter().map(|(_, e)| body(e.body_a)).collect(), &[])?;
+        append_rows(&file.get_item("events/body_b")?, events.iter().map(|(_, e)| body(e.body_b)).collect(), &[])?;
+        append_rows(&file.get_item("events/point")?, events.iter().flat_map(|(_, e)| e.point).collect(), &[3])?;
+        append_rows(&file.get_item("events/normal")?, events.iter().flat_map(|(_, e)| e.normal).collect(), &[3])?;
+        append_rows(&file.get_item("events/relative_speed")?, events.iter().map(|(_, e)| e.relative_speed).collect(), &[])?;
+        append_rows(&file.get_item("events/impulse")?, events.iter().map(|(_, e)| e.impulse).collect(), &[])
+    }
+}
+
+/// Stacked frames (T, H, W, 4) and poses (T, N, 7) returned by a rollout
+type RolloutArrays<'py> = (Bound<'py, PyArray4<u8>>, Bound<'py, PyArray3<f32>>);
+
+/// Impact event as returned to Python
+type ImpactTuple = (f32, Option<usize>, Option<usize>, f32, f32, String, String);
+
+/// Python wrapper for Simulator with optional rendering
+#[pyclass(name = "Simulator")]
+pub struct PySimulator {
+    inner: CoreSimulator,
+    renderer: Option<Renderer>,
+    half_extent: f32,
+    /// Stamp ground decals for strong impacts on every step
+    impact_decals: bool,
+    /// Rolling-shutter readout used for rendered frames, if enabled
+    rolling_shutter: Option<RollingShutter>,
+    /// Body poses at the start of the last `step` call (rolling-shutter frame start)
+    frame_start: Option<Poses>,
+    /// External viewer receiving a frame after every step call
+    stream: Option<FrameStreamer>,
+    /// Shared-memory file rewritten after every physics step
+    shared_state: Option<SharedStatePublisher>,
+}
+
+impl PySimulator {
+    /// Remember the current poses as the start of the next frame interval
+    fn mark_frame_start(&mut self) {
+        if self.rolling_shutter.is_some() {
+            self.frame_start = Some((self.inner.positions().to_vec(), self.inner.rotations().to_vec()));
+        }
+    }
+
+    /// Send the current state to the connected viewer, disconnecting it if
+    /// the connection broke
+    fn publish_stream(&mut self) {
+        if let Some(stream) = self.stream.as_mut() {
+            if let Err(e) = stream.publish(&self.inner) {
+                log::warn!("frame stream closed: {}", e);
+                self.stream = None;
+            }
+        }
+    }
+
+    /// Write the current state to the shared-memory file, closing it if the
+    /// bodies no longer fit
+    fn publish_shared_state(&mut self) {
+        if let Some(shared) = self.shared_state.as_mut() {
+            if let Err(e) = shared.publish(&self.inner) {
+                log::warn!("shared state closed: {}", e);
+                self.shared_state = None;
+            }
+        }
+    }
+
+    /// Pick up edited shader files when watching a shader directory
+    fn poll_shaders(&mut self) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.poll_shaders();
+        }
+    }
+
+    /// Frame metadata as a nested dict of plain Python values
+    fn metadata_dict<'py>(&self, py: Python<'py>, renderer: &Renderer) -> PyResult<Bound<'py, PyDict>> {
+        let FrameMetadata { time, camera, bodies, mut settings } = renderer.frame_metadata(&self.inner);
+        if let Some(shutter) = &self.rolling_shutter {
+            settings.push(("rolling_shutter_bands", shutter.bands as f32));
+            settings.push(("rolling_shutter_readout", shutter.readout));
+        }
+
+        let cam = PyDict::new(py);
+        cam.set_item("eye", camera.eye)?;
+        cam.set_item("target", camera.target)?;
+        cam.set_item("up", camera.up)?;
+        cam.set_item("fov_y", camera.fov_y)?;
+        cam.set_item("ortho_height", camera.ortho_height)?;
+        cam.set_item("near", camera.near)?;
+        cam.set_item("far", camera.far)?;
+        cam.set_item("width", camera.width)?;
+        cam.set_item("height", camera.height)?;
+        cam.set_item("view", camera.view)?;
+        cam.set_item("projection", camera.projection)?;
+        cam.set_item("intrinsics", camera.intrinsics)?;
+
+        let body = PyDict::new(py);
+        body.set_item("index", bodies.iter().map(|b| b.index).collect::<Vec<_>>())?;
+        body.set_item("shape", bodies.iter().map(|b| b.shape).collect::<Vec<_>>())?;
+        body.set_item("position", bodies.iter().map(|b| b.position).collect::<Vec<_>>())?;
+        body.set_item("rotation", bodies.iter().map(|b| b.rotation).collect::<Vec<_>>())?;
+        body.set_item("size", bodies.iter().map(|b| b.size).collect::<Vec<_>>())?;
+        body.set_item("id_color", bodies.iter().map(|b| b.id_color).collect::<Vec<_>>())?;
+
+        let render = PyDict::new(py);
+        for (name, value) in settings {
+            render.set_item(name, value)?;
+        }
+
+        let dict = PyDict::new(py);
+        dict.set_item("time", time)?;
+        dict.set_item("camera", cam)?;
+        dict.set_item("bodies", body)?;
+        dict.set_item("settings", render)?;
+        Ok(dict)
+    }
+
+    /// Write the frame metadata next to an image as `.json` or `.npz`
+    fn write_sidecar(&self, py: Python<'_>, renderer: &Renderer, image_path: &str, format: &str) -> PyResult<()> {
+        let metadata = self.metadata_dict(py, renderer)?;
+        let manifest = renderer.config_manifest().to_json();
+        let path = std::path::Path::new(image_path);
+        match format {
+            "json" => {
+                metadata.set_item("manifest", py.import("json")?.call_method1("loads", (manifest,))?)?;
+                let text: String = py.import("json")?
+                    .call_method1("dumps", (metadata,))?
+                    .extract()?;
+                std::fs::write(path.with_extension("json"), text)
+                    .map_err(|e| PyIOError::new_err(format!("Failed to write sidecar: {}", e)))
+            }
+            "npz" => {
+                // Flatten to "group.key" arrays; npz has no nesting, so the
+                // manifest stays a JSON string
+                let flat = PyDict::new(py);
+                flat.set_item("manifest", manifest)?;
+                for (group, value) in metadata.iter() {
+                    match value.downcast::<PyDict>() {
+                        Ok(inner) => {
+                            for (key, item) in inner.iter() {
+                                flat.set_item(format!("{}.{}", group, key), item)?;
+                            }
+                        }
+                        Err(_) => flat.set_item(group, value)?,
+                    }
+                }
+                let npz_path = path.with_extension("npz");
+                py.import("numpy")?.call_method("savez", (npz_path,), Some(&flat))?;
+                Ok(())
+            }
+            _ => Err(PyValueError::new_err(format!(
+                "unknown sidecar format '{}' (expected 'json' or 'npz')",
+                format
+            ))),
+        }
+    }
+
+    /// Render the current state, honouring the rolling-shutter mode
+    fn render_pixels(&self, renderer: &Renderer) -> Vec<u8> {
+        match (&self.rolling_shutter, &self.frame_start) {
+            (Some(shutter), Some((positions, rotations))) => {
+                renderer.render_rolling_shutter(&self.inner, positions, rotations, shutter)
+            }
+            _ => renderer.render_simulator(&self.inner),
+        }
+    }
+}
+
+#[pymethods]
+impl PySimulator {
+    /// Create a new simulator
+    ///
+    /// Args:
+    ///     scene: The scene to simulate
+    ///     width: Render width (default 1920)
+    ///     height: Render height (default 1080)
+    ///     backend: Physics engine ("rapier", or "pbd" for large granular scenes)
+    #[new]
+    #[pyo3(signature = (scene, width=1920, height=1080, backend="rapier"))]
+    fn new(scene: &PyScene, width: u32, height: u32, backend: &str) -> PyResult<Self> {
+        let backend = PhysicsBackendKind::from_name(backend)
+            .ok_or_else(|| PyValueError::new_err(format!("unknown physics backend '{}' (expected rapier or pbd)", backend)))?;
+
+        // Get half_extent from first body or default
+        let half_extent = scene.inner.bodies.first()
+            .map(|b| b.half_extents[0])
+            .unwrap_or(0.5);
+
+        let max_instances = scene.inner.bodies.len().max(1000) as u32;
+
+        // Get ground parameters from scene
         let ground_y = scene.inner.ground_y.unwrap_or(0.0);
         let ground_size = scene.inner.ground_size.max(50.0);
 
-        // Create renderer with ground parameters
-        let renderer = Renderer::new(width, height, max_instances, half_extent, ground_y, ground_size)
-            .map_err(|e| PyRuntimeError::new_err(format!("GPU initialization failed: {}", e)))?;
+        // Create renderer with ground parameters
+        let mut renderer = Renderer::new(width, height, max_instances, half_extent, ground_y, ground_size)
+            .map_err(|e| PyRuntimeError::new_err(format!("GPU initialization failed: {}", e)))?;
+        if scene.inner.physics.planar {
+            if let Some((min, max)) = scene.inner.xy_bounds() {
+                renderer.frame_plane(min, max);
+            }
+        }
+
+        Ok(Self {
+            inner: CoreSimulator::with_backend(&scene.inner, backend.create()),
+            renderer: Some(renderer),
+            half_extent,
+            impact_decals: false,
+            rolling_shutter: None,
+            frame_start: None,
+            stream: None,
+            shared_state: None,
+        })
+    }
+
+    /// Apply an edited scene without rebuilding the whole world
+    ///
+    /// Bodies are matched by index: changed bodies restart from their new
+    /// settings, extra bodies are removed from the end and new ones added,
+    /// and all other bodies keep moving from where they are. Changes to the
+    /// ground, gravity and other physics settings, joints or vehicles
+    /// rebuild everything and reset the time to 0.
+    ///
+    /// Returns:
+    ///     Dict with "added", "removed" and "changed" body counts and
+    ///     "rebuilt" (whether the whole world was rebuilt)
+    fn apply_scene_update<'py>(&mut self, py: Python<'py>, scene: &PyScene) -> PyResult<Bound<'py, PyDict>> {
+        let update = self.inner.apply_scene_update(&scene.inner);
+        self.frame_start = None;
+        let dict = PyDict::new(py);
+        dict.set_item("added", update.added)?;
+        dict.set_item("removed", update.removed)?;
+        dict.set_item("changed", update.changed)?;
+        dict.set_item("rebuilt", update.rebuilt)?;
+        Ok(dict)
+    }
+
+    /// Step the physics simulation
+    ///
+    /// Args:
+    ///     dt: Time step in seconds
+    ///     substeps: Number of substeps (default 1). Higher values improve
+    ///               collision accuracy for fast-moving objects.
+    #[pyo3(signature = (dt, substeps=1))]
+    fn step(&mut self, dt: f32, substeps: u32) {
+        self.poll_shaders();
+        self.mark_frame_start();
+        let sub_dt = dt / substeps as f32;
+        for _ in 0..substeps {
+            self.inner.step(sub_dt);
+            if let (true, Some(renderer)) = (self.impact_decals, self.renderer.as_mut()) {
+                renderer.stamp_impacts(self.inner.last_impacts());
+            }
+            if let Some(stream) = self.stream.as_mut() {
+                stream.record_events(self.inner.last_impacts());
+            }
+            self.publish_shared_state();
+        }
+        self.publish_stream();
+    }
+
+    /// Advance by dt seconds of real (wall-clock or video) time
+    ///
+    /// Honors pause and time scale. Physics always steps at the fixed step
+    /// size (set_fixed_dt, default 1/240 s); slow motion takes fewer steps
+    /// per call and leftover time carries over.
+    ///
+    /// Returns:
+    ///     Number of physics steps taken
+    fn advance(&mut self, dt: f32) -> u32 {
+        self.poll_shaders();
+        self.mark_frame_start();
+        let mut renderer = self.renderer.as_mut().filter(|_| self.impact_decals);
+        let mut stream = self.stream.as_mut();
+        let shared_state = &mut self.shared_state;
+        let steps = self.inner.advance_with(dt, |sim| {
+            if let Some(renderer) = renderer.as_mut() {
+                renderer.stamp_impacts(sim.last_impacts());
+            }
+            if let Some(stream) = stream.as_mut() {
+                stream.record_events(sim.last_impacts());
+            }
+            if let Some(Err(e)) = shared_state.as_mut().map(|shared| shared.publish(sim)) {
+                log::warn!("shared state closed: {}", e);
+                *shared_state = None;
+            }
+        });
+        if steps > 0 {
+            self.publish_stream();
+        }
+        steps
+    }
+
+    /// Take exactly one fixed-size physics step, even while paused
+    fn single_step(&mut self) {
+        self.poll_shaders();
+        self.mark_frame_start();
+        self.inner.single_step();
+        if let (true, Some(renderer)) = (self.impact_decals, self.renderer.as_mut()) {
+            renderer.stamp_impacts(self.inner.last_impacts());
+        }
+        if let Some(stream) = self.stream.as_mut() {
+            stream.record_events(self.inner.last_impacts());
+        }
+        self.publish_shared_state();
+        self.publish_stream();
+    }
+
+    /// Stream every step to an external viewer, or stop with None
+    ///
+    /// After each step(), advance() or single_step() call the current poses
+    /// and that call's impacts are sent as one binary frame (layout in
+    /// docs/sample-rendering.md). A broken connection stops the stream
+    /// with a logged warning.
+    ///
+    /// Args:
+    ///     address: "tcp://host:port", "unix:///path/to/socket" or
+    ///         "host:port" of a listening viewer
+    #[pyo3(signature = (address))]
+    fn stream_to(&mut self, address: Option<&str>) -> PyResult<()> {
+        self.stream = match address {
+            Some(address) => Some(FrameStreamer::connect(address).map_err(|e| PyIOError::new_err(e.to_string()))?),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Frames sent to the current viewer (0 when not streaming)
+    fn frames_streamed(&self) -> u64 {
+        self.stream.as_ref().map_or(0, FrameStreamer::frames_sent)
+    }
+
+    /// Publish body state to a shared-memory file, or stop with None
+    ///
+    /// The file (layout in docs/sample-rendering.md) is rewritten in place
+    /// after every physics step and guarded by a seqlock, so other
+    /// processes on this machine can map it and read poses and velocities
+    /// without any serialization. Put it under /dev/shm to keep it in RAM.
+    /// The current state is published immediately. Growing the scene past
+    /// the capacity stops publishing with a logged warning.
+    ///
+    /// Args:
+    ///     path: File to create (truncated if it exists)
+    ///     capacity: Bodies the file has room for (default: twice the
+    ///         current count, at least 1024)
+    #[pyo3(signature = (path, capacity=None))]
+    fn share_state(&mut self, path: Option<&str>, capacity: Option<usize>) -> PyResult<()> {
+        self.shared_state = match path {
+            Some(path) => {
+                let capacity = capacity.unwrap_or_else(|| (2 * self.inner.storage.len()).max(1024));
+                Some(SharedStatePublisher::create(path, capacity).map_err(|e| PyIOError::new_err(e.to_string()))?)
+            }
+            None => None,
+        };
+        if let Some(shared) = self.shared_state.as_mut() {
+            shared.publish(&self.inner).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Freeze or resume advance() (step() is unaffected)
+    fn set_paused(&mut self, paused: bool) {
+        self.inner.set_paused(paused);
+    }
+
+    /// Whether advance() is frozen
+    fn is_paused(&self) -> bool {
+        self.inner.is_paused()
+    }
+
+    /// Set simulated seconds per real second for advance()
+    ///
+    /// 0.1 plays at 10x slow motion, 2.0 at double speed.
+    fn set_time_scale(&mut self, scale: f32) {
+        self.inner.set_time_scale(scale);
+    }
+
+    /// Simulated seconds per real second for advance()
+    fn time_scale(&self) -> f32 {
+        self.inner.time_scale()
+    }
+
+    /// Make advance() follow a speed ramp keyed on simulation time
+    ///
+    /// Args:
+    ///     keys: List of (sim_time, time_scale) pairs, eased with a
+    ///           smoothstep in between; None restores the constant time scale
+    #[pyo3(signature = (keys=None))]
+    fn set_time_ramp(&mut self, keys: Option<Vec<(f32, f32)>>) {
+        self.inner.set_time_ramp(keys.map(|k| TimeRamp::new(&k)));
+    }
+
+    /// Slow advance() down around a moment, e.g. an impact
+    ///
+    /// Args:
+    ///     time: Simulation time to center the slow section on
+    ///     scale: Time scale while slowed (default 0.1 = 10x slow motion)
+    ///     hold: Seconds of simulation time spent at the slow scale
+    ///     ramp: Seconds of simulation time easing in and out on each side
+    #[pyo3(signature = (time, scale=0.1, hold=0.2, ramp=0.3))]
+    fn slow_motion_around(&mut self, time: f32, scale: f32, hold: f32, ramp: f32) {
+        self.inner.set_time_ramp(Some(TimeRamp::slow_around(time, scale, hold, ramp)));
+    }
+
+    /// Time scale advance() uses right now (follows the speed ramp if set)
+    fn effective_time_scale(&self) -> f32 {
+        self.inner.effective_time_scale()
+    }
+
+    /// Set the fixed physics step size used by advance() and single_step()
+    fn set_fixed_dt(&mut self, dt: f32) -> PyResult<()> {
+        if dt <= 0.0 {
+            return Err(PyValueError::new_err("dt must be positive"));
+        }
+        self.inner.set_fixed_dt(dt);
+        Ok(())
+    }
+
+    /// Get the current simulation time
+    fn time(&self) -> f32 {
+        self.inner.time
+    }
+
+    /// Name of the physics engine simulating the scene
+    fn backend(&self) -> &'static str {
+        self.inner.physics().name()
+    }
+
+    /// Get the number of bodies
+    fn body_count(&self) -> usize {
+        self.inner.body_count()
+    }
+
+    /// Get the number of static bodies
+    fn static_count(&self) -> usize {
+        self.inner.static_count()
+    }
+
+    /// Total translational kinetic energy of all bodies (J)
+    fn kinetic_energy(&self) -> f32 {
+        self.inner.kinetic_energy()
+    }
+
+    /// Total linear momentum of all bodies as (px, py, pz)
+    fn linear_momentum(&self) -> (f32, f32, f32) {
+        let [x, y, z] = self.inner.linear_momentum();
+        (x, y, z)
+    }
+
+    /// Indices of bodies whose centers are within `radius` of body `index`
+    fn neighbors_within(&self, index: usize, radius: f32) -> PyResult<Vec<usize>> {
+        if index >= self.inner.body_count() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        Ok(self.inner.neighbors_within(index, radius))
+    }
+
+    /// Per-region statistics over a grid of cubic cells
+    ///
+    /// Args:
+    ///     cell_size: Cell edge length (m)
+    ///
+    /// Returns:
+    ///     Dict of arrays over the occupied cells, sorted by cell: "cells"
+    ///     (M, 3) int32 cell coordinates, "count" (M,) uint32, "mean_speed"
+    ///     (M,) and "mass" (M,) float32
+    fn aggregate_by_grid<'py>(&self, py: Python<'py>, cell_size: f32) -> PyResult<Bound<'py, PyDict>> {
+        if cell_size <= 0.0 {
+            return Err(PyValueError::new_err("cell_size must be positive"));
+        }
+        let stats = self.inner.aggregate_by_grid(cell_size);
+        let cells: Vec<i32> = stats.iter().flat_map(|s| s.cell).collect();
+
+        let dict = PyDict::new(py);
+        dict.set_item("cells", cells.to_pyarray(py).reshape([stats.len(), 3])?)?;
+        dict.set_item("count", stats.iter().map(|s| s.count).collect::<Vec<_>>().to_pyarray(py))?;
+        dict.set_item("mean_speed", stats.iter().map(|s| s.mean_speed).collect::<Vec<_>>().to_pyarray(py))?;
+        dict.set_item("mass", stats.iter().map(|s| s.mass).collect::<Vec<_>>().to_pyarray(py))?;
+        Ok(dict)
+    }
+
+    /// Every pair of bodies whose centers are within `radius`
+    ///
+    /// Uses a spatial hash, so it stays fast for large scenes.
+    ///
+    /// Returns:
+    ///     (M, 2) int64 array of index pairs (i < j), sorted
+    fn all_pairs_within<'py>(&self, py: Python<'py>, radius: f32) -> Bound<'py, PyArray2<i64>> {
+        let pairs = self.inner.all_pairs_within(radius);
+        let flat: Vec<i64> = pairs.iter().flat_map(|&(i, j)| [i as i64, j as i64]).collect();
+        flat.to_pyarray(py).reshape([pairs.len(), 2]).unwrap()
+    }
+
+    /// Pairs of bodies with extreme mass ratios found when the world was
+    /// built
+    ///
+    /// Neighboring or jointed bodies more than 100x apart in mass jitter or
+    /// sag with the default solver, so the world is built with more solver
+    /// iterations (16 at 100:1, up to 32); these warnings say why.
+    ///
+    /// Returns:
+    ///     List of dicts with kind ("mass_ratio"), bodies (heavy, light)
+    ///     and message
+    fn build_warnings<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.inner.build_warnings().iter().map(|issue| issue_dict(py, issue)).collect()
+    }
+
+    /// Constraint island of every body
+    ///
+    /// Dynamic bodies linked through active contacts or joints share an
+    /// island, which Rapier wakes and puts to sleep as a unit.
+    ///
+    /// Returns:
+    ///     (N,) int64 island ids numbered by lowest body index, -1 for
+    ///     static bodies
+    fn island_ids<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<i64>> {
+        let ids: Vec<i64> = self.inner.island_ids().into_iter()
+            .map(|id| id.map_or(-1, |id| id as i64))
+            .collect();
+        ids.to_pyarray(py)
+    }
+
+    /// Whether each body is asleep, as an (N,) bool array
+    fn sleeping<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<bool>> {
+        self.inner.sleeping().to_pyarray(py)
+    }
+
+    /// Turn every sleeping dynamic body into a fixed one
+    ///
+    /// Settled piles then cost the solver nothing while new bodies keep
+    /// landing on them. Impacts don't wake frozen bodies; unfreeze them to
+    /// let them move again.
+    ///
+    /// Returns:
+    ///     Indices of the bodies frozen
+    fn freeze_sleeping(&mut self) -> Vec<usize> {
+        self.inner.freeze_sleeping()
+    }
+
+    /// Freeze a dynamic body whether or not it sleeps
+    ///
+    /// Returns:
+    ///     False for invalid, static, path-following or already frozen bodies
+    fn freeze(&mut self, index: usize) -> bool {
+        self.inner.freeze(index)
+    }
+
+    /// Let a frozen body move again (at rest)
+    ///
+    /// Returns:
+    ///     False if the body was not frozen
+    fn unfreeze(&mut self, index: usize) -> bool {
+        self.inner.unfreeze(index)
+    }
+
+    /// Let every frozen body move again
+    ///
+    /// Returns:
+    ///     Number of bodies unfrozen
+    fn unfreeze_all(&mut self) -> usize {
+        self.inner.unfreeze_all()
+    }
+
+    /// Indices of the frozen bodies
+    fn frozen_bodies(&self) -> Vec<usize> {
+        self.inner.frozen_bodies()
+    }
+
+    /// Simulate only bodies near a focus point in full
+    ///
+    /// Awake bodies farther than radius + margin from the focus stop
+    /// colliding and fly ballistically under gravity, stopping on the
+    /// ground, until they come back within radius. Jointed bodies are
+    /// always simulated in full.
+    ///
+    /// Args:
+    ///     radius: Distance from the focus simulated in full; None turns
+    ///         the level of simulation off
+    ///     focus: Center of the fully simulated region, e.g. the camera
+    ///         target
+    ///     margin: Extra distance before a body is simplified (default: 10%
+    ///         of the radius)
+    #[pyo3(signature = (radius, focus=[0.0, 0.0, 0.0], margin=None))]
+    fn set_level_of_simulation(&mut self, radius: Option<f32>, focus: [f32; 3], margin: Option<f32>) -> PyResult<()> {
+        let lod = match radius {
+            Some(radius) => {
+                let mut lod = SimulationLod::new(focus, radius);
+                if let Some(margin) = margin {
+                    lod.margin = margin;
+                }
+                if !(lod.radius > 0.0) || lod.margin < 0.0 {
+                    return Err(PyValueError::new_err("radius must be positive and margin non-negative"));
+                }
+                Some(lod)
+            }
+            None => None,
+        };
+        if !self.inner.set_lod(lod) {
+            return Err(PyRuntimeError::new_err("the physics backend has no level of simulation"));
+        }
+        Ok(())
+    }
+
+    /// Move the focus of the level of simulation (no effect while it is off)
+    fn set_lod_focus(&mut self, focus: [f32; 3]) {
+        self.inner.set_lod_focus(focus);
+    }
+
+    /// Indices of the bodies currently flying ballistically
+    fn coarse_bodies(&self) -> Vec<usize> {
+        self.inner.coarse_bodies()
+    }
+
+    /// Contact network after the last step
+    ///
+    /// Returns:
+    ///     Dict with "edges" ((M, 2) int64 body index pairs, -1 for the
+    ///     ground) and "impulse" ((M,) float32 normal impulse of each
+    ///     contact over the step, N·s)
+    fn contact_graph<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let graph = self.inner.contact_graph();
+        let edges: Vec<i64> = graph.edges.iter()
+            .flat_map(|e| [e.body_a as i64, e.body_b.map_or(-1, |b| b as i64)])
+            .collect();
+        let impulses: Vec<f32> = graph.edges.iter().map(|e| e.impulse).collect();
+        let dict = PyDict::new(py);
+        dict.set_item("edges", edges.to_pyarray(py).reshape([graph.edges.len(), 2]).unwrap())?;
+        dict.set_item("impulse", impulses.to_pyarray(py))?;
+        Ok(dict)
+    }
+
+    /// Write the contact network after the last step to a file
+    ///
+    /// Args:
+    ///     path: Output file
+    ///     format: "graphml" or "edgelist" (default: "graphml" for .graphml
+    ///         files, else "edgelist")
+    #[pyo3(signature = (path, format=None))]
+    fn save_contact_graph(&self, path: &str, format: Option<&str>) -> PyResult<()> {
+        let graph = self.inner.contact_graph();
+        let format = format.unwrap_or(if path.ends_with(".graphml") { "graphml" } else { "edgelist" });
+        let result = match format {
+            "graphml" => graph.save_graphml(path),
+            "edgelist" => graph.save_edge_list(path),
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown format '{}' (expected graphml or edgelist)",
+                    other
+                )))
+            }
+        };
+        result.map_err(|e| PyIOError::new_err(e.to_string()))
+    }
+
+    /// Current temperature of every body, as an (N,) float32 array
+    fn get_temperatures<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f32>> {
+        self.inner.temperatures().to_pyarray(py)
+    }
+
+    /// Set a body's current temperature (e.g. to heat one body mid-run)
+    fn set_temperature(&mut self, index: usize, temperature: f32) -> PyResult<()> {
+        if !self.inner.set_temperature(index, temperature) {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        Ok(())
+    }
+
+    /// Get positions as a NumPy array (N, 3)
+    fn get_positions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
+        let positions = self.inner.positions();
+        let n = positions.len();
+        let flat: Vec<f32> = positions.iter()
+            .flat_map(|p| p.iter().copied())
+            .collect();
+        flat.to_pyarray(py).reshape([n, 3]).unwrap()
+    }
+
+    /// Number of fluid particles
+    fn fluid_count(&self) -> usize {
+        self.inner.fluid().map_or(0, |fluid| fluid.len())
+    }
+
+    /// Get fluid particle positions as a NumPy array (N, 3)
+    fn get_fluid_positions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
+        let positions = self.inner.fluid().map_or(&[][..], |fluid| fluid.positions());
+        let flat: Vec<f32> = positions.iter().flat_map(|p| p.iter().copied()).collect();
+        flat.to_pyarray(py).reshape([positions.len(), 3]).unwrap()
+    }
+
+    /// Get fluid particle velocities as a NumPy array (N, 3)
+    fn get_fluid_velocities<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
+        let velocities = self.inner.fluid().map_or(&[][..], |fluid| fluid.velocities());
+        let flat: Vec<f32> = velocities.iter().flat_map(|v| v.iter().copied()).collect();
+        flat.to_pyarray(py).reshape([velocities.len(), 3]).unwrap()
+    }
+
+    /// Get rotations as a NumPy array (N, 4)
+    fn get_rotations<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
+        let rotations = self.inner.rotations();
+        let n = rotations.len();
+        let flat: Vec<f32> = rotations.iter()
+            .flat_map(|r| r.iter().copied())
+            .collect();
+        flat.to_pyarray(py).reshape([n, 4]).unwrap()
+    }
+
+    /// Set camera position and target
+    #[pyo3(signature = (eye, target))]
+    fn set_camera(&mut self, eye: [f32; 3], target: [f32; 3]) -> PyResult<()> {
+        if let Some(ref mut renderer) = self.renderer {
+            renderer.set_camera(eye, target);
+            Ok(())
+        } else {
+            Err(PyRuntimeError::new_err("Renderer not available"))
+        }
+    }
+
+    /// Use an orthographic projection showing `height` world units
+    /// vertically, or perspective again with None
+    #[pyo3(signature = (height=None))]
+    fn set_orthographic(&mut self, height: Option<f32>) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_orthographic(height);
+        Ok(())
+    }
+
+    /// Inset a second view, e.g. a top-down minimap, into a corner of
+    /// rendered frames
+    ///
+    /// Without `eye` the inset looks straight down at `target`
+    /// (orthographic, +X right, -Z up). The inset is a full extra render per
+    /// frame and shows no labels or other overlays.
+    ///
+    /// Args:
+    ///     enabled: Draw the inset; False removes it
+    ///     eye: Position of a perspective inset camera looking at `target`
+    ///     target: Point the inset camera looks at
+    ///     extent: Meters shown around `target` by the top-down view
+    ///     corner: "top_right" (default), "top_left", "bottom_left" or "bottom_right"
+    ///     size: Inset width as a fraction of the frame width
+    ///     margin: Distance in pixels from the frame edges
+    ///     border_width: Border width in pixels; 0 draws none
+    ///     border_color: Border color
+    #[pyo3(signature = (enabled=true, eye=None, target=[0.0, 0.0, 0.0], extent=20.0, corner="top_right", size=0.25, margin=16, border_width=2, border_color=[1.0, 1.0, 1.0]))]
+    #[allow(clippy::too_many_arguments)]
+    fn set_picture_in_picture(
+        &mut self,
+        enabled: bool,
+        eye: Option<[f32; 3]>,
+        target: [f32; 3],
+        extent: f32,
+        corner: &str,
+        size: f32,
+        margin: u32,
+        border_width: u32,
+        border_color: [f32; 3],
+    ) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        if !enabled {
+            renderer.set_picture_in_picture(None);
+            return Ok(());
+        }
+        let corner = Corner::from_name(corner).ok_or_else(|| PyValueError::new_err(format!(
+            "unknown corner '{}' (expected 'top_left', 'top_right', 'bottom_left' or 'bottom_right')", corner
+        )))?;
+        if !(size > 0.0 && size <= 1.0) {
+            return Err(PyValueError::new_err("size must be in (0, 1]"));
+        }
+        let aspect = renderer.camera.aspect;
+        let camera = match eye {
+            Some(eye) => Camera::new(eye, target, aspect),
+            None => Camera::top_down(target, extent, aspect),
+        };
+        renderer.set_picture_in_picture(Some(PictureInPicture {
+            corner,
+            size,
+            margin,
+            border_width,
+            border_color,
+            ..PictureInPicture::new(camera)
+        }));
+        Ok(())
+    }
+
+    /// Draw spring joints as coils (or straight lines with coil_radius=0)
+    ///
+    /// Springs are drawn with small spheres, which share the sphere
+    /// instance limit (the larger of the body count and 1000).
+    #[pyo3(signature = (enabled=true, coil_radius=0.1, thickness=0.03, turns=8.0, color=[0.7, 0.7, 0.75]))]
+    fn show_springs(&mut self, enabled: bool, coil_radius: f32, thickness: f32, turns: f32, color: [f32; 3]) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_spring_display(enabled.then_some(SpringDisplay { coil_radius, thickness, turns, color }));
+        Ok(())
+    }
+
+    /// Draw contact forces as force chains: rods between the centers of
+    /// touching bodies, thicker and redder the larger the impulse
+    ///
+    /// Rods are drawn with small spheres, which share the sphere instance
+    /// limit. Contacts with the ground are not drawn.
+    ///
+    /// Args:
+    ///     enabled: Draw force chains at all
+    ///     thickness: Rod diameter of the strongest contact (m)
+    ///     max_impulse: Impulse drawn at full thickness (default: the
+    ///         strongest contact of each frame)
+    ///     min_fraction: Skip contacts weaker than this fraction of the maximum
+    ///     show_bodies: Also draw the dynamic bodies (static ones always are)
+    #[pyo3(signature = (enabled=true, thickness=0.05, max_impulse=None, min_fraction=0.05, show_bodies=false))]
+    fn show_force_chains(
+        &mut self,
+        enabled: bool,
+        thickness: f32,
+        max_impulse: Option<f32>,
+        min_fraction: f32,
+        show_bodies: bool,
+    ) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_force_chain_display(enabled.then_some(ForceChainDisplay { thickness, max_impulse, min_fraction, show_bodies }));
+        Ok(())
+    }
+
+    /// Choose how the fluid is drawn (shown by default)
+    ///
+    /// Particles are drawn as overlapping sphere splats that merge into one
+    /// liquid surface; fast particles turn to foam.
+    ///
+    /// Args:
+    ///     enabled: Draw the fluid at all
+    ///     color: Body color of the liquid
+    ///     foam_color: Color of fast-moving spray
+    ///     foam_speed: Speed (m/s) at which particles are fully foam; 0
+    ///         disables foam
+    ///     blob_scale: Splat radius relative to the particle radius
+    #[pyo3(signature = (enabled=true, color=[0.08, 0.32, 0.55], foam_color=[0.9, 0.95, 1.0], foam_speed=4.0, blob_scale=1.5))]
+    fn show_fluid(&mut self, enabled: bool, color: [f32; 3], foam_color: [f32; 3], foam_speed: f32, blob_scale: f32) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_fluid_display(enabled.then_some(FluidDisplay { blob_scale, color, foam_color, foam_speed }));
+        Ok(())
+    }
+
+    /// Float text labels above bodies in rendered frames
+    ///
+    /// Labels face the camera and keep their pixel size at any distance,
+    /// which makes individual bodies easy to find in stills of large scenes.
+    /// Calling again replaces the labels of the given bodies and keeps the
+    /// others.
+    ///
+    /// Args:
+    ///     bodies: Body indices to label (default: all current bodies)
+    ///     text: "index" for the body index, "name" for shape and index
+    ///         (e.g. "cube_0042", as in Alembic and Blender exports), a list
+    ///         with one string per body, or any other string for all of them
+    #[pyo3(signature = (bodies=None, text=None))]
+    fn label_bodies(&mut self, bodies: Option<Vec<usize>>, text: Option<&Bound<'_, PyAny>>) -> PyResult<()> {
+        let body_count = self.inner.body_count();
+        let bodies = bodies.unwrap_or_else(|| (0..body_count).collect());
+        if let Some(&index) = bodies.iter().find(|&&i| i >= body_count) {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        let texts: Vec<LabelText> = match text {
+            None => vec![LabelText::Index; bodies.len()],
+            Some(text) => match text.extract::<String>() {
+                Ok(text) => {
+                    let label = match text.as_str() {
+                        "index" => LabelText::Index,
+                        "name" => LabelText::Name,
+                        _ => LabelText::Custom(text),
+                    };
+                    vec![label; bodies.len()]
+                }
+                Err(_) => {
+                    let texts: Vec<String> = text.extract()?;
+                    if texts.len() != bodies.len() {
+                        return Err(PyValueError::new_err(format!(
+                            "got {} label texts for {} bodies", texts.len(), bodies.len()
+                        )));
+                    }
+                    texts.into_iter().map(LabelText::Custom).collect()
+                }
+            },
+        };
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        for (index, text) in bodies.into_iter().zip(texts) {
+            renderer.set_label(index, Some(text));
+        }
+        Ok(())
+    }
+
+    /// Remove body labels
+    ///
+    /// Args:
+    ///     bodies: Body indices to unlabel (default: all)
+    #[pyo3(signature = (bodies=None))]
+    fn clear_labels(&mut self, bodies: Option<Vec<usize>>) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        match bodies {
+            Some(bodies) => bodies.into_iter().for_each(|index| renderer.set_label(index, None)),
+            None => renderer.clear_labels(),
+        }
+        Ok(())
+    }
+
+    /// Choose how body labels are drawn
+    ///
+    /// Args:
+    ///     pixel_height: Text height in pixels
+    ///     color: Text color
+    ///     background: RGBA backdrop behind the text; alpha 0 draws bare text
+    ///     depth_test: Hide labels behind nearer bodies; False draws them on top
+    ///     lift: Gap between the top of a body and its label (m)
+    #[pyo3(signature = (pixel_height=16.0, color=[1.0, 1.0, 1.0], background=[0.0, 0.0, 0.0, 0.6], depth_test=true, lift=0.05))]
+    fn set_label_style(&mut self, pixel_height: f32, color: [f32; 3], background: [f32; 4], depth_test: bool, lift: f32) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_label_display(LabelDisplay { pixel_height, color, background, depth_test, lift });
+        Ok(())
+    }
+
+    /// Draw a dimension line with its live length into rendered frames
+    ///
+    /// Args:
+    ///     a: Body index (its center, followed as it moves) or (x, y, z) world point
+    ///     b: Body index or world point
+    ///
+    /// Returns:
+    ///     Index of the measurement (see measurement_values and remove_measurement)
+    fn measure_distance(&mut self, a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyResult<usize> {
+        let body_count = self.inner.body_count();
+        let (a, b) = (parse_measure_point(a, body_count)?, parse_measure_point(b, body_count)?);
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        Ok(renderer.measure_distance(a, b))
+    }
+
+    /// Draw the angle at a pivot between two arms, with its live value in
+    /// degrees, into rendered frames
+    ///
+    /// Args:
+    ///     a: End of the first arm: body index or (x, y, z) world point
+    ///     pivot: Vertex of the angle: body index or world point
+    ///     b: End of the second arm: body index or world point
+    ///
+    /// Returns:
+    ///     Index of the measurement (see measurement_values and remove_measurement)
+    fn measure_angle(&mut self, a: &Bound<'_, PyAny>, pivot: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyResult<usize> {
+        let body_count = self.inner.body_count();
+        let a = parse_measure_point(a, body_count)?;
+        let pivot = parse_measure_point(pivot, body_count)?;
+        let b = parse_measure_point(b, body_count)?;
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        Ok(renderer.measure_angle(a, pivot, b))
+    }
+
+    /// Current values of all measurements, by index: meters for distances,
+    /// degrees for angles (None where an angle arm has zero length)
+    fn measurement_values(&self) -> PyResult<Vec<Option<f32>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        Ok(renderer.measurements().iter().map(|m| m.value(&self.inner.storage)).collect())
+    }
+
+    /// Stop drawing a measurement; later measurements move down one index
+    fn remove_measurement(&mut self, index: usize) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.remove_measurement(index)
+            .ok_or_else(|| PyIndexError::new_err(format!("measurement index {} out of range", index)))?;
+        Ok(())
+    }
+
+    /// Stop drawing all measurements
+    fn clear_measurements(&mut self) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.clear_measurements();
+        Ok(())
+    }
+
+    /// Choose how measurements are drawn (values use the label text size and
+    /// backdrop, see set_label_style)
+    ///
+    /// Args:
+    ///     color: Line color
+    ///     line_width: Line width in pixels
+    ///     tick: Length in pixels of the ticks across distance line ends; 0 draws none
+    ///     decimals: Decimal places of the values
+    ///     depth_test: Hide lines and values behind nearer bodies; False
+    ///         (default) draws them on top
+    #[pyo3(signature = (color=[1.0, 0.85, 0.2], line_width=2.0, tick=10.0, decimals=2, depth_test=false))]
+    fn set_measurement_style(&mut self, color: [f32; 3], line_width: f32, tick: f32, decimals: usize, depth_test: bool) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_measurement_display(MeasurementDisplay { color, line_width, tick, decimals, depth_test });
+        Ok(())
+    }
+
+    /// Draw a reference grid into rendered frames (hidden behind nearer
+    /// geometry)
+    ///
+    /// Args:
+    ///     origin: Center of the grid
+    ///     plane: "xz" (horizontal, default), "xy" or "yz"
+    ///     half_size: Distance from the center to each edge
+    ///     spacing: Distance between lines
+    ///     color: Line color as RGBA; alpha blends with the frame
+    ///     line_width: Line width in pixels
+    ///     axes: Two direction vectors spanning a tilted plane; overrides `plane`
+    ///
+    /// Returns:
+    ///     Index of the grid
+    #[pyo3(signature = (origin=[0.0, 0.0, 0.0], plane="xz", half_size=10.0, spacing=1.0, color=[1.0, 1.0, 1.0, 0.35], line_width=1.0, axes=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_grid(
+        &mut self,
+        origin: [f32; 3],
+        plane: &str,
+        half_size: f32,
+        spacing: f32,
+        color: [f32; 4],
+        line_width: f32,
+        axes: Option<[[f32; 3]; 2]>,
+    ) -> PyResult<usize> {
+        if !(half_size > 0.0 && spacing > 0.0) {
+            return Err(PyValueError::new_err("half_size and spacing must be positive"));
+        }
+        let axes = match (axes, plane) {
+            (Some(axes), _) => axes,
+            (None, "xz") => [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+            (None, "xy") => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+            (None, "yz") => [[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            (None, other) => return Err(PyValueError::new_err(format!(
+                "unknown grid plane '{}' (expected 'xz', 'xy' or 'yz')", other
+            ))),
+        };
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let grid = ReferenceGrid { color, line_width, ..ReferenceGrid::on_plane(origin, axes, half_size, spacing) };
+        Ok(renderer.add_grid(grid))
+    }
+
+    /// Stop drawing reference grids
+    fn clear_grids(&mut self) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.clear_grids();
+        Ok(())
+    }
+
+    /// Show or hide the world-axis gizmo in the lower left corner of
+    /// rendered frames (X red, Y green, Z blue)
+    ///
+    /// Args:
+    ///     enabled: Draw the gizmo
+    ///     size: Length in pixels of an axis pointing across the image
+    ///     margin: Distance in pixels from the image edges
+    ///     line_width: Line width in pixels
+    ///     letters: Draw the axis letters, in the label style
+    #[pyo3(signature = (enabled=true, size=60.0, margin=20.0, line_width=3.0, letters=true))]
+    fn show_axis_gizmo(&mut self, enabled: bool, size: f32, margin: f32, line_width: f32, letters: bool) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_axis_gizmo(enabled.then_some(AxisGizmo { size, margin, line_width, letters }));
+        Ok(())
+    }
+
+    /// Load the sky, ground, cube, sphere, fluid and tonemap shaders from a directory
+    /// and reload them whenever the files change (shader development)
+    ///
+    /// Edited files are picked up on the next step()/advance() or
+    /// reload_shaders() call. Missing files keep the built-in shader.
+    ///
+    /// Args:
+    ///     dir: Shader directory (e.g. "rust/physobx-core/shaders"), or None
+    ///          to go back to the built-in shaders
+    ///
+    /// Returns:
+    ///     File names of the shaders loaded
+    #[pyo3(signature = (dir))]
+    fn watch_shaders(&mut self, dir: Option<String>) -> PyResult<Vec<&'static str>> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let result = match dir {
+            Some(dir) => renderer.watch_shaders(dir),
+            None => renderer.unwatch_shaders().map(|_| Vec::new()),
+        };
+        result.map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Reload watched shaders that changed on disk now
+    ///
+    /// Unlike the automatic reload during stepping, a shader that fails to
+    /// compile raises RuntimeError (the previous version stays in use).
+    ///
+    /// Returns:
+    ///     File names of the shaders reloaded
+    fn reload_shaders(&mut self) -> PyResult<Vec<&'static str>> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.reload_changed_shaders().map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Enable per-object motion blur for rendered frames
+    ///
+    /// Each body is smeared along its screen-space motion since the previously
+    /// rendered frame.
+    ///
+    /// Args:
+    ///     enabled: Turn motion blur on or off
+    ///     shutter: Fraction of the frame interval the shutter is open
+    ///              (0.5 = 180 degree shutter, 1.0 = full-frame streaks)
+    ///     samples: Samples along each pixel's motion path
+    #[pyo3(signature = (enabled=true, shutter=0.5, samples=16))]
+    fn set_motion_blur(&mut self, enabled: bool, shutter: f32, samples: u32) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_motion_blur(shutter, if enabled { samples } else { 0 });
+        renderer.reset_motion_history();
+        Ok(())
+    }
+
+    /// Render a frame and return as NumPy array (H, W, 4)
+    fn render_frame<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        let pixels = self.render_pixels(renderer);
+        let (width, height) = renderer.dimensions();
+
+        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    }
+
+    /// Top-down occupancy grid of the ground area computed from body AABBs
+    ///
+    /// Args:
+    ///     resolution: Cells per side (default 256)
+    ///     height_range: Only bodies overlapping (min_y, max_y) count
+    ///
+    /// Returns:
+    ///     (resolution, resolution) uint8 array, 1 = occupied; rows run along
+    ///     +Z and columns along +X over [-ground_size, ground_size]
+    #[pyo3(signature = (resolution=256, height_range=(f32::NEG_INFINITY, f32::INFINITY)))]
+    fn render_occupancy<'py>(
+        &self,
+        py: Python<'py>,
+        resolution: u32,
+        height_range: (f32, f32),
+    ) -> PyResult<Bound<'py, PyArray2<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        let grid = renderer.render_occupancy(
+            &self.inner.storage,
+            resolution,
+            [height_range.0, height_range.1],
+        );
+        let res = resolution as usize;
+        Ok(grid.to_pyarray(py).reshape([res, res]).unwrap())
+    }
+
+    /// Project world points into the rendered image with the current camera
+    ///
+    /// Args:
+    ///     points: (N, 3) world-space points, e.g. body centroids or corners
+    ///
+    /// Returns:
+    ///     Tuple of (N, 2) float32 pixel coordinates (origin top-left, +Y
+    ///     down, lens distortion applied; NaN behind the camera), (N,)
+    ///     float32 depth along the view axis and (N,) bool in-frustum flags
+    fn project_points<'py>(
+        &self,
+        py: Python<'py>,
+        points: Vec<[f32; 3]>,
+    ) -> PyResult<Projection<'py>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        let projected = renderer.project_points(&points);
+        let pixels: Vec<f32> = projected.iter().flat_map(|p| p.pixel).collect();
+        let depth: Vec<f32> = projected.iter().map(|p| p.depth).collect();
+        let in_frustum: Vec<bool> = projected.iter().map(|p| p.in_frustum).collect();
+        Ok((
+            pixels.to_pyarray(py).reshape([points.len(), 2]).unwrap(),
+            depth.to_pyarray(py),
+            in_frustum.to_pyarray(py),
+        ))
+    }
+
+    /// World point seen at a pixel at the given depth
+    ///
+    /// Args:
+    ///     pixel: (x, y) in image pixels (origin top-left, +Y down)
+    ///     depth: Distance along the camera's view axis
+    ///
+    /// Returns:
+    ///     (x, y, z) world position; inverse of project_points
+    fn unproject(&self, pixel: (f32, f32), depth: f32) -> PyResult<(f32, f32, f32)> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let [x, y, z] = renderer.unproject([pixel.0, pixel.1], depth);
+        Ok((x, y, z))
+    }
+
+    /// Camera ray through a pixel, for picking and click-to-spawn tools
+    ///
+    /// Returns:
+    ///     Tuple of (origin, direction) with origin at the camera eye and a
+    ///     unit-length direction
+    fn screen_ray(&self, pixel: (f32, f32)) -> PyResult<Ray> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let (o, d) = renderer.screen_ray([pixel.0, pixel.1]);
+        Ok(((o[0], o[1], o[2]), (d[0], d[1], d[2])))
+    }
+
+    /// Render the current state with bodies colored by divergence
+    ///
+    /// Args:
+    ///     divergence: (N,) per-body values, e.g. a row of
+    ///                 compare_trajectories(...)["divergence"]
+    ///     max_divergence: Value shown as full red (default: largest value)
+    ///
+    /// Returns:
+    ///     (height, width, 4) uint8 frame, blue = 0 through yellow to red
+    #[pyo3(signature = (divergence, max_divergence=None))]
+    fn render_divergence<'py>(
+        &self,
+        py: Python<'py>,
+        divergence: Vec<f32>,
+        max_divergence: Option<f32>,
+    ) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        let max = max_divergence.unwrap_or_else(|| divergence.iter().copied().fold(0.0, f32::max));
+        let pixels = renderer.render_divergence(&self.inner, &divergence, max);
+        let (width, height) = renderer.dimensions();
+        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    }
+
+    /// Render the current state with bodies colored by temperature
+    ///
+    /// Args:
+    ///     min_temperature: Temperature shown as blue (default: lowest)
+    ///     max_temperature: Temperature shown as red (default: highest)
+    ///
+    /// Returns:
+    ///     (height, width, 4) uint8 frame; static bodies keep their colors
+    #[pyo3(signature = (min_temperature=None, max_temperature=None))]
+    fn render_temperature<'py>(
+        &self,
+        py: Python<'py>,
+        min_temperature: Option<f32>,
+        max_temperature: Option<f32>,
+    ) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        let temperatures = self.inner.temperatures();
+        let min = min_temperature.unwrap_or_else(|| temperatures.iter().copied().fold(f32::INFINITY, f32::min));
+        let max = max_temperature.unwrap_or_else(|| temperatures.iter().copied().fold(f32::NEG_INFINITY, f32::max));
+        let pixels = renderer.render_temperature(&self.inner, min, max);
+        let (width, height) = renderer.dimensions();
+        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    }
+
+    /// Render the current state with bodies colored by constraint island
+    ///
+    /// Returns:
+    ///     (height, width, 4) uint8 frame; static bodies keep their colors
+    fn render_islands<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let pixels = renderer.render_islands(&self.inner);
+        let (width, height) = renderer.dimensions();
+        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    }
+
+    /// Render a composite of several layers of the current state
+    ///
+    /// Layers are blended bottom to top over black, each with its own
+    /// opacity and blend mode, e.g. the frame with bodies tinted by index:
+    /// `[{"source": "color"}, {"source": "segmentation", "opacity": 0.4}]`.
+    ///
+    /// Args:
+    ///     layers: List of dicts with keys
+    ///             source: "color" (frame with annotations), "annotations"
+    ///                     (labels, measurements, grids and gizmo only),
+    ///                     "segmentation" (flat color per body),
+    ///                     "islands", "temperature" or "heatmap"
+    ///             opacity: Layer opacity in [0, 1] (default: 1)
+    ///             blend: "normal", "multiply", "screen" or "add"
+    ///                    (default: "normal")
+    ///             values: (N,) per-body values of a "heatmap" layer
+    ///             min, max: Value range of "temperature" and "heatmap"
+    ///                       layers (default: range of the values)
+    ///
+    /// Returns:
+    ///     (height, width, 4) uint8 frame
+    fn render_layers<'py>(
+        &self,
+        py: Python<'py>,
+        layers: Vec<Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let layers = layers.iter()
+            .map(|layer| parse_layer(layer, &self.inner))
+            .collect::<PyResult<Vec<_>>>()?;
+        let pixels = renderer.render_layers(&self.inner, &layers);
+        let (width, height) = renderer.dimensions();
+        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    }
+
+    /// Render only a pixel rectangle of the current frame
+    ///
+    /// Saves pixel shading and readback over cropping full renders; geometry,
+    /// shadows and post-processing still run at full size. The patch matches
+    /// the same rectangle of a full frame; it is clipped to the image.
+    ///
+    /// Args:
+    ///     x: Left edge in pixels
+    ///     y: Top edge in pixels
+    ///     width: Patch width in pixels
+    ///     height: Patch height in pixels
+    ///
+    /// Returns:
+    ///     (height, width, 4) uint8 array of the clipped patch
+    fn render_roi<'py>(
+        &self,
+        py: Python<'py>,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let [_, _, width, height] = renderer.target.clamp_rect([x, y, width, height]);
+        let pixels = renderer.render_roi(&self.inner, x, y, width, height);
+        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    }
+
+    /// Render a square close-up of each listed body, e.g. as training chips
+    /// for classification or pose estimation
+    ///
+    /// Each crop looks along the main camera's viewing direction with the
+    /// body centered and filling the crop. Labels and the picture-in-picture
+    /// inset are left out.
+    ///
+    /// Args:
+    ///     body_ids: Indices of the bodies to crop
+    ///     crop_size: Side length of each crop in pixels (at most the
+    ///         image's smaller side)
+    ///
+    /// Returns:
+    ///     (N, crop_size, crop_size, 4) uint8 array, one crop per id; ids
+    ///     past the body count give black crops
+    fn render_object_crops<'py>(
+        &self,
+        py: Python<'py>,
+        body_ids: Vec<usize>,
+        crop_size: u32,
+    ) -> PyResult<Bound<'py, PyArray4<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let (width, height) = renderer.dimensions();
+        let size = crop_size.min(width).min(height) as usize;
+        let pixels = renderer.render_object_crops(&self.inner, &body_ids, crop_size);
+        Ok(pixels.to_pyarray(py).reshape([body_ids.len(), size, size, 4]).unwrap())
+    }
+
+    /// Render this simulator and another one side by side from this
+    /// simulator's camera, for before/after comparisons
+    ///
+    /// Args:
+    ///     other: Simulator shown right of the split (its renderer is unused)
+    ///     split: Position of the split as a fraction of the image width
+    ///     divider_width: Divider width in pixels; 0 draws none
+    ///     divider_color: Divider color
+    ///
+    /// Returns:
+    ///     (height, width, 4) uint8 array
+    #[pyo3(signature = (other, split=0.5, divider_width=4, divider_color=[1.0, 1.0, 1.0]))]
+    fn render_side_by_side<'py>(
+        &self,
+        py: Python<'py>,
+        other: PyRef<'py, PySimulator>,
+        split: f32,
+        divider_width: u32,
+        divider_color: [f32; 3],
+    ) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let layout = SplitScreen { split, divider_width, divider_color };
+        let pixels = renderer.render_side_by_side(&self.inner, &other.inner, &layout);
+        let (width, height) = renderer.dimensions();
+        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    }
+
+    /// Render the body index under each pixel of the current state
+    ///
+    /// Returns:
+    ///     (height, width) int32 array of body indices, -1 for background
+    fn render_instance_ids<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray2<i32>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        let ids = renderer.render_instance_ids(&self.inner);
+        let (width, height) = renderer.dimensions();
+        Ok(ids.to_pyarray(py).reshape([height as usize, width as usize]).unwrap())
+    }
+
+    /// Fraction of each body visible in the current frame
+    ///
+    /// Visible pixels divided by the pixels the body would cover if nothing
+    /// occluded it. Use this to drop heavily-occluded objects from labels.
+    ///
+    /// Returns:
+    ///     (N,) float32 array in [0, 1]; bodies outside the frame report 0
+    fn visibility_fractions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray1<f32>>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        Ok(renderer.visibility_fractions(&self.inner).to_pyarray(py))
+    }
+
+    /// Oriented 3D bounding box of every body in the camera frame, with its
+    /// corners projected into the rendered image, for 3D detection labels
+    ///
+    /// The camera frame is +X right, +Y down, +Z forward (matching the
+    /// intrinsics of `frame_metadata`). Corner k sits at local signs given
+    /// by bits 0, 1 and 2 of k (x, y, z; clear = negative).
+    ///
+    /// Returns:
+    ///     Dict with "indices" (N,) body indices, "centers" (N, 3),
+    ///     "half_extents" (N, 3) along the body axes, "rotations" (N, 4)
+    ///     body-to-camera quaternions (x, y, z, w), "corners" (N, 8, 3) in
+    ///     the camera frame, "pixels" (N, 8, 2) projected corners (NaN
+    ///     behind the camera) and "in_frustum" (N,) whether any corner is
+    ///     in view
+    fn bounding_boxes_3d<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let boxes = renderer.bounding_boxes_3d(&self.inner);
+        let n = boxes.len();
+        let dict = PyDict::new(py);
+        dict.set_item("indices", boxes.iter().map(|b| b.index as u32).collect::<Vec<_>>().into_pyarray(py))?;
+        let centers: Vec<f32> = boxes.iter().flat_map(|b| b.center).collect();
+        dict.set_item("centers", centers.to_pyarray(py).reshape([n, 3]).unwrap())?;
+        let half_extents: Vec<f32> = boxes.iter().flat_map(|b| b.half_extents).collect();
+        dict.set_item("half_extents", half_extents.to_pyarray(py).reshape([n, 3]).unwrap())?;
+        let rotations: Vec<f32> = boxes.iter().flat_map(|b| b.rotation).collect();
+        dict.set_item("rotations", rotations.to_pyarray(py).reshape([n, 4]).unwrap())?;
+        let corners: Vec<f32> = boxes.iter().flat_map(|b| b.corners.into_iter().flatten()).collect();
+        dict.set_item("corners", corners.to_pyarray(py).reshape([n, 8, 3]).unwrap())?;
+        let pixels: Vec<f32> = boxes.iter().flat_map(|b| b.pixels.into_iter().flatten()).collect();
+        dict.set_item("pixels", pixels.to_pyarray(py).reshape([n, 8, 2]).unwrap())?;
+        dict.set_item("in_frustum", boxes.iter().map(|b| b.in_frustum).collect::<Vec<_>>().into_pyarray(py))?;
+        Ok(dict)
+    }
+
+    /// 6-DoF pose of every body in the OpenCV camera frame (+X right, +Y
+    /// down, +Z forward), e.g. for pose estimation labels
+    ///
+    /// Returns:
+    ///     Dict with "indices" (N,) body indices, "rotations" (N, 3, 3)
+    ///     body-to-camera rotations, "translations" (N, 3) body centers in
+    ///     the camera frame and "symmetries" (list of N str) each shape's
+    ///     symmetry ("full" for spheres, "24-fold" for cubes, ...)
+    fn object_poses<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let poses = renderer.object_poses(&self.inner);
+        let n = poses.len();
+        let dict = PyDict::new(py);
+        dict.set_item("indices", poses.iter().map(|p| p.index as u32).collect::<Vec<_>>().into_pyarray(py))?;
+        let rotations: Vec<f32> = poses.iter().flat_map(|p| p.rotation.into_iter().flatten()).collect();
+        dict.set_item("rotations", rotations.to_pyarray(py).reshape([n, 3, 3]).unwrap())?;
+        let translations: Vec<f32> = poses.iter().flat_map(|p| p.translation).collect();
+        dict.set_item("translations", translations.to_pyarray(py).reshape([n, 3]).unwrap())?;
+        let symmetries: Vec<String> = poses.iter().map(|p| ShapeSymmetry::of(p.shape, p.half_extents).label()).collect();
+        dict.set_item("symmetries", symmetries)?;
+        Ok(dict)
+    }
+
+    /// Step and render a whole rollout in a single call
+    ///
+    /// Args:
+    ///     steps: Number of simulation steps
+    ///     dt: Time step in seconds
+    ///     render_every: Render (and record poses) every N steps (default 1)
+    ///     substeps: Number of substeps per step (default 1)
+    ///
+    /// Returns:
+    ///     Tuple of (frames, poses): frames is a (T, H, W, 4) uint8 array and
+    ///     poses is a (T, N, 7) float32 array of [x, y, z, qx, qy, qz, qw]
+    #[pyo3(signature = (steps, dt, render_every=1, substeps=1))]
+    fn rollout<'py>(
+        &mut self,
+        py: Python<'py>,
+        steps: u32,
+        dt: f32,
+        render_every: u32,
+        substeps: u32,
+    ) -> PyResult<RolloutArrays<'py>> {
+        if render_every == 0 {
+            return Err(PyValueError::new_err("render_every must be at least 1"));
+        }
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        let (width, height) = renderer.dimensions();
+        let frame_count = (steps / render_every) as usize;
+        let body_count = self.inner.body_count();
+        let frame_size = (width * height * 4) as usize;
+
+        let mut frames: Vec<u8> = Vec::with_capacity(frame_count * frame_size);
+        let mut poses: Vec<f32> = Vec::with_capacity(frame_count * body_count * 7);
+
+        // Pipelined readback: frame N renders and reads back into one staging
+        // slot while the CPU simulates the steps leading up to frame N+1
+        let mut pending: Option<PendingFrame> = None;
+        let mut slot = 0;
+
+        // Rolling-shutter frames span the steps since the previous frame
+        let shutter = self.rolling_shutter;
+        let mut interval_start: Poses = match shutter {
+            Some(_) => (self.inner.positions().to_vec(), self.inner.rotations().to_vec()),
+            None => Default::default(),
+        };
+
+        let sub_dt = dt / substeps as f32;
+        for step in 1..=steps {
+            for _ in 0..substeps {
+                self.inner.step(sub_dt);
+                if self.impact_decals {
+                    renderer.stamp_impacts(self.inner.last_impacts());
+                }
+            }
+
+            if step % render_every != 0 {
+                continue;
+            }
+            for (p, r) in self.inner.positions().iter().zip(self.inner.rotations()) {
+                poses.extend_from_slice(p);
+                poses.extend_from_slice(r);
+            }
+
+            if let Some(shutter) = &shutter {
+                let (positions, rotations) = &interval_start;
+                frames.extend_from_slice(
+                    &renderer.render_rolling_shutter(&self.inner, positions, rotations, shutter)
+                );
+                interval_start = (self.inner.positions().to_vec(), self.inner.rotations().to_vec());
+            } else {
+                let submitted = renderer.submit_simulator(&self.inner, slot);
+                slot ^= 1;
+                if let Some(previous) = pending.replace(submitted) {
+                    frames.extend_from_slice(&renderer.finish_frame(previous));
+                }
+            }
+        }
+        if let Some(last) = pending {
+            frames.extend_from_slice(&renderer.finish_frame(last));
+        }
+
+        let frames = frames.to_pyarray(py)
+            .reshape([frame_count, height as usize, width as usize, 4])
+            .unwrap();
+        let poses = poses.to_pyarray(py)
+            .reshape([frame_count, body_count, 7])
+            .unwrap();
+        Ok((frames, poses))
+    }
+
+    /// Step without rendering and record body poses as a table
+    ///
+    /// One row per body per recorded step (ordered by frame, then body)
+    /// with columns "frame", "time", "body", "x", "y", "z", "qx", "qy",
+    /// "qz" and "qw". The columns are handed over without copying, so
+    /// long recordings go straight into pandas, pyarrow or Polars.
+    ///
+    /// Args:
+    ///     steps: Number of simulation steps
+    ///     dt: Time step in seconds
+    ///     record_every: Record poses every N steps (default 1)
+    ///     substeps: Number of substeps per step (default 1)
+    ///     arrow: Return a pyarrow.RecordBatch instead of a dict of numpy
+    ///         arrays (requires pyarrow; polars.from_arrow() takes it
+    ///         without copying)
+    ///
+    /// Returns:
+    ///     Dict of equal-length numpy columns (uint32 "frame" and "body",
+    ///     float32 otherwise), or a pyarrow.RecordBatch of the same columns
+    #[pyo3(signature = (steps, dt, record_every=1, substeps=1, arrow=false))]
+    fn record_trajectory<'py>(
+        &mut self,
+        py: Python<'py>,
+        steps: u32,
+        dt: f32,
+        record_every: u32,
+        substeps: u32,
+        arrow: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if record_every == 0 {
+            return Err(PyValueError::new_err("record_every must be at least 1"));
+        }
+
+        let mut trajectory = Trajectory::default();
+        let sub_dt = dt / substeps as f32;
+        for step in 1..=steps {
+            for _ in 0..substeps {
+                self.inner.step(sub_dt);
+            }
+            if step % record_every == 0 {
+                trajectory.push(self.inner.time, self.inner.positions(), self.inner.rotations());
+            }
+        }
+
+        let TrajectoryColumns { frame, time, body, positions: [x, y, z], rotations: [qx, qy, qz, qw] } = trajectory.to_columns();
+        let names = ["frame", "time", "body", "x", "y", "z", "qx", "qy", "qz", "qw"];
+        let columns = [
+            frame.into_pyarray(py).into_any(),
+            time.into_pyarray(py).into_any(),
+            body.into_pyarray(py).into_any(),
+            x.into_pyarray(py).into_any(),
+            y.into_pyarray(py).into_any(),
+            z.into_pyarray(py).into_any(),
+            qx.into_pyarray(py).into_any(),
+            qy.into_pyarray(py).into_any(),
+            qz.into_pyarray(py).into_any(),
+            qw.into_pyarray(py).into_any(),
+        ];
+
+        if arrow {
+            // pyarrow wraps primitive numpy arrays without copying
+            let pyarrow = py.import("pyarrow")?;
+            let arrays = columns.iter()
+                .map(|c| pyarrow.call_method1("array", (c,)))
+                .collect::<PyResult<Vec<_>>>()?;
+            return pyarrow.getattr("RecordBatch")?.call_method1("from_arrays", (arrays, names.to_vec()));
+        }
+
+        let dict = PyDict::new(py);
+        for (name, column) in names.into_iter().zip(columns) {
+            dict.set_item(name, column)?;
+        }
+        Ok(dict.into_any())
+    }
+
+    /// Step and stream a long recording into an HDF5 file (requires h5py)
+    ///
+    /// Entries are buffered and appended a chunk at a time, so recordings
+    /// far larger than memory can be written. Datasets grow along their
+    /// first axis, one entry per recorded step:
+    ///
+    /// - "step" (F,) uint32 and "time" (F,) float32
+    /// - "poses/positions" (F, N, 3) and "poses/rotations" (F, N, 4) float32
+    /// - "velocities/linear" and "velocities/angular" (F, N, 3) float32
+    /// - "frames" (F, H, W, 4) uint8, only with frames=True
+    /// - "events/*": one entry per impact with "frame" (index of the
+    ///   recorded step it falls in), "time", "body_a", "body_b" (-1 for
+    ///   the ground), "point", "normal", "relative_speed" and "impulse"
+    /// - "bodies/*": per-body "shape_types", "radii", "half_heights",
+    ///   "colors" and "statics", written once
+    ///
+    /// The file attributes hold dt, substeps, record_every and body_count.
+    /// Impacts already queued for take_impacts() are written with the
+    /// first recorded step.
+    ///
+    /// Args:
+    ///     path: Output .h5 path (overwritten)
+    ///     steps: Number of simulation steps
+    ///     dt: Time step in seconds
+    ///     record_every: Record every N steps (default 1)
+    ///     substeps: Number of substeps per step (default 1)
+    ///     frames: Also render and store a frame per recorded step
+    ///     chunk_frames: Recorded steps per HDF5 chunk (default 64)
+    ///     compression: "gzip", "lzf" or None (default "gzip")
+    ///     compression_level: gzip level 0-9 (default 4)
+    ///
+    /// Returns:
+    ///     Number of recorded steps
+    #[pyo3(signature = (path, steps, dt, record_every=1, substeps=1, frames=false, chunk_frames=64, compression=Some("gzip"), compression_level=4))]
+    #[allow(clippy::too_many_arguments)]
+    fn record_hdf5(
+        &mut self,
+        py: Python<'_>,
+        path: &str,
+        steps: u32,
+        dt: f32,
+        record_every: u32,
+        substeps: u32,
+        frames: bool,
+        chunk_frames: usize,
+        compression: Option<&str>,
+        compression_level: u8,
+    ) -> PyResult<usize> {
+        if record_every == 0 {
+            return Err(PyValueError::new_err("record_every must be at least 1"));
+        }
+        if chunk_frames == 0 {
+            return Err(PyValueError::new_err("chunk_frames must be at least 1"));
+        }
+        let bodies = self.inner.body_count();
+        if bodies == 0 {
+            return Err(PyValueError::new_err("scene has no bodies to record"));
+        }
+        let frame = if frames {
+            let renderer = self.renderer.as_ref()
+                .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+            Some(renderer.dimensions())
+        } else {
+            None
+        };
+
+        let options = PyDict::new(py);
+        if let Some(compression) = compression {
+            options.set_item("compression", compression)?;
+            if compression == "gzip" {
+                options.set_item("compression_opts", compression_level)?;
+            }
+            options.set_item("shuffle", true)?;
+        }
+
+        let file = py.import("h5py")?.call_method1("File", (path, "w"))?;
+        let attrs = file.getattr("attrs")?;
+        attrs.set_item("dt", dt)?;
+        attrs.set_item("substeps", substeps)?;
+        attrs.set_item("record_every", record_every)?;
+        attrs.set_item("body_count", bodies)?;
+
+        let storage = &self.inner.storage;
+        let data = |name: &str, values: Bound<'_, PyAny>| -> PyResult<()> {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("data", values)?;
+            file.call_method("create_dataset", (name,), Some(&kwargs)).map(|_| ())
+        };
+        data("bodies/shape_types", storage.shape_types.to_pyarray(py).into_any())?;
+        data("bodies/radii", storage.radii.to_pyarray(py).into_any())?;
+        data("bodies/half_heights", storage.half_heights.to_pyarray(py).into_any())?;
+        data("bodies/colors", storage.colors.as_flattened().to_pyarray(py).reshape([bodies, 3])?.into_any())?;
+        data("bodies/statics", storage.statics.to_pyarray(py).into_any())?;
+
+        create_growing_dataset(&file, "step", &[], chunk_frames, "u4", &options)?;
+        create_growing_dataset(&file, "time", &[], chunk_frames, "f4", &options)?;
+        create_growing_dataset(&file, "poses/positions", &[bodies, 3], chunk_frames, "f4", &options)?;
+        create_growing_dataset(&file, "poses/rotations", &[bodies, 4], chunk_frames, "f4", &options)?;
+        create_growing_dataset(&file, "velocities/linear", &[bodies, 3], chunk_frames, "f4", &options)?;
+        create_growing_dataset(&file, "velocities/angular", &[bodies, 3], chunk_frames, "f4", &options)?;
+        if let Some((width, height)) = frame {
+            // One image per chunk keeps single-frame reads cheap
+            create_growing_dataset(&file, "frames", &[height as usize, width as usize, 4], 1, "u1", &options)?;
+        }
+        let event_chunk = chunk_frames * 16;
+        let event_datasets: [(&str, &[usize], &str); 8] = [
+            ("events/frame", &[], "u4"),
+            ("events/time", &[], "f4"),
+            ("events/body_a", &[], "i4"),
+            ("events/body_b", &[], "i4"),
+            ("events/point", &[3], "f4"),
+            ("events/normal", &[3], "f4"),
+            ("events/relative_speed", &[], "f4"),
+            ("events/impulse", &[], "f4"),
+        ];
+        for (name, row, dtype) in event_datasets {
+            create_growing_dataset(&file, name, row, event_chunk, dtype, &options)?;
+        }
+
+        let was_recording = self.inner.impact_recording();
+        self.inner.set_impact_recording(true);
+
+        let mut chunk = Hdf5Chunk::default();
+        let mut recorded = 0usize;
+        let mut buffered = 0usize;
+        let sub_dt = dt / substeps as f32;
+        let result = (|| {
+            for step in 1..=steps {
+                for _ in 0..substeps {
+                    self.inner.step(sub_dt);
+                }
+                if step % record_every != 0 {
+                    continue;
+                }
+
+                let storage = &self.inner.storage;
+                chunk.steps.push(step);
+                chunk.times.push(self.inner.time);
+                chunk.positions.extend_from_slice(storage.positions.as_flattened());
+                chunk.rotations.extend_from_slice(storage.rotations.as_flattened());
+                chunk.linear_velocities.extend_from_slice(storage.linear_velocities.as_flattened());
+                chunk.angular_velocities.extend_from_slice(storage.angular_velocities.as_flattened());
+                if let Some(renderer) = self.renderer.as_ref().filter(|_| frame.is_some()) {
+                    chunk.frames.extend_from_slice(&renderer.render_simulator(&self.inner));
+                }
+                chunk.events.extend(self.inner.take_impacts().into_iter().map(|e| (recorded as u32, e)));
+                recorded += 1;
+                buffered += 1;
+
+                if buffered == chunk_frames {
+                    chunk.flush(&file, bodies, frame)?;
+                    buffered = 0;
+                }
+            }
+            chunk.flush(&file, bodies, frame)
+        })();
+
+        self.inner.set_impact_recording(was_recording);
+        file.call_method0("close")?;
+        result.map(|_| recorded)
+    }
+
+    /// Step and write every body's transform to an Alembic archive
+    /// (requires the PyAlembic `alembic` and `imath` modules)
+    ///
+    /// Each body becomes a transform node named after its shape and index
+    /// ("cube_0000", "sphere_0001", ...) directly under the archive root,
+    /// with one matrix sample per recorded step on a uniform time sampling
+    /// of dt * record_every seconds. Units are meters with Y up, as in
+    /// Maya and Houdini; parent geometry under the nodes to instance it.
+    ///
+    /// Args:
+    ///     path: Output .abc path (overwritten)
+    ///     steps: Number of simulation steps
+    ///     dt: Time step in seconds
+    ///     record_every: Write a sample every N steps (default 1)
+    ///     substeps: Number of substeps per step (default 1)
+    ///
+    /// Returns:
+    ///     Number of samples written per body
+    #[pyo3(signature = (path, steps, dt, record_every=1, substeps=1))]
+    fn record_alembic(
+        &mut self,
+        py: Python<'_>,
+        path: &str,
+        steps: u32,
+        dt: f32,
+        record_every: u32,
+        substeps: u32,
+    ) -> PyResult<usize> {
+        if record_every == 0 {
+            return Err(PyValueError::new_err("record_every must be at least 1"));
+        }
+        let alembic = py.import("alembic")?;
+        let abc_geom = alembic.getattr("AbcGeom")?;
+        let m44 = py.import("imath")?.getattr("M44d")?;
+
+        let archive = alembic.getattr("Abc")?.call_method1("OArchive", (path,))?;
+        let interval = (dt * record_every as f32) as f64;
+        let sampling = alembic.getattr("AbcCoreAbstract")?
+            .call_method1("TimeSampling", (interval, self.inner.time as f64 + interval))?;
+        let sampling = archive.call_method1("addTimeSampling", (sampling,))?;
+        let top = archive.call_method0("getTop")?;
+        let schemas = self.inner.shape_types().iter().enumerate()
+            .map(|(i, &id)| {
+                let name = format!("{}_{:04}", ShapeType::from_id(id).map_or("body", ShapeType::name), i);
+                abc_geom.call_method1("OXform", (&top, name, &sampling))?.call_method0("getSchema")
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let sample = abc_geom.call_method0("XformSample")?;
+
+        let mut written = 0;
+        let sub_dt = dt / substeps as f32;
+        for step in 1..=steps {
+            for _ in 0..substeps {
+                self.inner.step(sub_dt);
+            }
+            if step % record_every != 0 {
+                continue;
+            }
+            for ((schema, &p), &r) in schemas.iter().zip(self.inner.positions()).zip(self.inner.rotations()) {
+                // PyImath builds matrices from tuples, not lists
+                let rows = imath_rows(p, r).into_iter()
+                    .map(|row| PyTuple::new(py, row))
+                    .collect::<PyResult<Vec<_>>>()?;
+                sample.call_method1("setMatrix", (m44.call1((PyTuple::new(py, rows)?,))?,))?;
+                schema.call_method1("set", (&sample,))?;
+            }
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Step and write a bundle a Blender importer can re-render offline
+    ///
+    /// `path` becomes a directory (conventionally ending in ".physobx")
+    /// holding "scene.json" (units, axes, frame rate, ground, camera and
+    /// one entry per body with its stable id, name, shape, size, color and
+    /// material) and "trajectory.npz" ("time" (F,), "positions" (F, N, 3),
+    /// "rotations" (F, N, 4) as x, y, z, w and "body_id" (N,)). Frame 0 is
+    /// the state before stepping. Body ids are the order bodies were added
+    /// to the scene, so they stay the same across re-exports.
+    ///
+    /// Args:
+    ///     path: Bundle directory (created; existing files are replaced)
+    ///     steps: Number of simulation steps
+    ///     dt: Time step in seconds
+    ///     record_every: Record a frame every N steps (default 1)
+    ///     substeps: Number of substeps per step (default 1)
+    ///
+    /// Returns:
+    ///     Number of frames written, including frame 0
+    #[pyo3(signature = (path, steps, dt, record_every=1, substeps=1))]
+    fn export_blender_bundle(
+        &mut self,
+        py: Python<'_>,
+        path: &str,
+        steps: u32,
+        dt: f32,
+        record_every: u32,
+        substeps: u32,
+    ) -> PyResult<usize> {
+        if record_every == 0 {
+            return Err(PyValueError::new_err("record_every must be at least 1"));
+        }
+        let dir = std::path::Path::new(path);
+        std::fs::create_dir_all(dir)
+            .map_err(|e| PyIOError::new_err(format!("Failed to create bundle: {}", e)))?;
+
+        let mut trajectory = Trajectory::default();
+        trajectory.push(self.inner.time, self.inner.positions(), self.inner.rotations());
+        let sub_dt = dt / substeps as f32;
+        for step in 1..=steps {
+            for _ in 0..substeps {
+                self.inner.step(sub_dt);
+            }
+            if step % record_every == 0 {
+                trajectory.push(self.inner.time, self.inner.positions(), self.inner.rotations());
+            }
+        }
+
+        let storage = &self.inner.storage;
+        let scene = self.inner.scene();
+        let bodies = (0..storage.len())
+            .map(|i| {
+                let shape = ShapeType::from_id(storage.shape_types[i]).unwrap_or(ShapeType::Cube);
+                let body = PyDict::new(py);
+                body.set_item("id", i)?;
+                body.set_item("name", format!("{}_{:04}", shape.name(), i))?;
+                body.set_item("shape", shape.name())?;
+                match shape {
+                    ShapeType::Cube => {
+                        let half_extents = scene.bodies.get(i).map_or([storage.radii[i]; 3], |b| b.half_extents);
+                        body.set_item("half_extents", half_extents)?;
+                    }
+                    ShapeType::Sphere => body.set_item("radius", storage.radii[i])?,
+                    _ => {
+                        body.set_item("radius", storage.radii[i])?;
+                        body.set_item("half_height", storage.half_heights[i])?;
+                    }
+                }
+                body.set_item("color", storage.colors[i])?;
+                body.set_item("static", storage.statics[i])?;
+                body.set_item("mass", storage.masses[i])?;
+                if let Some(config) = scene.bodies.get(i) {
+                    body.set_item("friction", config.friction)?;
+                    body.set_item("restitution", config.restitution)?;
+                }
+                Ok(body)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let manifest = PyDict::new(py);
+        manifest.set_item("format", "physobx-bundle")?;
+        manifest.set_item("version", 1)?;
+        manifest.set_item("units", "meters")?;
+        manifest.set_item("up_axis", "Y")?;
+        manifest.set_item("quaternion_order", "xyzw")?;
+        manifest.set_item("fps", 1.0 / (dt * record_every as f32))?;
+        manifest.set_item("frame_count", trajectory.len())?;
+        manifest.set_item("dt", dt)?;
+        manifest.set_item("substeps", substeps)?;
+        manifest.set_item("record_every", record_every)?;
+        let ground = match scene.ground_y {
+            Some(y) => {
+                let ground = PyDict::new(py);
+                ground.set_item("y", y)?;
+                ground.set_item("half_size", scene.ground_size)?;
+                Some(ground)
+            }
+            None => None,
+        };
+        manifest.set_item("ground", ground)?;
+        let camera = match self.renderer.as_ref() {
+            Some(renderer) => {
+                let metadata = renderer.frame_metadata(&self.inner).camera;
+                let camera = PyDict::new(py);
+                camera.set_item("eye", metadata.eye)?;
+                camera.set_item("target", metadata.target)?;
+                camera.set_item("up", metadata.up)?;
+                camera.set_item("fov_y", metadata.fov_y)?;
+                camera.set_item("ortho_height", metadata.ortho_height)?;
+                camera.set_item("near", metadata.near)?;
+                camera.set_item("far", metadata.far)?;
+                camera.set_item("width", metadata.width)?;
+                camera.set_item("height", metadata.height)?;
+                Some(camera)
+            }
+            None => None,
+        };
+        manifest.set_item("camera", camera)?;
+        manifest.set_item("bodies", bodies)?;
+
+        let options = PyDict::new(py);
+        options.set_item("indent", 2)?;
+        let text: String = py.import("json")?
+            .call_method("dumps", (manifest,), Some(&options))?
+            .extract()?;
+        std::fs::write(dir.join("scene.json"), text)
+            .map_err(|e| PyIOError::new_err(format!("Failed to write scene.json: {}", e)))?;
+
+        let frames = trajectory.len();
+        let n = storage.len();
+        let arrays = PyDict::new(py);
+        arrays.set_item("time", trajectory.times.to_pyarray(py))?;
+        let positions: Vec<f32> = trajectory.positions.iter().flatten().flatten().copied().collect();
+        arrays.set_item("positions", positions.into_pyarray(py).reshape([frames, n, 3])?)?;
+        let rotations: Vec<f32> = trajectory.rotations.iter().flatten().flatten().copied().collect();
+        arrays.set_item("rotations", rotations.into_pyarray(py).reshape([frames, n, 4])?)?;
+        arrays.set_item("body_id", (0..n as u32).collect::<Vec<_>>().into_pyarray(py))?;
+        py.import("numpy")?.call_method("savez_compressed", (dir.join("trajectory.npz"),), Some(&arrays))?;
+        Ok(frames)
+    }
+
+    /// Save current frame as PNG
+    ///
+    /// Args:
+    ///     path: Output image path
+    ///     sidecar: Also write frame metadata (camera matrices, body poses,
+    ///              instance-ID colors, render settings) next to the image,
+    ///              as "json" or "npz" (default None)
+    #[pyo3(signature = (path, sidecar=None))]
+    fn save_png(&self, py: Python<'_>, path: &str, sidecar: Option<&str>) -> PyResult<()> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+
+        if let Some(format) = sidecar {
+            self.write_sidecar(py, renderer, path, format)?;
+        }
+
+        let pixels = self.render_pixels(renderer);
+        renderer.save_pixels(&pixels, path)
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to save PNG: {}", e)))
+    }
+
+    /// Metadata describing a frame of the current state
+    ///
+    /// Returns a dict with "time", "camera" (eye, target, up, fov_y,
+    /// ortho_height, near, far, width, height and row-major view /
+    /// projection / intrinsics matrices), "bodies" (per-body index, shape, position, rotation, size
+    /// and instance-ID color) and "settings" (render settings in effect).
+    fn frame_metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        self.metadata_dict(py, renderer)
+    }
+
+    /// Every setting that affects rendered frames, for reproducing them
+    ///
+    /// Saved PNGs embed the same record as a "physobx:manifest" text chunk,
+    /// and metadata sidecars include it.
+    ///
+    /// Returns:
+    ///     Dict with "crate_version", "adapter" (GPU name, backend, driver),
+    ///     "camera", "settings" (numeric render settings), "options" (color
+    ///     space, overlays, ...), "shaders" (source hash per shader file),
+    ///     "post_effects" (source hash per custom effect) and "fingerprint"
+    ///     (hash of everything but the adapter)
+    fn render_manifest<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        py.import("json")?.call_method1("loads", (renderer.config_manifest().to_json(),))
+    }
+
+    /// Embed the render manifest in saved PNGs (on by default)
+    #[pyo3(signature = (enabled=true))]
+    fn set_embed_manifest(&mut self, enabled: bool) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_embed_manifest(enabled);
+        Ok(())
+    }
+
+    /// Distort and vignette rendered frames like a calibrated real camera
+    ///
+    /// Distortion uses the Brown-Conrady model on normalized image coordinates,
+    /// matching OpenCV's (k1, k2, p1, p2, k3) calibration coefficients. Call
+    /// with no arguments to restore an ideal pinhole camera.
+    ///
+    /// Args:
+    ///     k1, k2, k3: Radial distortion coefficients (k1 < 0 = barrel)
+    ///     p1, p2: Tangential distortion coefficients
+    ///     vignette: Vignetting strength (0 = off, 1 = natural cos^4 falloff)
+    #[pyo3(signature = (k1=0.0, k2=0.0, p1=0.0, p2=0.0, k3=0.0, vignette=0.0))]
+    fn set_lens(&mut self, k1: f32, k2: f32, p1: f32, p2: f32, k3: f32, vignette: f32) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.set_lens(&LensModel { k1, k2, k3, p1, p2, vignette });
+        Ok(())
+    }
 
-        Ok(Self {
-            inner: CoreSimulator::new(&scene.inner),
-            renderer: Some(renderer),
-            half_extent,
-        })
+    /// Stylize rendered frames with film-like post effects
+    ///
+    /// Call with no arguments to turn all effects off.
+    ///
+    /// Args:
+    ///     chromatic_aberration: Red/blue fringing towards the image corners
+    ///                           (0.005 is subtle, 0.02 is strong)
+    ///     grain: Film grain strength (0.02 is subtle); changes every frame
+    ///     lut: Path to a 3D `.cube` color grading LUT, or None
+    ///     lut_strength: Blend between the original (0) and graded (1) image
+    #[pyo3(signature = (chromatic_aberration=0.0, grain=0.0, lut=None, lut_strength=1.0))]
+    fn set_post_effects(
+        &mut self,
+        chromatic_aberration: f32,
+        grain: f32,
+        lut: Option<&str>,
+        lut_strength: f32,
+    ) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let lut = lut.map(ColorLut::load).transpose().map_err(|e| match e {
+            LutError::Io(_) => PyIOError::new_err(e.to_string()),
+            _ => PyValueError::new_err(e.to_string()),
+        })?;
+        renderer.set_post_effects(&PostEffects { chromatic_aberration, grain, lut, lut_strength });
+        Ok(())
     }
 
-    /// Step the physics simulation
+    /// Choose the color space of rendered frames
+    ///
+    /// Frames default to sRGB. The conversion happens at the end of
+    /// tonemapping, so returned arrays, saved images and streamed frames all
+    /// carry code values of the chosen space; overlays (labels, measurements,
+    /// grids) keep their sRGB colors.
     ///
     /// Args:
-    ///     dt: Time step in seconds
-    ///     substeps: Number of substeps (default 1). Higher values improve
-    ///               collision accuracy for fast-moving objects.
-    #[pyo3(signature = (dt, substeps=1))]
-    fn step(&mut self, dt: f32, substeps: u32) {
-        let sub_dt = dt / substeps as f32;
-        for _ in 0..substeps {
-            self.inner.step(sub_dt);
-        }
+    ///     transfer: "srgb", "linear", "rec709" (BT.709 OETF) or "gamma2.2"
+    ///     primaries: "rec709" (same as sRGB), "display-p3" or "rec2020"
+    ///     embed_icc: Embed a matching ICC profile in saved PNGs
+    #[pyo3(signature = (transfer="srgb", primaries="rec709", embed_icc=false))]
+    fn set_output_color_space(&mut self, transfer: &str, primaries: &str, embed_icc: bool) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let transfer = TransferFunction::from_name(transfer).ok_or_else(|| PyValueError::new_err(format!(
+            "unknown transfer function '{}', expected srgb, linear, rec709 or gamma2.2", transfer
+        )))?;
+        let primaries = Primaries::from_name(primaries).ok_or_else(|| PyValueError::new_err(format!(
+            "unknown primaries '{}', expected rec709, display-p3 or rec2020", primaries
+        )))?;
+        renderer.set_output_color_space(OutputColorSpace { primaries, transfer });
+        renderer.set_embed_icc(embed_icc);
+        Ok(())
     }
 
-    /// Get the current simulation time
-    fn time(&self) -> f32 {
-        self.inner.time
+    /// ICC profile of the current output color space, e.g. to tag frames
+    /// written by other tools
+    ///
+    /// Returns:
+    ///     ICC v4 profile bytes
+    fn output_icc_profile<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let renderer = self.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        Ok(PyBytes::new(py, &renderer.output_color_space().icc_profile()))
     }
 
-    /// Get the number of bodies
-    fn body_count(&self) -> usize {
-        self.inner.body_count()
+    /// Append a WGSL compute shader to the post-effect stack
+    ///
+    /// Effects run in the order added, after tonemapping and the film-look
+    /// effects, each on the previous one's result. The entry point runs once
+    /// per pixel in 8x8 workgroups and may use these group 0 bindings:
+    ///
+    ///     @binding(0) var input: texture_2d<f32>;    // previous result (sRGB-encoded)
+    ///     @binding(1) var output: texture_storage_2d<rgba8unorm, write>;
+    ///     @binding(2) var depth: texture_depth_2d;
+    ///     @binding(3) var hdr: texture_2d<f32>;      // linear scene color
+    ///     @binding(4) var velocity: texture_2d<f32>;
+    ///
+    /// Every pixel of `output` must be written.
+    ///
+    /// Args:
+    ///     source: WGSL source code
+    ///     entry_point: Name of the compute entry point
+    ///
+    /// Returns:
+    ///     Position of the effect in the stack
+    #[pyo3(signature = (source, entry_point="main"))]
+    fn add_compute_effect(&mut self, source: &str, entry_point: &str) -> PyResult<usize> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let effect = ComputeEffect::new(&renderer.ctx, source, entry_point)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(renderer.push_post_effect(Box::new(effect)))
     }
 
-    /// Get positions as a NumPy array (N, 3)
-    fn get_positions<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
-        let positions = self.inner.positions();
-        let n = positions.len();
-        let flat: Vec<f32> = positions.iter()
-            .flat_map(|p| p.iter().copied())
-            .collect();
-        flat.to_pyarray(py).reshape([n, 3]).unwrap()
+    /// Append ink outlines (silhouettes and sharp color edges) to the
+    /// post-effect stack, returning the effect's position
+    fn add_outline_effect(&mut self) -> PyResult<usize> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let effect = ComputeEffect::outline(&renderer.ctx);
+        Ok(renderer.push_post_effect(Box::new(effect)))
     }
 
-    /// Get rotations as a NumPy array (N, 4)
-    fn get_rotations<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<f32>> {
-        let rotations = self.inner.rotations();
-        let n = rotations.len();
-        let flat: Vec<f32> = rotations.iter()
-            .flat_map(|r| r.iter().copied())
-            .collect();
-        flat.to_pyarray(py).reshape([n, 4]).unwrap()
+    /// Remove the effect at `index` from the post-effect stack
+    fn remove_compute_effect(&mut self, index: usize) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.remove_post_effect(index)
+            .map(|_| ())
+            .ok_or_else(|| PyIndexError::new_err(format!("effect index {} out of range", index)))
     }
 
-    /// Set camera position and target
-    #[pyo3(signature = (eye, target))]
-    fn set_camera(&mut self, eye: [f32; 3], target: [f32; 3]) -> PyResult<()> {
-        if let Some(ref mut renderer) = self.renderer {
-            renderer.set_camera(eye, target);
-            Ok(())
-        } else {
-            Err(PyRuntimeError::new_err("Renderer not available"))
+    /// Remove all effects from the post-effect stack
+    fn clear_compute_effects(&mut self) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.clear_post_effect_stack();
+        Ok(())
+    }
+
+    /// Render frames with a rolling shutter, like a CMOS camera
+    ///
+    /// Image rows are read out top to bottom over the last frame interval
+    /// (the poses before and after the most recent `step` call), so fast
+    /// motion shears and wobbles. Each band of rows is a separate render.
+    ///
+    /// Args:
+    ///     enabled: Turn the rolling shutter on or off
+    ///     bands: Number of row bands rendered at distinct times
+    ///     readout: Fraction of the frame interval spent reading out rows
+    #[pyo3(signature = (enabled=true, bands=16, readout=1.0))]
+    fn set_rolling_shutter(&mut self, enabled: bool, bands: u32, readout: f32) -> PyResult<()> {
+        if enabled && bands == 0 {
+            return Err(PyValueError::new_err("bands must be at least 1"));
         }
+        self.rolling_shutter = enabled.then_some(RollingShutter { bands, readout });
+        self.frame_start = None;
+        Ok(())
     }
 
-    /// Render a frame and return as NumPy array (H, W, 4)
-    fn render_frame<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray3<u8>>> {
-        let renderer = self.renderer.as_ref()
+    /// Leave persistent dark marks on the ground where bodies hit it hard
+    ///
+    /// Args:
+    ///     enabled: Whether new impacts stamp decals
+    ///     min_impulse: Impacts with a smaller impulse leave no mark
+    ///     radius: Decal radius (world units) at the threshold impulse;
+    ///             stronger hits grow with the square root of the impulse
+    #[pyo3(signature = (enabled=true, min_impulse=50.0, radius=0.5))]
+    fn enable_impact_decals(&mut self, enabled: bool, min_impulse: f32, radius: f32) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
             .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.decals.min_impulse = min_impulse;
+        renderer.decals.base_radius = radius;
+        self.impact_decals = enabled;
+        Ok(())
+    }
 
-        // Get separated cube and sphere data (with colors)
-        let (cube_positions, cube_rotations, cube_colors) = self.inner.cube_data();
-        let (sphere_positions, sphere_radii, sphere_colors) = self.inner.sphere_data();
+    /// Remove all impact decals from the ground
+    fn clear_decals(&mut self) -> PyResult<()> {
+        let renderer = self.renderer.as_mut()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        renderer.clear_decals();
+        Ok(())
+    }
 
-        let pixels = renderer.render_frame_with_shapes(
-            &cube_positions,
-            &cube_rotations,
-            &cube_colors,
-            &sphere_positions,
-            &sphere_radii,
-            &sphere_colors,
-        );
-        let (width, height) = renderer.dimensions();
+    /// Cast a ray into the world and return the closest hit
+    ///
+    /// Returns:
+    ///     Dict with "body" (index, None for the ground), "distance",
+    ///     "point" and "normal", or None if nothing is hit
+    #[pyo3(signature = (origin, direction, max_distance=1000.0))]
+    fn raycast<'py>(
+        &self,
+        py: Python<'py>,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+    ) -> PyResult<Option<Bound<'py, PyDict>>> {
+        self.inner.raycast(origin, direction, max_distance)
+            .map(|hit| ray_hit_dict(py, &hit))
+            .transpose()
+    }
 
-        Ok(pixels.to_pyarray(py).reshape([height as usize, width as usize, 4]).unwrap())
+    /// Predict when two bodies will first touch, without stepping
+    ///
+    /// Bodies follow their current velocities (and gravity, if awake and
+    /// dynamic); contacts and damping are ignored.
+    ///
+    /// Returns:
+    ///     Dict with "time" (s from now, 0 if already touching), "body" (= b),
+    ///     "point" and "normal" (out of body a) at impact, or None if they do
+    ///     not meet within max_t
+    #[pyo3(signature = (a, b, max_t=1.0))]
+    fn predict_toi<'py>(&self, py: Python<'py>, a: usize, b: usize, max_t: f32) -> PyResult<Option<Bound<'py, PyDict>>> {
+        for index in [a, b] {
+            if index >= self.inner.body_count() {
+                return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+            }
+        }
+        self.inner.predict_toi(a, b, max_t)
+            .map(|hit| toi_dict(py, &hit))
+            .transpose()
     }
 
-    /// Save current frame as PNG
-    fn save_png(&self, path: &str) -> PyResult<()> {
+    /// Predict a body's next new contact with any body or the ground
+    ///
+    /// Contacts the body already has (e.g. the ground it rests on) are
+    /// skipped.
+    ///
+    /// Returns:
+    ///     Same as predict_toi, with "body" None for the ground, or None if
+    ///     nothing is hit within max_t
+    #[pyo3(signature = (index, max_t=1.0))]
+    fn predict_impact<'py>(&self, py: Python<'py>, index: usize, max_t: f32) -> PyResult<Option<Bound<'py, PyDict>>> {
+        if index >= self.inner.body_count() {
+            return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+        }
+        self.inner.predict_impact(index, max_t)
+            .map(|hit| toi_dict(py, &hit))
+            .transpose()
+    }
+
+    /// Ray-cast through a pixel of the rendered image (mouse picking)
+    ///
+    /// Returns:
+    ///     Same as raycast: the hit under the pixel, or None
+    #[pyo3(signature = (pixel, max_distance=1000.0))]
+    fn pick<'py>(&self, py: Python<'py>, pixel: (f32, f32), max_distance: f32) -> PyResult<Option<Bound<'py, PyDict>>> {
         let renderer = self.renderer.as_ref()
             .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        let (origin, direction) = renderer.screen_ray([pixel.0, pixel.1]);
+        self.raycast(py, origin, direction, max_distance)
+    }
+
+    /// Set a vehicle's throttle and steering, both in [-1, 1]
+    ///
+    /// Positive throttle drives forward, negative reverses and 0 lets the
+    /// wheels roll freely. Positive steering turns left. Controls stay in
+    /// effect until changed.
+    #[pyo3(signature = (vehicle, throttle, steering=0.0))]
+    fn set_vehicle_controls(&mut self, vehicle: usize, throttle: f32, steering: f32) -> PyResult<()> {
+        if !self.inner.set_vehicle_controls(vehicle, throttle, steering) {
+            return Err(PyIndexError::new_err(format!("vehicle index {} out of range", vehicle)));
+        }
+        Ok(())
+    }
+
+    /// Lock or unlock movement of a dynamic body along world axes
+    ///
+    /// Velocity along newly locked axes is removed. `axes` is e.g. "z" or
+    /// "" to unlock all.
+    fn lock_translations(&mut self, index: usize, axes: &str) -> PyResult<()> {
+        let axes = parse_axes(axes)?;
+        if !self.inner.lock_translations(index, axes) {
+            return Err(PyIndexError::new_err(format!("no dynamic body with index {}", index)));
+        }
+        Ok(())
+    }
+
+    /// Lock or unlock rotation of a dynamic body about world axes
+    ///
+    /// Spin about newly locked axes is removed. `axes` is e.g. "xyz" or ""
+    /// to unlock all.
+    fn lock_rotations(&mut self, index: usize, axes: &str) -> PyResult<()> {
+        let axes = parse_axes(axes)?;
+        if !self.inner.lock_rotations(index, axes) {
+            return Err(PyIndexError::new_err(format!("no dynamic body with index {}", index)));
+        }
+        Ok(())
+    }
+
+    /// Start dragging a body by a world-space point on it
+    ///
+    /// Adds a spring "mouse joint" pulling the grabbed point toward a target
+    /// that starts at the point; move it with drag() and remove it with
+    /// release(). Stiffness (1/s^2) and damping (1/s) are per unit mass.
+    ///
+    /// Returns:
+    ///     Joint id for drag and release
+    #[pyo3(signature = (body, point, stiffness=100.0, damping=20.0))]
+    fn grab(&mut self, body: usize, point: [f32; 3], stiffness: f32, damping: f32) -> PyResult<usize> {
+        self.inner.grab(body, point, stiffness, damping)
+            .ok_or_else(|| PyIndexError::new_err(format!("body index {} out of range", body)))
+    }
+
+    /// Move the target a grabbed body is pulled toward
+    fn drag(&mut self, joint: usize, target: [f32; 3]) -> PyResult<()> {
+        if !self.inner.drag(joint, target) {
+            return Err(PyIndexError::new_err(format!("mouse joint {} does not exist", joint)));
+        }
+        Ok(())
+    }
+
+    /// Let go of a grabbed body
+    fn release(&mut self, joint: usize) -> PyResult<()> {
+        if !self.inner.release(joint) {
+            return Err(PyIndexError::new_err(format!("mouse joint {} does not exist", joint)));
+        }
+        Ok(())
+    }
+
+    /// Rigidly attach body b to body a, e.g. an object to a gripper
+    ///
+    /// Args:
+    ///     a: Body index of the holder
+    ///     b: Body index of the held body
+    ///     relative_pose: (position, rotation) of b in a's frame, rotation
+    ///         as (x, y, z, w); None keeps their current relative pose
+    ///
+    /// Returns:
+    ///     Attachment id for detach
+    #[pyo3(signature = (a, b, relative_pose=None))]
+    fn attach(&mut self, a: usize, b: usize, relative_pose: Option<([f32; 3], [f32; 4])>) -> PyResult<usize> {
+        for index in [a, b] {
+            if index >= self.inner.body_count() {
+                return Err(PyIndexError::new_err(format!("body index {} out of range", index)));
+            }
+        }
+        self.inner.attach(a, b, relative_pose)
+            .ok_or_else(|| PyValueError::new_err("cannot attach a body to itself"))
+    }
+
+    /// Release an attachment made with attach()
+    fn detach(&mut self, attachment: usize) -> PyResult<()> {
+        if !self.inner.detach(attachment) {
+            return Err(PyIndexError::new_err(format!("attachment {} does not exist", attachment)));
+        }
+        Ok(())
+    }
+
+    /// Enable or disable impact event recording (off by default)
+    fn set_impact_recording(&mut self, enabled: bool) {
+        self.inner.set_impact_recording(enabled);
+    }
+
+    /// Take the impacts recorded since the last call
+    ///
+    /// Call once per frame to bucket all substep impacts into that frame.
+    ///
+    /// Returns:
+    ///     List of (time, body_a, body_b, relative_speed, impulse, material_a, material_b)
+    ///     tuples; body indices are None for the ground
+    fn take_impacts(&mut self) -> Vec<ImpactTuple> {
+        self.inner.take_impacts().into_iter()
+            .map(|e| (
+                e.time,
+                e.body_a,
+                e.body_b,
+                e.relative_speed,
+                e.impulse,
+                self.inner.material_name(e.body_a),
+                self.inner.material_name(e.body_b),
+            ))
+            .collect()
+    }
+
+    /// Take the recorded impacts as an audio cue list
+    ///
+    /// Args:
+    ///     min_speed: Ignore impacts with a closing speed below this (m/s)
+    ///
+    /// Returns:
+    ///     List of (time, intensity, material) tuples with intensity in [0, 1]
+    #[pyo3(signature = (min_speed=0.5))]
+    fn take_audio_cues(&mut self, min_speed: f32) -> Vec<(f32, f32, String)> {
+        let events = self.inner.take_impacts();
+        self.inner.audio_cues(&events, min_speed).into_iter()
+            .map(|cue| (cue.time, cue.intensity, cue.material))
+            .collect()
+    }
+
+    /// Attach an IMU to a body
+    ///
+    /// Args:
+    ///     body: Body index
+    ///     accel_noise: Accelerometer white noise std (m/s^2)
+    ///     gyro_noise: Gyroscope white noise std (rad/s)
+    ///     seed: Noise RNG seed
+    ///     accel_noise_chain: NoiseChain for the accelerometer (replaces accel_noise)
+    ///     gyro_noise_chain: NoiseChain for the gyroscope (replaces gyro_noise)
+    ///
+    /// Returns:
+    ///     Sensor id for read_imu
+    #[pyo3(signature = (body, accel_noise=0.0, gyro_noise=0.0, seed=0, accel_noise_chain=None, gyro_noise_chain=None))]
+    fn add_imu(
+        &mut self,
+        body: usize,
+        accel_noise: f32,
+        gyro_noise: f32,
+        seed: u64,
+        accel_noise_chain: Option<&PyNoiseChain>,
+        gyro_noise_chain: Option<&PyNoiseChain>,
+    ) -> PyResult<usize> {
+        let mut imu = ImuSensor::new(body).with_noise(ImuNoise { accel_std: accel_noise, gyro_std: gyro_noise }, seed);
+        if let Some(chain) = accel_noise_chain {
+            imu.accel_noise = chain.inner.clone();
+        }
+        if let Some(chain) = gyro_noise_chain {
+            imu.gyro_noise = chain.inner.clone();
+        }
+        self.inner.add_imu(imu)
+            .ok_or_else(|| PyIndexError::new_err(format!("body index {} out of range", body)))
+    }
+
+    /// Latest IMU reading, updated every step
+    ///
+    /// Returns:
+    ///     Tuple of (time, linear_acceleration, angular_velocity) in the body
+    ///     frame; acceleration is the specific force (reads +9.81 up at rest)
+    fn read_imu(&self, sensor: usize) -> PyResult<(f32, [f32; 3], [f32; 3])> {
+        let reading = self.inner.imu_reading(sensor)
+            .ok_or_else(|| PyIndexError::new_err(format!("IMU {} does not exist", sensor)))?;
+        Ok((reading.time, reading.linear_acceleration, reading.angular_velocity))
+    }
 
-        // Get separated cube and sphere data (with colors)
-        let (cube_positions, cube_rotations, cube_colors) = self.inner.cube_data();
-        let (sphere_positions, sphere_radii, sphere_colors) = self.inner.sphere_data();
+    /// Attach a force-torque sensor to a body
+    ///
+    /// The sensor reads the net contact wrench (gravity removed) on the body
+    /// every step. Returns the sensor id for take_wrenches.
+    fn add_force_torque_sensor(&mut self, body: usize) -> PyResult<usize> {
+        self.inner.add_force_torque_sensor(ForceTorqueSensor::new(body))
+            .ok_or_else(|| PyIndexError::new_err(format!("body index {} out of range", body)))
+    }
 
-        renderer.save_png_with_shapes(
-            &cube_positions,
-            &cube_rotations,
-            &cube_colors,
-            &sphere_positions,
-            &sphere_radii,
-            &sphere_colors,
-            path,
-        ).map_err(|e| PyRuntimeError::new_err(format!("Failed to save PNG: {}", e)))
+    /// Take the wrench history of a force-torque sensor since the last call
+    ///
+    /// Returns:
+    ///     (T, 7) float32 array of [time, fx, fy, fz, tx, ty, tz] in the body
+    ///     frame, one row per step; torque is about the center of mass
+    fn take_wrenches<'py>(&mut self, py: Python<'py>, sensor: usize) -> PyResult<Bound<'py, PyArray2<f32>>> {
+        let wrenches = self.inner.take_wrenches(sensor)
+            .ok_or_else(|| PyIndexError::new_err(format!("force-torque sensor {} does not exist", sensor)))?;
+        let flat: Vec<f32> = wrenches.iter()
+            .flat_map(|w| [w.time, w.force[0], w.force[1], w.force[2], w.torque[0], w.torque[1], w.torque[2]])
+            .collect();
+        Ok(flat.to_pyarray(py).reshape([wrenches.len(), 7]).unwrap())
     }
 
     /// Get shape types as NumPy array (0=cube, 1=sphere)
@@ -254,6 +4532,524 @@ impl PySimulator {
     }
 }
 
+/// Python wrapper for a seeded sensor noise chain
+#[pyclass(name = "NoiseChain")]
+pub struct PyNoiseChain {
+    inner: NoiseChain,
+}
+
+#[pymethods]
+impl PyNoiseChain {
+    /// Create an empty noise chain; models are applied in the order added
+    #[new]
+    #[pyo3(signature = (seed=0))]
+    fn new(seed: u64) -> Self {
+        Self { inner: NoiseChain::new(seed) }
+    }
+
+    /// Add zero-mean white noise
+    fn gaussian(mut slf: PyRefMut<'_, Self>, std: f32) -> PyRefMut<'_, Self> {
+        slf.inner.models.push(NoiseModel::Gaussian { std });
+        slf
+    }
+
+    /// Add a random-walk bias growing by `rate` per sqrt(second)
+    fn bias_drift(mut slf: PyRefMut<'_, Self>, rate: f32) -> PyRefMut<'_, Self> {
+        slf.inner.models.push(NoiseModel::BiasDrift { rate });
+        slf
+    }
+
+    /// Drop whole readings (set to NaN) with the given probability
+    fn dropout(mut slf: PyRefMut<'_, Self>, probability: f32) -> PyRefMut<'_, Self> {
+        slf.inner.models.push(NoiseModel::Dropout { probability });
+        slf
+    }
+
+    /// Round values to multiples of `step`
+    fn quantization(mut slf: PyRefMut<'_, Self>, step: f32) -> PyRefMut<'_, Self> {
+        slf.inner.models.push(NoiseModel::Quantization { step });
+        slf
+    }
+
+    /// Corrupt a reading (1D float32 array) and return the noisy copy
+    ///
+    /// Args:
+    ///     values: The clean reading
+    ///     dt: Time since the previous reading (drives bias drift)
+    #[pyo3(signature = (values, dt=1.0 / 60.0))]
+    fn apply<'py>(&mut self, py: Python<'py>, values: Vec<f32>, dt: f32) -> Bound<'py, PyArray1<f32>> {
+        let mut values = values;
+        self.inner.apply(&mut values, dt);
+        values.to_pyarray(py)
+    }
+}
+
+/// Python wrapper for a BOP-format 6-DoF pose label exporter
+#[pyclass(name = "BopExporter")]
+pub struct PyBopExporter {
+    inner: BopExporter,
+}
+
+#[pymethods]
+impl PyBopExporter {
+    /// Create an exporter for one BOP scene folder
+    #[new]
+    #[pyo3(signature = (scene_id=0))]
+    fn new(scene_id: u32) -> Self {
+        Self { inner: BopExporter::new(scene_id) }
+    }
+
+    /// Record the camera and the poses of all dynamic bodies of a simulator
+    ///
+    /// Args:
+    ///     sim: Simulator with a renderer; its current camera is recorded
+    ///
+    /// Returns:
+    ///     Frame id (counting up from 0)
+    fn add_frame(&mut self, sim: PyRef<'_, PySimulator>) -> PyResult<u32> {
+        let renderer = sim.renderer.as_ref()
+            .ok_or_else(|| PyRuntimeError::new_err("Renderer not available"))?;
+        Ok(self.inner.add_frame(renderer, &sim.inner))
+    }
+
+    /// Number of recorded frames
+    fn frame_count(&self) -> usize {
+        self.inner.frame_count()
+    }
+
+    /// Path to save a frame's image at, below the dataset directory
+    fn image_path(&self, dir: &str, frame_id: u32) -> String {
+        self.inner.image_path(dir, frame_id).to_string_lossy().into_owned()
+    }
+
+    /// Write models_info.json, scene_camera.json and scene_gt.json below
+    /// `dir`, creating folders as needed
+    fn write(&self, dir: &str) -> PyResult<()> {
+        self.inner.write(dir)
+            .map_err(|e| PyIOError::new_err(format!("Failed to write BOP labels: {}", e)))
+    }
+}
+
+/// Add camera noise to a rendered (H, W, 4) uint8 frame
+///
+/// Args:
+///     frame: RGBA frame from render_frame
+///     gaussian_std: Read noise std in 8-bit levels
+///     shot_gain: Photons per 8-bit level for shot noise (0 = off; lower = noisier)
+///     blur_length: Motion blur streak length in pixels (0 = off)
+///     blur_angle: Motion blur direction in radians (0 = along +X)
+///     seed: Noise RNG seed
+///
+/// Returns:
+///     The noisy frame as a new array
+#[pyfunction]
+#[pyo3(signature = (frame, gaussian_std=0.0, shot_gain=0.0, blur_length=0.0, blur_angle=0.0, seed=0))]
+fn apply_image_noise<'py>(
+    py: Python<'py>,
+    frame: &Bound<'py, PyArray3<u8>>,
+    gaussian_std: f32,
+    shot_gain: f32,
+    blur_length: f32,
+    blur_angle: f32,
+    seed: u64,
+) -> PyResult<Bound<'py, PyArray3<u8>>> {
+    let shape = frame.shape().to_vec();
+    if shape[2] != 4 {
+        return Err(PyValueError::new_err("frame must have shape (H, W, 4)"));
+    }
+    let mut pixels = frame.to_vec()?;
+    let noise = ImageNoise { gaussian_std, shot_gain, blur_length, blur_angle };
+    noise.apply(&mut pixels, shape[1] as u32, shape[0] as u32, &mut Rng::new(seed));
+    Ok(pixels.to_pyarray(py).reshape([shape[0], shape[1], 4]).unwrap())
+}
+
+/// Convert a (T, N, 7) pose array from Simulator.rollout into a trajectory
+fn trajectory_from_poses(poses: &Bound<'_, PyArray3<f32>>, times: &[f32]) -> PyResult<Trajectory> {
+    let shape = poses.shape().to_vec();
+    if shape[2] != 7 {
+        return Err(PyValueError::new_err("poses must have shape (T, N, 7)"));
+    }
+
+    let flat = poses.to_vec()?;
+    let mut trajectory = Trajectory::default();
+    for (i, frame) in flat.chunks(shape[1] * 7).take(shape[0]).enumerate() {
+        let positions: Vec<[f32; 3]> = frame.chunks_exact(7).map(|p| [p[0], p[1], p[2]]).collect();
+        let rotations: Vec<[f32; 4]> = frame.chunks_exact(7).map(|p| [p[3], p[4], p[5], p[6]]).collect();
+        trajectory.push(times.get(i).copied().unwrap_or(i as f32), &positions, &rotations);
+    }
+    Ok(trajectory)
+}
+
+/// Measure how far two runs of a scene drift apart
+///
+/// Args:
+///     a: (T, N, 7) poses of the first run, as returned by Simulator.rollout
+///     b: (T, N, 7) poses of the second run
+///     threshold: Distance (m) above which a body counts as diverged
+///
+/// Returns:
+///     Dict with "divergence" ((T, N) float32 per-body distance), "mean" and
+///     "max" ((T,) over bodies), "per_body_max" ((N,) over frames),
+///     "final_mean", "max_divergence" and "first_divergent_frame" (None if
+///     no body ever exceeds the threshold). Extra frames or bodies in the
+///     longer run are ignored.
+#[pyfunction]
+#[pyo3(signature = (a, b, threshold=1e-3))]
+fn compare_trajectories<'py>(
+    py: Python<'py>,
+    a: &Bound<'py, PyArray3<f32>>,
+    b: &Bound<'py, PyArray3<f32>>,
+    threshold: f32,
+) -> PyResult<Bound<'py, PyDict>> {
+    let result = analysis::compare_trajectories(
+        &trajectory_from_poses(a, &[])?,
+        &trajectory_from_poses(b, &[])?,
+        threshold,
+    );
+
+    let frames = result.divergence.len();
+    let bodies = result.per_body_max.len();
+    let flat: Vec<f32> = result.divergence.iter().flatten().copied().collect();
+
+    let dict = PyDict::new(py);
+    dict.set_item("divergence", flat.to_pyarray(py).reshape([frames, bodies]).unwrap())?;
+    dict.set_item("mean", result.mean.to_pyarray(py))?;
+    dict.set_item("max", result.max.to_pyarray(py))?;
+    dict.set_item("per_body_max", result.per_body_max.to_pyarray(py))?;
+    dict.set_item("final_mean", result.final_mean)?;
+    dict.set_item("max_divergence", result.overall_max)?;
+    dict.set_item("first_divergent_frame", result.first_divergent_frame)?;
+    Ok(dict)
+}
+
+/// Find the moments worth centering a clip on in a recorded rollout
+///
+/// Args:
+///     poses: (T, N, 7) float32 array of [x, y, z, qx, qy, qz, qw], as
+///            returned by Simulator.rollout
+///     times: Simulation time of each of the T frames
+///     impacts: Impacts from Simulator.take_impacts (optional)
+///     topple_angle: Tilt of a body's up axis that counts as toppled (radians)
+///     collapse_drop: Drop below the starting height that counts as fallen (m)
+///     collapse_fraction: Fraction of bodies that must fall for a collapse
+///
+/// Returns:
+///     List of dicts with "kind" ("peak_impulse", "first_topple" or
+///     "collapse_onset"), "time", "score" and "body" (None if not about one
+///     body), sorted by time
+#[pyfunction]
+#[pyo3(signature = (poses, times, impacts=None, topple_angle=std::f32::consts::FRAC_PI_4, collapse_drop=0.25, collapse_fraction=0.1))]
+fn detect_moments<'py>(
+    py: Python<'py>,
+    poses: &Bound<'py, PyArray3<f32>>,
+    times: Vec<f32>,
+    impacts: Option<Vec<ImpactTuple>>,
+    topple_angle: f32,
+    collapse_drop: f32,
+    collapse_fraction: f32,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    if times.len() != poses.shape()[0] {
+        return Err(PyValueError::new_err("times must have one entry per pose frame"));
+    }
+    let trajectory = trajectory_from_poses(poses, &times)?;
+
+    let impacts: Vec<ImpactEvent> = impacts.unwrap_or_default().into_iter()
+        .map(|(time, body_a, body_b, relative_speed, impulse, _, _)| ImpactEvent {
+            time,
+            body_a,
+            body_b,
+            point: [0.0; 3],
+            normal: [0.0; 3],
+            relative_speed,
+            impulse,
+        })
+        .collect();
+
+    let settings = MomentSettings { topple_angle, collapse_drop, collapse_fraction };
+    analysis::detect_moments(&trajectory, &impacts, &settings).iter()
+        .map(|m| {
+            let dict = PyDict::new(py);
+            dict.set_item("kind", m.kind.name())?;
+            dict.set_item("time", m.time)?;
+            dict.set_item("score", m.score)?;
+            dict.set_item("body", m.body)?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/// Run a scene over a grid of physics parameters, in parallel
+///
+/// Every combination of the given values is simulated for `duration`
+/// seconds. Omitted axes keep the scene's own values.
+///
+/// Args:
+///     scene: Base scene
+///     duration: Simulated seconds per run
+///     gravity: Gravity magnitudes (m/s^2) along the scene's gravity direction
+///     friction: Friction coefficients applied to every body and the ground
+///     restitution: Restitution coefficients applied to every body and the ground
+///     dt: Step sizes (default [1/60])
+///
+/// Returns:
+///     Dict of equal-length columns (one row per run, ready for
+///     pandas.DataFrame): the parameters "gravity", "friction",
+///     "restitution" (NaN = scene values) and "dt", and the metrics "steps",
+///     "mean_displacement", "max_displacement", "min_height",
+///     "final_kinetic_energy", "settled_fraction", "impact_count" and
+///     "peak_impulse"
+#[pyfunction]
+#[pyo3(signature = (scene, duration, gravity=None, friction=None, restitution=None, dt=None))]
+fn sweep<'py>(
+    py: Python<'py>,
+    scene: &PyScene,
+    duration: f32,
+    gravity: Option<Vec<f32>>,
+    friction: Option<Vec<f32>>,
+    restitution: Option<Vec<f32>>,
+    dt: Option<Vec<f32>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let grid = SweepGrid {
+        gravity: gravity.unwrap_or_default(),
+        friction: friction.unwrap_or_default(),
+        restitution: restitution.unwrap_or_default(),
+        dt: dt.unwrap_or_default(),
+    };
+    if grid.dt.iter().any(|&d| d <= 0.0) {
+        return Err(PyValueError::new_err("dt values must be positive"));
+    }
+
+    let results = py.allow_threads(|| run_sweep(&scene.inner, &grid, duration));
+
+    let column = |f: &dyn Fn(&SweepResult) -> f32| -> Vec<f32> { results.iter().map(f).collect() };
+    let dict = PyDict::new(py);
+    dict.set_item("gravity", column(&|r| r.point.gravity).to_pyarray(py))?;
+    dict.set_item("friction", column(&|r| r.point.friction.unwrap_or(f32::NAN)).to_pyarray(py))?;
+    dict.set_item("restitution", column(&|r| r.point.restitution.unwrap_or(f32::NAN)).to_pyarray(py))?;
+    dict.set_item("dt", column(&|r| r.point.dt).to_pyarray(py))?;
+    let steps: Vec<u32> = results.iter().map(|r| r.metrics.steps).collect();
+    dict.set_item("steps", steps.to_pyarray(py))?;
+    dict.set_item("mean_displacement", column(&|r| r.metrics.mean_displacement).to_pyarray(py))?;
+    dict.set_item("max_displacement", column(&|r| r.metrics.max_displacement).to_pyarray(py))?;
+    dict.set_item("min_height", column(&|r| r.metrics.min_height).to_pyarray(py))?;
+    dict.set_item("final_kinetic_energy", column(&|r| r.metrics.final_kinetic_energy).to_pyarray(py))?;
+    dict.set_item("settled_fraction", column(&|r| r.metrics.settled_fraction).to_pyarray(py))?;
+    let impacts: Vec<u32> = results.iter().map(|r| r.metrics.impact_count).collect();
+    dict.set_item("impact_count", impacts.to_pyarray(py))?;
+    dict.set_item("peak_impulse", column(&|r| r.metrics.peak_impulse).to_pyarray(py))?;
+    Ok(dict)
+}
+
+/// Parse a gradient parameter spec: ("mass", body) or (kind, body, axis)
+fn parse_scene_parameter(spec: &Bound<'_, PyAny>) -> PyResult<SceneParameter> {
+    let (kind, body, axis): (String, usize, Option<Bound<'_, PyAny>>) = match spec.extract::<(String, usize)>() {
+        Ok((kind, body)) => (kind, body, None),
+        Err(_) => {
+            let (kind, body, axis) = spec.extract::<(String, usize, Bound<'_, PyAny>)>()?;
+            (kind, body, Some(axis))
+        }
+    };
+
+    let axis = match axis {
+        None => None,
+        Some(axis) => Some(match axis.extract::<usize>() {
+            Ok(i) if i < 3 => i,
+            Ok(i) => return Err(PyValueError::new_err(format!("axis {} out of range (expected 0, 1 or 2)", i))),
+            Err(_) => match axis.extract::<String>()?.as_str() {
+                "x" => 0,
+                "y" => 1,
+                "z" => 2,
+                other => return Err(PyValueError::new_err(format!("unknown axis '{}' (expected x, y or z)", other))),
+            },
+        }),
+    };
+
+    let need_axis = || axis.ok_or_else(|| PyValueError::new_err(format!("parameter '{}' needs an axis", kind)));
+    match kind.as_str() {
+        "velocity" => Ok(SceneParameter::Velocity { body, axis: need_axis()? }),
+        "angular_velocity" => Ok(SceneParameter::AngularVelocity { body, axis: need_axis()? }),
+        "position" => Ok(SceneParameter::Position { body, axis: need_axis()? }),
+        "mass" => Ok(SceneParameter::Mass { body }),
+        other => Err(PyValueError::new_err(format!(
+            "unknown parameter '{}' (expected velocity, angular_velocity, position or mass)",
+            other
+        ))),
+    }
+}
+
+/// Final simulation state as a dict of NumPy arrays
+fn state_dict<'py>(py: Python<'py>, sim: &CoreSimulator) -> PyResult<Bound<'py, PyDict>> {
+    let storage = &sim.storage;
+    let n = storage.len();
+    let rows3 = |v: &[[f32; 3]]| v.iter().flatten().copied().collect::<Vec<f32>>().to_pyarray(py).reshape([n, 3]);
+
+    let dict = PyDict::new(py);
+    dict.set_item("time", sim.time)?;
+    dict.set_item("positions", rows3(&storage.positions)?)?;
+    let rotations: Vec<f32> = storage.rotations.iter().flatten().copied().collect();
+    dict.set_item("rotations", rotations.to_pyarray(py).reshape([n, 4])?)?;
+    dict.set_item("velocities", rows3(&storage.linear_velocities)?)?;
+    dict.set_item("angular_velocities", rows3(&storage.angular_velocities)?)?;
+    Ok(dict)
+}
+
+/// Finite-difference gradient of a loss over the final state
+///
+/// Each parameter is perturbed by +/- epsilon and the scene rerun from
+/// scratch (2 * len(params) + 1 rollouts), giving central differences.
+///
+/// Args:
+///     scene: Base scene
+///     loss: Callable taking the final state dict ("time", "positions",
+///           "rotations", "velocities", "angular_velocities") and returning
+///           a float
+///     params: Parameters to differentiate, e.g. ("velocity", 3, "x"),
+///             ("angular_velocity", 3, "y"), ("position", 0, 2) or ("mass", 3)
+///     steps: Steps per rollout
+///     dt: Step size (default 1/60)
+///     epsilon: Absolute perturbation (default 1e-3)
+///
+/// Returns:
+///     Tuple of (loss at the base parameters, gradient as an (P,) float32 array)
+#[pyfunction]
+#[pyo3(signature = (scene, loss, params, steps, dt=1.0 / 60.0, epsilon=1e-3))]
+fn finite_difference_gradient<'py>(
+    py: Python<'py>,
+    scene: &PyScene,
+    loss: &Bound<'py, PyAny>,
+    params: Vec<Bound<'py, PyAny>>,
+    steps: u32,
+    dt: f32,
+    epsilon: f32,
+) -> PyResult<(f32, Bound<'py, PyArray1<f32>>)> {
+    if epsilon <= 0.0 {
+        return Err(PyValueError::new_err("epsilon must be positive"));
+    }
+    let params = params.iter().map(parse_scene_parameter).collect::<PyResult<Vec<_>>>()?;
+    for param in &params {
+        if param.get(&scene.inner).is_none() {
+            return Err(PyIndexError::new_err(format!("{:?} does not exist in the scene", param)));
+        }
+    }
+
+    let result = gradient::finite_difference_gradient(&scene.inner, &params, steps, dt, epsilon, |sim| {
+        loss.call1((state_dict(py, sim)?,))?.extract::<f32>()
+    })?;
+    Ok((result.loss, result.gradient.to_pyarray(py)))
+}
+
+/// Fit friction / restitution so the scene reproduces an observed trajectory
+///
+/// Runs Nelder-Mead on the RMS position error of the tracked bodies, with
+/// simplex vertices simulated in parallel. Fitted values apply to every body
+/// and the ground.
+///
+/// Args:
+///     scene: Scene matching the observed setup
+///     bodies: Indices of the tracked bodies
+///     times: (T,) observation times in simulation seconds, ascending
+///     positions: (T, K, 3) float32 observed positions of the K tracked bodies
+///     params: Parameters to fit ("friction", "restitution")
+///     initial: Starting values (default: the scene's ground material)
+///     dt: Simulation step size (default 1/60)
+///     max_iterations: Nelder-Mead iterations (default 100)
+///     tolerance: Stop when the simplex's error spread is below this (m)
+///
+/// Returns:
+///     Dict with one entry per fitted parameter plus "rms_error" (m) and
+///     "evaluations"
+#[pyfunction]
+#[pyo3(signature = (scene, bodies, times, positions, params=vec!["friction".to_string(), "restitution".to_string()], initial=None, dt=1.0 / 60.0, max_iterations=100, tolerance=1e-4))]
+#[allow(clippy::too_many_arguments)]
+fn fit_materials<'py>(
+    py: Python<'py>,
+    scene: &PyScene,
+    bodies: Vec<usize>,
+    times: Vec<f32>,
+    positions: &Bound<'py, PyArray3<f32>>,
+    params: Vec<String>,
+    initial: Option<Vec<f32>>,
+    dt: f32,
+    max_iterations: u32,
+    tolerance: f32,
+) -> PyResult<Bound<'py, PyDict>> {
+    let shape = positions.shape().to_vec();
+    if shape[0] != times.len() || shape[1] != bodies.len() || shape[2] != 3 {
+        return Err(PyValueError::new_err("positions must have shape (len(times), len(bodies), 3)"));
+    }
+    if let Some(&body) = bodies.iter().find(|&&b| b >= scene.inner.bodies.len()) {
+        return Err(PyIndexError::new_err(format!("body index {} out of range", body)));
+    }
+    if dt <= 0.0 {
+        return Err(PyValueError::new_err("dt must be positive"));
+    }
+    let params = params.iter()
+        .map(|name| match name.as_str() {
+            "friction" => Ok(FitParameter::Friction),
+            "restitution" => Ok(FitParameter::Restitution),
+            other => Err(PyValueError::new_err(format!(
+                "unknown parameter '{}' (expected friction or restitution)",
+                other
+            ))),
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    if initial.as_ref().is_some_and(|v| v.len() != params.len()) {
+        return Err(PyValueError::new_err("initial must have one value per parameter"));
+    }
+
+    let flat = positions.to_vec()?;
+    let observation = Observation {
+        bodies,
+        times,
+        positions: flat.chunks(shape[1] * 3)
+            .map(|frame| frame.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect())
+            .collect(),
+    };
+    let options = FitOptions { dt, max_iterations, tolerance };
+
+    let result = py.allow_threads(|| {
+        fit_parameters(&scene.inner, &params, initial.as_deref(), &observation, &options)
+    });
+
+    let dict = PyDict::new(py);
+    for (param, value) in params.iter().zip(&result.values) {
+        dict.set_item(param.name(), value)?;
+    }
+    dict.set_item("rms_error", result.rms_error)?;
+    dict.set_item("evaluations", result.evaluations)?;
+    Ok(dict)
+}
+
+/// Read the state another process publishes with Simulator.share_state()
+///
+/// Retries while a step is being written, so the arrays always come from
+/// one step.
+///
+/// Args:
+///     path: Shared state file
+///
+/// Returns:
+///     Dict with "step" (publish count), "time", and (N, 3) / (N, 4)
+///     float32 "positions", "rotations", "velocities" and
+///     "angular_velocities"
+#[pyfunction]
+fn read_shared_state<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyDict>> {
+    let snapshot = SharedStateReader::open(path)
+        .and_then(|reader| reader.read())
+        .map_err(|e| PyIOError::new_err(e.to_string()))?;
+    let n = snapshot.positions.len();
+    let rows3 = |v: &[[f32; 3]]| v.as_flattened().to_pyarray(py).reshape([n, 3]);
+
+    let dict = PyDict::new(py);
+    dict.set_item("step", snapshot.step)?;
+    dict.set_item("time", snapshot.time)?;
+    dict.set_item("positions", rows3(&snapshot.positions)?)?;
+    dict.set_item("rotations", snapshot.rotations.as_flattened().to_pyarray(py).reshape([n, 4])?)?;
+    dict.set_item("velocities", rows3(&snapshot.linear_velocities)?)?;
+    dict.set_item("angular_velocities", rows3(&snapshot.angular_velocities)?)?;
+    Ok(dict)
+}
+
 /// Physobx Python module
 #[pymodule]
 fn physobx(m: &Bound<'_, PyModule>) -> PyResult<()> {
@@ -261,7 +5057,18 @@ fn physobx(m: &Bound<'_, PyModule>) -> PyResult<()> {
     let _ = env_logger::try_init();
 
     m.add_function(wrap_pyfunction!(version, m)?)?;
+    m.add_function(wrap_pyfunction!(densities, m)?)?;
+    m.add_function(wrap_pyfunction!(material_presets, m)?)?;
+    m.add_function(wrap_pyfunction!(apply_image_noise, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_moments, m)?)?;
+    m.add_function(wrap_pyfunction!(compare_trajectories, m)?)?;
+    m.add_function(wrap_pyfunction!(sweep, m)?)?;
+    m.add_function(wrap_pyfunction!(finite_difference_gradient, m)?)?;
+    m.add_function(wrap_pyfunction!(fit_materials, m)?)?;
+    m.add_function(wrap_pyfunction!(read_shared_state, m)?)?;
     m.add_class::<PyScene>()?;
     m.add_class::<PySimulator>()?;
+    m.add_class::<PyNoiseChain>()?;
+    m.add_class::<PyBopExporter>()?;
     Ok(())
 }